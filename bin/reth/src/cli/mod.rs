@@ -14,10 +14,10 @@ use reth_chainspec::ChainSpec;
 use reth_cli_commands::{
     config_cmd, db, dump_genesis, import, init_cmd, init_state,
     node::{self, NoArgs},
-    p2p, prune, recover, stage,
+    p2p, prune, recover, stage, static_file, verify_witness,
 };
 use reth_cli_runner::CliRunner;
-use reth_db::DatabaseEnv;
+use reth_db::AnyDatabaseEnv;
 use reth_node_builder::{NodeBuilder, WithLaunchContext};
 use reth_tracing::FileWorkerGuard;
 use std::{ffi::OsString, fmt, future::Future, sync::Arc};
@@ -134,7 +134,7 @@ impl<Ext: clap::Args + fmt::Debug> Cli<Ext> {
     /// ````
     pub fn run<L, Fut>(mut self, launcher: L) -> eyre::Result<()>
     where
-        L: FnOnce(WithLaunchContext<NodeBuilder<Arc<DatabaseEnv>>>, Ext) -> Fut,
+        L: FnOnce(WithLaunchContext<NodeBuilder<Arc<AnyDatabaseEnv>>>, Ext) -> Fut,
         Fut: Future<Output = eyre::Result<()>>,
     {
         // add network name to logs dir
@@ -161,7 +161,9 @@ impl<Ext: clap::Args + fmt::Debug> Cli<Ext> {
                 runner.run_blocking_until_ctrl_c(command.execute())
             }
             Commands::DumpGenesis(command) => runner.run_blocking_until_ctrl_c(command.execute()),
-            Commands::Db(command) => runner.run_blocking_until_ctrl_c(command.execute()),
+            Commands::Db(command) => runner.run_blocking_until_ctrl_c(
+                command.execute(|chain_spec| block_executor!(chain_spec)),
+            ),
             Commands::Stage(command) => runner.run_command_until_exit(|ctx| {
                 command.execute(ctx, |chain_spec| block_executor!(chain_spec))
             }),
@@ -172,6 +174,10 @@ impl<Ext: clap::Args + fmt::Debug> Cli<Ext> {
             Commands::Debug(command) => runner.run_command_until_exit(|ctx| command.execute(ctx)),
             Commands::Recover(command) => runner.run_command_until_exit(|ctx| command.execute(ctx)),
             Commands::Prune(command) => runner.run_until_ctrl_c(command.execute()),
+            Commands::StaticFile(command) => runner.run_until_ctrl_c(command.execute()),
+            Commands::VerifyWitness(command) => runner.run_blocking_until_ctrl_c(
+                command.execute(|chain_spec| block_executor!(chain_spec)),
+            ),
         }
     }
 
@@ -235,6 +241,12 @@ pub enum Commands<Ext: clap::Args + fmt::Debug = NoArgs> {
     /// Prune according to the configuration without any limits
     #[command(name = "prune")]
     Prune(prune::PruneCommand),
+    /// Static file maintenance utilities
+    #[command(name = "static-file")]
+    StaticFile(static_file::Command),
+    /// Verify a block statelessly from its execution witness
+    #[command(name = "verify-witness")]
+    VerifyWitness(verify_witness::VerifyWitnessCommand),
 }
 
 #[cfg(test)]
@@ -13,7 +13,7 @@ use reth_blockchain_tree::{
 use reth_cli_commands::common::{AccessRights, Environment, EnvironmentArgs};
 use reth_cli_runner::CliContext;
 use reth_consensus::Consensus;
-use reth_db::DatabaseEnv;
+use reth_db::AnyDatabaseEnv;
 use reth_errors::RethResult;
 use reth_evm::execute::{BlockExecutionOutput, BlockExecutorProvider, Executor};
 use reth_execution_types::ExecutionOutcome;
@@ -83,7 +83,7 @@ impl Command {
     /// If the database is empty, returns the genesis block.
     fn lookup_best_block(
         &self,
-        factory: ProviderFactory<Arc<DatabaseEnv>>,
+        factory: ProviderFactory<Arc<AnyDatabaseEnv>>,
     ) -> RethResult<Arc<SealedBlock>> {
         let provider = factory.provider()?;
 
@@ -10,7 +10,7 @@ use reth_blockchain_tree_api::{
     BlockAttachment, BlockStatus, BlockValidationKind, CanonicalOutcome, InsertPayloadOk,
 };
 use reth_consensus::{Consensus, ConsensusError};
-use reth_db_api::database::Database;
+use reth_db_api::{database::Database, models::StoredBadBlock};
 use reth_evm::execute::BlockExecutorProvider;
 use reth_execution_errors::{BlockExecutionError, BlockValidationError};
 use reth_execution_types::{Chain, ExecutionOutcome};
@@ -678,7 +678,11 @@ where
     ) -> Result<InsertPayloadOk, InsertBlockError> {
         match block.try_seal_with_senders() {
             Ok(block) => self.insert_block(block, BlockValidationKind::Exhaustive),
-            Err(block) => Err(InsertBlockError::sender_recovery_error(block)),
+            Err(block) => {
+                let err = InsertBlockError::sender_recovery_error(block);
+                self.save_bad_block(&err);
+                Err(err)
+            }
         }
     }
 
@@ -782,15 +786,47 @@ where
 
         // validate block consensus rules
         if let Err(err) = self.validate_block(&block) {
-            return Err(InsertBlockError::consensus_error(err, block.block))
+            let err = InsertBlockError::consensus_error(err, block.block);
+            self.save_bad_block(&err);
+            return Err(err)
         }
 
         let status = self
             .try_insert_validated_block(block.clone(), block_validation_kind)
-            .map_err(|kind| InsertBlockError::new(block.block, kind))?;
+            .map_err(|kind| InsertBlockError::new(block.block, kind))
+            .inspect_err(|err| self.save_bad_block(err))?;
         Ok(InsertPayloadOk::Inserted(status))
     }
 
+    /// Saves the block of the given error to the bad block store, if the block failed validation.
+    ///
+    /// Failing to save the block is not fatal, the block is still tracked as invalid by the engine.
+    fn save_bad_block(&self, err: &InsertBlockError) {
+        if !err.kind().is_invalid_block() {
+            return
+        }
+
+        let block = err.block();
+        let parent_state_root = match self.sidechain_block_by_hash(block.parent_hash) {
+            Some(parent) => Ok(Some(parent.state_root)),
+            None => self
+                .externals
+                .provider_factory
+                .header(&block.parent_hash)
+                .map(|parent| parent.map(|parent| parent.state_root)),
+        };
+        let res = parent_state_root.and_then(|pre_state_root| {
+            self.externals.save_bad_block(StoredBadBlock::new(
+                block.clone(),
+                err.kind().to_string(),
+                pre_state_root.unwrap_or_default(),
+            ))
+        });
+        if let Err(error) = res {
+            warn!(target: "blockchain_tree", %error, hash=?block.hash(), "Failed to save bad block");
+        }
+    }
+
     /// Discard all blocks that precede block number from the buffer.
     pub fn remove_old_blocks(&mut self, block: BlockNumber) {
         self.state.buffered_blocks.remove_old_blocks(block);
@@ -1400,7 +1436,7 @@ mod tests {
     };
     use reth_provider::{
         test_utils::{blocks::BlockchainTestData, create_test_provider_factory_with_chain_spec},
-        ProviderFactory,
+        BadBlockReader, ProviderFactory,
     };
     use reth_stages_api::StageCheckpoint;
     use reth_trie::{root::state_root_unhashed, StateRoot};
@@ -2433,4 +2469,31 @@ mod tests {
 
         assert_eq!(tree.block_indices().last_finalized_block(), block1a.number);
     }
+
+    #[test]
+    fn saves_bad_blocks() {
+        let data = BlockchainTestData::default_from_number(11);
+        let (block1, _) = data.blocks[0].clone();
+        let genesis = data.genesis;
+
+        let mut externals = setup_externals(vec![]);
+        let consensus = Arc::new(TestConsensus::default());
+        consensus.set_fail_validation(true);
+        externals.consensus = consensus;
+        let provider_factory = externals.provider_factory.clone();
+        setup_genesis(&provider_factory, genesis);
+
+        let config = BlockchainTreeConfig::new(1, 2, 3, 2);
+        let mut tree = BlockchainTree::new(externals, config, PruneModes::default())
+            .expect("failed to create tree");
+
+        let err = tree.insert_block(block1.clone(), BlockValidationKind::Exhaustive).unwrap_err();
+        assert!(err.kind().is_invalid_block());
+
+        let bad_block = provider_factory.provider().unwrap().bad_block(block1.hash()).unwrap();
+        assert_eq!(
+            bad_block,
+            Some(StoredBadBlock::new(block1.block, err.kind().to_string(), EMPTY_ROOT_HASH))
+        );
+    }
 }
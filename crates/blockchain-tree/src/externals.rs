@@ -2,11 +2,13 @@
 
 use reth_consensus::Consensus;
 use reth_db::{static_file::HeaderMask, tables};
-use reth_db_api::{cursor::DbCursorRO, database::Database, transaction::DbTx};
+use reth_db_api::{
+    cursor::DbCursorRO, database::Database, models::StoredBadBlock, transaction::DbTx,
+};
 use reth_primitives::{BlockHash, BlockNumber, StaticFileSegment};
 use reth_provider::{
-    FinalizedBlockReader, FinalizedBlockWriter, ProviderFactory, StaticFileProviderFactory,
-    StatsReader,
+    BadBlockWriter, FinalizedBlockReader, FinalizedBlockWriter, ProviderFactory,
+    StaticFileProviderFactory, StatsReader,
 };
 use reth_storage_errors::provider::ProviderResult;
 use std::{collections::BTreeMap, sync::Arc};
@@ -100,4 +102,10 @@ impl<DB: Database, E> TreeExternals<DB, E> {
         provider_rw.commit()?;
         Ok(())
     }
+    pub(crate) fn save_bad_block(&self, bad_block: StoredBadBlock) -> ProviderResult<()> {
+        let provider_rw = self.provider_factory.provider_rw()?;
+        provider_rw.insert_bad_block(bad_block)?;
+        provider_rw.commit()?;
+        Ok(())
+    }
 }
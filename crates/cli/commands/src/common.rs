@@ -4,7 +4,10 @@ use clap::Parser;
 use reth_beacon_consensus::EthBeaconConsensus;
 use reth_chainspec::ChainSpec;
 use reth_config::{config::EtlConfig, Config};
-use reth_db::{init_db, open_db_read_only, DatabaseEnv};
+use reth_db::{
+    backend::{init_db, open_db_read_only},
+    AnyDatabaseEnv,
+};
 use reth_db_common::init::init_genesis;
 use reth_downloaders::{bodies::noop::NoopBodiesDownloader, headers::noop::NoopHeaderDownloader};
 use reth_evm::noop::NoopBlockExecutorProvider;
@@ -76,14 +79,14 @@ impl EnvironmentArgs {
             config.stages.etl.dir = Some(EtlConfig::from_datadir(data_dir.data_dir()));
         }
 
-        info!(target: "reth::cli", ?db_path, ?sf_path, "Opening storage");
+        info!(target: "reth::cli", ?db_path, ?sf_path, backend = %self.db.backend, "Opening storage");
         let (db, sfp) = match access {
             AccessRights::RW => (
-                Arc::new(init_db(db_path, self.db.database_args())?),
+                Arc::new(init_db(self.db.backend, db_path, self.db.database_args())?),
                 StaticFileProvider::read_write(sf_path)?,
             ),
             AccessRights::RO => (
-                Arc::new(open_db_read_only(&db_path, self.db.database_args())?),
+                Arc::new(open_db_read_only(self.db.backend, &db_path, self.db.database_args())?),
                 StaticFileProvider::read_only(sf_path)?,
             ),
         };
@@ -105,9 +108,9 @@ impl EnvironmentArgs {
     fn create_provider_factory(
         &self,
         config: &Config,
-        db: Arc<DatabaseEnv>,
+        db: Arc<AnyDatabaseEnv>,
         static_file_provider: StaticFileProvider,
-    ) -> eyre::Result<ProviderFactory<Arc<DatabaseEnv>>> {
+    ) -> eyre::Result<ProviderFactory<Arc<AnyDatabaseEnv>>> {
         let has_receipt_pruning = config.prune.as_ref().map_or(false, |a| a.has_receipts_pruning());
         let prune_modes =
             config.prune.as_ref().map(|prune| prune.segments.clone()).unwrap_or_default();
@@ -161,7 +164,7 @@ pub struct Environment {
     /// Configuration for reth node
     pub config: Config,
     /// Provider factory.
-    pub provider_factory: ProviderFactory<Arc<DatabaseEnv>>,
+    pub provider_factory: ProviderFactory<Arc<AnyDatabaseEnv>>,
     /// Datadir path.
     pub data_dir: ChainPath<DataDirPath>,
 }
@@ -0,0 +1,215 @@
+use clap::Parser;
+use comfy_table::{presets, Table};
+use reth_db_api::{database::Database, models::StoredBadBlock};
+use reth_evm::execute::{BlockExecutorProvider, Executor};
+use reth_primitives::{keccak256, Account, Address, BlockHash, Bytes, B256, U256};
+use reth_provider::{BadBlockReader, HeaderProvider, ProviderFactory};
+use reth_revm::{
+    database::StateProviderDatabase,
+    primitives::{AccountInfo, Bytecode},
+};
+use reth_trie::{HashedPostState, HashedStorage};
+use serde::Serialize;
+use std::{
+    collections::HashMap,
+    fs,
+    path::PathBuf,
+    sync::{Arc, Mutex},
+};
+use tracing::info;
+
+/// The arguments for the `reth db bad-block` command
+#[derive(Parser, Debug)]
+pub struct Command {
+    /// The hash of the bad block to dump.
+    ///
+    /// If omitted, all stored bad blocks are listed.
+    hash: Option<BlockHash>,
+
+    /// Writes the dump to the given file instead of stdout.
+    #[arg(long, short)]
+    output: Option<PathBuf>,
+}
+
+impl Command {
+    /// Execute `db bad-block` command
+    pub fn execute<DB: Database, E: BlockExecutorProvider>(
+        self,
+        provider_factory: ProviderFactory<DB>,
+        executor: E,
+    ) -> eyre::Result<()> {
+        let Some(hash) = self.hash else {
+            let mut table = Table::new();
+            table.load_preset(presets::ASCII_MARKDOWN);
+            table.set_header(["Number", "Hash", "Error"]);
+            for bad_block in provider_factory.provider()?.bad_blocks()? {
+                table.add_row([
+                    bad_block.block.number.to_string(),
+                    bad_block.block.hash().to_string(),
+                    bad_block.error,
+                ]);
+            }
+            println!("{table}");
+            return Ok(())
+        };
+
+        let bad_block = provider_factory
+            .provider()?
+            .bad_block(hash)?
+            .ok_or_else(|| eyre::eyre!("Bad block {hash} not found"))?;
+        let dump = dump_bad_block(&provider_factory, executor, bad_block)?;
+
+        let json = serde_json::to_string_pretty(&dump)?;
+        if let Some(output) = self.output {
+            fs::write(&output, json)?;
+            info!(target: "reth::cli", ?output, "Bad block dumped");
+        } else {
+            println!("{json}");
+        }
+
+        Ok(())
+    }
+}
+
+/// A bad block along with everything needed to reproduce its execution offline.
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct BadBlockDump {
+    hash: BlockHash,
+    number: u64,
+    error: String,
+    pre_state_root: B256,
+    /// The RLP encoded block.
+    rlp: Bytes,
+    /// The error the block failed with when it was re-executed, if any.
+    execution_error: Option<String>,
+    /// All trie nodes and preimages touched while executing the block, keyed by their hash.
+    witness: HashMap<B256, Bytes>,
+    /// The bytecodes of the contracts executed by the block, keyed by their code hash.
+    codes: HashMap<B256, Bytes>,
+    /// The RLP encoded ancestor headers, from the oldest one whose hash was read by the block up
+    /// to the parent, in ascending order.
+    headers: Vec<Bytes>,
+}
+
+/// Re-executes the bad block on top of its parent state and records its execution witness.
+fn dump_bad_block<DB: Database, E: BlockExecutorProvider>(
+    provider_factory: &ProviderFactory<DB>,
+    executor: E,
+    bad_block: StoredBadBlock,
+) -> eyre::Result<BadBlockDump> {
+    let StoredBadBlock { block, error, pre_state_root, .. } = bad_block;
+    let hash = block.hash();
+    let number = block.number;
+    let rlp = Bytes::from(alloy_rlp::encode(&block));
+
+    let state_provider = provider_factory.history_by_block_hash(block.parent_hash)?;
+    let block = block
+        .seal_with_senders()
+        .ok_or_else(|| eyre::eyre!("Failed to recover senders of bad block {hash}"))?
+        .unseal();
+
+    let reads = Arc::new(Mutex::new(Reads::default()));
+    let db = RecordingDatabase {
+        db: StateProviderDatabase::new(&state_provider),
+        reads: Arc::clone(&reads),
+    };
+    // The block is expected to fail, the witness is recorded either way.
+    let result = executor.executor(db).execute((&block, U256::ZERO).into());
+
+    let Reads { state: mut hashed_state, codes, oldest_block_hash } =
+        std::mem::take(&mut *reads.lock().expect("not poisoned"));
+    let execution_error = match result {
+        Ok(output) => {
+            hashed_state.extend(HashedPostState::from_bundle_state(&output.state.state));
+            None
+        }
+        Err(err) => Some(err.to_string()),
+    };
+    let witness = state_provider.witness(HashedPostState::default(), hashed_state)?;
+
+    let parent_number = number.saturating_sub(1);
+    let headers = provider_factory
+        .headers_range(
+            oldest_block_hash.unwrap_or(parent_number).min(parent_number)..=parent_number,
+        )?
+        .into_iter()
+        .map(|header| Bytes::from(alloy_rlp::encode(header)))
+        .collect();
+
+    Ok(BadBlockDump {
+        hash,
+        number,
+        error,
+        pre_state_root,
+        rlp,
+        execution_error,
+        witness,
+        codes,
+        headers,
+    })
+}
+
+/// The state read by a [`RecordingDatabase`].
+#[derive(Debug, Default)]
+struct Reads {
+    /// The pre-state of the accounts and storage slots read.
+    state: HashedPostState,
+    /// The bytecodes read, keyed by their code hash.
+    codes: HashMap<B256, Bytes>,
+    /// The number of the oldest block whose hash was read.
+    oldest_block_hash: Option<u64>,
+}
+
+/// A [`reth_revm::Database`] that records the pre-state of all accounts and storage slots, the
+/// bytecodes and the block hashes read from the underlying database.
+struct RecordingDatabase<DB> {
+    db: DB,
+    reads: Arc<Mutex<Reads>>,
+}
+
+impl<DB: reth_revm::Database> reth_revm::Database for RecordingDatabase<DB> {
+    type Error = DB::Error;
+
+    fn basic(&mut self, address: Address) -> Result<Option<AccountInfo>, Self::Error> {
+        let info = self.db.basic(address)?;
+        let mut reads = self.reads.lock().expect("not poisoned");
+        reads
+            .state
+            .accounts
+            .entry(keccak256(address))
+            .or_insert_with(|| info.clone().map(Account::from));
+        if let Some(code) = info.as_ref().and_then(|info| info.code.as_ref()) {
+            reads.codes.insert(code.hash_slow(), code.original_bytes());
+        }
+        Ok(info)
+    }
+
+    fn code_by_hash(&mut self, code_hash: B256) -> Result<Bytecode, Self::Error> {
+        let code = self.db.code_by_hash(code_hash)?;
+        self.reads.lock().expect("not poisoned").codes.insert(code_hash, code.original_bytes());
+        Ok(code)
+    }
+
+    fn storage(&mut self, address: Address, index: U256) -> Result<U256, Self::Error> {
+        let value = self.db.storage(address, index)?;
+        self.reads
+            .lock()
+            .expect("not poisoned")
+            .state
+            .storages
+            .entry(keccak256(address))
+            .or_insert_with(|| HashedStorage::new(false))
+            .storage
+            .entry(keccak256(B256::from(index)))
+            .or_insert(value);
+        Ok(value)
+    }
+
+    fn block_hash(&mut self, number: u64) -> Result<B256, Self::Error> {
+        let hash = self.db.block_hash(number)?;
+        let mut reads = self.reads.lock().expect("not poisoned");
+        reads.oldest_block_hash = Some(reads.oldest_block_hash.map_or(number, |n| n.min(number)));
+        Ok(hash)
+    }
+}
@@ -1,21 +1,45 @@
 use crate::db::get::{maybe_json_value_parser, table_key};
 use ahash::RandomState;
 use clap::Parser;
-use reth_db::{DatabaseEnv, RawKey, RawTable, RawValue, TableViewer, Tables};
-use reth_db_api::{cursor::DbCursorRO, database::Database, table::Table, transaction::DbTx};
+use eyre::{bail, ensure};
+use reth_db::{AnyDatabaseEnv, RawKey, RawTable, RawValue, TableViewer, Tables};
+use reth_db_api::{
+    cursor::DbCursorRO,
+    database::Database,
+    table::{Encode, Table},
+    transaction::DbTx,
+};
 use reth_db_common::DbTool;
+use reth_fs_util as fs;
+use reth_primitives::{keccak256, Bytes, B256};
+use reth_provider::{providers::StaticFileProvider, StaticFileProviderFactory, TableDigestHasher};
+use reth_static_file_types::StaticFileSegment;
+use serde::{Deserialize, Serialize};
 use std::{
     hash::{BuildHasher, Hasher},
+    marker::PhantomData,
+    path::{Path, PathBuf},
     sync::Arc,
     time::{Duration, Instant},
 };
 use tracing::{info, warn};
 
+/// Interval at which the progress of a range checksum is written to its report.
+const CHECKPOINT_INTERVAL: Duration = Duration::from_secs(10);
+
 #[derive(Parser, Debug)]
 /// The arguments for the `reth db checksum` command
 pub struct Command {
     /// The table name
-    table: Tables,
+    #[arg(required_unless_present = "segment")]
+    table: Option<Tables>,
+
+    /// The static file segment to checksum instead of a table.
+    ///
+    /// Keys of static file segments are block or transaction numbers, and their digests are
+    /// computed over the decompressed rows.
+    #[arg(long, conflicts_with = "table")]
+    segment: Option<StaticFileSegment>,
 
     /// The start of the range to checksum.
     #[arg(long, value_parser = maybe_json_value_parser)]
@@ -27,15 +51,49 @@ pub struct Command {
 
     /// The maximum number of records that are queried and used to compute the
     /// checksum.
-    #[arg(long)]
+    #[arg(long, conflicts_with_all = ["segment", "range_size", "against", "output"])]
     limit: Option<usize>,
+
+    /// Splits the keys into ranges of this many entries, and computes a digest for each range.
+    ///
+    /// Ranges only end between distinct keys, so ranges of `DUPSORT` tables may be larger.
+    #[arg(long, value_name = "ENTRIES", value_parser = clap::value_parser!(u64).range(1..))]
+    range_size: Option<u64>,
+
+    /// Computes the digests over the ranges of a report created on another node, and marks the
+    /// ranges that do not match.
+    ///
+    /// Combined with `--range-size`, only the mismatching ranges are kept, split into smaller
+    /// ranges. Exchanging reports between the two nodes this way bisects to the divergent keys.
+    #[arg(long, value_name = "FILE", conflicts_with_all = ["start_key", "end_key"])]
+    against: Option<PathBuf>,
+
+    /// Writes the range digests as a JSON report to this file, which can be consumed by
+    /// `--against` and `reth db diff --checksum-report`.
+    ///
+    /// Progress is periodically checkpointed to the report.
+    #[arg(long, short, value_name = "FILE")]
+    output: Option<PathBuf>,
+
+    /// Resumes an interrupted run from the checkpoint in the `--output` report.
+    #[arg(long, requires = "output")]
+    resume: bool,
 }
 
 impl Command {
     /// Execute `db checksum` command
-    pub fn execute(self, tool: &DbTool<Arc<DatabaseEnv>>) -> eyre::Result<()> {
+    pub fn execute(self, tool: &DbTool<Arc<AnyDatabaseEnv>>) -> eyre::Result<()> {
         warn!("This command should be run without the node running!");
-        self.table.view(&ChecksumViewer {
+
+        let range_mode = self.segment.is_some() ||
+            self.range_size.is_some() ||
+            self.against.is_some() ||
+            self.output.is_some();
+        if range_mode {
+            return self.execute_ranges(tool)
+        }
+
+        self.table.expect("required without segment").view(&ChecksumViewer {
             tool,
             start_key: self.start_key,
             end_key: self.end_key,
@@ -43,6 +101,457 @@ impl Command {
         })?;
         Ok(())
     }
+
+    /// Computes the range digests of a table or static file segment.
+    fn execute_ranges(self, tool: &DbTool<Arc<AnyDatabaseEnv>>) -> eyre::Result<()> {
+        let source = match (self.table, self.segment) {
+            (Some(table), _) => ChecksumSource::Table(table.name().to_string()),
+            (None, Some(segment)) => ChecksumSource::Segment(segment),
+            (None, None) => unreachable!("required by clap"),
+        };
+
+        let against = self.against.as_deref().map(ChecksumReport::load).transpose()?;
+        if let Some(against) = &against {
+            ensure!(
+                against.source == source,
+                "Report to compare against is for {:?}, not {source:?}",
+                against.source
+            );
+            ensure!(against.checkpoint.is_none(), "Report to compare against is incomplete");
+        }
+
+        let mut report = match self.output.as_deref().filter(|_| self.resume) {
+            Some(output) if output.exists() => {
+                let report = ChecksumReport::load(output)?;
+                ensure!(
+                    report.source == source && report.range_size == self.range_size,
+                    "Report at {output:?} was created with different arguments"
+                );
+                if report.checkpoint.is_none() {
+                    info!("Report at {output:?} is already complete");
+                    return Ok(())
+                }
+                info!(checkpoint = ?report.checkpoint, "Resuming from checkpoint");
+                report
+            }
+            _ => ChecksumReport {
+                source: source.clone(),
+                range_size: self.range_size,
+                entries: 0,
+                root: None,
+                ranges: Vec::new(),
+                checkpoint: Some(ChecksumCheckpoint::default()),
+            },
+        };
+
+        let mut job = RangeChecksum {
+            report: &mut report,
+            output: self.output.as_deref(),
+            against: against.as_ref(),
+            last_checkpoint: Instant::now(),
+        };
+        match self.table {
+            Some(table) => {
+                let provider =
+                    tool.provider_factory.provider()?.disable_long_read_transaction_safety();
+                table.view(&RangeChecksumViewer {
+                    tx: provider.tx_ref(),
+                    start_key: self.start_key.as_deref(),
+                    end_key: self.end_key.as_deref(),
+                    job: std::cell::RefCell::new(&mut job),
+                })?;
+            }
+            None => {
+                let parse = |key: Option<&str>| {
+                    key.map(|key| {
+                        serde_json::from_str::<u64>(key)
+                            .map(|number| Bytes::from(number.to_be_bytes()))
+                            .map_err(|err| eyre::eyre!(err))
+                    })
+                    .transpose()
+                };
+                let start_key = parse(self.start_key.as_deref())?;
+                let end_key = parse(self.end_key.as_deref())?;
+                let source = SegmentSource {
+                    provider: tool.provider_factory.static_file_provider(),
+                    segment: self.segment.expect("required without table"),
+                };
+                job.run(&source, start_key, end_key)?;
+            }
+        }
+
+        let mismatches = report.ranges.iter().filter(|range| range.matches == Some(false)).count();
+        info!(
+            entries = report.entries,
+            ranges = report.ranges.len(),
+            mismatches,
+            root = ?report.root,
+            "Computed range digests for {source:?}"
+        );
+        for range in report.ranges.iter().filter(|range| range.matches == Some(false)) {
+            info!(start = ?range.start, end = ?range.end, entries = range.entries, "Mismatching range");
+        }
+        if self.output.is_none() {
+            println!("{}", serde_json::to_string_pretty(&report)?);
+        }
+
+        Ok(())
+    }
+}
+
+/// Table or static file segment covered by a [`ChecksumReport`].
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub(crate) enum ChecksumSource {
+    /// A database table, by name.
+    Table(String),
+    /// A static file segment.
+    Segment(StaticFileSegment),
+}
+
+/// Range digests of a table or static file segment, as written by `reth db checksum --output`.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub(crate) struct ChecksumReport {
+    /// What the digests were computed over.
+    pub(crate) source: ChecksumSource,
+    /// Number of entries per range, or `None` if every range is checksummed as a whole.
+    range_size: Option<u64>,
+    /// Number of entries covered by the ranges.
+    entries: u64,
+    /// Merkle root over the range digests, once complete.
+    root: Option<B256>,
+    /// The range digests, in key order.
+    pub(crate) ranges: Vec<RangeDigest>,
+    /// Where to resume from, if the report is incomplete.
+    checkpoint: Option<ChecksumCheckpoint>,
+}
+
+impl ChecksumReport {
+    /// Loads a report from a JSON file.
+    pub(crate) fn load(path: &Path) -> eyre::Result<Self> {
+        Ok(fs::read_json_file(path)?)
+    }
+
+    /// Returns the ranges that should be looked at when diffing: the mismatching ones if the
+    /// report was compared against another one, and all of them otherwise.
+    pub(crate) fn ranges_to_diff(&self) -> impl Iterator<Item = &RangeDigest> {
+        let compared = self.ranges.iter().any(|range| range.matches.is_some());
+        self.ranges.iter().filter(move |range| !compared || range.matches == Some(false))
+    }
+}
+
+/// Progress of an incomplete [`ChecksumReport`].
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+struct ChecksumCheckpoint {
+    /// Key to resume from, when checksumming a whole table or segment.
+    next_key: Option<Bytes>,
+    /// Index of the next range of the report compared against.
+    next_range: usize,
+}
+
+/// Digest of the entries with keys in `start..end`.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub(crate) struct RangeDigest {
+    /// First key of the range, unbounded if `None`.
+    pub(crate) start: Option<Bytes>,
+    /// Key the range ends before, unbounded if `None`.
+    pub(crate) end: Option<Bytes>,
+    /// Number of entries in the range.
+    entries: u64,
+    /// Digest of the entries of the range, see [`TableDigestHasher`].
+    digest: B256,
+    /// Whether the digest matches the one of the report compared against.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    matches: Option<bool>,
+}
+
+/// Visitor of the key and value columns of an entry.
+type EntryVisitor<'a> = dyn FnMut(&[u8], &[&[u8]]) -> eyre::Result<()> + 'a;
+
+/// Source of entries to compute range digests of.
+trait DigestSource {
+    /// Calls `f` with the key and the value columns of every entry with a key in `start..end`, in
+    /// key order.
+    fn walk(
+        &self,
+        start: Option<&[u8]>,
+        end: Option<&[u8]>,
+        f: &mut EntryVisitor<'_>,
+    ) -> eyre::Result<()>;
+}
+
+/// Entries of a database table.
+struct TableSource<'a, TX, T> {
+    tx: &'a TX,
+    _table: PhantomData<T>,
+}
+
+impl<TX: DbTx, T: Table> DigestSource for TableSource<'_, TX, T> {
+    fn walk(
+        &self,
+        start: Option<&[u8]>,
+        end: Option<&[u8]>,
+        f: &mut EntryVisitor<'_>,
+    ) -> eyre::Result<()> {
+        let raw_key = |key: &[u8]| RawKey::<T::Key>::from_vec(key.to_vec());
+        let mut cursor = self.tx.cursor_read::<RawTable<T>>()?;
+        let walker = match (start, end) {
+            (Some(start), Some(end)) => cursor.walk_range(raw_key(start)..raw_key(end))?,
+            (Some(start), None) => cursor.walk_range(raw_key(start)..)?,
+            (None, Some(end)) => cursor.walk_range(..raw_key(end))?,
+            (None, None) => cursor.walk_range(..)?,
+        };
+
+        for entry in walker {
+            let (key, value): (RawKey<T::Key>, RawValue<T::Value>) = entry?;
+            f(key.raw_key(), &[value.raw_value()])?;
+        }
+        Ok(())
+    }
+}
+
+/// Rows of a static file segment, keyed by their big-endian block or transaction number.
+struct SegmentSource {
+    provider: StaticFileProvider,
+    segment: StaticFileSegment,
+}
+
+impl DigestSource for SegmentSource {
+    fn walk(
+        &self,
+        start: Option<&[u8]>,
+        end: Option<&[u8]>,
+        f: &mut EntryVisitor<'_>,
+    ) -> eyre::Result<()> {
+        let number = |key: &[u8]| -> eyre::Result<u64> {
+            Ok(u64::from_be_bytes(
+                key.try_into().map_err(|_| eyre::eyre!("Invalid static file key {key:?}"))?,
+            ))
+        };
+
+        let highest = if self.segment.is_block_based() {
+            self.provider.get_highest_static_file_block(self.segment)
+        } else {
+            self.provider.get_highest_static_file_tx(self.segment)
+        };
+        let Some(highest) = highest else { return Ok(()) };
+
+        let start = start.map(number).transpose()?.unwrap_or_default();
+        let end = end.map(number).transpose()?.map_or(highest + 1, |end| end.min(highest + 1));
+        if start >= end {
+            return Ok(())
+        }
+
+        let mask = (1 << self.segment.columns()) - 1;
+        let rows =
+            self.provider.fetch_range_iter(self.segment, start..end, move |cursor, number| {
+                Ok(cursor
+                    .get(number.into(), mask)?
+                    .map(|row| (number, row.into_iter().map(<[u8]>::to_vec).collect::<Vec<_>>())))
+            })?;
+        for row in rows {
+            let (number, columns) = row?;
+            let columns = columns.iter().map(Vec::as_slice).collect::<Vec<_>>();
+            f(&number.to_be_bytes(), &columns)?;
+        }
+        Ok(())
+    }
+}
+
+/// Computes the range digests of a [`DigestSource`] into a [`ChecksumReport`].
+struct RangeChecksum<'a> {
+    report: &'a mut ChecksumReport,
+    output: Option<&'a Path>,
+    against: Option<&'a ChecksumReport>,
+    last_checkpoint: Instant,
+}
+
+impl RangeChecksum<'_> {
+    /// Computes the remaining range digests of the report, starting from its checkpoint.
+    fn run(
+        &mut self,
+        source: &dyn DigestSource,
+        start_key: Option<Bytes>,
+        end_key: Option<Bytes>,
+    ) -> eyre::Result<()> {
+        let checkpoint = self.report.checkpoint.clone().unwrap_or_default();
+
+        match self.against {
+            Some(against) => {
+                for (index, range) in against.ranges.iter().enumerate().skip(checkpoint.next_range)
+                {
+                    let mut digest = None;
+                    digest_ranges(source, range.start.clone(), range.end.clone(), None, |d| {
+                        digest = Some(d);
+                        Ok(())
+                    })?;
+                    let mut digest = digest.expect("at least one range");
+                    let matches = digest.digest == range.digest;
+
+                    match self.report.range_size {
+                        // Split the mismatching range for the next bisection step.
+                        Some(range_size) if !matches => {
+                            let mut ranges = Vec::new();
+                            digest_ranges(
+                                source,
+                                range.start.clone(),
+                                range.end.clone(),
+                                Some(range_size),
+                                |mut d| {
+                                    d.matches = Some(false);
+                                    ranges.push(d);
+                                    Ok(())
+                                },
+                            )?;
+                            self.report.ranges.extend(ranges);
+                        }
+                        Some(_) => {}
+                        None => {
+                            digest.matches = Some(matches);
+                            self.report.ranges.push(digest);
+                        }
+                    }
+
+                    self.report.checkpoint =
+                        Some(ChecksumCheckpoint { next_key: None, next_range: index + 1 });
+                    self.maybe_checkpoint()?;
+                }
+            }
+            None => {
+                let start = checkpoint.next_key.or(start_key);
+                let range_size = self.report.range_size;
+                digest_ranges(source, start, end_key, range_size, |d| {
+                    let next_key = d.end.clone();
+                    self.report.ranges.push(d);
+                    self.report.checkpoint = Some(ChecksumCheckpoint { next_key, next_range: 0 });
+                    self.maybe_checkpoint()
+                })?;
+            }
+        }
+
+        self.report.entries = self.report.ranges.iter().map(|range| range.entries).sum();
+        self.report.root = Some(merkle_root(self.report.ranges.iter().map(|range| range.digest)));
+        self.report.checkpoint = None;
+        self.write()
+    }
+
+    /// Writes the report if the checkpoint interval elapsed.
+    fn maybe_checkpoint(&mut self) -> eyre::Result<()> {
+        if self.last_checkpoint.elapsed() >= CHECKPOINT_INTERVAL {
+            self.report.entries = self.report.ranges.iter().map(|range| range.entries).sum();
+            self.write()?;
+            info!(
+                ranges = self.report.ranges.len(),
+                entries = self.report.entries,
+                "Checkpointed progress"
+            );
+            self.last_checkpoint = Instant::now();
+        }
+        Ok(())
+    }
+
+    /// Writes the report to the output file, if any.
+    fn write(&self) -> eyre::Result<()> {
+        if let Some(output) = self.output {
+            // Write to a temporary file first, so an interruption never corrupts the checkpoint.
+            let tmp = output.with_extension("tmp");
+            fs::write_json_file(&tmp, &self.report)?;
+            fs::rename(tmp, output)?;
+        }
+        Ok(())
+    }
+}
+
+/// Walks the entries in `start..end`, calling `on_range` with the digest of every range of
+/// `range_size` entries, or of the whole walk if `None`.
+///
+/// Ranges only end between distinct keys, and at least one range is always emitted.
+fn digest_ranges(
+    source: &dyn DigestSource,
+    start: Option<Bytes>,
+    end: Option<Bytes>,
+    range_size: Option<u64>,
+    mut on_range: impl FnMut(RangeDigest) -> eyre::Result<()>,
+) -> eyre::Result<()> {
+    let mut range_start = start.clone();
+    let mut hasher = TableDigestHasher::default();
+    let mut last_key: Option<Vec<u8>> = None;
+
+    source.walk(
+        start.as_deref().map(|s| &s[..]),
+        end.as_deref().map(|e| &e[..]),
+        &mut |key, columns| {
+            let is_new_key = last_key.as_deref() != Some(key);
+            if is_new_key && range_size.is_some_and(|range_size| hasher.entries() >= range_size) {
+                let key = Bytes::copy_from_slice(key);
+                let (entries, digest) = std::mem::take(&mut hasher).finalize();
+                on_range(RangeDigest {
+                    start: range_start.replace(key.clone()),
+                    end: Some(key),
+                    entries,
+                    digest,
+                    matches: None,
+                })?;
+            }
+
+            hasher.update(key, columns);
+            if is_new_key {
+                last_key = Some(key.to_vec());
+            }
+            Ok(())
+        },
+    )?;
+
+    let (entries, digest) = hasher.finalize();
+    on_range(RangeDigest { start: range_start, end, entries, digest, matches: None })
+}
+
+/// Computes a binary Merkle root over the digests, promoting the last digest of odd levels.
+fn merkle_root(digests: impl IntoIterator<Item = B256>) -> B256 {
+    let mut level = digests.into_iter().collect::<Vec<_>>();
+    if level.is_empty() {
+        return keccak256([])
+    }
+    while level.len() > 1 {
+        level = level
+            .chunks(2)
+            .map(|pair| match pair {
+                [left, right] => keccak256([left.as_slice(), right.as_slice()].concat()),
+                [single] => *single,
+                _ => unreachable!("chunks of two"),
+            })
+            .collect();
+    }
+    level[0]
+}
+
+/// Computes the range digests of a database table.
+struct RangeChecksumViewer<'a, 'b, TX> {
+    tx: &'a TX,
+    start_key: Option<&'a str>,
+    end_key: Option<&'a str>,
+    job: std::cell::RefCell<&'a mut RangeChecksum<'b>>,
+}
+
+impl<TX: DbTx> TableViewer<()> for RangeChecksumViewer<'_, '_, TX> {
+    type Error = eyre::Report;
+
+    fn view<T: Table>(&self) -> Result<(), Self::Error> {
+        let encode = |key: Option<&str>| {
+            key.map(|key| table_key::<T>(key).map(|key| Bytes::from(key.encode().into())))
+                .transpose()
+        };
+        let start_key = encode(self.start_key)?;
+        let end_key = encode(self.end_key)?;
+        if let (Some(start), Some(end)) = (&start_key, &end_key) {
+            if start > end {
+                bail!("Start key is after the end key")
+            }
+        }
+
+        let source = TableSource::<_, T> { tx: self.tx, _table: PhantomData };
+        self.job.borrow_mut().run(&source, start_key, end_key)
+    }
 }
 
 pub(crate) struct ChecksumViewer<'a, DB: Database> {
@@ -131,3 +640,128 @@ impl<DB: Database> TableViewer<(u64, Duration)> for ChecksumViewer<'_, DB> {
         Ok((checksum, elapsed))
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Sorted in-memory entries.
+    struct VecSource(Vec<(Vec<u8>, Vec<u8>)>);
+
+    impl DigestSource for VecSource {
+        fn walk(
+            &self,
+            start: Option<&[u8]>,
+            end: Option<&[u8]>,
+            f: &mut EntryVisitor<'_>,
+        ) -> eyre::Result<()> {
+            for (key, value) in &self.0 {
+                if start.is_some_and(|start| key.as_slice() < start) ||
+                    end.is_some_and(|end| key.as_slice() >= end)
+                {
+                    continue
+                }
+                f(key, &[value])?;
+            }
+            Ok(())
+        }
+    }
+
+    fn report(range_size: Option<u64>) -> ChecksumReport {
+        ChecksumReport {
+            source: ChecksumSource::Table("PlainAccountState".to_string()),
+            range_size,
+            entries: 0,
+            root: None,
+            ranges: Vec::new(),
+            checkpoint: Some(ChecksumCheckpoint::default()),
+        }
+    }
+
+    fn run(
+        source: &VecSource,
+        range_size: Option<u64>,
+        against: Option<&ChecksumReport>,
+    ) -> ChecksumReport {
+        let mut report = report(range_size);
+        RangeChecksum {
+            report: &mut report,
+            output: None,
+            against,
+            last_checkpoint: Instant::now(),
+        }
+        .run(source, None, None)
+        .unwrap();
+        report
+    }
+
+    #[test]
+    fn ranges_end_between_distinct_keys() {
+        let source = VecSource(vec![
+            (vec![1], vec![1]),
+            (vec![1], vec![2]),
+            (vec![1], vec![3]),
+            (vec![2], vec![1]),
+            (vec![3], vec![1]),
+        ]);
+
+        let report = run(&source, Some(2), None);
+        assert_eq!(report.entries, 5);
+        assert!(report.checkpoint.is_none());
+        assert_eq!(
+            report
+                .ranges
+                .iter()
+                .map(|range| (range.start.clone(), range.end.clone(), range.entries))
+                .collect::<Vec<_>>(),
+            vec![(None, Some(Bytes::from(vec![2])), 3), (Some(Bytes::from(vec![2])), None, 2),]
+        );
+
+        // The root does not depend on how the ranges were checkpointed.
+        let resumed = {
+            let mut report = report.clone();
+            report.ranges.truncate(1);
+            report.checkpoint =
+                Some(ChecksumCheckpoint { next_key: report.ranges[0].end.clone(), next_range: 0 });
+            RangeChecksum {
+                report: &mut report,
+                output: None,
+                against: None,
+                last_checkpoint: Instant::now(),
+            }
+            .run(&source, None, None)
+            .unwrap();
+            report
+        };
+        assert_eq!(resumed, report);
+    }
+
+    #[test]
+    fn against_bisects_to_divergent_key() {
+        let entries = (0..100u8).map(|i| (vec![i], vec![i])).collect::<Vec<_>>();
+        let primary = VecSource(entries.clone());
+        let mut secondary_entries = entries;
+        secondary_entries[42].1 = vec![0];
+        let secondary = VecSource(secondary_entries);
+
+        let primary_report = run(&primary, Some(10), None);
+        assert_eq!(primary_report.ranges.len(), 10);
+        assert_ne!(run(&secondary, Some(10), None).root, primary_report.root);
+
+        // Comparing without splitting marks the mismatching range.
+        let compared = run(&secondary, None, Some(&primary_report));
+        assert_eq!(
+            compared.ranges.iter().map(|range| range.matches).collect::<Vec<_>>(),
+            (0..10).map(|i| Some(i != 4)).collect::<Vec<_>>()
+        );
+
+        // Splitting the mismatching ranges narrows them down to the divergent key.
+        let bisected = run(&secondary, Some(1), Some(&primary_report));
+        assert_eq!(bisected.ranges.len(), 10);
+        let bisected = run(&primary, None, Some(&bisected));
+        let mismatches = bisected.ranges_to_diff().collect::<Vec<_>>();
+        assert_eq!(mismatches.len(), 1);
+        assert_eq!(mismatches[0].start, Some(Bytes::from(vec![42])));
+        assert_eq!(mismatches[0].end, Some(Bytes::from(vec![43])));
+    }
+}
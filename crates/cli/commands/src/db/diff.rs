@@ -1,17 +1,28 @@
+use crate::db::checksum::{ChecksumReport, ChecksumSource};
 use clap::Parser;
-use reth_db::{open_db_read_only, tables_to_generic, DatabaseEnv, Tables};
-use reth_db_api::{cursor::DbCursorRO, database::Database, table::Table, transaction::DbTx};
+use jsonrpsee::http_client::{HttpClient, HttpClientBuilder};
+use reth_db::{backend::open_db_read_only, tables_to_generic, AnyDatabaseEnv, Tables};
+use reth_db_api::{
+    cursor::DbCursorRO,
+    database::Database,
+    table::{Decode, Table},
+    transaction::DbTx,
+};
 use reth_db_common::DbTool;
 use reth_node_core::{
     args::DatabaseArgs,
     dirs::{DataDirPath, PlatformPath},
 };
+use reth_primitives::Bytes;
+use reth_provider::{TableDigestCursor, TableDigestReader};
+use reth_rpc_api::RethApiClient;
 use std::{
     collections::HashMap,
     fmt::Debug,
     fs::{self, File},
     hash::Hash,
     io::Write,
+    ops::Bound,
     path::{Path, PathBuf},
     sync::Arc,
 };
@@ -21,17 +32,30 @@ use tracing::{info, warn};
 /// The arguments for the `reth db diff` command
 pub struct Command {
     /// The path to the data dir for all reth files and subdirectories.
-    #[arg(long, verbatim_doc_comment)]
-    secondary_datadir: PlatformPath<DataDirPath>,
+    #[arg(long, verbatim_doc_comment, required_unless_present = "remote_rpc")]
+    secondary_datadir: Option<PlatformPath<DataDirPath>>,
 
     /// Arguments for the second database
     #[command(flatten)]
     second_db: DatabaseArgs,
 
+    /// The HTTP RPC URL of a running node to diff against, instead of a secondary datadir.
+    ///
+    /// The node must serve the `reth` RPC namespace. Tables are compared by range digests,
+    /// narrowing down the key ranges that differ without transferring the table contents.
+    #[arg(long, value_name = "URL", conflicts_with = "secondary_datadir")]
+    remote_rpc: Option<String>,
+
     /// The table name to diff. If not specified, all tables are diffed.
     #[arg(long, verbatim_doc_comment)]
     table: Option<Tables>,
 
+    /// A report written by `reth db checksum --output`. Only its table is diffed, restricted to
+    /// its mismatching ranges, or to all of its ranges if it was not compared against another
+    /// report.
+    #[arg(long, value_name = "FILE", conflicts_with = "table")]
+    checksum_report: Option<PathBuf>,
+
     /// The output directory for the diff report.
     #[arg(long, verbatim_doc_comment)]
     output: PlatformPath<PathBuf>,
@@ -51,18 +75,53 @@ impl Command {
     ///
     /// The discrepancies and extra elements, along with a brief summary of the diff results are
     /// then written to a file in the output directory.
-    pub fn execute(self, tool: &DbTool<Arc<DatabaseEnv>>) -> eyre::Result<()> {
-        warn!("Make sure the node is not running when running `reth db diff`!");
-        // open second db
-        let second_db_path: PathBuf = self.secondary_datadir.join("db").into();
-        let second_db = open_db_read_only(&second_db_path, self.second_db.database_args())?;
-
-        let tables = match &self.table {
-            Some(table) => std::slice::from_ref(table),
-            None => Tables::ALL,
+    ///
+    /// If a remote RPC URL is given instead, see [`find_remote_diffs`].
+    pub async fn execute(self, tool: &DbTool<Arc<AnyDatabaseEnv>>) -> eyre::Result<()> {
+        let (tables, ranges) = match self.checksum_report.as_deref() {
+            Some(path) => {
+                let report = ChecksumReport::load(path)?;
+                let ChecksumSource::Table(table) = &report.source else {
+                    eyre::bail!("Checksum report is not for a database table: {:?}", report.source)
+                };
+                let table = table.parse::<Tables>().map_err(|err| eyre::eyre!(err))?;
+                let ranges = report
+                    .ranges_to_diff()
+                    .map(|range| (range.start.clone(), range.end.clone()))
+                    .collect::<Vec<_>>();
+                info!("Diffing {} ranges of table {table} from the checksum report", ranges.len());
+                (vec![table], ranges)
+            }
+            None => (
+                self.table.map_or_else(|| Tables::ALL.to_vec(), |table| vec![table]),
+                vec![(None, None)],
+            ),
         };
 
-        for table in tables {
+        if let Some(url) = &self.remote_rpc {
+            let client = HttpClientBuilder::default().build(url)?;
+            for table in tables {
+                let provider =
+                    tool.provider_factory.provider()?.disable_long_read_transaction_safety();
+                info!("Analyzing table {table} against {url}...");
+                let diffs = find_remote_diffs(&provider, &client, table, &ranges).await?;
+                write_remote_diffs(&self.output, table, url, &diffs)?;
+            }
+            return Ok(())
+        }
+
+        warn!("Make sure the node is not running when running `reth db diff`!");
+        // open second db
+        let secondary_datadir =
+            self.secondary_datadir.as_ref().expect("required unless diffing against a remote RPC");
+        let second_db_path: PathBuf = secondary_datadir.join("db").into();
+        let second_db = open_db_read_only(
+            self.second_db.backend,
+            &second_db_path,
+            self.second_db.database_args(),
+        )?;
+
+        for table in &tables {
             let mut primary_tx = tool.provider_factory.db_ref().tx()?;
             let mut secondary_tx = second_db.tx()?;
 
@@ -75,7 +134,8 @@ impl Command {
             tables_to_generic!(table, |Table| find_diffs::<Table>(
                 primary_tx,
                 secondary_tx,
-                output_dir
+                output_dir,
+                &ranges
             ))?;
         }
 
@@ -83,11 +143,148 @@ impl Command {
     }
 }
 
-/// Find diffs for a table, then analyzing the result
+/// A range of a table with different contents in the local database and on the remote node,
+/// that could not be narrowed down further.
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct RemoteRangeDiff {
+    /// Position of the first entry of the range, unbounded if `None`.
+    start: Option<TableDigestCursor>,
+    /// Position the range ends before, unbounded if `None`.
+    end: Option<TableDigestCursor>,
+    /// Number of entries in the range in the local database.
+    local_entries: u64,
+    /// Number of entries in the range on the remote node.
+    remote_entries: u64,
+}
+
+/// Diffs a table within the given raw key ranges against a remote node, using `reth_tableDigest`.
+///
+/// Ranges with mismatching digests are bisected at the median entry of whichever side has more
+/// entries, until one side holds at most a single entry. The remote node caps the number of
+/// entries it digests per request, so large ranges are compared in chunks ending where the remote
+/// digest did.
+async fn find_remote_diffs(
+    provider: &impl TableDigestReader,
+    client: &HttpClient,
+    table: Tables,
+    ranges: &[(Option<Bytes>, Option<Bytes>)],
+) -> eyre::Result<Vec<RemoteRangeDiff>> {
+    let name = table.name();
+    let to_rpc = |cursor: &Option<TableDigestCursor>| {
+        cursor.clone().map(|cursor| reth_rpc_types::reth::TableDigestCursor {
+            key: cursor.key,
+            subkey: cursor.subkey,
+        })
+    };
+    let from_rpc = |cursor: Option<reth_rpc_types::reth::TableDigestCursor>| {
+        cursor.map(|cursor| TableDigestCursor { key: cursor.key, subkey: cursor.subkey })
+    };
+
+    // ranges are popped from the back, so push them in reverse to report diffs in key order
+    let mut pending = ranges
+        .iter()
+        .rev()
+        .map(|(start, end)| {
+            (
+                start.clone().map(TableDigestCursor::from_key),
+                end.clone().map(TableDigestCursor::from_key),
+            )
+        })
+        .collect::<Vec<_>>();
+    let mut diffs = Vec::new();
+    while let Some((start, mut end)) = pending.pop() {
+        let remote =
+            client.reth_table_digest(name.to_string(), to_rpc(&start), to_rpc(&end), None).await?;
+        if let Some(next) = from_rpc(remote.next) {
+            pending.push((Some(next.clone()), end));
+            end = Some(next);
+        }
+
+        let local = provider.table_digest(name, start.as_ref(), end.as_ref(), u64::MAX)?;
+        if local.entries == remote.entries && local.digest == remote.digest {
+            continue
+        }
+
+        let half = local.entries.max(remote.entries) / 2;
+        let mid = if half == 0 {
+            None
+        } else if local.entries >= remote.entries {
+            provider.table_digest(name, start.as_ref(), end.as_ref(), half)?.next
+        } else {
+            from_rpc(
+                client
+                    .reth_table_digest(name.to_string(), to_rpc(&start), to_rpc(&end), Some(half))
+                    .await?
+                    .next,
+            )
+        };
+
+        match mid {
+            Some(mid) => {
+                pending.push((Some(mid.clone()), end));
+                pending.push((start, Some(mid)));
+            }
+            None => diffs.push(RemoteRangeDiff {
+                start,
+                end,
+                local_entries: local.entries,
+                remote_entries: remote.entries,
+            }),
+        }
+    }
+
+    Ok(diffs)
+}
+
+/// Writes the result of [`find_remote_diffs`] for a table to the output directory.
+fn write_remote_diffs(
+    output_dir: impl AsRef<Path>,
+    table: Tables,
+    url: &str,
+    diffs: &[RemoteRangeDiff],
+) -> eyre::Result<()> {
+    info!("");
+    info!("Diff results for {table} against {url}:");
+
+    fs::create_dir_all(output_dir.as_ref())?;
+    let file_name = output_dir.as_ref().join(format!("{table}.txt"));
+    let mut file = File::create(&file_name)?;
+
+    writeln!(file, "Diff results for {table} against {url}")?;
+    if diffs.is_empty() {
+        writeln!(file, "No mismatching ranges found in table {table}")?;
+        info!("No mismatching ranges found in table {table}");
+    } else {
+        writeln!(file, "Found {} mismatching ranges in table {table}", diffs.len())?;
+        info!("Found {} mismatching ranges in table {table}", diffs.len());
+    }
+
+    let bound = |cursor: &Option<TableDigestCursor>| match cursor {
+        Some(TableDigestCursor { key, subkey: Some(subkey) }) => format!("{key}/{subkey}"),
+        Some(TableDigestCursor { key, subkey: None }) => key.to_string(),
+        None => "..".to_string(),
+    };
+    for diff in diffs {
+        writeln!(
+            file,
+            "[{}, {}): {} local entries, {} remote entries",
+            bound(&diff.start),
+            bound(&diff.end),
+            diff.local_entries,
+            diff.remote_entries
+        )?;
+    }
+
+    info!("Done writing diff results for {table} to {}", file_name.display());
+    Ok(())
+}
+
+/// Find diffs for a table within the given encoded key ranges, then analyzing the result
 fn find_diffs<T: Table>(
     primary_tx: impl DbTx,
     secondary_tx: impl DbTx,
     output_dir: impl AsRef<Path>,
+    ranges: &[(Option<Bytes>, Option<Bytes>)],
 ) -> eyre::Result<()>
 where
     T::Key: Hash,
@@ -96,7 +293,17 @@ where
     let table = T::NAME;
 
     info!("Analyzing table {table}...");
-    let result = find_diffs_advanced::<T>(&primary_tx, &secondary_tx)?;
+    let mut result = TableDiffResult::<T>::default();
+    for (start, end) in ranges {
+        let decode = |key: &Option<Bytes>| -> eyre::Result<Option<T::Key>> {
+            Ok(key.as_ref().map(T::Key::decode).transpose()?)
+        };
+        let range = (
+            decode(start)?.map_or(Bound::Unbounded, Bound::Included),
+            decode(end)?.map_or(Bound::Unbounded, Bound::Excluded),
+        );
+        find_diffs_advanced::<T>(&primary_tx, &secondary_tx, range, &mut result)?;
+    }
     info!("Done analyzing table {table}!");
 
     // Pretty info summary header: newline then header
@@ -165,12 +372,14 @@ where
     Ok(())
 }
 
-/// This diff algorithm is slightly different, it will walk _each_ table, cross-checking for the
-/// element in the other table.
+/// This diff algorithm is slightly different, it will walk _each_ table within the key range,
+/// cross-checking for the element in the other table.
 fn find_diffs_advanced<T: Table>(
     primary_tx: &impl DbTx,
     secondary_tx: &impl DbTx,
-) -> eyre::Result<TableDiffResult<T>>
+    range: (Bound<T::Key>, Bound<T::Key>),
+    result: &mut TableDiffResult<T>,
+) -> eyre::Result<()>
 where
     T::Value: PartialEq,
     T::Key: Hash,
@@ -178,11 +387,11 @@ where
     // initialize the zipped walker
     let mut primary_zip_cursor =
         primary_tx.cursor_read::<T>().expect("Was not able to obtain a cursor.");
-    let primary_walker = primary_zip_cursor.walk(None)?;
+    let primary_walker = primary_zip_cursor.walk_range(range.clone())?;
 
     let mut secondary_zip_cursor =
         secondary_tx.cursor_read::<T>().expect("Was not able to obtain a cursor.");
-    let secondary_walker = secondary_zip_cursor.walk(None)?;
+    let secondary_walker = secondary_zip_cursor.walk_range(range)?;
     let zipped_cursor = primary_walker.zip(secondary_walker);
 
     // initialize the cursors for seeking when we are cross checking elements
@@ -192,8 +401,6 @@ where
     let mut secondary_cursor =
         secondary_tx.cursor_read::<T>().expect("Was not able to obtain a cursor.");
 
-    let mut result = TableDiffResult::<T>::default();
-
     // this loop will walk both tables, cross-checking for the element in the other table.
     // it basically just loops through both tables at the same time. if the keys are different, it
     // will check each key in the other table. if the keys are the same, it will compare the
@@ -226,7 +433,7 @@ where
         }
     }
 
-    Ok(result)
+    Ok(())
 }
 
 /// Includes a table element between two databases with the same key, but different values
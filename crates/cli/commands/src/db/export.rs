@@ -0,0 +1,41 @@
+use clap::Parser;
+use reth_db::AnyDatabaseEnv;
+use reth_db_common::{archive::DEFAULT_CHUNK_SIZE, DbTool};
+use std::{
+    path::{Path, PathBuf},
+    sync::Arc,
+};
+use tracing::info;
+
+#[derive(Parser, Debug)]
+/// The arguments for the `reth db export` command
+pub struct Command {
+    /// The directory to write the archive to. It must not exist or be empty.
+    #[arg(long, short, value_name = "PATH")]
+    output: PathBuf,
+
+    /// The maximum size of a table chunk in bytes.
+    #[arg(long, value_name = "BYTES", default_value_t = DEFAULT_CHUNK_SIZE)]
+    chunk_size: u64,
+}
+
+impl Command {
+    /// Execute `db export` command
+    pub fn execute(
+        self,
+        static_files_path: &Path,
+        tool: &DbTool<Arc<AnyDatabaseEnv>>,
+    ) -> eyre::Result<()> {
+        let manifest = tool.export_archive(&self.output, static_files_path, self.chunk_size)?;
+
+        info!(
+            target: "reth::cli",
+            path = ?self.output,
+            tables = manifest.tables.len(),
+            static_files = manifest.static_files.len(),
+            "Exported database archive"
+        );
+
+        Ok(())
+    }
+}
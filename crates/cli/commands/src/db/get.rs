@@ -1,14 +1,18 @@
 use clap::Parser;
 use reth_db::{
-    static_file::{ColumnSelectorOne, ColumnSelectorTwo, HeaderMask, ReceiptMask, TransactionMask},
+    static_file::{
+        AccountChangeSetMask, ColumnSelectorOne, ColumnSelectorTwo, HeaderMask, ReceiptMask,
+        StorageChangeSetMask, TransactionMask,
+    },
     tables, RawKey, RawTable, Receipts, TableViewer, Transactions,
 };
 use reth_db_api::{
     database::Database,
+    models::{BlockAccountChangeSet, BlockStorageChangeSet},
     table::{Decompress, DupSort, Table},
 };
 use reth_db_common::DbTool;
-use reth_primitives::{BlockHash, Header};
+use reth_primitives::{BlockHash, BlockNumber, Header};
 use reth_provider::StaticFileProviderFactory;
 use reth_static_file_types::StaticFileSegment;
 use tracing::error;
@@ -72,6 +76,14 @@ impl Command {
                         table_key::<tables::Receipts>(&key)?,
                         <ReceiptMask<<Receipts as Table>::Value>>::MASK,
                     ),
+                    StaticFileSegment::AccountChangeSets => (
+                        serde_json::from_str::<BlockNumber>(&key)?,
+                        <AccountChangeSetMask<BlockAccountChangeSet>>::MASK,
+                    ),
+                    StaticFileSegment::StorageChangeSets => (
+                        serde_json::from_str::<BlockNumber>(&key)?,
+                        <StorageChangeSetMask<BlockStorageChangeSet>>::MASK,
+                    ),
                 };
 
                 let content = tool.provider_factory.static_file_provider().find_static_file(
@@ -113,6 +125,16 @@ impl Command {
                                     )?;
                                     println!("{}", serde_json::to_string_pretty(&receipt)?);
                                 }
+                                StaticFileSegment::AccountChangeSets => {
+                                    let changes =
+                                        BlockAccountChangeSet::decompress(content[0].as_slice())?;
+                                    println!("{}", serde_json::to_string_pretty(&changes)?);
+                                }
+                                StaticFileSegment::StorageChangeSets => {
+                                    let changes =
+                                        BlockStorageChangeSet::decompress(content[0].as_slice())?;
+                                    println!("{}", serde_json::to_string_pretty(&changes)?);
+                                }
                             }
                         }
                     }
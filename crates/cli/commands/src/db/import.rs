@@ -0,0 +1,71 @@
+use crate::common::EnvironmentArgs;
+use clap::Parser;
+use eyre::{bail, ensure};
+use reth_db::{backend::create_db, is_database_empty};
+use reth_db_common::archive::{import_archive, read_archive_manifest};
+use reth_provider::{
+    providers::StaticFileProvider, ProviderFactory, PruneCheckpointReader,
+    StaticFileProviderFactory,
+};
+use reth_prune::PruneSegment;
+use std::{path::PathBuf, sync::Arc};
+use tracing::info;
+
+#[derive(Parser, Debug)]
+/// The arguments for the `reth db import` command
+pub struct Command {
+    /// The directory of the archive created by `reth db export`.
+    #[arg(long, short, value_name = "PATH")]
+    input: PathBuf,
+}
+
+impl Command {
+    /// Execute `db import` command
+    ///
+    /// The database and static files of the datadir must not exist or be empty.
+    pub fn execute(self, env: &EnvironmentArgs) -> eyre::Result<()> {
+        let data_dir = env.datadir.clone().resolve_datadir(env.chain.chain);
+        let db_path = data_dir.db();
+        let static_files_path = data_dir.static_files();
+
+        ensure!(is_database_empty(&db_path), "Database is not empty: {db_path:?}");
+        ensure!(
+            is_database_empty(&static_files_path),
+            "Static files directory is not empty: {static_files_path:?}"
+        );
+
+        let manifest = read_archive_manifest(&self.input)?;
+        ensure!(
+            manifest.genesis_hash == env.chain.genesis_hash(),
+            "Archive genesis hash {} does not match the chain genesis hash {}",
+            manifest.genesis_hash,
+            env.chain.genesis_hash()
+        );
+
+        let db_args = env.db.database_args();
+        let db = Arc::new(create_db(env.db.backend, &db_path, db_args.clone())?);
+        db.create_tables()?;
+        import_archive(&db, &self.input, &static_files_path)?;
+        // Recorded after the import, since the version history is keyed by timestamp.
+        db.record_client_version(db_args.client_version().clone())?;
+
+        // Static files copied from a running node may be ahead of the database, which is healed
+        // here, but they must never be behind the stage checkpoints.
+        let factory = ProviderFactory::new(
+            db,
+            env.chain.clone(),
+            StaticFileProvider::read_write(static_files_path)?,
+        );
+        let provider = factory.provider()?;
+        let has_receipt_pruning = provider.get_prune_checkpoint(PruneSegment::Receipts)?.is_some();
+        if let Some(unwind_target) =
+            factory.static_file_provider().check_consistency(&provider, has_receipt_pruning)?
+        {
+            bail!("Imported static files are behind the stage checkpoints, an unwind to {unwind_target} is required")
+        }
+
+        info!(target: "reth::cli", path = ?self.input, checkpoints = ?manifest.stage_checkpoints, "Imported database archive");
+
+        Ok(())
+    }
+}
@@ -1,8 +1,8 @@
 use super::tui::DbListTUI;
 use clap::Parser;
 use eyre::WrapErr;
-use reth_db::{DatabaseEnv, RawValue, TableViewer, Tables};
-use reth_db_api::{database::Database, table::Table};
+use reth_db::{AnyDatabaseEnv, RawValue, TableViewer, Tables};
+use reth_db_api::{database::Database, table::Table, transaction::DbTx};
 use reth_db_common::{DbTool, ListFilter};
 use reth_primitives::hex;
 use std::{cell::RefCell, sync::Arc};
@@ -51,7 +51,7 @@ pub struct Command {
 
 impl Command {
     /// Execute `db list` command
-    pub fn execute(self, tool: &DbTool<Arc<DatabaseEnv>>) -> eyre::Result<()> {
+    pub fn execute(self, tool: &DbTool<Arc<AnyDatabaseEnv>>) -> eyre::Result<()> {
         self.table.view(&ListTableViewer { tool, args: &self })
     }
 
@@ -82,7 +82,7 @@ impl Command {
 }
 
 struct ListTableViewer<'a> {
-    tool: &'a DbTool<Arc<DatabaseEnv>>,
+    tool: &'a DbTool<Arc<AnyDatabaseEnv>>,
     args: &'a Command,
 }
 
@@ -91,9 +91,7 @@ impl TableViewer<()> for ListTableViewer<'_> {
 
     fn view<T: Table>(&self) -> Result<(), Self::Error> {
         self.tool.provider_factory.db_ref().view(|tx| {
-            let table_db = tx.inner.open_db(Some(self.args.table.name())).wrap_err("Could not open db.")?;
-            let stats = tx.inner.db_stat(&table_db).wrap_err(format!("Could not find table: {}", stringify!($table)))?;
-            let total_entries = stats.entries();
+            let total_entries = tx.entries::<T>().wrap_err(format!("Could not find table: {}", self.args.table.name()))?;
             let final_entry_idx = total_entries.saturating_sub(1);
             if self.args.skip > final_entry_idx {
                 error!(
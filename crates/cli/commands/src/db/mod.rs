@@ -1,13 +1,22 @@
 use crate::common::{AccessRights, Environment, EnvironmentArgs};
 use clap::{Parser, Subcommand};
+use reth_chainspec::ChainSpec;
 use reth_db::version::{get_db_version, DatabaseVersionError, DB_VERSION};
 use reth_db_common::DbTool;
-use std::io::{self, Write};
-
+use reth_evm::execute::BlockExecutorProvider;
+use reth_provider::ChainSpecProvider;
+use std::{
+    io::{self, Write},
+    sync::Arc,
+};
+
+mod bad_block;
 mod checksum;
 mod clear;
 mod diff;
+mod export;
 mod get;
+mod import;
 mod list;
 mod stats;
 /// DB List TUI
@@ -36,6 +45,12 @@ pub enum Subcommands {
     Diff(diff::Command),
     /// Gets the content of a table for the given key
     Get(get::Command),
+    /// Exports the database and static files into a portable, checksummed archive
+    Export(export::Command),
+    /// Imports an archive created by `reth db export` into an empty datadir
+    Import(import::Command),
+    /// Lists the stored bad blocks, or dumps a bad block along with its execution witness
+    BadBlock(bad_block::Command),
     /// Deletes all database entries
     Drop {
         /// Bypasses the interactive confirmation and drops the database directly
@@ -62,11 +77,20 @@ macro_rules! db_ro_exec {
 
 impl Command {
     /// Execute `db` command
-    pub async fn execute(self) -> eyre::Result<()> {
+    pub async fn execute<E, F>(self, executor: F) -> eyre::Result<()>
+    where
+        E: BlockExecutorProvider,
+        F: FnOnce(Arc<ChainSpec>) -> E,
+    {
         let data_dir = self.env.datadir.clone().resolve_datadir(self.env.chain.chain);
         let db_path = data_dir.db();
         let static_files_path = data_dir.static_files();
 
+        // importing creates the database, so it must not be required to exist
+        if let Subcommands::Import(command) = self.command {
+            return command.execute(&self.env)
+        }
+
         // ensure the provided datadir exist
         eyre::ensure!(
             data_dir.data_dir().is_dir(),
@@ -96,7 +120,7 @@ impl Command {
             }
             Subcommands::Diff(command) => {
                 db_ro_exec!(self.env, tool, {
-                    command.execute(&tool)?;
+                    command.execute(&tool).await?;
                 });
             }
             Subcommands::Get(command) => {
@@ -104,6 +128,17 @@ impl Command {
                     command.execute(&tool)?;
                 });
             }
+            Subcommands::Export(command) => {
+                db_ro_exec!(self.env, tool, {
+                    command.execute(&static_files_path, &tool)?;
+                });
+            }
+            Subcommands::Import(_) => unreachable!("handled above"),
+            Subcommands::BadBlock(command) => {
+                let Environment { provider_factory, .. } = self.env.init(AccessRights::RO)?;
+                let executor = executor(provider_factory.chain_spec());
+                command.execute(provider_factory, executor)?;
+            }
             Subcommands::Drop { force } => {
                 if !force {
                     // Ask for confirmation
@@ -4,7 +4,10 @@ use comfy_table::{Cell, Row, Table as ComfyTable};
 use eyre::WrapErr;
 use human_bytes::human_bytes;
 use itertools::Itertools;
-use reth_db::{mdbx, static_file::iter_static_files, DatabaseEnv, TableViewer, Tables};
+use reth_db::{
+    mdbx, redb::RedbEnv, static_file::iter_static_files, AnyDatabaseEnv, DatabaseEnv, TableViewer,
+    Tables,
+};
 use reth_db_api::database::Database;
 use reth_db_common::DbTool;
 use reth_fs_util as fs;
@@ -39,7 +42,7 @@ impl Command {
     pub fn execute(
         self,
         data_dir: ChainPath<DataDirPath>,
-        tool: &DbTool<Arc<DatabaseEnv>>,
+        tool: &DbTool<Arc<AnyDatabaseEnv>>,
     ) -> eyre::Result<()> {
         if self.checksum {
             let checksum_report = self.checksum_report(tool)?;
@@ -58,7 +61,7 @@ impl Command {
         Ok(())
     }
 
-    fn db_stats_table(&self, tool: &DbTool<Arc<DatabaseEnv>>) -> eyre::Result<ComfyTable> {
+    fn db_stats_table(&self, tool: &DbTool<Arc<AnyDatabaseEnv>>) -> eyre::Result<ComfyTable> {
         let mut table = ComfyTable::new();
         table.load_preset(comfy_table::presets::ASCII_MARKDOWN);
         table.set_header([
@@ -70,7 +73,16 @@ impl Command {
             "Total Size",
         ]);
 
-        tool.provider_factory.db_ref().view(|tx| {
+        match tool.provider_factory.db_ref().as_ref() {
+            AnyDatabaseEnv::Mdbx(db) => Self::add_mdbx_stats_rows(db, &mut table)?,
+            AnyDatabaseEnv::Redb(db) => Self::add_redb_stats_rows(db, &mut table)?,
+        }
+
+        Ok(table)
+    }
+
+    fn add_mdbx_stats_rows(db: &DatabaseEnv, table: &mut ComfyTable) -> eyre::Result<()> {
+        db.view(|tx| {
             let mut db_tables = Tables::ALL.iter().map(|table| table.name()).collect::<Vec<_>>();
             db_tables.sort();
             let mut total_size = 0;
@@ -103,21 +115,7 @@ impl Command {
                 table.add_row(row);
             }
 
-            let max_widths = table.column_max_content_widths();
-            let mut separator = Row::new();
-            for width in max_widths {
-                separator.add_cell(Cell::new("-".repeat(width as usize)));
-            }
-            table.add_row(separator);
-
-            let mut row = Row::new();
-            row.add_cell(Cell::new("Tables"))
-                .add_cell(Cell::new(""))
-                .add_cell(Cell::new(""))
-                .add_cell(Cell::new(""))
-                .add_cell(Cell::new(""))
-                .add_cell(Cell::new(human_bytes(total_size as f64)));
-            table.add_row(row);
+            Self::add_total_rows(table, total_size);
 
             let freelist = tx.inner.env().freelist()?;
             let pagesize = tx.inner.db_stat(&mdbx::Database::freelist_db())?.page_size() as usize;
@@ -133,9 +131,54 @@ impl Command {
             table.add_row(row);
 
             Ok::<(), eyre::Report>(())
-        })??;
+        })?
+    }
 
-        Ok(table)
+    fn add_redb_stats_rows(db: &RedbEnv, table: &mut ComfyTable) -> eyre::Result<()> {
+        let mut db_tables = Tables::ALL.iter().map(|table| table.name()).collect::<Vec<_>>();
+        db_tables.sort();
+        let mut total_size = 0;
+        for db_table in db_tables {
+            let (entries, stats) = db
+                .table_stats(db_table)?
+                .ok_or_else(|| eyre::eyre!("Could not find table: {db_table}"))?;
+
+            // redb stores large values inline in leaf pages, so there are no overflow pages.
+            let table_size =
+                (stats.stored_bytes() + stats.metadata_bytes() + stats.fragmented_bytes()) as usize;
+
+            total_size += table_size;
+            let mut row = Row::new();
+            row.add_cell(Cell::new(db_table))
+                .add_cell(Cell::new(entries))
+                .add_cell(Cell::new(stats.branch_pages()))
+                .add_cell(Cell::new(stats.leaf_pages()))
+                .add_cell(Cell::new(""))
+                .add_cell(Cell::new(human_bytes(table_size as f64)));
+            table.add_row(row);
+        }
+
+        Self::add_total_rows(table, total_size);
+
+        Ok(())
+    }
+
+    fn add_total_rows(table: &mut ComfyTable, total_size: usize) {
+        let max_widths = table.column_max_content_widths();
+        let mut separator = Row::new();
+        for width in max_widths {
+            separator.add_cell(Cell::new("-".repeat(width as usize)));
+        }
+        table.add_row(separator);
+
+        let mut row = Row::new();
+        row.add_cell(Cell::new("Tables"))
+            .add_cell(Cell::new(""))
+            .add_cell(Cell::new(""))
+            .add_cell(Cell::new(""))
+            .add_cell(Cell::new(""))
+            .add_cell(Cell::new(human_bytes(total_size as f64)));
+        table.add_row(row);
     }
 
     fn static_files_stats_table(
@@ -306,7 +349,7 @@ impl Command {
         Ok(table)
     }
 
-    fn checksum_report(&self, tool: &DbTool<Arc<DatabaseEnv>>) -> eyre::Result<ComfyTable> {
+    fn checksum_report(&self, tool: &DbTool<Arc<AnyDatabaseEnv>>) -> eyre::Result<ComfyTable> {
         let mut table = ComfyTable::new();
         table.load_preset(comfy_table::presets::ASCII_MARKDOWN);
         table.set_header(vec![Cell::new("Table"), Cell::new("Checksum"), Cell::new("Elapsed")]);
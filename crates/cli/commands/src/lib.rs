@@ -20,5 +20,7 @@ pub mod p2p;
 pub mod prune;
 pub mod recover;
 pub mod stage;
+pub mod static_file;
 #[cfg(feature = "dev")]
 pub mod test_vectors;
+pub mod verify_witness;
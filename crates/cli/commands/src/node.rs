@@ -4,13 +4,13 @@ use clap::{value_parser, Args, Parser};
 use reth_chainspec::ChainSpec;
 use reth_cli_runner::CliContext;
 use reth_cli_util::parse_socket_address;
-use reth_db::{init_db, DatabaseEnv};
+use reth_db::{backend::init_db, AnyDatabaseEnv};
 use reth_node_builder::{NodeBuilder, WithLaunchContext};
 use reth_node_core::{
     args::{
         utils::{chain_help, chain_value_parser, SUPPORTED_CHAINS},
-        DatabaseArgs, DatadirArgs, DebugArgs, DevArgs, NetworkArgs, PayloadBuilderArgs,
-        PruningArgs, RpcServerArgs, TxPoolArgs,
+        DatabaseArgs, DatadirArgs, DebugArgs, DevArgs, EngineTreeArgs, NetworkArgs,
+        PayloadBuilderArgs, PruningArgs, RpcServerArgs, TxPoolArgs,
     },
     node_config::NodeConfig,
     version,
@@ -104,6 +104,10 @@ pub struct NodeCommand<Ext: clap::Args + fmt::Debug = NoArgs> {
     #[command(flatten)]
     pub pruning: PruningArgs,
 
+    /// All engine related arguments
+    #[command(flatten)]
+    pub engine: EngineTreeArgs,
+
     /// Additional cli arguments
     #[command(flatten, next_help_heading = "Extension")]
     pub ext: Ext,
@@ -132,7 +136,7 @@ impl<Ext: clap::Args + fmt::Debug> NodeCommand<Ext> {
     /// closure.
     pub async fn execute<L, Fut>(self, ctx: CliContext, launcher: L) -> eyre::Result<()>
     where
-        L: FnOnce(WithLaunchContext<NodeBuilder<Arc<DatabaseEnv>>>, Ext) -> Fut,
+        L: FnOnce(WithLaunchContext<NodeBuilder<Arc<AnyDatabaseEnv>>>, Ext) -> Fut,
         Fut: Future<Output = eyre::Result<()>>,
     {
         tracing::info!(target: "reth::cli", version = ?version::SHORT_VERSION, "Starting reth");
@@ -152,9 +156,19 @@ impl<Ext: clap::Args + fmt::Debug> NodeCommand<Ext> {
             db,
             dev,
             pruning,
+            engine,
             ext,
         } = self;
 
+        // the proof window is checked here, since its maximum is configurable as well
+        if rpc.rpc_eth_proof_window > rpc.rpc_max_eth_proof_window {
+            eyre::bail!(
+                "--rpc.eth-proof-window {} exceeds --rpc.max-eth-proof-window {}",
+                rpc.rpc_eth_proof_window,
+                rpc.rpc_max_eth_proof_window
+            )
+        }
+
         // set up node config
         let mut node_config = NodeConfig {
             datadir,
@@ -170,6 +184,7 @@ impl<Ext: clap::Args + fmt::Debug> NodeCommand<Ext> {
             db,
             dev,
             pruning,
+            engine,
         };
 
         // Register the prometheus recorder before creating the database,
@@ -179,8 +194,10 @@ impl<Ext: clap::Args + fmt::Debug> NodeCommand<Ext> {
         let data_dir = node_config.datadir();
         let db_path = data_dir.db();
 
-        tracing::info!(target: "reth::cli", path = ?db_path, "Opening database");
-        let database = Arc::new(init_db(db_path.clone(), self.db.database_args())?.with_metrics());
+        tracing::info!(target: "reth::cli", path = ?db_path, backend = %self.db.backend, "Opening database");
+        let database = Arc::new(
+            init_db(self.db.backend, db_path.clone(), self.db.database_args())?.with_metrics(),
+        );
 
         if with_unused_ports {
             node_config = node_config.with_unused_ports();
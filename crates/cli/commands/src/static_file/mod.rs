@@ -0,0 +1,38 @@
+//! Command for maintaining the static files.
+use crate::common::{AccessRights, EnvironmentArgs};
+use clap::{Parser, Subcommand};
+
+mod recompress;
+
+/// `reth static-file` command
+#[derive(Debug, Parser)]
+pub struct Command {
+    #[command(flatten)]
+    env: EnvironmentArgs,
+
+    #[command(subcommand)]
+    command: Subcommands,
+}
+
+#[derive(Subcommand, Debug)]
+/// `reth static-file` subcommands
+pub enum Subcommands {
+    /// Rewrites the static files with another compression, and reports the size savings
+    Recompress(recompress::Command),
+}
+
+impl Command {
+    /// Execute `static-file` command
+    pub async fn execute(self) -> eyre::Result<()> {
+        match self.command {
+            Subcommands::Recompress(command) => {
+                // Opening the environment with write access locks the storage, so that no node
+                // is running while the static files are being replaced.
+                let env = self.env.init(AccessRights::RW)?;
+                command.execute(&env.data_dir.static_files())?;
+            }
+        }
+
+        Ok(())
+    }
+}
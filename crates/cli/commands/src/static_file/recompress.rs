@@ -0,0 +1,214 @@
+use clap::{Parser, ValueEnum};
+use comfy_table::{Cell, Row, Table as ComfyTable};
+use eyre::ensure;
+use human_bytes::human_bytes;
+use itertools::Itertools;
+use reth_db::static_file::iter_static_files;
+use reth_fs_util as fs;
+use reth_nippy_jar::{
+    compression::{ColumnCodec, DeltaVarint, DictionarySamples, Lz4, ZstdColumn},
+    ConsistencyFailStrategy, NippyJar, NippyJarCursor, NippyJarHeader, NippyJarWriter,
+};
+use reth_static_file_types::{find_fixed_range, SegmentHeader, StaticFileSegment};
+use std::path::Path;
+use tracing::{info, warn};
+
+/// Codec of a static file column.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum Codec {
+    /// Values are stored as is
+    Uncompressed,
+    /// LZ4 compression
+    Lz4,
+    /// Zstd compression without dictionaries
+    Zstd,
+    /// Zstd compression with a dictionary trained on the column values
+    ZstdDict,
+    /// Delta and varint encoding of little-endian integers, for monotonic integer columns
+    DeltaVarint,
+}
+
+#[derive(Parser, Debug)]
+/// The arguments for the `reth static-file recompress` command
+pub struct Command {
+    /// The static file segments to recompress. All segments are recompressed if not provided.
+    #[arg(long, value_delimiter = ',')]
+    segments: Vec<StaticFileSegment>,
+
+    /// The column codecs. Either a single codec for all columns, or a codec per column of the
+    /// segment.
+    #[arg(long, value_delimiter = ',', default_value = "zstd-dict")]
+    codecs: Vec<Codec>,
+
+    /// Zstd compression level. A level of `0` uses zstd's default.
+    #[arg(long = "zstd.level", default_value_t = 0)]
+    zstd_level: i32,
+
+    /// Max size of a zstd dictionary in bytes.
+    #[arg(long = "zstd.dict-size", default_value_t = 64 * 1024)]
+    zstd_dict_size: usize,
+
+    /// Number of rows appended to a static file after which a new zstd dictionary is trained.
+    ///
+    /// Only affects the static files that are still being appended to. `0` disables retraining.
+    #[arg(long = "zstd.retrain-interval", default_value_t = 100_000)]
+    zstd_retrain_interval: usize,
+
+    /// Only reports the size savings, without replacing the static files.
+    #[arg(long)]
+    dry_run: bool,
+}
+
+impl Command {
+    /// Execute `static-file recompress` command
+    pub fn execute(self, static_files_path: &Path) -> eyre::Result<()> {
+        let mut table = ComfyTable::new();
+        table.load_preset(comfy_table::presets::ASCII_MARKDOWN);
+        table.set_header(["Segment", "Block Range", "Size", "Recompressed Size", "Savings"]);
+
+        let (mut total_size, mut total_recompressed_size) = (0, 0);
+        let static_files = iter_static_files(static_files_path)?;
+        for (segment, ranges) in static_files.into_iter().sorted_by_key(|(segment, _)| *segment) {
+            if !self.segments.is_empty() && !self.segments.contains(&segment) {
+                continue
+            }
+
+            for (block_range, _) in ranges {
+                let path = static_files_path
+                    .join(segment.filename(&find_fixed_range(block_range.start())));
+
+                let Some((size, recompressed_size)) = self.recompress(segment, &path)? else {
+                    continue
+                };
+                total_size += size;
+                total_recompressed_size += recompressed_size;
+
+                let mut row = Row::new();
+                row.add_cell(Cell::new(segment))
+                    .add_cell(Cell::new(format!("{block_range}")))
+                    .add_cell(Cell::new(human_bytes(size as f64)))
+                    .add_cell(Cell::new(human_bytes(recompressed_size as f64)))
+                    .add_cell(Cell::new(savings(size, recompressed_size)));
+                table.add_row(row);
+            }
+        }
+
+        let mut row = Row::new();
+        row.add_cell(Cell::new("Total"))
+            .add_cell(Cell::new(""))
+            .add_cell(Cell::new(human_bytes(total_size as f64)))
+            .add_cell(Cell::new(human_bytes(total_recompressed_size as f64)))
+            .add_cell(Cell::new(savings(total_size, total_recompressed_size)));
+        table.add_row(row);
+
+        println!("{table}");
+
+        Ok(())
+    }
+
+    /// Recompresses the static file at `path`, returning its size before and after.
+    ///
+    /// The static file is rewritten next to the original one, which is only replaced once the
+    /// rewrite is complete, see [`NippyJar::replace`]. Returns `None` if the static file is
+    /// skipped.
+    fn recompress(
+        &self,
+        segment: StaticFileSegment,
+        path: &Path,
+    ) -> eyre::Result<Option<(u64, u64)>> {
+        let jar = NippyJar::<SegmentHeader>::load(path)?;
+        if jar.rows() == 0 {
+            return Ok(None)
+        }
+        if jar.index_path().exists() {
+            warn!(target: "reth::cli", ?path, "Skipping static file with an index");
+            return Ok(None)
+        }
+
+        let codecs = || self.codecs(jar.columns());
+        ensure!(
+            self.codecs.len() == 1 || self.codecs.len() == jar.columns(),
+            "{segment} static files have {} columns, but {} codecs were provided",
+            jar.columns(),
+            self.codecs.len()
+        );
+
+        info!(target: "reth::cli", ?path, rows = jar.rows(), "Recompressing static file");
+
+        // Removes the leftovers of a recompression that was interrupted before replacing the
+        // static file. Loading the static file already finished an interrupted replacement.
+        let recompressed_path = NippyJar::replacement_path(path);
+        NippyJar::new_without_header(jar.columns(), &recompressed_path).delete()?;
+        let mut recompressed =
+            NippyJar::new(jar.columns(), &recompressed_path, jar.user_header().clone())
+                .with_column_codecs(codecs());
+
+        // Trains the dictionaries on the first values of each column
+        let mut samples = DictionarySamples::new(&codecs());
+        if samples.is_used() {
+            let mut cursor = NippyJarCursor::new(&jar)?;
+            while !samples.is_full() {
+                let Some(row) = cursor.next_row()? else { break };
+                for (column, value) in row.into_iter().enumerate() {
+                    samples.add(column, value);
+                }
+            }
+            recompressed.train_dictionaries(&mut samples, jar.rows());
+        }
+
+        let mut writer = NippyJarWriter::new(recompressed, ConsistencyFailStrategy::ThrowError)?;
+        let mut cursor = NippyJarCursor::new(&jar)?;
+        while let Some(row) = cursor.next_row()? {
+            for value in row {
+                writer.append_column(Some(Ok(value)))?;
+            }
+        }
+        writer.commit()?;
+        let recompressed = writer.into_jar();
+
+        let size = jar_size(&jar);
+        let recompressed_size = jar_size(&recompressed);
+
+        if self.dry_run {
+            recompressed.delete()?;
+        } else {
+            recompressed.replace(path)?;
+        }
+
+        Ok(Some((size, recompressed_size)))
+    }
+
+    /// Returns the codecs for the `columns` of a static file.
+    fn codecs(&self, columns: usize) -> Vec<ColumnCodec> {
+        (0..columns)
+            .map(|column| {
+                match self.codecs.get(column).or(self.codecs.first()).expect("at least one codec") {
+                    Codec::Uncompressed => ColumnCodec::Uncompressed,
+                    Codec::Lz4 => ColumnCodec::Lz4(Lz4::default()),
+                    Codec::Zstd => ColumnCodec::Zstd(ZstdColumn::new(self.zstd_level)),
+                    Codec::ZstdDict => ColumnCodec::Zstd(
+                        ZstdColumn::new(self.zstd_level)
+                            .with_dictionary(self.zstd_dict_size, self.zstd_retrain_interval),
+                    ),
+                    Codec::DeltaVarint => ColumnCodec::DeltaVarint(DeltaVarint::default()),
+                }
+            })
+            .collect()
+    }
+}
+
+/// Returns the total size of the data, offsets and configuration files of `jar`.
+fn jar_size<H: NippyJarHeader>(jar: &NippyJar<H>) -> u64 {
+    [jar.data_path().to_path_buf(), jar.offsets_path(), jar.config_path()]
+        .iter()
+        .map(|path| fs::metadata(path).map(|metadata| metadata.len()).unwrap_or_default())
+        .sum()
+}
+
+/// Formats the size savings of `recompressed_size` relative to `size`.
+fn savings(size: u64, recompressed_size: u64) -> String {
+    if size == 0 {
+        return "N/A".to_string()
+    }
+    format!("{:.2}%", (size as f64 - recompressed_size as f64) / size as f64 * 100.0)
+}
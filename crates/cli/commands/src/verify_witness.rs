@@ -0,0 +1,106 @@
+//! Command that verifies a block statelessly from its execution witness.
+use alloy_rlp::Decodable;
+use clap::Parser;
+use eyre::OptionExt;
+use reth_beacon_consensus::EthBeaconConsensus;
+use reth_chainspec::ChainSpec;
+use reth_evm::execute::BlockExecutorProvider;
+use reth_node_core::args::utils::{chain_help, chain_value_parser, SUPPORTED_CHAINS};
+use reth_primitives::{hex, Block, Bytes, B256, U256};
+use std::{collections::HashMap, path::PathBuf, sync::Arc};
+use tracing::info;
+
+/// Executes a block on top of the state of its execution witness and verifies the post-state root,
+/// without a database.
+#[derive(Debug, Parser)]
+pub struct VerifyWitnessCommand {
+    /// The chain this node is running.
+    ///
+    /// Possible values are either a built-in chain or the path to a chain specification file.
+    #[arg(
+        long,
+        value_name = "CHAIN_OR_PATH",
+        long_help = chain_help(),
+        default_value = SUPPORTED_CHAINS[0],
+        value_parser = chain_value_parser
+    )]
+    chain: Arc<ChainSpec>,
+
+    /// The path to the hex encoded RLP of the block, as returned by `debug_getRawBlock`.
+    #[arg(long, value_name = "PATH")]
+    block: PathBuf,
+
+    /// The path to the JSON execution witness of the block, as returned by
+    /// `debug_executionWitness`.
+    #[arg(long, value_name = "PATH")]
+    witness: PathBuf,
+
+    /// The total difficulty of the chain up to the block.
+    ///
+    /// Defaults to the final total difficulty of the merge, i.e. the block is post-merge.
+    #[arg(long, value_name = "TD")]
+    total_difficulty: Option<U256>,
+}
+
+impl VerifyWitnessCommand {
+    /// Execute the `verify-witness` command
+    pub async fn execute<E, F>(self, executor: F) -> eyre::Result<()>
+    where
+        E: BlockExecutorProvider,
+        F: FnOnce(Arc<ChainSpec>) -> E,
+    {
+        let encoded = hex::decode(reth_fs_util::read_to_string(&self.block)?.trim())?;
+        let block = Block::decode(&mut &encoded[..])?
+            .seal_slow()
+            .seal_with_senders()
+            .ok_or_eyre("failed to recover block senders")?;
+        let witness: HashMap<B256, Bytes> = reth_fs_util::read_json_file(&self.witness)?;
+
+        let total_difficulty = self
+            .total_difficulty
+            .or_else(|| self.chain.get_final_paris_total_difficulty())
+            .unwrap_or_default();
+        let consensus = EthBeaconConsensus::new(self.chain.clone());
+        let executor = executor(self.chain);
+
+        info!(target: "reth::cli", number = block.number, hash = %block.hash(), "Verifying block");
+        let output = reth_stateless::verify_witness(
+            &executor,
+            &consensus,
+            &block,
+            total_difficulty,
+            witness.into_values(),
+        )?;
+        info!(
+            target: "reth::cli",
+            number = block.number,
+            hash = %block.hash(),
+            gas_used = output.gas_used,
+            state_root = %block.state_root,
+            "Verified block"
+        );
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_verify_witness_command() {
+        let args = VerifyWitnessCommand::parse_from([
+            "reth",
+            "--block",
+            "block.hex",
+            "--witness",
+            "witness.json",
+            "--total-difficulty",
+            "1000",
+        ]);
+        assert_eq!(args.block, PathBuf::from("block.hex"));
+        assert_eq!(args.witness, PathBuf::from("witness.json"));
+        assert_eq!(args.total_difficulty, Some(U256::from(1000)));
+    }
+}
@@ -26,6 +26,8 @@ pub struct Config {
     pub peers: PeersConfig,
     /// Configuration for peer sessions.
     pub sessions: SessionsConfig,
+    /// Configuration for the execution extensions.
+    pub exex: ExExConfig,
 }
 
 impl Config {
@@ -298,18 +300,29 @@ pub struct EtlConfig {
     pub dir: Option<PathBuf>,
     /// The maximum size in bytes of data held in memory before being flushed to disk as a file.
     pub file_size: usize,
+    /// The number of threads sorting and flushing data to disk in the background. The in-memory
+    /// data is split between them and the collecting thread, `0` flushes data on the collecting
+    /// thread.
+    pub workers: usize,
+    /// Whether the files flushed to disk are compressed.
+    pub compression: bool,
 }
 
 impl Default for EtlConfig {
     fn default() -> Self {
-        Self { dir: None, file_size: Self::default_file_size() }
+        Self {
+            dir: None,
+            file_size: Self::default_file_size(),
+            workers: Self::default_workers(),
+            compression: false,
+        }
     }
 }
 
 impl EtlConfig {
     /// Creates an ETL configuration
     pub const fn new(dir: Option<PathBuf>, file_size: usize) -> Self {
-        Self { dir, file_size }
+        Self { dir, file_size, workers: Self::default_workers(), compression: false }
     }
 
     /// Return default ETL directory from datadir path.
@@ -322,6 +335,11 @@ impl EtlConfig {
         // 500 MB
         500 * (1024 * 1024)
     }
+
+    /// Default number of ETL workers.
+    pub const fn default_workers() -> usize {
+        0
+    }
 }
 
 /// History stage configuration.
@@ -362,6 +380,15 @@ impl PruneConfig {
     }
 }
 
+/// Execution extensions configuration.
+#[derive(Debug, Clone, Copy, Default, Deserialize, PartialEq, Eq, Serialize)]
+#[serde(default)]
+pub struct ExExConfig {
+    /// Whether notifications are written to a write-ahead log before they are sent to the
+    /// execution extensions, so that unfinished notifications are replayed after a restart.
+    pub wal: bool,
+}
+
 /// Helper type to support older versions of Duration deserialization.
 fn deserialize_duration<'de, D>(deserializer: D) -> Result<Option<Duration>, D::Error>
 where
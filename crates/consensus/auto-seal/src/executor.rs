@@ -0,0 +1,154 @@
+//! Block execution that accounts for the overrides of sealed blocks.
+
+use crate::overrides::{execute_with_overrides, AutoSealOverrides};
+use reth_evm::execute::{
+    BatchExecutor, BlockExecutionError, BlockExecutionInput, BlockExecutionOutput,
+    BlockExecutorProvider, ExecutionOutcome, Executor, NoopHook, OnStateHook, ProviderError,
+};
+use reth_primitives::{BlockNumber, BlockWithSenders, Receipt};
+use reth_prune_types::PruneModes;
+use reth_revm::Database;
+use std::fmt::Display;
+
+/// A [`BlockExecutorProvider`] that applies the [`AutoSealOverrides`] of blocks sealed by the
+/// auto seal miner when they are executed.
+///
+/// All components of a dev node that execute blocks must use this, otherwise blocks sealed with
+/// overrides fail with a state root mismatch.
+#[derive(Debug, Clone)]
+pub struct AutoSealExecutorProvider<E> {
+    inner: E,
+    overrides: AutoSealOverrides,
+}
+
+impl<E> AutoSealExecutorProvider<E> {
+    /// Creates a new provider that wraps the given executor.
+    pub const fn new(inner: E, overrides: AutoSealOverrides) -> Self {
+        Self { inner, overrides }
+    }
+}
+
+impl<E: BlockExecutorProvider> BlockExecutorProvider for AutoSealExecutorProvider<E> {
+    type Executor<DB: Database<Error: Into<ProviderError> + Display>> = AutoSealExecutor<E, DB>;
+
+    type BatchExecutor<DB: Database<Error: Into<ProviderError> + Display>> =
+        AutoSealBatchExecutor<E::BatchExecutor<DB>>;
+
+    fn executor<DB>(&self, db: DB) -> Self::Executor<DB>
+    where
+        DB: Database<Error: Into<ProviderError> + Display>,
+    {
+        AutoSealExecutor { inner: self.inner.clone(), overrides: self.overrides.clone(), db }
+    }
+
+    fn batch_executor<DB>(&self, db: DB) -> Self::BatchExecutor<DB>
+    where
+        DB: Database<Error: Into<ProviderError> + Display>,
+    {
+        AutoSealBatchExecutor {
+            inner: self.inner.batch_executor(db),
+            overrides: self.overrides.clone(),
+        }
+    }
+}
+
+/// The single block executor of the [`AutoSealExecutorProvider`].
+#[derive(Debug)]
+pub struct AutoSealExecutor<E, DB> {
+    inner: E,
+    overrides: AutoSealOverrides,
+    db: DB,
+}
+
+impl<E, DB> Executor<DB> for AutoSealExecutor<E, DB>
+where
+    E: BlockExecutorProvider,
+    DB: Database<Error: Into<ProviderError> + Display>,
+{
+    type Input<'a> = BlockExecutionInput<'a, BlockWithSenders>;
+    type Output = BlockExecutionOutput<Receipt>;
+    type Error = BlockExecutionError;
+
+    fn execute(self, input: Self::Input<'_>) -> Result<Self::Output, Self::Error> {
+        self.execute_with_state_hook(input, NoopHook)
+    }
+
+    /// Executes the block, invoking the hook with the state changes of every transaction.
+    ///
+    /// The hook is ignored for blocks sealed with overrides.
+    fn execute_with_state_hook<F>(
+        self,
+        input: Self::Input<'_>,
+        state_hook: F,
+    ) -> Result<Self::Output, Self::Error>
+    where
+        F: OnStateHook,
+    {
+        let overrides = (!self.overrides.is_empty())
+            .then(|| self.overrides.get(&input.block.header.hash_slow()))
+            .flatten();
+
+        match overrides {
+            Some(overrides) => execute_with_overrides(
+                &self.inner,
+                self.db,
+                input.block,
+                input.total_difficulty,
+                &overrides,
+            ),
+            None => self.inner.executor(self.db).execute_with_state_hook(input, state_hook),
+        }
+    }
+}
+
+/// The batch executor of the [`AutoSealExecutorProvider`].
+///
+/// Overrides can't be applied in the middle of a batch, so this fails on blocks that were sealed
+/// with overrides.
+#[derive(Debug)]
+pub struct AutoSealBatchExecutor<B> {
+    inner: B,
+    overrides: AutoSealOverrides,
+}
+
+impl<B, DB> BatchExecutor<DB> for AutoSealBatchExecutor<B>
+where
+    B: for<'a> BatchExecutor<
+        DB,
+        Input<'a> = BlockExecutionInput<'a, BlockWithSenders>,
+        Output = ExecutionOutcome,
+        Error = BlockExecutionError,
+    >,
+{
+    type Input<'a> = BlockExecutionInput<'a, BlockWithSenders>;
+    type Output = ExecutionOutcome;
+    type Error = BlockExecutionError;
+
+    fn execute_and_verify_one(&mut self, input: Self::Input<'_>) -> Result<(), Self::Error> {
+        if !self.overrides.is_empty() &&
+            self.overrides.get(&input.block.header.hash_slow()).is_some()
+        {
+            return Err(BlockExecutionError::msg(format!(
+                "block {} was sealed with state overrides and can't be executed in a batch",
+                input.block.number
+            )))
+        }
+        self.inner.execute_and_verify_one(input)
+    }
+
+    fn finalize(self) -> Self::Output {
+        self.inner.finalize()
+    }
+
+    fn set_tip(&mut self, tip: BlockNumber) {
+        self.inner.set_tip(tip)
+    }
+
+    fn set_prune_modes(&mut self, prune_modes: PruneModes) {
+        self.inner.set_prune_modes(prune_modes)
+    }
+
+    fn size_hint(&self) -> Option<usize> {
+        self.inner.size_hint()
+    }
+}
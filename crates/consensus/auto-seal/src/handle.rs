@@ -0,0 +1,115 @@
+//! A handle to control the auto seal miner.
+
+use crate::{StateOverrides, Storage};
+use reth_execution_errors::BlockExecutionError;
+use reth_primitives::{B256, U256};
+use std::time::{SystemTime, UNIX_EPOCH};
+use tokio::sync::{mpsc::UnboundedSender, oneshot};
+
+/// Errors returned by the [`AutoSealHandle`].
+#[derive(Debug, thiserror::Error)]
+pub enum AutoSealError {
+    /// The block could not be built.
+    #[error(transparent)]
+    Execution(#[from] BlockExecutionError),
+    /// The engine did not accept the sealed block.
+    #[error("sealed block {0} was not accepted by the engine")]
+    Rejected(B256),
+    /// The mining task is not running anymore.
+    #[error("mining task is not running")]
+    MiningTaskClosed,
+}
+
+/// Commands sent from an [`AutoSealHandle`] to the [`MiningTask`](crate::MiningTask).
+#[derive(Debug)]
+pub(crate) enum MiningCommand {
+    /// Seals the given number of blocks with the best transactions of the pool.
+    Mine { blocks: u64, tx: oneshot::Sender<Result<(), AutoSealError>> },
+    /// Seals a block without transactions that applies the state overrides.
+    SetState { overrides: StateOverrides, tx: oneshot::Sender<Result<(), AutoSealError>> },
+    /// Reverts the chain to the snapshot with the given ID.
+    Revert { id: U256, tx: oneshot::Sender<Result<bool, AutoSealError>> },
+}
+
+/// A handle to control the [`MiningTask`](crate::MiningTask) independently of its
+/// [`MiningMode`](crate::MiningMode).
+///
+/// Blocks sealed through the handle are made canonical before the handle returns.
+#[derive(Debug, Clone)]
+pub struct AutoSealHandle {
+    storage: Storage,
+    to_miner: UnboundedSender<MiningCommand>,
+}
+
+impl AutoSealHandle {
+    /// Creates a new handle.
+    pub(crate) const fn new(storage: Storage, to_miner: UnboundedSender<MiningCommand>) -> Self {
+        Self { storage, to_miner }
+    }
+
+    /// Seals the given number of blocks, each with the best transactions of the pool.
+    pub async fn mine(&self, blocks: u64) -> Result<(), AutoSealError> {
+        self.send(|tx| MiningCommand::Mine { blocks, tx }).await?
+    }
+
+    /// Seals a block without transactions that applies the given state overrides.
+    pub async fn set_state(&self, overrides: StateOverrides) -> Result<(), AutoSealError> {
+        self.send(|tx| MiningCommand::SetState { overrides, tx }).await?
+    }
+
+    /// Takes a snapshot of the current chain and returns its ID.
+    ///
+    /// The block of the oldest snapshot is reported as finalized to the engine, so that the chain
+    /// can be reverted to it.
+    pub async fn snapshot(&self) -> U256 {
+        self.storage.write().await.snapshot()
+    }
+
+    /// Reverts the chain to the snapshot with the given ID.
+    ///
+    /// The snapshot and all snapshots taken after it are removed. Since the engine only reorgs to
+    /// new blocks, this seals an empty block on top of the snapshot's block, so the chain ends up
+    /// one block past the snapshot, with the same state.
+    ///
+    /// Returns `false` if the snapshot does not exist.
+    pub async fn revert(&self, id: U256) -> Result<bool, AutoSealError> {
+        self.send(|tx| MiningCommand::Revert { id, tx }).await?
+    }
+
+    /// Moves the timestamp of the following blocks forward by the given number of seconds.
+    ///
+    /// Returns the total offset from the current time in seconds.
+    pub async fn increase_time(&self, seconds: u64) -> i64 {
+        let mut storage = self.storage.write().await;
+        storage.time_offset =
+            storage.time_offset.saturating_add(seconds.try_into().unwrap_or(i64::MAX));
+        storage.time_offset
+    }
+
+    /// Sets the current time of the following blocks to the given timestamp.
+    ///
+    /// Returns the total offset from the current time in seconds.
+    pub async fn set_time(&self, timestamp: u64) -> i64 {
+        let now = SystemTime::now().duration_since(UNIX_EPOCH).unwrap_or_default().as_secs();
+        let mut storage = self.storage.write().await;
+        storage.time_offset = i64::try_from(timestamp)
+            .unwrap_or(i64::MAX)
+            .saturating_sub(now.try_into().unwrap_or(i64::MAX));
+        storage.time_offset
+    }
+
+    /// Sets the timestamp of the next block only.
+    pub async fn set_next_block_timestamp(&self, timestamp: u64) {
+        self.storage.write().await.next_timestamp = Some(timestamp);
+    }
+
+    /// Sends a command to the mining task and waits for its response.
+    async fn send<T>(
+        &self,
+        command: impl FnOnce(oneshot::Sender<T>) -> MiningCommand,
+    ) -> Result<T, AutoSealError> {
+        let (tx, rx) = oneshot::channel();
+        self.to_miner.send(command(tx)).map_err(|_| AutoSealError::MiningTaskClosed)?;
+        rx.await.map_err(|_| AutoSealError::MiningTaskClosed)
+    }
+}
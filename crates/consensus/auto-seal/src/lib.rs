@@ -6,6 +6,10 @@
 //!
 //! These downloaders poll the miner, assemble the block, and return transactions that are ready to
 //! be mined.
+//!
+//! The miner can also be controlled with an [`AutoSealHandle`], e.g. to seal blocks on demand or
+//! to seal [`StateOverrides`] into a block. Blocks sealed with overrides must be executed with the
+//! [`AutoSealExecutorProvider`].
 
 #![doc(
     html_logo_url = "https://raw.githubusercontent.com/paradigmxyz/reth/main/assets/reth-docs.png",
@@ -19,20 +23,18 @@ use reth_beacon_consensus::BeaconEngineMessage;
 use reth_chainspec::{ChainSpec, EthereumHardforks};
 use reth_consensus::{Consensus, ConsensusError, PostExecutionInput};
 use reth_engine_primitives::EngineTypes;
-use reth_execution_errors::{
-    BlockExecutionError, BlockValidationError, InternalBlockExecutionError,
-};
+use reth_execution_errors::{BlockExecutionError, InternalBlockExecutionError};
 use reth_execution_types::ExecutionOutcome;
 use reth_primitives::{
-    eip4844::calculate_excess_blob_gas, proofs, Block, BlockBody, BlockHash, BlockHashOrNumber,
-    BlockNumber, BlockWithSenders, Bloom, Header, Requests, SealedBlock, SealedHeader,
-    TransactionSigned, Withdrawals, B256, U256,
+    eip4844::calculate_excess_blob_gas, proofs, Address, Block, BlockBody, BlockHash,
+    BlockHashOrNumber, BlockNumber, BlockWithSenders, Bloom, Header, Requests, SealedBlock,
+    SealedHeader, TransactionSigned, Withdrawals, B256, U256,
 };
 use reth_provider::{BlockReaderIdExt, StateProviderFactory, StateRootProvider};
 use reth_revm::database::StateProviderDatabase;
 use reth_transaction_pool::TransactionPool;
 use std::{
-    collections::HashMap,
+    collections::{BTreeMap, HashMap},
     sync::Arc,
     time::{SystemTime, UNIX_EPOCH},
 };
@@ -40,12 +42,19 @@ use tokio::sync::{mpsc::UnboundedSender, RwLock, RwLockReadGuard, RwLockWriteGua
 use tracing::trace;
 
 mod client;
+mod executor;
+mod handle;
 mod mode;
+mod overrides;
 mod task;
 
 pub use crate::client::AutoSealClient;
+pub use executor::{AutoSealBatchExecutor, AutoSealExecutor, AutoSealExecutorProvider};
+pub use handle::{AutoSealError, AutoSealHandle};
 pub use mode::{FixedBlockTimeMiner, MiningMode, ReadyTransactionMiner};
-use reth_evm::execute::{BlockExecutionOutput, BlockExecutorProvider, Executor};
+use overrides::{execute_with_overrides, BlockOverrides};
+pub use overrides::{AccountOverride, AutoSealOverrides, StateOverrides};
+use reth_evm::execute::{BlockExecutionOutput, BlockExecutorProvider};
 pub use task::MiningTask;
 
 /// A consensus implementation intended for local development and testing purposes.
@@ -107,6 +116,7 @@ pub struct AutoSealBuilder<Client, Pool, Engine: EngineTypes, EvmConfig> {
     storage: Storage,
     to_engine: UnboundedSender<BeaconEngineMessage<Engine>>,
     evm_config: EvmConfig,
+    overrides: AutoSealOverrides,
 }
 
 // === impl AutoSealBuilder ===
@@ -140,6 +150,7 @@ where
             mode,
             to_engine,
             evm_config,
+            overrides: AutoSealOverrides::default(),
         }
     }
 
@@ -149,12 +160,21 @@ where
         self
     }
 
+    /// Sets the [`AutoSealOverrides`] the overrides of sealed blocks are recorded in.
+    ///
+    /// These must be shared with the [`AutoSealExecutorProvider`]s of the node.
+    pub fn overrides(mut self, overrides: AutoSealOverrides) -> Self {
+        self.overrides = overrides;
+        self
+    }
+
     /// Consumes the type and returns all components
     #[track_caller]
     pub fn build(
         self,
     ) -> (AutoSealConsensus, AutoSealClient, MiningTask<Client, Pool, EvmConfig, Engine>) {
-        let Self { client, consensus, pool, mode, storage, to_engine, evm_config } = self;
+        let Self { client, consensus, pool, mode, storage, to_engine, evm_config, overrides } =
+            self;
         let auto_client = AutoSealClient::new(storage.clone());
         let task = MiningTask::new(
             Arc::clone(&consensus.chain_spec),
@@ -164,6 +184,7 @@ where
             client,
             pool,
             evm_config,
+            overrides,
         );
         (consensus, auto_client, task)
     }
@@ -219,6 +240,23 @@ pub(crate) struct StorageInner {
     pub(crate) best_hash: B256,
     /// The total difficulty of the chain until this block
     pub(crate) total_difficulty: U256,
+    /// Seconds added to the current time to get the timestamp of a new block.
+    pub(crate) time_offset: i64,
+    /// The timestamp of the next block, if it was set explicitly.
+    pub(crate) next_timestamp: Option<u64>,
+    /// Snapshots the chain can be reverted to, keyed by their ID.
+    pub(crate) snapshots: BTreeMap<U256, Snapshot>,
+    /// The ID of the next snapshot.
+    pub(crate) next_snapshot_id: U256,
+}
+
+/// A block the chain can be reverted to.
+#[derive(Debug, Clone)]
+pub(crate) struct Snapshot {
+    /// The header of the block.
+    pub(crate) header: SealedHeader,
+    /// The time offset when the snapshot was taken.
+    pub(crate) time_offset: i64,
 }
 
 // === impl StorageInner ===
@@ -241,6 +279,76 @@ impl StorageInner {
         self.headers.get(&num).cloned()
     }
 
+    /// Returns the timestamp of the next block.
+    pub(crate) fn next_block_timestamp(&mut self) -> u64 {
+        self.next_timestamp.take().unwrap_or_else(|| {
+            let now = SystemTime::now().duration_since(UNIX_EPOCH).unwrap_or_default().as_secs();
+            now.saturating_add_signed(self.time_offset)
+        })
+    }
+
+    /// Returns the header of the best block.
+    pub(crate) fn best_header(&self) -> SealedHeader {
+        let header = self.headers.get(&self.best_block).cloned().unwrap_or_default();
+        header.seal(self.best_hash)
+    }
+
+    /// Returns the header of the block that is finalized, which is the block of the oldest
+    /// snapshot, so that the chain can still be reverted to it.
+    pub(crate) fn finalized_header(&self) -> SealedHeader {
+        self.snapshots
+            .values()
+            .next()
+            .map(|snapshot| snapshot.header.clone())
+            .unwrap_or_else(|| self.best_header())
+    }
+
+    /// Takes a snapshot of the best block and returns its ID.
+    pub(crate) fn snapshot(&mut self) -> U256 {
+        let id = self.next_snapshot_id;
+        self.next_snapshot_id += U256::from(1);
+        self.snapshots
+            .insert(id, Snapshot { header: self.best_header(), time_offset: self.time_offset });
+        id
+    }
+
+    /// Removes all blocks after the block of the given snapshot, along with the snapshot and all
+    /// snapshots taken after it.
+    ///
+    /// Returns the hashes of the removed blocks, or `None` if the snapshot does not exist.
+    pub(crate) fn revert(&mut self, id: U256) -> Option<Vec<BlockHash>> {
+        let snapshot = self.snapshots.get(&id)?.clone();
+        self.snapshots.split_off(&id);
+        let number = snapshot.header.number;
+
+        let mut removed = Vec::new();
+        self.hash_to_number.retain(|hash, num| {
+            let keep = *num <= number;
+            if !keep {
+                removed.push(*hash);
+            }
+            keep
+        });
+        for hash in &removed {
+            self.bodies.remove(hash);
+        }
+        self.headers.retain(|num, header| {
+            let keep = *num <= number;
+            if !keep {
+                self.total_difficulty -= header.difficulty;
+            }
+            keep
+        });
+
+        self.best_block = number;
+        self.best_hash = snapshot.header.hash();
+        self.time_offset = snapshot.time_offset;
+        self.next_timestamp = None;
+
+        trace!(target: "consensus::auto", num=self.best_block, hash=?self.best_hash, "reverted to snapshot");
+        Some(removed)
+    }
+
     /// Inserts a new header+body pair
     pub(crate) fn insert_new_block(&mut self, mut header: Header, body: BlockBody) {
         header.number = self.best_block + 1;
@@ -332,23 +440,26 @@ impl StorageInner {
         header
     }
 
-    /// Builds and executes a new block with the given transactions, on the provided executor.
+    /// Builds and executes a new block with the given transactions and overrides, on the provided
+    /// executor.
     ///
     /// This returns the header of the executed block, as well as the poststate from execution.
     #[allow(clippy::too_many_arguments)]
     pub(crate) fn build_and_execute<Provider, Executor>(
         &mut self,
         transactions: Vec<TransactionSigned>,
+        senders: Vec<Address>,
         ommers: Vec<Header>,
         provider: &Provider,
         chain_spec: Arc<ChainSpec>,
         executor: &Executor,
+        overrides: &BlockOverrides,
     ) -> Result<(SealedHeader, ExecutionOutcome), BlockExecutionError>
     where
         Executor: BlockExecutorProvider,
         Provider: StateProviderFactory,
     {
-        let timestamp = SystemTime::now().duration_since(UNIX_EPOCH).unwrap_or_default().as_secs();
+        let timestamp = self.next_block_timestamp();
 
         // if shanghai is active, include empty withdrawals
         let withdrawals =
@@ -373,8 +484,7 @@ impl StorageInner {
             withdrawals: withdrawals.clone(),
             requests: requests.clone(),
         }
-        .with_recovered_senders()
-        .ok_or(BlockExecutionError::Validation(BlockValidationError::SenderRecoveryError))?;
+        .with_senders_unchecked(senders);
 
         trace!(target: "consensus::auto", transactions=?&block.body, "executing transactions");
 
@@ -389,7 +499,7 @@ impl StorageInner {
             requests: block_execution_requests,
             gas_used,
             ..
-        } = executor.executor(&mut db).execute((&block, U256::ZERO).into())?;
+        } = execute_with_overrides(executor, &mut db, &block, U256::ZERO, overrides)?;
         let execution_outcome = ExecutionOutcome::new(
             state,
             receipts.into(),
@@ -441,3 +551,33 @@ impl StorageInner {
         Ok((new_header, execution_outcome))
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn revert_to_snapshot() {
+        let mut storage = StorageInner::default();
+        storage.insert_new_block(Header::default(), BlockBody::default());
+        let snapshot_hash = storage.best_hash;
+        let id = storage.snapshot();
+
+        storage.time_offset = 10;
+        storage.insert_new_block(Header::default(), BlockBody::default());
+        let reverted_hash = storage.best_hash;
+        let later = storage.snapshot();
+        assert_eq!(storage.finalized_header().hash(), snapshot_hash);
+
+        assert_eq!(storage.revert(id), Some(vec![reverted_hash]));
+        assert_eq!(storage.best_block, 1);
+        assert_eq!(storage.best_hash, snapshot_hash);
+        assert_eq!(storage.time_offset, 0);
+        assert!(!storage.headers.contains_key(&2));
+        assert!(!storage.bodies.contains_key(&reverted_hash));
+
+        // snapshots taken after the reverted one are gone
+        assert_eq!(storage.revert(later), None);
+        assert_eq!(storage.finalized_header().hash(), snapshot_hash);
+    }
+}
@@ -0,0 +1,342 @@
+//! State overrides sealed into blocks by the auto seal miner.
+//!
+//! Overrides are applied on top of the parent state before the transactions of a block are
+//! executed. They become part of the block's state changes, so its state root, changesets and
+//! unwinds account for them like for any other state change. Since they are not part of the block
+//! itself, the overrides of every sealed block are recorded in [`AutoSealOverrides`], which the
+//! [`AutoSealExecutorProvider`](crate::AutoSealExecutorProvider) consults when the block is
+//! executed again.
+
+use parking_lot::RwLock;
+use reth_evm::execute::{
+    BlockExecutionError, BlockExecutionOutput, BlockExecutorProvider, Executor, ProviderError,
+};
+use reth_primitives::{Address, BlockHash, BlockWithSenders, Bytes, Receipt, TxHash, B256, U256};
+use reth_revm::{
+    db::{
+        states::{reverts::AccountInfoRevert, StorageSlot},
+        AccountRevert, AccountStatus, BundleAccount, RevertToSlot,
+    },
+    primitives::{AccountInfo, Bytecode},
+    Database,
+};
+use std::{collections::HashMap, fmt::Display, sync::Arc};
+
+/// Changes to a single account.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct AccountOverride {
+    /// The new balance of the account.
+    pub balance: Option<U256>,
+    /// The new nonce of the account.
+    pub nonce: Option<u64>,
+    /// The new code of the account.
+    pub code: Option<Bytes>,
+    /// The storage slots to set, all other slots are kept.
+    pub storage: HashMap<U256, U256>,
+}
+
+/// Account changes keyed by the account address.
+pub type StateOverrides = HashMap<Address, AccountOverride>;
+
+/// Everything the miner applied on top of the transactions of a single block.
+#[derive(Debug, Clone, Default)]
+pub(crate) struct BlockOverrides {
+    /// The state changes applied before the transactions are executed.
+    pub(crate) state: StateOverrides,
+    /// The senders of impersonated transactions, which don't match the transaction signatures.
+    pub(crate) senders: HashMap<TxHash, Address>,
+}
+
+impl BlockOverrides {
+    /// Returns `true` if the block is executed as is.
+    pub(crate) fn is_empty(&self) -> bool {
+        self.state.is_empty() && self.senders.is_empty()
+    }
+}
+
+/// The overrides of all blocks sealed by the auto seal miner, keyed by block hash.
+///
+/// This is shared between the [`MiningTask`](crate::MiningTask) and every
+/// [`AutoSealExecutorProvider`](crate::AutoSealExecutorProvider) that executes sealed blocks.
+/// Overrides are kept in memory only: blocks that were executed before a restart are never
+/// executed again, unless they are synced from scratch.
+#[derive(Debug, Clone, Default)]
+pub struct AutoSealOverrides {
+    blocks: Arc<RwLock<HashMap<BlockHash, BlockOverrides>>>,
+}
+
+impl AutoSealOverrides {
+    /// Returns `true` if no block was sealed with overrides.
+    pub fn is_empty(&self) -> bool {
+        self.blocks.read().is_empty()
+    }
+
+    /// Returns the overrides of the given block.
+    pub(crate) fn get(&self, hash: &BlockHash) -> Option<BlockOverrides> {
+        self.blocks.read().get(hash).cloned()
+    }
+
+    /// Records the overrides of a sealed block.
+    pub(crate) fn insert(&self, hash: BlockHash, overrides: BlockOverrides) {
+        self.blocks.write().insert(hash, overrides);
+    }
+
+    /// Removes the overrides of the given blocks.
+    pub(crate) fn remove(&self, hashes: impl IntoIterator<Item = BlockHash>) {
+        let mut blocks = self.blocks.write();
+        for hash in hashes {
+            blocks.remove(&hash);
+        }
+    }
+}
+
+/// Executes the block on top of `db` with the given overrides applied.
+///
+/// The overrides are included in the returned state changes and reverts, as if the block's
+/// transactions had made them.
+pub(crate) fn execute_with_overrides<E, DB>(
+    executor: &E,
+    mut db: DB,
+    block: &BlockWithSenders,
+    total_difficulty: U256,
+    overrides: &BlockOverrides,
+) -> Result<BlockExecutionOutput<Receipt>, BlockExecutionError>
+where
+    E: BlockExecutorProvider,
+    DB: Database<Error: Into<ProviderError> + Display>,
+{
+    // impersonated transactions are executed with the sender they were submitted with
+    let with_senders;
+    let block = if overrides.senders.is_empty() {
+        block
+    } else {
+        let mut block = block.clone();
+        for (transaction, sender) in block.block.body.iter().zip(block.senders.iter_mut()) {
+            if let Some(impersonated) = overrides.senders.get(&transaction.hash()) {
+                *sender = *impersonated;
+            }
+        }
+        with_senders = block;
+        &with_senders
+    };
+
+    if overrides.state.is_empty() {
+        return executor.executor(db).execute((block, total_difficulty).into())
+    }
+
+    let mut accounts = HashMap::with_capacity(overrides.state.len());
+    for (address, account) in &overrides.state {
+        let original_info = db.basic(*address).map_err(Into::into)?;
+
+        let mut info = original_info.clone().unwrap_or_default();
+        if let Some(balance) = account.balance {
+            info.balance = balance;
+        }
+        if let Some(nonce) = account.nonce {
+            info.nonce = nonce;
+        }
+        if let Some(code) = &account.code {
+            let bytecode = Bytecode::new_raw(code.clone());
+            info.code_hash = bytecode.hash_slow();
+            info.code = Some(bytecode);
+        }
+
+        let mut storage = HashMap::with_capacity(account.storage.len());
+        for (slot, value) in &account.storage {
+            let original = db.storage(*address, *slot).map_err(Into::into)?;
+            storage.insert(*slot, StorageSlot::new_changed(original, *value));
+        }
+
+        let status = if original_info.is_some() {
+            AccountStatus::Changed
+        } else {
+            AccountStatus::InMemoryChange
+        };
+        accounts
+            .insert(*address, BundleAccount { info: Some(info), original_info, storage, status });
+    }
+
+    let mut output = executor
+        .executor(OverrideDatabase { db, accounts: &accounts })
+        .execute((block, total_difficulty).into())?;
+
+    // The executed state treats the overridden values as the original ones, restore the actual
+    // original values so the overrides are written and reverted with the block.
+    let state = &mut output.state;
+    if state.reverts.is_empty() {
+        state.reverts.push(Vec::new());
+    }
+    for (address, account) in accounts {
+        let reverts = state.reverts.last_mut().expect("not empty");
+        let revert = match reverts.iter().position(|(reverted, _)| *reverted == address) {
+            Some(idx) => &mut reverts[idx].1,
+            None => {
+                let previous_status = if account.original_info.is_some() {
+                    AccountStatus::Loaded
+                } else {
+                    AccountStatus::LoadedNotExisting
+                };
+                reverts.push((
+                    address,
+                    AccountRevert {
+                        account: AccountInfoRevert::DoNothing,
+                        storage: HashMap::new(),
+                        previous_status,
+                        wipe_storage: false,
+                    },
+                ));
+                &mut reverts.last_mut().expect("not empty").1
+            }
+        };
+        revert.account = match &account.original_info {
+            Some(info) => AccountInfoRevert::RevertTo(info.clone()),
+            None => AccountInfoRevert::DeleteIt,
+        };
+        for (slot, value) in &account.storage {
+            revert.storage.insert(*slot, RevertToSlot::Some(value.previous_or_original_value));
+        }
+
+        if let Some(code) = account.info.as_ref().and_then(|info| info.code.clone()) {
+            state.contracts.insert(code.hash_slow(), code);
+        }
+
+        match state.state.get_mut(&address) {
+            Some(executed) => {
+                executed.original_info = account.original_info;
+                for (slot, value) in account.storage {
+                    executed
+                        .storage
+                        .entry(slot)
+                        .and_modify(|executed| {
+                            executed.previous_or_original_value = value.previous_or_original_value
+                        })
+                        .or_insert(value);
+                }
+            }
+            None => {
+                state.state.insert(address, account);
+            }
+        }
+    }
+    state.state_size = state.state.values().map(BundleAccount::size_hint).sum();
+    state.reverts_size = state.reverts.iter().flatten().map(|(_, revert)| revert.size_hint()).sum();
+
+    Ok(output)
+}
+
+/// A [`Database`] that serves the overridden accounts from memory.
+struct OverrideDatabase<'a, DB> {
+    db: DB,
+    accounts: &'a HashMap<Address, BundleAccount>,
+}
+
+impl<DB: Database> Database for OverrideDatabase<'_, DB> {
+    type Error = DB::Error;
+
+    fn basic(&mut self, address: Address) -> Result<Option<AccountInfo>, Self::Error> {
+        match self.accounts.get(&address) {
+            Some(account) => Ok(account.info.clone()),
+            None => self.db.basic(address),
+        }
+    }
+
+    fn code_by_hash(&mut self, code_hash: B256) -> Result<Bytecode, Self::Error> {
+        let overridden = self.accounts.values().find_map(|account| {
+            account.info.as_ref().filter(|info| info.code_hash == code_hash)?.code.clone()
+        });
+        match overridden {
+            Some(code) => Ok(code),
+            None => self.db.code_by_hash(code_hash),
+        }
+    }
+
+    fn storage(&mut self, address: Address, index: U256) -> Result<U256, Self::Error> {
+        match self.accounts.get(&address).and_then(|account| account.storage.get(&index)) {
+            Some(slot) => Ok(slot.present_value),
+            None => self.db.storage(address, index),
+        }
+    }
+
+    fn block_hash(&mut self, number: u64) -> Result<B256, Self::Error> {
+        self.db.block_hash(number)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use reth_chainspec::{ChainSpecBuilder, MAINNET};
+    use reth_evm_ethereum::execute::EthExecutorProvider;
+    use reth_primitives::{keccak256, Account, Block, Header};
+    use reth_revm::{database::StateProviderDatabase, test_utils::StateProviderTest};
+
+    #[test]
+    fn overrides_are_part_of_the_state_changes() {
+        let existing = Address::with_last_byte(1);
+        let created = Address::with_last_byte(2);
+        let code = Bytes::from_static(&[0x60, 0x00]);
+
+        let mut db = StateProviderTest::default();
+        db.insert_account(
+            existing,
+            Account { balance: U256::from(1), ..Default::default() },
+            None,
+            HashMap::from([(B256::with_last_byte(1), U256::from(2))]),
+        );
+
+        let overrides = BlockOverrides {
+            state: HashMap::from([
+                (
+                    existing,
+                    AccountOverride {
+                        balance: Some(U256::from(10)),
+                        storage: HashMap::from([(U256::from(1), U256::from(3))]),
+                        ..Default::default()
+                    },
+                ),
+                (created, AccountOverride { code: Some(code.clone()), ..Default::default() }),
+            ]),
+            senders: HashMap::new(),
+        };
+
+        let chain_spec = Arc::new(ChainSpecBuilder::from(&*MAINNET).shanghai_activated().build());
+        let executor = EthExecutorProvider::ethereum(chain_spec);
+        let block =
+            Block { header: Header { number: 1, ..Default::default() }, ..Default::default() }
+                .with_senders_unchecked(Vec::new());
+
+        let output = execute_with_overrides(
+            &executor,
+            StateProviderDatabase::new(&db),
+            &block,
+            U256::ZERO,
+            &overrides,
+        )
+        .unwrap();
+        let state = output.state;
+
+        let account = &state.state[&existing];
+        assert_eq!(account.original_info.as_ref().unwrap().balance, U256::from(1));
+        assert_eq!(account.info.as_ref().unwrap().balance, U256::from(10));
+        assert_eq!(
+            account.storage[&U256::from(1)],
+            StorageSlot::new_changed(U256::from(2), U256::from(3))
+        );
+
+        let account = &state.state[&created];
+        assert_eq!(account.original_info, None);
+        assert_eq!(account.info.as_ref().unwrap().code_hash, keccak256(&code));
+        assert!(state.contracts.contains_key(&keccak256(&code)));
+
+        let reverts: HashMap<_, _> = state.reverts[0].iter().cloned().collect();
+        assert_eq!(
+            reverts[&existing].account,
+            AccountInfoRevert::RevertTo(AccountInfo {
+                balance: U256::from(1),
+                ..Default::default()
+            })
+        );
+        assert_eq!(reverts[&existing].storage[&U256::from(1)], RevertToSlot::Some(U256::from(2)));
+        assert_eq!(reverts[&created].account, AccountInfoRevert::DeleteIt);
+    }
+}
@@ -1,23 +1,29 @@
-use crate::{mode::MiningMode, Storage};
+use crate::{
+    handle::MiningCommand, mode::MiningMode, AutoSealError, AutoSealHandle, AutoSealOverrides,
+    BlockOverrides, StateOverrides, Storage,
+};
 use futures_util::{future::BoxFuture, FutureExt};
 use reth_beacon_consensus::{BeaconEngineMessage, ForkchoiceStatus};
 use reth_chainspec::ChainSpec;
 use reth_engine_primitives::EngineTypes;
 use reth_evm::execute::BlockExecutorProvider;
-use reth_primitives::IntoRecoveredTransaction;
+use reth_primitives::{IntoRecoveredTransaction, SealedHeader, U256};
 use reth_provider::{CanonChainTracker, StateProviderFactory};
 use reth_rpc_types::engine::ForkchoiceState;
 use reth_stages_api::PipelineEvent;
 use reth_tokio_util::EventStream;
-use reth_transaction_pool::{TransactionPool, ValidPoolTransaction};
+use reth_transaction_pool::{PoolTransaction, TransactionPool, ValidPoolTransaction};
 use std::{
-    collections::VecDeque,
+    collections::{HashMap, VecDeque},
     future::Future,
     pin::Pin,
     sync::Arc,
     task::{Context, Poll},
 };
-use tokio::sync::{mpsc::UnboundedSender, oneshot};
+use tokio::sync::{
+    mpsc::{unbounded_channel, UnboundedReceiver, UnboundedSender},
+    oneshot,
+};
 use tracing::{debug, error, warn};
 
 /// A Future that listens for new ready transactions and puts new blocks into storage
@@ -34,14 +40,20 @@ pub struct MiningTask<Client, Pool: TransactionPool, Executor, Engine: EngineTyp
     storage: Storage,
     /// Pool where transactions are stored
     pool: Pool,
-    /// backlog of sets of transactions ready to be mined
-    queued: VecDeque<Vec<Arc<ValidPoolTransaction<<Pool as TransactionPool>::Transaction>>>>,
+    /// backlog of sets of transactions ready to be mined, and of commands from handles
+    queued: VecDeque<MiningWork<<Pool as TransactionPool>::Transaction>>,
     // TODO: ideally this would just be a sender of hashes
     to_engine: UnboundedSender<BeaconEngineMessage<Engine>>,
     /// The pipeline events to listen on
     pipe_line_events: Option<EventStream<PipelineEvent>>,
     /// The type used for block execution
     block_executor: Executor,
+    /// The overrides of sealed blocks
+    overrides: AutoSealOverrides,
+    /// Receives commands from [`AutoSealHandle`]s
+    from_handles: UnboundedReceiver<MiningCommand>,
+    /// Sends commands to this task, handed out to new [`AutoSealHandle`]s
+    to_miner: UnboundedSender<MiningCommand>,
 }
 
 // === impl MiningTask ===
@@ -59,7 +71,9 @@ impl<Executor, Client, Pool: TransactionPool, Engine: EngineTypes>
         client: Client,
         pool: Pool,
         block_executor: Executor,
+        overrides: AutoSealOverrides,
     ) -> Self {
+        let (to_miner, from_handles) = unbounded_channel();
         Self {
             chain_spec,
             client,
@@ -71,6 +85,9 @@ impl<Executor, Client, Pool: TransactionPool, Engine: EngineTypes>
             queued: Default::default(),
             pipe_line_events: None,
             block_executor,
+            overrides,
+            from_handles,
+            to_miner,
         }
     }
 
@@ -78,6 +95,11 @@ impl<Executor, Client, Pool: TransactionPool, Engine: EngineTypes>
     pub fn set_pipeline_events(&mut self, events: EventStream<PipelineEvent>) {
         self.pipe_line_events = Some(events);
     }
+
+    /// Returns a new [`AutoSealHandle`] to control this task.
+    pub fn handle(&self) -> AutoSealHandle {
+        AutoSealHandle::new(self.storage.clone(), self.to_miner.clone())
+    }
 }
 
 impl<Executor, Client, Pool, Engine> Future for MiningTask<Client, Pool, Executor, Engine>
@@ -96,7 +118,11 @@ where
         loop {
             if let Poll::Ready(transactions) = this.miner.poll(&this.pool, cx) {
                 // miner returned a set of transaction that we feed to the producer
-                this.queued.push_back(transactions);
+                this.queued.push_back(MiningWork::Transactions(transactions));
+            }
+
+            while let Poll::Ready(Some(command)) = this.from_handles.poll_recv(cx) {
+                this.queued.push_back(MiningWork::Command(command));
             }
 
             if this.insert_task.is_none() {
@@ -106,95 +132,22 @@ where
                 }
 
                 // ready to queue in new insert task
-                let storage = this.storage.clone();
-                let transactions = this.queued.pop_front().expect("not empty");
-
-                let to_engine = this.to_engine.clone();
-                let client = this.client.clone();
-                let chain_spec = Arc::clone(&this.chain_spec);
-                let pool = this.pool.clone();
+                let work = this.queued.pop_front().expect("not empty");
+                let sealer = BlockSealer {
+                    chain_spec: Arc::clone(&this.chain_spec),
+                    client: this.client.clone(),
+                    storage: this.storage.clone(),
+                    pool: this.pool.clone(),
+                    to_engine: this.to_engine.clone(),
+                    executor: this.block_executor.clone(),
+                    overrides: this.overrides.clone(),
+                };
                 let events = this.pipe_line_events.take();
-                let executor = this.block_executor.clone();
 
                 // Create the mining future that creates a block, notifies the engine that drives
                 // the pipeline
                 this.insert_task = Some(Box::pin(async move {
-                    let mut storage = storage.write().await;
-
-                    let transactions: Vec<_> = transactions
-                        .into_iter()
-                        .map(|tx| {
-                            let recovered = tx.to_recovered_transaction();
-                            recovered.into_signed()
-                        })
-                        .collect();
-                    let ommers = vec![];
-
-                    match storage.build_and_execute(
-                        transactions.clone(),
-                        ommers.clone(),
-                        &client,
-                        chain_spec,
-                        &executor,
-                    ) {
-                        Ok((new_header, _bundle_state)) => {
-                            // clear all transactions from pool
-                            pool.remove_transactions(
-                                transactions.iter().map(|tx| tx.hash()).collect(),
-                            );
-
-                            let state = ForkchoiceState {
-                                head_block_hash: new_header.hash(),
-                                finalized_block_hash: new_header.hash(),
-                                safe_block_hash: new_header.hash(),
-                            };
-                            drop(storage);
-
-                            // TODO: make this a future
-                            // await the fcu call rx for SYNCING, then wait for a VALID response
-                            loop {
-                                // send the new update to the engine, this will trigger the engine
-                                // to download and execute the block we just inserted
-                                let (tx, rx) = oneshot::channel();
-                                let _ = to_engine.send(BeaconEngineMessage::ForkchoiceUpdated {
-                                    state,
-                                    payload_attrs: None,
-                                    tx,
-                                });
-                                debug!(target: "consensus::auto", ?state, "Sent fork choice update");
-
-                                match rx.await.unwrap() {
-                                    Ok(fcu_response) => {
-                                        match fcu_response.forkchoice_status() {
-                                            ForkchoiceStatus::Valid => break,
-                                            ForkchoiceStatus::Invalid => {
-                                                error!(target: "consensus::auto", ?fcu_response, "Forkchoice update returned invalid response");
-                                                return None
-                                            }
-                                            ForkchoiceStatus::Syncing => {
-                                                debug!(target: "consensus::auto", ?fcu_response, "Forkchoice update returned SYNCING, waiting for VALID");
-                                                // wait for the next fork choice update
-                                                continue
-                                            }
-                                        }
-                                    }
-                                    Err(err) => {
-                                        error!(target: "consensus::auto", %err, "Autoseal fork choice update failed");
-                                        return None
-                                    }
-                                }
-                            }
-
-                            // update canon chain for rpc
-                            client.set_canonical_head(new_header.clone());
-                            client.set_safe(new_header.clone());
-                            client.set_finalized(new_header.clone());
-                        }
-                        Err(err) => {
-                            warn!(target: "consensus::auto", %err, "failed to execute block")
-                        }
-                    }
-
+                    sealer.run(work).await;
                     events
                 }));
             }
@@ -216,6 +169,168 @@ where
     }
 }
 
+/// Work items of the [`MiningTask`].
+enum MiningWork<T: PoolTransaction> {
+    /// Transactions the [`MiningMode`] selected for the next block.
+    Transactions(Vec<Arc<ValidPoolTransaction<T>>>),
+    /// A command sent through an [`AutoSealHandle`].
+    Command(MiningCommand),
+}
+
+/// Seals blocks and waits until the engine made them canonical.
+struct BlockSealer<Client, Pool, Executor, Engine: EngineTypes> {
+    chain_spec: Arc<ChainSpec>,
+    client: Client,
+    storage: Storage,
+    pool: Pool,
+    to_engine: UnboundedSender<BeaconEngineMessage<Engine>>,
+    executor: Executor,
+    overrides: AutoSealOverrides,
+}
+
+impl<Client, Pool, Executor, Engine> BlockSealer<Client, Pool, Executor, Engine>
+where
+    Client: StateProviderFactory + CanonChainTracker,
+    Pool: TransactionPool,
+    Engine: EngineTypes,
+    Executor: BlockExecutorProvider,
+{
+    /// Processes a single work item.
+    async fn run(self, work: MiningWork<Pool::Transaction>) {
+        match work {
+            MiningWork::Transactions(transactions) => {
+                let _ = self.seal(transactions, StateOverrides::default()).await;
+            }
+            MiningWork::Command(MiningCommand::Mine { blocks, tx }) => {
+                let _ = tx.send(self.mine(blocks).await);
+            }
+            MiningWork::Command(MiningCommand::SetState { overrides, tx }) => {
+                let _ = tx.send(self.seal(Vec::new(), overrides).await.map(drop));
+            }
+            MiningWork::Command(MiningCommand::Revert { id, tx }) => {
+                let _ = tx.send(self.revert(id).await);
+            }
+        }
+    }
+
+    /// Seals the given number of blocks with the best transactions of the pool.
+    async fn mine(&self, blocks: u64) -> Result<(), AutoSealError> {
+        for _ in 0..blocks {
+            let transactions = self.pool.best_transactions().collect();
+            self.seal(transactions, StateOverrides::default()).await?;
+        }
+        Ok(())
+    }
+
+    /// Reverts the chain to the given snapshot by sealing an empty block on top of it.
+    async fn revert(&self, id: U256) -> Result<bool, AutoSealError> {
+        let Some(removed) = self.storage.write().await.revert(id) else { return Ok(false) };
+        self.overrides.remove(removed);
+        self.seal(Vec::new(), StateOverrides::default()).await?;
+        Ok(true)
+    }
+
+    /// Seals a new block with the given transactions and state overrides, and waits until the
+    /// engine made it canonical.
+    async fn seal(
+        &self,
+        transactions: Vec<Arc<ValidPoolTransaction<Pool::Transaction>>>,
+        state: StateOverrides,
+    ) -> Result<SealedHeader, AutoSealError> {
+        let mut storage = self.storage.write().await;
+
+        let (transactions, senders): (Vec<_>, Vec<_>) = transactions
+            .into_iter()
+            .map(|tx| {
+                let recovered = tx.to_recovered_transaction();
+                let sender = recovered.signer();
+                (recovered.into_signed(), sender)
+            })
+            .unzip();
+        let ommers = vec![];
+
+        // transactions of impersonated accounts are not signed by their sender
+        let mut overrides = BlockOverrides { state, senders: HashMap::new() };
+        for (tx, sender) in transactions.iter().zip(&senders) {
+            if tx.recover_signer().as_ref() != Some(sender) {
+                overrides.senders.insert(tx.hash(), *sender);
+            }
+        }
+
+        let new_header = match storage.build_and_execute(
+            transactions.clone(),
+            senders,
+            ommers,
+            &self.client,
+            Arc::clone(&self.chain_spec),
+            &self.executor,
+            &overrides,
+        ) {
+            Ok((new_header, _bundle_state)) => new_header,
+            Err(err) => {
+                warn!(target: "consensus::auto", %err, "failed to execute block");
+                return Err(err.into())
+            }
+        };
+
+        if !overrides.is_empty() {
+            self.overrides.insert(new_header.hash(), overrides);
+        }
+
+        // clear all transactions from pool
+        self.pool.remove_transactions(transactions.iter().map(|tx| tx.hash()).collect());
+
+        let finalized = storage.finalized_header();
+        let state = ForkchoiceState {
+            head_block_hash: new_header.hash(),
+            finalized_block_hash: finalized.hash(),
+            safe_block_hash: finalized.hash(),
+        };
+        drop(storage);
+
+        // TODO: make this a future
+        // await the fcu call rx for SYNCING, then wait for a VALID response
+        loop {
+            // send the new update to the engine, this will trigger the engine
+            // to download and execute the block we just inserted
+            let (tx, rx) = oneshot::channel();
+            let _ = self.to_engine.send(BeaconEngineMessage::ForkchoiceUpdated {
+                state,
+                payload_attrs: None,
+                tx,
+            });
+            debug!(target: "consensus::auto", ?state, "Sent fork choice update");
+
+            match rx.await.unwrap() {
+                Ok(fcu_response) => {
+                    match fcu_response.forkchoice_status() {
+                        ForkchoiceStatus::Valid => break,
+                        ForkchoiceStatus::Invalid => {
+                            error!(target: "consensus::auto", ?fcu_response, "Forkchoice update returned invalid response");
+                            return Err(AutoSealError::Rejected(new_header.hash()))
+                        }
+                        ForkchoiceStatus::Syncing => {
+                            // wait for the next fork choice update
+                            debug!(target: "consensus::auto", ?fcu_response, "Forkchoice update returned SYNCING, waiting for VALID");
+                        }
+                    }
+                }
+                Err(err) => {
+                    error!(target: "consensus::auto", %err, "Autoseal fork choice update failed");
+                    return Err(AutoSealError::Rejected(new_header.hash()))
+                }
+            }
+        }
+
+        // update canon chain for rpc
+        self.client.set_canonical_head(new_header.clone());
+        self.client.set_safe(finalized.clone());
+        self.client.set_finalized(finalized);
+
+        Ok(new_header)
+    }
+}
+
 impl<Client, Pool: TransactionPool, EvmConfig: std::fmt::Debug, Engine: EngineTypes> std::fmt::Debug
     for MiningTask<Client, Pool, EvmConfig, Engine>
 {
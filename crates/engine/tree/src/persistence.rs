@@ -2,14 +2,17 @@
 
 use reth_chain_state::ExecutedBlock;
 use reth_db::Database;
+use reth_db_api::models::StoredBadBlock;
 use reth_errors::ProviderError;
 use reth_primitives::B256;
-use reth_provider::{writer::UnifiedStorageWriter, ProviderFactory, StaticFileProviderFactory};
+use reth_provider::{
+    writer::UnifiedStorageWriter, BadBlockWriter, ProviderFactory, StaticFileProviderFactory,
+};
 use reth_prune::{Pruner, PrunerError, PrunerOutput};
 use std::sync::mpsc::{Receiver, SendError, Sender};
 use thiserror::Error;
 use tokio::sync::oneshot;
-use tracing::{debug, error};
+use tracing::{debug, error, warn};
 
 /// Writes parts of reth's in memory tree state to the database and static files.
 ///
@@ -45,6 +48,14 @@ impl<DB: Database> PersistenceService<DB> {
         // TODO: doing this properly depends on pruner segment changes
         self.pruner.run(block_num)
     }
+
+    /// Writes the bad block to the bad block store.
+    fn save_bad_block(&self, bad_block: StoredBadBlock) -> Result<(), ProviderError> {
+        let provider_rw = self.provider.provider_rw()?;
+        provider_rw.insert_bad_block(bad_block)?;
+        provider_rw.commit()?;
+        Ok(())
+    }
 }
 
 impl<DB> PersistenceService<DB>
@@ -92,6 +103,17 @@ where
                     // we ignore the error because the caller may or may not care about the result
                     let _ = sender.send(res);
                 }
+                PersistenceAction::SaveBadBlock(bad_block, sender) => {
+                    // bad blocks are only kept for debugging, failing to save one must not stop
+                    // the service
+                    let hash = bad_block.block.hash();
+                    if let Err(error) = self.save_bad_block(*bad_block) {
+                        warn!(target: "engine::persistence", %error, ?hash, "Failed to save bad block");
+                    }
+
+                    // we ignore the error because the caller may or may not care about the result
+                    let _ = sender.send(());
+                }
             }
         }
         Ok(())
@@ -129,6 +151,9 @@ pub enum PersistenceAction {
     /// Prune associated block data before the given block number, according to already-configured
     /// prune modes.
     PruneBefore(u64, oneshot::Sender<PrunerOutput>),
+
+    /// Saves a block that failed validation to the bad block store.
+    SaveBadBlock(Box<StoredBadBlock>, oneshot::Sender<()>),
 }
 
 /// A handle to the persistence service
@@ -217,6 +242,17 @@ impl PersistenceHandle {
     ) -> Result<(), SendError<PersistenceAction>> {
         self.send_action(PersistenceAction::PruneBefore(block_num, tx))
     }
+
+    /// Tells the persistence service to save a block that failed validation.
+    ///
+    /// When the operation completes, `()` is returned in the receiver end of the sender argument.
+    pub fn save_bad_block(
+        &self,
+        bad_block: StoredBadBlock,
+        tx: oneshot::Sender<()>,
+    ) -> Result<(), SendError<PersistenceAction>> {
+        self.send_action(PersistenceAction::SaveBadBlock(Box::new(bad_block), tx))
+    }
 }
 
 #[cfg(test)]
@@ -225,7 +261,9 @@ mod tests {
     use reth_chain_state::test_utils::TestBlockBuilder;
     use reth_exex_types::FinishedExExHeight;
     use reth_primitives::B256;
-    use reth_provider::{test_utils::create_test_provider_factory, ProviderFactory};
+    use reth_provider::{
+        test_utils::create_test_provider_factory, BadBlockReader, ProviderFactory,
+    };
     use reth_prune::Pruner;
 
     fn default_persistence_handle() -> PersistenceHandle {
@@ -318,4 +356,32 @@ mod tests {
             assert_eq!(last_hash, actual_hash);
         }
     }
+
+    #[tokio::test]
+    async fn test_save_bad_block() {
+        reth_tracing::init_test_tracing();
+        let provider = create_test_provider_factory();
+        let (_finished_exex_height_tx, finished_exex_height_rx) =
+            tokio::sync::watch::channel(FinishedExExHeight::NoExExs);
+        let pruner = Pruner::<_, ProviderFactory<_>>::new(
+            provider.clone(),
+            vec![],
+            5,
+            0,
+            None,
+            finished_exex_height_rx,
+        );
+        let persistence_handle = PersistenceHandle::spawn_service(provider.clone(), pruner);
+
+        let block = TestBlockBuilder::default().generate_random_block(1, B256::random()).block;
+        let bad_block =
+            StoredBadBlock::new(block, "invalid state root".to_string(), B256::random());
+        let (tx, rx) = oneshot::channel();
+
+        persistence_handle.save_bad_block(bad_block.clone(), tx).unwrap();
+        rx.await.unwrap();
+
+        let bad_blocks = provider.provider().unwrap().bad_blocks().unwrap();
+        assert_eq!(bad_blocks, vec![bad_block]);
+    }
 }
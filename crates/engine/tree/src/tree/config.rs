@@ -23,6 +23,9 @@ pub struct TreeConfig {
     max_invalid_header_cache_length: u32,
     /// Maximum number of blocks to execute sequentially in a batch.
     max_execute_block_batch_size: usize,
+    /// Whether to compute the state root and the trie updates with the state root task, which
+    /// receives the state changes during block execution.
+    use_state_root_task: bool,
 }
 
 impl Default for TreeConfig {
@@ -33,6 +36,7 @@ impl Default for TreeConfig {
             block_buffer_limit: DEFAULT_BLOCK_BUFFER_LIMIT,
             max_invalid_header_cache_length: DEFAULT_MAX_INVALID_HEADER_CACHE_LENGTH,
             max_execute_block_batch_size: DEFAULT_MAX_EXECUTE_BLOCK_BATCH_SIZE,
+            use_state_root_task: false,
         }
     }
 }
@@ -45,6 +49,7 @@ impl TreeConfig {
         block_buffer_limit: u32,
         max_invalid_header_cache_length: u32,
         max_execute_block_batch_size: usize,
+        use_state_root_task: bool,
     ) -> Self {
         Self {
             persistence_threshold,
@@ -52,6 +57,7 @@ impl TreeConfig {
             block_buffer_limit,
             max_invalid_header_cache_length,
             max_execute_block_batch_size,
+            use_state_root_task,
         }
     }
 
@@ -80,6 +86,11 @@ impl TreeConfig {
         self.max_execute_block_batch_size
     }
 
+    /// Returns whether the state root task is used.
+    pub const fn use_state_root_task(&self) -> bool {
+        self.use_state_root_task
+    }
+
     /// Setter for persistence threshold.
     pub const fn with_persistence_threshold(mut self, persistence_threshold: u64) -> Self {
         self.persistence_threshold = persistence_threshold;
@@ -118,4 +129,10 @@ impl TreeConfig {
         self.max_execute_block_batch_size = max_execute_block_batch_size;
         self
     }
+
+    /// Setter for whether to use the state root task.
+    pub const fn with_state_root_task(mut self, use_state_root_task: bool) -> Self {
+        self.use_state_root_task = use_state_root_task;
+        self
+    }
 }
@@ -1,5 +1,5 @@
 use reth_metrics::{
-    metrics::{Counter, Gauge},
+    metrics::{Counter, Gauge, Histogram},
     Metrics,
 };
 
@@ -15,5 +15,15 @@ pub(crate) struct EngineApiMetrics {
     pub(crate) forkchoice_updated_messages: Counter,
     /// The total count of new payload messages received.
     pub(crate) new_payload_messages: Counter,
+    /// Time it took to compute the state root after the block was executed.
+    pub(crate) state_root_duration: Histogram,
+    /// Time it took the state root task to return the state root after the block was executed.
+    pub(crate) state_root_task_duration: Histogram,
+    /// The number of state roots of the state root task that differed from the state roots
+    /// computed after the block was executed.
+    pub(crate) state_root_task_mismatches: Counter,
+    /// The number of times the state root task failed and the state root was computed after the
+    /// block was executed instead.
+    pub(crate) state_root_task_errors: Counter,
     // TODO add latency metrics
 }
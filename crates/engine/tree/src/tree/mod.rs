@@ -17,6 +17,7 @@ use reth_chain_state::{
     CanonicalInMemoryState, ExecutedBlock, MemoryOverlayStateProvider, NewCanonicalChain,
 };
 use reth_consensus::{Consensus, PostExecutionInput};
+use reth_db_api::{database::Database, models::StoredBadBlock};
 use reth_engine_primitives::EngineTypes;
 use reth_errors::{ConsensusError, ProviderResult};
 use reth_evm::execute::{BlockExecutorProvider, Executor};
@@ -28,8 +29,8 @@ use reth_primitives::{
     SealedBlockWithSenders, SealedHeader, B256, U256,
 };
 use reth_provider::{
-    BlockReader, ExecutionOutcome, ProviderError, StateProviderBox, StateProviderFactory,
-    StateRootProvider,
+    providers::ConsistentDbView, BlockReader, DatabaseProviderFactory, ExecutionOutcome,
+    ProviderError, StateProviderBox, StateProviderFactory, StateRootProvider,
 };
 use reth_revm::database::StateProviderDatabase;
 use reth_rpc_types::{
@@ -43,6 +44,7 @@ use reth_stages_api::ControlFlow;
 use reth_trie::HashedPostState;
 use std::{
     collections::{BTreeMap, HashMap, HashSet},
+    marker::PhantomData,
     ops::Bound,
     sync::{
         mpsc::{Receiver, RecvError, RecvTimeoutError, Sender},
@@ -59,7 +61,14 @@ use tracing::*;
 
 mod config;
 mod metrics;
-use crate::{engine::EngineApiRequest, tree::metrics::EngineApiMetrics};
+mod root;
+use crate::{
+    engine::EngineApiRequest,
+    tree::{
+        metrics::EngineApiMetrics,
+        root::{StateRootConfig, StateRootHandle, StateRootTask},
+    },
+};
 pub use config::TreeConfig;
 
 /// Keeps track of the state of the tree.
@@ -363,7 +372,7 @@ pub enum TreeAction {
 /// This type is responsible for processing engine API requests, maintaining the canonical state and
 /// emitting events.
 #[derive(Debug)]
-pub struct EngineApiTreeHandler<P, E, T: EngineTypes> {
+pub struct EngineApiTreeHandler<DB, P, E, T: EngineTypes> {
     provider: P,
     executor_provider: E,
     consensus: Arc<dyn Consensus>,
@@ -399,11 +408,19 @@ pub struct EngineApiTreeHandler<P, E, T: EngineTypes> {
     config: TreeConfig,
     /// Metrics for the engine api.
     metrics: EngineApiMetrics,
+    _marker: PhantomData<DB>,
 }
 
-impl<P, E, T> EngineApiTreeHandler<P, E, T>
+impl<DB, P, E, T> EngineApiTreeHandler<DB, P, E, T>
 where
-    P: BlockReader + StateProviderFactory + Clone + 'static,
+    DB: Database + 'static,
+    P: DatabaseProviderFactory<DB>
+        + BlockReader
+        + StateProviderFactory
+        + Clone
+        + Send
+        + Sync
+        + 'static,
     E: BlockExecutorProvider,
     T: EngineTypes,
 {
@@ -439,6 +456,7 @@ where
             config,
             metrics: Default::default(),
             incoming_tx,
+            _marker: PhantomData,
         }
     }
 
@@ -1718,8 +1736,21 @@ where
         let sealed_block = Arc::new(block.block.clone());
         let block = block.unseal();
 
+        // spawn the state root task before execution, so that it can prefetch the trie nodes of
+        // the state touched by the block while transactions are executed
+        let state_root_task = if self.config.use_state_root_task() {
+            self.spawn_state_root_task(block.parent_hash)
+        } else {
+            None
+        };
+
         let exec_time = Instant::now();
-        let output = executor.execute((&block, U256::MAX).into())?;
+        let output = match &state_root_task {
+            Some(handle) => {
+                executor.execute_with_state_hook((&block, U256::MAX).into(), handle.state_hook())?
+            }
+            None => executor.execute((&block, U256::MAX).into())?,
+        };
         debug!(target: "engine", elapsed=?exec_time.elapsed(), ?block_number, "Executed block");
 
         self.consensus.validate_block_post_execution(
@@ -1727,11 +1758,47 @@ where
             PostExecutionInput::new(&output.receipts, &output.requests),
         )?;
 
-        let hashed_state = HashedPostState::from_bundle_state(&output.state.state);
+        let hashed_state = Arc::new(HashedPostState::from_bundle_state(&output.state.state));
 
         let root_time = Instant::now();
-        let (state_root, trie_output) =
-            state_provider.hashed_state_root_with_updates(hashed_state.clone())?;
+        // the state root task has already revealed the trie nodes touched by the transactions, if
+        // it fails or disagrees with the header the state root is computed regularly and compared
+        let mut task_output = None;
+        let mut task_mismatch = None;
+        if let Some(handle) = state_root_task {
+            let task_time = Instant::now();
+            match handle.finish(hashed_state.clone()) {
+                Ok((task_state_root, task_trie_output)) => {
+                    self.metrics.state_root_task_duration.record(task_time.elapsed());
+                    if task_state_root == block.state_root {
+                        task_output = Some((task_state_root, task_trie_output));
+                    } else {
+                        task_mismatch = Some(task_state_root);
+                    }
+                }
+                Err(error) => {
+                    self.metrics.state_root_task_errors.increment(1);
+                    debug!(target: "engine", ?block_number, %error, "State root task failed");
+                }
+            }
+        }
+
+        let (state_root, trie_output) = match task_output {
+            Some(output) => output,
+            None => {
+                let regular_time = Instant::now();
+                let output =
+                    state_provider.hashed_state_root_with_updates(hashed_state.as_ref().clone())?;
+                self.metrics.state_root_duration.record(regular_time.elapsed());
+
+                if let Some(task_state_root) = task_mismatch.filter(|root| *root != output.0) {
+                    self.metrics.state_root_task_mismatches.increment(1);
+                    warn!(target: "engine", ?block_number, %task_state_root, state_root = %output.0, "State root task mismatch");
+                }
+                output
+            }
+        };
+
         if state_root != block.state_root {
             return Err(ConsensusError::BodyStateRootDiff(
                 GotExpected { got: state_root, expected: block.state_root }.into(),
@@ -1750,7 +1817,7 @@ where
                 block_number,
                 vec![Requests::from(output.requests)],
             )),
-            hashed_state: Arc::new(hashed_state),
+            hashed_state,
             trie: Arc::new(trie_output),
         };
 
@@ -1776,6 +1843,33 @@ where
         Ok(InsertPayloadOk::Inserted(BlockStatus::Valid(attachment)))
     }
 
+    /// Spawns a [`StateRootTask`] for a block on top of the given parent.
+    ///
+    /// The task reads the trie from a consistent view of the database, so this returns `None` if
+    /// the parent doesn't descend from the last persisted block. The state of the in-memory
+    /// ancestors is passed to the task as an overlay.
+    fn spawn_state_root_task(&self, parent_hash: B256) -> Option<StateRootHandle> {
+        let (historical, blocks) = self
+            .state
+            .tree_state
+            .blocks_by_hash(parent_hash)
+            .unwrap_or_else(|| (parent_hash, Vec::new()));
+        if historical != self.persistence_state.last_persisted_block_hash {
+            return None
+        }
+
+        // blocks are ordered from newest to oldest
+        let mut input = HashedPostState::default();
+        for block in blocks.iter().rev() {
+            input.extend(block.hashed_state.as_ref().clone());
+        }
+
+        let consistent_view = ConsistentDbView::new(self.provider.clone(), Some(historical));
+        let prefix_sets = Arc::new(input.construct_prefix_sets());
+        let state_sorted = Arc::new(input.into_sorted());
+        Some(StateRootTask::spawn(StateRootConfig { consistent_view, state_sorted, prefix_sets }))
+    }
+
     /// Handles an error that occurred while inserting a block.
     ///
     /// If this is a validation error this will mark the block as invalid.
@@ -1802,6 +1896,8 @@ where
             self.latest_valid_hash_for_invalid_payload(block.parent_hash)?
         };
 
+        self.save_bad_block(&block, &validation_err);
+
         // keep track of the invalid header
         self.state.invalid_headers.insert(block.header);
         Ok(PayloadStatus::new(
@@ -1810,6 +1906,25 @@ where
         ))
     }
 
+    /// Sends the given invalid block to the persistence service to be saved in the bad block
+    /// store.
+    ///
+    /// This does not wait for the block to be written.
+    fn save_bad_block(&self, block: &SealedBlock, error: &impl std::fmt::Display) {
+        let pre_state_root = match self.sealed_header_by_hash(block.parent_hash) {
+            Ok(parent) => parent.map(|parent| parent.state_root).unwrap_or_default(),
+            Err(err) => {
+                warn!(target: "engine::tree", %err, hash=?block.hash(), "Failed to fetch parent of bad block");
+                return
+            }
+        };
+        let bad_block = StoredBadBlock::new(block.clone(), error.to_string(), pre_state_root);
+        let (tx, _rx) = oneshot::channel();
+        if self.persistence.save_bad_block(bad_block, tx).is_err() {
+            warn!(target: "engine::tree", hash=?block.hash(), "Failed to send bad block to persistence service");
+        }
+    }
+
     /// Attempts to find the header for the given block hash if it is canonical.
     pub fn find_canonical_header(&self, hash: B256) -> Result<Option<SealedHeader>, ProviderError> {
         let mut canonical = self.canonical_in_memory_state.header_by_hash(hash);
@@ -2024,6 +2139,7 @@ mod tests {
     use reth_beacon_consensus::{EthBeaconConsensus, ForkchoiceStatus};
     use reth_chain_state::{test_utils::TestBlockBuilder, BlockState};
     use reth_chainspec::{ChainSpec, HOLESKY, MAINNET};
+    use reth_db_api::mock::DatabaseMock;
     use reth_ethereum_engine_primitives::EthEngineTypes;
     use reth_evm::test_utils::MockExecutorProvider;
     use reth_primitives::Bytes;
@@ -2036,7 +2152,12 @@ mod tests {
     use tokio::sync::mpsc::unbounded_channel;
 
     struct TestHarness {
-        tree: EngineApiTreeHandler<MockEthProvider, MockExecutorProvider, EthEngineTypes>,
+        tree: EngineApiTreeHandler<
+            DatabaseMock,
+            MockEthProvider,
+            MockExecutorProvider,
+            EthEngineTypes,
+        >,
         to_tree_tx: Sender<FromEngine<EngineApiRequest<EthEngineTypes>>>,
         from_tree_rx: UnboundedReceiver<EngineApiEvent>,
         blocks: Vec<ExecutedBlock>,
@@ -0,0 +1,574 @@
+//! State root task computing the state root of a block from the state changes streamed during its
+//! execution.
+
+use alloy_rlp::{encode_fixed_size, Decodable};
+use reth_db_api::database::Database;
+use reth_evm::execute::OnStateHook;
+use reth_primitives::{keccak256, B256};
+use reth_provider::{providers::ConsistentDbView, DatabaseProviderFactory};
+use reth_revm::primitives::EvmState;
+use reth_trie::{
+    prefix_set::TriePrefixSetsMut,
+    updates::{StorageTrieUpdates, TrieUpdates},
+    HashedPostState, HashedPostStateSorted, MultiProof, Nibbles, TrieAccount,
+};
+use reth_trie_parallel::{parallel_proof::ParallelProof, parallel_root::ParallelStateRootError};
+use reth_trie_sparse::{SparseStateTrie, SparseTrieError, SparseTrieResult};
+use std::{
+    collections::{HashMap, HashSet},
+    sync::{
+        mpsc::{channel, Receiver, Sender},
+        Arc,
+    },
+    thread::JoinHandle,
+};
+use tracing::*;
+
+/// Result of the [`StateRootTask`], the state root and the trie updates of the block.
+pub(crate) type StateRootResult = Result<(B256, TrieUpdates), StateRootTaskError>;
+
+/// Errors of the [`StateRootTask`].
+#[derive(Debug, thiserror::Error)]
+pub(crate) enum StateRootTaskError {
+    /// Error while generating a multiproof.
+    #[error(transparent)]
+    Proof(#[from] ParallelStateRootError),
+    /// Error while updating the sparse trie.
+    #[error(transparent)]
+    SparseTrie(#[from] SparseTrieError),
+    /// The storage root of a changed account wasn't revealed.
+    #[error("missing storage root of account {0}")]
+    MissingStorageRoot(B256),
+    /// Error while decoding a revealed account.
+    #[error(transparent)]
+    Rlp(#[from] alloy_rlp::Error),
+    /// The task was terminated before the state root was computed.
+    #[error("state root task terminated")]
+    Terminated,
+}
+
+/// Messages received by the [`StateRootTask`].
+#[derive(Debug)]
+pub(crate) enum StateRootMessage {
+    /// State changes of an executed transaction.
+    StateUpdate(EvmState),
+    /// The block is executed, with the state changes of the whole block.
+    FinishedStateUpdates(Arc<HashedPostState>),
+}
+
+/// The database state the [`StateRootTask`] generates the multiproofs on top of.
+#[derive(Debug)]
+pub(crate) struct StateRootConfig<DB, Provider> {
+    /// Consistent view of the database, at the last persisted block the parent block of the
+    /// executed block builds on.
+    pub(crate) consistent_view: ConsistentDbView<DB, Provider>,
+    /// Sorted hashed state of the blocks between the last persisted block and the parent block,
+    /// which aren't persisted yet.
+    pub(crate) state_sorted: Arc<HashedPostStateSorted>,
+    /// Prefix sets of the state of the blocks that aren't persisted yet.
+    pub(crate) prefix_sets: Arc<TriePrefixSetsMut>,
+}
+
+/// [`OnStateHook`] sending the state changes of executed transactions to the [`StateRootTask`].
+#[derive(Debug)]
+pub(crate) struct StateHookSender(Sender<StateRootMessage>);
+
+impl OnStateHook for StateHookSender {
+    fn on_state(&mut self, state: &EvmState) {
+        // The task might have terminated early on error, which is reported once it's finished.
+        let _ = self.0.send(StateRootMessage::StateUpdate(state.clone()));
+    }
+}
+
+/// Handle to a spawned [`StateRootTask`].
+#[derive(Debug)]
+pub(crate) struct StateRootHandle {
+    /// Channel to the task.
+    tx: Sender<StateRootMessage>,
+    /// The thread running the task.
+    thread: JoinHandle<StateRootResult>,
+}
+
+impl StateRootHandle {
+    /// Returns a new [`OnStateHook`] that streams the state changes of executed transactions to
+    /// the task.
+    pub(crate) fn state_hook(&self) -> StateHookSender {
+        StateHookSender(self.tx.clone())
+    }
+
+    /// Sends the state changes of the whole executed block to the task and waits for the state
+    /// root.
+    pub(crate) fn finish(self, hashed_state: Arc<HashedPostState>) -> StateRootResult {
+        let _ = self.tx.send(StateRootMessage::FinishedStateUpdates(hashed_state));
+        drop(self.tx);
+        self.thread.join().unwrap_or(Err(StateRootTaskError::Terminated))
+    }
+}
+
+/// Task computing the state root and the trie updates of a block with a [`SparseStateTrie`].
+///
+/// While the block is executed, the task receives the state changes of every transaction and
+/// reveals the accounts and storage slots they touch in the sparse trie, using multiproofs
+/// generated by [`ParallelProof`]. Once the execution is finished, the state changes of the whole
+/// block are applied to the sparse trie, which only needs to reveal the few accounts changed after
+/// the transactions, such as the block rewards, before the root is computed.
+///
+/// The state changes received while a multiproof is generated are batched into the next one.
+#[derive(Debug)]
+pub(crate) struct StateRootTask<DB, Provider> {
+    /// The database state the multiproofs are generated on top of.
+    config: StateRootConfig<DB, Provider>,
+    /// Channel the messages are received on.
+    rx: Receiver<StateRootMessage>,
+    /// Sparse trie of the parent state, with the accounts and storage slots revealed so far.
+    trie: SparseStateTrie,
+    /// Storage roots of the revealed accounts in the parent state.
+    storage_roots: HashMap<B256, B256>,
+}
+
+impl<DB, Provider> StateRootTask<DB, Provider>
+where
+    DB: Database + 'static,
+    Provider: DatabaseProviderFactory<DB> + Clone + Send + Sync + 'static,
+{
+    /// Spawns the task for the given database state in a new thread, and returns the handle to
+    /// it.
+    pub(crate) fn spawn(config: StateRootConfig<DB, Provider>) -> StateRootHandle {
+        let (tx, rx) = channel();
+        let task = Self {
+            config,
+            rx,
+            trie: SparseStateTrie::default().with_updates(true),
+            storage_roots: HashMap::default(),
+        };
+        let thread = std::thread::Builder::new()
+            .name("State Root Task".to_string())
+            .spawn(|| task.run())
+            .expect("failed to spawn state root task");
+        StateRootHandle { tx, thread }
+    }
+
+    /// Processes the messages until the state changes of the whole block are received, and
+    /// returns the state root and the trie updates.
+    fn run(mut self) -> StateRootResult {
+        loop {
+            let message = self.rx.recv().map_err(|_| StateRootTaskError::Terminated)?;
+
+            let mut targets = HashMap::default();
+            let mut finished = self.on_message(message, &mut targets);
+            // Batch the state changes that were received while the last multiproof was generated.
+            while finished.is_none() {
+                let Ok(message) = self.rx.try_recv() else { break };
+                finished = self.on_message(message, &mut targets);
+            }
+
+            self.reveal(targets)?;
+
+            if let Some(hashed_state) = finished {
+                return self.state_root(hashed_state)
+            }
+        }
+    }
+
+    /// Extends the targets with the accounts and storage slots changed by the state update that
+    /// weren't revealed yet. Returns the state changes of the whole block if the execution is
+    /// finished.
+    fn on_message(
+        &self,
+        message: StateRootMessage,
+        targets: &mut HashMap<B256, HashSet<B256>>,
+    ) -> Option<Arc<HashedPostState>> {
+        match message {
+            StateRootMessage::StateUpdate(state) => {
+                for (address, account) in state {
+                    if !account.is_touched() {
+                        continue
+                    }
+
+                    let hashed_address = keccak256(address);
+                    let slots = account
+                        .storage
+                        .into_iter()
+                        .filter(|(_, value)| value.is_changed())
+                        .map(|(slot, _)| keccak256(B256::from(slot)));
+                    self.extend_targets(targets, hashed_address, slots);
+                }
+                None
+            }
+            StateRootMessage::FinishedStateUpdates(hashed_state) => Some(hashed_state),
+        }
+    }
+
+    /// Adds the account and the storage slots to the targets, unless they're already revealed.
+    fn extend_targets(
+        &self,
+        targets: &mut HashMap<B256, HashSet<B256>>,
+        hashed_address: B256,
+        hashed_slots: impl IntoIterator<Item = B256>,
+    ) {
+        let slots = hashed_slots
+            .into_iter()
+            .filter(|slot| !self.trie.is_storage_slot_revealed(&hashed_address, slot))
+            .collect::<Vec<_>>();
+        if !slots.is_empty() || !self.trie.is_account_revealed(&hashed_address) {
+            targets.entry(hashed_address).or_default().extend(slots);
+        }
+    }
+
+    /// Generates the multiproof of the targets and reveals it in the sparse trie.
+    fn reveal(&mut self, targets: HashMap<B256, HashSet<B256>>) -> Result<(), StateRootTaskError> {
+        if targets.is_empty() {
+            return Ok(())
+        }
+
+        trace!(target: "engine::root", accounts = targets.len(), "revealing multiproof");
+        let multiproof = self.multiproof(&targets)?;
+        self.reveal_multiproof(targets, multiproof)
+    }
+
+    /// Generates the multiproof of the targets on top of the parent state.
+    fn multiproof(
+        &self,
+        targets: &HashMap<B256, HashSet<B256>>,
+    ) -> Result<MultiProof, ParallelStateRootError> {
+        ParallelProof::new(
+            self.config.consistent_view.clone(),
+            self.config.state_sorted.clone(),
+            self.config.prefix_sets.clone(),
+        )
+        .with_branch_node_masks(true)
+        .multiproof(
+            targets
+                .iter()
+                .map(|(account, slots)| (*account, slots.iter().copied().collect()))
+                .collect(),
+        )
+    }
+
+    /// Reveals the multiproof of the targets in the sparse trie.
+    fn reveal_multiproof(
+        &mut self,
+        targets: HashMap<B256, HashSet<B256>>,
+        mut multiproof: MultiProof,
+    ) -> Result<(), StateRootTaskError> {
+        // Storage proofs are only generated for accounts in the parent state, the storage of the
+        // other target accounts is empty.
+        for hashed_address in targets.keys() {
+            multiproof.storages.entry(*hashed_address).or_default();
+        }
+        for (hashed_address, storage) in &multiproof.storages {
+            self.storage_roots.entry(*hashed_address).or_insert(storage.root);
+        }
+        Ok(self.trie.reveal_multiproof(targets, multiproof)?)
+    }
+
+    /// Applies the state changes of the block to the sparse trie and computes the state root and
+    /// the trie updates.
+    fn state_root(mut self, hashed_state: Arc<HashedPostState>) -> StateRootResult {
+        // Reveal the accounts and slots that were changed outside of the transactions.
+        let mut targets = HashMap::default();
+        for hashed_address in hashed_state.accounts.keys() {
+            self.extend_targets(&mut targets, *hashed_address, []);
+        }
+        for (hashed_address, storage) in &hashed_state.storages {
+            self.extend_targets(&mut targets, *hashed_address, storage.storage.keys().copied());
+        }
+        self.reveal(targets)?;
+
+        let mut storage_roots = HashMap::with_capacity(hashed_state.storages.len());
+        for (hashed_address, storage) in &hashed_state.storages {
+            let hashed_address = *hashed_address;
+            if storage.wiped {
+                self.trie.wipe_storage(hashed_address);
+            }
+            for (hashed_slot, value) in &storage.storage {
+                let path = Nibbles::unpack(hashed_slot);
+                if value.is_zero() {
+                    self.update_with_retry(Some(hashed_address), |trie| {
+                        trie.remove_storage_leaf(hashed_address, &path)
+                    })?;
+                } else {
+                    let value = encode_fixed_size(value).to_vec();
+                    self.update_with_retry(Some(hashed_address), |trie| {
+                        trie.update_storage_leaf(hashed_address, path.clone(), value.clone())
+                    })?;
+                }
+            }
+
+            if let Some(storage_root) = self.trie.storage_root(hashed_address) {
+                storage_roots.insert(hashed_address, storage_root);
+            }
+        }
+
+        for (hashed_address, account) in &hashed_state.accounts {
+            let path = Nibbles::unpack(hashed_address);
+            match account {
+                Some(account) => {
+                    let storage_root = self.storage_root(&storage_roots, *hashed_address)?;
+                    let value = alloy_rlp::encode(TrieAccount::from((*account, storage_root)));
+                    self.update_with_retry(None, |trie| {
+                        trie.update_account_leaf(path.clone(), value.clone())
+                    })?;
+                }
+                None => self.update_with_retry(None, |trie| trie.remove_account_leaf(&path))?,
+            }
+        }
+
+        // Accounts with changed storage are normally part of the changed accounts, otherwise only
+        // the storage root of the revealed account is updated.
+        for (hashed_address, storage_root) in storage_roots {
+            if hashed_state.accounts.contains_key(&hashed_address) {
+                continue
+            }
+            let Some(value) = self.trie.get_account_value(&hashed_address) else { continue };
+            let mut account = TrieAccount::decode(&mut &value[..])?;
+            account.storage_root = storage_root;
+            let path = Nibbles::unpack(hashed_address);
+            let value = alloy_rlp::encode(account);
+            self.update_with_retry(None, |trie| {
+                trie.update_account_leaf(path.clone(), value.clone())
+            })?;
+        }
+
+        // The account trie stays blind only if no account was changed.
+        let state_root = self.trie.root().ok_or(SparseTrieError::Blind)?;
+
+        let (account_updates, storage_updates) = self.trie.take_updates();
+        let storage_tries = storage_updates
+            .into_iter()
+            .map(|(hashed_address, updates)| {
+                let updates = StorageTrieUpdates::from_nodes(
+                    updates.wiped,
+                    updates.updated_nodes,
+                    updates.removed_nodes,
+                );
+                (hashed_address, updates)
+            })
+            .collect();
+        let trie_updates = TrieUpdates::from_nodes(
+            account_updates.updated_nodes,
+            account_updates.removed_nodes,
+            storage_tries,
+        );
+        Ok((state_root, trie_updates))
+    }
+
+    /// Returns the storage root of the account, either the updated one or the one of the parent
+    /// state.
+    fn storage_root(
+        &self,
+        updated: &HashMap<B256, B256>,
+        hashed_address: B256,
+    ) -> Result<B256, StateRootTaskError> {
+        updated
+            .get(&hashed_address)
+            .or_else(|| self.storage_roots.get(&hashed_address))
+            .copied()
+            .ok_or(StateRootTaskError::MissingStorageRoot(hashed_address))
+    }
+
+    /// Applies the update to the sparse trie. If the update requires a node that wasn't revealed,
+    /// e.g. the sibling of a removed leaf, the node is revealed with the multiproof of a key under
+    /// its path and the update is retried.
+    ///
+    /// The storage trie of the given account is updated, or the account trie if it's `None`.
+    fn update_with_retry(
+        &mut self,
+        hashed_address: Option<B256>,
+        mut update: impl FnMut(&mut SparseStateTrie) -> SparseTrieResult<()>,
+    ) -> Result<(), StateRootTaskError> {
+        let mut last_blinded = None;
+        loop {
+            match update(&mut self.trie) {
+                Err(SparseTrieError::BlindedNode { path, hash }) => {
+                    if last_blinded.as_ref() == Some(&path) {
+                        return Err(SparseTrieError::BlindedNode { path, hash }.into())
+                    }
+
+                    let mut key = B256::ZERO;
+                    path.pack_to(key.as_mut_slice());
+                    trace!(target: "engine::root", ?hashed_address, ?path, "revealing blinded node");
+
+                    let targets = match hashed_address {
+                        Some(hashed_address) => {
+                            HashMap::from([(hashed_address, HashSet::from([key]))])
+                        }
+                        None => HashMap::from([(key, HashSet::default())]),
+                    };
+                    let multiproof = self.multiproof(&targets)?;
+                    // The blinded keys aren't marked as revealed, since they might not exist.
+                    self.reveal_multiproof(HashMap::default(), multiproof)?;
+                    last_blinded = Some(path);
+                }
+                result => return Ok(result?),
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rand::Rng;
+    use reth_db::tables;
+    use reth_db_api::{cursor::DbCursorRO, transaction::DbTx};
+    use reth_primitives::{Account, Address, StorageEntry, U256};
+    use reth_provider::{test_utils::create_test_provider_factory, HashingWriter, TrieWriter};
+    use reth_revm::primitives::{Account as RevmAccount, AccountInfo, EvmStorageSlot};
+    use reth_trie::{BranchNodeCompact, HashedStorage, StateRoot, StorageTrieEntry, StoredNibbles};
+    use reth_trie_db::DatabaseStateRoot;
+
+    /// Entries of the account and storage trie tables.
+    type TrieTables = (Vec<(StoredNibbles, BranchNodeCompact)>, Vec<(B256, StorageTrieEntry)>);
+
+    /// Returns the entries of the account and storage trie tables.
+    fn trie_tables(tx: &impl DbTx) -> TrieTables {
+        let account_nodes = tx
+            .cursor_read::<tables::AccountsTrie>()
+            .unwrap()
+            .walk(None)
+            .unwrap()
+            .collect::<Result<Vec<_>, _>>()
+            .unwrap();
+        let storage_nodes = tx
+            .cursor_dup_read::<tables::StoragesTrie>()
+            .unwrap()
+            .walk(None)
+            .unwrap()
+            .collect::<Result<Vec<_>, _>>()
+            .unwrap();
+        (account_nodes, storage_nodes)
+    }
+
+    #[test]
+    fn state_root_task_streamed_updates() {
+        let factory = create_test_provider_factory();
+        let mut rng = rand::thread_rng();
+
+        // Persisted state, half of the accounts with storage.
+        let state = (0..50)
+            .map(|_| {
+                let account =
+                    Account { balance: U256::from(rng.gen::<u64>()), ..Default::default() };
+                let storage = if rng.gen_bool(0.5) {
+                    (0..rng.gen_range(1..20))
+                        .map(|_| (B256::random(), U256::from(rng.gen::<u64>() + 1)))
+                        .collect()
+                } else {
+                    HashMap::default()
+                };
+                (Address::random(), (account, storage))
+            })
+            .collect::<HashMap<Address, (Account, HashMap<B256, U256>)>>();
+
+        let provider_rw = factory.provider_rw().unwrap();
+        provider_rw
+            .insert_account_for_hashing(
+                state.iter().map(|(address, (account, _))| (*address, Some(*account))),
+            )
+            .unwrap();
+        provider_rw
+            .insert_storage_for_hashing(state.iter().map(|(address, (_, storage))| {
+                (
+                    *address,
+                    storage.iter().map(|(slot, value)| StorageEntry { key: *slot, value: *value }),
+                )
+            }))
+            .unwrap();
+        let (_, updates) = StateRoot::from_tx(provider_rw.tx_ref()).root_with_updates().unwrap();
+        provider_rw.write_trie_updates(&updates).unwrap();
+        provider_rw.commit().unwrap();
+
+        // State of the in-memory ancestors.
+        let mut input = HashedPostState::default();
+        for (address, (account, _)) in state.iter().take(10) {
+            let account = Account { nonce: account.nonce + 1, ..*account };
+            input.accounts.insert(keccak256(address), Some(account));
+        }
+
+        let handle = StateRootTask::spawn(StateRootConfig {
+            consistent_view: ConsistentDbView::new(factory.clone(), None),
+            state_sorted: Arc::new(input.clone().into_sorted()),
+            prefix_sets: Arc::new(input.construct_prefix_sets()),
+        });
+
+        // Stream the state changes of the transactions, touching existing and new accounts and
+        // updating, inserting and removing storage slots.
+        let mut hook = handle.state_hook();
+        let mut block_state = HashedPostState::default();
+        let addresses = state.keys().copied().collect::<Vec<_>>();
+        for _ in 0..10 {
+            let mut evm_state = EvmState::default();
+            let mut tx_state = HashedPostState::default();
+            for _ in 0..5 {
+                let address = if rng.gen_bool(0.2) {
+                    Address::random()
+                } else {
+                    addresses[rng.gen_range(0..addresses.len())]
+                };
+                let info = AccountInfo {
+                    balance: U256::from(rng.gen::<u64>()),
+                    nonce: rng.gen_range(0..100),
+                    ..Default::default()
+                };
+                let mut account = RevmAccount::from(info.clone());
+                account.mark_touch();
+
+                let mut hashed_storage = HashedStorage::new(false);
+                let existing = state.get(&address).map(|(_, storage)| storage);
+                for slot in existing.into_iter().flat_map(|storage| storage.keys()).take(3) {
+                    let value =
+                        if rng.gen_bool(0.5) { U256::ZERO } else { U256::from(rng.gen::<u64>()) };
+                    account.storage.insert(
+                        U256::from_be_bytes(slot.0),
+                        EvmStorageSlot::new_changed(U256::ZERO, value),
+                    );
+                    hashed_storage.storage.insert(keccak256(slot), value);
+                }
+                let slot = B256::random();
+                let value = U256::from(rng.gen::<u64>() + 1);
+                account.storage.insert(
+                    U256::from_be_bytes(slot.0),
+                    EvmStorageSlot::new_changed(U256::ZERO, value),
+                );
+                hashed_storage.storage.insert(keccak256(slot), value);
+
+                let hashed_address = keccak256(address);
+                tx_state.accounts.insert(hashed_address, Some(info.into()));
+                tx_state.storages.insert(hashed_address, hashed_storage);
+                evm_state.insert(address, account);
+            }
+            hook.on_state(&evm_state);
+            block_state.extend(tx_state);
+        }
+
+        // Changes after the transactions, such as block rewards, aren't streamed.
+        let beneficiary = Account { balance: U256::from(rng.gen::<u64>()), ..Default::default() };
+        block_state.accounts.insert(B256::random(), Some(beneficiary));
+
+        let (state_root, trie_updates) = handle.finish(Arc::new(block_state.clone())).unwrap();
+
+        let provider_rw = factory.provider_rw().unwrap();
+        let (_, ancestor_updates) = StateRoot::overlay_root_with_updates(
+            provider_rw.tx_ref(),
+            input.clone(),
+            Default::default(),
+        )
+        .unwrap();
+        input.extend(block_state);
+        let (expected_root, expected_updates) =
+            StateRoot::overlay_root_with_updates(provider_rw.tx_ref(), input, Default::default())
+                .unwrap();
+        assert_eq!(state_root, expected_root);
+        provider_rw.write_trie_updates(&expected_updates).unwrap();
+        let expected_tables = trie_tables(provider_rw.tx_ref());
+        drop(provider_rw);
+
+        // The trie updates of the block written on top of the ones of the ancestors result in the
+        // same trie as the sequentially computed updates of all blocks.
+        let provider_rw = factory.provider_rw().unwrap();
+        provider_rw.write_trie_updates(&ancestor_updates).unwrap();
+        provider_rw.write_trie_updates(&trie_updates).unwrap();
+        assert_eq!(trie_tables(provider_rw.tx_ref()), expected_tables);
+    }
+}
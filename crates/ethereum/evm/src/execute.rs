@@ -10,7 +10,8 @@ use reth_ethereum_consensus::validate_block_post_execution;
 use reth_evm::{
     execute::{
         BatchExecutor, BlockExecutionError, BlockExecutionInput, BlockExecutionOutput,
-        BlockExecutorProvider, BlockValidationError, Executor, ProviderError,
+        BlockExecutorProvider, BlockValidationError, Executor, NoopHook, OnStateHook,
+        ProviderError,
     },
     system_calls::{
         apply_beacon_root_contract_call, apply_consolidation_requests_contract_call,
@@ -37,6 +38,8 @@ use revm_primitives::{
 #[cfg(not(feature = "std"))]
 use alloc::{boxed::Box, sync::Arc, vec, vec::Vec};
 #[cfg(feature = "std")]
+pub use reth_evm_parallel::{ParallelExecutionConfig, ParallelExecutor};
+#[cfg(feature = "std")]
 use std::sync::Arc;
 
 /// Provides executors to execute regular ethereum blocks
@@ -44,6 +47,9 @@ use std::sync::Arc;
 pub struct EthExecutorProvider<EvmConfig = EthEvmConfig> {
     chain_spec: Arc<ChainSpec>,
     evm_config: EvmConfig,
+    /// Executes the transactions of a block in parallel, if set.
+    #[cfg(feature = "std")]
+    parallel: Option<ParallelExecutor>,
 }
 
 impl EthExecutorProvider {
@@ -61,7 +67,21 @@ impl EthExecutorProvider {
 impl<EvmConfig> EthExecutorProvider<EvmConfig> {
     /// Creates a new executor provider.
     pub const fn new(chain_spec: Arc<ChainSpec>, evm_config: EvmConfig) -> Self {
-        Self { chain_spec, evm_config }
+        Self {
+            chain_spec,
+            evm_config,
+            #[cfg(feature = "std")]
+            parallel: None,
+        }
+    }
+
+    /// Configures the provider to execute the transactions of a block in parallel.
+    ///
+    /// The executors return the same output as with sequential execution.
+    #[cfg(feature = "std")]
+    pub const fn with_parallel_execution(mut self, config: ParallelExecutionConfig) -> Self {
+        self.parallel = Some(ParallelExecutor::new(config));
+        self
     }
 }
 
@@ -73,11 +93,19 @@ where
     where
         DB: Database<Error: Into<ProviderError>>,
     {
-        EthBlockExecutor::new(
-            self.chain_spec.clone(),
-            self.evm_config.clone(),
-            State::builder().with_database(db).with_bundle_update().without_state_clear().build(),
-        )
+        EthBlockExecutor {
+            executor: EthEvmExecutor {
+                chain_spec: self.chain_spec.clone(),
+                evm_config: self.evm_config.clone(),
+                #[cfg(feature = "std")]
+                parallel: self.parallel,
+            },
+            state: State::builder()
+                .with_database(db)
+                .with_bundle_update()
+                .without_state_clear()
+                .build(),
+        }
     }
 }
 
@@ -122,6 +150,9 @@ struct EthEvmExecutor<EvmConfig> {
     chain_spec: Arc<ChainSpec>,
     /// How to create an EVM.
     evm_config: EvmConfig,
+    /// Executes the transactions in parallel, if set.
+    #[cfg(feature = "std")]
+    parallel: Option<ParallelExecutor>,
 }
 
 impl<EvmConfig> EthEvmExecutor<EvmConfig>
@@ -138,14 +169,18 @@ where
     ///
     /// It does __not__ apply post-execution changes that do not require an [EVM](Evm), for that see
     /// [`EthBlockExecutor::post_execution`].
-    fn execute_state_transitions<Ext, DB>(
+    ///
+    /// The state hook is invoked with the state changes of every transaction.
+    fn execute_state_transitions<Ext, DB, F>(
         &self,
         block: &BlockWithSenders,
         mut evm: Evm<'_, Ext, &mut State<DB>>,
+        mut state_hook: F,
     ) -> Result<EthExecuteOutput, BlockExecutionError>
     where
         DB: Database,
         DB::Error: Into<ProviderError> + Display,
+        F: OnStateHook,
     {
         // apply pre execution changes
         apply_beacon_root_contract_call(
@@ -165,6 +200,13 @@ where
         )?;
 
         // execute transactions
+        #[cfg(feature = "std")]
+        let mut parallel_results = self.execute_transactions_in_parallel(block, &mut evm);
+        #[cfg(not(feature = "std"))]
+        let mut parallel_results: Option<
+            vec::IntoIter<Result<ResultAndState, EVMError<ProviderError>>>,
+        > = None;
+
         let mut cumulative_gas_used = 0;
         let mut receipts = Vec::with_capacity(block.body.len());
         for (sender, transaction) in block.transactions_with_sender() {
@@ -179,23 +221,33 @@ where
                 .into())
             }
 
-            self.evm_config.fill_tx_env(evm.tx_mut(), transaction, *sender);
-
-            // Execute transaction.
-            let ResultAndState { result, state } = evm.transact().map_err(move |err| {
-                let new_err = match err {
-                    EVMError::Transaction(e) => EVMError::Transaction(e),
-                    EVMError::Header(e) => EVMError::Header(e),
-                    EVMError::Database(e) => EVMError::Database(e.into()),
-                    EVMError::Custom(e) => EVMError::Custom(e),
-                    EVMError::Precompile(e) => EVMError::Precompile(e),
-                };
+            // Execute transaction, or take the result of its parallel execution.
+            let result = match parallel_results.as_mut() {
+                // Only the results up to the first failed transaction are returned, and execution
+                // stops at that transaction.
+                #[cfg(feature = "std")]
+                Some(results) => results.next().ok_or_else(|| {
+                    BlockExecutionError::msg("parallel execution returned no transaction result")
+                })?,
+                _ => {
+                    self.evm_config.fill_tx_env(evm.tx_mut(), transaction, *sender);
+                    evm.transact().map_err(|err| match err {
+                        EVMError::Transaction(e) => EVMError::Transaction(e),
+                        EVMError::Header(e) => EVMError::Header(e),
+                        EVMError::Database(e) => EVMError::Database(e.into()),
+                        EVMError::Custom(e) => EVMError::Custom(e),
+                        EVMError::Precompile(e) => EVMError::Precompile(e),
+                    })
+                }
+            };
+            let ResultAndState { result, state } = result.map_err(move |err| {
                 // Ensure hash is calculated for error log, if not already done
                 BlockValidationError::EVM {
                     hash: transaction.recalculate_hash(),
-                    error: Box::new(new_err),
+                    error: Box::new(err),
                 }
             })?;
+            state_hook.on_state(&state);
             evm.db_mut().commit(state);
 
             // append gas used
@@ -237,6 +289,33 @@ where
 
         Ok(EthExecuteOutput { receipts, requests, gas_used: cumulative_gas_used })
     }
+
+    /// Executes the transactions of the block in parallel on top of the state of the given EVM, if
+    /// a [`ParallelExecutor`] is configured.
+    ///
+    /// Returns the results of the transactions in order. The state changes are not committed.
+    #[cfg(feature = "std")]
+    fn execute_transactions_in_parallel<Ext, DB>(
+        &self,
+        block: &BlockWithSenders,
+        evm: &mut Evm<'_, Ext, &mut State<DB>>,
+    ) -> Option<std::vec::IntoIter<Result<ResultAndState, EVMError<ProviderError>>>>
+    where
+        DB: Database,
+        DB::Error: Into<ProviderError> + Display,
+    {
+        let parallel = self.parallel.as_ref()?;
+        let env = EnvWithHandlerCfg::new(evm.context.evm.env.clone(), evm.handler.cfg);
+        let txs = block
+            .transactions_with_sender()
+            .map(|(sender, transaction)| {
+                let mut tx_env = Default::default();
+                self.evm_config.fill_tx_env(&mut tx_env, transaction, *sender);
+                tx_env
+            })
+            .collect::<Vec<_>>();
+        Some(parallel.execute(&self.evm_config, evm.db_mut(), &env, &txs).into_iter())
+    }
 }
 
 /// A basic Ethereum block executor.
@@ -255,7 +334,24 @@ pub struct EthBlockExecutor<EvmConfig, DB> {
 impl<EvmConfig, DB> EthBlockExecutor<EvmConfig, DB> {
     /// Creates a new Ethereum block executor.
     pub const fn new(chain_spec: Arc<ChainSpec>, evm_config: EvmConfig, state: State<DB>) -> Self {
-        Self { executor: EthEvmExecutor { chain_spec, evm_config }, state }
+        Self {
+            executor: EthEvmExecutor {
+                chain_spec,
+                evm_config,
+                #[cfg(feature = "std")]
+                parallel: None,
+            },
+            state,
+        }
+    }
+
+    /// Sets the [`ParallelExecutor`] that executes the transactions of a block.
+    ///
+    /// If `None`, the transactions are executed sequentially.
+    #[cfg(feature = "std")]
+    pub const fn with_parallel_executor(mut self, parallel: Option<ParallelExecutor>) -> Self {
+        self.executor.parallel = parallel;
+        self
     }
 
     #[inline]
@@ -305,6 +401,25 @@ where
         block: &BlockWithSenders,
         total_difficulty: U256,
     ) -> Result<EthExecuteOutput, BlockExecutionError> {
+        self.execute_without_verification_with_state_hook(block, total_difficulty, NoopHook)
+    }
+
+    /// Execute a single block and apply the state changes to the internal state, invoking the
+    /// state hook with the state changes of every transaction.
+    ///
+    /// Returns the receipts of the transactions in the block, the total gas used and the list of
+    /// EIP-7685 [requests](Request).
+    ///
+    /// Returns an error if execution fails.
+    fn execute_without_verification_with_state_hook<F>(
+        &mut self,
+        block: &BlockWithSenders,
+        total_difficulty: U256,
+        state_hook: F,
+    ) -> Result<EthExecuteOutput, BlockExecutionError>
+    where
+        F: OnStateHook,
+    {
         // 1. prepare state on new block
         self.on_new_block(&block.header);
 
@@ -312,7 +427,7 @@ where
         let env = self.evm_env_for_block(&block.header, total_difficulty);
         let output = {
             let evm = self.executor.evm_config.evm_with_env(&mut self.state, env);
-            self.executor.execute_state_transitions(block, evm)
+            self.executor.execute_state_transitions(block, evm, state_hook)
         }?;
 
         // 3. apply post execution changes
@@ -374,10 +489,21 @@ where
     /// Returns the receipts of the transactions in the block.
     ///
     /// Returns an error if the block could not be executed or failed verification.
-    fn execute(mut self, input: Self::Input<'_>) -> Result<Self::Output, Self::Error> {
+    fn execute(self, input: Self::Input<'_>) -> Result<Self::Output, Self::Error> {
+        self.execute_with_state_hook(input, NoopHook)
+    }
+
+    fn execute_with_state_hook<F>(
+        mut self,
+        input: Self::Input<'_>,
+        state_hook: F,
+    ) -> Result<Self::Output, Self::Error>
+    where
+        F: OnStateHook,
+    {
         let BlockExecutionInput { block, total_difficulty } = input;
         let EthExecuteOutput { receipts, requests, gas_used } =
-            self.execute_without_verification(block, total_difficulty)?;
+            self.execute_without_verification_with_state_hook(block, total_difficulty, state_hook)?;
 
         // NOTE: we need to merge keep the reverts for the bundle retention
         self.state.merge_transitions(BundleRetention::Reverts);
@@ -473,7 +599,8 @@ mod tests {
     use reth_chainspec::{ChainSpecBuilder, ForkCondition};
     use reth_primitives::{
         constants::{EMPTY_ROOT_HASH, ETH_TO_WEI},
-        keccak256, public_key_to_address, Account, Block, Transaction, TxKind, TxLegacy, B256,
+        keccak256, public_key_to_address, Account, Address, Block, Transaction, TxKind, TxLegacy,
+        B256,
     };
     use reth_revm::{
         database::StateProviderDatabase, test_utils::StateProviderTest, TransitionState,
@@ -522,7 +649,7 @@ mod tests {
     }
 
     fn executor_provider(chain_spec: Arc<ChainSpec>) -> EthExecutorProvider<EthEvmConfig> {
-        EthExecutorProvider { chain_spec, evm_config: Default::default() }
+        EthExecutorProvider::new(chain_spec, Default::default())
     }
 
     #[test]
@@ -1405,4 +1532,126 @@ mod tests {
             ),
         }
     }
+
+    /// Sets up accounts for three senders and a counter contract that increments slot 0, and
+    /// returns a block of conflicting transactions between them.
+    ///
+    /// All transactions pay fees to the beneficiary, one of them also sends value to it.
+    fn create_state_and_block_with_conflicts(
+        chain_spec: &ChainSpec,
+        nonce_offset: u64,
+    ) -> (StateProviderTest, BlockWithSenders) {
+        let mut db = StateProviderTest::default();
+        let secp = Secp256k1::new();
+        let mut rng = generators::rng();
+        let key_pairs = [(); 3].map(|_| Keypair::new(&secp, &mut rng));
+        let senders = key_pairs.map(|key_pair| public_key_to_address(key_pair.public_key()));
+        for sender in senders {
+            db.insert_account(
+                sender,
+                Account { nonce: 0, balance: U256::from(ETH_TO_WEI), bytecode_hash: None },
+                None,
+                HashMap::new(),
+            );
+        }
+
+        // PUSH1 0 SLOAD PUSH1 1 ADD PUSH1 0 SSTORE STOP
+        let counter_code =
+            Bytes::from_static(&[0x60, 0x00, 0x54, 0x60, 0x01, 0x01, 0x60, 0x00, 0x55, 0x00]);
+        let counter = Address::with_last_byte(0xcc);
+        db.insert_account(
+            counter,
+            Account {
+                nonce: 1,
+                balance: U256::ZERO,
+                bytecode_hash: Some(keccak256(&counter_code)),
+            },
+            Some(counter_code),
+            HashMap::from([(B256::ZERO, U256::from(7))]),
+        );
+
+        // PUSH1 1 PUSH1 0 SSTORE PUSH1 1 PUSH1 0 RETURN
+        let init_code =
+            Bytes::from_static(&[0x60, 0x01, 0x60, 0x00, 0x55, 0x60, 0x01, 0x60, 0x00, 0xf3]);
+        let beneficiary = Address::with_last_byte(0xbe);
+
+        let tx = |sender: usize, nonce: u64, to: TxKind, value: u64, input: Bytes| {
+            sign_tx_with_key_pair(
+                key_pairs[sender],
+                Transaction::Legacy(TxLegacy {
+                    chain_id: Some(chain_spec.chain.id()),
+                    nonce: nonce + nonce_offset,
+                    gas_price: 1,
+                    gas_limit: 100_000,
+                    to,
+                    value: U256::from(value),
+                    input,
+                }),
+            )
+        };
+        let body = vec![
+            tx(0, 0, TxKind::Call(senders[1]), 1_000, Bytes::new()),
+            tx(0, 1, TxKind::Call(senders[2]), 2_000, Bytes::new()),
+            tx(1, 0, TxKind::Call(senders[0]), 3_000, Bytes::new()),
+            tx(2, 0, TxKind::Call(counter), 0, Bytes::new()),
+            tx(0, 2, TxKind::Call(counter), 0, Bytes::new()),
+            tx(1, 1, TxKind::Create, 0, init_code),
+            tx(2, 1, TxKind::Call(counter), 0, Bytes::new()),
+            tx(2, 2, TxKind::Call(beneficiary), 4_000, Bytes::new()),
+            tx(1, 2, TxKind::Call(senders[2]), 5_000, Bytes::new()),
+        ];
+
+        let header = Header { number: 1, beneficiary, gas_limit: 30_000_000, ..Header::default() };
+        let block = Block { header, body, ommers: vec![], withdrawals: None, requests: None }
+            .with_recovered_senders()
+            .unwrap();
+
+        (db, block)
+    }
+
+    #[test]
+    fn parallel_execution_matches_sequential() {
+        let chain_spec = Arc::new(ChainSpecBuilder::mainnet().berlin_activated().build());
+        let (db, block) = create_state_and_block_with_conflicts(&chain_spec, 0);
+
+        let mut sequential = executor_provider(chain_spec.clone())
+            .executor(StateProviderDatabase::new(&db))
+            .execute((&block, U256::ZERO).into())
+            .unwrap();
+        sequential.state.reverts.sort();
+        assert!(sequential.receipts.iter().all(|receipt| receipt.success));
+
+        let config = ParallelExecutionConfig { workers: 4.try_into().unwrap() };
+        let provider = executor_provider(chain_spec).with_parallel_execution(config);
+        for _ in 0..10 {
+            let mut parallel = provider
+                .executor(StateProviderDatabase::new(&db))
+                .execute((&block, U256::ZERO).into())
+                .unwrap();
+            // The order of accounts in reverts is not deterministic.
+            parallel.state.reverts.sort();
+            assert_eq!(parallel, sequential);
+        }
+    }
+
+    #[test]
+    fn parallel_execution_reports_failed_transaction() {
+        let chain_spec = Arc::new(ChainSpecBuilder::mainnet().berlin_activated().build());
+        // All nonces are too high.
+        let (db, block) = create_state_and_block_with_conflicts(&chain_spec, 1);
+
+        let sequential = executor_provider(chain_spec.clone())
+            .executor(StateProviderDatabase::new(&db))
+            .execute((&block, U256::ZERO).into())
+            .unwrap_err();
+
+        let config = ParallelExecutionConfig { workers: 4.try_into().unwrap() };
+        let parallel = executor_provider(chain_spec)
+            .with_parallel_execution(config)
+            .executor(StateProviderDatabase::new(&db))
+            .execute((&block, U256::ZERO).into())
+            .unwrap_err();
+
+        assert_eq!(parallel.to_string(), sequential.to_string());
+    }
 }
@@ -1,6 +1,6 @@
 //! Ethereum EVM support
 
 #[doc(inline)]
-pub use reth_evm_ethereum::execute::EthExecutorProvider;
+pub use reth_evm_ethereum::execute::{EthExecutorProvider, ParallelExecutionConfig};
 #[doc(inline)]
 pub use reth_evm_ethereum::EthEvmConfig;
@@ -8,7 +8,7 @@ use reth_beacon_consensus::EthBeaconConsensus;
 use reth_ethereum_engine_primitives::{
     EthBuiltPayload, EthPayloadAttributes, EthPayloadBuilderAttributes,
 };
-use reth_evm_ethereum::execute::EthExecutorProvider;
+use reth_evm_ethereum::execute::{EthExecutorProvider, ParallelExecutionConfig};
 use reth_network::NetworkHandle;
 use reth_node_api::{FullNodeComponents, NodeAddOns};
 use reth_node_builder::{
@@ -99,7 +99,18 @@ where
 /// A regular ethereum evm and executor builder.
 #[derive(Debug, Default, Clone, Copy)]
 #[non_exhaustive]
-pub struct EthereumExecutorBuilder;
+pub struct EthereumExecutorBuilder {
+    /// Executes the transactions of a block in parallel, if set.
+    pub parallel: Option<ParallelExecutionConfig>,
+}
+
+impl EthereumExecutorBuilder {
+    /// Configures the executor to execute the transactions of a block in parallel.
+    pub const fn with_parallel_execution(mut self, config: ParallelExecutionConfig) -> Self {
+        self.parallel = Some(config);
+        self
+    }
+}
 
 impl<Node> ExecutorBuilder<Node> for EthereumExecutorBuilder
 where
@@ -114,7 +125,10 @@ where
     ) -> eyre::Result<(Self::EVM, Self::Executor)> {
         let chain_spec = ctx.chain_spec();
         let evm_config = EthEvmConfig::default();
-        let executor = EthExecutorProvider::new(chain_spec, evm_config);
+        let mut executor = EthExecutorProvider::new(chain_spec, evm_config);
+        if let Some(config) = self.parallel {
+            executor = executor.with_parallel_execution(config);
+        }
 
         Ok((evm_config, executor))
     }
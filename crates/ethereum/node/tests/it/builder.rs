@@ -7,7 +7,10 @@ use reth_db::{
     DatabaseEnv,
 };
 use reth_node_builder::{EngineNodeLauncher, FullNodeComponents, NodeBuilder, NodeConfig};
-use reth_node_ethereum::node::{EthereumAddOns, EthereumNode};
+use reth_node_ethereum::{
+    evm::ParallelExecutionConfig,
+    node::{EthereumAddOns, EthereumExecutorBuilder, EthereumNode},
+};
 use reth_provider::providers::BlockchainProvider2;
 use reth_tasks::TaskManager;
 
@@ -41,6 +44,20 @@ fn test_basic_setup() {
         .check_launch();
 }
 
+#[test]
+fn test_parallel_executor_setup() {
+    let config = NodeConfig::test();
+    let db = create_test_rw_db();
+    let executor = EthereumExecutorBuilder::default()
+        .with_parallel_execution(ParallelExecutionConfig::default());
+    let _builder = NodeBuilder::new(config)
+        .with_database(db)
+        .with_types::<EthereumNode>()
+        .with_components(EthereumNode::components().executor(executor))
+        .with_add_ons::<EthereumAddOns>()
+        .check_launch();
+}
+
 #[tokio::test]
 async fn test_eth_launcher() {
     let tasks = TaskManager::current();
@@ -13,7 +13,7 @@ use reth_basic_payload_builder::{
     commit_withdrawals, is_better_payload, BuildArguments, BuildOutcome, PayloadBuilder,
     PayloadConfig, WithdrawalsOutcome,
 };
-use reth_errors::RethError;
+use reth_errors::{ProviderError, RethError};
 use reth_evm::{
     system_calls::{
         post_block_withdrawal_requests_contract_call, pre_block_beacon_root_contract_call,
@@ -31,17 +31,21 @@ use reth_primitives::{
     },
     eip4844::calculate_excess_blob_gas,
     proofs::{self, calculate_requests_root},
-    Block, EthereumHardforks, Header, IntoRecoveredTransaction, Receipt, EMPTY_OMMER_ROOT_HASH,
-    U256,
+    Block, EthereumHardforks, Header, IntoRecoveredTransaction, Receipt, TransactionSigned,
+    EMPTY_OMMER_ROOT_HASH, U256,
 };
 use reth_provider::StateProviderFactory;
 use reth_revm::{database::StateProviderDatabase, state_change::apply_blockhashes_update};
-use reth_transaction_pool::{BestTransactionsAttributes, TransactionPool};
+use reth_transaction_pool::{bundle::PoolBundle, BestTransactionsAttributes, TransactionPool};
 use revm::{
-    db::states::bundle_state::BundleRetention,
-    primitives::{EVMError, EnvWithHandlerCfg, InvalidTransaction, ResultAndState},
-    DatabaseCommit, State,
+    db::{states::bundle_state::BundleRetention, CacheDB},
+    primitives::{
+        AccountInfo, Address, BlockEnv, Bytecode, CfgEnvWithHandlerCfg, EVMError,
+        EnvWithHandlerCfg, InvalidTransaction, ResultAndState, B256,
+    },
+    Database, DatabaseCommit, DatabaseRef, State,
 };
+use std::cell::RefCell;
 use tracing::{debug, trace, warn};
 
 /// Ethereum payload builder
@@ -326,6 +330,35 @@ where
     .map_err(|err| PayloadBuilderError::Internal(err.into()))?;
 
     let mut receipts = Vec::new();
+
+    // include the bundles that target this block at the top of the block
+    if let Some(bundle_pool) = pool.bundle_pool() {
+        for bundle in bundle_pool.bundles_for_block(block_number, attributes.timestamp) {
+            // check if the job was cancelled, if so we can exit early
+            if cancel.is_cancelled() {
+                return Ok(BuildOutcome::Cancelled)
+            }
+
+            let Some(executed) = execute_bundle(
+                &evm_config,
+                &mut db,
+                &initialized_cfg,
+                &initialized_block_env,
+                &bundle,
+                cumulative_gas_used,
+                block_gas_limit,
+            )?
+            else {
+                continue
+            };
+
+            cumulative_gas_used = executed.cumulative_gas_used;
+            total_fees += executed.fees;
+            receipts.extend(executed.receipts.into_iter().map(Some));
+            executed_txs.extend(executed.transactions);
+        }
+    }
+
     while let Some(pool_tx) = best_txs.next() {
         // ensure we still have capacity for this transaction
         if cumulative_gas_used + pool_tx.gas_limit() > block_gas_limit {
@@ -548,3 +581,196 @@ where
 
     Ok(BuildOutcome::Better { payload, cached_reads })
 }
+
+/// The transactions and receipts of a bundle that was included in a payload.
+#[derive(Debug)]
+struct ExecutedBundle {
+    /// The executed transactions of the bundle.
+    transactions: Vec<TransactionSigned>,
+    /// The receipts of the executed transactions.
+    receipts: Vec<Receipt>,
+    /// The cumulative gas used of the block after the bundle.
+    cumulative_gas_used: u64,
+    /// The priority fees paid by the transactions of the bundle.
+    fees: U256,
+}
+
+/// A read-only view of the [`State`] of a payload, used as the base of the layer a bundle is
+/// executed in.
+///
+/// Reads only load accounts into the cache of the state, they don't change it.
+struct StateRef<'a, DB>(RefCell<&'a mut State<DB>>);
+
+impl<DB: Database> DatabaseRef for StateRef<'_, DB> {
+    type Error = DB::Error;
+
+    fn basic_ref(&self, address: Address) -> Result<Option<AccountInfo>, Self::Error> {
+        self.0.borrow_mut().basic(address)
+    }
+
+    fn code_by_hash_ref(&self, code_hash: B256) -> Result<Bytecode, Self::Error> {
+        self.0.borrow_mut().code_by_hash(code_hash)
+    }
+
+    fn storage_ref(&self, address: Address, index: U256) -> Result<U256, Self::Error> {
+        self.0.borrow_mut().storage(address, index)
+    }
+
+    fn block_hash_ref(&self, number: u64) -> Result<B256, Self::Error> {
+        self.0.borrow_mut().block_hash(number)
+    }
+}
+
+/// Executes the transactions of the bundle on top of the given state.
+///
+/// The bundle is executed atomically: the transactions are executed in a layer on top of the
+/// state, which is only committed to the state once all of them succeeded. If one of them is
+/// invalid, does not fit into the block or reverts without being allowed to, the layer is
+/// discarded and `None` is returned.
+fn execute_bundle<EvmConfig, DB>(
+    evm_config: &EvmConfig,
+    db: &mut State<DB>,
+    initialized_cfg: &CfgEnvWithHandlerCfg,
+    initialized_block_env: &BlockEnv,
+    bundle: &PoolBundle,
+    mut cumulative_gas_used: u64,
+    block_gas_limit: u64,
+) -> Result<Option<ExecutedBundle>, PayloadBuilderError>
+where
+    EvmConfig: ConfigureEvm,
+    DB: Database<Error = ProviderError>,
+{
+    let base_fee = initialized_block_env.basefee.to::<u64>();
+    let mut layer = CacheDB::new(StateRef(RefCell::new(&mut *db)));
+    // the state changes of the transactions, committed to the state if the bundle is included
+    let mut states = Vec::with_capacity(bundle.transactions().len());
+
+    let mut transactions = Vec::with_capacity(bundle.transactions().len());
+    let mut receipts = Vec::with_capacity(bundle.transactions().len());
+    let mut fees = U256::ZERO;
+
+    let dropped = 'bundle: {
+        for tx in bundle.transactions() {
+            if cumulative_gas_used + tx.gas_limit() > block_gas_limit {
+                trace!(target: "payload_builder", bundle=?bundle.hash(), tx=?tx.hash, "dropping bundle that exceeds the block gas limit");
+                break 'bundle true
+            }
+
+            let env = EnvWithHandlerCfg::new_with_cfg_env(
+                initialized_cfg.clone(),
+                initialized_block_env.clone(),
+                evm_config.tx_env(tx),
+            );
+            let mut evm = evm_config.evm_with_env(&mut layer, env);
+
+            let ResultAndState { result, state } = match evm.transact() {
+                Ok(res) => res,
+                Err(EVMError::Transaction(err)) => {
+                    trace!(target: "payload_builder", %err, bundle=?bundle.hash(), tx=?tx.hash, "dropping bundle with invalid transaction");
+                    break 'bundle true
+                }
+                // this is an error that we should treat as fatal for this attempt
+                Err(err) => return Err(PayloadBuilderError::EvmExecutionError(err)),
+            };
+            // drop evm so db is released.
+            drop(evm);
+
+            if !result.is_success() && !bundle.can_revert(&tx.hash) {
+                trace!(target: "payload_builder", bundle=?bundle.hash(), tx=?tx.hash, "dropping bundle with reverted transaction");
+                break 'bundle true
+            }
+            layer.commit(state.clone());
+            states.push(state);
+
+            let gas_used = result.gas_used();
+            cumulative_gas_used += gas_used;
+
+            #[allow(clippy::needless_update)] // side-effect of optimism fields
+            receipts.push(Receipt {
+                tx_type: tx.tx_type(),
+                success: result.is_success(),
+                cumulative_gas_used,
+                logs: result.into_logs(),
+                ..Default::default()
+            });
+
+            let miner_fee = tx
+                .effective_tip_per_gas(Some(base_fee))
+                .expect("fee is always valid; execution succeeded");
+            fees += U256::from(miner_fee) * U256::from(gas_used);
+
+            transactions.push(tx.clone().into_signed());
+        }
+        false
+    };
+
+    if dropped {
+        return Ok(None)
+    }
+
+    for state in states {
+        db.commit(state);
+    }
+
+    Ok(Some(ExecutedBundle { transactions, receipts, cumulative_gas_used, fees }))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use reth_primitives::{public_key_to_address, Account, Address, Transaction, TxKind, TxLegacy};
+    use reth_revm::test_utils::StateProviderTest;
+    use reth_testing_utils::generators::{self, sign_tx_with_key_pair};
+    use revm::primitives::{CfgEnv, SpecId};
+
+    #[test]
+    fn bundles_are_executed_atomically() {
+        let key = generators::generate_keys(&mut generators::rng(), 1)[0];
+        let sender = public_key_to_address(key.public_key());
+        let mut provider = StateProviderTest::default();
+        provider.insert_account(
+            sender,
+            Account { nonce: 0, balance: U256::from(10u128.pow(18)), bytecode_hash: None },
+            None,
+            Default::default(),
+        );
+        let mut db = State::builder()
+            .with_database(StateProviderDatabase::new(provider))
+            .with_bundle_update()
+            .build();
+
+        let transfer = |nonce| {
+            let tx = sign_tx_with_key_pair(
+                key,
+                Transaction::Legacy(TxLegacy {
+                    chain_id: Some(1),
+                    nonce,
+                    gas_price: 1,
+                    gas_limit: 21_000,
+                    to: TxKind::Call(Address::repeat_byte(1)),
+                    value: U256::from(1),
+                    ..Default::default()
+                }),
+            );
+            tx.with_signer(sender)
+        };
+        let cfg = CfgEnvWithHandlerCfg::new_with_spec_id(CfgEnv::default(), SpecId::CANCUN);
+        let block_env = BlockEnv { gas_limit: U256::from(30_000_000), ..Default::default() };
+        let execute = |db: &mut State<_>, bundle: &PoolBundle| {
+            execute_bundle(&EthEvmConfig::default(), db, &cfg, &block_env, bundle, 0, 30_000_000)
+                .unwrap()
+        };
+
+        // the second transaction has a nonce gap, so the first one is rolled back as well
+        assert!(execute(&mut db, &PoolBundle::new(vec![transfer(0), transfer(2)], 1)).is_none());
+        assert_eq!(db.basic(sender).unwrap().unwrap().nonce, 0);
+        assert!(db.transition_state.as_ref().unwrap().transitions.is_empty());
+
+        let executed = execute(&mut db, &PoolBundle::new(vec![transfer(0), transfer(1)], 1));
+        let executed = executed.unwrap();
+        assert_eq!(executed.transactions.len(), 2);
+        assert_eq!(executed.cumulative_gas_used, 42_000);
+        assert_eq!(db.basic(sender).unwrap().unwrap().nonce, 2);
+        assert_eq!(db.basic(Address::repeat_byte(1)).unwrap().unwrap().balance, U256::from(2));
+    }
+}
@@ -0,0 +1,85 @@
+#![allow(missing_docs)]
+use alloy_primitives::{keccak256, TxHash, TxNumber};
+use criterion::{
+    black_box, criterion_group, criterion_main, measurement::WallTime, BenchmarkGroup, Criterion,
+};
+use reth_etl::Collector;
+
+criterion_group! {
+    name = benches;
+    config = Criterion::default();
+    targets = collector
+}
+criterion_main!(benches);
+
+/// Size in bytes of the in-memory buffer of the collector.
+const BUFFER_CAPACITY: usize = 4 * 1024 * 1024;
+
+/// It benchmarks collecting transaction hashes to transaction numbers, the way the
+/// `TransactionLookup` stage does, and iterating over them in a sorted manner.
+/// * `iter`: single threaded merge of all files.
+/// * `partitions`: merge of 4 key ranges on their own threads, consumed concurrently.
+///
+/// Both with and without background flush workers, and with and without compression.
+pub fn collector(c: &mut Criterion) {
+    let mut group = c.benchmark_group("ETL Collector");
+    group.sample_size(10);
+
+    for size in [100_000, 1_000_000] {
+        let entries = (0..size as TxNumber)
+            .map(|number| (keccak256(number.to_be_bytes()), number))
+            .collect::<Vec<_>>();
+
+        for workers in [0, 4] {
+            for compression in [false, true] {
+                for partitions in [None, Some(4)] {
+                    measure_collector(&mut group, &entries, workers, compression, partitions);
+                }
+            }
+        }
+    }
+}
+
+fn measure_collector(
+    group: &mut BenchmarkGroup<'_, WallTime>,
+    entries: &[(TxHash, TxNumber)],
+    workers: usize,
+    compression: bool,
+    partitions: Option<usize>,
+) {
+    let merge =
+        partitions.map_or("iter".to_string(), |partitions| format!("{partitions}-partitions"));
+    let name =
+        format!("{} | workers: {workers} | compression: {compression} | {merge}", entries.len());
+
+    group.bench_function(name, |b| {
+        b.iter(|| {
+            let mut collector = Collector::new(BUFFER_CAPACITY, None)
+                .with_workers(workers)
+                .with_compression(compression);
+            for (hash, number) in entries {
+                collector.insert(*hash, *number).unwrap();
+            }
+
+            match partitions {
+                None => {
+                    for entry in collector.iter().unwrap() {
+                        black_box(entry.unwrap());
+                    }
+                }
+                Some(partitions) => {
+                    let iters = collector.iter_partitions(partitions).unwrap();
+                    std::thread::scope(|scope| {
+                        for iter in iters {
+                            scope.spawn(move || {
+                                for entry in iter {
+                                    black_box(entry.unwrap());
+                                }
+                            });
+                        }
+                    });
+                }
+            }
+        })
+    });
+}
@@ -0,0 +1,197 @@
+use std::{
+    fs::File,
+    io::{self, BufReader, BufWriter, Cursor, Read, Seek, SeekFrom, Write},
+    path::Path,
+};
+
+use tempfile::NamedTempFile;
+
+/// Target size in bytes of the blocks of an [`EtlFile`], before compression.
+const BLOCK_SIZE: usize = 64 * 1024;
+
+/// An encoded key and compressed value pair, as stored in an [`EtlFile`].
+pub(crate) type Entry = (Vec<u8>, Vec<u8>);
+
+/// A block of entries of an [`EtlFile`].
+#[derive(Debug)]
+pub(crate) struct EtlBlock {
+    /// Key of the first entry of the block.
+    pub(crate) first_key: Vec<u8>,
+    /// Offset of the block in the file.
+    offset: u64,
+    /// Number of entries in the file before the block.
+    entries_before: usize,
+}
+
+/// A temporary ETL file.
+///
+/// Each entry is stored as the lengths of its key and value, followed by the key and the value.
+/// Entries are grouped into blocks of roughly [`BLOCK_SIZE`] bytes, which allows reading the file
+/// starting at a given key. Compressed files store every block as its compressed length, followed
+/// by the LZ4 compressed block.
+#[derive(Debug)]
+pub(crate) struct EtlFile {
+    /// The temporary file, removed on drop.
+    file: NamedTempFile,
+    /// Reader over all entries of the file.
+    reader: EtlFileReader,
+    /// Whether the blocks of the file are compressed.
+    compressed: bool,
+    /// Number of entries in the file.
+    len: usize,
+    /// Blocks of the file, in the order of their keys.
+    blocks: Vec<EtlBlock>,
+}
+
+impl EtlFile {
+    /// Create a new file with the given data (which should be pre-sorted) at the given path.
+    ///
+    /// The file will be a temporary file.
+    pub(crate) fn new<K, V>(dir: &Path, buffer: Vec<(K, V)>, compressed: bool) -> io::Result<Self>
+    where
+        K: AsRef<[u8]>,
+        V: AsRef<[u8]>,
+    {
+        let mut w = BufWriter::new(NamedTempFile::new_in(dir)?);
+        let mut blocks = Vec::new();
+        let mut block = Vec::with_capacity(BLOCK_SIZE);
+        let mut offset = 0;
+        for (index, entry) in buffer.iter().enumerate() {
+            let k = entry.0.as_ref();
+            let v = entry.1.as_ref();
+
+            if block.is_empty() {
+                blocks.push(EtlBlock { first_key: k.to_vec(), offset, entries_before: index });
+            }
+
+            block.extend_from_slice(&k.len().to_be_bytes());
+            block.extend_from_slice(&v.len().to_be_bytes());
+            block.extend_from_slice(k);
+            block.extend_from_slice(v);
+
+            if block.len() >= BLOCK_SIZE {
+                offset += write_block(&mut w, &block, compressed)?;
+                block.clear();
+            }
+        }
+        if !block.is_empty() {
+            write_block(&mut w, &block, compressed)?;
+        }
+
+        let file = w.into_inner()?;
+        let len = buffer.len();
+        let reader = EtlFileReader::new(file.reopen()?, compressed, len);
+        Ok(Self { file, reader, compressed, len, blocks })
+    }
+
+    /// Returns the blocks of the file.
+    pub(crate) fn blocks(&self) -> &[EtlBlock] {
+        &self.blocks
+    }
+
+    /// Returns a new reader over the entries of the file, starting at the block that may contain
+    /// `key`.
+    ///
+    /// The reader can return entries with keys lower than `key`, which must be skipped.
+    pub(crate) fn reader_from(&self, key: Option<&[u8]>) -> io::Result<EtlFileReader> {
+        // The last block starting before `key`, as an entry equal to `key` can be its last one.
+        let block = key
+            .map(|key| self.blocks.partition_point(|block| block.first_key.as_slice() < key))
+            .and_then(|index| index.checked_sub(1))
+            .and_then(|index| self.blocks.get(index));
+        let (offset, entries_before) =
+            block.map_or((0, 0), |block| (block.offset, block.entries_before));
+
+        let mut file = self.file.reopen()?;
+        file.seek(SeekFrom::Start(offset))?;
+        Ok(EtlFileReader::new(file, self.compressed, self.len - entries_before))
+    }
+
+    /// Read the next entry in the file.
+    ///
+    /// Can return error if it reaches EOF before filling the internal buffers.
+    pub(crate) fn read_next(&mut self) -> io::Result<Option<Entry>> {
+        self.reader.read_next()
+    }
+}
+
+/// Writes a block of entries, returning the number of bytes written.
+fn write_block(w: &mut impl Write, block: &[u8], compressed: bool) -> io::Result<u64> {
+    if compressed {
+        let block = lz4_flex::block::compress_prepend_size(block);
+        w.write_all(&block.len().to_be_bytes())?;
+        w.write_all(&block)?;
+        Ok((block.len() + 8) as u64)
+    } else {
+        w.write_all(block)?;
+        Ok(block.len() as u64)
+    }
+}
+
+/// Reader over the entries of an [`EtlFile`].
+#[derive(Debug)]
+pub(crate) struct EtlFileReader {
+    file: BufReader<File>,
+    /// The decompressed current block, if the file is compressed.
+    block: Option<Cursor<Vec<u8>>>,
+    /// Number of entries left to read.
+    remaining: usize,
+}
+
+impl EtlFileReader {
+    fn new(file: File, compressed: bool, remaining: usize) -> Self {
+        Self {
+            file: BufReader::new(file),
+            block: compressed.then(|| Cursor::new(Vec::new())),
+            remaining,
+        }
+    }
+
+    /// Read the next entry in the file.
+    ///
+    /// Can return error if it reaches EOF before filling the internal buffers.
+    pub(crate) fn read_next(&mut self) -> io::Result<Option<Entry>> {
+        if self.remaining == 0 {
+            return Ok(None)
+        }
+
+        let mut buffer_key_length = [0; 8];
+        let mut buffer_value_length = [0; 8];
+
+        self.read_exact(&mut buffer_key_length)?;
+        self.read_exact(&mut buffer_value_length)?;
+
+        let key_length = usize::from_be_bytes(buffer_key_length);
+        let value_length = usize::from_be_bytes(buffer_value_length);
+        let mut key = vec![0; key_length];
+        let mut value = vec![0; value_length];
+
+        self.read_exact(&mut key)?;
+        self.read_exact(&mut value)?;
+
+        self.remaining -= 1;
+
+        Ok(Some((key, value)))
+    }
+
+    /// Reads the exact number of bytes required to fill `buf`, decompressing the next block if the
+    /// current one is exhausted.
+    ///
+    /// Entries never span across blocks.
+    fn read_exact(&mut self, buf: &mut [u8]) -> io::Result<()> {
+        let Some(block) = &mut self.block else { return self.file.read_exact(buf) };
+
+        if block.position() == block.get_ref().len() as u64 {
+            let mut block_length = [0; 8];
+            self.file.read_exact(&mut block_length)?;
+            let mut compressed = vec![0; usize::from_be_bytes(block_length)];
+            self.file.read_exact(&mut compressed)?;
+
+            let decompressed = lz4_flex::block::decompress_size_prepended(&compressed)
+                .map_err(|err| io::Error::new(io::ErrorKind::InvalidData, err.to_string()))?;
+            *block = Cursor::new(decompressed);
+        }
+
+        block.read_exact(buf)
+    }
+}
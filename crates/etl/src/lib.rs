@@ -16,14 +16,29 @@
 
 use std::{
     cmp::Reverse,
-    collections::BinaryHeap,
-    io::{self, BufReader, BufWriter, Read, Seek, SeekFrom, Write},
-    path::{Path, PathBuf},
+    collections::{BinaryHeap, VecDeque},
+    io,
+    marker::PhantomData,
+    path::PathBuf,
+    sync::{
+        mpsc::{self, Receiver, SyncSender},
+        Mutex, PoisonError,
+    },
+    thread::{self, JoinHandle},
 };
 
-use rayon::prelude::*;
+use file::{Entry, EtlFile, EtlFileReader};
+use rayon::{prelude::*, ThreadPool, ThreadPoolBuilder};
 use reth_db_api::table::{Compress, Encode, Key, Value};
-use tempfile::{NamedTempFile, TempDir};
+use tempfile::TempDir;
+
+mod file;
+
+/// Number of entries sent at once by the thread merging the range of an [`EtlPartitionIter`].
+const PARTITION_BATCH_SIZE: usize = 1024;
+
+/// Number of batches the thread merging the range of an [`EtlPartitionIter`] merges ahead.
+const PARTITION_BATCHES_AHEAD: usize = 4;
 
 /// An ETL (extract, transform, load) data collector.
 ///
@@ -54,12 +69,21 @@ where
     buffer: Vec<(<K as Encode>::Encoded, <V as Compress>::Compressed)>,
     /// Total number of elements in the collector, including all files
     len: usize,
+    /// Maximum number of buffers sorted and flushed to disk in the background at once
+    workers: usize,
+    /// Whether the ETL files are compressed
+    compression: bool,
+    /// Pool of `workers` threads sorting and flushing buffers to disk, created on the first
+    /// background flush
+    flush_pool: Option<ThreadPool>,
+    /// Results of the background flushes, oldest first
+    pending_files: VecDeque<PendingFile>,
 }
 
 impl<K, V> Collector<K, V>
 where
-    K: Key,
-    V: Value,
+    K: Key + 'static,
+    V: Value + 'static,
 {
     /// Create a new collector with some capacity.
     ///
@@ -73,9 +97,32 @@ where
             buffer_capacity_bytes,
             buffer: Vec::new(),
             len: 0,
+            workers: 0,
+            compression: false,
+            flush_pool: None,
+            pending_files: VecDeque::new(),
         }
     }
 
+    /// Sets the number of buffers that can be sorted and flushed to disk by a pool of as many
+    /// background threads at once, while new entries are inserted.
+    ///
+    /// The buffer capacity is split between the buffer being filled and the ones being flushed, so
+    /// the collector holds at most `buffer_capacity_bytes` in memory regardless of the number of
+    /// workers. With `0` workers, buffers are flushed by the inserting thread.
+    pub fn with_workers(mut self, workers: usize) -> Self {
+        self.workers = workers;
+        self
+    }
+
+    /// Sets whether the ETL files are compressed with LZ4.
+    ///
+    /// Compression lowers the temporary storage usage at the cost of CPU time.
+    pub const fn with_compression(mut self, compression: bool) -> Self {
+        self.compression = compression;
+        self
+    }
+
     /// Returns number of elements currently in the collector.
     pub fn len(&self) -> usize {
         self.len
@@ -88,6 +135,10 @@ where
 
     /// Clears the collector, removing all data, including the temporary directory.
     pub fn clear(&mut self) {
+        // Background flushes write to the temporary directory, so they need to finish first
+        for pending_file in self.pending_files.drain(..) {
+            let _ = wait_pending_file(pending_file);
+        }
         self.dir = None;
         // Clear vectors and free the allocated memory
         self.files = Vec::new();
@@ -102,7 +153,7 @@ where
         let value = value.compress();
         self.buffer_size_bytes += key.as_ref().len() + value.as_ref().len();
         self.buffer.push((key, value));
-        if self.buffer_size_bytes > self.buffer_capacity_bytes {
+        if self.buffer_size_bytes > self.buffer_capacity_bytes / (self.workers + 1) {
             self.flush()?;
         }
         self.len += 1;
@@ -129,12 +180,62 @@ where
 
     fn flush(&mut self) -> io::Result<()> {
         self.buffer_size_bytes = 0;
-        self.buffer.par_sort_unstable_by(|a, b| a.0.cmp(&b.0));
         let mut buf = Vec::with_capacity(self.buffer.len());
         std::mem::swap(&mut buf, &mut self.buffer);
 
         let path = self.dir()?.path().to_path_buf();
-        self.files.push(EtlFile::new(path.as_path(), buf)?);
+        let compression = self.compression;
+        let flush = move || {
+            buf.par_sort_unstable_by(|a, b| a.0.cmp(&b.0));
+            EtlFile::new(path.as_path(), buf, compression)
+        };
+
+        if self.workers == 0 {
+            self.files.push(flush()?);
+            return Ok(())
+        }
+
+        // Waits for the oldest flush if all workers are busy
+        if self.pending_files.len() >= self.workers {
+            self.join_pending_file()?;
+        }
+        let flush_pool = match self.flush_pool.take() {
+            Some(flush_pool) => flush_pool,
+            None => ThreadPoolBuilder::new()
+                .num_threads(self.workers)
+                .thread_name(|i| format!("etl-flush-{i}"))
+                // A panicking flush drops its sender, which is reported by `join_pending_file`
+                .panic_handler(|_| {})
+                .build()
+                .map_err(io::Error::other)?,
+        };
+        let (sender, receiver) = mpsc::sync_channel(1);
+        flush_pool.spawn(move || {
+            let _ = sender.send(flush());
+        });
+        self.flush_pool = Some(flush_pool);
+        self.pending_files.push_back(Mutex::new(receiver));
+
+        Ok(())
+    }
+
+    /// Waits for the oldest background flush, and adds its file to the collector.
+    fn join_pending_file(&mut self) -> io::Result<()> {
+        if let Some(pending_file) = self.pending_files.pop_front() {
+            self.files.push(wait_pending_file(pending_file)?);
+        }
+
+        Ok(())
+    }
+
+    /// Flushes the remaining items to disk, and waits for all background flushes.
+    fn finish_flushes(&mut self) -> io::Result<()> {
+        if self.buffer_size_bytes > 0 {
+            self.flush()?;
+        }
+        while !self.pending_files.is_empty() {
+            self.join_pending_file()?;
+        }
 
         Ok(())
     }
@@ -148,10 +249,7 @@ where
     /// The keys and values have been pre-encoded, meaning they *SHOULD NOT* be encoded or
     /// compressed again.
     pub fn iter(&mut self) -> std::io::Result<EtlIter<'_>> {
-        // Flush the remaining items to disk
-        if self.buffer_size_bytes > 0 {
-            self.flush()?;
-        }
+        self.finish_flushes()?;
 
         let mut heap = BinaryHeap::new();
         for (current_id, file) in self.files.iter_mut().enumerate() {
@@ -162,6 +260,86 @@ where
 
         Ok(EtlIter { heap, files: &mut self.files })
     }
+
+    /// Returns up to `partitions` iterators over consecutive key ranges of the collector data.
+    ///
+    /// The range of every iterator is merged across all underlying files by its own thread, a few
+    /// batches ahead of the iteration, so the ranges are only merged in parallel if the iterators
+    /// are consumed concurrently. The items of each iterator are sorted, and chaining the
+    /// iterators yields the same items as [`Collector::iter`]. Fewer iterators are returned if the
+    /// data can't be split into `partitions` ranges, and none if the collector is empty.
+    ///
+    /// # Note
+    ///
+    /// The keys and values have been pre-encoded, meaning they *SHOULD NOT* be encoded or
+    /// compressed again.
+    pub fn iter_partitions(&mut self, partitions: usize) -> io::Result<Vec<EtlPartitionIter<'_>>> {
+        self.finish_flushes()?;
+
+        // Splits the keys at the quantiles of the first keys of all file blocks
+        let mut keys = self
+            .files
+            .iter()
+            .flat_map(|file| file.blocks().iter().map(|block| block.first_key.as_slice()))
+            .collect::<Vec<_>>();
+        keys.sort_unstable();
+        let Some(&first_key) = keys.first() else { return Ok(Vec::new()) };
+        let partitions = partitions.max(1);
+        let mut bounds = (1..partitions)
+            .map(|partition| keys[partition * keys.len() / partitions])
+            .filter(|key| *key > first_key)
+            .collect::<Vec<_>>();
+        bounds.dedup();
+
+        let lower_bounds = std::iter::once(None).chain(bounds.iter().copied().map(Some));
+        let upper_bounds = bounds.iter().copied().map(Some).chain(std::iter::once(None));
+        lower_bounds
+            .zip(upper_bounds)
+            .enumerate()
+            .map(|(partition, (lower, upper))| {
+                let readers = self
+                    .files
+                    .iter()
+                    .map(|file| file.reader_from(lower))
+                    .collect::<io::Result<Vec<_>>>()?;
+                EtlPartitionIter::new(
+                    partition,
+                    readers,
+                    lower.map(<[u8]>::to_vec),
+                    upper.map(<[u8]>::to_vec),
+                )
+            })
+            .collect()
+    }
+}
+
+impl<K, V> Drop for Collector<K, V>
+where
+    K: Encode + Ord,
+    V: Compress,
+{
+    fn drop(&mut self) {
+        // Background flushes write to the temporary directory, so they need to finish before it's
+        // removed
+        for pending_file in self.pending_files.drain(..) {
+            let _ = wait_pending_file(pending_file);
+        }
+    }
+}
+
+/// Receiver of the file of a background flush.
+///
+/// The receiver is only accessed through a mutable reference to the [`Collector`], the mutex keeps
+/// the collector [`Sync`].
+type PendingFile = Mutex<Receiver<io::Result<EtlFile>>>;
+
+/// Waits for a background flush, and returns its file.
+fn wait_pending_file(pending_file: PendingFile) -> io::Result<EtlFile> {
+    pending_file
+        .into_inner()
+        .unwrap_or_else(PoisonError::into_inner)
+        .recv()
+        .map_err(|_| io::Error::other("ETL flush worker panicked"))?
 }
 
 /// Type alias for the items stored in the heap of [`EtlIter`].
@@ -213,67 +391,136 @@ impl<'a> Iterator for EtlIter<'a> {
     }
 }
 
-/// A temporary ETL file.
+/// `EtlPartitionIter` is an iterator over the sorted key-value pairs of a key range of the ETL
+/// files created by a [`Collector`]. It's created with [`Collector::iter_partitions`].
+///
+/// The key-value pairs are merged by a dedicated thread, and sent to the iterator in batches. The
+/// thread stops once the iterator is dropped.
 #[derive(Debug)]
-struct EtlFile {
-    file: BufReader<NamedTempFile>,
-    len: usize,
+pub struct EtlPartitionIter<'a> {
+    /// Batches of merged key-value pairs, or the error that stopped the merge.
+    receiver: Option<Receiver<io::Result<Vec<Entry>>>>,
+    /// Current batch of key-value pairs.
+    batch: std::vec::IntoIter<Entry>,
+    /// Thread merging the key-value pairs.
+    handle: Option<JoinHandle<()>>,
+    /// The merge thread reads the ETL files of the collector.
+    _files: PhantomData<&'a mut Vec<EtlFile>>,
 }
 
-impl EtlFile {
-    /// Create a new file with the given data (which should be pre-sorted) at the given path.
-    ///
-    /// The file will be a temporary file.
-    pub(crate) fn new<K, V>(dir: &Path, buffer: Vec<(K, V)>) -> std::io::Result<Self>
-    where
-        Self: Sized,
-        K: AsRef<[u8]>,
-        V: AsRef<[u8]>,
-    {
-        let file = NamedTempFile::new_in(dir)?;
-        let mut w = BufWriter::new(file);
-        for entry in &buffer {
-            let k = entry.0.as_ref();
-            let v = entry.1.as_ref();
-
-            w.write_all(&k.len().to_be_bytes())?;
-            w.write_all(&v.len().to_be_bytes())?;
-            w.write_all(k)?;
-            w.write_all(v)?;
-        }
-
-        let mut file = BufReader::new(w.into_inner()?);
-        file.seek(SeekFrom::Start(0))?;
-        let len = buffer.len();
-        Ok(Self { file, len })
+impl<'a> EtlPartitionIter<'a> {
+    /// Spawns the thread merging the entries of `readers` with keys in `lower..upper`.
+    fn new(
+        partition: usize,
+        readers: Vec<EtlFileReader>,
+        lower: Option<Vec<u8>>,
+        upper: Option<Vec<u8>>,
+    ) -> io::Result<Self> {
+        let (sender, receiver) = mpsc::sync_channel(PARTITION_BATCHES_AHEAD);
+        let handle =
+            thread::Builder::new().name(format!("etl-merge-{partition}")).spawn(move || {
+                if let Err(err) = merge_partition(readers, lower, upper, &sender) {
+                    let _ = sender.send(Err(err));
+                }
+            })?;
+
+        Ok(Self {
+            receiver: Some(receiver),
+            batch: Vec::new().into_iter(),
+            handle: Some(handle),
+            _files: PhantomData,
+        })
     }
+}
 
-    /// Read the next entry in the file.
-    ///
-    /// Can return error if it reaches EOF before filling the internal buffers.
-    pub(crate) fn read_next(&mut self) -> std::io::Result<Option<(Vec<u8>, Vec<u8>)>> {
-        if self.len == 0 {
-            return Ok(None)
-        }
+impl<'a> Iterator for EtlPartitionIter<'a> {
+    type Item = io::Result<(Vec<u8>, Vec<u8>)>;
 
-        let mut buffer_key_length = [0; 8];
-        let mut buffer_value_length = [0; 8];
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            if let Some(entry) = self.batch.next() {
+                return Some(Ok(entry))
+            }
 
-        self.file.read_exact(&mut buffer_key_length)?;
-        self.file.read_exact(&mut buffer_value_length)?;
+            match self.receiver.as_ref()?.recv() {
+                Ok(Ok(batch)) => self.batch = batch.into_iter(),
+                Ok(Err(err)) => {
+                    self.receiver = None;
+                    return Some(Err(err))
+                }
+                // The merge thread is done
+                Err(_) => {
+                    self.receiver = None;
+                    let handle = self.handle.take()?;
+                    return handle
+                        .join()
+                        .is_err()
+                        .then(|| Err(io::Error::other("ETL merge thread panicked")))
+                }
+            }
+        }
+    }
+}
 
-        let key_length = usize::from_be_bytes(buffer_key_length);
-        let value_length = usize::from_be_bytes(buffer_value_length);
-        let mut key = vec![0; key_length];
-        let mut value = vec![0; value_length];
+impl<'a> Drop for EtlPartitionIter<'a> {
+    fn drop(&mut self) {
+        // Dropping the receiver stops the merge thread, which reads the ETL files of the collector
+        // that can be removed once the iterator is dropped
+        self.receiver = None;
+        if let Some(handle) = self.handle.take() {
+            let _ = handle.join();
+        }
+    }
+}
 
-        self.file.read_exact(&mut key)?;
-        self.file.read_exact(&mut value)?;
+/// Merges the entries of `readers` with keys in `lower..upper`, and sends them to `sender` in
+/// batches.
+///
+/// Returns early if the receiver is dropped.
+fn merge_partition(
+    mut readers: Vec<EtlFileReader>,
+    lower: Option<Vec<u8>>,
+    upper: Option<Vec<u8>>,
+    sender: &SyncSender<io::Result<Vec<Entry>>>,
+) -> io::Result<()> {
+    let in_range = |key: &[u8]| !matches!(&upper, Some(upper) if key >= upper.as_slice());
+
+    let mut heap = BinaryHeap::new();
+    for (id, reader) in readers.iter_mut().enumerate() {
+        // Readers start at the beginning of a block, which can precede the lower bound
+        while let Some(entry) = reader.read_next()? {
+            if matches!(&lower, Some(lower) if entry.0 < *lower) {
+                continue
+            }
+            if in_range(&entry.0) {
+                heap.push((Reverse(entry), id));
+            }
+            break
+        }
+    }
 
-        self.len -= 1;
+    let mut batch = Vec::with_capacity(PARTITION_BATCH_SIZE);
+    while let Some((Reverse(entry), id)) = heap.pop() {
+        if let Some(next) = readers[id].read_next()? {
+            if in_range(&next.0) {
+                heap.push((Reverse(next), id));
+            }
+        }
 
-        Ok(Some((key, value)))
+        batch.push(entry);
+        if batch.len() == PARTITION_BATCH_SIZE {
+            let batch = std::mem::replace(&mut batch, Vec::with_capacity(PARTITION_BATCH_SIZE));
+            if sender.send(Ok(batch)).is_err() {
+                return Ok(())
+            }
+        }
     }
+
+    if !batch.is_empty() {
+        let _ = sender.send(Ok(batch));
+    }
+
+    Ok(())
 }
 
 #[cfg(test)]
@@ -314,4 +561,56 @@ mod tests {
         assert!(collector.is_empty());
         assert!(!temp_dir_path.exists());
     }
+
+    #[test]
+    fn etl_partitions() {
+        let mut entries: Vec<_> =
+            (0..10_000).map(|id| (TxHash::random(), id as TxNumber)).collect();
+        // Duplicate keys end up in the same partition
+        let duplicates: Vec<_> =
+            entries[..1_000].iter().map(|(hash, id)| (*hash, id + 10_000)).collect();
+        entries.extend(duplicates);
+
+        for (workers, compression) in [(0, false), (0, true), (3, false), (3, true)] {
+            let mut collector =
+                Collector::new(64 * 1024, None).with_workers(workers).with_compression(compression);
+            for (k, v) in entries.clone() {
+                collector.insert(k, v).unwrap();
+            }
+            assert_eq!(collector.len(), entries.len());
+
+            let expected = collector.iter().unwrap().collect::<io::Result<Vec<_>>>().unwrap();
+            assert_eq!(expected.len(), entries.len());
+            assert!(expected.windows(2).all(|pair| pair[0].0 <= pair[1].0));
+
+            for partitions in [1, 4, 100] {
+                let iters = collector.iter_partitions(partitions).unwrap();
+                assert!(iters.len() > 1 || partitions == 1);
+                assert!(iters.len() <= partitions);
+
+                let mut items = Vec::new();
+                let mut last_key = None;
+                for iter in iters {
+                    let partition = iter.collect::<io::Result<Vec<_>>>().unwrap();
+                    assert!(!partition.is_empty());
+                    // Partitions don't overlap
+                    assert!(last_key.as_ref() < partition.first().map(|(key, _)| key));
+                    last_key = partition.last().map(|(key, _)| key.clone());
+                    items.extend(partition);
+                }
+                items.sort_unstable();
+                let mut expected = expected.clone();
+                expected.sort_unstable();
+                assert_eq!(items, expected);
+            }
+
+            // Dropping a partition before it's consumed stops its merge
+            let mut iters = collector.iter_partitions(4).unwrap();
+            assert!(iters[0].next().is_some());
+            drop(iters);
+
+            collector.clear();
+            assert!(collector.iter_partitions(4).unwrap().is_empty());
+        }
+    }
 }
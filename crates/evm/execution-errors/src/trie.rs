@@ -1,6 +1,6 @@
 //! Errors when computing the state root.
 
-use alloy_primitives::B256;
+use alloy_primitives::{Bytes, B256};
 use nybbles::Nibbles;
 use reth_storage_errors::{db::DatabaseError, provider::ProviderError};
 use thiserror_no_std::Error;
@@ -86,3 +86,30 @@ impl From<TrieWitnessError> for ProviderError {
         Self::TrieWitnessError(value.to_string())
     }
 }
+
+/// Sparse trie errors.
+#[derive(Error, Debug, PartialEq, Eq, Clone)]
+pub enum SparseTrieError {
+    /// Sparse trie is still blind. Thrown on attempt to update it.
+    #[error("sparse trie is blind")]
+    Blind,
+    /// Encountered a blinded node while traversing the trie.
+    #[error("encountered blinded node at {path:?}: {hash}")]
+    BlindedNode {
+        /// Blinded node path.
+        path: Nibbles,
+        /// Blinded node hash.
+        hash: B256,
+    },
+    /// Encountered a node that can't be revealed at the path.
+    #[error("encountered invalid node at {path:?} when revealing: {node}")]
+    InvalidNode {
+        /// Node path.
+        path: Nibbles,
+        /// RLP encoded node.
+        node: Bytes,
+    },
+    /// Encountered a missing node of a revealed parent.
+    #[error("node at {0:?} is missing")]
+    MissingNode(Nibbles),
+}
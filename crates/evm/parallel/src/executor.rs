@@ -0,0 +1,104 @@
+//! Parallel execution of the transactions of a block.
+
+use crate::{
+    state::{serve_state_requests, StateReader},
+    worker::{lock_mutex_in_array, WorkerExecutor},
+};
+use reth_evm::{execute::ProviderError, ConfigureEvm};
+use revm::Database;
+use revm_primitives::{EVMError, EnvWithHandlerCfg, ResultAndState, SpecId, TxEnv};
+use std::{num::NonZeroUsize, sync::mpsc, thread};
+
+/// Configuration of the [`ParallelExecutor`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ParallelExecutionConfig {
+    /// The number of worker threads that execute transactions.
+    pub workers: NonZeroUsize,
+}
+
+impl Default for ParallelExecutionConfig {
+    /// Uses one worker per available core.
+    fn default() -> Self {
+        Self { workers: thread::available_parallelism().unwrap_or(NonZeroUsize::MIN) }
+    }
+}
+
+/// Executes the transactions of a block in parallel, Block-STM style.
+///
+/// Transactions are executed optimistically on a multi-version state that tracks the writes of
+/// every transaction. The values a transaction read are validated against the writes of all lower
+/// transactions, and the transaction is re-executed if any of them changed. Transactions are
+/// committed in block order, so the results are identical to sequential execution.
+///
+/// The executor is chain-agnostic: the EVM is built with the given [`ConfigureEvm`], and the
+/// caller is responsible for any pre- and post-execution changes and for committing the returned
+/// state changes.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct ParallelExecutor {
+    config: ParallelExecutionConfig,
+}
+
+impl ParallelExecutor {
+    /// Creates a new parallel executor.
+    pub const fn new(config: ParallelExecutionConfig) -> Self {
+        Self { config }
+    }
+
+    /// Returns the configuration of the executor.
+    pub const fn config(&self) -> &ParallelExecutionConfig {
+        &self.config
+    }
+
+    /// Executes the given transactions on top of the given database and returns their results in
+    /// order.
+    ///
+    /// The database is not modified and only accessed from the calling thread. The returned state
+    /// changes must be committed in order to obtain the post-execution state.
+    ///
+    /// If a transaction fails, its error is the last returned result and the transactions after
+    /// it are not included.
+    pub fn execute<EvmConfig, DB>(
+        &self,
+        evm_config: &EvmConfig,
+        db: &mut DB,
+        env: &EnvWithHandlerCfg,
+        txs: &[TxEnv],
+    ) -> Vec<Result<ResultAndState, EVMError<ProviderError>>>
+    where
+        EvmConfig: ConfigureEvm,
+        DB: Database<Error: Into<ProviderError>>,
+    {
+        if txs.is_empty() {
+            return Vec::new()
+        }
+
+        let state_clear = env.spec_id() >= SpecId::SPURIOUS_DRAGON;
+        let executor = WorkerExecutor::new(evm_config, env, txs, state_clear);
+        let workers = self.config.workers.get().min(txs.len());
+
+        let (requests_tx, requests_rx) = mpsc::channel();
+        thread::scope(|scope| {
+            for _ in 0..workers {
+                let reader = StateReader::new(requests_tx.clone());
+                let executor = &executor;
+                scope.spawn(move || executor.run(&reader));
+            }
+            // The server stops once all workers are done and dropped their readers.
+            drop(requests_tx);
+            serve_state_requests(db, requests_rx);
+        });
+
+        // If the scheduler was halted, the failed transaction is the one after the last
+        // committed transaction.
+        let n_committed_txs = executor.scheduler.get_n_committed_txs();
+        let n_results = (n_committed_txs + 1).min(txs.len());
+        (0..n_results)
+            .map(|tx_index| {
+                lock_mutex_in_array(&executor.execution_outputs, tx_index)
+                    .take()
+                    .expect("Output must be ready.")
+                    .result
+            })
+            .collect()
+    }
+}
@@ -0,0 +1,28 @@
+//! Parallel execution of the transactions of a block.
+//!
+//! This implements a Block-STM style engine: transactions are executed optimistically on worker
+//! threads against a multi-version state, validated against the writes of lower transactions and
+//! re-executed on conflicts, see [`ParallelExecutor`].
+//!
+//! The engine is generic over [`ConfigureEvm`](reth_evm::ConfigureEvm), so it can be used by the
+//! block executors of any chain.
+
+#![doc(
+    html_logo_url = "https://raw.githubusercontent.com/paradigmxyz/reth/main/assets/reth-docs.png",
+    html_favicon_url = "https://avatars0.githubusercontent.com/u/97369466?s=256",
+    issue_tracker_base_url = "https://github.com/paradigmxyz/reth/issues/"
+)]
+#![cfg_attr(not(test), warn(unused_crate_dependencies))]
+#![cfg_attr(docsrs, feature(doc_cfg, doc_auto_cfg))]
+
+mod executor;
+pub use executor::{ParallelExecutionConfig, ParallelExecutor};
+
+mod scheduler;
+mod state;
+mod versioned_state;
+mod versioned_storage;
+mod worker;
+
+/// The index of a transaction in the block.
+pub(crate) type TxIndex = usize;
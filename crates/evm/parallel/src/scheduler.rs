@@ -1,26 +1,32 @@
-use std::cmp::min;
-use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
-use std::sync::{Mutex, MutexGuard, TryLockError};
-use crate::executor::lock_mutex_in_array;
-use crate::TxIndex;
-
-
-pub struct TransactionCommitter<'a> {
+//! Hands out execution and validation tasks to the worker threads.
+
+use crate::{worker::lock_mutex_in_array, TxIndex};
+use std::{
+    cmp::min,
+    sync::{
+        atomic::{AtomicBool, AtomicUsize, Ordering},
+        Mutex, MutexGuard, TryLockError,
+    },
+};
+
+/// Commits transactions in order, see [`Scheduler::try_enter_commit_phase`].
+#[derive(Debug)]
+pub(crate) struct TransactionCommitter<'a> {
     scheduler: &'a Scheduler,
     commit_index_guard: MutexGuard<'a, usize>,
 }
 
 impl<'a> TransactionCommitter<'a> {
-    pub fn new(scheduler: &'a Scheduler, commit_index_guard: MutexGuard<'a, usize>) -> Self {
+    const fn new(scheduler: &'a Scheduler, commit_index_guard: MutexGuard<'a, usize>) -> Self {
         Self { scheduler, commit_index_guard }
     }
 
     /// Tries to commit the next uncommitted transaction in the chunk. Returns the index of the
     /// transaction to commit if successful, or None if the transaction is not yet executed.
-    pub fn try_commit(&mut self) -> Option<usize> {
+    pub(crate) fn try_commit(&mut self) -> Option<TxIndex> {
         if self.scheduler.done() {
-            return None;
-        };
+            return None
+        }
         assert!(
             *self.commit_index_guard < self.scheduler.chunk_size,
             "The commit index must be less than the chunk size, since the scheduler is not done."
@@ -28,7 +34,7 @@ impl<'a> TransactionCommitter<'a> {
 
         let mut status = self.scheduler.lock_tx_status(*self.commit_index_guard);
         if *status != TransactionStatus::Executed {
-            return None;
+            return None
         }
         *status = TransactionStatus::Committed;
         *self.commit_index_guard += 1;
@@ -40,7 +46,7 @@ impl<'a> TransactionCommitter<'a> {
 
     /// Halts the scheduler. Decrements the commit index to indicate that the final transaction to
     /// commit has been excluded from the block.
-    pub fn halt_scheduler(&mut self) {
+    pub(crate) fn halt_scheduler(&mut self) {
         assert!(*self.commit_index_guard > 0, "Commit index underflow.");
         *self.commit_index_guard -= 1;
 
@@ -48,39 +54,48 @@ impl<'a> TransactionCommitter<'a> {
     }
 }
 
-
-#[derive(Clone, Copy, Debug, PartialEq)]
-pub enum TransactionStatus {
+/// The status of a transaction in the [`Scheduler`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub(crate) enum TransactionStatus {
     ReadyToExecute,
     Executing,
     Executed,
     Aborting,
     Committed,
 }
-pub struct Scheduler {
+
+/// A task for a worker thread.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub(crate) enum Task {
+    Execution(TxIndex),
+    Validation(TxIndex),
+    Ask,
+    NoneAvailable,
+    Done,
+}
+
+/// Schedules the execution and validation of a chunk of transactions.
+///
+/// Transactions are executed optimistically and validated against the writes of lower
+/// transactions. A transaction whose reads became stale is aborted and re-executed. Transactions
+/// are committed strictly in order, see [`TransactionCommitter`].
+#[derive(Debug)]
+pub(crate) struct Scheduler {
     execution_index: AtomicUsize,
     validation_index: AtomicUsize,
-    // The index of the next transaction to commit.
+    /// The index of the next transaction to commit.
     commit_index: Mutex<usize>,
     chunk_size: usize,
     tx_statuses: Box<[Mutex<TransactionStatus>]>,
-    // Set to true when all transactions have been committed, or when calling the halt_scheduler
-    // procedure, providing a cheap way for all threads to exit their main loops.
+    /// Set to true when all transactions have been committed, or when calling the `halt_scheduler`
+    /// procedure, providing a cheap way for all threads to exit their main loops.
     done_marker: AtomicBool,
 }
 
-pub enum Task {
-    ExecutionTask(TxIndex),
-    ValidationTask(TxIndex),
-    AskForTask,
-    NoTaskAvailable,
-    Done,
-}
-
-
 impl Scheduler {
-    pub fn new(chunk_size: usize) -> Scheduler {
-        Scheduler {
+    /// Creates a new scheduler for `chunk_size` transactions.
+    pub(crate) fn new(chunk_size: usize) -> Self {
+        Self {
             execution_index: AtomicUsize::new(0),
             validation_index: AtomicUsize::new(chunk_size),
             commit_index: Mutex::new(0),
@@ -88,48 +103,49 @@ impl Scheduler {
             tx_statuses: std::iter::repeat_with(|| Mutex::new(TransactionStatus::ReadyToExecute))
                 .take(chunk_size)
                 .collect(),
-            done_marker: AtomicBool::new(false),
+            done_marker: AtomicBool::new(chunk_size == 0),
         }
     }
 
-    pub fn next_task(&self) -> Task {
+    /// Returns the next task to work on.
+    pub(crate) fn next_task(&self) -> Task {
         if self.done() {
-            return Task::Done;
+            return Task::Done
         }
 
         let index_to_validate = self.validation_index.load(Ordering::Acquire);
         let index_to_execute = self.execution_index.load(Ordering::Acquire);
-       // println!("sglk validate index {}, execute index {}",index_to_validate,index_to_execute);
         if min(index_to_validate, index_to_execute) >= self.chunk_size {
-            return Task::NoTaskAvailable;
+            return Task::NoneAvailable
         }
 
         if index_to_validate < index_to_execute {
             if let Some(tx_index) = self.next_version_to_validate() {
-                return Task::ValidationTask(tx_index);
+                return Task::Validation(tx_index)
             }
         }
 
         if let Some(tx_index) = self.next_version_to_execute() {
-            return Task::ExecutionTask(tx_index);
+            return Task::Execution(tx_index)
         }
 
-        Task::AskForTask
+        Task::Ask
     }
 
     /// Updates the Scheduler that an execution task has been finished and triggers the creation of
     /// new tasks accordingly: schedules validation for the current and higher transactions, if not
     /// already scheduled.
-    pub fn finish_execution(&self, tx_index: TxIndex) {
+    pub(crate) fn finish_execution(&self, tx_index: TxIndex) {
         self.set_executed_status(tx_index);
         self.decrease_validation_index(tx_index);
     }
 
-    pub fn try_validation_abort(&self, tx_index: TxIndex) -> bool {
+    /// Tries to abort the executed transaction, returns `true` if it was aborted.
+    pub(crate) fn try_validation_abort(&self, tx_index: TxIndex) -> bool {
         let mut status = self.lock_tx_status(tx_index);
         if *status == TransactionStatus::Executed {
             *status = TransactionStatus::Aborting;
-            return true;
+            return true
         }
         false
     }
@@ -137,25 +153,25 @@ impl Scheduler {
     /// Updates the Scheduler that a validation task has aborted and triggers the creation of new
     /// tasks: schedules validation for higher transactions + re-executes the current transaction
     /// (if ready).
-    pub fn finish_abort(&self, tx_index: TxIndex) -> Task {
+    pub(crate) fn finish_abort(&self, tx_index: TxIndex) -> Task {
         self.set_ready_status(tx_index);
         if self.execution_index.load(Ordering::Acquire) > tx_index && self.try_incarnate(tx_index) {
-            Task::ExecutionTask(tx_index)
+            Task::Execution(tx_index)
         } else {
-            Task::AskForTask
+            Task::Ask
         }
     }
 
     /// This method is called after a transaction gets re-executed during a commit. It decreases the
     /// validation index to ensure that higher transactions are validated. There is no need to set
     /// the transaction status to Executed, as it is already set to Committed.
-    pub fn finish_execution_during_commit(&self, tx_index: TxIndex) {
+    pub(crate) fn finish_execution_during_commit(&self, tx_index: TxIndex) {
         self.decrease_validation_index(tx_index + 1);
     }
 
-    /// Tries to takes the lock on the commit index. Returns a `TransactionCommitter` if successful,
-    /// or None if the lock is already taken.
-    pub fn try_enter_commit_phase(&self) -> Option<TransactionCommitter<'_>> {
+    /// Tries to takes the lock on the commit index. Returns a [`TransactionCommitter`] if
+    /// successful, or None if the lock is already taken.
+    pub(crate) fn try_enter_commit_phase(&self) -> Option<TransactionCommitter<'_>> {
         match self.commit_index.try_lock() {
             Ok(guard) => Some(TransactionCommitter::new(self, guard)),
             Err(TryLockError::WouldBlock) => None,
@@ -165,11 +181,13 @@ impl Scheduler {
         }
     }
 
-    pub fn get_n_committed_txs(&self) -> usize {
-        *self.commit_index.lock().unwrap()
+    /// Returns the number of committed transactions.
+    pub(crate) fn get_n_committed_txs(&self) -> usize {
+        *self.commit_index.lock().expect("Commit index is poisoned.")
     }
 
-    pub fn halt(&self) {
+    /// Stops handing out tasks.
+    pub(crate) fn halt(&self) {
         self.done_marker.store(true, Ordering::Release);
     }
 
@@ -179,7 +197,6 @@ impl Scheduler {
 
     fn set_executed_status(&self, tx_index: TxIndex) {
         let mut status = self.lock_tx_status(tx_index);
-        println!("sglk tx status {} is {:?}",tx_index, status);
         assert_eq!(
             *status,
             TransactionStatus::Executing,
@@ -201,7 +218,6 @@ impl Scheduler {
     }
 
     fn decrease_validation_index(&self, target_index: TxIndex) {
-        println!("sglk decrease validation index {}", target_index);
         self.validation_index.fetch_min(target_index, Ordering::SeqCst);
     }
 
@@ -211,7 +227,7 @@ impl Scheduler {
             let mut status = self.lock_tx_status(tx_index);
             if *status == TransactionStatus::ReadyToExecute {
                 *status = TransactionStatus::Executing;
-                return true;
+                return true
             }
         }
         false
@@ -220,14 +236,13 @@ impl Scheduler {
     fn next_version_to_validate(&self) -> Option<TxIndex> {
         let index_to_validate = self.validation_index.load(Ordering::Acquire);
         if index_to_validate >= self.chunk_size {
-            return None;
+            return None
         }
         let index_to_validate = self.validation_index.fetch_add(1, Ordering::SeqCst);
         if index_to_validate < self.chunk_size {
             let status = self.lock_tx_status(index_to_validate);
-            println!("sglk smaller here {}, status {:?}",index_to_validate,status);
             if *status == TransactionStatus::Executed {
-                return Some(index_to_validate);
+                return Some(index_to_validate)
             }
         }
         None
@@ -236,12 +251,11 @@ impl Scheduler {
     fn next_version_to_execute(&self) -> Option<TxIndex> {
         let index_to_execute = self.execution_index.load(Ordering::Acquire);
         if index_to_execute >= self.chunk_size {
-            return None;
+            return None
         }
         let index_to_execute = self.execution_index.fetch_add(1, Ordering::SeqCst);
-        println!("next version to execute is {}",index_to_execute);
         if self.try_incarnate(index_to_execute) {
-            return Some(index_to_execute);
+            return Some(index_to_execute)
         }
         None
     }
@@ -250,17 +264,61 @@ impl Scheduler {
     fn done(&self) -> bool {
         self.done_marker.load(Ordering::Acquire)
     }
+}
 
-    #[cfg(any(feature = "testing", test))]
-    pub fn set_tx_status(&self, tx_index: TxIndex, status: TransactionStatus) {
-        if tx_index < self.chunk_size {
-            let mut tx_status = self.lock_tx_status(tx_index);
-            *tx_status = status;
-        }
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn empty_chunk_is_done() {
+        let scheduler = Scheduler::new(0);
+        assert_eq!(scheduler.next_task(), Task::Done);
+        assert_eq!(scheduler.get_n_committed_txs(), 0);
+    }
+
+    #[test]
+    fn execute_validate_commit() {
+        let scheduler = Scheduler::new(2);
+        assert_eq!(scheduler.next_task(), Task::Execution(0));
+        assert_eq!(scheduler.next_task(), Task::Execution(1));
+        assert_eq!(scheduler.next_task(), Task::NoneAvailable);
+
+        scheduler.finish_execution(1);
+        assert_eq!(scheduler.next_task(), Task::Validation(1));
+
+        // Transaction 1 read stale values and is re-executed.
+        assert!(scheduler.try_validation_abort(1));
+        assert_eq!(scheduler.finish_abort(1), Task::Execution(1));
+
+        // Nothing can be committed before transaction 0 is executed.
+        assert_eq!(scheduler.try_enter_commit_phase().unwrap().try_commit(), None);
+
+        scheduler.finish_execution(0);
+        scheduler.finish_execution(1);
+        let mut committer = scheduler.try_enter_commit_phase().unwrap();
+        assert!(scheduler.try_enter_commit_phase().is_none());
+        assert_eq!(committer.try_commit(), Some(0));
+        assert_eq!(committer.try_commit(), Some(1));
+        assert_eq!(committer.try_commit(), None);
+        drop(committer);
+
+        assert_eq!(scheduler.next_task(), Task::Done);
+        assert_eq!(scheduler.get_n_committed_txs(), 2);
     }
 
-    #[cfg(any(feature = "testing", test))]
-    pub fn get_tx_status(&self, tx_index: TxIndex) -> MutexGuard<'_, TransactionStatus> {
-        self.lock_tx_status(tx_index)
+    #[test]
+    fn halt_excludes_last_commit() {
+        let scheduler = Scheduler::new(2);
+        assert_eq!(scheduler.next_task(), Task::Execution(0));
+        scheduler.finish_execution(0);
+
+        let mut committer = scheduler.try_enter_commit_phase().unwrap();
+        assert_eq!(committer.try_commit(), Some(0));
+        committer.halt_scheduler();
+        drop(committer);
+
+        assert_eq!(scheduler.next_task(), Task::Done);
+        assert_eq!(scheduler.get_n_committed_txs(), 0);
     }
 }
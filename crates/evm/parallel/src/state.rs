@@ -0,0 +1,124 @@
+//! Access to the pre-execution state from worker threads.
+//!
+//! The database a block is executed on is not required to be [`Send`], so worker threads never
+//! touch it directly. Instead, the thread that drives the parallel execution owns the database and
+//! serves the reads that miss the [`VersionedState`](crate::versioned_state::VersionedState) over
+//! a channel, see [`serve_state_requests`].
+
+use reth_evm::execute::ProviderError;
+use revm_primitives::{db::Database, AccountInfo, Address, Bytecode, B256, U256};
+use std::sync::mpsc::{self, Receiver, Sender};
+
+const STATE_SERVER_ERR: &str = "The state server must outlive the worker threads.";
+
+/// A read of the pre-execution state.
+#[derive(Debug, Clone, Copy)]
+pub(crate) enum StateRequest {
+    /// Requests the account info of an address.
+    Basic(Address),
+    /// Requests the bytecode of a code hash.
+    CodeByHash(B256),
+    /// Requests the value of a storage slot.
+    Storage(Address, U256),
+    /// Requests the hash of a block.
+    BlockHash(u64),
+}
+
+/// The response to a [`StateRequest`].
+#[derive(Debug)]
+pub(crate) enum StateResponse {
+    /// Account info of an address.
+    Basic(Option<AccountInfo>),
+    /// The bytecode of a code hash.
+    CodeByHash(Bytecode),
+    /// The value of a storage slot.
+    Storage(U256),
+    /// The hash of a block.
+    BlockHash(B256),
+}
+
+type StateResult = Result<StateResponse, ProviderError>;
+
+/// A request together with the channel the response is sent on.
+pub(crate) type StateMessage = (StateRequest, Sender<StateResult>);
+
+/// Reads the pre-execution state on behalf of a single worker thread.
+#[derive(Debug)]
+pub(crate) struct StateReader {
+    requests: Sender<StateMessage>,
+    response_tx: Sender<StateResult>,
+    response_rx: Receiver<StateResult>,
+}
+
+impl StateReader {
+    /// Creates a new reader that sends its requests on the given channel.
+    pub(crate) fn new(requests: Sender<StateMessage>) -> Self {
+        let (response_tx, response_rx) = mpsc::channel();
+        Self { requests, response_tx, response_rx }
+    }
+
+    /// Returns the account info of the given address.
+    pub(crate) fn basic(&self, address: Address) -> Result<Option<AccountInfo>, ProviderError> {
+        match self.request(StateRequest::Basic(address))? {
+            StateResponse::Basic(account) => Ok(account),
+            response => unreachable!("unexpected response {response:?}"),
+        }
+    }
+
+    /// Returns the bytecode of the given code hash.
+    pub(crate) fn code_by_hash(&self, code_hash: B256) -> Result<Bytecode, ProviderError> {
+        match self.request(StateRequest::CodeByHash(code_hash))? {
+            StateResponse::CodeByHash(code) => Ok(code),
+            response => unreachable!("unexpected response {response:?}"),
+        }
+    }
+
+    /// Returns the value of the given storage slot.
+    pub(crate) fn storage(&self, address: Address, index: U256) -> Result<U256, ProviderError> {
+        match self.request(StateRequest::Storage(address, index))? {
+            StateResponse::Storage(value) => Ok(value),
+            response => unreachable!("unexpected response {response:?}"),
+        }
+    }
+
+    /// Returns the hash of the given block.
+    pub(crate) fn block_hash(&self, number: u64) -> Result<B256, ProviderError> {
+        match self.request(StateRequest::BlockHash(number))? {
+            StateResponse::BlockHash(hash) => Ok(hash),
+            response => unreachable!("unexpected response {response:?}"),
+        }
+    }
+
+    fn request(&self, request: StateRequest) -> StateResult {
+        self.requests.send((request, self.response_tx.clone())).expect(STATE_SERVER_ERR);
+        self.response_rx.recv().expect(STATE_SERVER_ERR)
+    }
+}
+
+/// Serves the requests of all [`StateReader`]s from the given database.
+///
+/// Returns once every sender of the request channel has been dropped, i.e. once all worker
+/// threads are done.
+pub(crate) fn serve_state_requests<DB>(db: &mut DB, requests: Receiver<StateMessage>)
+where
+    DB: Database<Error: Into<ProviderError>>,
+{
+    while let Ok((request, response_tx)) = requests.recv() {
+        let response = match request {
+            StateRequest::Basic(address) => db.basic(address).map(StateResponse::Basic),
+            StateRequest::CodeByHash(code_hash) => {
+                db.code_by_hash(code_hash).map(StateResponse::CodeByHash)
+            }
+            // Some databases (e.g. `State`) expect the account to be loaded before any of its
+            // storage is accessed, which is not guaranteed if the account was read from the
+            // versioned state by the worker.
+            StateRequest::Storage(address, index) => db
+                .basic(address)
+                .and_then(|_| db.storage(address, index))
+                .map(StateResponse::Storage),
+            StateRequest::BlockHash(number) => db.block_hash(number).map(StateResponse::BlockHash),
+        };
+        // The worker only stops listening once it panicked, which is handled by the caller.
+        let _ = response_tx.send(response.map_err(Into::into));
+    }
+}
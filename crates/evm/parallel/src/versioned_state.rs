@@ -0,0 +1,391 @@
+//! The multi-version state shared by all worker threads.
+
+use crate::{state::StateReader, versioned_storage::VersionedStorage, TxIndex};
+use reth_evm::execute::ProviderError;
+use revm::Database;
+use revm_primitives::{
+    AccountInfo, Address, Bytecode, EvmState, HashMap, B256, KECCAK_EMPTY, U256,
+};
+use std::{
+    collections::BTreeMap,
+    sync::{Mutex, MutexGuard},
+};
+
+/// The values a transaction read, or the values a transaction wrote.
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub(crate) struct StateMaps {
+    /// Account infos, `None` if the account does not exist.
+    pub(crate) accounts: HashMap<Address, Option<AccountInfo>>,
+    /// Storage slot values.
+    pub(crate) storage: HashMap<(Address, U256), U256>,
+    /// Accounts whose storage was cleared, because they were created or destroyed.
+    ///
+    /// Only used for writes.
+    pub(crate) cleared_storage: Vec<Address>,
+    /// The balance of the coinbase the transaction paid its fee on top of, if the coinbase was
+    /// only loaded to pay the fee.
+    ///
+    /// The fee is then deferred instead of writing the coinbase, so that transactions don't
+    /// conflict on it. The transaction only depends on the coinbase existing and being non-empty,
+    /// since the fee is added to it either way. Only used for reads.
+    pub(crate) coinbase_balance: Option<U256>,
+    /// The deferred fee paid to the coinbase, see [`Self::coinbase_balance`].
+    ///
+    /// Only used for writes.
+    pub(crate) coinbase_reward: Option<U256>,
+}
+
+impl StateMaps {
+    /// Extracts the writes of an executed transaction from its resulting [`EvmState`].
+    ///
+    /// This mirrors how [`State`](revm::db::State) applies the [`EvmState`] on commit, so that
+    /// later transactions observe the same values they would see in sequential execution. Writes
+    /// that do not change a value the transaction read are skipped to avoid false conflicts.
+    pub(crate) fn writes_of(
+        state: &EvmState,
+        reads: &Self,
+        coinbase: Address,
+        state_clear: bool,
+    ) -> Self {
+        let mut writes = Self::default();
+        for (address, account) in state {
+            if !account.is_touched() {
+                continue
+            }
+
+            // The coinbase was not accessed other than to pay the fee.
+            if let Some(balance) = reads.coinbase_balance.filter(|_| *address == coinbase) {
+                let reward = account.info.balance - balance;
+                writes.coinbase_reward = Some(reward).filter(|reward| !reward.is_zero());
+                continue
+            }
+
+            let (info, storage_cleared) = if account.is_selfdestructed() {
+                (None, true)
+            } else if account.is_created() {
+                (Some(account.info.clone()), true)
+            } else if account.is_empty() && state_clear {
+                (None, true)
+            } else {
+                (Some(account.info.clone()), false)
+            };
+
+            if storage_cleared || reads.accounts.get(address) != Some(&info) {
+                writes.accounts.insert(*address, info);
+            }
+            if storage_cleared {
+                writes.cleared_storage.push(*address);
+            }
+            // Storage of destroyed accounts is gone, so there is nothing else to write.
+            if account.is_selfdestructed() {
+                continue
+            }
+            for (key, slot) in &account.storage {
+                if slot.is_changed() || (storage_cleared && slot.present_value != U256::ZERO) {
+                    writes.storage.insert((*address, *key), slot.present_value);
+                }
+            }
+        }
+        writes
+    }
+}
+
+/// The state of all transactions of a block, versioned by the index of the transaction that
+/// wrote it.
+#[derive(Debug)]
+pub(crate) struct VersionedState {
+    /// The beneficiary of the block.
+    coinbase: Address,
+    accounts: VersionedStorage<Address, Option<AccountInfo>>,
+    storage: VersionedStorage<(Address, U256), U256>,
+    /// Tracks which transactions cleared the storage of an account.
+    cleared_storage: VersionedStorage<Address, ()>,
+    /// Bytecodes deployed by the transactions. These are keyed by their hash, so they do not need
+    /// to be versioned.
+    contracts: HashMap<B256, Bytecode>,
+    /// The deferred fees paid to the coinbase by each transaction, see
+    /// [`StateMaps::coinbase_balance`].
+    coinbase_rewards: BTreeMap<TxIndex, U256>,
+}
+
+impl VersionedState {
+    /// Creates an empty versioned state for a block with the given beneficiary.
+    pub(crate) fn new(coinbase: Address) -> Self {
+        Self {
+            coinbase,
+            accounts: Default::default(),
+            storage: Default::default(),
+            cleared_storage: Default::default(),
+            contracts: Default::default(),
+            coinbase_rewards: Default::default(),
+        }
+    }
+
+    /// Returns the account info as seen by the transaction at `tx_index`, if it is known without
+    /// reading the pre-execution state.
+    fn account(&self, tx_index: TxIndex, address: Address) -> Option<Option<AccountInfo>> {
+        if address != self.coinbase {
+            return self.accounts.read(tx_index, address)
+        }
+
+        // The deferred fees paid after the latest write are added on top of it.
+        let (rewards_start, account) = match self.accounts.read_write(tx_index, address) {
+            Some((write_index, account)) => (write_index + 1, account),
+            None => (0, self.accounts.initial_value(&address)?),
+        };
+        let reward = self
+            .coinbase_rewards
+            .range(rewards_start..tx_index)
+            .fold(U256::ZERO, |total, (_, reward)| total.saturating_add(*reward));
+        if reward.is_zero() {
+            return Some(account)
+        }
+        let mut account = account.unwrap_or_default();
+        account.balance = account.balance.saturating_add(reward);
+        Some(Some(account))
+    }
+
+    /// Returns the storage value as seen by the transaction at `tx_index`, if it is known without
+    /// reading the pre-execution state.
+    fn storage(&self, tx_index: TxIndex, address: Address, index: U256) -> Option<U256> {
+        let write = self.storage.read_write(tx_index, (address, index));
+        let cleared = self.cleared_storage.read_write(tx_index, address);
+        match (write, cleared) {
+            // A storage write in the same transaction that cleared the storage happened after
+            // the account was created.
+            (Some((write_index, value)), Some((cleared_index, _)))
+                if write_index >= cleared_index =>
+            {
+                Some(value)
+            }
+            (_, Some(_)) => Some(U256::ZERO),
+            (Some((_, value)), None) => Some(value),
+            (None, None) => self.storage.initial_value(&(address, index)),
+        }
+    }
+
+    /// Returns `true` if the given reads of the transaction at `tx_index` still match the
+    /// versioned state.
+    fn validate_reads(&self, tx_index: TxIndex, reads: &StateMaps) -> bool {
+        // The first transaction has no predecessors whose writes could invalidate its reads.
+        if tx_index == 0 {
+            return true
+        }
+        reads
+            .accounts
+            .iter()
+            .all(|(address, expected)| self.account(tx_index, *address).as_ref() == Some(expected)) &&
+            reads.storage.iter().all(|((address, index), expected)| {
+                self.storage(tx_index, *address, *index).as_ref() == Some(expected)
+            }) &&
+            (reads.coinbase_balance.is_none() ||
+                self.account(tx_index, self.coinbase)
+                    .flatten()
+                    .is_some_and(|account| !account.is_empty()))
+    }
+
+    fn apply_writes(&mut self, tx_index: TxIndex, writes: &StateMaps) {
+        for (address, info) in &writes.accounts {
+            if let Some(AccountInfo { code_hash, code: Some(code), .. }) = info {
+                if *code_hash != KECCAK_EMPTY {
+                    self.contracts.entry(*code_hash).or_insert_with(|| code.clone());
+                }
+            }
+            self.accounts.write(tx_index, *address, info.clone());
+        }
+        for (key, value) in &writes.storage {
+            self.storage.write(tx_index, *key, *value);
+        }
+        for address in &writes.cleared_storage {
+            self.cleared_storage.write(tx_index, *address, ());
+        }
+        if let Some(reward) = writes.coinbase_reward {
+            self.coinbase_rewards.insert(tx_index, reward);
+        }
+    }
+
+    fn delete_writes(&mut self, tx_index: TxIndex, writes: &StateMaps) {
+        for address in writes.accounts.keys() {
+            self.accounts.delete_write(*address, tx_index);
+        }
+        for key in writes.storage.keys() {
+            self.storage.delete_write(*key, tx_index);
+        }
+        for address in &writes.cleared_storage {
+            self.cleared_storage.delete_write(*address, tx_index);
+        }
+        if writes.coinbase_reward.is_some() {
+            self.coinbase_rewards.remove(&tx_index);
+        }
+    }
+}
+
+/// The versioned state as seen by a single transaction.
+#[derive(Debug)]
+pub(crate) struct VersionedStateProxy<'a> {
+    tx_index: TxIndex,
+    state: &'a Mutex<VersionedState>,
+}
+
+impl<'a> VersionedStateProxy<'a> {
+    /// Returns a view of the given state as seen by the transaction at `tx_index`.
+    pub(crate) const fn new(tx_index: TxIndex, state: &'a Mutex<VersionedState>) -> Self {
+        Self { tx_index, state }
+    }
+
+    fn state(&self) -> MutexGuard<'_, VersionedState> {
+        self.state.lock().expect("Failed to acquire state lock.")
+    }
+
+    /// Returns the account info as seen by the transaction, if it is known without reading the
+    /// pre-execution state.
+    pub(crate) fn account(&self, address: Address) -> Option<Option<AccountInfo>> {
+        self.state().account(self.tx_index, address)
+    }
+
+    /// Returns `true` if the given reads of the transaction still match the versioned state.
+    pub(crate) fn validate_reads(&self, reads: &StateMaps) -> bool {
+        self.state().validate_reads(self.tx_index, reads)
+    }
+
+    /// Records the writes of the transaction.
+    pub(crate) fn apply_writes(&self, writes: &StateMaps) {
+        self.state().apply_writes(self.tx_index, writes)
+    }
+
+    /// Removes the writes of the transaction, e.g. because it is re-executed.
+    pub(crate) fn delete_writes(&self, writes: &StateMaps) {
+        self.state().delete_writes(self.tx_index, writes)
+    }
+
+    /// Returns a [`Database`] for executing the transaction that records every value it reads.
+    pub(crate) fn database<'b>(&'b self, reader: &'b StateReader) -> VersionedDatabase<'b> {
+        VersionedDatabase { proxy: self, reader, reads: StateMaps::default(), paying_fee: false }
+    }
+}
+
+/// A [`Database`] that reads the versioned state as seen by a single transaction, falling back to
+/// the pre-execution state.
+#[derive(Debug)]
+pub(crate) struct VersionedDatabase<'a> {
+    proxy: &'a VersionedStateProxy<'a>,
+    reader: &'a StateReader,
+    reads: StateMaps,
+    /// Whether the EVM is paying the fee of the transaction to the coinbase.
+    paying_fee: bool,
+}
+
+impl VersionedDatabase<'_> {
+    /// Returns the values read so far.
+    pub(crate) fn take_reads(&mut self) -> StateMaps {
+        std::mem::take(&mut self.reads)
+    }
+
+    /// Sets whether the EVM is paying the fee of the transaction to the coinbase.
+    ///
+    /// If the coinbase is first loaded while paying the fee, the fee is deferred instead of
+    /// reading the coinbase, see [`StateMaps::coinbase_balance`].
+    pub(crate) fn set_paying_fee(&mut self, paying_fee: bool) {
+        self.paying_fee = paying_fee;
+    }
+}
+
+impl Database for VersionedDatabase<'_> {
+    type Error = ProviderError;
+
+    fn basic(&mut self, address: Address) -> Result<Option<AccountInfo>, Self::Error> {
+        let versioned = self.proxy.account(address);
+        let account = match versioned {
+            Some(account) => account,
+            None => {
+                let account = self.reader.basic(address)?;
+                let mut state = self.proxy.state();
+                state.accounts.set_initial_value(address, account);
+                state.account(self.proxy.tx_index, address).expect("initial value is set")
+            }
+        };
+
+        let is_coinbase = address == self.proxy.state().coinbase;
+        match &account {
+            // The fee can only be deferred if paying it doesn't create or touch an empty account.
+            Some(info) if is_coinbase && self.paying_fee && !info.is_empty() => {
+                self.reads.coinbase_balance = Some(info.balance);
+            }
+            _ => {
+                self.reads.accounts.insert(address, account.clone());
+            }
+        }
+        Ok(account)
+    }
+
+    fn code_by_hash(&mut self, code_hash: B256) -> Result<Bytecode, Self::Error> {
+        if let Some(code) = self.proxy.state().contracts.get(&code_hash) {
+            return Ok(code.clone())
+        }
+        self.reader.code_by_hash(code_hash)
+    }
+
+    fn storage(&mut self, address: Address, index: U256) -> Result<U256, Self::Error> {
+        let versioned = self.proxy.state().storage(self.proxy.tx_index, address, index);
+        let value = match versioned {
+            Some(value) => value,
+            None => {
+                let value = self.reader.storage(address, index)?;
+                self.proxy.state().storage.set_initial_value((address, index), value);
+                value
+            }
+        };
+        self.reads.storage.insert((address, index), value);
+        Ok(value)
+    }
+
+    fn block_hash(&mut self, number: u64) -> Result<B256, Self::Error> {
+        self.reader.block_hash(number)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn coinbase_rewards_are_added_to_latest_write() {
+        let coinbase = Address::with_last_byte(0xbe);
+        let account =
+            |balance: u64| AccountInfo { balance: U256::from(balance), ..Default::default() };
+        let reward = |reward: u64| StateMaps {
+            coinbase_reward: Some(U256::from(reward)),
+            ..Default::default()
+        };
+
+        let mut state = VersionedState::new(coinbase);
+        assert_eq!(state.account(1, coinbase), None);
+        state.accounts.set_initial_value(coinbase, Some(account(10)));
+        state.apply_writes(1, &reward(5));
+        state.apply_writes(3, &reward(7));
+        assert_eq!(state.account(1, coinbase), Some(Some(account(10))));
+        assert_eq!(state.account(2, coinbase), Some(Some(account(15))));
+        assert_eq!(state.account(4, coinbase), Some(Some(account(22))));
+
+        let write = StateMaps {
+            accounts: HashMap::from_iter([(coinbase, Some(account(100)))]),
+            ..Default::default()
+        };
+        state.apply_writes(2, &write);
+        assert_eq!(state.account(3, coinbase), Some(Some(account(100))));
+        assert_eq!(state.account(4, coinbase), Some(Some(account(107))));
+
+        // Deferring the fee only requires the coinbase to be non-empty.
+        let reads = StateMaps { coinbase_balance: Some(U256::from(10)), ..Default::default() };
+        assert!(state.validate_reads(4, &reads));
+        state.apply_writes(
+            2,
+            &StateMaps { accounts: HashMap::from_iter([(coinbase, None)]), ..Default::default() },
+        );
+        assert!(!state.validate_reads(3, &reads));
+
+        state.delete_writes(2, &write);
+        state.delete_writes(3, &reward(7));
+        assert_eq!(state.account(4, coinbase), Some(Some(account(15))));
+    }
+}
@@ -0,0 +1,118 @@
+//! Multi-version storage cell used by [`VersionedState`](crate::versioned_state::VersionedState).
+
+use crate::TxIndex;
+use std::{
+    collections::{BTreeMap, HashMap},
+    fmt::Debug,
+    hash::Hash,
+};
+
+/// A storage unit.
+///
+/// It is versioned in the sense that it holds a state of write operations done on it by
+/// different versions of executions.
+/// This allows maintaining the cells with the correct values in the context of each execution.
+#[derive(Debug)]
+pub(crate) struct VersionedStorage<K, V>
+where
+    K: Clone + Copy + Eq + Hash + Debug,
+    V: Clone + Debug,
+{
+    cached_initial_values: HashMap<K, V>,
+    writes: HashMap<K, BTreeMap<TxIndex, V>>,
+}
+
+impl<K, V> Default for VersionedStorage<K, V>
+where
+    K: Clone + Copy + Eq + Hash + Debug,
+    V: Clone + Debug,
+{
+    fn default() -> Self {
+        Self { cached_initial_values: Default::default(), writes: Default::default() }
+    }
+}
+
+impl<K, V> VersionedStorage<K, V>
+where
+    K: Clone + Copy + Eq + Hash + Debug,
+    V: Clone + Debug,
+{
+    /// Returns the value of the given key as seen by the transaction at `tx_index`.
+    ///
+    /// This is the value written by the highest transaction index that is lower than `tx_index`,
+    /// falling back to the cached initial value.
+    pub(crate) fn read(&self, tx_index: TxIndex, key: K) -> Option<V> {
+        self.read_write(tx_index, key)
+            .map(|(_, value)| value)
+            .or_else(|| self.cached_initial_values.get(&key).cloned())
+    }
+
+    /// Returns the latest write to the given key that is visible to the transaction at
+    /// `tx_index`, together with the index of the transaction that wrote it.
+    ///
+    /// Unlike [`Self::read`], this does not fall back to the cached initial value.
+    pub(crate) fn read_write(&self, tx_index: TxIndex, key: K) -> Option<(TxIndex, V)> {
+        self.writes
+            .get(&key)
+            .and_then(|cell| cell.range(..tx_index).next_back())
+            .map(|(index, value)| (*index, value.clone()))
+    }
+
+    /// Returns the cached initial value of the given key, if any.
+    pub(crate) fn initial_value(&self, key: &K) -> Option<V> {
+        self.cached_initial_values.get(key).cloned()
+    }
+
+    /// Records a write of the transaction at `tx_index`.
+    pub(crate) fn write(&mut self, tx_index: TxIndex, key: K, value: V) {
+        let cell = self.writes.entry(key).or_default();
+        cell.insert(tx_index, value);
+    }
+
+    /// Removes the write of the transaction at `tx_index`.
+    pub(crate) fn delete_write(&mut self, key: K, tx_index: TxIndex) {
+        self.writes
+            .get_mut(&key)
+            .expect(
+                "A 'delete_write' call must be preceded by a 'write' call with the corresponding \
+                 key",
+            )
+            .remove(&tx_index);
+    }
+
+    /// This method inserts the provided key-value pair into the cached initial values map.
+    ///
+    /// It is typically used when reading a value that is not found in the versioned storage. In
+    /// such a scenario, the value is retrieved from the initial storage and written to the
+    /// cached initial values for future references.
+    pub(crate) fn set_initial_value(&mut self, key: K, value: V) {
+        self.cached_initial_values.insert(key, value);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn read_latest_lower_write() {
+        let mut storage = VersionedStorage::<u8, u64>::default();
+        storage.set_initial_value(1, 10);
+        assert_eq!(storage.read(0, 1), Some(10));
+        assert_eq!(storage.read(0, 2), None);
+
+        storage.write(1, 1, 11);
+        storage.write(3, 1, 13);
+        assert_eq!(storage.read(1, 1), Some(10));
+        assert_eq!(storage.read(2, 1), Some(11));
+        assert_eq!(storage.read(3, 1), Some(11));
+        assert_eq!(storage.read(4, 1), Some(13));
+        assert_eq!(storage.read_write(3, 1), Some((1, 11)));
+        assert_eq!(storage.read_write(1, 1), None);
+        assert_eq!(storage.initial_value(&1), Some(10));
+
+        storage.delete_write(1, 1);
+        assert_eq!(storage.read(3, 1), Some(10));
+        assert_eq!(storage.read(4, 1), Some(13));
+    }
+}
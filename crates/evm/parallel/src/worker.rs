@@ -0,0 +1,220 @@
+//! The work loop of a single worker thread.
+
+use crate::{
+    scheduler::{Scheduler, Task},
+    state::StateReader,
+    versioned_state::{StateMaps, VersionedState, VersionedStateProxy},
+    TxIndex,
+};
+use reth_evm::{execute::ProviderError, ConfigureEvm};
+use revm_primitives::{EVMError, EnvWithHandlerCfg, ResultAndState, TxEnv};
+use std::{
+    fmt::Debug,
+    sync::{Arc, Mutex, MutexGuard},
+    thread,
+    time::Duration,
+};
+
+const EXECUTION_OUTPUTS_UNWRAP_ERROR: &str = "Execution task outputs should not be None.";
+
+/// The outcome of executing a transaction.
+pub(crate) type ExecutionResult = Result<ResultAndState, EVMError<ProviderError>>;
+
+/// The output of the latest execution of a transaction.
+#[derive(Debug)]
+pub(crate) struct ExecutionTaskOutput {
+    pub(crate) reads: StateMaps,
+    pub(crate) writes: StateMaps,
+    pub(crate) result: ExecutionResult,
+}
+
+/// Executes, validates and commits the transactions of a block, shared by all worker threads.
+#[derive(Debug)]
+pub(crate) struct WorkerExecutor<'a, EvmConfig> {
+    evm_config: &'a EvmConfig,
+    env: &'a EnvWithHandlerCfg,
+    txs: &'a [TxEnv],
+    /// Whether empty touched accounts are removed, see EIP-161.
+    state_clear: bool,
+    pub(crate) scheduler: Scheduler,
+    state: Mutex<VersionedState>,
+    pub(crate) execution_outputs: Box<[Mutex<Option<ExecutionTaskOutput>>]>,
+}
+
+impl<'a, EvmConfig> WorkerExecutor<'a, EvmConfig>
+where
+    EvmConfig: ConfigureEvm,
+{
+    /// Creates a new executor for the given transactions.
+    pub(crate) fn new(
+        evm_config: &'a EvmConfig,
+        env: &'a EnvWithHandlerCfg,
+        txs: &'a [TxEnv],
+        state_clear: bool,
+    ) -> Self {
+        let execution_outputs =
+            std::iter::repeat_with(|| Mutex::new(None)).take(txs.len()).collect();
+        Self {
+            evm_config,
+            env,
+            txs,
+            state_clear,
+            scheduler: Scheduler::new(txs.len()),
+            state: Mutex::new(VersionedState::new(env.block.coinbase)),
+            execution_outputs,
+        }
+    }
+
+    /// Works on tasks until all transactions are committed, or the scheduler is halted.
+    pub(crate) fn run(&self, reader: &StateReader) {
+        // Make sure the other workers do not wait forever for a transaction that will never be
+        // committed if this worker panics.
+        let _guard = HaltOnPanic(&self.scheduler);
+
+        let mut task = Task::Ask;
+        loop {
+            self.commit_while_possible(reader);
+            task = match task {
+                Task::Execution(tx_index) => {
+                    self.execute(tx_index, reader);
+                    Task::Ask
+                }
+                Task::Validation(tx_index) => self.validate(tx_index),
+                Task::NoneAvailable => {
+                    // There's no available task at the moment; sleep for a bit to save CPU power.
+                    // (since busy-looping might damage performance when using hyper-threads).
+                    thread::sleep(Duration::from_micros(1));
+                    Task::Ask
+                }
+                Task::Ask => self.scheduler.next_task(),
+                Task::Done => break,
+            };
+        }
+    }
+
+    fn commit_while_possible(&self, reader: &StateReader) {
+        if let Some(mut transaction_committer) = self.scheduler.try_enter_commit_phase() {
+            while let Some(tx_index) = transaction_committer.try_commit() {
+                let commit_succeeded = self.commit_tx(tx_index, reader);
+                if !commit_succeeded {
+                    transaction_committer.halt_scheduler();
+                }
+            }
+        }
+    }
+
+    fn validate(&self, tx_index: TxIndex) -> Task {
+        let tx_versioned_state = VersionedStateProxy::new(tx_index, &self.state);
+        let execution_output = lock_mutex_in_array(&self.execution_outputs, tx_index);
+        let execution_output = execution_output.as_ref().expect(EXECUTION_OUTPUTS_UNWRAP_ERROR);
+        let reads_valid = tx_versioned_state.validate_reads(&execution_output.reads);
+
+        let aborted = !reads_valid && self.scheduler.try_validation_abort(tx_index);
+        if aborted {
+            tx_versioned_state.delete_writes(&execution_output.writes);
+            self.scheduler.finish_abort(tx_index)
+        } else {
+            Task::Ask
+        }
+    }
+
+    /// Commits the transaction, re-executing it first if its reads are stale.
+    ///
+    /// Returns `false` if the transaction failed and no further transactions can be committed.
+    fn commit_tx(&self, tx_index: TxIndex, reader: &StateReader) -> bool {
+        let mut execution_output = lock_mutex_in_array(&self.execution_outputs, tx_index);
+        let execution_output_ref = execution_output.as_ref().expect(EXECUTION_OUTPUTS_UNWRAP_ERROR);
+
+        // All lower transactions are committed, so their writes are final and this validation is
+        // conclusive.
+        let tx_versioned_state = VersionedStateProxy::new(tx_index, &self.state);
+        if !tx_versioned_state.validate_reads(&execution_output_ref.reads) {
+            // Revalidate failed: re-execute the transaction.
+            tx_versioned_state.delete_writes(&execution_output_ref.writes);
+            // Release the execution output lock as it is acquired in execution (avoid dead-lock).
+            drop(execution_output);
+
+            self.execute_tx(tx_index, reader);
+            self.scheduler.finish_execution_during_commit(tx_index);
+
+            execution_output = lock_mutex_in_array(&self.execution_outputs, tx_index);
+            let execution_output = execution_output.as_ref().expect(EXECUTION_OUTPUTS_UNWRAP_ERROR);
+            // Another validation after the re-execution for sanity check.
+            assert!(tx_versioned_state.validate_reads(&execution_output.reads));
+        }
+
+        let execution_output = execution_output.as_mut().expect(EXECUTION_OUTPUTS_UNWRAP_ERROR);
+        let ExecutionTaskOutput { reads, result, .. } = execution_output;
+        if let (Some(balance), Ok(ResultAndState { state, .. })) = (reads.coinbase_balance, result)
+        {
+            // The fee was paid on top of the coinbase as seen during execution, which is only
+            // final now that all lower transactions are committed.
+            let coinbase = self.env.block.coinbase;
+            let account = state.get_mut(&coinbase).expect("the fee is paid to the coinbase");
+            let reward = account.info.balance - balance;
+            account.info = tx_versioned_state
+                .account(coinbase)
+                .flatten()
+                .expect("the coinbase is validated to exist");
+            account.info.balance = account.info.balance.saturating_add(reward);
+        }
+        execution_output.result.is_ok()
+    }
+
+    fn execute(&self, tx_index: TxIndex, reader: &StateReader) {
+        self.execute_tx(tx_index, reader);
+        self.scheduler.finish_execution(tx_index)
+    }
+
+    fn execute_tx(&self, tx_index: TxIndex, reader: &StateReader) {
+        let tx_versioned_state = VersionedStateProxy::new(tx_index, &self.state);
+        let mut db = tx_versioned_state.database(reader);
+
+        let result = {
+            let mut evm = self.evm_config.evm_with_env(&mut db, self.env.clone());
+            *evm.tx_mut() = self.txs[tx_index].clone();
+            // Defer the fee paid to the coinbase, so that all transactions don't conflict on it.
+            let reward_beneficiary = evm.handler.post_execution.reward_beneficiary.clone();
+            evm.handler.post_execution.reward_beneficiary = Arc::new(move |context, gas| {
+                context.evm.db.set_paying_fee(true);
+                let result = reward_beneficiary(context, gas);
+                context.evm.db.set_paying_fee(false);
+                result
+            });
+            evm.transact()
+        };
+
+        let reads = db.take_reads();
+        let writes = match &result {
+            Ok(ResultAndState { state, .. }) => {
+                StateMaps::writes_of(state, &reads, self.env.block.coinbase, self.state_clear)
+            }
+            Err(_) => StateMaps::default(),
+        };
+        tx_versioned_state.apply_writes(&writes);
+
+        let mut execution_output = lock_mutex_in_array(&self.execution_outputs, tx_index);
+        *execution_output = Some(ExecutionTaskOutput { reads, writes, result });
+    }
+}
+
+/// Halts the scheduler when dropped during a panic.
+struct HaltOnPanic<'a>(&'a Scheduler);
+
+impl Drop for HaltOnPanic<'_> {
+    fn drop(&mut self) {
+        if thread::panicking() {
+            self.0.halt();
+        }
+    }
+}
+
+/// Locks the mutex of the given transaction, panicking if it is poisoned.
+pub(crate) fn lock_mutex_in_array<T: Debug>(
+    array: &[Mutex<T>],
+    tx_index: TxIndex,
+) -> MutexGuard<'_, T> {
+    array[tx_index].lock().unwrap_or_else(|error| {
+        panic!("Cell of transaction index {} is poisoned. Data: {:?}.", tx_index, *error.get_ref())
+    })
+}
@@ -9,7 +9,34 @@ use core::fmt::Display;
 
 use reth_primitives::{BlockNumber, BlockWithSenders, Receipt};
 use reth_prune_types::PruneModes;
-use revm_primitives::db::Database;
+use revm_primitives::{db::Database, EvmState};
+
+/// A hook that is called with the state changes of every transaction, as soon as it is executed
+/// by an [`Executor`].
+///
+/// This can be used to start processing the state changes of a block, e.g. prefetching the trie
+/// nodes of the changed accounts, before the execution of the whole block is finished.
+pub trait OnStateHook {
+    /// Invoked with the state changes of an executed transaction, before they are committed.
+    fn on_state(&mut self, state: &EvmState);
+}
+
+impl<F> OnStateHook for F
+where
+    F: FnMut(&EvmState),
+{
+    fn on_state(&mut self, state: &EvmState) {
+        self(state)
+    }
+}
+
+/// An [`OnStateHook`] that ignores the state changes.
+#[derive(Default, Debug, Clone, Copy)]
+pub struct NoopHook;
+
+impl OnStateHook for NoopHook {
+    fn on_state(&mut self, _state: &EvmState) {}
+}
 
 /// A general purpose executor trait that executes an input (e.g. block) and produces an output
 /// (e.g. state changes and receipts).
@@ -32,6 +59,26 @@ pub trait Executor<DB> {
     /// # Returns
     /// The output of the block execution.
     fn execute(self, input: Self::Input<'_>) -> Result<Self::Output, Self::Error>;
+
+    /// Consumes the type and executes the block, invoking the hook with the state changes of
+    /// every transaction as soon as it is executed.
+    ///
+    /// The hook only receives the state changes of the transactions, the changes applied before
+    /// and after them (e.g. system calls and block rewards) are only part of the output.
+    ///
+    /// Executors that don't support streaming the state changes ignore the hook by default.
+    fn execute_with_state_hook<F>(
+        self,
+        input: Self::Input<'_>,
+        state_hook: F,
+    ) -> Result<Self::Output, Self::Error>
+    where
+        F: OnStateHook,
+        Self: Sized,
+    {
+        let _ = state_hook;
+        self.execute(input)
+    }
 }
 
 /// A general purpose executor that can execute multiple inputs in sequence, validate the outputs,
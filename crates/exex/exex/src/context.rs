@@ -23,6 +23,9 @@ pub struct ExExContext<Node: FullNodeComponents> {
     /// The exex should emit a `FinishedHeight` whenever a processed block is safe to prune.
     /// Additionally, the exex can pre-emptively emit a `FinishedHeight` event to specify what
     /// blocks to receive notifications for.
+    ///
+    /// Notifications are persisted in a write-ahead log, and the ones the exex has not reported a
+    /// `FinishedHeight` for are delivered again after a restart.
     pub events: UnboundedSender<ExExEvent>,
     /// Channel to receive [`ExExNotification`]s.
     ///
@@ -1,4 +1,5 @@
-use reth_primitives::BlockNumber;
+use crate::ExExNotification;
+use reth_primitives::BlockNumHash;
 
 /// Events emitted by an `ExEx`.
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
@@ -8,6 +9,17 @@ pub enum ExExEvent {
     /// The `ExEx` must guarantee that it will not require all earlier blocks in the future,
     /// meaning that Reth is allowed to prune them.
     ///
-    /// On reorgs, it's possible for the height to go down.
-    FinishedHeight(BlockNumber),
+    /// On reorgs, it's possible for the height to go down. The hash tells the blocks of a reorg
+    /// apart from the reorged out blocks at the same height.
+    FinishedHeight(BlockNumHash),
+}
+
+/// Returns the block an `ExEx` has finished at after processing the notification.
+pub(crate) fn finished_height_of(notification: &ExExNotification) -> BlockNumHash {
+    match notification {
+        ExExNotification::ChainCommitted { new } | ExExNotification::ChainReorged { new, .. } => {
+            new.tip().num_hash()
+        }
+        ExExNotification::ChainReverted { old } => old.fork_block(),
+    }
 }
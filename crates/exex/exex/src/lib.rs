@@ -20,8 +20,8 @@
 //! processed. This event is used by Reth to determine what state can be pruned.
 //!
 //! An `ExEx` will only receive notifications for blocks greater than the block emitted in the
-//! event. To clarify: if the `ExEx` emits `ExExEvent::FinishedHeight(0)` it will receive
-//! notifications for any `block_number > 0`.
+//! event. To clarify: if the `ExEx` emits `ExExEvent::FinishedHeight` with block number 0 it
+//! will receive notifications for any `block_number > 0`.
 //!
 //! [`Future`]: std::future::Future
 //! [`ExExContext`]: crate::ExExContext
@@ -46,6 +46,11 @@ pub use event::*;
 mod manager;
 pub use manager::*;
 
+pub mod remote;
+
+pub mod wal;
+pub use wal::{ExExProgress, Wal};
+
 // Re-export exex types
 #[doc(inline)]
 pub use reth_exex_types::*;
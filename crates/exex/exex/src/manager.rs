@@ -1,11 +1,12 @@
-use crate::{ExExEvent, ExExNotification, FinishedExExHeight};
+use crate::{wal::Wal, ExExEvent, ExExNotification, FinishedExExHeight};
 use metrics::Gauge;
 use reth_metrics::{metrics::Counter, Metrics};
-use reth_primitives::BlockNumber;
+use reth_primitives::{BlockNumHash, BlockNumber};
 use reth_tracing::tracing::debug;
 use std::{
     collections::VecDeque,
     future::{poll_fn, Future},
+    mem,
     pin::Pin,
     sync::{
         atomic::{AtomicUsize, Ordering},
@@ -13,9 +14,12 @@ use std::{
     },
     task::{ready, Context, Poll},
 };
-use tokio::sync::{
-    mpsc::{self, error::SendError, Receiver, UnboundedReceiver, UnboundedSender},
-    watch,
+use tokio::{
+    sync::{
+        mpsc::{self, error::SendError, Receiver, UnboundedReceiver, UnboundedSender},
+        watch,
+    },
+    task::JoinHandle,
 };
 use tokio_util::sync::{PollSendError, PollSender, ReusableBoxFuture};
 
@@ -155,6 +159,118 @@ pub struct ExExManagerMetrics {
     num_exexs: Gauge,
 }
 
+/// Notifications committed to the [`Wal`], together with their IDs.
+type CommittedNotifications = Vec<(usize, ExExNotification)>;
+
+/// A batch of operations on the [`Wal`].
+#[derive(Debug, Default)]
+struct WalBatch {
+    /// Notifications to commit, in order.
+    notifications: Vec<ExExNotification>,
+    /// Finished heights reported by `ExEx`'s, together with the ID of the next notification that
+    /// was delivered to the `ExEx` at the time.
+    finished_heights: Vec<(String, BlockNumHash, usize)>,
+}
+
+impl WalBatch {
+    fn is_empty(&self) -> bool {
+        self.notifications.is_empty() && self.finished_heights.is_empty()
+    }
+
+    /// Writes the batch to the log and returns the committed notifications with their IDs.
+    ///
+    /// If any `ExEx` finished a height, the notifications that all `ExEx`'s have finished are
+    /// removed from the log.
+    fn write(self, wal: &mut Wal, exex_ids: &[String]) -> eyre::Result<CommittedNotifications> {
+        let committed = self
+            .notifications
+            .into_iter()
+            .map(|notification| Ok((wal.commit(&notification)?, notification)))
+            .collect::<eyre::Result<Vec<_>>>()?;
+
+        if !self.finished_heights.is_empty() {
+            for (exex_id, height, delivered_until) in self.finished_heights {
+                wal.finish_height(&exex_id, height, delivered_until)?;
+            }
+            wal.finalize(exex_ids.iter().map(String::as_str))?;
+        }
+
+        Ok(committed)
+    }
+}
+
+/// Writes [`WalBatch`]es to the [`Wal`] on a blocking task, so that the disk IO does not block
+/// the [`ExExManager`].
+///
+/// Operations are queued while a batch is written, and written as the next batch once it is
+/// done.
+#[derive(Debug)]
+struct WalWriter {
+    /// The log, if no batch is being written.
+    wal: Option<Wal>,
+    /// The task writing the current batch, which gives the log back once it is done.
+    task: Option<JoinHandle<(Wal, eyre::Result<CommittedNotifications>)>>,
+    /// The number of notifications in the current batch.
+    in_flight: usize,
+    /// The operations queued for the next batch.
+    queued: WalBatch,
+}
+
+impl WalWriter {
+    fn new(wal: Wal) -> Self {
+        Self { wal: Some(wal), task: None, in_flight: 0, queued: WalBatch::default() }
+    }
+
+    /// Returns the number of notifications that are queued or being written.
+    fn len(&self) -> usize {
+        self.queued.notifications.len() + self.in_flight
+    }
+
+    /// Queues a notification to be committed.
+    fn commit(&mut self, notification: ExExNotification) {
+        self.queued.notifications.push(notification);
+    }
+
+    /// Queues a finished height reported by an `ExEx`.
+    fn finish_height(&mut self, exex_id: String, height: BlockNumHash, delivered_until: usize) {
+        self.queued.finished_heights.push((exex_id, height, delivered_until));
+    }
+
+    /// Advances the writing of the queued operations and returns the notifications that were
+    /// committed since the last call, in order.
+    fn poll_write(
+        &mut self,
+        cx: &mut Context<'_>,
+        exex_ids: impl Fn() -> Vec<String>,
+    ) -> eyre::Result<CommittedNotifications> {
+        let mut committed = Vec::new();
+        loop {
+            if let Some(task) = &mut self.task {
+                let Poll::Ready(result) = Pin::new(task).poll(cx) else { break };
+                let (wal, result) = result?;
+                self.wal = Some(wal);
+                self.task = None;
+                self.in_flight = 0;
+                committed.extend(result?);
+            }
+
+            if self.queued.is_empty() {
+                break
+            }
+
+            let mut wal = self.wal.take().expect("no batch is being written");
+            let batch = mem::take(&mut self.queued);
+            let exex_ids = exex_ids();
+            self.in_flight = batch.notifications.len();
+            self.task = Some(tokio::task::spawn_blocking(move || {
+                let result = batch.write(&mut wal, &exex_ids);
+                (wal, result)
+            }));
+        }
+        Ok(committed)
+    }
+}
+
 /// The execution extension manager.
 ///
 /// The manager is responsible for:
@@ -168,6 +284,8 @@ pub struct ExExManagerMetrics {
 pub struct ExExManager {
     /// Handles to communicate with the `ExEx`'s.
     exex_handles: Vec<ExExHandle>,
+    /// Writer of the write-ahead log of the notifications, if enabled.
+    wal: Option<WalWriter>,
 
     /// [`ExExNotification`] channel from the [`ExExManagerHandle`]s.
     handle_rx: UnboundedReceiver<ExExNotification>,
@@ -227,6 +345,7 @@ impl ExExManager {
 
         Self {
             exex_handles: handles,
+            wal: None,
 
             handle_rx,
 
@@ -251,6 +370,38 @@ impl ExExManager {
         }
     }
 
+    /// Enables the write-ahead log of notifications.
+    ///
+    /// Every notification is written to the log before it is delivered. Notifications that an
+    /// `ExEx` has not finished processing in a previous run are replayed to it in order, starting
+    /// from its last finished notification. `ExEx`'s without any recorded progress only receive
+    /// new notifications.
+    pub fn with_wal(mut self, mut wal: Wal) -> eyre::Result<Self> {
+        for exex in &mut self.exex_handles {
+            let progress = wal.register_exex(&exex.id)?;
+            exex.next_notification_id = progress.next_id;
+            exex.finished_height = progress.finished_height;
+        }
+        wal.finalize(self.exex_handles.iter().map(|exex| exex.id.as_str()))?;
+
+        let min_id = self
+            .exex_handles
+            .iter()
+            .map(|exex| exex.next_notification_id)
+            .min()
+            .unwrap_or_else(|| wal.next_id());
+        let notifications = wal.read_from(min_id)?;
+        debug!(%min_id, replayed = notifications.len(), "Replaying notifications from WAL");
+
+        self.min_id = min_id;
+        self.next_id = wal.next_id();
+        self.buffer = notifications.into();
+        self.wal = Some(WalWriter::new(wal));
+        self.update_capacity();
+
+        Ok(self)
+    }
+
     /// Returns the handle to the manager.
     pub fn handle(&self) -> ExExManagerHandle {
         self.handle.clone()
@@ -259,7 +410,7 @@ impl ExExManager {
     /// Updates the current buffer capacity and notifies all `is_ready` watchers of the manager's
     /// readiness to receive notifications.
     fn update_capacity(&self) {
-        let capacity = self.max_capacity.saturating_sub(self.buffer.len());
+        let capacity = self.max_capacity.saturating_sub(self.len());
         self.current_capacity.store(capacity, Ordering::Relaxed);
         self.metrics.current_capacity.set(capacity as f64);
         self.metrics.buffer_size.set(self.buffer.len() as f64);
@@ -269,12 +420,39 @@ impl ExExManager {
         let _ = self.is_ready.send(capacity > 0);
     }
 
+    /// Returns the number of notifications in the internal buffer, including the ones that are
+    /// still being written to the write-ahead log.
+    fn len(&self) -> usize {
+        self.buffer.len() + self.wal.as_ref().map_or(0, WalWriter::len)
+    }
+
     /// Pushes a new notification into the managers internal buffer, assigning the notification a
     /// unique ID.
+    ///
+    /// If the write-ahead log is enabled, the notification is queued to be written to it first,
+    /// and only pushed into the buffer once it has been committed.
     fn push_notification(&mut self, notification: ExExNotification) {
-        let next_id = self.next_id;
-        self.buffer.push_back((next_id, notification));
-        self.next_id += 1;
+        match &mut self.wal {
+            Some(wal) => wal.commit(notification),
+            None => {
+                self.buffer.push_back((self.next_id, notification));
+                self.next_id += 1;
+            }
+        }
+    }
+
+    /// Advances the write-ahead log and pushes the committed notifications into the internal
+    /// buffer.
+    fn poll_wal(&mut self, cx: &mut Context<'_>) -> eyre::Result<()> {
+        let Some(wal) = &mut self.wal else { return Ok(()) };
+        let exex_handles = &self.exex_handles;
+        let committed =
+            wal.poll_write(cx, || exex_handles.iter().map(|exex| exex.id.clone()).collect())?;
+        for (id, notification) in committed {
+            self.buffer.push_back((id, notification));
+            self.next_id = id + 1;
+        }
+        Ok(())
     }
 }
 
@@ -283,7 +461,7 @@ impl Future for ExExManager {
 
     fn poll(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
         // drain handle notifications
-        while self.buffer.len() < self.max_capacity {
+        while self.len() < self.max_capacity {
             if let Poll::Ready(Some(notification)) = self.handle_rx.poll_recv(cx) {
                 debug!(
                     committed_tip = ?notification.committed_chain().map(|chain| chain.tip().number),
@@ -296,6 +474,11 @@ impl Future for ExExManager {
             break
         }
 
+        // write notifications to the write-ahead log
+        if let Err(err) = self.poll_wal(cx) {
+            return Poll::Ready(Err(err))
+        }
+
         // update capacity
         self.update_capacity();
 
@@ -329,16 +512,33 @@ impl Future for ExExManager {
         self.update_capacity();
 
         // handle incoming exex events
-        for exex in &mut self.exex_handles {
+        let this = &mut *self;
+        for exex in &mut this.exex_handles {
             while let Poll::Ready(Some(event)) = exex.receiver.poll_recv(cx) {
                 debug!(exex_id = %exex.id, ?event, "Received event from exex");
                 exex.metrics.events_sent_total.increment(1);
                 match event {
-                    ExExEvent::FinishedHeight(height) => exex.finished_height = Some(height),
+                    ExExEvent::FinishedHeight(height) => {
+                        exex.finished_height = Some(height.number);
+                        if let Some(wal) = &mut this.wal {
+                            wal.finish_height(exex.id.clone(), height, exex.next_notification_id);
+                        }
+                    }
                 }
             }
         }
 
+        // record finished heights and remove notifications that all exexs have finished from the
+        // write-ahead log
+        let buffer_len = self.buffer.len();
+        if let Err(err) = self.poll_wal(cx) {
+            return Poll::Ready(Err(err))
+        }
+        if self.buffer.len() > buffer_len {
+            // notifications were committed after the senders were advanced
+            cx.waker().wake_by_ref();
+        }
+
         // update watch channel block number
         let finished_height = self.exex_handles.iter_mut().try_fold(u64::MAX, |curr, exex| {
             let height = match exex.finished_height {
@@ -481,9 +681,52 @@ impl Clone for ExExManagerHandle {
 
 #[cfg(test)]
 mod tests {
+    use super::*;
+    use futures::poll;
+    use reth_provider::Chain;
+
     #[tokio::test]
     async fn delivers_events() {}
 
+    #[tokio::test]
+    async fn replays_unfinished_notifications_from_wal() -> eyre::Result<()> {
+        let dir = tempfile::tempdir()?;
+        let notification = ExExNotification::ChainCommitted {
+            new: Arc::new(Chain::from_block(Default::default(), Default::default(), None)),
+        };
+
+        // deliver a notification that the exex never finishes
+        let (exex, _events, mut notifications) = ExExHandle::new("exex".to_string());
+        let mut manager = ExExManager::new(vec![exex], 1).with_wal(Wal::new(dir.path())?)?;
+        manager.handle().send(notification.clone())?;
+        tokio::select! {
+            result = &mut manager => panic!("manager exited: {result:?}"),
+            received = notifications.recv() => assert_eq!(received, Some(notification.clone())),
+        }
+        drop(manager);
+
+        // the notification is delivered again after a restart
+        let (exex, events, mut notifications) = ExExHandle::new("exex".to_string());
+        let mut manager = ExExManager::new(vec![exex], 1).with_wal(Wal::new(dir.path())?)?;
+        assert!(poll!(&mut manager).is_pending());
+        let tip = notification.committed_chain().unwrap().tip().num_hash();
+        assert_eq!(notifications.recv().await, Some(notification));
+
+        // once finished, the notification is removed from the log
+        events.send(ExExEvent::FinishedHeight(tip))?;
+        loop {
+            assert!(poll!(&mut manager).is_pending());
+            if manager.wal.as_ref().is_some_and(|wal| wal.task.is_none() && wal.queued.is_empty()) {
+                break
+            }
+            tokio::task::yield_now().await;
+        }
+        drop(manager);
+        assert!(Wal::new(dir.path())?.is_empty());
+
+        Ok(())
+    }
+
     #[tokio::test]
     async fn capacity() {}
 
@@ -0,0 +1,138 @@
+//! An `ExEx` that forwards notifications to an out-of-process `ExEx` over a Unix socket.
+//!
+//! See [`reth_exex_types::remote`] for the protocol.
+
+use crate::{event::finished_height_of, ExExContext, ExExEvent};
+use futures::{SinkExt, StreamExt};
+use reth_exex_types::remote::{RemoteAck, RemoteNotification, MAX_FRAME_LENGTH};
+use reth_node_api::FullNodeComponents;
+use reth_primitives::bytes::Bytes;
+use reth_tracing::tracing::{debug, info, warn};
+use std::{
+    collections::VecDeque,
+    path::{Path, PathBuf},
+};
+use tokio::net::{UnixListener, UnixStream};
+use tokio_util::codec::{Framed, LengthDelimitedCodec};
+
+/// The default maximum number of notifications sent to the client without an acknowledgment.
+pub const DEFAULT_MAX_IN_FLIGHT: usize = 16;
+
+/// An `ExEx` that forwards all notifications to an out-of-process `ExEx` connected over a Unix
+/// socket.
+///
+/// One client is served at a time. Notifications are only pulled from the node while a client is
+/// connected and fewer than [`RemoteExEx::with_max_in_flight`] notifications are unacknowledged,
+/// which applies backpressure to the node. Acknowledgments from the client are translated into
+/// [`ExExEvent::FinishedHeight`] events, and unacknowledged notifications are sent again to the
+/// next client after a disconnect.
+///
+/// ```ignore
+/// builder.install_exex("remote", |ctx| async move { Ok(RemoteExEx::new(ctx, path).run()) })
+/// ```
+#[derive(Debug)]
+pub struct RemoteExEx<Node: FullNodeComponents> {
+    ctx: ExExContext<Node>,
+    /// The path of the Unix socket to listen on.
+    path: PathBuf,
+    /// The maximum number of unacknowledged notifications.
+    max_in_flight: usize,
+    /// Notifications that were sent, but not acknowledged yet.
+    pending: VecDeque<RemoteNotification>,
+    /// The ID of the next notification.
+    next_id: u64,
+}
+
+impl<Node: FullNodeComponents> RemoteExEx<Node> {
+    /// Creates a new remote `ExEx` that listens on the given socket path.
+    pub fn new(ctx: ExExContext<Node>, path: impl AsRef<Path>) -> Self {
+        Self {
+            ctx,
+            path: path.as_ref().to_path_buf(),
+            max_in_flight: DEFAULT_MAX_IN_FLIGHT,
+            pending: VecDeque::new(),
+            next_id: 0,
+        }
+    }
+
+    /// Sets the maximum number of notifications sent to the client without an acknowledgment.
+    pub fn with_max_in_flight(mut self, max_in_flight: usize) -> Self {
+        self.max_in_flight = max_in_flight.max(1);
+        self
+    }
+
+    /// Serves clients until the node shuts down.
+    pub async fn run(mut self) -> eyre::Result<()> {
+        // a stale socket is left behind if the node was not shut down gracefully
+        if self.path.exists() {
+            std::fs::remove_file(&self.path)?;
+        }
+        let listener = UnixListener::bind(&self.path)?;
+        info!(target: "exex::remote", path = ?self.path, "Listening for remote ExEx clients");
+
+        loop {
+            let (stream, _) = listener.accept().await?;
+            info!(target: "exex::remote", "Remote ExEx client connected");
+
+            match self.serve(stream).await {
+                Ok(true) => info!(target: "exex::remote", "Remote ExEx client disconnected"),
+                Ok(false) => return Ok(()),
+                Err(err) => warn!(target: "exex::remote", %err, "Remote ExEx client failed"),
+            }
+        }
+    }
+
+    /// Serves a single client.
+    ///
+    /// Returns `Ok(true)` if the client disconnected, and `Ok(false)` if the node shut down.
+    async fn serve(&mut self, stream: UnixStream) -> eyre::Result<bool> {
+        let codec = LengthDelimitedCodec::builder().max_frame_length(MAX_FRAME_LENGTH).new_codec();
+        let mut framed = Framed::new(stream, codec);
+
+        // redeliver the notifications the previous client did not acknowledge
+        for notification in &self.pending {
+            framed.feed(Bytes::from(notification.encode()?)).await?;
+        }
+        SinkExt::<Bytes>::flush(&mut framed).await?;
+
+        loop {
+            tokio::select! {
+                notification = self.ctx.notifications.recv(),
+                    if self.pending.len() < self.max_in_flight =>
+                {
+                    let Some(notification) = notification else { return Ok(false) };
+                    let notification = RemoteNotification { id: self.next_id, notification };
+                    self.next_id += 1;
+
+                    // the notification stays pending until it is acknowledged, so that it is sent
+                    // again to the next client if sending it fails
+                    debug!(target: "exex::remote", id = notification.id, "Sending notification");
+                    let frame = Bytes::from(notification.encode()?);
+                    self.pending.push_back(notification);
+                    framed.send(frame).await?;
+                }
+                frame = framed.next() => {
+                    let Some(frame) = frame else { return Ok(true) };
+                    self.on_ack(RemoteAck::decode(&frame?)?)?;
+                }
+            }
+        }
+    }
+
+    /// Removes all acknowledged notifications and reports the finished height to the node.
+    fn on_ack(&mut self, ack: RemoteAck) -> eyre::Result<()> {
+        debug!(target: "exex::remote", id = ack.id, "Received acknowledgment");
+
+        let mut finished_height = None;
+        while self.pending.front().is_some_and(|notification| notification.id <= ack.id) {
+            let notification = self.pending.pop_front().expect("not empty");
+            finished_height = Some(finished_height_of(&notification.notification));
+        }
+
+        if let Some(height) = finished_height {
+            self.ctx.events.send(ExExEvent::FinishedHeight(height))?;
+        }
+
+        Ok(())
+    }
+}
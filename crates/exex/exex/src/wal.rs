@@ -0,0 +1,409 @@
+//! Write-ahead log of [`ExExNotification`]s.
+//!
+//! If enabled, every notification received by the [`ExExManager`](crate::ExExManager) is written
+//! to disk before it is delivered, together with the progress of each `ExEx` through the log. On
+//! startup, the notifications an `ExEx` has not finished yet are replayed to it in their
+//! original order, so reorgs and reverts that happened while it was offline are delivered as
+//! well.
+
+use crate::{event::finished_height_of, ExExNotification};
+use reth_fs_util as fs;
+use reth_primitives::{BlockNumHash, BlockNumber, B256};
+use reth_tracing::tracing::debug;
+use serde::{Deserialize, Serialize};
+use std::{
+    collections::{BTreeMap, HashMap},
+    fs::File,
+    io::{BufReader, Write},
+    path::{Path, PathBuf},
+};
+
+/// The extension of notification files in the WAL directory.
+const NOTIFICATION_EXTENSION: &str = "wal";
+
+/// The name of the file that stores the progress of each `ExEx`.
+const PROGRESS_FILE_NAME: &str = "progress.json";
+
+/// The progress of an `ExEx` through the [`Wal`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ExExProgress {
+    /// The ID of the first notification the `ExEx` has not finished processing yet.
+    pub next_id: usize,
+    /// The last finished height reported by the `ExEx`, if any.
+    pub finished_height: Option<BlockNumber>,
+}
+
+/// The header of a notification stored in the [`Wal`].
+///
+/// It's written in front of the notification, so that the log can be opened without reading the
+/// notifications themselves.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+struct WalEntry {
+    /// The number of the block an `ExEx` has finished at after processing the notification.
+    number: BlockNumber,
+    /// The hash of the block an `ExEx` has finished at after processing the notification.
+    hash: B256,
+}
+
+impl WalEntry {
+    fn new(notification: &ExExNotification) -> Self {
+        let BlockNumHash { number, hash } = finished_height_of(notification);
+        Self { number, hash }
+    }
+
+    /// Returns the block an `ExEx` has finished at after processing the notification.
+    const fn finished_height(&self) -> BlockNumHash {
+        BlockNumHash::new(self.number, self.hash)
+    }
+}
+
+/// A durable, on-disk log of [`ExExNotification`]s.
+///
+/// Each notification is stored in its own file named after its ID, which increases
+/// monotonically. Notifications are removed with [`Wal::finalize`] once every `ExEx` has
+/// finished processing them.
+#[derive(Debug)]
+pub struct Wal {
+    /// The directory the log is stored in.
+    directory: PathBuf,
+    /// The headers of all notifications in the log, keyed by their ID.
+    entries: BTreeMap<usize, WalEntry>,
+    /// The ID of the next notification to be committed.
+    next_id: usize,
+    /// The progress of each `ExEx`, keyed by the `ExEx` ID.
+    progress: HashMap<String, ExExProgress>,
+}
+
+impl Wal {
+    /// Opens the log in the given directory, creating it if it does not exist.
+    pub fn new(directory: impl AsRef<Path>) -> eyre::Result<Self> {
+        let directory = directory.as_ref().to_path_buf();
+        fs::create_dir_all(&directory)?;
+
+        let mut entries = BTreeMap::new();
+        for entry in fs::read_dir(&directory)? {
+            let path = entry?.path();
+            if path.extension().and_then(|ext| ext.to_str()) != Some(NOTIFICATION_EXTENSION) {
+                continue
+            }
+            let Some(id) =
+                path.file_stem().and_then(|stem| stem.to_str()).and_then(|stem| stem.parse().ok())
+            else {
+                continue
+            };
+            entries.insert(id, read_entry(&path)?);
+        }
+
+        let progress_path = directory.join(PROGRESS_FILE_NAME);
+        let progress = if progress_path.exists() {
+            fs::read_json_file(&progress_path)?
+        } else {
+            HashMap::new()
+        };
+
+        let next_id = entries.last_key_value().map_or_else(
+            // an empty log continues after the notifications all ExExs have finished
+            || progress.values().map(|progress: &ExExProgress| progress.next_id).max().unwrap_or(0),
+            |(id, _)| id + 1,
+        );
+
+        debug!(target: "exex::wal", ?directory, notifications = entries.len(), %next_id, "Opened WAL");
+
+        Ok(Self { directory, entries, next_id, progress })
+    }
+
+    /// Returns the ID of the next notification to be committed.
+    pub const fn next_id(&self) -> usize {
+        self.next_id
+    }
+
+    /// Returns the number of notifications in the log.
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    /// Returns `true` if the log is empty.
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    /// Returns the progress of the given `ExEx`, if it has any.
+    pub fn progress(&self, exex_id: &str) -> Option<ExExProgress> {
+        self.progress.get(exex_id).copied()
+    }
+
+    /// Writes the notification to the log and returns its ID.
+    pub fn commit(&mut self, notification: &ExExNotification) -> eyre::Result<usize> {
+        let id = self.next_id;
+        let path = self.notification_path(id);
+
+        // write to a temporary file first, so that a crash never leaves a partial notification
+        let tmp_path = path.with_extension("tmp");
+        let entry = WalEntry::new(notification);
+        let mut data = bincode::serialize(&entry)?;
+        bincode::serialize_into(&mut data, notification)?;
+        let mut file = fs::create_file(&tmp_path)?;
+        file.write_all(&data)?;
+        file.sync_data()?;
+        fs::rename(&tmp_path, &path)?;
+        sync_directory(&self.directory)?;
+
+        self.entries.insert(id, entry);
+        self.next_id += 1;
+
+        Ok(id)
+    }
+
+    /// Reads the notification with the given ID from the log.
+    pub fn read(&self, id: usize) -> eyre::Result<Option<ExExNotification>> {
+        if !self.entries.contains_key(&id) {
+            return Ok(None)
+        }
+        read_notification(&self.notification_path(id)).map(Some)
+    }
+
+    /// Reads all notifications starting from the given ID.
+    pub fn read_from(&self, id: usize) -> eyre::Result<Vec<(usize, ExExNotification)>> {
+        self.entries
+            .range(id..)
+            .map(|(&id, _)| Ok((id, read_notification(&self.notification_path(id))?)))
+            .collect()
+    }
+
+    /// Starts tracking the progress of an `ExEx` that has no progress yet.
+    ///
+    /// The `ExEx` starts at the next notification to be committed, and the returned progress
+    /// is persisted.
+    pub fn register_exex(&mut self, exex_id: &str) -> eyre::Result<ExExProgress> {
+        if let Some(progress) = self.progress(exex_id) {
+            return Ok(progress)
+        }
+
+        let progress = ExExProgress { next_id: self.next_id, finished_height: None };
+        self.progress.insert(exex_id.to_string(), progress);
+        self.write_progress()?;
+        Ok(progress)
+    }
+
+    /// Records a finished height reported by an `ExEx`.
+    ///
+    /// The notifications up to the first one before `delivered_until` (exclusive) that leaves the
+    /// `ExEx` at the finished block are marked as finished. The hash of the block is compared as
+    /// well, so that finishing the blocks of a reorged out chain doesn't finish the reorg.
+    /// Reverts the `ExEx` hasn't unwound yet are finished together with the next finished
+    /// notification.
+    pub fn finish_height(
+        &mut self,
+        exex_id: &str,
+        finished_height: BlockNumHash,
+        delivered_until: usize,
+    ) -> eyre::Result<()> {
+        let Some(mut progress) = self.progress(exex_id) else {
+            eyre::bail!("ExEx {exex_id} is not registered in the WAL")
+        };
+        progress.finished_height = Some(finished_height.number);
+
+        if let Some((&id, _)) = self
+            .entries
+            .range(progress.next_id..delivered_until)
+            .find(|(_, entry)| entry.finished_height() == finished_height)
+        {
+            progress.next_id = id + 1;
+        }
+
+        if self.progress.insert(exex_id.to_string(), progress) != Some(progress) {
+            self.write_progress()?;
+        }
+
+        Ok(())
+    }
+
+    /// Removes all notifications that every given `ExEx` has finished processing.
+    ///
+    /// The progress of `ExEx`s that are not in the list is dropped. Returns the number of removed
+    /// notifications.
+    pub fn finalize<'a>(
+        &mut self,
+        exex_ids: impl IntoIterator<Item = &'a str>,
+    ) -> eyre::Result<usize> {
+        let exex_ids = exex_ids.into_iter().collect::<Vec<_>>();
+        let len = self.progress.len();
+        self.progress.retain(|exex_id, _| exex_ids.contains(&exex_id.as_str()));
+        if self.progress.len() != len {
+            self.write_progress()?;
+        }
+
+        let finished_until = exex_ids
+            .iter()
+            .map(|exex_id| self.progress(exex_id).map_or(0, |progress| progress.next_id))
+            .min()
+            .unwrap_or(self.next_id);
+
+        let finished = self.entries.range(..finished_until).map(|(&id, _)| id).collect::<Vec<_>>();
+        for &id in &finished {
+            fs::remove_file(self.notification_path(id))?;
+            self.entries.remove(&id);
+        }
+
+        if !finished.is_empty() {
+            debug!(target: "exex::wal", %finished_until, removed = finished.len(), "Finalized WAL");
+        }
+
+        Ok(finished.len())
+    }
+
+    fn notification_path(&self, id: usize) -> PathBuf {
+        self.directory.join(format!("{id}.{NOTIFICATION_EXTENSION}"))
+    }
+
+    fn write_progress(&self) -> eyre::Result<()> {
+        let path = self.directory.join(PROGRESS_FILE_NAME);
+        let tmp_path = path.with_extension("tmp");
+        fs::write_json_file(&tmp_path, &self.progress)?;
+        File::open(&tmp_path)?.sync_data()?;
+        fs::rename(&tmp_path, &path)?;
+        sync_directory(&self.directory)
+    }
+}
+
+/// Flushes the directory entries, so that renames into the directory survive a crash.
+fn sync_directory(directory: &Path) -> eyre::Result<()> {
+    // directories can't be opened as files on Windows, where renames are durable on their own
+    #[cfg(unix)]
+    File::open(directory)?.sync_all()?;
+    #[cfg(not(unix))]
+    let _ = directory;
+    Ok(())
+}
+
+/// Reads the header of a notification file, without reading the notification.
+fn read_entry(path: &Path) -> eyre::Result<WalEntry> {
+    Ok(bincode::deserialize_from(BufReader::new(File::open(path)?))?)
+}
+
+fn read_notification(path: &Path) -> eyre::Result<ExExNotification> {
+    let mut reader = BufReader::new(File::open(path)?);
+    let _: WalEntry = bincode::deserialize_from(&mut reader)?;
+    Ok(bincode::deserialize_from(reader)?)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use reth_primitives::{Address, Receipt, Receipts, U256};
+    use reth_provider::{Chain, ExecutionOutcome};
+    use reth_revm::db::BundleState;
+    use reth_testing_utils::generators::{self, random_block_range, random_receipt, Rng};
+    use std::{ops::RangeInclusive, sync::Arc};
+
+    fn chain(rng: &mut impl Rng, range: RangeInclusive<BlockNumber>) -> Arc<Chain> {
+        let first = *range.start();
+        let blocks = random_block_range(rng, range, Default::default(), 0..3)
+            .into_iter()
+            .map(|block| block.seal_with_senders().unwrap())
+            .collect::<Vec<_>>();
+        let receipts = blocks
+            .iter()
+            .map(|block| {
+                block
+                    .body
+                    .iter()
+                    .map(|tx| Some(random_receipt(rng, tx, Some(1))))
+                    .collect::<Vec<Option<Receipt>>>()
+            })
+            .collect::<Vec<_>>();
+        let address = Address::random();
+        let bundle = BundleState::new(
+            [(
+                address,
+                None,
+                Some(Default::default()),
+                HashMap::from([(U256::from(1), (U256::ZERO, U256::from(2)))]),
+            )],
+            [[(address, Some(None), [(U256::from(1), U256::ZERO)])]],
+            [],
+        );
+        let outcome = ExecutionOutcome::new(bundle, Receipts::from(receipts), first, Vec::new());
+        Arc::new(Chain::new(blocks, outcome, None))
+    }
+
+    #[test]
+    fn commit_read_and_reopen() -> eyre::Result<()> {
+        let mut rng = generators::rng();
+        let dir = tempfile::tempdir()?;
+
+        let committed = ExExNotification::ChainCommitted { new: chain(&mut rng, 1..=3) };
+        let reorged = ExExNotification::ChainReorged {
+            old: chain(&mut rng, 2..=3),
+            new: chain(&mut rng, 2..=4),
+        };
+
+        let mut wal = Wal::new(dir.path())?;
+        assert_eq!(wal.commit(&committed)?, 0);
+        assert_eq!(wal.commit(&reorged)?, 1);
+        assert_eq!(wal.read(1)?, Some(reorged.clone()));
+
+        let wal = Wal::new(dir.path())?;
+        assert_eq!(wal.next_id(), 2);
+        assert_eq!(wal.read_from(0)?, vec![(0, committed), (1, reorged.clone())]);
+
+        // only the headers are read when the log is opened
+        let path = wal.notification_path(1);
+        let data = fs::read(&path)?;
+        fs::write(&path, &data[..data.len() / 2])?;
+        let wal = Wal::new(dir.path())?;
+        assert_eq!(wal.entries[&1], WalEntry::new(&reorged));
+        assert!(wal.read(1).is_err());
+
+        Ok(())
+    }
+
+    #[test]
+    fn finish_and_finalize() -> eyre::Result<()> {
+        let mut rng = generators::rng();
+        let dir = tempfile::tempdir()?;
+
+        let mut wal = Wal::new(dir.path())?;
+        wal.register_exex("a")?;
+        wal.register_exex("b")?;
+
+        let notifications = [
+            ExExNotification::ChainCommitted { new: chain(&mut rng, 1..=3) },
+            ExExNotification::ChainReverted { old: chain(&mut rng, 2..=3) },
+            ExExNotification::ChainCommitted { new: chain(&mut rng, 2..=5) },
+        ];
+        for notification in &notifications {
+            wal.commit(notification)?;
+        }
+        let [committed, reverted, recommitted] = notifications.each_ref().map(finished_height_of);
+
+        // the revert is only finished once the ExEx unwinds to its fork block
+        wal.finish_height("a", committed, 3)?;
+        assert_eq!(wal.progress("a"), Some(ExExProgress { next_id: 1, finished_height: Some(3) }));
+        wal.finish_height("a", reverted, 3)?;
+        assert_eq!(wal.progress("a").unwrap().next_id, 2);
+
+        // a block at the same height on another fork doesn't finish the commit
+        wal.finish_height("a", BlockNumHash::new(5, B256::random()), 3)?;
+        assert_eq!(wal.progress("a").unwrap().next_id, 2);
+
+        // the revert is also finished once the ExEx finishes a later commit
+        wal.finish_height("b", recommitted, 3)?;
+        assert_eq!(wal.progress("b").unwrap().next_id, 3);
+
+        assert_eq!(wal.finalize(["a", "b"])?, 2);
+        assert_eq!(wal.len(), 1);
+
+        // the progress is persisted
+        let mut wal = Wal::new(dir.path())?;
+        assert_eq!(wal.progress("a").unwrap().next_id, 2);
+        assert_eq!(wal.next_id(), 3);
+
+        // removed ExExs no longer hold back the log
+        assert_eq!(wal.finalize(["b"])?, 1);
+        assert!(wal.is_empty());
+        assert_eq!(Wal::new(dir.path())?.next_id(), 3);
+
+        Ok(())
+    }
+}
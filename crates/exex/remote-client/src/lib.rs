@@ -0,0 +1,81 @@
+//! Client for execution extensions running outside of the node process.
+//!
+//! The node forwards its notifications with the `RemoteExEx` from `reth-exex`, and the client
+//! acknowledges them once processed:
+//!
+//! ```no_run
+//! # async fn run() -> Result<(), reth_exex_remote_client::RemoteExExClientError> {
+//! use reth_exex_remote_client::RemoteExExClient;
+//!
+//! let mut client = RemoteExExClient::connect("/tmp/reth-exex.ipc").await?;
+//! while let Some(notification) = client.recv().await? {
+//!     // process notification.notification
+//!     client.ack(notification.id).await?;
+//! }
+//! # Ok(())
+//! # }
+//! ```
+
+#![doc(
+    html_logo_url = "https://raw.githubusercontent.com/paradigmxyz/reth/main/assets/reth-docs.png",
+    html_favicon_url = "https://avatars0.githubusercontent.com/u/97369466?s=256",
+    issue_tracker_base_url = "https://github.com/paradigmxyz/reth/issues/"
+)]
+#![cfg_attr(not(test), warn(unused_crate_dependencies))]
+#![cfg_attr(docsrs, feature(doc_cfg, doc_auto_cfg))]
+
+use bytes::Bytes;
+use futures::{SinkExt, StreamExt};
+pub use reth_exex_types::{
+    remote::{RemoteAck, RemoteNotification, MAX_FRAME_LENGTH},
+    ExExNotification,
+};
+use std::{io, path::Path};
+use tokio::net::UnixStream;
+use tokio_util::codec::{Framed, LengthDelimitedCodec};
+
+/// Errors of the [`RemoteExExClient`].
+#[derive(Debug, thiserror::Error)]
+pub enum RemoteExExClientError {
+    /// The connection to the node failed.
+    #[error(transparent)]
+    Io(#[from] io::Error),
+    /// A message could not be encoded or decoded.
+    #[error("invalid message: {0}")]
+    Codec(#[from] bincode::Error),
+}
+
+/// A client of the node's remote `ExEx`.
+///
+/// Notifications must be acknowledged in order with [`RemoteExExClient::ack`]. The node stops
+/// sending notifications while too many are unacknowledged, and sends unacknowledged
+/// notifications again after a reconnect.
+#[derive(Debug)]
+pub struct RemoteExExClient {
+    framed: Framed<UnixStream, LengthDelimitedCodec>,
+}
+
+impl RemoteExExClient {
+    /// Connects to the remote `ExEx` listening on the given socket path.
+    pub async fn connect(path: impl AsRef<Path>) -> Result<Self, RemoteExExClientError> {
+        let stream = UnixStream::connect(path).await?;
+        let codec = LengthDelimitedCodec::builder().max_frame_length(MAX_FRAME_LENGTH).new_codec();
+        Ok(Self { framed: Framed::new(stream, codec) })
+    }
+
+    /// Receives the next notification.
+    ///
+    /// Returns `None` if the node closed the connection.
+    pub async fn recv(&mut self) -> Result<Option<RemoteNotification>, RemoteExExClientError> {
+        match self.framed.next().await {
+            Some(frame) => Ok(Some(RemoteNotification::decode(&frame?)?)),
+            None => Ok(None),
+        }
+    }
+
+    /// Acknowledges that all notifications up to and including the given ID were processed.
+    pub async fn ack(&mut self, id: u64) -> Result<(), RemoteExExClientError> {
+        self.framed.send(Bytes::from(RemoteAck { id }.encode()?)).await?;
+        Ok(())
+    }
+}
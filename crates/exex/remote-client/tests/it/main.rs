@@ -0,0 +1,61 @@
+//! Integration tests for the remote `ExEx` and its client.
+
+use reth_exex::{remote::RemoteExEx, ExExEvent, ExExNotification};
+use reth_exex_remote_client::{RemoteExExClient, RemoteNotification};
+use reth_exex_test_utils::test_exex_context;
+use reth_primitives::BlockNumHash;
+use reth_provider::{Chain, ExecutionOutcome};
+use std::{path::Path, sync::Arc, time::Duration};
+use tokio::time::{sleep, timeout};
+
+async fn connect(path: &Path) -> eyre::Result<RemoteExExClient> {
+    // the socket is created once the exex starts running
+    for _ in 0..100 {
+        if let Ok(client) = RemoteExExClient::connect(path).await {
+            return Ok(client)
+        }
+        sleep(Duration::from_millis(10)).await;
+    }
+    eyre::bail!("remote exex did not start listening")
+}
+
+#[tokio::test]
+async fn forwards_notifications_with_backpressure() -> eyre::Result<()> {
+    let (ctx, mut handle) = test_exex_context().await?;
+    let dir = tempfile::tempdir()?;
+    let path = dir.path().join("exex.ipc");
+
+    let exex = tokio::spawn(RemoteExEx::new(ctx, &path).with_max_in_flight(1).run());
+    let mut client = connect(&path).await?;
+
+    let chain =
+        Arc::new(Chain::from_block(handle.genesis.clone(), ExecutionOutcome::default(), None));
+    let committed = ExExNotification::ChainCommitted { new: chain.clone() };
+    let reverted = ExExNotification::ChainReverted { old: chain };
+
+    handle.notifications_tx.send(committed.clone()).await?;
+    handle.notifications_tx.send(reverted.clone()).await?;
+    assert_eq!(client.recv().await?, Some(RemoteNotification { id: 0, notification: committed }));
+
+    // the second notification is held back until the first one is acknowledged
+    assert!(timeout(Duration::from_millis(100), client.recv()).await.is_err());
+    handle.assert_events_empty();
+
+    client.ack(0).await?;
+    let reverted = RemoteNotification { id: 1, notification: reverted };
+    assert_eq!(client.recv().await?, Some(reverted.clone()));
+    let genesis = handle.genesis.num_hash();
+    assert_eq!(handle.events_rx.recv().await, Some(ExExEvent::FinishedHeight(genesis)));
+
+    // unacknowledged notifications are sent again after a reconnect
+    drop(client);
+    let mut client = connect(&path).await?;
+    assert_eq!(client.recv().await?, Some(reverted));
+
+    client.ack(1).await?;
+    let fork_block = BlockNumHash::new(0, handle.genesis.parent_hash);
+    assert_eq!(handle.events_rx.recv().await, Some(ExExEvent::FinishedHeight(fork_block)));
+
+    assert!(!exex.is_finished());
+    Ok(())
+}
@@ -10,6 +10,8 @@
 
 mod finished_height;
 mod notification;
+#[cfg(feature = "remote")]
+pub mod remote;
 
 pub use finished_height::FinishedExExHeight;
 pub use notification::ExExNotification;
@@ -0,0 +1,60 @@
+//! Messages of the protocol spoken between the node and out-of-process `ExEx`s.
+//!
+//! Messages are encoded with `bincode` and sent as frames prefixed with their length as a 4-byte
+//! big-endian integer. The node sends a [`RemoteNotification`] for every [`ExExNotification`],
+//! and the client acknowledges processed notifications in order with a [`RemoteAck`].
+
+use crate::ExExNotification;
+use serde::{Deserialize, Serialize};
+
+/// The maximum length of a frame in bytes.
+///
+/// Both sides must configure their codec with this limit, since a notification can carry the
+/// blocks and state changes of a large chain segment.
+pub const MAX_FRAME_LENGTH: usize = 1024 * 1024 * 1024;
+
+/// A notification sent from the node to an out-of-process `ExEx`.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct RemoteNotification {
+    /// The ID of the notification, increasing monotonically.
+    ///
+    /// Notifications that were not acknowledged before a client disconnected are sent again with
+    /// the same ID to the next client.
+    pub id: u64,
+    /// The notification.
+    pub notification: ExExNotification,
+}
+
+impl RemoteNotification {
+    /// Encodes the notification into a frame payload.
+    pub fn encode(&self) -> Result<Vec<u8>, bincode::Error> {
+        bincode::serialize(self)
+    }
+
+    /// Decodes a notification from a frame payload.
+    pub fn decode(buf: &[u8]) -> Result<Self, bincode::Error> {
+        bincode::deserialize(buf)
+    }
+}
+
+/// An acknowledgment sent from an out-of-process `ExEx` to the node.
+///
+/// Acknowledges that all notifications up to and including the given ID have been processed, which
+/// lets the node prune the blocks of these notifications.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct RemoteAck {
+    /// The ID of the last processed notification.
+    pub id: u64,
+}
+
+impl RemoteAck {
+    /// Encodes the acknowledgment into a frame payload.
+    pub fn encode(&self) -> Result<Vec<u8>, bincode::Error> {
+        bincode::serialize(self)
+    }
+
+    /// Decodes an acknowledgment from a frame payload.
+    pub fn decode(buf: &[u8]) -> Result<Self, bincode::Error> {
+        bincode::deserialize(buf)
+    }
+}
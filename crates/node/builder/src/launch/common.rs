@@ -7,7 +7,7 @@ use crate::{
 };
 use eyre::Context;
 use rayon::ThreadPoolBuilder;
-use reth_auto_seal_consensus::MiningMode;
+use reth_auto_seal_consensus::{AutoSealExecutorProvider, AutoSealOverrides, MiningMode};
 use reth_beacon_consensus::EthBeaconConsensus;
 use reth_blockchain_tree::{
     BlockchainTree, BlockchainTreeConfig, ShareableBlockchainTree, TreeExternals,
@@ -18,7 +18,7 @@ use reth_consensus::Consensus;
 use reth_db_api::{database::Database, database_metrics::DatabaseMetrics};
 use reth_db_common::init::{init_genesis, InitDatabaseError};
 use reth_downloaders::{bodies::noop::NoopBodiesDownloader, headers::noop::NoopHeaderDownloader};
-use reth_evm::noop::NoopBlockExecutorProvider;
+use reth_evm::{either::Either, noop::NoopBlockExecutorProvider};
 use reth_network_p2p::headers::client::HeadersClient;
 use reth_node_api::FullNodeTypes;
 use reth_node_core::{
@@ -390,6 +390,7 @@ where
             StaticFileProvider::read_write(self.data_dir().static_files())?,
         )
         .with_prune_modes(self.prune_modes())
+        .with_revert_state_cache_size(self.node_config().rpc.rpc_eth_proof_cache_size)
         .with_static_files_metrics();
 
         let has_receipt_pruning =
@@ -664,11 +665,19 @@ where
 
         let consensus: Arc<dyn Consensus> = Arc::new(components.consensus().clone());
 
-        let tree_externals = TreeExternals::new(
-            self.provider_factory().clone(),
-            consensus.clone(),
-            components.block_executor().clone(),
-        );
+        // blocks sealed by the dev miner may include state overrides, which the tree must apply
+        // when it executes them. Outside of dev mode no block has overrides.
+        let auto_seal_overrides = AutoSealOverrides::default();
+        let executor = if self.is_dev() {
+            Either::Right(AutoSealExecutorProvider::new(
+                components.block_executor().clone(),
+                auto_seal_overrides.clone(),
+            ))
+        } else {
+            Either::Left(components.block_executor().clone())
+        };
+        let tree_externals =
+            TreeExternals::new(self.provider_factory().clone(), consensus.clone(), executor);
         let tree = BlockchainTree::new(tree_externals, *self.tree_config(), self.prune_modes())?
             .with_sync_metrics_tx(self.sync_metrics_tx())
             // Note: This is required because we need to ensure that both the components and the
@@ -702,6 +711,7 @@ where
             node_adapter,
             head,
             consensus,
+            auto_seal_overrides,
         };
 
         let ctx = LaunchContextWith {
@@ -759,6 +769,11 @@ where
         &self.right().blockchain_db
     }
 
+    /// Returns the state overrides of blocks sealed by the dev miner.
+    pub const fn auto_seal_overrides(&self) -> &AutoSealOverrides {
+        &self.right().auto_seal_overrides
+    }
+
     /// Returns the initial backfill to sync to at launch.
     ///
     /// This returns the configured `debug.tip` if set, otherwise it will check if backfill was
@@ -942,6 +957,7 @@ where
     node_adapter: NodeAdapter<T, CB::Components>,
     head: Head,
     consensus: Arc<dyn Consensus>,
+    auto_seal_overrides: AutoSealOverrides,
 }
 
 #[cfg(test)]
@@ -963,8 +979,10 @@ mod tests {
     fn test_save_prune_config() {
         with_tempdir("prune-store-test", |config_path| {
             let mut reth_config = Config::default();
-            let node_config =
-                NodeConfig { pruning: PruningArgs { full: true }, ..NodeConfig::test() };
+            let node_config = NodeConfig {
+                pruning: PruningArgs { full: true, ..Default::default() },
+                ..NodeConfig::test()
+            };
             LaunchContext::save_pruning_config_if_full_node(
                 &mut reth_config,
                 &node_config,
@@ -20,6 +20,7 @@ use reth_engine_tree::{
     engine::{EngineApiRequest, EngineRequestHandler},
     tree::TreeConfig,
 };
+use reth_evm::either::Either as EvmEither;
 use reth_exex::ExExManagerHandle;
 use reth_network::{NetworkSyncUpdater, SyncState};
 use reth_network_api::{BlockDownloaderProvider, NetworkEventListenerProvider};
@@ -32,12 +33,14 @@ use reth_node_core::{
     version::{CARGO_PKG_VERSION, CLIENT_CODE, NAME_CLIENT, VERGEN_GIT_SHA},
 };
 use reth_node_events::{cl::ConsensusLayerHealthEvents, node};
+use reth_primitives::format_ether;
 use reth_provider::providers::BlockchainProvider2;
 use reth_rpc_engine_api::{capabilities::EngineCapabilities, EngineApi};
 use reth_rpc_types::engine::ClientVersionV1;
 use reth_tasks::TaskExecutor;
 use reth_tokio_util::EventSender;
 use reth_tracing::tracing::{debug, error, info};
+use reth_transaction_pool::TransactionPool;
 use tokio::sync::{mpsc::unbounded_channel, oneshot};
 use tokio_stream::wrappers::UnboundedReceiverStream;
 
@@ -127,7 +130,7 @@ where
             ctx.configs().clone(),
         )
         .launch()
-        .await;
+        .await?;
 
         // create pipeline
         let network_client = ctx.components().network().fetch_client().await?;
@@ -147,19 +150,78 @@ where
         // Configure the pipeline
         let pipeline_exex_handle =
             exex_manager_handle.clone().unwrap_or_else(ExExManagerHandle::empty);
-        let pipeline = build_networked_pipeline(
-            &ctx.toml_config().stages,
-            network_client.clone(),
-            ctx.consensus(),
-            ctx.provider_factory().clone(),
-            ctx.task_executor(),
-            ctx.sync_metrics_tx(),
-            ctx.prune_config(),
-            max_block,
-            static_file_producer,
-            ctx.components().block_executor().clone(),
-            pipeline_exex_handle,
-        )?;
+        let (pipeline, client, executor, auto_seal) = if ctx.is_dev() {
+            info!(target: "reth::cli", "Starting Reth in dev mode");
+
+            for (idx, (address, alloc)) in ctx.chain_spec().genesis.alloc.iter().enumerate() {
+                info!(target: "reth::cli", "Allocated Genesis Account: {:02}. {} ({} ETH)", idx, address.to_string(), format_ether(alloc.balance));
+            }
+
+            // install auto-seal
+            let mining_mode =
+                ctx.dev_mining_mode(ctx.components().pool().pending_transactions_listener());
+            info!(target: "reth::cli", mode=%mining_mode, "configuring dev mining mode");
+
+            let (_, client, mut task) = reth_auto_seal_consensus::AutoSealBuilder::new(
+                ctx.chain_spec(),
+                ctx.blockchain_db().clone(),
+                ctx.components().pool().clone(),
+                consensus_engine_tx.clone(),
+                mining_mode,
+                ctx.components().block_executor().clone(),
+            )
+            .overrides(ctx.auto_seal_overrides().clone())
+            .build();
+
+            // sealed blocks may include state overrides, which the engine must apply when it
+            // executes them
+            let executor = reth_auto_seal_consensus::AutoSealExecutorProvider::new(
+                ctx.components().block_executor().clone(),
+                ctx.auto_seal_overrides().clone(),
+            );
+
+            let pipeline = build_networked_pipeline(
+                &ctx.toml_config().stages,
+                client.clone(),
+                ctx.consensus(),
+                ctx.provider_factory().clone(),
+                ctx.task_executor(),
+                ctx.sync_metrics_tx(),
+                ctx.prune_config(),
+                max_block,
+                static_file_producer,
+                executor.clone(),
+                pipeline_exex_handle,
+            )?;
+
+            task.set_pipeline_events(pipeline.events());
+            let auto_seal = task.handle();
+            debug!(target: "reth::cli", "Spawning auto mine task");
+            ctx.task_executor().spawn(Box::pin(task));
+
+            (pipeline, Either::Left(client), EvmEither::Right(executor), Some(auto_seal))
+        } else {
+            let pipeline = build_networked_pipeline(
+                &ctx.toml_config().stages,
+                network_client.clone(),
+                ctx.consensus(),
+                ctx.provider_factory().clone(),
+                ctx.task_executor(),
+                ctx.sync_metrics_tx(),
+                ctx.prune_config(),
+                max_block,
+                static_file_producer,
+                ctx.components().block_executor().clone(),
+                pipeline_exex_handle,
+            )?;
+
+            (
+                pipeline,
+                Either::Right(network_client.clone()),
+                EvmEither::Left(ctx.components().block_executor().clone()),
+                None,
+            )
+        };
 
         let pipeline_events = pipeline.events();
 
@@ -176,9 +238,9 @@ where
         // Configure the consensus engine
         let mut eth_service = EngineService::new(
             ctx.consensus(),
-            ctx.components().block_executor().clone(),
+            executor,
             ctx.chain_spec(),
-            network_client.clone(),
+            client,
             UnboundedReceiverStream::new(consensus_engine_rx),
             pipeline,
             Box::new(ctx.task_executor().clone()),
@@ -186,7 +248,8 @@ where
             ctx.blockchain_db().clone(),
             pruner,
             ctx.components().payload_builder().clone(),
-            TreeConfig::default(),
+            TreeConfig::default()
+                .with_state_root_task(ctx.node_config().engine.state_root_task_enabled),
         );
 
         let event_sender = EventSender::default();
@@ -248,6 +311,8 @@ where
             ctx.node_config(),
             jwt_secret,
             rpc,
+            ctx.consensus(),
+            auto_seal,
         )
         .await?;
 
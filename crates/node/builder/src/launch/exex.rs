@@ -2,7 +2,7 @@
 
 use crate::{common::WithConfigs, exex::BoxedLaunchExEx};
 use futures::future;
-use reth_exex::{ExExContext, ExExHandle, ExExManager, ExExManagerHandle};
+use reth_exex::{wal::Wal, ExExContext, ExExHandle, ExExManager, ExExManagerHandle};
 use reth_node_api::FullNodeComponents;
 use reth_primitives::Head;
 use reth_provider::CanonStateSubscriptions;
@@ -32,12 +32,12 @@ impl<Node: FullNodeComponents + Clone> ExExLauncher<Node> {
     ///
     /// Spawns all extensions and returns the handle to the exex manager if any extensions are
     /// installed.
-    pub async fn launch(self) -> Option<ExExManagerHandle> {
+    pub async fn launch(self) -> eyre::Result<Option<ExExManagerHandle>> {
         let Self { head, extensions, components, config_container } = self;
 
         if extensions.is_empty() {
             // nothing to launch
-            return None
+            return Ok(None)
         }
 
         let mut exex_handles = Vec::with_capacity(extensions.len());
@@ -83,7 +83,11 @@ impl<Node: FullNodeComponents + Clone> ExExLauncher<Node> {
         // spawn exex manager
         debug!(target: "reth::cli", "spawning exex manager");
         // todo(onbjerg): rm magic number
-        let exex_manager = ExExManager::new(exex_handles, 1024);
+        let mut exex_manager = ExExManager::new(exex_handles, 1024);
+        if config_container.toml_config.exex.wal {
+            let wal = Wal::new(config_container.config.datadir().exex_wal())?;
+            exex_manager = exex_manager.with_wal(wal)?;
+        }
         let exex_manager_handle = exex_manager.handle();
         components.task_executor().spawn_critical("exex manager", async move {
             exex_manager.await.expect("exex manager crashed");
@@ -106,7 +110,7 @@ impl<Node: FullNodeComponents + Clone> ExExLauncher<Node> {
 
         info!(target: "reth::cli", "ExEx Manager started");
 
-        Some(exex_manager_handle)
+        Ok(Some(exex_manager_handle))
     }
 }
 
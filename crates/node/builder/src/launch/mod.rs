@@ -174,7 +174,7 @@ where
             ctx.configs().clone(),
         )
         .launch()
-        .await;
+        .await?;
 
         // create pipeline
         let network_client = ctx.components().network().fetch_client().await?;
@@ -209,7 +209,7 @@ where
         // Configure the pipeline
         let pipeline_exex_handle =
             exex_manager_handle.clone().unwrap_or_else(ExExManagerHandle::empty);
-        let (pipeline, client) = if ctx.is_dev() {
+        let (pipeline, client, auto_seal) = if ctx.is_dev() {
             info!(target: "reth::cli", "Starting Reth in dev mode");
 
             for (idx, (address, alloc)) in ctx.chain_spec().genesis.alloc.iter().enumerate() {
@@ -229,6 +229,7 @@ where
                 mining_mode,
                 ctx.components().block_executor().clone(),
             )
+            .overrides(ctx.auto_seal_overrides().clone())
             .build();
 
             let pipeline = crate::setup::build_networked_pipeline(
@@ -241,16 +242,20 @@ where
                 ctx.prune_config(),
                 max_block,
                 static_file_producer,
-                ctx.components().block_executor().clone(),
+                reth_auto_seal_consensus::AutoSealExecutorProvider::new(
+                    ctx.components().block_executor().clone(),
+                    ctx.auto_seal_overrides().clone(),
+                ),
                 pipeline_exex_handle,
             )?;
 
             let pipeline_events = pipeline.events();
             task.set_pipeline_events(pipeline_events);
+            let auto_seal = task.handle();
             debug!(target: "reth::cli", "Spawning auto mine task");
             ctx.task_executor().spawn(Box::pin(task));
 
-            (pipeline, Either::Left(client))
+            (pipeline, Either::Left(client), Some(auto_seal))
         } else {
             let pipeline = crate::setup::build_networked_pipeline(
                 &ctx.toml_config().stages,
@@ -266,7 +271,7 @@ where
                 pipeline_exex_handle,
             )?;
 
-            (pipeline, Either::Right(network_client.clone()))
+            (pipeline, Either::Right(network_client.clone()), None)
         };
 
         let pipeline_events = pipeline.events();
@@ -353,6 +358,8 @@ where
             ctx.node_config(),
             jwt_secret,
             rpc,
+            ctx.consensus(),
+            auto_seal,
         )
         .await?;
 
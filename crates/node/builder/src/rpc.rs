@@ -3,19 +3,28 @@
 use std::{
     fmt,
     ops::{Deref, DerefMut},
+    sync::Arc,
 };
 
 use futures::TryFutureExt;
+use reth_auto_seal_consensus::AutoSealHandle;
+use reth_consensus::Consensus;
 use reth_node_api::{BuilderProvider, FullNodeComponents};
 use reth_node_core::{
     node_config::NodeConfig,
-    rpc::{api::EngineApiServer, eth::FullEthApiServer},
+    rpc::{
+        api::{
+            AnvilApiServer, BlockSubmissionValidationApiServer, EngineApiServer, GanacheApiServer,
+        },
+        eth::FullEthApiServer,
+    },
 };
 use reth_payload_builder::PayloadBuilderHandle;
+use reth_rpc::{AnvilApi, ValidationApi};
 use reth_rpc_builder::{
     auth::{AuthRpcModule, AuthServerHandle},
     config::RethRpcServerConfig,
-    RpcModuleBuilder, RpcRegistryInner, RpcServerHandle, TransportRpcModules,
+    RethRpcModule, RpcModuleBuilder, RpcRegistryInner, RpcServerHandle, TransportRpcModules,
 };
 use reth_rpc_layer::JwtSecret;
 use reth_tasks::TaskExecutor;
@@ -251,12 +260,17 @@ impl<'a, Node: FullNodeComponents, EthApi> RpcContext<'a, Node, EthApi> {
 }
 
 /// Launch the rpc servers.
+///
+/// The [`RethRpcModule::Anvil`] module is only installed if the handle to the dev miner is given,
+/// which only the legacy [`DefaultNodeLauncher`](crate::DefaultNodeLauncher) provides.
 pub async fn launch_rpc_servers<Node, Engine, EthApi>(
     node: Node,
     engine_api: Engine,
     config: &NodeConfig,
     jwt_secret: JwtSecret,
     add_ons: RpcAddOns<Node, EthApi>,
+    consensus: Arc<dyn Consensus>,
+    auto_seal: Option<AutoSealHandle>,
 ) -> eyre::Result<(RethRpcServerHandles, RpcRegistry<Node, EthApi>)>
 where
     EthApi: EthApiBuilderProvider<Node> + FullEthApiServer,
@@ -276,6 +290,26 @@ where
         .with_evm_config(node.evm_config().clone())
         .build_with_auth_server(module_config, engine_api, EthApi::eth_api_builder());
 
+    // block submissions are validated with the node's own executor and consensus
+    let validation = ValidationApi::new(
+        node.provider().clone(),
+        consensus,
+        node.block_executor().clone(),
+        Box::new(node.task_executor().clone()),
+    );
+    modules.merge_if_module_configured(RethRpcModule::Flashbots, validation.into_rpc())?;
+
+    // the anvil namespace controls the dev miner, so it can only be installed on dev nodes
+    if let Some(auto_seal) = auto_seal {
+        let anvil = AnvilApi::new(auto_seal, registry.eth_api().clone());
+        modules.merge_if_module_configured(
+            RethRpcModule::Anvil,
+            AnvilApiServer::into_rpc(anvil.clone()),
+        )?;
+        modules
+            .merge_if_module_configured(RethRpcModule::Anvil, GanacheApiServer::into_rpc(anvil))?;
+    }
+
     let mut registry = RpcRegistry { registry };
     let ctx = RpcContext {
         node: node.clone(),
@@ -6,6 +6,7 @@ use clap::{
     error::ErrorKind,
     Arg, Args, Command, Error,
 };
+use reth_db::DatabaseBackend;
 use reth_storage_errors::db::LogLevel;
 
 /// Parameters for database configuration
@@ -19,6 +20,10 @@ pub struct DatabaseArgs {
     /// NFS volume.
     #[arg(long = "db.exclusive")]
     pub exclusive: Option<bool>,
+    /// Embedded database storing the tables. The backend of an existing database can't be
+    /// changed.
+    #[arg(long = "db.backend", value_enum, default_value_t)]
+    pub backend: DatabaseBackend,
 }
 
 impl DatabaseArgs {
@@ -132,6 +137,17 @@ mod tests {
         assert!(result.is_err());
     }
 
+    #[test]
+    fn test_command_parser_with_backend() {
+        let cmd = CommandParser::<DatabaseArgs>::try_parse_from(["reth", "--db.backend", "redb"])
+            .unwrap();
+        assert_eq!(cmd.args.backend, DatabaseBackend::Redb);
+
+        let result =
+            CommandParser::<DatabaseArgs>::try_parse_from(["reth", "--db.backend", "invalid"]);
+        assert!(result.is_err());
+    }
+
     #[test]
     fn test_command_parser_without_log_level() {
         let cmd = CommandParser::<DatabaseArgs>::try_parse_from(["reth"]).unwrap();
@@ -0,0 +1,38 @@
+//! clap [Args](clap::Args) for engine configuration
+
+use clap::Args;
+
+/// Parameters for configuring the engine tree
+#[derive(Debug, Clone, Copy, Args, PartialEq, Eq, Default)]
+#[command(next_help_heading = "Engine")]
+pub struct EngineTreeArgs {
+    /// Compute the state root in a background task while the block executes and use it instead
+    /// of the sequential state root.
+    ///
+    /// Only used by the experimental engine.
+    #[arg(long = "engine.state-root-task", default_value_t = false)]
+    pub state_root_task_enabled: bool,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use clap::Parser;
+
+    /// A helper type to parse Args more easily
+    #[derive(Parser)]
+    struct CommandParser<T: Args> {
+        #[command(flatten)]
+        args: T,
+    }
+
+    #[test]
+    fn test_parse_engine_args() {
+        let args = CommandParser::<EngineTreeArgs>::parse_from(["reth"]).args;
+        assert_eq!(args, EngineTreeArgs::default());
+
+        let args =
+            CommandParser::<EngineTreeArgs>::parse_from(["reth", "--engine.state-root-task"]).args;
+        assert!(args.state_root_task_enabled);
+    }
+}
@@ -40,6 +40,10 @@ pub use gas_price_oracle::GasPriceOracleArgs;
 mod txpool;
 pub use txpool::TxPoolArgs;
 
+/// EngineTreeArgs for configuring the engine tree
+mod engine;
+pub use engine::EngineTreeArgs;
+
 /// DevArgs for configuring the dev testnet
 mod dev;
 pub use dev::DevArgs;
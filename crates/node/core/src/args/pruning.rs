@@ -1,9 +1,12 @@
 //! Pruning and full node arguments
 
-use clap::Args;
+use clap::{builder::RangedU64ValueParser, Args};
+use humantime::parse_duration;
 use reth_chainspec::ChainSpec;
 use reth_config::config::PruneConfig;
+use reth_primitives::BlockNumber;
 use reth_prune_types::{PruneMode, PruneModes, ReceiptsLogPruneConfig, MINIMUM_PRUNING_DISTANCE};
+use std::time::Duration;
 
 /// Parameters for pruning and full node
 #[derive(Debug, Clone, Args, PartialEq, Eq, Default)]
@@ -13,16 +16,211 @@ pub struct PruningArgs {
     /// This flag takes priority over pruning configuration in reth.toml.
     #[arg(long, default_value_t = false)]
     pub full: bool,
+
+    /// Prune all transaction senders.
+    #[arg(long = "prune.senderrecovery.full", group = "sender_recovery")]
+    pub sender_recovery_full: bool,
+    /// Prune transaction senders before the `head-N` block number. In other words, keep the
+    /// senders of the last N + 1 blocks.
+    #[arg(
+        long = "prune.senderrecovery.distance",
+        value_name = "BLOCKS",
+        group = "sender_recovery"
+    )]
+    pub sender_recovery_distance: Option<u64>,
+    /// Prune transaction senders before the specified block number. The specified block number
+    /// is not pruned.
+    #[arg(
+        long = "prune.senderrecovery.before",
+        value_name = "BLOCK_NUMBER",
+        group = "sender_recovery"
+    )]
+    pub sender_recovery_before: Option<BlockNumber>,
+    /// Prune transaction senders of blocks older than the specified duration, e.g. `30d`.
+    #[arg(
+        long = "prune.senderrecovery.duration",
+        value_name = "DURATION",
+        value_parser = parse_duration,
+        group = "sender_recovery"
+    )]
+    pub sender_recovery_duration: Option<Duration>,
+
+    /// Prune all transaction lookup entries.
+    #[arg(long = "prune.transactionlookup.full", group = "transaction_lookup")]
+    pub transaction_lookup_full: bool,
+    /// Prune transaction lookup entries before the `head-N` block number. In other words, keep
+    /// the entries of the last N + 1 blocks.
+    #[arg(
+        long = "prune.transactionlookup.distance",
+        value_name = "BLOCKS",
+        group = "transaction_lookup"
+    )]
+    pub transaction_lookup_distance: Option<u64>,
+    /// Prune transaction lookup entries before the specified block number. The specified block
+    /// number is not pruned.
+    #[arg(
+        long = "prune.transactionlookup.before",
+        value_name = "BLOCK_NUMBER",
+        group = "transaction_lookup"
+    )]
+    pub transaction_lookup_before: Option<BlockNumber>,
+    /// Prune transaction lookup entries of blocks older than the specified duration, e.g. `30d`.
+    #[arg(
+        long = "prune.transactionlookup.duration",
+        value_name = "DURATION",
+        value_parser = parse_duration,
+        group = "transaction_lookup"
+    )]
+    pub transaction_lookup_duration: Option<Duration>,
+
+    /// Prune receipts before the `head-N` block number. In other words, keep the receipts of the
+    /// last N + 1 blocks. Needs to be at least [`MINIMUM_PRUNING_DISTANCE`].
+    #[arg(
+        long = "prune.receipts.distance",
+        value_name = "BLOCKS",
+        value_parser = RangedU64ValueParser::<u64>::new().range(MINIMUM_PRUNING_DISTANCE..),
+        group = "receipts"
+    )]
+    pub receipts_distance: Option<u64>,
+    /// Prune receipts before the specified block number. The specified block number is not
+    /// pruned.
+    #[arg(long = "prune.receipts.before", value_name = "BLOCK_NUMBER", group = "receipts")]
+    pub receipts_before: Option<BlockNumber>,
+    /// Prune receipts of blocks older than the specified duration, e.g. `30d`. At least the
+    /// last [`MINIMUM_PRUNING_DISTANCE`] blocks are kept, so the duration needs to be at least
+    /// [`MINIMUM_PRUNING_DISTANCE`] seconds.
+    #[arg(
+        long = "prune.receipts.duration",
+        value_name = "DURATION",
+        value_parser = parse_min_pruning_distance_duration,
+        group = "receipts"
+    )]
+    pub receipts_duration: Option<Duration>,
+
+    /// Prune account history before the `head-N` block number. In other words, keep the history
+    /// of the last N + 1 blocks. Needs to be at least [`MINIMUM_PRUNING_DISTANCE`].
+    #[arg(
+        long = "prune.accounthistory.distance",
+        value_name = "BLOCKS",
+        value_parser = RangedU64ValueParser::<u64>::new().range(MINIMUM_PRUNING_DISTANCE..),
+        group = "account_history"
+    )]
+    pub account_history_distance: Option<u64>,
+    /// Prune account history before the specified block number. The specified block number is
+    /// not pruned.
+    #[arg(
+        long = "prune.accounthistory.before",
+        value_name = "BLOCK_NUMBER",
+        group = "account_history"
+    )]
+    pub account_history_before: Option<BlockNumber>,
+    /// Prune account history of blocks older than the specified duration, e.g. `30d`. At least the
+    /// last [`MINIMUM_PRUNING_DISTANCE`] blocks are kept, so the duration needs to be at least
+    /// [`MINIMUM_PRUNING_DISTANCE`] seconds.
+    #[arg(
+        long = "prune.accounthistory.duration",
+        value_name = "DURATION",
+        value_parser = parse_min_pruning_distance_duration,
+        group = "account_history"
+    )]
+    pub account_history_duration: Option<Duration>,
+
+    /// Prune storage history before the `head-N` block number. In other words, keep the history
+    /// of the last N + 1 blocks. Needs to be at least [`MINIMUM_PRUNING_DISTANCE`].
+    #[arg(
+        long = "prune.storagehistory.distance",
+        value_name = "BLOCKS",
+        value_parser = RangedU64ValueParser::<u64>::new().range(MINIMUM_PRUNING_DISTANCE..),
+        group = "storage_history"
+    )]
+    pub storage_history_distance: Option<u64>,
+    /// Prune storage history before the specified block number. The specified block number is
+    /// not pruned.
+    #[arg(
+        long = "prune.storagehistory.before",
+        value_name = "BLOCK_NUMBER",
+        group = "storage_history"
+    )]
+    pub storage_history_before: Option<BlockNumber>,
+    /// Prune storage history of blocks older than the specified duration, e.g. `30d`. At least the
+    /// last [`MINIMUM_PRUNING_DISTANCE`] blocks are kept, so the duration needs to be at least
+    /// [`MINIMUM_PRUNING_DISTANCE`] seconds.
+    #[arg(
+        long = "prune.storagehistory.duration",
+        value_name = "DURATION",
+        value_parser = parse_min_pruning_distance_duration,
+        group = "storage_history"
+    )]
+    pub storage_history_duration: Option<Duration>,
 }
 
 impl PruningArgs {
     /// Returns pruning configuration.
+    ///
+    /// The segments configured with `--prune.*` arguments override the ones of `--full`.
     pub fn prune_config(&self, chain_spec: &ChainSpec) -> Option<PruneConfig> {
-        if !self.full {
-            return None
+        let mut config =
+            if self.full { Self::full_prune_config(chain_spec) } else { Default::default() };
+
+        let segments = &mut config.segments;
+        for (segment, mode) in [
+            (
+                &mut segments.sender_recovery,
+                prune_mode(
+                    self.sender_recovery_full,
+                    self.sender_recovery_distance,
+                    self.sender_recovery_before,
+                    self.sender_recovery_duration,
+                ),
+            ),
+            (
+                &mut segments.transaction_lookup,
+                prune_mode(
+                    self.transaction_lookup_full,
+                    self.transaction_lookup_distance,
+                    self.transaction_lookup_before,
+                    self.transaction_lookup_duration,
+                ),
+            ),
+            (
+                &mut segments.receipts,
+                prune_mode(
+                    false,
+                    self.receipts_distance,
+                    self.receipts_before,
+                    self.receipts_duration,
+                ),
+            ),
+            (
+                &mut segments.account_history,
+                prune_mode(
+                    false,
+                    self.account_history_distance,
+                    self.account_history_before,
+                    self.account_history_duration,
+                ),
+            ),
+            (
+                &mut segments.storage_history,
+                prune_mode(
+                    false,
+                    self.storage_history_distance,
+                    self.storage_history_before,
+                    self.storage_history_duration,
+                ),
+            ),
+        ] {
+            if mode.is_some() {
+                *segment = mode;
+            }
         }
 
-        Some(PruneConfig {
+        (!config.segments.is_empty()).then_some(config)
+    }
+
+    /// Returns the pruning configuration of a full node.
+    fn full_prune_config(chain_spec: &ChainSpec) -> PruneConfig {
+        PruneConfig {
             block_interval: 5,
             segments: PruneModes {
                 sender_recovery: Some(PruneMode::Full),
@@ -35,6 +233,9 @@ impl PruningArgs {
                     .or(Some(PruneMode::Full)),
                 account_history: Some(PruneMode::Distance(MINIMUM_PRUNING_DISTANCE)),
                 storage_history: Some(PruneMode::Distance(MINIMUM_PRUNING_DISTANCE)),
+                account_history_filter: Default::default(),
+                storage_history_filter: Default::default(),
+                bytecodes: None,
                 receipts_log_filter: ReceiptsLogPruneConfig(
                     chain_spec
                         .deposit_contract
@@ -44,10 +245,39 @@ impl PruningArgs {
                         .collect(),
                 ),
             },
-        })
+        }
     }
 }
 
+/// Returns the prune mode set by one of the `--prune.<segment>.*` arguments, if any.
+fn prune_mode(
+    full: bool,
+    distance: Option<u64>,
+    before: Option<BlockNumber>,
+    duration: Option<Duration>,
+) -> Option<PruneMode> {
+    if full {
+        return Some(PruneMode::Full)
+    }
+    distance
+        .map(PruneMode::Distance)
+        .or_else(|| before.map(PruneMode::Before))
+        .or_else(|| duration.map(|duration| PruneMode::Duration(duration.as_secs())))
+}
+
+/// Parses a duration of at least [`MINIMUM_PRUNING_DISTANCE`] seconds, because block timestamps
+/// are strictly increasing, so a shorter one never covers [`MINIMUM_PRUNING_DISTANCE`] blocks.
+fn parse_min_pruning_distance_duration(value: &str) -> Result<Duration, String> {
+    let duration = parse_duration(value).map_err(|err| err.to_string())?;
+    if duration.as_secs() < MINIMUM_PRUNING_DISTANCE {
+        return Err(format!(
+            "duration needs to be at least {MINIMUM_PRUNING_DISTANCE} seconds, to keep at least \
+             {MINIMUM_PRUNING_DISTANCE} blocks"
+        ))
+    }
+    Ok(duration)
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -66,4 +296,74 @@ mod tests {
         let args = CommandParser::<PruningArgs>::parse_from(["reth"]).args;
         assert_eq!(args, default_args);
     }
+
+    #[test]
+    fn parse_prune_modes() {
+        let chain_spec = ChainSpec::default();
+
+        let args = CommandParser::<PruningArgs>::parse_from([
+            "reth",
+            "--prune.senderrecovery.full",
+            "--prune.transactionlookup.before",
+            "100",
+            "--prune.receipts.duration",
+            "30d",
+            "--prune.accounthistory.distance",
+            "20000",
+        ])
+        .args;
+        assert_eq!(
+            args.prune_config(&chain_spec).map(|config| config.segments),
+            Some(PruneModes {
+                sender_recovery: Some(PruneMode::Full),
+                transaction_lookup: Some(PruneMode::Before(100)),
+                receipts: Some(PruneMode::Duration(30 * 24 * 60 * 60)),
+                account_history: Some(PruneMode::Distance(20000)),
+                ..PruneModes::none()
+            })
+        );
+
+        // Segment arguments override the full node configuration
+        let args = CommandParser::<PruningArgs>::parse_from([
+            "reth",
+            "--full",
+            "--prune.storagehistory.duration",
+            "1week",
+        ])
+        .args;
+        let config = args.prune_config(&chain_spec).unwrap();
+        assert_eq!(config.segments.storage_history, Some(PruneMode::Duration(7 * 24 * 60 * 60)));
+        assert_eq!(
+            config.segments.account_history,
+            Some(PruneMode::Distance(MINIMUM_PRUNING_DISTANCE))
+        );
+
+        assert_eq!(
+            CommandParser::<PruningArgs>::parse_from(["reth"]).args.prune_config(&chain_spec),
+            None
+        );
+        // Only one prune mode per segment
+        assert!(CommandParser::<PruningArgs>::try_parse_from([
+            "reth",
+            "--prune.receipts.before",
+            "100",
+            "--prune.receipts.duration",
+            "30d",
+        ])
+        .is_err());
+        // Distance of history segments is validated
+        assert!(CommandParser::<PruningArgs>::try_parse_from([
+            "reth",
+            "--prune.accounthistory.distance",
+            "100",
+        ])
+        .is_err());
+        // Duration of history segments is validated
+        assert!(CommandParser::<PruningArgs>::try_parse_from([
+            "reth",
+            "--prune.storagehistory.duration",
+            "1h",
+        ])
+        .is_err());
+    }
 }
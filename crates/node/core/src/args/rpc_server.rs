@@ -12,6 +12,7 @@ use clap::{
     Arg, Args, Command,
 };
 use rand::Rng;
+use reth_provider::providers::DEFAULT_REVERT_STATE_CACHE_SIZE;
 use reth_rpc_server_types::{constants, RethRpcModule, RpcModuleSelection};
 
 use crate::args::{
@@ -159,12 +160,28 @@ pub struct RpcServerArgs {
     /// The maximum proof window for historical proof generation.
     /// This value allows for generating historical proofs up to
     /// configured number of blocks from current tip (up to `tip - window`).
+    ///
+    /// Historical proofs are limited by the retained account and storage history, and the window
+    /// can't exceed `--rpc.max-eth-proof-window`.
+    #[arg(long = "rpc.eth-proof-window", default_value_t = constants::DEFAULT_ETH_PROOF_WINDOW)]
+    pub rpc_eth_proof_window: u64,
+
+    /// The maximum allowed historical proof window.
+    /// Archive nodes can raise it to serve proofs of blocks further back than roughly one month.
     #[arg(
-        long = "rpc.eth-proof-window",
-        default_value_t = constants::DEFAULT_ETH_PROOF_WINDOW,
-        value_parser = RangedU64ValueParser::<u64>::new().range(..=constants::MAX_ETH_PROOF_WINDOW)
+        long = "rpc.max-eth-proof-window",
+        value_name = "BLOCKS",
+        default_value_t = constants::MAX_ETH_PROOF_WINDOW
     )]
-    pub rpc_eth_proof_window: u64,
+    pub rpc_max_eth_proof_window: u64,
+
+    /// Number of revert states of historical blocks cached for historical proofs and state roots.
+    #[arg(
+        long = "rpc.eth-proof-cache-size",
+        value_name = "COUNT",
+        default_value_t = DEFAULT_REVERT_STATE_CACHE_SIZE
+    )]
+    pub rpc_eth_proof_cache_size: usize,
 
     /// Maximum number of concurrent getproof requests.
     #[arg(long = "rpc.proof-permits", alias = "rpc-proof-permits", value_name = "COUNT", default_value_t = constants::DEFAULT_PROOF_PERMITS)]
@@ -301,6 +318,8 @@ impl Default for RpcServerArgs {
             rpc_max_logs_per_response: (constants::DEFAULT_MAX_LOGS_PER_RESPONSE as u64).into(),
             rpc_gas_cap: constants::gas_oracle::RPC_DEFAULT_GAS_CAP,
             rpc_eth_proof_window: constants::DEFAULT_ETH_PROOF_WINDOW,
+            rpc_max_eth_proof_window: constants::MAX_ETH_PROOF_WINDOW,
+            rpc_eth_proof_cache_size: DEFAULT_REVERT_STATE_CACHE_SIZE,
             gas_price_oracle: GasPriceOracleArgs::default(),
             rpc_state_cache: RpcStateCacheArgs::default(),
             rpc_proof_permits: constants::DEFAULT_PROOF_PERMITS,
@@ -396,4 +415,22 @@ mod tests {
 
         assert_eq!(args, default_args);
     }
+
+    #[test]
+    fn test_rpc_server_eth_proof_window_args() {
+        let args = CommandParser::<RpcServerArgs>::parse_from([
+            "reth",
+            "--rpc.eth-proof-window",
+            "1000000",
+            "--rpc.max-eth-proof-window",
+            "1000000",
+            "--rpc.eth-proof-cache-size",
+            "16",
+        ])
+        .args;
+
+        assert_eq!(args.rpc_eth_proof_window, 1_000_000);
+        assert_eq!(args.rpc_max_eth_proof_window, 1_000_000);
+        assert_eq!(args.rpc_eth_proof_cache_size, 16);
+    }
 }
@@ -323,6 +323,13 @@ impl<D> ChainPath<D> {
         self.data_dir().join("blobstore")
     }
 
+    /// Returns the path to the write-ahead log of `ExEx` notifications for this chain.
+    ///
+    /// `<DIR>/<CHAIN_ID>/exex/wal`
+    pub fn exex_wal(&self) -> PathBuf {
+        self.data_dir().join("exex").join("wal")
+    }
+
     /// Returns the path to the local transactions backup file
     ///
     /// `<DIR>/<CHAIN_ID>/txpool-transactions-backup.rlp`
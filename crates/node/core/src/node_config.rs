@@ -2,8 +2,8 @@
 
 use crate::{
     args::{
-        DatabaseArgs, DatadirArgs, DebugArgs, DevArgs, NetworkArgs, PayloadBuilderArgs,
-        PruningArgs, RpcServerArgs, TxPoolArgs,
+        DatabaseArgs, DatadirArgs, DebugArgs, DevArgs, EngineTreeArgs, NetworkArgs,
+        PayloadBuilderArgs, PruningArgs, RpcServerArgs, TxPoolArgs,
     },
     dirs::{ChainPath, DataDirPath},
     utils::get_single_header,
@@ -124,6 +124,9 @@ pub struct NodeConfig {
 
     /// All pruning related arguments
     pub pruning: PruningArgs,
+
+    /// All engine related arguments
+    pub engine: EngineTreeArgs,
 }
 
 impl NodeConfig {
@@ -225,6 +228,12 @@ impl NodeConfig {
         self
     }
 
+    /// Set the engine args for the node
+    pub const fn with_engine(mut self, engine: EngineTreeArgs) -> Self {
+        self.engine = engine;
+        self
+    }
+
     /// Set the pruning args for the node
     pub const fn with_pruning(mut self, pruning: PruningArgs) -> Self {
         self.pruning = pruning;
@@ -382,6 +391,7 @@ impl Default for NodeConfig {
             db: DatabaseArgs::default(),
             dev: DevArgs::default(),
             pruning: PruningArgs::default(),
+            engine: EngineTreeArgs::default(),
             datadir: DatadirArgs::default(),
         }
     }
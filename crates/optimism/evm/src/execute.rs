@@ -1,11 +1,12 @@
 //! Optimism block executor.
 
-use crate::{l1::ensure_create2_deployer, OptimismBlockExecutionError, OptimismEvmConfig, parallel};
+use crate::{l1::ensure_create2_deployer, OptimismBlockExecutionError, OptimismEvmConfig};
 use reth_chainspec::{ChainSpec, EthereumHardforks, OptimismHardfork};
 use reth_evm::{
     execute::{
         BatchExecutor, BlockExecutionError, BlockExecutionInput, BlockExecutionOutput,
-        BlockExecutorProvider, BlockValidationError, Executor, ProviderError,
+        BlockExecutorProvider, BlockValidationError, Executor, NoopHook, OnStateHook,
+        ProviderError,
     },
     system_calls::apply_beacon_root_contract_call,
     ConfigureEvm,
@@ -109,13 +110,17 @@ where
     ///
     /// It does __not__ apply post-execution changes.
     /// todo sglk pte here, stay return parameter && state db should change
-    fn execute_pre_and_transactions<Ext, DB>(
+    ///
+    /// The state hook is invoked with the state changes of every transaction.
+    fn execute_pre_and_transactions<Ext, DB, F>(
         &self,
         block: &BlockWithSenders,
         mut evm: Evm<'_, Ext, &mut State<DB>>,
+        mut state_hook: F,
     ) -> Result<(Vec<Receipt>, u64), BlockExecutionError>
     where
         DB: Database<Error: Into<ProviderError> + std::fmt::Display>,
+        F: OnStateHook,
     {
         // apply pre execution changes
         apply_beacon_root_contract_call(
@@ -200,6 +205,7 @@ where
                 "Executed transaction"
             );
          println!("state is {:?}", &state);
+            state_hook.on_state(&state);
             evm.db_mut().commit(state);
 
             // append gas used
@@ -223,7 +229,6 @@ where
                 .then_some(1),
             });
         }
-        // let (receipts, cumulative_gas_used) = parallel::parallel_execute(block, &mut evm, is_regolith)?;   // todo error fix
 
         drop(evm);
 
@@ -294,6 +299,24 @@ where
         block: &BlockWithSenders,
         total_difficulty: U256,
     ) -> Result<(Vec<Receipt>, u64), BlockExecutionError> {
+        self.execute_without_verification_with_state_hook(block, total_difficulty, NoopHook)
+    }
+
+    /// Execute a single block and apply the state changes to the internal state, invoking the
+    /// state hook with the state changes of every transaction.
+    ///
+    /// Returns the receipts of the transactions in the block and the total gas used.
+    ///
+    /// Returns an error if execution fails.
+    fn execute_without_verification_with_state_hook<F>(
+        &mut self,
+        block: &BlockWithSenders,
+        total_difficulty: U256,
+        state_hook: F,
+    ) -> Result<(Vec<Receipt>, u64), BlockExecutionError>
+    where
+        F: OnStateHook,
+    {
         // 1. prepare state on new block
         self.on_new_block(&block.header);
 
@@ -302,7 +325,7 @@ where
 
         let (receipts, gas_used) = {
             let evm = self.executor.evm_config.evm_with_env(&mut self.state, env);
-            self.executor.execute_pre_and_transactions(block, evm)
+            self.executor.execute_pre_and_transactions(block, evm, state_hook)
         }?;
 
         // 3. apply post execution changes
@@ -352,9 +375,21 @@ where
     /// Returns an error if the block could not be executed or failed verification.
     ///
     /// State changes are committed to the database.
-    fn execute(mut self, input: Self::Input<'_>) -> Result<Self::Output, Self::Error> {
+    fn execute(self, input: Self::Input<'_>) -> Result<Self::Output, Self::Error> {
+        self.execute_with_state_hook(input, NoopHook)
+    }
+
+    fn execute_with_state_hook<F>(
+        mut self,
+        input: Self::Input<'_>,
+        state_hook: F,
+    ) -> Result<Self::Output, Self::Error>
+    where
+        F: OnStateHook,
+    {
         let BlockExecutionInput { block, total_difficulty } = input;
-        let (receipts, gas_used) = self.execute_without_verification(block, total_difficulty)?;
+        let (receipts, gas_used) =
+            self.execute_without_verification_with_state_hook(block, total_difficulty, state_hook)?;
 
         // NOTE: we need to merge keep the reverts for the bundle retention
         self.state.merge_transitions(BundleRetention::Reverts);
@@ -1,7 +1,7 @@
 //! Support for pruning.
 
 use crate::{
-    segments::{PruneInput, Segment},
+    segments::{block_timestamp, PruneInput, Segment},
     Metrics, PrunerError, PrunerEvent,
 };
 use alloy_primitives::BlockNumber;
@@ -181,7 +181,13 @@ impl<DB: Database, S> Pruner<DB, S> {
             if let Some((to_block, prune_mode)) = segment
                 .mode()
                 .map(|mode| {
-                    mode.prune_target_block(tip_block_number, segment.segment(), segment.purpose())
+                    mode.resolve_duration(
+                        tip_block_number,
+                        segment.segment().min_blocks(segment.purpose()),
+                        |block_number| block_timestamp(provider, block_number),
+                    )?
+                    .prune_target_block(tip_block_number, segment.segment(), segment.purpose())
+                    .map_err(PrunerError::from)
                 })
                 .transpose()?
                 .flatten()
@@ -199,7 +205,12 @@ impl<DB: Database, S> Pruner<DB, S> {
                 let previous_checkpoint = provider.get_prune_checkpoint(segment.segment())?;
                 let segment_output = segment.prune(
                     provider,
-                    PruneInput { previous_checkpoint, to_block, limiter: limiter.clone() },
+                    PruneInput {
+                        previous_checkpoint,
+                        to_block,
+                        tip_block_number,
+                        limiter: limiter.clone(),
+                    },
                 )?;
                 if let Some(checkpoint) = segment_output.checkpoint {
                     segment
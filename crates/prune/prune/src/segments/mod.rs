@@ -7,21 +7,23 @@ use crate::PrunerError;
 use alloy_primitives::{BlockNumber, TxNumber};
 use reth_db_api::database::Database;
 use reth_provider::{
-    errors::provider::ProviderResult, BlockReader, DatabaseProviderRW, PruneCheckpointWriter,
+    errors::provider::ProviderResult, BlockReader, DatabaseProviderRW, HeaderProvider,
+    ProviderError, PruneCheckpointWriter,
 };
 use reth_prune_types::{
     PruneCheckpoint, PruneLimiter, PruneMode, PrunePurpose, PruneSegment, SegmentOutput,
 };
 pub use set::SegmentSet;
 pub use static_file::{
-    Headers as StaticFileHeaders, Receipts as StaticFileReceipts,
+    AccountChangeSets as StaticFileAccountChangeSets, Headers as StaticFileHeaders,
+    Receipts as StaticFileReceipts, StorageChangeSets as StaticFileStorageChangeSets,
     Transactions as StaticFileTransactions,
 };
 use std::{fmt::Debug, ops::RangeInclusive};
 use tracing::error;
 pub use user::{
-    AccountHistory, Receipts as UserReceipts, ReceiptsByLogs, SenderRecovery, StorageHistory,
-    TransactionLookup,
+    AccountHistory, Bytecodes, Receipts as UserReceipts, ReceiptsByLogs, SenderRecovery,
+    StorageHistory, TransactionLookup,
 };
 
 /// A segment represents a pruning of some portion of the data.
@@ -58,6 +60,17 @@ pub trait Segment<DB: Database>: Debug + Send + Sync {
     }
 }
 
+/// Returns the timestamp of the block, used to resolve [`PruneMode::Duration`].
+pub(crate) fn block_timestamp<DB: Database>(
+    provider: &DatabaseProviderRW<DB>,
+    block_number: BlockNumber,
+) -> ProviderResult<u64> {
+    provider
+        .header_by_number(block_number)?
+        .map(|header| header.timestamp)
+        .ok_or_else(|| ProviderError::HeaderNotFound(block_number.into()))
+}
+
 /// Segment pruning input, see [`Segment::prune`].
 #[derive(Debug)]
 #[cfg_attr(test, derive(Clone))]
@@ -65,6 +78,8 @@ pub struct PruneInput {
     pub(crate) previous_checkpoint: Option<PruneCheckpoint>,
     /// Target block up to which the pruning needs to be done, inclusive.
     pub(crate) to_block: BlockNumber,
+    /// Tip block number the target block of the segment was calculated from.
+    pub(crate) tip_block_number: BlockNumber,
     /// Limits pruning of a segment.
     pub(crate) limiter: PruneLimiter,
 }
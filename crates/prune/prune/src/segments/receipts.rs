@@ -131,6 +131,7 @@ mod tests {
                     .get_prune_checkpoint(PruneSegment::Receipts)
                     .unwrap(),
                 to_block,
+                tip_block_number: to_block,
                 limiter: limiter.clone(),
             };
 
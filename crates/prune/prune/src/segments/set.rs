@@ -1,12 +1,15 @@
 use crate::segments::{
-    AccountHistory, ReceiptsByLogs, Segment, SenderRecovery, StorageHistory, TransactionLookup,
-    UserReceipts,
+    AccountHistory, Bytecodes, ReceiptsByLogs, Segment, SenderRecovery, StorageHistory,
+    TransactionLookup, UserReceipts,
 };
 use reth_db_api::database::Database;
 use reth_provider::providers::StaticFileProvider;
 use reth_prune_types::PruneModes;
 
-use super::{StaticFileHeaders, StaticFileReceipts, StaticFileTransactions};
+use super::{
+    StaticFileAccountChangeSets, StaticFileHeaders, StaticFileReceipts,
+    StaticFileStorageChangeSets, StaticFileTransactions,
+};
 
 /// Collection of [Segment]. Thread-safe, allocated on the heap.
 #[derive(Debug)]
@@ -51,6 +54,9 @@ impl<DB: Database> SegmentSet<DB> {
             receipts,
             account_history,
             storage_history,
+            account_history_filter,
+            storage_history_filter,
+            bytecodes,
             receipts_log_filter,
         } = prune_modes;
 
@@ -60,11 +66,21 @@ impl<DB: Database> SegmentSet<DB> {
             // Static file transactions
             .segment(StaticFileTransactions::new(static_file_provider.clone()))
             // Static file receipts
-            .segment(StaticFileReceipts::new(static_file_provider))
+            .segment(StaticFileReceipts::new(static_file_provider.clone()))
+            // Static file account change sets
+            .segment(StaticFileAccountChangeSets::new(static_file_provider.clone()))
+            // Static file storage change sets
+            .segment(StaticFileStorageChangeSets::new(static_file_provider))
             // Account history
-            .segment_opt(account_history.map(AccountHistory::new))
+            .segment_opt(
+                account_history.map(|mode| AccountHistory::new(mode, account_history_filter)),
+            )
             // Storage history
-            .segment_opt(storage_history.map(StorageHistory::new))
+            .segment_opt(
+                storage_history.map(|mode| StorageHistory::new(mode, storage_history_filter)),
+            )
+            // Bytecodes, after the history segments that release their references
+            .segment_opt(bytecodes.map(Bytecodes::new))
             // User receipts
             .segment_opt(receipts.map(UserReceipts::new))
             // Receipts by logs
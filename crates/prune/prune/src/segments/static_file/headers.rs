@@ -241,6 +241,7 @@ mod tests {
                     .get_prune_checkpoint(PruneSegment::Headers)
                     .unwrap(),
                 to_block,
+                tip_block_number: to_block,
                 limiter: limiter.clone(),
             };
 
@@ -321,6 +322,7 @@ mod tests {
         let input = PruneInput {
             previous_checkpoint: None,
             to_block: 1,
+            tip_block_number: 1,
             // Less than total number of tables for `Headers` segment
             limiter,
         };
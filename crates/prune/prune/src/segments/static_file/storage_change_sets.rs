@@ -0,0 +1,82 @@
+use crate::{
+    segments::{PruneInput, Segment},
+    PrunerError,
+};
+use reth_db::tables;
+use reth_db_api::{database::Database, models::BlockNumberAddress};
+use reth_provider::{providers::StaticFileProvider, DatabaseProviderRW};
+use reth_prune_types::{
+    PruneMode, PruneProgress, PrunePurpose, PruneSegment, SegmentOutput, SegmentOutputCheckpoint,
+};
+use reth_static_file_types::StaticFileSegment;
+use tracing::{instrument, trace};
+
+#[derive(Debug)]
+pub struct StorageChangeSets {
+    static_file_provider: StaticFileProvider,
+}
+
+impl StorageChangeSets {
+    pub const fn new(static_file_provider: StaticFileProvider) -> Self {
+        Self { static_file_provider }
+    }
+}
+
+impl<DB: Database> Segment<DB> for StorageChangeSets {
+    fn segment(&self) -> PruneSegment {
+        PruneSegment::StorageChangeSets
+    }
+
+    fn mode(&self) -> Option<PruneMode> {
+        self.static_file_provider
+            .get_highest_static_file_block(StaticFileSegment::StorageChangeSets)
+            .map(PruneMode::before_inclusive)
+    }
+
+    fn purpose(&self) -> PrunePurpose {
+        PrunePurpose::StaticFile
+    }
+
+    #[instrument(level = "trace", target = "pruner", skip(self, provider), ret)]
+    fn prune(
+        &self,
+        provider: &DatabaseProviderRW<DB>,
+        input: PruneInput,
+    ) -> Result<SegmentOutput, PrunerError> {
+        let range = match input.get_next_block_range() {
+            Some(range) => range,
+            None => {
+                trace!(target: "pruner", "No storage change sets to prune");
+                return Ok(SegmentOutput::done())
+            }
+        };
+        let range_end = *range.end();
+
+        let mut limiter = input.limiter;
+        let mut last_pruned_block = None;
+        let (pruned, done) = provider.prune_table_with_range::<tables::StorageChangeSets>(
+            BlockNumberAddress::range(range),
+            &mut limiter,
+            |_| false,
+            |(BlockNumberAddress((block_number, _)), _)| last_pruned_block = Some(block_number),
+        )?;
+        trace!(target: "pruner", %pruned, %done, "Pruned storage change sets");
+
+        let last_pruned_block = last_pruned_block
+            // If there's more storage change sets to prune, set the checkpoint block number to
+            // previous, so we could finish pruning its storage change sets on the next run.
+            .map(|block_number| if done { block_number } else { block_number.saturating_sub(1) })
+            .unwrap_or(range_end);
+
+        let progress = PruneProgress::new(done, &limiter);
+
+        Ok(SegmentOutput {
+            progress,
+            pruned,
+            checkpoint: Some(SegmentOutputCheckpoint {
+                block_number: Some(last_pruned_block),
+                tx_number: None,
+            }),
+        })
+    }
+}
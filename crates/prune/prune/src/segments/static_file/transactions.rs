@@ -124,6 +124,7 @@ mod tests {
                     .get_prune_checkpoint(PruneSegment::Transactions)
                     .unwrap(),
                 to_block,
+                tip_block_number: to_block,
                 limiter: limiter.clone(),
             };
 
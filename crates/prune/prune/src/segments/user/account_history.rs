@@ -1,16 +1,23 @@
 use crate::{
-    segments::{user::history::prune_history_indices, PruneInput, Segment},
+    segments::{block_timestamp, user::history::prune_history_indices, PruneInput, Segment},
     PrunerError,
 };
+use alloy_primitives::BlockNumber;
 use itertools::Itertools;
 use reth_db::tables;
-use reth_db_api::{database::Database, models::ShardedKey};
+use reth_db_api::{
+    cursor::{DbCursorRO, DbCursorRW, DbDupCursorRO},
+    database::Database,
+    models::ShardedKey,
+    transaction::{DbTx, DbTxMut},
+};
 use reth_provider::DatabaseProviderRW;
 use reth_prune_types::{
-    PruneInterruptReason, PruneMode, PruneProgress, PrunePurpose, PruneSegment, SegmentOutput,
-    SegmentOutputCheckpoint,
+    HistoryPruneConfig, PruneInterruptReason, PruneLimiter, PruneMode, PruneProgress, PrunePurpose,
+    PruneSegment, SegmentOutput, SegmentOutputCheckpoint,
 };
 use rustc_hash::FxHashMap;
+use std::ops::RangeInclusive;
 use tracing::{instrument, trace};
 
 /// Number of account history tables to prune in one step.
@@ -19,14 +26,17 @@ use tracing::{instrument, trace};
 /// [`tables::AccountsHistory`]. We want to prune them to the same block number.
 const ACCOUNT_HISTORY_TABLES_TO_PRUNE: usize = 2;
 
+/// Prunes the account history according to the segment prune mode, except for the addresses in
+/// the filter, which are pruned according to their own prune modes.
 #[derive(Debug)]
 pub struct AccountHistory {
     mode: PruneMode,
+    filter: HistoryPruneConfig,
 }
 
 impl AccountHistory {
-    pub const fn new(mode: PruneMode) -> Self {
-        Self { mode }
+    pub const fn new(mode: PruneMode, filter: HistoryPruneConfig) -> Self {
+        Self { mode, filter }
     }
 }
 
@@ -49,14 +59,11 @@ impl<DB: Database> Segment<DB> for AccountHistory {
         provider: &DatabaseProviderRW<DB>,
         input: PruneInput,
     ) -> Result<SegmentOutput, PrunerError> {
-        let range = match input.get_next_block_range() {
-            Some(range) => range,
-            None => {
-                trace!(target: "pruner", "No account history to prune");
-                return Ok(SegmentOutput::done())
-            }
-        };
-        let range_end = *range.end();
+        let range = input.get_next_block_range();
+        if range.is_none() && self.filter.is_empty() {
+            trace!(target: "pruner", "No account history to prune");
+            return Ok(SegmentOutput::done())
+        }
 
         let mut limiter = if let Some(limit) = input.limiter.deleted_entries_limit() {
             input.limiter.set_deleted_entries_limit(limit / ACCOUNT_HISTORY_TABLES_TO_PRUNE)
@@ -70,6 +77,52 @@ impl<DB: Database> Segment<DB> for AccountHistory {
             ))
         }
 
+        let (mut pruned, mut done, checkpoint) = match range {
+            Some(range) => {
+                let (pruned, done, last_pruned_block) =
+                    self.prune_range(provider, range, &mut limiter)?;
+                let checkpoint = SegmentOutputCheckpoint {
+                    block_number: Some(last_pruned_block),
+                    tx_number: None,
+                };
+                (pruned, done, Some(checkpoint))
+            }
+            None => (
+                0,
+                true,
+                input.previous_checkpoint.map(SegmentOutputCheckpoint::from_prune_checkpoint),
+            ),
+        };
+
+        // Filtered addresses are pruned only after the rest of the range, so the checkpoint
+        // doesn't need to account for them.
+        if done && !self.filter.is_empty() {
+            let (pruned_filtered, filtered_done) =
+                self.prune_filtered(provider, input.tip_block_number, &mut limiter)?;
+            pruned += pruned_filtered;
+            done = filtered_done;
+        }
+
+        let progress = PruneProgress::new(done, &limiter);
+
+        Ok(SegmentOutput { progress, pruned, checkpoint })
+    }
+}
+
+impl AccountHistory {
+    /// Prunes the account history in the given block range, skipping the filtered addresses.
+    ///
+    /// Returns the number of pruned entries, whether the range is pruned completely and the
+    /// highest block number it's pruned up to.
+    fn prune_range<DB: Database>(
+        &self,
+        provider: &DatabaseProviderRW<DB>,
+        range: RangeInclusive<BlockNumber>,
+        limiter: &mut PruneLimiter,
+    ) -> Result<(usize, bool, BlockNumber), PrunerError> {
+        let range_end = *range.end();
+
+        let mut pruned_changesets = 0;
         let mut last_changeset_pruned_block = None;
         // Deleted account changeset keys (account addresses) with the highest block number deleted
         // for that key.
@@ -80,16 +133,16 @@ impl<DB: Database> Segment<DB> for AccountHistory {
         // size should be up to 0.5MB + some hashmap overhead. `blocks_since_last_run` is
         // additionally limited by the `max_reorg_depth`, so no OOM is expected here.
         let mut highest_deleted_accounts = FxHashMap::default();
-        let (pruned_changesets, done) = provider
-            .prune_table_with_range::<tables::AccountChangeSets>(
-                range,
-                &mut limiter,
-                |_| false,
-                |(block_number, account)| {
-                    highest_deleted_accounts.insert(account.address, block_number);
-                    last_changeset_pruned_block = Some(block_number);
-                },
-            )?;
+        let (_, done) = provider.prune_table_with_range::<tables::AccountChangeSets>(
+            range,
+            limiter,
+            |(_, account)| self.filter.contains(&account.address),
+            |(block_number, account)| {
+                highest_deleted_accounts.insert(account.address, block_number);
+                last_changeset_pruned_block = Some(block_number);
+                pruned_changesets += 1;
+            },
+        )?;
         trace!(target: "pruner", pruned = %pruned_changesets, %done, "Pruned account history (changesets)");
 
         let last_changeset_pruned_block = last_changeset_pruned_block
@@ -113,16 +166,84 @@ impl<DB: Database> Segment<DB> for AccountHistory {
         )?;
         trace!(target: "pruner", ?outcomes, %done, "Pruned account history (indices)");
 
-        let progress = PruneProgress::new(done, &limiter);
+        Ok((pruned_changesets + outcomes.deleted, done, last_changeset_pruned_block))
+    }
 
-        Ok(SegmentOutput {
-            progress,
-            pruned: pruned_changesets + outcomes.deleted,
-            checkpoint: Some(SegmentOutputCheckpoint {
-                block_number: Some(last_changeset_pruned_block),
-                tx_number: None,
-            }),
-        })
+    /// Prunes the account history of the filtered addresses according to their own prune modes.
+    ///
+    /// The change sets to delete are looked up in the history indices, as the history of the
+    /// filtered addresses may need to be pruned beyond the block range of the segment.
+    ///
+    /// Returns the number of pruned entries and whether the history is pruned completely.
+    fn prune_filtered<DB: Database>(
+        &self,
+        provider: &DatabaseProviderRW<DB>,
+        tip_block_number: BlockNumber,
+        limiter: &mut PruneLimiter,
+    ) -> Result<(usize, bool), PrunerError> {
+        let mut changesets_cursor =
+            provider.tx_ref().cursor_dup_write::<tables::AccountChangeSets>()?;
+        let mut history_cursor = provider.tx_ref().cursor_read::<tables::AccountsHistory>()?;
+
+        let mut pruned_changesets = 0;
+        let mut done = true;
+        let mut highest_sharded_keys = Vec::new();
+        'addresses: for (address, to_block) in self
+            .filter
+            .resolve_durations(
+                tip_block_number,
+                PruneSegment::AccountHistory.min_blocks(PrunePurpose::User),
+                |block_number| block_timestamp(provider, block_number),
+            )?
+            .prune_target_blocks(tip_block_number, PruneSegment::AccountHistory)?
+        {
+            let Some(to_block) = to_block else { continue };
+
+            let mut last_pruned_block = None;
+            'shards: for entry in history_cursor.walk(Some(ShardedKey::new(address, 0)))? {
+                let (key, block_numbers) = entry?;
+                if key.key != address {
+                    break
+                }
+
+                for block_number in block_numbers.iter() {
+                    if block_number > to_block {
+                        break 'shards
+                    }
+                    if limiter.is_limit_reached() {
+                        done = false;
+                        break 'shards
+                    }
+
+                    if changesets_cursor
+                        .seek_by_key_subkey(block_number, address)?
+                        .is_some_and(|account| account.address == address)
+                    {
+                        changesets_cursor.delete_current()?;
+                        limiter.increment_deleted_entries_count();
+                        pruned_changesets += 1;
+                    }
+                    last_pruned_block = Some(block_number);
+                }
+            }
+
+            highest_sharded_keys.extend(
+                last_pruned_block.map(|block_number| ShardedKey::new(address, block_number)),
+            );
+            if !done {
+                break 'addresses
+            }
+        }
+        trace!(target: "pruner", pruned = %pruned_changesets, %done, "Pruned filtered account history (changesets)");
+
+        let outcomes = prune_history_indices::<DB, tables::AccountsHistory, _>(
+            provider,
+            highest_sharded_keys,
+            |a, b| a.key == b.key,
+        )?;
+        trace!(target: "pruner", ?outcomes, %done, "Pruned filtered account history (indices)");
+
+        Ok((pruned_changesets + outcomes.deleted, done))
     }
 }
 
@@ -132,12 +253,15 @@ mod tests {
         user::account_history::ACCOUNT_HISTORY_TABLES_TO_PRUNE, AccountHistory, PruneInput,
         Segment, SegmentOutput,
     };
-    use alloy_primitives::{BlockNumber, B256};
+    use alloy_primitives::{Address, BlockNumber, B256};
     use assert_matches::assert_matches;
     use reth_db::{tables, BlockNumberList};
+    use reth_db_api::models::ShardedKey;
+    use reth_primitives::Account;
     use reth_provider::PruneCheckpointReader;
     use reth_prune_types::{
-        PruneCheckpoint, PruneInterruptReason, PruneLimiter, PruneMode, PruneProgress, PruneSegment,
+        HistoryPruneConfig, PruneCheckpoint, PruneInterruptReason, PruneLimiter, PruneMode,
+        PruneProgress, PruneSegment, SegmentOutputCheckpoint, MINIMUM_PRUNING_DISTANCE,
     };
     use reth_stages::test_utils::{StorageKind, TestStageDB};
     use reth_testing_utils::{
@@ -196,9 +320,10 @@ mod tests {
                         .get_prune_checkpoint(PruneSegment::AccountHistory)
                         .unwrap(),
                     to_block,
+                    tip_block_number: to_block,
                     limiter: limiter.clone(),
                 };
-                let segment = AccountHistory::new(prune_mode);
+                let segment = AccountHistory::new(prune_mode, Default::default());
 
                 let provider = db.factory.provider_rw().unwrap();
                 let result = segment.prune(&provider, input).unwrap();
@@ -304,4 +429,82 @@ mod tests {
         test_prune(998, 2, (PruneProgress::Finished, 998));
         test_prune(1400, 3, (PruneProgress::Finished, 804));
     }
+
+    #[test]
+    fn prune_filtered() {
+        let db = TestStageDB::default();
+
+        let retained_address = Address::with_last_byte(1);
+        let address = Address::with_last_byte(2);
+        let pruned_address = Address::with_last_byte(3);
+
+        // Every address changes in every block
+        let changesets = (0..=10)
+            .map(|_| {
+                [retained_address, address, pruned_address]
+                    .map(|address| (address, Account::default(), Vec::new()))
+                    .to_vec()
+            })
+            .collect::<Vec<_>>();
+        db.insert_changesets(changesets.clone(), None).expect("insert changesets");
+        db.insert_history(changesets, None).expect("insert history");
+
+        let segment = AccountHistory::new(
+            PruneMode::Before(4),
+            HistoryPruneConfig(BTreeMap::from([
+                (retained_address, PruneMode::Before(2)),
+                (pruned_address, PruneMode::Before(8)),
+            ])),
+        );
+        let input = PruneInput {
+            previous_checkpoint: None,
+            to_block: 3,
+            tip_block_number: MINIMUM_PRUNING_DISTANCE + 10,
+            limiter: PruneLimiter::default(),
+        };
+
+        let provider = db.factory.provider_rw().unwrap();
+        let result = segment.prune(&provider, input).unwrap();
+        provider.commit().expect("commit");
+
+        // The checkpoint is not affected by the filtered addresses
+        assert_matches!(
+            result,
+            SegmentOutput {
+                progress: PruneProgress::Finished,
+                pruned: 14,
+                checkpoint: Some(SegmentOutputCheckpoint { block_number: Some(3), .. })
+            }
+        );
+
+        let changesets = db.table::<tables::AccountChangeSets>().unwrap();
+        let changeset_blocks = |address: Address| {
+            changesets
+                .iter()
+                .filter(|(_, change)| change.address == address)
+                .map(|(block_number, _)| *block_number)
+                .collect::<Vec<_>>()
+        };
+        assert_eq!(changeset_blocks(retained_address), (2..=10).collect::<Vec<_>>());
+        assert_eq!(changeset_blocks(address), (4..=10).collect::<Vec<_>>());
+        assert_eq!(changeset_blocks(pruned_address), (8..=10).collect::<Vec<_>>());
+
+        assert_eq!(
+            db.table::<tables::AccountsHistory>().unwrap(),
+            vec![
+                (
+                    ShardedKey::new(retained_address, u64::MAX),
+                    BlockNumberList::new_pre_sorted((2..=10).collect::<Vec<_>>())
+                ),
+                (
+                    ShardedKey::new(address, u64::MAX),
+                    BlockNumberList::new_pre_sorted((4..=10).collect::<Vec<_>>())
+                ),
+                (
+                    ShardedKey::new(pruned_address, u64::MAX),
+                    BlockNumberList::new_pre_sorted((8..=10).collect::<Vec<_>>())
+                ),
+            ]
+        );
+    }
 }
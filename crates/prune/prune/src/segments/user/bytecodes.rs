@@ -0,0 +1,473 @@
+use crate::{
+    segments::{PruneInput, Segment},
+    PrunerError,
+};
+use alloy_primitives::{Address, BlockNumber, B256};
+use parking_lot::Mutex;
+use reth_db::tables;
+use reth_db_api::{cursor::DbCursorRO, database::Database, transaction::DbTx};
+use reth_provider::{BlockHashReader, ChangeSetReader, DatabaseProviderRW};
+use reth_prune_types::{
+    PruneInterruptReason, PruneLimiter, PruneMode, PruneProgress, PrunePurpose, PruneSegment,
+    SegmentOutput, SegmentOutputCheckpoint,
+};
+use reth_static_file_types::StaticFileSegment;
+use rustc_hash::FxHashSet;
+use tracing::{instrument, trace};
+
+/// Minimum number of blocks the prune target needs to advance by since the last finished sweep,
+/// before the bytecodes are marked and swept again.
+///
+/// Finding the unreferenced bytecodes requires walking the whole account state, so it's not done
+/// on every pruner run.
+const SWEEP_INTERVAL: BlockNumber = 10_000;
+
+/// Number of entries walked while marking that count as one deleted entry against the
+/// [`PruneLimiter`].
+const MARKED_ENTRIES_PER_DELETED_ENTRY: usize = 100;
+
+/// Removes the bytecodes that are neither referenced by [`tables::PlainAccountState`] nor by the
+/// retained account change sets, in the database or in static files.
+///
+/// This is a mark-and-sweep: all referenced code hashes are collected first, and the remaining
+/// entries of [`tables::Bytecodes`] are deleted. Code of accounts that are reverted on unwind is
+/// always referenced by the change sets of the unwound blocks, so it's never removed.
+///
+/// Marking is bounded by the [`PruneLimiter`] and continued on the next run, with the progress
+/// kept in memory. The blocks added in between are marked before continuing, and the mark starts
+/// over if any marked block was unwound.
+#[derive(Debug)]
+pub struct Bytecodes {
+    mode: PruneMode,
+    mark: Mutex<Option<Mark>>,
+}
+
+impl Bytecodes {
+    pub const fn new(mode: PruneMode) -> Self {
+        Self { mode, mark: Mutex::new(None) }
+    }
+}
+
+impl<DB: Database> Segment<DB> for Bytecodes {
+    fn segment(&self) -> PruneSegment {
+        PruneSegment::Bytecodes
+    }
+
+    fn mode(&self) -> Option<PruneMode> {
+        Some(self.mode)
+    }
+
+    fn purpose(&self) -> PrunePurpose {
+        PrunePurpose::User
+    }
+
+    #[instrument(level = "trace", target = "pruner", skip(self, provider), ret)]
+    fn prune(
+        &self,
+        provider: &DatabaseProviderRW<DB>,
+        input: PruneInput,
+    ) -> Result<SegmentOutput, PrunerError> {
+        let previous_checkpoint =
+            input.previous_checkpoint.map(SegmentOutputCheckpoint::from_prune_checkpoint);
+
+        let mut mark = self.mark.lock();
+        if let Some(is_canonical) =
+            mark.as_ref().map(|mark| mark.is_canonical(provider)).transpose()?
+        {
+            if !is_canonical {
+                trace!(target: "pruner", "Marked blocks were unwound, marking again");
+                *mark = None;
+            }
+        }
+        if mark.is_none() {
+            let last_sweep_block =
+                previous_checkpoint.and_then(|checkpoint| checkpoint.block_number);
+            if last_sweep_block
+                .is_some_and(|block_number| input.to_block < block_number + SWEEP_INTERVAL)
+            {
+                trace!(target: "pruner", ?last_sweep_block, "No bytecodes to prune");
+                return Ok(SegmentOutput {
+                    checkpoint: previous_checkpoint,
+                    ..SegmentOutput::done()
+                })
+            }
+        }
+
+        let mut limiter = input.limiter;
+        if limiter.is_limit_reached() {
+            return Ok(SegmentOutput::not_done(
+                PruneInterruptReason::new(&limiter),
+                previous_checkpoint,
+            ))
+        }
+
+        let marking = match mark.as_mut() {
+            Some(marking) => marking,
+            None => mark.insert(Mark::new(provider, input.tip_block_number)?),
+        };
+        let marked = marking.run(provider, input.tip_block_number, &mut limiter)?;
+        trace!(target: "pruner", referenced = %marking.referenced.len(), %marked, "Marked referenced bytecodes");
+        if !marked {
+            return Ok(SegmentOutput::not_done(
+                PruneInterruptReason::new(&limiter),
+                previous_checkpoint,
+            ))
+        }
+
+        let mut pruned = 0;
+        let (_, done) = provider.prune_table_with_range::<tables::Bytecodes>(
+            ..,
+            &mut limiter,
+            |(code_hash, _)| marking.referenced.contains(code_hash),
+            |_| pruned += 1,
+        )?;
+        trace!(target: "pruner", %pruned, %done, "Pruned bytecodes");
+
+        let progress = PruneProgress::new(done, &limiter);
+        if done {
+            *mark = None;
+        }
+
+        Ok(SegmentOutput {
+            progress,
+            pruned,
+            // Only a finished sweep moves the checkpoint, so an interrupted one is continued on
+            // the next run.
+            checkpoint: if done {
+                Some(SegmentOutputCheckpoint {
+                    block_number: Some(input.to_block),
+                    tx_number: None,
+                })
+            } else {
+                previous_checkpoint
+            },
+        })
+    }
+}
+
+/// Progress of marking the referenced bytecodes.
+#[derive(Debug)]
+struct Mark {
+    /// Code hashes found to be referenced so far.
+    referenced: FxHashSet<B256>,
+    /// Next block whose account change sets are marked.
+    next_block: BlockNumber,
+    /// Hash of the block before `next_block`, to detect unwinds.
+    last_block_hash: Option<B256>,
+    /// Progress of walking the plain account state, once all change sets up to the tip are
+    /// marked.
+    plain_state: PlainStateMark,
+}
+
+/// Progress of walking [`tables::PlainAccountState`] while marking.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum PlainStateMark {
+    /// The walk hasn't started yet.
+    Pending,
+    /// The walk continues from the address.
+    From(Address),
+    /// The whole table was walked.
+    Done,
+}
+
+impl Mark {
+    /// Starts marking from the lowest block with account change sets, or after the `tip` block if
+    /// there are none.
+    fn new<DB: Database>(
+        provider: &DatabaseProviderRW<DB>,
+        tip: BlockNumber,
+    ) -> Result<Self, PrunerError> {
+        let has_static_files = provider
+            .static_file_provider()
+            .get_highest_static_file_block(StaticFileSegment::AccountChangeSets)
+            .is_some();
+        let next_block = if has_static_files {
+            0
+        } else {
+            provider
+                .tx_ref()
+                .cursor_read::<tables::AccountChangeSets>()?
+                .first()?
+                .map_or(tip + 1, |(block_number, _)| block_number)
+        };
+
+        Ok(Self {
+            referenced: FxHashSet::default(),
+            next_block,
+            last_block_hash: None,
+            plain_state: PlainStateMark::Pending,
+        })
+    }
+
+    /// Returns `false` if the last marked block is no longer canonical.
+    fn is_canonical<DB: Database>(
+        &self,
+        provider: &DatabaseProviderRW<DB>,
+    ) -> Result<bool, PrunerError> {
+        let Some(last_block_hash) = self.last_block_hash else { return Ok(true) };
+        Ok(provider.block_hash(self.next_block - 1)? == Some(last_block_hash))
+    }
+
+    /// Continues marking up to the `tip` block, until the limit is reached.
+    ///
+    /// Returns `true` if all referenced bytecodes are marked.
+    fn run<DB: Database>(
+        &mut self,
+        provider: &DatabaseProviderRW<DB>,
+        tip: BlockNumber,
+        limiter: &mut PruneLimiter,
+    ) -> Result<bool, PrunerError> {
+        let mut walked = 0;
+        let mut walk = |limiter: &mut PruneLimiter| {
+            walked += 1;
+            if walked % MARKED_ENTRIES_PER_DELETED_ENTRY == 0 {
+                limiter.increment_deleted_entries_count();
+            }
+        };
+
+        // Once the plain account state walk started, the accounts changed by new blocks may have
+        // been walked already, so their current state is marked as well.
+        let mark_current = self.plain_state != PlainStateMark::Pending;
+        while self.next_block <= tip {
+            if limiter.is_limit_reached() {
+                return Ok(false)
+            }
+
+            for change in provider.account_block_changeset(self.next_block)? {
+                self.referenced.extend(change.info.and_then(|account| account.bytecode_hash));
+                if mark_current {
+                    let account =
+                        provider.tx_ref().get::<tables::PlainAccountState>(change.address)?;
+                    self.referenced.extend(account.and_then(|account| account.bytecode_hash));
+                }
+                walk(limiter);
+            }
+            self.last_block_hash = provider.block_hash(self.next_block)?;
+            self.next_block += 1;
+        }
+
+        let mut cursor = provider.tx_ref().cursor_read::<tables::PlainAccountState>()?;
+        let mut entry = match self.plain_state {
+            PlainStateMark::Pending => cursor.first()?,
+            PlainStateMark::From(address) => cursor.seek(address)?,
+            PlainStateMark::Done => return Ok(true),
+        };
+        while let Some((address, account)) = entry {
+            if limiter.is_limit_reached() {
+                self.plain_state = PlainStateMark::From(address);
+                return Ok(false)
+            }
+
+            self.referenced.extend(account.bytecode_hash);
+            walk(limiter);
+            entry = cursor.next()?;
+        }
+        self.plain_state = PlainStateMark::Done;
+
+        Ok(true)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::segments::{Bytecodes, PruneInput, Segment, SegmentOutput};
+    use alloy_primitives::{Address, BlockNumber, B256};
+    use assert_matches::assert_matches;
+    use reth_db::tables;
+    use reth_db_api::{models::AccountBeforeTx, transaction::DbTxMut};
+    use reth_primitives::{Account, Bytecode, Bytes};
+    use reth_provider::PruneCheckpointReader;
+    use reth_prune_types::{
+        PruneInterruptReason, PruneLimiter, PruneMode, PruneProgress, PruneSegment,
+    };
+    use reth_stages::test_utils::TestStageDB;
+
+    #[test]
+    fn prune() {
+        let db = TestStageDB::default();
+
+        let code = |byte: u8| Bytecode::new_raw(Bytes::from(vec![byte; 3]));
+        let code_hashes = (1..=5u8)
+            .map(|byte| {
+                let code = code(byte);
+                (code.hash_slow(), code)
+            })
+            .collect::<Vec<_>>();
+        let retained = |count: usize| {
+            let mut retained = code_hashes[..count].to_vec();
+            retained.sort_unstable_by_key(|(code_hash, _)| *code_hash);
+            retained
+        };
+        let account = |code_hash: B256| Account {
+            nonce: 1,
+            balance: Default::default(),
+            bytecode_hash: Some(code_hash),
+        };
+
+        db.commit(|tx| {
+            for (code_hash, code) in &code_hashes {
+                tx.put::<tables::Bytecodes>(*code_hash, code.clone())?;
+            }
+            // The first bytecode is referenced by the state, the second by a change set.
+            tx.put::<tables::PlainAccountState>(
+                Address::with_last_byte(1),
+                account(code_hashes[0].0),
+            )?;
+            tx.put::<tables::AccountChangeSets>(
+                1,
+                AccountBeforeTx {
+                    address: Address::with_last_byte(2),
+                    info: Some(account(code_hashes[1].0)),
+                },
+            )?;
+            Ok(())
+        })
+        .unwrap();
+
+        let test_prune = |to_block: BlockNumber, expected_result: (PruneProgress, usize)| {
+            let prune_mode = PruneMode::Before(to_block + 1);
+            let input = PruneInput {
+                previous_checkpoint: db
+                    .factory
+                    .provider()
+                    .unwrap()
+                    .get_prune_checkpoint(PruneSegment::Bytecodes)
+                    .unwrap(),
+                to_block,
+                tip_block_number: to_block,
+                limiter: PruneLimiter::default().set_deleted_entries_limit(2),
+            };
+            let segment = Bytecodes::new(prune_mode);
+
+            let provider = db.factory.provider_rw().unwrap();
+            let result = segment.prune(&provider, input).unwrap();
+            assert_matches!(
+                result,
+                SegmentOutput { progress, pruned, .. } if (progress, pruned) == expected_result
+            );
+
+            if let Some(checkpoint) = result.checkpoint {
+                segment
+                    .save_checkpoint(&provider, checkpoint.as_prune_checkpoint(prune_mode))
+                    .unwrap();
+            }
+            provider.commit().expect("commit");
+
+            db.factory
+                .provider()
+                .unwrap()
+                .get_prune_checkpoint(PruneSegment::Bytecodes)
+                .unwrap()
+                .and_then(|checkpoint| checkpoint.block_number)
+        };
+
+        // The sweep is interrupted by the limit and doesn't save a checkpoint
+        assert_eq!(
+            test_prune(
+                10,
+                (PruneProgress::HasMoreData(PruneInterruptReason::DeletedEntriesLimitReached), 2)
+            ),
+            None
+        );
+        assert_eq!(test_prune(11, (PruneProgress::Finished, 1)), Some(11));
+        assert_eq!(
+            db.table::<tables::Bytecodes>().unwrap(),
+            retained(2),
+            "only referenced bytecodes are retained"
+        );
+
+        // Unreferenced again after the change set is gone, but the sweep interval hasn't passed
+        db.commit(|tx| {
+            tx.clear::<tables::AccountChangeSets>()?;
+            Ok(())
+        })
+        .unwrap();
+        assert_eq!(test_prune(12, (PruneProgress::Finished, 0)), Some(11));
+        assert_eq!(
+            test_prune(11 + super::SWEEP_INTERVAL, (PruneProgress::Finished, 1)),
+            Some(10_011)
+        );
+        assert_eq!(db.table::<tables::Bytecodes>().unwrap(), retained(1));
+    }
+
+    #[test]
+    fn prune_marks_across_runs() {
+        let db = TestStageDB::default();
+
+        let code = |byte: u8| Bytecode::new_raw(Bytes::from(vec![byte; 3]));
+        let (referenced, created, unreferenced) = (code(1), code(2), code(3));
+        let account = |code: Option<&Bytecode>| Account {
+            nonce: 1,
+            balance: Default::default(),
+            bytecode_hash: code.map(|code| code.hash_slow()),
+        };
+
+        db.commit(|tx| {
+            for code in [&referenced, &created, &unreferenced] {
+                tx.put::<tables::Bytecodes>(code.hash_slow(), code.clone())?;
+            }
+            for byte in 1..=250 {
+                tx.put::<tables::PlainAccountState>(
+                    Address::with_last_byte(byte),
+                    account((byte == 250).then_some(&referenced)),
+                )?;
+            }
+            Ok(())
+        })
+        .unwrap();
+
+        let segment = Bytecodes::new(PruneMode::Full);
+        let run = |tip: BlockNumber| {
+            let input = PruneInput {
+                previous_checkpoint: None,
+                to_block: tip,
+                tip_block_number: tip,
+                // Walking 100 entries while marking counts as deleting one.
+                limiter: PruneLimiter::default().set_deleted_entries_limit(1),
+            };
+            let provider = db.factory.provider_rw().unwrap();
+            let result = segment.prune(&provider, input).unwrap();
+            provider.commit().expect("commit");
+            result
+        };
+
+        // The mark is interrupted after walking the first accounts.
+        assert_matches!(
+            run(0),
+            SegmentOutput { progress: PruneProgress::HasMoreData(_), pruned: 0, .. }
+        );
+
+        // A new block creates an account with code at an address that was already walked.
+        db.commit(|tx| {
+            tx.put::<tables::AccountChangeSets>(
+                1,
+                AccountBeforeTx { address: Address::with_last_byte(2), info: None },
+            )?;
+            tx.put::<tables::PlainAccountState>(
+                Address::with_last_byte(2),
+                account(Some(&created)),
+            )?;
+            Ok(())
+        })
+        .unwrap();
+
+        let (mut runs, mut pruned) = (0, 0);
+        loop {
+            runs += 1;
+            let result = run(1);
+            pruned += result.pruned;
+            if result.progress.is_finished() {
+                break
+            }
+        }
+        assert!(runs > 1, "marking is spread over several runs");
+        assert_eq!(pruned, 1);
+
+        let mut retained = vec![
+            (referenced.hash_slow(), referenced.clone()),
+            (created.hash_slow(), created.clone()),
+        ];
+        retained.sort_unstable_by_key(|(code_hash, _)| *code_hash);
+        assert_eq!(db.table::<tables::Bytecodes>().unwrap(), retained);
+    }
+}
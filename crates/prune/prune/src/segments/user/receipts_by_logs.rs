@@ -1,5 +1,5 @@
 use crate::{
-    segments::{PruneInput, Segment},
+    segments::{block_timestamp, PruneInput, Segment},
     PrunerError,
 };
 use reth_db::tables;
@@ -49,6 +49,12 @@ impl<DB: Database> Segment<DB> for ReceiptsByLogs {
             .map(|(bn, _)| bn)
             .unwrap_or_default();
 
+        let config = self.config.resolve_durations(
+            input.to_block,
+            PruneSegment::ContractLogs.min_blocks(PrunePurpose::User),
+            |block_number| block_timestamp(provider, block_number),
+        )?;
+
         // Get status checkpoint from latest run
         let mut last_pruned_block =
             input.previous_checkpoint.and_then(|checkpoint| checkpoint.block_number);
@@ -65,7 +71,7 @@ impl<DB: Database> Segment<DB> for ReceiptsByLogs {
 
         // Figure out what receipts have already been pruned, so we can have an accurate
         // `address_filter`
-        let address_filter = self.config.group_by_block(input.to_block, last_pruned_block)?;
+        let address_filter = config.group_by_block(input.to_block, last_pruned_block)?;
 
         // Splits all transactions in different block ranges. Each block range will have its own
         // filter address list and will check it while going through the table
@@ -197,8 +203,7 @@ impl<DB: Database> Segment<DB> for ReceiptsByLogs {
         //
         // Only applies if we were able to prune everything intended for this run, otherwise the
         // checkpoint is the `last_pruned_block`.
-        let prune_mode_block = self
-            .config
+        let prune_mode_block = config
             .lowest_block_with_distance(input.to_block, initial_last_pruned_block)?
             .unwrap_or(to_block);
 
@@ -294,6 +299,7 @@ mod tests {
                         .get_prune_checkpoint(PruneSegment::ContractLogs)
                         .unwrap(),
                     to_block: tip,
+                    tip_block_number: tip,
                     limiter,
                 },
             );
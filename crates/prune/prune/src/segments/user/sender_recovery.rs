@@ -137,6 +137,7 @@ mod tests {
                     .get_prune_checkpoint(PruneSegment::SenderRecovery)
                     .unwrap(),
                 to_block,
+                tip_block_number: to_block,
                 limiter: limiter.clone(),
             };
 
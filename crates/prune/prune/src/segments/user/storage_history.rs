@@ -1,19 +1,25 @@
 use crate::{
-    segments::{user::history::prune_history_indices, PruneInput, Segment, SegmentOutput},
+    segments::{
+        block_timestamp, user::history::prune_history_indices, PruneInput, Segment, SegmentOutput,
+    },
     PrunerError,
 };
+use alloy_primitives::{BlockNumber, B256};
 use itertools::Itertools;
 use reth_db::tables;
 use reth_db_api::{
+    cursor::{DbCursorRO, DbCursorRW, DbDupCursorRO},
     database::Database,
     models::{storage_sharded_key::StorageShardedKey, BlockNumberAddress},
+    transaction::{DbTx, DbTxMut},
 };
 use reth_provider::DatabaseProviderRW;
 use reth_prune_types::{
-    PruneInterruptReason, PruneMode, PruneProgress, PrunePurpose, PruneSegment,
-    SegmentOutputCheckpoint,
+    HistoryPruneConfig, PruneInterruptReason, PruneLimiter, PruneMode, PruneProgress, PrunePurpose,
+    PruneSegment, SegmentOutputCheckpoint,
 };
 use rustc_hash::FxHashMap;
+use std::ops::RangeInclusive;
 use tracing::{instrument, trace};
 
 /// Number of storage history tables to prune in one step
@@ -22,14 +28,17 @@ use tracing::{instrument, trace};
 /// [`tables::StoragesHistory`]. We want to prune them to the same block number.
 const STORAGE_HISTORY_TABLES_TO_PRUNE: usize = 2;
 
+/// Prunes the storage history according to the segment prune mode, except for the addresses in
+/// the filter, which are pruned according to their own prune modes.
 #[derive(Debug)]
 pub struct StorageHistory {
     mode: PruneMode,
+    filter: HistoryPruneConfig,
 }
 
 impl StorageHistory {
-    pub const fn new(mode: PruneMode) -> Self {
-        Self { mode }
+    pub const fn new(mode: PruneMode, filter: HistoryPruneConfig) -> Self {
+        Self { mode, filter }
     }
 }
 
@@ -52,14 +61,11 @@ impl<DB: Database> Segment<DB> for StorageHistory {
         provider: &DatabaseProviderRW<DB>,
         input: PruneInput,
     ) -> Result<SegmentOutput, PrunerError> {
-        let range = match input.get_next_block_range() {
-            Some(range) => range,
-            None => {
-                trace!(target: "pruner", "No storage history to prune");
-                return Ok(SegmentOutput::done())
-            }
-        };
-        let range_end = *range.end();
+        let range = input.get_next_block_range();
+        if range.is_none() && self.filter.is_empty() {
+            trace!(target: "pruner", "No storage history to prune");
+            return Ok(SegmentOutput::done())
+        }
 
         let mut limiter = if let Some(limit) = input.limiter.deleted_entries_limit() {
             input.limiter.set_deleted_entries_limit(limit / STORAGE_HISTORY_TABLES_TO_PRUNE)
@@ -73,6 +79,52 @@ impl<DB: Database> Segment<DB> for StorageHistory {
             ))
         }
 
+        let (mut pruned, mut done, checkpoint) = match range {
+            Some(range) => {
+                let (pruned, done, last_pruned_block) =
+                    self.prune_range(provider, range, &mut limiter)?;
+                let checkpoint = SegmentOutputCheckpoint {
+                    block_number: Some(last_pruned_block),
+                    tx_number: None,
+                };
+                (pruned, done, Some(checkpoint))
+            }
+            None => (
+                0,
+                true,
+                input.previous_checkpoint.map(SegmentOutputCheckpoint::from_prune_checkpoint),
+            ),
+        };
+
+        // Filtered addresses are pruned only after the rest of the range, so the checkpoint
+        // doesn't need to account for them.
+        if done && !self.filter.is_empty() {
+            let (pruned_filtered, filtered_done) =
+                self.prune_filtered(provider, input.tip_block_number, &mut limiter)?;
+            pruned += pruned_filtered;
+            done = filtered_done;
+        }
+
+        let progress = PruneProgress::new(done, &limiter);
+
+        Ok(SegmentOutput { progress, pruned, checkpoint })
+    }
+}
+
+impl StorageHistory {
+    /// Prunes the storage history in the given block range, skipping the filtered addresses.
+    ///
+    /// Returns the number of pruned entries, whether the range is pruned completely and the
+    /// highest block number it's pruned up to.
+    fn prune_range<DB: Database>(
+        &self,
+        provider: &DatabaseProviderRW<DB>,
+        range: RangeInclusive<BlockNumber>,
+        limiter: &mut PruneLimiter,
+    ) -> Result<(usize, bool, BlockNumber), PrunerError> {
+        let range_end = *range.end();
+
+        let mut pruned_changesets = 0;
         let mut last_changeset_pruned_block = None;
         // Deleted storage changeset keys (account addresses and storage slots) with the highest
         // block number deleted for that key.
@@ -83,16 +135,16 @@ impl<DB: Database> Segment<DB> for StorageHistory {
         // size should be up to 0.5MB + some hashmap overhead. `blocks_since_last_run` is
         // additionally limited by the `max_reorg_depth`, so no OOM is expected here.
         let mut highest_deleted_storages = FxHashMap::default();
-        let (pruned_changesets, done) = provider
-            .prune_table_with_range::<tables::StorageChangeSets>(
-                BlockNumberAddress::range(range),
-                &mut limiter,
-                |_| false,
-                |(BlockNumberAddress((block_number, address)), entry)| {
-                    highest_deleted_storages.insert((address, entry.key), block_number);
-                    last_changeset_pruned_block = Some(block_number);
-                },
-            )?;
+        let (_, done) = provider.prune_table_with_range::<tables::StorageChangeSets>(
+            BlockNumberAddress::range(range),
+            limiter,
+            |(BlockNumberAddress((_, address)), _)| self.filter.contains(address),
+            |(BlockNumberAddress((block_number, address)), entry)| {
+                highest_deleted_storages.insert((address, entry.key), block_number);
+                last_changeset_pruned_block = Some(block_number);
+                pruned_changesets += 1;
+            },
+        )?;
         trace!(target: "pruner", deleted = %pruned_changesets, %done, "Pruned storage history (changesets)");
 
         let last_changeset_pruned_block = last_changeset_pruned_block
@@ -121,16 +173,92 @@ impl<DB: Database> Segment<DB> for StorageHistory {
         )?;
         trace!(target: "pruner", ?outcomes, %done, "Pruned storage history (indices)");
 
-        let progress = PruneProgress::new(done, &limiter);
+        Ok((pruned_changesets + outcomes.deleted, done, last_changeset_pruned_block))
+    }
 
-        Ok(SegmentOutput {
-            progress,
-            pruned: pruned_changesets + outcomes.deleted,
-            checkpoint: Some(SegmentOutputCheckpoint {
-                block_number: Some(last_changeset_pruned_block),
-                tx_number: None,
-            }),
-        })
+    /// Prunes the storage history of the filtered addresses according to their own prune modes.
+    ///
+    /// The change sets to delete are looked up in the history indices, as the history of the
+    /// filtered addresses may need to be pruned beyond the block range of the segment.
+    ///
+    /// Returns the number of pruned entries and whether the history is pruned completely.
+    fn prune_filtered<DB: Database>(
+        &self,
+        provider: &DatabaseProviderRW<DB>,
+        tip_block_number: BlockNumber,
+        limiter: &mut PruneLimiter,
+    ) -> Result<(usize, bool), PrunerError> {
+        let mut changesets_cursor =
+            provider.tx_ref().cursor_dup_write::<tables::StorageChangeSets>()?;
+        let mut history_cursor = provider.tx_ref().cursor_read::<tables::StoragesHistory>()?;
+
+        let mut pruned_changesets = 0;
+        let mut done = true;
+        let mut highest_sharded_keys = Vec::<StorageShardedKey>::new();
+        'addresses: for (address, to_block) in self
+            .filter
+            .resolve_durations(
+                tip_block_number,
+                PruneSegment::StorageHistory.min_blocks(PrunePurpose::User),
+                |block_number| block_timestamp(provider, block_number),
+            )?
+            .prune_target_blocks(tip_block_number, PruneSegment::StorageHistory)?
+        {
+            let Some(to_block) = to_block else { continue };
+
+            // Shards are sorted by storage slot, so the ones of the same slot are adjacent.
+            for entry in
+                history_cursor.walk(Some(StorageShardedKey::new(address, B256::ZERO, 0)))?
+            {
+                let (key, block_numbers) = entry?;
+                if key.address != address {
+                    break
+                }
+                let storage_key = key.sharded_key.key;
+
+                for block_number in block_numbers.iter() {
+                    if block_number > to_block {
+                        break
+                    }
+                    if limiter.is_limit_reached() {
+                        done = false;
+                        break 'addresses
+                    }
+
+                    if changesets_cursor
+                        .seek_by_key_subkey((block_number, address).into(), storage_key)?
+                        .is_some_and(|entry| entry.key == storage_key)
+                    {
+                        changesets_cursor.delete_current()?;
+                        limiter.increment_deleted_entries_count();
+                        pruned_changesets += 1;
+                    }
+
+                    match highest_sharded_keys.last_mut() {
+                        Some(last)
+                            if last.address == address && last.sharded_key.key == storage_key =>
+                        {
+                            last.sharded_key.highest_block_number = block_number
+                        }
+                        _ => highest_sharded_keys.push(StorageShardedKey::new(
+                            address,
+                            storage_key,
+                            block_number,
+                        )),
+                    }
+                }
+            }
+        }
+        trace!(target: "pruner", pruned = %pruned_changesets, %done, "Pruned filtered storage history (changesets)");
+
+        let outcomes = prune_history_indices::<DB, tables::StoragesHistory, _>(
+            provider,
+            highest_sharded_keys,
+            |a, b| a.address == b.address && a.sharded_key.key == b.sharded_key.key,
+        )?;
+        trace!(target: "pruner", ?outcomes, %done, "Pruned filtered storage history (indices)");
+
+        Ok((pruned_changesets + outcomes.deleted, done))
     }
 }
 
@@ -140,11 +268,16 @@ mod tests {
         user::storage_history::STORAGE_HISTORY_TABLES_TO_PRUNE, PruneInput, Segment, SegmentOutput,
         StorageHistory,
     };
-    use alloy_primitives::{BlockNumber, B256};
+    use alloy_primitives::{Address, BlockNumber, B256, U256};
     use assert_matches::assert_matches;
     use reth_db::{tables, BlockNumberList};
+    use reth_db_api::models::storage_sharded_key::StorageShardedKey;
+    use reth_primitives::{Account, StorageEntry};
     use reth_provider::PruneCheckpointReader;
-    use reth_prune_types::{PruneCheckpoint, PruneLimiter, PruneMode, PruneProgress, PruneSegment};
+    use reth_prune_types::{
+        HistoryPruneConfig, PruneCheckpoint, PruneLimiter, PruneMode, PruneProgress, PruneSegment,
+        SegmentOutputCheckpoint, MINIMUM_PRUNING_DISTANCE,
+    };
     use reth_stages::test_utils::{StorageKind, TestStageDB};
     use reth_testing_utils::{
         generators,
@@ -203,9 +336,10 @@ mod tests {
                     .get_prune_checkpoint(PruneSegment::StorageHistory)
                     .unwrap(),
                 to_block,
+                tip_block_number: to_block,
                 limiter: limiter.clone(),
             };
-            let segment = StorageHistory::new(prune_mode);
+            let segment = StorageHistory::new(prune_mode, Default::default());
 
             let provider = db.factory.provider_rw().unwrap();
             let result = segment.prune(&provider, input).unwrap();
@@ -318,4 +452,88 @@ mod tests {
         test_prune(998, 2, (PruneProgress::Finished, 499));
         test_prune(1200, 3, (PruneProgress::Finished, 202));
     }
+
+    #[test]
+    fn prune_filtered() {
+        let db = TestStageDB::default();
+
+        let retained_address = Address::with_last_byte(1);
+        let address = Address::with_last_byte(2);
+        let pruned_address = Address::with_last_byte(3);
+        let slots = [B256::with_last_byte(1), B256::with_last_byte(2)];
+
+        // Every storage slot of every address changes in every block
+        let changesets = (0..=10)
+            .map(|_| {
+                [retained_address, address, pruned_address]
+                    .map(|address| {
+                        let storage =
+                            slots.map(|key| StorageEntry { key, value: U256::from(1) }).to_vec();
+                        (address, Account::default(), storage)
+                    })
+                    .to_vec()
+            })
+            .collect::<Vec<_>>();
+        db.insert_changesets(changesets.clone(), None).expect("insert changesets");
+        db.insert_history(changesets, None).expect("insert history");
+
+        let segment = StorageHistory::new(
+            PruneMode::Before(4),
+            HistoryPruneConfig(BTreeMap::from([
+                (retained_address, PruneMode::Before(2)),
+                (pruned_address, PruneMode::Before(8)),
+            ])),
+        );
+        let input = PruneInput {
+            previous_checkpoint: None,
+            to_block: 3,
+            tip_block_number: MINIMUM_PRUNING_DISTANCE + 10,
+            limiter: PruneLimiter::default(),
+        };
+
+        let provider = db.factory.provider_rw().unwrap();
+        let result = segment.prune(&provider, input).unwrap();
+        provider.commit().expect("commit");
+
+        // The checkpoint is not affected by the filtered addresses
+        assert_matches!(
+            result,
+            SegmentOutput {
+                progress: PruneProgress::Finished,
+                pruned: 28,
+                checkpoint: Some(SegmentOutputCheckpoint { block_number: Some(3), .. })
+            }
+        );
+
+        let changesets = db.table::<tables::StorageChangeSets>().unwrap();
+        let changeset_blocks = |address: Address, slot: B256| {
+            changesets
+                .iter()
+                .filter(|(key, entry)| key.address() == address && entry.key == slot)
+                .map(|(key, _)| key.block_number())
+                .collect::<Vec<_>>()
+        };
+        let history = db.table::<tables::StoragesHistory>().unwrap();
+        let history_blocks = |address: Address, slot: B256| {
+            history
+                .iter()
+                .filter(|(key, _)| key.address == address && key.sharded_key.key == slot)
+                .map(|(key, blocks)| {
+                    assert_eq!(*key, StorageShardedKey::last(address, slot));
+                    blocks.iter().collect::<Vec<_>>()
+                })
+                .collect::<Vec<_>>()
+        };
+
+        for slot in slots {
+            for (address, blocks) in [
+                (retained_address, (2..=10).collect::<Vec<_>>()),
+                (address, (4..=10).collect()),
+                (pruned_address, (8..=10).collect()),
+            ] {
+                assert_eq!(changeset_blocks(address, slot), blocks);
+                assert_eq!(history_blocks(address, slot), vec![blocks]);
+            }
+        }
+    }
 }
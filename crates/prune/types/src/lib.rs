@@ -81,6 +81,17 @@ impl ReceiptsLogPruneConfig {
         Ok(map)
     }
 
+    /// Resolves the [`PruneMode::Duration`] of every address, keeping at least `min_blocks`
+    /// blocks, see [`PruneMode::resolve_duration`].
+    pub fn resolve_durations<E>(
+        &self,
+        tip: BlockNumber,
+        min_blocks: u64,
+        mut block_timestamp: impl FnMut(BlockNumber) -> Result<u64, E>,
+    ) -> Result<Self, E> {
+        resolve_durations(&self.0, tip, min_blocks, &mut block_timestamp).map(Self)
+    }
+
     /// Returns the lowest block where we start filtering logs which use `PruneMode::Distance(_)`.
     pub fn lowest_block_with_distance(
         &self,
@@ -103,3 +114,61 @@ impl ReceiptsLogPruneConfig {
         Ok(lowest.map(|lowest| lowest.max(pruned_block)))
     }
 }
+
+/// Configuration for pruning the account or storage history of specific addresses according to
+/// their own [`PruneMode`], instead of the one of the history segment.
+#[derive(Debug, Clone, PartialEq, Eq, Default, Serialize, Deserialize)]
+pub struct HistoryPruneConfig(pub BTreeMap<Address, PruneMode>);
+
+impl HistoryPruneConfig {
+    /// Checks if the configuration is empty
+    pub fn is_empty(&self) -> bool {
+        self.0.is_empty()
+    }
+
+    /// Checks if the address has its own prune mode.
+    pub fn contains(&self, address: &Address) -> bool {
+        self.0.contains_key(address)
+    }
+
+    /// Resolves the [`PruneMode::Duration`] of every address, keeping at least `min_blocks`
+    /// blocks, see [`PruneMode::resolve_duration`].
+    pub fn resolve_durations<E>(
+        &self,
+        tip: BlockNumber,
+        min_blocks: u64,
+        mut block_timestamp: impl FnMut(BlockNumber) -> Result<u64, E>,
+    ) -> Result<Self, E> {
+        resolve_durations(&self.0, tip, min_blocks, &mut block_timestamp).map(Self)
+    }
+
+    /// Given the `tip` block number, returns the block up to which the history of each address
+    /// needs to be pruned, inclusive, or [`None`] if there is nothing to prune yet.
+    pub fn prune_target_blocks(
+        &self,
+        tip: BlockNumber,
+        segment: PruneSegment,
+    ) -> Result<BTreeMap<Address, Option<BlockNumber>>, PruneSegmentError> {
+        self.0
+            .iter()
+            .map(|(address, mode)| {
+                let target = mode.prune_target_block(tip, segment, PrunePurpose::User)?;
+                Ok((*address, target.map(|(block, _)| block)))
+            })
+            .collect()
+    }
+}
+
+fn resolve_durations<E>(
+    modes: &BTreeMap<Address, PruneMode>,
+    tip: BlockNumber,
+    min_blocks: u64,
+    block_timestamp: &mut impl FnMut(BlockNumber) -> Result<u64, E>,
+) -> Result<BTreeMap<Address, PruneMode>, E> {
+    modes
+        .iter()
+        .map(|(address, mode)| {
+            Ok((*address, mode.resolve_duration(tip, min_blocks, &mut *block_timestamp)?))
+        })
+        .collect()
+}
@@ -14,6 +14,12 @@ pub enum PruneMode {
     Distance(u64),
     /// Prune blocks before the specified block number. The specified block number is not pruned.
     Before(BlockNumber),
+    /// Prune blocks with timestamps older than the specified number of seconds before the tip
+    /// block timestamp. In other words, keep blocks from the last N seconds.
+    ///
+    /// Needs to be resolved against the block timestamps with [`PruneMode::resolve_duration`],
+    /// and is never pruned otherwise. Human readable durations like `30d` are used in the config.
+    Duration(#[serde(with = "humantime_secs")] u64),
 }
 
 impl PruneMode {
@@ -43,11 +49,47 @@ impl PruneMode {
             Self::Before(n) if tip - n >= segment.min_blocks(purpose) => {
                 Some(((*n).saturating_sub(1), *self))
             }
+            // Nothing to prune until resolved against the block timestamps
+            Self::Duration(_) => None,
             _ => return Err(PruneSegmentError::Configuration(segment)),
         };
         Ok(result)
     }
 
+    /// Resolves [`PruneMode::Duration`] into [`PruneMode::Distance`] from the `tip`, which keeps
+    /// all blocks with timestamps within the duration before the tip block timestamp, but at
+    /// least `min_blocks` blocks. Other variants are returned as is.
+    ///
+    /// The amount of blocks within a duration depends on the block timestamps, so a duration can't
+    /// be fully validated against the minimum amount of blocks of a segment beforehand. Keeping
+    /// `min_blocks` blocks instead doesn't fail the segment on every run, see
+    /// [`PruneSegment::min_blocks`].
+    ///
+    /// Block timestamps are returned by `block_timestamp` and expected to be non-decreasing, so
+    /// the first block within the duration is found with a binary search.
+    pub fn resolve_duration<E>(
+        self,
+        tip: BlockNumber,
+        min_blocks: u64,
+        mut block_timestamp: impl FnMut(BlockNumber) -> Result<u64, E>,
+    ) -> Result<Self, E> {
+        let Self::Duration(duration) = self else { return Ok(self) };
+
+        let cutoff = block_timestamp(tip)?.saturating_sub(duration);
+        let (mut low, mut high) = (0, tip);
+        while low < high {
+            let mid = low + (high - low) / 2;
+            if block_timestamp(mid)? < cutoff {
+                low = mid + 1;
+            } else {
+                high = mid;
+            }
+        }
+
+        // Keep blocks `low..=tip`, which is nothing to prune yet if `low` is the genesis block.
+        Ok(Self::Distance((tip - low + 1).max(min_blocks)))
+    }
+
     /// Check if target block should be pruned according to the provided prune mode and tip.
     pub const fn should_prune(&self, block: BlockNumber, tip: BlockNumber) -> bool {
         match self {
@@ -59,6 +101,7 @@ impl PruneMode {
                 block < tip - *distance
             }
             Self::Before(n) => *n > block,
+            Self::Duration(_) => false,
         }
     }
 
@@ -68,6 +111,23 @@ impl PruneMode {
     }
 }
 
+/// (De)serializes a number of seconds as a human readable duration, e.g. `30d`.
+mod humantime_secs {
+    use serde::{Deserialize, Deserializer, Serializer};
+    use std::time::Duration;
+
+    pub(super) fn serialize<S: Serializer>(secs: &u64, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.collect_str(&humantime::format_duration(Duration::from_secs(*secs)))
+    }
+
+    pub(super) fn deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<u64, D::Error> {
+        let duration = String::deserialize(deserializer)?;
+        humantime::parse_duration(&duration)
+            .map(|duration| duration.as_secs())
+            .map_err(serde::de::Error::custom)
+    }
+}
+
 #[cfg(test)]
 impl Default for PruneMode {
     fn default() -> Self {
@@ -81,7 +141,7 @@ mod tests {
         PruneMode, PrunePurpose, PruneSegment, PruneSegmentError, MINIMUM_PRUNING_DISTANCE,
     };
     use assert_matches::assert_matches;
-    use serde::Deserialize;
+    use serde::{Deserialize, Serialize};
 
     #[test]
     fn test_prune_target_block() {
@@ -154,7 +214,7 @@ mod tests {
 
     #[test]
     fn prune_mode_deserialize() {
-        #[derive(Debug, Deserialize)]
+        #[derive(Debug, Deserialize, Serialize)]
         struct Config {
             a: Option<PruneMode>,
             b: Option<PruneMode>,
@@ -166,6 +226,7 @@ mod tests {
         a = "full"
         b = { distance = 10 }
         c = { before = 20 }
+        d = { duration = "30d" }
     "#;
 
         assert_matches!(
@@ -174,8 +235,71 @@ mod tests {
                 a: Some(PruneMode::Full),
                 b: Some(PruneMode::Distance(10)),
                 c: Some(PruneMode::Before(20)),
-                d: None
+                d: Some(PruneMode::Duration(2_592_000)),
+            })
+        );
+
+        assert_eq!(
+            toml::to_string(&Config {
+                a: None,
+                b: None,
+                c: None,
+                d: Some(PruneMode::Duration(90))
             })
+            .unwrap(),
+            "[d]\nduration = \"1m 30s\"\n"
+        );
+    }
+
+    #[test]
+    fn test_resolve_duration() {
+        // One block every 12 seconds
+        let block_timestamp = |block_number: u64| Ok::<_, ()>(block_number * 12);
+        let tip = 20000;
+        let segment = PruneSegment::Receipts;
+
+        let min_blocks = segment.min_blocks(PrunePurpose::User);
+
+        // Blocks `19000..=20000` are within the duration
+        let mode = PruneMode::Duration(1000 * 12).resolve_duration(tip, 0, block_timestamp);
+        assert_eq!(mode, Ok(PruneMode::Distance(1001)));
+        // Too close to the tip, so the minimum amount of blocks is kept instead
+        let mode =
+            PruneMode::Duration(1000 * 12).resolve_duration(tip, min_blocks, block_timestamp);
+        assert_eq!(mode, Ok(PruneMode::Distance(MINIMUM_PRUNING_DISTANCE)));
+        assert_eq!(
+            mode.unwrap().prune_target_block(tip, segment, PrunePurpose::User),
+            Ok(Some((
+                tip - MINIMUM_PRUNING_DISTANCE,
+                PruneMode::Distance(MINIMUM_PRUNING_DISTANCE)
+            )))
+        );
+
+        // The first block after the cutoff is kept
+        let mode = PruneMode::Duration(MINIMUM_PRUNING_DISTANCE * 12 + 1)
+            .resolve_duration(tip, min_blocks, block_timestamp)
+            .unwrap();
+        assert_eq!(mode, PruneMode::Distance(MINIMUM_PRUNING_DISTANCE + 1));
+        assert_eq!(
+            mode.prune_target_block(tip, segment, PrunePurpose::User),
+            Ok(Some((tip - MINIMUM_PRUNING_DISTANCE - 1, mode)))
+        );
+
+        // All blocks are within the duration
+        let mode = PruneMode::Duration(tip * 12)
+            .resolve_duration(tip, min_blocks, block_timestamp)
+            .unwrap();
+        assert_eq!(mode.prune_target_block(tip, segment, PrunePurpose::User), Ok(None));
+
+        // Nothing to prune without resolving
+        assert_eq!(
+            PruneMode::Duration(0).prune_target_block(tip, segment, PrunePurpose::User),
+            Ok(None)
+        );
+        assert!(!PruneMode::Duration(0).should_prune(0, tip));
+        assert_eq!(
+            PruneMode::Before(10).resolve_duration(tip, min_blocks, block_timestamp),
+            Ok(PruneMode::Before(10))
         );
     }
 }
@@ -27,15 +27,28 @@ pub enum PruneSegment {
     Headers,
     /// Prune segment responsible for the `Transactions` table.
     Transactions,
+    /// Prune segment responsible for the `AccountChangeSets` table, after it has been moved to
+    /// static files.
+    AccountChangeSets,
+    /// Prune segment responsible for the `StorageChangeSets` table, after it has been moved to
+    /// static files.
+    StorageChangeSets,
+    /// Prune segment responsible for the `Bytecodes` table, removing the bytecodes that are
+    /// neither referenced by the current state nor by the retained account change sets.
+    Bytecodes,
 }
 
 impl PruneSegment {
     /// Returns minimum number of blocks to left in the database for this segment.
     pub const fn min_blocks(&self, purpose: PrunePurpose) -> u64 {
         match self {
-            Self::SenderRecovery | Self::TransactionLookup | Self::Headers | Self::Transactions => {
-                0
-            }
+            Self::SenderRecovery |
+            Self::TransactionLookup |
+            Self::Headers |
+            Self::Transactions |
+            Self::AccountChangeSets |
+            Self::StorageChangeSets |
+            Self::Bytecodes => 0,
             Self::Receipts if purpose.is_static_file() => 0,
             Self::ContractLogs | Self::AccountHistory | Self::StorageHistory => {
                 MINIMUM_PRUNING_DISTANCE
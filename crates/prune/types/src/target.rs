@@ -1,4 +1,4 @@
-use crate::{PruneMode, ReceiptsLogPruneConfig};
+use crate::{HistoryPruneConfig, PruneMode, ReceiptsLogPruneConfig};
 use serde::{Deserialize, Deserializer, Serialize};
 
 /// Minimum distance from the tip necessary for the node to work correctly:
@@ -37,6 +37,32 @@ pub struct PruneModes {
         deserialize_with = "deserialize_opt_prune_mode_with_min_blocks::<MINIMUM_PRUNING_DISTANCE, _>"
     )]
     pub storage_history: Option<PruneMode>,
+    /// Account History pruning configuration of specific addresses, overriding `account_history`
+    /// for them. Only used if `account_history` is set.
+    ///
+    /// The history of an address is only retained from the point it's added on, since it may
+    /// have been pruned according to `account_history` before.
+    #[serde(
+        skip_serializing_if = "HistoryPruneConfig::is_empty",
+        deserialize_with = "deserialize_history_prune_config_with_min_blocks::<MINIMUM_PRUNING_DISTANCE, _>"
+    )]
+    pub account_history_filter: HistoryPruneConfig,
+    /// Storage History pruning configuration of specific addresses, overriding `storage_history`
+    /// for them. Only used if `storage_history` is set.
+    ///
+    /// The history of an address is only retained from the point it's added on, since it may
+    /// have been pruned according to `storage_history` before.
+    #[serde(
+        skip_serializing_if = "HistoryPruneConfig::is_empty",
+        deserialize_with = "deserialize_history_prune_config_with_min_blocks::<MINIMUM_PRUNING_DISTANCE, _>"
+    )]
+    pub storage_history_filter: HistoryPruneConfig,
+    /// Bytecodes pruning configuration. Bytecodes that are neither referenced by the current
+    /// state nor by the retained account change sets are removed once the target block of this
+    /// mode advanced far enough since the last removal, as finding them requires a scan of the
+    /// whole state.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub bytecodes: Option<PruneMode>,
     /// Receipts pruning configuration by retaining only those receipts that contain logs emitted
     /// by the specified addresses, discarding others. This setting is overridden by `receipts`.
     ///
@@ -59,6 +85,9 @@ impl PruneModes {
             receipts: Some(PruneMode::Full),
             account_history: Some(PruneMode::Full),
             storage_history: Some(PruneMode::Full),
+            account_history_filter: Default::default(),
+            storage_history_filter: Default::default(),
+            bytecodes: Some(PruneMode::Full),
             receipts_log_filter: Default::default(),
         }
     }
@@ -82,28 +111,66 @@ impl PruneModes {
 /// 2. For [`PruneMode::Distance(distance`)], it fails if `distance < MIN_BLOCKS + 1`. `+ 1` is
 ///    needed because `PruneMode::Distance(0)` means that we leave zero blocks from the latest,
 ///    meaning we have one block in the database.
+/// 3. For [`PruneMode::Duration(seconds`)], it fails if `seconds < MIN_BLOCKS`, because block
+///    timestamps are strictly increasing, so such a duration never leaves `MIN_BLOCKS` blocks. The
+///    amount of blocks a longer duration leaves depends on the block timestamps, so at least
+///    `MIN_BLOCKS` blocks are left when it's resolved by [`PruneMode::resolve_duration`].
 fn deserialize_opt_prune_mode_with_min_blocks<'de, const MIN_BLOCKS: u64, D: Deserializer<'de>>(
     deserializer: D,
 ) -> Result<Option<PruneMode>, D::Error> {
-    let prune_mode = Option::<PruneMode>::deserialize(deserializer)?;
+    Option::<PruneMode>::deserialize(deserializer)?
+        .map(validate_prune_mode_with_min_blocks::<MIN_BLOCKS, _>)
+        .transpose()
+}
 
+/// Deserializes [`HistoryPruneConfig`] and validates that the prune mode of every address is not
+/// less than the const generic parameter `MIN_BLOCKS`, see
+/// [`deserialize_opt_prune_mode_with_min_blocks`].
+fn deserialize_history_prune_config_with_min_blocks<
+    'de,
+    const MIN_BLOCKS: u64,
+    D: Deserializer<'de>,
+>(
+    deserializer: D,
+) -> Result<HistoryPruneConfig, D::Error> {
+    let config = HistoryPruneConfig::deserialize(deserializer)?;
+    for prune_mode in config.0.values() {
+        validate_prune_mode_with_min_blocks::<MIN_BLOCKS, D::Error>(*prune_mode)?;
+    }
+    Ok(config)
+}
+
+fn validate_prune_mode_with_min_blocks<const MIN_BLOCKS: u64, E: serde::de::Error>(
+    prune_mode: PruneMode,
+) -> Result<PruneMode, E> {
     match prune_mode {
-        Some(PruneMode::Full) if MIN_BLOCKS > 0 => {
-            Err(serde::de::Error::invalid_value(
+        PruneMode::Full if MIN_BLOCKS > 0 => {
+            Err(E::invalid_value(
                 serde::de::Unexpected::Str("full"),
                 // This message should have "expected" wording
                 &format!("prune mode that leaves at least {MIN_BLOCKS} blocks in the database")
                     .as_str(),
             ))
         }
-        Some(PruneMode::Distance(distance)) if distance < MIN_BLOCKS => {
-            Err(serde::de::Error::invalid_value(
+        PruneMode::Distance(distance) if distance < MIN_BLOCKS => {
+            Err(E::invalid_value(
                 serde::de::Unexpected::Unsigned(distance),
                 // This message should have "expected" wording
                 &format!("prune mode that leaves at least {MIN_BLOCKS} blocks in the database")
                     .as_str(),
             ))
         }
+        PruneMode::Duration(seconds) if seconds < MIN_BLOCKS => {
+            Err(E::invalid_value(
+                serde::de::Unexpected::Other("duration"),
+                // This message should have "expected" wording
+                &format!(
+                    "prune duration of at least {MIN_BLOCKS} seconds, to leave at least \
+                     {MIN_BLOCKS} blocks in the database"
+                )
+                .as_str(),
+            ))
+        }
         _ => Ok(prune_mode),
     }
 }
@@ -132,5 +199,37 @@ mod tests {
             serde_json::from_str::<V>(r#""full""#),
             Err(err) if err.to_string() == "invalid value: string \"full\", expected prune mode that leaves at least 10 blocks in the database"
         );
+
+        assert!(serde_json::from_str::<V>(r#"{"duration": "10s"}"#).is_ok());
+        assert_matches!(
+            serde_json::from_str::<V>(r#"{"duration": "9s"}"#),
+            Err(err) if err.to_string() == "invalid value: duration, expected prune duration of at least 10 seconds, to leave at least 10 blocks in the database"
+        );
+    }
+
+    #[test]
+    fn test_deserialize_history_prune_config_with_min_blocks() {
+        #[derive(Debug, Deserialize, PartialEq, Eq)]
+        struct V(
+            #[serde(
+                deserialize_with = "deserialize_history_prune_config_with_min_blocks::<10, _>"
+            )]
+            HistoryPruneConfig,
+        );
+
+        assert!(serde_json::from_str::<V>(
+            r#"{"0x0000000000000000000000000000000000000001": {"distance": 10}, "0x0000000000000000000000000000000000000002": {"before": 5}}"#
+        )
+        .is_ok());
+        assert_matches!(
+            serde_json::from_str::<V>(
+                r#"{"0x0000000000000000000000000000000000000001": {"distance": 10}, "0x0000000000000000000000000000000000000002": {"distance": 9}}"#
+            ),
+            Err(err) if err.to_string() == "invalid value: integer `9`, expected prune mode that leaves at least 10 blocks in the database"
+        );
+        assert_matches!(
+            serde_json::from_str::<V>(r#"{"0x0000000000000000000000000000000000000001": "full"}"#),
+            Err(err) if err.to_string() == "invalid value: string \"full\", expected prune mode that leaves at least 10 blocks in the database"
+        );
     }
 }
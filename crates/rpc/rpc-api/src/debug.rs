@@ -1,11 +1,12 @@
 use jsonrpsee::{core::RpcResult, proc_macros::rpc};
 use reth_primitives::{Address, BlockId, BlockNumberOrTag, Bytes, B256};
 use reth_rpc_types::{
+    debug::BadBlock,
     trace::geth::{
         BlockTraceResult, GethDebugTracingCallOptions, GethDebugTracingOptions, GethTrace,
         TraceResult,
     },
-    Bundle, RichBlock, StateContext, TransactionRequest,
+    Bundle, StateContext, TransactionRequest,
 };
 use std::collections::HashMap;
 
@@ -35,19 +36,39 @@ pub trait DebugApi {
     #[method(name = "getRawReceipts")]
     async fn raw_receipts(&self, block_id: BlockId) -> RpcResult<Vec<Bytes>>;
 
-    /// Returns an array of recent bad blocks that the client has seen on the network.
+    /// Returns an array of recent bad blocks that the client has seen on the network, most recently
+    /// seen first.
     #[method(name = "getBadBlocks")]
-    async fn bad_blocks(&self) -> RpcResult<Vec<RichBlock>>;
+    async fn bad_blocks(&self) -> RpcResult<Vec<BadBlock>>;
 
     /// Returns the structured logs created during the execution of EVM between two blocks
     /// (excluding start) as a JSON object.
+    ///
+    /// For the third parameter see [`GethDebugTracingOptions`] reference.
     #[method(name = "traceChain")]
     async fn debug_trace_chain(
         &self,
         start_exclusive: BlockNumberOrTag,
         end_inclusive: BlockNumberOrTag,
+        opts: Option<GethDebugTracingOptions>,
     ) -> RpcResult<Vec<BlockTraceResult>>;
 
+    /// Creates a subscription that streams the traces of all blocks between two blocks (excluding
+    /// start), in ascending order, one [`BlockTraceResult`] per block.
+    ///
+    /// The subscription ends once the last block has been sent, or when tracing a block fails.
+    #[subscription(
+        name = "subscribeTraceChain" => "traceChainResult",
+        unsubscribe = "unsubscribeTraceChain",
+        item = BlockTraceResult
+    )]
+    async fn debug_subscribe_trace_chain(
+        &self,
+        start_exclusive: BlockNumberOrTag,
+        end_inclusive: BlockNumberOrTag,
+        opts: Option<GethDebugTracingOptions>,
+    ) -> jsonrpsee::core::SubscriptionResult;
+
     /// The `debug_traceBlock` method will return a full stack trace of all invoked opcodes of all
     /// transaction that were included in this block.
     ///
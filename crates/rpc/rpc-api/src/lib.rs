@@ -37,8 +37,10 @@ pub use servers::*;
 pub mod servers {
     pub use crate::{
         admin::AdminApiServer,
+        anvil::AnvilApiServer,
         debug::DebugApiServer,
         engine::{EngineApiServer, EngineEthApiServer},
+        ganache::GanacheApiServer,
         mev::MevApiServer,
         net::NetApiServer,
         otterscan::OtterscanServer,
@@ -51,7 +53,7 @@ pub mod servers {
     };
     pub use reth_rpc_eth_api::{
         self as eth, EthApiServer, EthBundleApiServer, EthCallBundleApiServer, EthFilterApiServer,
-        EthPubSubApiServer,
+        EthPubSubApiServer, EthSendBundleApiServer,
     };
 }
 
@@ -72,6 +74,7 @@ pub mod clients {
         mev::MevApiClient,
         net::NetApiClient,
         otterscan::OtterscanClient,
+        reth::RethApiClient,
         rpc::RpcApiServer,
         trace::TraceApiClient,
         txpool::TxPoolApiClient,
@@ -80,5 +83,6 @@ pub mod clients {
     };
     pub use reth_rpc_eth_api::{
         EthApiClient, EthBundleApiClient, EthCallBundleApiClient, EthFilterApiClient,
+        EthSendBundleApiClient,
     };
 }
@@ -64,6 +64,9 @@ pub trait Otterscan {
     ) -> RpcResult<OtsBlockTransactions>;
 
     /// Gets paginated inbound/outbound transaction calls for a certain address.
+    ///
+    /// Only the blocks that changed the account or its storage are searched, so unlike Erigon,
+    /// transactions that only called the address without changing its state are not returned.
     #[method(name = "searchTransactionsBefore")]
     async fn search_transactions_before(
         &self,
@@ -73,6 +76,9 @@ pub trait Otterscan {
     ) -> RpcResult<TransactionsWithReceipts>;
 
     /// Gets paginated inbound/outbound transaction calls for a certain address.
+    ///
+    /// Only the blocks that changed the account or its storage are searched, so unlike Erigon,
+    /// transactions that only called the address without changing its state are not returned.
     #[method(name = "searchTransactionsAfter")]
     async fn search_transactions_after(
         &self,
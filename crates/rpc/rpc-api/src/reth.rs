@@ -1,5 +1,6 @@
 use jsonrpsee::{core::RpcResult, proc_macros::rpc};
 use reth_primitives::{Address, BlockId, U256};
+use reth_rpc_types::reth::{TableDigest, TableDigestCursor};
 use std::collections::HashMap;
 
 /// Reth API namespace for reth-specific methods
@@ -12,4 +13,18 @@ pub trait RethApi {
         &self,
         block_id: BlockId,
     ) -> RpcResult<HashMap<Address, U256>>;
+
+    /// Returns the digest of the raw entries of a database table from `from` up to, but excluding,
+    /// `to`, unbounded if `None`.
+    ///
+    /// The number of digested entries is capped by the node, in which case the returned `next` is
+    /// the position to continue from.
+    #[method(name = "tableDigest")]
+    async fn reth_table_digest(
+        &self,
+        table: String,
+        from: Option<TableDigestCursor>,
+        to: Option<TableDigestCursor>,
+        limit: Option<u64>,
+    ) -> RpcResult<TableDigest>;
 }
@@ -18,7 +18,10 @@
 //! ```
 //! use reth_evm::ConfigureEvm;
 //! use reth_network_api::{NetworkInfo, Peers};
-//! use reth_provider::{AccountReader, CanonStateSubscriptions, ChangeSetReader, FullRpcProvider};
+//! use reth_provider::{
+//!     AccountHistoryReader, AccountReader, BadBlockReader, CanonStateSubscriptions,
+//!     ChangeSetReader, FullRpcProvider, TableDigestReader,
+//! };
 //! use reth_rpc::EthApi;
 //! use reth_rpc_builder::{
 //!     RethRpcModule, RpcModuleBuilder, RpcServerConfig, ServerBuilder, TransportRpcModuleConfig,
@@ -33,7 +36,12 @@
 //!     events: Events,
 //!     evm_config: EvmConfig,
 //! ) where
-//!     Provider: FullRpcProvider + AccountReader + ChangeSetReader,
+//!     Provider: FullRpcProvider
+//!         + AccountReader
+//!         + AccountHistoryReader
+//!         + BadBlockReader
+//!         + ChangeSetReader
+//!         + TableDigestReader,
 //!     Pool: TransactionPool + 'static,
 //!     Network: NetworkInfo + Peers + Clone + 'static,
 //!     Events: CanonStateSubscriptions + Clone + 'static,
@@ -69,7 +77,10 @@
 //! use reth_engine_primitives::EngineTypes;
 //! use reth_evm::ConfigureEvm;
 //! use reth_network_api::{NetworkInfo, Peers};
-//! use reth_provider::{AccountReader, CanonStateSubscriptions, ChangeSetReader, FullRpcProvider};
+//! use reth_provider::{
+//!     AccountHistoryReader, AccountReader, BadBlockReader, CanonStateSubscriptions,
+//!     ChangeSetReader, FullRpcProvider, TableDigestReader,
+//! };
 //! use reth_rpc::EthApi;
 //! use reth_rpc_api::EngineApiServer;
 //! use reth_rpc_builder::{
@@ -88,7 +99,12 @@
 //!     engine_api: EngineApi,
 //!     evm_config: EvmConfig,
 //! ) where
-//!     Provider: FullRpcProvider + AccountReader + ChangeSetReader,
+//!     Provider: FullRpcProvider
+//!         + AccountReader
+//!         + AccountHistoryReader
+//!         + BadBlockReader
+//!         + ChangeSetReader
+//!         + TableDigestReader,
 //!     Pool: TransactionPool + 'static,
 //!     Network: NetworkInfo + Peers + Clone + 'static,
 //!     Events: CanonStateSubscriptions + Clone + 'static,
@@ -155,12 +171,13 @@ use reth_engine_primitives::EngineTypes;
 use reth_evm::ConfigureEvm;
 use reth_network_api::{noop::NoopNetwork, NetworkInfo, Peers};
 use reth_provider::{
-    AccountReader, BlockReader, CanonStateSubscriptions, ChainSpecProvider, ChangeSetReader,
-    EvmEnvProvider, FullRpcProvider, StateProviderFactory,
+    AccountHistoryReader, AccountReader, BadBlockReader, BlockReader, CanonStateSubscriptions,
+    ChainSpecProvider, ChangeSetReader, EvmEnvProvider, FullRpcProvider, StateProviderFactory,
+    TableDigestReader,
 };
 use reth_rpc::{
-    AdminApi, DebugApi, EngineEthApi, EthBundle, NetApi, OtterscanApi, RPCApi, RethApi, TraceApi,
-    TxPoolApi, Web3Api,
+    AdminApi, DebugApi, EngineEthApi, EthBundle, EthSimBundle, NetApi, OtterscanApi, RPCApi,
+    RethApi, TraceApi, TxPoolApi, Web3Api,
 };
 use reth_rpc_api::servers::*;
 use reth_rpc_eth_api::{
@@ -223,7 +240,12 @@ pub async fn launch<Provider, Pool, Network, Tasks, Events, EvmConfig, EthApi>(
     eth: DynEthApiBuilder<Provider, Pool, EvmConfig, Network, Tasks, Events, EthApi>,
 ) -> Result<RpcServerHandle, RpcError>
 where
-    Provider: FullRpcProvider + AccountReader + ChangeSetReader,
+    Provider: FullRpcProvider
+        + AccountReader
+        + AccountHistoryReader
+        + BadBlockReader
+        + ChangeSetReader
+        + TableDigestReader,
     Pool: TransactionPool + 'static,
     Network: NetworkInfo + Peers + Clone + 'static,
     Tasks: TaskSpawner + Clone + 'static,
@@ -409,7 +431,12 @@ impl<Provider, Pool, Network, Tasks, Events, EvmConfig>
 impl<Provider, Pool, Network, Tasks, Events, EvmConfig>
     RpcModuleBuilder<Provider, Pool, Network, Tasks, Events, EvmConfig>
 where
-    Provider: FullRpcProvider + AccountReader + ChangeSetReader,
+    Provider: FullRpcProvider
+        + AccountReader
+        + AccountHistoryReader
+        + BadBlockReader
+        + ChangeSetReader
+        + TableDigestReader,
     Pool: TransactionPool + 'static,
     Network: NetworkInfo + Peers + Clone + 'static,
     Tasks: TaskSpawner + Clone + 'static,
@@ -793,7 +820,12 @@ where
 impl<Provider, Pool, Network, Tasks, Events, EthApi>
     RpcRegistryInner<Provider, Pool, Network, Tasks, Events, EthApi>
 where
-    Provider: FullRpcProvider + AccountReader + ChangeSetReader,
+    Provider: FullRpcProvider
+        + AccountReader
+        + AccountHistoryReader
+        + BadBlockReader
+        + ChangeSetReader
+        + TableDigestReader,
     Network: NetworkInfo + Peers + Clone + 'static,
     Tasks: TaskSpawner + Clone + 'static,
     EthApi: Clone,
@@ -912,17 +944,30 @@ where
         EthBundle::new(eth_api, self.blocking_pool_guard.clone())
     }
 
+    /// Instantiates [`EthSimBundle`] Api
+    ///
+    /// # Panics
+    ///
+    /// If called outside of the tokio runtime. See also [`Self::eth_api`]
+    pub fn sim_bundle_api(&self) -> EthSimBundle<EthApi>
+    where
+        EthApi: EthTransactions + LoadPendingBlock + Call,
+    {
+        let eth_api = self.eth_api().clone();
+        EthSimBundle::new(eth_api, self.blocking_pool_guard.clone())
+    }
+
     /// Instantiates `OtterscanApi`
     ///
     /// # Panics
     ///
     /// If called outside of the tokio runtime. See also [`Self::eth_api`]
-    pub fn otterscan_api(&self) -> OtterscanApi<EthApi>
+    pub fn otterscan_api(&self) -> OtterscanApi<Provider, EthApi>
     where
         EthApi: EthApiServer,
     {
         let eth_api = self.eth_api().clone();
-        OtterscanApi::new(eth_api)
+        OtterscanApi::new(self.provider.clone(), eth_api)
     }
 
     /// Instantiates `DebugApi`
@@ -960,7 +1005,12 @@ where
 impl<Provider, Pool, Network, Tasks, Events, EthApi>
     RpcRegistryInner<Provider, Pool, Network, Tasks, Events, EthApi>
 where
-    Provider: FullRpcProvider + AccountReader + ChangeSetReader,
+    Provider: FullRpcProvider
+        + AccountReader
+        + AccountHistoryReader
+        + BadBlockReader
+        + ChangeSetReader
+        + TableDigestReader,
     Pool: TransactionPool + 'static,
     Network: NetworkInfo + Peers + Clone + 'static,
     Tasks: TaskSpawner + Clone + 'static,
@@ -1091,14 +1141,29 @@ where
                         )
                         .into_rpc()
                         .into(),
-                        RethRpcModule::Ots => OtterscanApi::new(eth_api.clone()).into_rpc().into(),
+                        RethRpcModule::Ots => {
+                            OtterscanApi::new(self.provider.clone(), eth_api.clone())
+                                .into_rpc()
+                                .into()
+                        }
                         RethRpcModule::Reth => {
                             RethApi::new(self.provider.clone(), Box::new(self.executor.clone()))
                                 .into_rpc()
                                 .into()
                         }
-                        RethRpcModule::EthCallBundle => {
-                            EthBundle::new(eth_api.clone(), self.blocking_pool_guard.clone())
+                        RethRpcModule::EthCallBundle => EthCallBundleApiServer::into_rpc(
+                            EthBundle::new(eth_api.clone(), self.blocking_pool_guard.clone()),
+                        )
+                        .into(),
+                        RethRpcModule::EthSendBundle => EthSendBundleApiServer::into_rpc(
+                            EthBundle::new(eth_api.clone(), self.blocking_pool_guard.clone()),
+                        )
+                        .into(),
+                        // anvil is only served by dev nodes, and flashbots requires the node's
+                        // block executor and consensus, so both are installed by the node launcher
+                        RethRpcModule::Anvil | RethRpcModule::Flashbots => Methods::new(),
+                        RethRpcModule::Mev => {
+                            EthSimBundle::new(eth_api.clone(), self.blocking_pool_guard.clone())
                                 .into_rpc()
                                 .into()
                         }
@@ -1703,6 +1768,31 @@ impl TransportRpcModules {
         self.merge_ipc(other)?;
         Ok(())
     }
+
+    /// Merge the given [Methods] in the methods of all transports that are configured with the
+    /// given module.
+    ///
+    /// Fails if any of the methods in other is present already.
+    pub fn merge_if_module_configured(
+        &mut self,
+        module: RethRpcModule,
+        other: impl Into<Methods>,
+    ) -> Result<(), RegisterMethodError> {
+        let other = other.into();
+        let is_selected = |selection: Option<&RpcModuleSelection>| {
+            selection.is_some_and(|selection| selection.iter_selection().any(|m| m == module))
+        };
+        if is_selected(self.config.http()) {
+            self.merge_http(other.clone())?;
+        }
+        if is_selected(self.config.ws()) {
+            self.merge_ws(other.clone())?;
+        }
+        if is_selected(self.config.ipc()) {
+            self.merge_ipc(other)?;
+        }
+        Ok(())
+    }
 }
 
 /// A handle to the spawned servers.
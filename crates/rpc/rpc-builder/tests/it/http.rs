@@ -237,7 +237,8 @@ where
     DebugApiClient::raw_block(client, block_id).await.unwrap_err();
     DebugApiClient::raw_transaction(client, B256::default()).await.unwrap();
     DebugApiClient::raw_receipts(client, block_id).await.unwrap();
-    assert!(is_unimplemented(DebugApiClient::bad_blocks(client).await.err().unwrap()));
+    DebugApiClient::debug_trace_chain(client, 1u64.into(), 1u64.into(), None).await.unwrap_err();
+    assert!(DebugApiClient::bad_blocks(client).await.unwrap().is_empty());
 }
 
 async fn test_basic_net_calls<C>(client: &C)
@@ -323,18 +324,15 @@ where
         .err()
         .unwrap();
 
-    assert!(is_unimplemented(
-        OtterscanClient::search_transactions_before(client, address, block_number, page_size,)
-            .await
-            .err()
-            .unwrap()
-    ));
-    assert!(is_unimplemented(
-        OtterscanClient::search_transactions_after(client, address, block_number, page_size,)
+    let page =
+        OtterscanClient::search_transactions_before(client, address, block_number, page_size)
             .await
-            .err()
-            .unwrap()
-    ));
+            .unwrap();
+    assert!(page.txs.is_empty() && !page.first_page && page.last_page);
+    let page = OtterscanClient::search_transactions_after(client, address, block_number, page_size)
+        .await
+        .unwrap();
+    assert!(page.txs.is_empty() && page.first_page && !page.last_page);
     assert!(OtterscanClient::get_transaction_by_sender_and_nonce(client, sender, nonce)
         .await
         .err()
@@ -22,6 +22,14 @@ pub trait EthSigner: Send + Sync + DynClone {
         self.accounts().contains(addr)
     }
 
+    /// Returns `true` if this signer signs transactions on behalf of the address without holding
+    /// its key, so that their signatures don't recover to the address.
+    ///
+    /// This is only the case for impersonated accounts on dev nodes.
+    fn is_impersonating(&self, _addr: &Address) -> bool {
+        false
+    }
+
     /// Returns the signature
     async fn sign(&self, address: Address, message: &[u8]) -> Result<Signature>;
 
@@ -86,6 +86,10 @@ pub trait EthState: LoadState + SpawnBlocking {
     }
 
     /// Returns values stored of given account, with Merkle-proof, at given blocknumber.
+    ///
+    /// Proofs of historical blocks within the proof window are generated on top of the latest trie
+    /// by reverting the state from the account and storage change sets. Returns
+    /// [`EthApiError::HistoryPruned`] if the history of the block has been pruned.
     fn get_proof(
         &self,
         address: Address,
@@ -7,7 +7,7 @@ use alloy_dyn_abi::TypedData;
 use futures::Future;
 use reth_primitives::{
     Address, BlockId, Bytes, Receipt, SealedBlockWithSenders, TransactionMeta, TransactionSigned,
-    TxHash, TxKind, B256, U256,
+    TransactionSignedEcRecovered, TxHash, TxKind, B256, U256,
 };
 use reth_provider::{BlockReaderIdExt, ReceiptProvider, TransactionsProvider};
 use reth_rpc_eth_types::{
@@ -467,8 +467,14 @@ pub trait EthTransactions: LoadTransaction {
 
             let signed_tx = self.sign_request(&from, transaction)?;
 
-            let recovered =
-                signed_tx.into_ecrecovered().ok_or(EthApiError::InvalidTransactionSignature)?;
+            let recovered = if self.find_signer(&from)?.is_impersonating(&from) {
+                // impersonated accounts on dev nodes are signed with a placeholder key, so the
+                // transaction is sent from `from` instead of the recovered signer
+                signed_tx.recover_signer().ok_or(EthApiError::InvalidTransactionSignature)?;
+                TransactionSignedEcRecovered::from_signed_transaction(signed_tx, from)
+            } else {
+                signed_tx.into_ecrecovered().ok_or(EthApiError::InvalidTransactionSignature)?
+            };
 
             let pool_transaction = match recovered.try_into() {
                 Ok(converted) => converted,
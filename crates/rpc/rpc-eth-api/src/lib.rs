@@ -18,7 +18,7 @@ pub mod filter;
 pub mod helpers;
 pub mod pubsub;
 
-pub use bundle::{EthBundleApiServer, EthCallBundleApiServer};
+pub use bundle::{EthBundleApiServer, EthCallBundleApiServer, EthSendBundleApiServer};
 pub use core::{EthApiServer, FullEthApiServer};
 pub use filter::EthFilterApiServer;
 pub use helpers::{
@@ -30,7 +30,7 @@ pub use pubsub::EthPubSubApiServer;
 pub use helpers::transaction::RawTransactionForwarder;
 
 #[cfg(feature = "client")]
-pub use bundle::{EthBundleApiClient, EthCallBundleApiClient};
+pub use bundle::{EthBundleApiClient, EthCallBundleApiClient, EthSendBundleApiClient};
 #[cfg(feature = "client")]
 pub use core::EthApiClient;
 #[cfg(feature = "client")]
@@ -60,6 +60,9 @@ pub enum EthApiError {
     /// Thrown when the target block for proof computation exceeds the maximum configured window.
     #[error("distance to target block exceeds maximum proof window")]
     ExceedsMaxProofWindow,
+    /// Thrown when the state or history required for the request has been pruned.
+    #[error(transparent)]
+    HistoryPruned(reth_errors::ProviderError),
     /// An internal error where prevrandao is not set in the evm's environment
     #[error("prevrandao not in the EVM's environment after merge")]
     PrevrandaoNotSet,
@@ -170,7 +173,9 @@ impl From<EthApiError> for jsonrpsee_types::error::ErrorObject<'static> {
             EthApiError::EvmCustom(_) |
             EthApiError::EvmPrecompile(_) |
             EthApiError::InvalidRewardPercentiles => internal_rpc_err(error.to_string()),
-            EthApiError::UnknownBlockNumber | EthApiError::UnknownBlockOrTxIndex => {
+            EthApiError::UnknownBlockNumber |
+            EthApiError::UnknownBlockOrTxIndex |
+            EthApiError::HistoryPruned(_) => {
                 rpc_error_with_code(EthRpcErrorCode::ResourceNotFound.code(), error.to_string())
             }
             EthApiError::UnknownSafeOrFinalizedBlock => {
@@ -225,6 +230,11 @@ impl From<reth_errors::ProviderError> for EthApiError {
             ProviderError::FinalizedBlockNotFound | ProviderError::SafeBlockNotFound => {
                 Self::UnknownSafeOrFinalizedBlock
             }
+            err @ (ProviderError::UnknownTable(_) | ProviderError::TooManyHistorySlots { .. }) => {
+                Self::InvalidParams(err.to_string())
+            }
+            err @ (ProviderError::StateAtBlockPruned(_) |
+            ProviderError::AddressHistoryPruned { .. }) => Self::HistoryPruned(err),
             err => Self::Internal(err.into()),
         }
     }
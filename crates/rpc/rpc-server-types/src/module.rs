@@ -260,6 +260,19 @@ pub enum RethRpcModule {
     /// This is separate from [`RethRpcModule::Eth`] because it is a non standardized call that
     /// should be opt-in.
     EthCallBundle,
+    /// For the non-standard `eth_` namespace calls `eth_sendBundle` and `eth_cancelBundle`, which
+    /// submit bundles to the bundle pool of the payload builder
+    ///
+    /// This is separate from [`RethRpcModule::Eth`] because it is a non standardized call that
+    /// should be opt-in.
+    EthSendBundle,
+    /// `anvil_` and `evm_` modules for local testing, only available on dev nodes started with
+    /// the legacy launcher
+    Anvil,
+    /// `flashbots_` module, for validating block submissions of builders
+    Flashbots,
+    /// `mev_` module, for simulating MEV-Share bundles
+    Mev,
 }
 
 // === impl RethRpcModule ===
@@ -309,6 +322,10 @@ impl FromStr for RethRpcModule {
             "reth" => Self::Reth,
             "ots" => Self::Ots,
             "eth-call-bundle" | "eth_callBundle" => Self::EthCallBundle,
+            "eth-send-bundle" | "eth_sendBundle" => Self::EthSendBundle,
+            "anvil" => Self::Anvil,
+            "flashbots" => Self::Flashbots,
+            "mev" => Self::Mev,
             _ => return Err(ParseError::VariantNotFound),
         })
     }
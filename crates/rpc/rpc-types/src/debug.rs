@@ -0,0 +1,21 @@
+//! Types for the `debug` namespace.
+
+use alloy_primitives::{Bytes, B256};
+use alloy_rpc_types::Block;
+use serde::{Deserialize, Serialize};
+
+/// A block that failed validation, as returned by `debug_getBadBlocks`.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BadBlock {
+    /// Hash of the block.
+    pub hash: B256,
+    /// The block, with full transactions if their senders could be recovered.
+    pub block: Block,
+    /// The RLP encoded block.
+    pub rlp: Bytes,
+    /// The error the block failed validation with.
+    pub error: String,
+    /// The state root of the parent block, the state the block was executed on.
+    pub pre_state_root: B256,
+}
@@ -25,6 +25,10 @@ pub use alloy_rpc_types::*;
 // Ethereum specific serde types coming from alloy.
 pub use alloy_serde::*;
 
+pub mod debug;
+
+pub mod reth;
+
 pub mod trace {
     //! RPC types for trace endpoints and inspectors.
     pub use alloy_rpc_types_trace::*;
@@ -36,13 +40,17 @@ pub use alloy_rpc_types_admin as admin;
 // Anvil specific rpc types coming from alloy.
 pub use alloy_rpc_types_anvil as anvil;
 
-// re-export mev
-pub use alloy_rpc_types_mev as mev;
+// mev types, extending the MEV-Share types with nested bundles
+pub mod mev;
 
 // re-export beacon
 #[cfg(feature = "jsonrpsee-types")]
 pub use alloy_rpc_types_beacon as beacon;
 
+// relay types, extending the beacon relay types
+#[cfg(feature = "jsonrpsee-types")]
+pub mod relay;
+
 // re-export txpool
 pub use alloy_rpc_types_txpool as txpool;
 
@@ -0,0 +1,101 @@
+//! MEV bundle types, extending the MEV-Share types with nested bundles and per transaction
+//! simulation results.
+
+use alloy_primitives::{Address, Bytes, TxHash, U256};
+use serde::{Deserialize, Serialize};
+
+pub use alloy_rpc_types_mev::*;
+
+/// A bundle of transactions for `mev_sendBundle` and `mev_simBundle`.
+#[derive(Clone, Debug, Default, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct SendBundleRequest {
+    /// The version of the MEV-share API to use.
+    #[serde(rename = "version")]
+    pub protocol_version: ProtocolVersion,
+    /// Data used by block builders to check if the bundle should be considered for inclusion.
+    pub inclusion: Inclusion,
+    /// The transactions and bundles to include in the bundle.
+    #[serde(rename = "body")]
+    pub bundle_body: Vec<BundleItem>,
+    /// Requirements for the bundle to be included in the block.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub validity: Option<Validity>,
+    /// Preferences on what data should be shared about the bundle and its transactions
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub privacy: Option<Privacy>,
+}
+
+/// An item of a bundle: a transaction hash, a signed transaction or a nested bundle.
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(untagged)]
+pub enum BundleItem {
+    /// The hash of either a transaction or bundle we are trying to backrun.
+    Hash {
+        /// Tx hash.
+        hash: TxHash,
+    },
+    /// A new signed transaction.
+    #[serde(rename_all = "camelCase")]
+    Tx {
+        /// Bytes of the signed transaction.
+        tx: Bytes,
+        /// If true, the transaction can revert without the bundle being considered invalid.
+        #[serde(default)]
+        can_revert: bool,
+    },
+    /// A nested bundle.
+    Bundle {
+        /// The nested bundle.
+        bundle: Box<SendBundleRequest>,
+    },
+}
+
+/// Response of `mev_simBundle`.
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct SimBundleResponse {
+    /// Whether the simulation was successful.
+    pub success: bool,
+    /// Error message if the simulation failed.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+    /// The block number of the simulated block.
+    #[serde(with = "alloy_serde::quantity")]
+    pub state_block: u64,
+    /// The gas price of the simulated block.
+    #[serde(with = "alloy_serde::quantity")]
+    pub mev_gas_price: u64,
+    /// The profit of the simulated block.
+    #[serde(with = "alloy_serde::quantity")]
+    pub profit: u64,
+    /// The refundable value of the simulated block.
+    #[serde(with = "alloy_serde::quantity")]
+    pub refundable_value: u64,
+    /// The gas used by the simulated block.
+    #[serde(with = "alloy_serde::quantity")]
+    pub gas_used: u64,
+    /// Logs returned by `mev_simBundle`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub logs: Option<Vec<SimBundleLogs>>,
+    /// The results of all transactions of the bundle and its nested bundles, in execution order.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub transactions: Vec<SimBundleTransactionResult>,
+}
+
+/// The simulation result of a single transaction of `mev_simBundle`.
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct SimBundleTransactionResult {
+    /// The hash of the transaction.
+    pub tx_hash: TxHash,
+    /// The sender of the transaction.
+    pub from_address: Address,
+    /// The gas used by the transaction.
+    #[serde(with = "alloy_serde::quantity")]
+    pub gas_used: u64,
+    /// The balance change of the coinbase caused by the transaction.
+    pub profit: U256,
+    /// Whether the transaction reverted.
+    pub reverted: bool,
+}
@@ -0,0 +1,42 @@
+//! Types for the block submission validation API of relays.
+
+use alloy_primitives::B256;
+use alloy_rpc_types_beacon::{payload::beacon_payload_v3, BlsSignature};
+use alloy_rpc_types_engine::{BlobsBundleV1, ExecutionPayloadV3};
+use serde::{Deserialize, Serialize};
+
+pub use alloy_rpc_types_beacon::relay::*;
+
+/// A request to validate a Deneb block submission.
+///
+/// See <https://github.com/flashbots/builder/blob/df9c765067d57ab4b2d0ad39dbb156cbe4965778/eth/block-validation/api.go#L198-L202>
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct BuilderBlockValidationRequestV3 {
+    /// The bid trace of the submission.
+    pub message: BidTrace,
+    /// The submitted execution payload.
+    #[serde(with = "beacon_payload_v3")]
+    pub execution_payload: ExecutionPayloadV3,
+    /// The blobs of the payload's blob transactions.
+    pub blobs_bundle: BlobsBundleV1,
+    /// The signature of the builder.
+    pub signature: BlsSignature,
+    /// The parent beacon block root of the submitted block.
+    pub parent_beacon_block_root: B256,
+    /// The gas limit registered by the proposer.
+    #[serde(with = "u64_string")]
+    pub registered_gas_limit: u64,
+}
+
+/// (De)serializes a `u64` as a decimal string.
+mod u64_string {
+    use serde::{de::Error, Deserialize, Deserializer, Serializer};
+
+    pub(super) fn serialize<S: Serializer>(value: &u64, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.collect_str(value)
+    }
+
+    pub(super) fn deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<u64, D::Error> {
+        String::deserialize(deserializer)?.parse().map_err(D::Error::custom)
+    }
+}
@@ -0,0 +1,29 @@
+//! Types for the `reth` namespace.
+
+use alloy_primitives::{Bytes, B256};
+use serde::{Deserialize, Serialize};
+
+/// Digest of a range of raw database table entries, as returned by `reth_tableDigest`.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TableDigest {
+    /// Number of digested entries.
+    pub entries: u64,
+    /// Keccak256 digest of the length-prefixed raw keys and values, in key order.
+    pub digest: B256,
+    /// Position of the first entry that was not digested because the node's entry limit was
+    /// reached, or `None` if the whole range was digested.
+    pub next: Option<TableDigestCursor>,
+}
+
+/// Position of an entry of a database table, as used by `reth_tableDigest`.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TableDigestCursor {
+    /// Raw key of the entry.
+    pub key: Bytes,
+    /// Raw value of the entry among the duplicates of `key` in a `DupSort` table, or `None` to
+    /// point at the first entry of `key`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub subkey: Option<Bytes>,
+}
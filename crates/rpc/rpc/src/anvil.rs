@@ -0,0 +1,387 @@
+use std::{collections::HashSet, sync::Arc};
+
+use alloy_dyn_abi::TypedData;
+use async_trait::async_trait;
+use jsonrpsee::core::RpcResult;
+use parking_lot::RwLock;
+use reth_auto_seal_consensus::{AccountOverride, AutoSealError, AutoSealHandle, StateOverrides};
+use reth_primitives::{sign_message, Address, Bytes, Signature, TransactionSigned, B256, U256};
+use reth_rpc_api::{AnvilApiServer, GanacheApiServer};
+use reth_rpc_eth_api::helpers::{signer, EthSigner, EthTransactions};
+use reth_rpc_eth_types::{EthApiError, SignError};
+use reth_rpc_server_types::result::{internal_rpc_err, invalid_params_rpc_err};
+use reth_rpc_types::{
+    anvil::{Forking, Metadata, MineOptions, NodeInfo},
+    Block, TypedTransactionRequest,
+};
+use reth_rpc_types_compat::transaction::to_primitive_transaction;
+use secp256k1::SecretKey;
+
+/// `anvil` and `evm` API implementation for dev nodes.
+///
+/// This controls the auto seal miner through its [`AutoSealHandle`]. State changes are sealed
+/// into a new block without transactions, and reverting to a snapshot seals an empty block on top
+/// of the snapshot's block.
+///
+/// Impersonated accounts can send transactions via `eth_sendTransaction`. These transactions are
+/// signed with a placeholder key, so `from` of the returned transactions is the placeholder
+/// signer, and only accounts without code can be impersonated.
+#[derive(Clone)]
+pub struct AnvilApi<Eth> {
+    /// Handle to the auto seal miner.
+    miner: AutoSealHandle,
+    /// The eth API that signs transactions of impersonated accounts.
+    eth_api: Eth,
+    /// Signer for the impersonated accounts.
+    impersonated: ImpersonatedSigner,
+}
+
+impl<Eth> AnvilApi<Eth> {
+    /// Creates a new instance of `AnvilApi`.
+    pub fn new(miner: AutoSealHandle, eth_api: Eth) -> Self {
+        Self { miner, eth_api, impersonated: ImpersonatedSigner::random() }
+    }
+}
+
+impl<Eth> AnvilApi<Eth>
+where
+    Eth: EthTransactions,
+{
+    /// Sets the state of a single account.
+    async fn set_account(&self, address: Address, account: AccountOverride) -> RpcResult<()> {
+        let overrides = StateOverrides::from([(address, account)]);
+        self.miner.set_state(overrides).await.map_err(into_rpc_err)
+    }
+
+    /// Seals the given number of blocks, moving the time forward by `interval` seconds before
+    /// each block.
+    async fn mine(&self, blocks: u64, interval: Option<u64>) -> RpcResult<()> {
+        match interval {
+            Some(interval) => {
+                for _ in 0..blocks {
+                    self.miner.increase_time(interval).await;
+                    self.miner.mine(1).await.map_err(into_rpc_err)?;
+                }
+                Ok(())
+            }
+            None => self.miner.mine(blocks).await.map_err(into_rpc_err),
+        }
+    }
+
+    /// Lets `eth_sendTransaction` send transactions from the given account.
+    fn impersonate(&self, address: Address) {
+        self.impersonated.accounts.write().insert(address);
+
+        // the signer is registered lazily, since the dev accounts replace all signers on launch
+        let mut signers = self.eth_api.signers().write();
+        if !signers.iter().any(|signer| signer.is_signer_for(&address)) {
+            signers.push(Box::new(self.impersonated.clone()));
+        }
+    }
+}
+
+#[async_trait]
+impl<Eth> AnvilApiServer for AnvilApi<Eth>
+where
+    Eth: EthTransactions + 'static,
+{
+    /// Handler for `anvil_impersonateAccount`
+    async fn anvil_impersonate_account(&self, address: Address) -> RpcResult<()> {
+        self.impersonate(address);
+        Ok(())
+    }
+
+    /// Handler for `anvil_stopImpersonatingAccount`
+    async fn anvil_stop_impersonating_account(&self, address: Address) -> RpcResult<()> {
+        self.impersonated.accounts.write().remove(&address);
+        Ok(())
+    }
+
+    /// Handler for `anvil_autoImpersonateAccount`
+    async fn anvil_auto_impersonate_account(&self, _enabled: bool) -> RpcResult<()> {
+        Err(unsupported("anvil_autoImpersonateAccount is not supported"))
+    }
+
+    /// Handler for `anvil_getAutomine`
+    async fn anvil_get_automine(&self) -> RpcResult<bool> {
+        Err(unsupported("anvil_getAutomine is not supported"))
+    }
+
+    /// Handler for `anvil_mine`
+    async fn anvil_mine(&self, blocks: Option<U256>, interval: Option<U256>) -> RpcResult<()> {
+        let blocks = blocks.map_or(1, |blocks| blocks.saturating_to());
+        self.mine(blocks, interval.map(|interval| interval.saturating_to())).await
+    }
+
+    /// Handler for `anvil_setAutomine`
+    async fn anvil_set_automine(&self, _enabled: bool) -> RpcResult<()> {
+        Err(unsupported("anvil_setAutomine is not supported"))
+    }
+
+    /// Handler for `anvil_setIntervalMining`
+    async fn anvil_set_interval_mining(&self, _interval: u64) -> RpcResult<()> {
+        Err(unsupported("anvil_setIntervalMining is not supported"))
+    }
+
+    /// Handler for `anvil_dropTransaction`
+    async fn anvil_drop_transaction(&self, _tx_hash: B256) -> RpcResult<Option<B256>> {
+        Err(unsupported("anvil_dropTransaction is not supported"))
+    }
+
+    /// Handler for `anvil_reset`
+    async fn anvil_reset(&self, _fork: Option<Forking>) -> RpcResult<()> {
+        Err(unsupported("anvil_reset is not supported"))
+    }
+
+    /// Handler for `anvil_setRpcUrl`
+    async fn anvil_set_rpc_url(&self, _url: String) -> RpcResult<()> {
+        Err(unsupported("anvil_setRpcUrl is not supported"))
+    }
+
+    /// Handler for `anvil_setBalance`
+    async fn anvil_set_balance(&self, address: Address, balance: U256) -> RpcResult<()> {
+        self.set_account(address, AccountOverride { balance: Some(balance), ..Default::default() })
+            .await
+    }
+
+    /// Handler for `anvil_setCode`
+    async fn anvil_set_code(&self, address: Address, code: Bytes) -> RpcResult<()> {
+        self.set_account(address, AccountOverride { code: Some(code), ..Default::default() }).await
+    }
+
+    /// Handler for `anvil_setNonce`
+    async fn anvil_set_nonce(&self, address: Address, nonce: U256) -> RpcResult<()> {
+        let nonce = nonce.try_into().map_err(|_| invalid_params_rpc_err("nonce too large"))?;
+        self.set_account(address, AccountOverride { nonce: Some(nonce), ..Default::default() })
+            .await
+    }
+
+    /// Handler for `anvil_setStorageAt`
+    async fn anvil_set_storage_at(
+        &self,
+        address: Address,
+        slot: U256,
+        value: B256,
+    ) -> RpcResult<bool> {
+        let storage = [(slot, U256::from_be_bytes(value.0))].into();
+        self.set_account(address, AccountOverride { storage, ..Default::default() }).await?;
+        Ok(true)
+    }
+
+    /// Handler for `anvil_setCoinbase`
+    async fn anvil_set_coinbase(&self, _address: Address) -> RpcResult<()> {
+        Err(unsupported("anvil_setCoinbase is not supported"))
+    }
+
+    /// Handler for `anvil_setChainId`
+    async fn anvil_set_chain_id(&self, _chain_id: u64) -> RpcResult<()> {
+        Err(unsupported("anvil_setChainId is not supported"))
+    }
+
+    /// Handler for `anvil_setLoggingEnabled`
+    async fn anvil_set_logging_enabled(&self, _enabled: bool) -> RpcResult<()> {
+        Err(unsupported("anvil_setLoggingEnabled is not supported"))
+    }
+
+    /// Handler for `anvil_setMinGasPrice`
+    async fn anvil_set_min_gas_price(&self, _gas_price: U256) -> RpcResult<()> {
+        Err(unsupported("anvil_setMinGasPrice is not supported"))
+    }
+
+    /// Handler for `anvil_setNextBlockBaseFeePerGas`
+    async fn anvil_set_next_block_base_fee_per_gas(&self, _base_fee: U256) -> RpcResult<()> {
+        Err(unsupported("anvil_setNextBlockBaseFeePerGas is not supported"))
+    }
+
+    /// Handler for `anvil_setTime`
+    async fn anvil_set_time(&self, timestamp: u64) -> RpcResult<u64> {
+        let offset = self.miner.set_time(timestamp).await;
+        Ok(offset.try_into().unwrap_or_default())
+    }
+
+    /// Handler for `anvil_dumpState`
+    async fn anvil_dump_state(&self) -> RpcResult<Bytes> {
+        Err(unsupported("anvil_dumpState is not supported"))
+    }
+
+    /// Handler for `anvil_loadState`
+    async fn anvil_load_state(&self, _state: Bytes) -> RpcResult<bool> {
+        Err(unsupported("anvil_loadState is not supported"))
+    }
+
+    /// Handler for `anvil_nodeInfo`
+    async fn anvil_node_info(&self) -> RpcResult<NodeInfo> {
+        Err(unsupported("anvil_nodeInfo is not supported"))
+    }
+
+    /// Handler for `anvil_metadata`
+    async fn anvil_metadata(&self) -> RpcResult<Metadata> {
+        Err(unsupported("anvil_metadata is not supported"))
+    }
+
+    /// Handler for `anvil_snapshot`
+    async fn anvil_snapshot(&self) -> RpcResult<U256> {
+        Ok(self.miner.snapshot().await)
+    }
+
+    /// Handler for `anvil_revert`
+    async fn anvil_revert(&self, id: U256) -> RpcResult<bool> {
+        self.miner.revert(id).await.map_err(into_rpc_err)
+    }
+
+    /// Handler for `anvil_increaseTime`
+    async fn anvil_increase_time(&self, seconds: U256) -> RpcResult<i64> {
+        Ok(self.miner.increase_time(seconds.saturating_to()).await)
+    }
+
+    /// Handler for `anvil_setNextBlockTimestamp`
+    async fn anvil_set_next_block_timestamp(&self, seconds: u64) -> RpcResult<()> {
+        self.miner.set_next_block_timestamp(seconds).await;
+        Ok(())
+    }
+
+    /// Handler for `anvil_setBlockGasLimit`
+    async fn anvil_set_block_gas_limit(&self, _gas_limit: U256) -> RpcResult<bool> {
+        Err(unsupported("anvil_setBlockGasLimit is not supported"))
+    }
+
+    /// Handler for `anvil_setBlockTimestampInterval`
+    async fn anvil_set_block_timestamp_interval(&self, _seconds: u64) -> RpcResult<()> {
+        Err(unsupported("anvil_setBlockTimestampInterval is not supported"))
+    }
+
+    /// Handler for `anvil_removeBlockTimestampInterval`
+    async fn anvil_remove_block_timestamp_interval(&self) -> RpcResult<bool> {
+        Err(unsupported("anvil_removeBlockTimestampInterval is not supported"))
+    }
+
+    /// Handler for `anvil_mine_detailed`
+    async fn anvil_mine_detailed(&self, _opts: Option<MineOptions>) -> RpcResult<Vec<Block>> {
+        Err(unsupported("anvil_mine_detailed is not supported"))
+    }
+
+    /// Handler for `anvil_enableTraces`
+    async fn anvil_enable_traces(&self) -> RpcResult<()> {
+        Err(unsupported("anvil_enableTraces is not supported"))
+    }
+
+    /// Handler for `anvil_removePoolTransactions`
+    async fn anvil_remove_pool_transactions(&self, _address: Address) -> RpcResult<()> {
+        Err(unsupported("anvil_removePoolTransactions is not supported"))
+    }
+}
+
+#[async_trait]
+impl<Eth> GanacheApiServer for AnvilApi<Eth>
+where
+    Eth: EthTransactions + 'static,
+{
+    /// Handler for `evm_increaseTime`
+    async fn evm_increase_time(&self, seconds: U256) -> RpcResult<i64> {
+        Ok(self.miner.increase_time(seconds.saturating_to()).await)
+    }
+
+    /// Handler for `evm_mine`
+    async fn evm_mine(&self, opts: Option<MineOptions>) -> RpcResult<String> {
+        let (timestamp, blocks) = match opts.unwrap_or_default() {
+            MineOptions::Options { timestamp, blocks } => (timestamp, blocks),
+            MineOptions::Timestamp(timestamp) => (timestamp, None),
+        };
+        if let Some(timestamp) = timestamp {
+            self.miner.set_next_block_timestamp(timestamp).await;
+        }
+        self.mine(blocks.unwrap_or(1), None).await?;
+        Ok("0x0".to_string())
+    }
+
+    /// Handler for `evm_revert`
+    async fn evm_revert(&self, snapshot_id: U256) -> RpcResult<bool> {
+        self.miner.revert(snapshot_id).await.map_err(into_rpc_err)
+    }
+
+    /// Handler for `evm_setTime`
+    async fn evm_set_time(&self, timestamp: u64) -> RpcResult<bool> {
+        self.miner.set_time(timestamp).await;
+        Ok(true)
+    }
+
+    /// Handler for `evm_snapshot`
+    async fn evm_snapshot(&self) -> RpcResult<U256> {
+        Ok(self.miner.snapshot().await)
+    }
+}
+
+impl<Eth> std::fmt::Debug for AnvilApi<Eth> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("AnvilApi").finish_non_exhaustive()
+    }
+}
+
+/// Signs transactions of impersonated accounts with a placeholder key.
+///
+/// The accounts are not listed by `eth_accounts` and can't sign messages.
+#[derive(Debug, Clone)]
+struct ImpersonatedSigner {
+    accounts: Arc<RwLock<HashSet<Address>>>,
+    key: SecretKey,
+}
+
+impl ImpersonatedSigner {
+    /// Creates a signer without accounts and with a random placeholder key.
+    fn random() -> Self {
+        let (key, _) = secp256k1::generate_keypair(&mut rand::thread_rng());
+        Self { accounts: Default::default(), key }
+    }
+}
+
+#[async_trait]
+impl EthSigner for ImpersonatedSigner {
+    fn accounts(&self) -> Vec<Address> {
+        Vec::new()
+    }
+
+    fn is_signer_for(&self, addr: &Address) -> bool {
+        self.accounts.read().contains(addr)
+    }
+
+    fn is_impersonating(&self, addr: &Address) -> bool {
+        self.is_signer_for(addr)
+    }
+
+    async fn sign(&self, _address: Address, _message: &[u8]) -> signer::Result<Signature> {
+        Err(SignError::CouldNotSign)
+    }
+
+    fn sign_transaction(
+        &self,
+        request: TypedTransactionRequest,
+        address: &Address,
+    ) -> signer::Result<TransactionSigned> {
+        if !self.is_signer_for(address) {
+            return Err(SignError::NoAccount)
+        }
+        let transaction =
+            to_primitive_transaction(request).ok_or(SignError::InvalidTransactionRequest)?;
+        let signature =
+            sign_message(B256::from_slice(self.key.as_ref()), transaction.signature_hash())
+                .map_err(|_| SignError::CouldNotSign)?;
+
+        Ok(TransactionSigned::from_transaction_and_signature(transaction, signature))
+    }
+
+    fn sign_typed_data(
+        &self,
+        _address: Address,
+        _payload: &TypedData,
+    ) -> signer::Result<Signature> {
+        Err(SignError::CouldNotSign)
+    }
+}
+
+fn unsupported(msg: &'static str) -> jsonrpsee::types::ErrorObject<'static> {
+    EthApiError::Unsupported(msg).into()
+}
+
+fn into_rpc_err(err: AutoSealError) -> jsonrpsee::types::ErrorObject<'static> {
+    internal_rpc_err(err.to_string())
+}
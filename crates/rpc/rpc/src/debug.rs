@@ -1,16 +1,21 @@
 use alloy_rlp::{Decodable, Encodable};
 use async_trait::async_trait;
-use jsonrpsee::core::RpcResult;
+use futures::{Stream, StreamExt, TryStreamExt};
+use jsonrpsee::{core::RpcResult, PendingSubscriptionSink};
 use reth_chainspec::EthereumHardforks;
 use reth_evm::{system_calls::pre_block_beacon_root_contract_call, ConfigureEvmEnv};
 use reth_primitives::{
-    Address, Block, BlockId, BlockNumberOrTag, Bytes, TransactionSignedEcRecovered, B256, U256,
+    Address, Block, BlockId, BlockNumberOrTag, BlockWithSenders, Bytes,
+    TransactionSignedEcRecovered, B256, U256,
 };
 use reth_provider::{
-    BlockReaderIdExt, ChainSpecProvider, EvmEnvProvider, HeaderProvider, StateProofProvider,
-    StateProviderFactory, TransactionVariant,
+    BadBlockReader, BlockReaderIdExt, ChainSpecProvider, EvmEnvProvider, HeaderProvider,
+    StateProofProvider, StateProviderFactory, TransactionVariant,
+};
+use reth_revm::{
+    database::StateProviderDatabase,
+    state_change::{apply_blockhashes_update, post_block_balance_increments},
 };
-use reth_revm::database::StateProviderDatabase;
 use reth_rpc_api::DebugApiServer;
 use reth_rpc_eth_api::{
     helpers::{Call, EthApiSpec, EthTransactions, TraceExt},
@@ -19,14 +24,16 @@ use reth_rpc_eth_api::{
 use reth_rpc_eth_types::{EthApiError, StateCacheDb};
 use reth_rpc_server_types::{result::internal_rpc_err, ToRpcResult};
 use reth_rpc_types::{
+    debug::BadBlock,
     state::EvmOverrides,
     trace::geth::{
         BlockTraceResult, FourByteFrame, GethDebugBuiltInTracerType, GethDebugTracerType,
         GethDebugTracingCallOptions, GethDebugTracingOptions, GethTrace, NoopFrame, TraceResult,
     },
-    BlockError, Bundle, RichBlock, StateContext, TransactionRequest,
+    BlockError, BlockTransactionsKind, Bundle, StateContext, TransactionRequest,
 };
-use reth_tasks::pool::BlockingTaskGuard;
+use reth_rpc_types_compat::block::{from_block, from_block_with_tx_hashes};
+use reth_tasks::{pool::BlockingTaskGuard, TaskSpawner};
 use reth_trie::{HashedPostState, HashedStorage};
 use revm::{
     db::{states::bundle_state::BundleRetention, CacheDB},
@@ -40,6 +47,15 @@ use revm_inspectors::tracing::{
 use revm_primitives::{keccak256, HashMap};
 use std::sync::Arc;
 use tokio::sync::{AcquireError, OwnedSemaphorePermit};
+use tracing::debug;
+
+use crate::eth::pubsub::pipe_from_stream;
+
+/// The maximum number of blocks that are traced concurrently by `debug_traceChain`.
+const TRACE_CHAIN_CONCURRENCY: usize = 8;
+
+/// The maximum number of blocks that can be traced by a single `debug_traceChain` call.
+const MAX_TRACE_CHAIN_BLOCKS: u64 = 100;
 
 /// `debug` API implementation.
 ///
@@ -80,6 +96,69 @@ where
         self.inner.blocking_task_guard.clone().acquire_owned().await
     }
 
+    /// Resolves the given block number or tag to a block number, the pending block is not
+    /// supported.
+    fn resolve_block_number(&self, number_or_tag: BlockNumberOrTag) -> Result<u64, Eth::Error> {
+        self.inner
+            .provider
+            .convert_block_number(number_or_tag)
+            .map_err(Eth::Error::from_eth_err)?
+            .ok_or_else(|| {
+                EthApiError::InvalidParams("pending block not supported".to_string()).into()
+            })
+    }
+
+    /// Returns a stream that traces all blocks in `(start_exclusive, end_inclusive]` with the
+    /// given options, see [`Self::debug_trace_block`].
+    ///
+    /// Up to [`TRACE_CHAIN_CONCURRENCY`] blocks are traced in parallel on the blocking task pool,
+    /// the results are yielded in ascending block order. The range is limited to
+    /// [`MAX_TRACE_CHAIN_BLOCKS`] blocks.
+    pub fn trace_chain_stream(
+        &self,
+        start_exclusive: BlockNumberOrTag,
+        end_inclusive: BlockNumberOrTag,
+        opts: GethDebugTracingOptions,
+    ) -> Result<impl Stream<Item = Result<BlockTraceResult, Eth::Error>> + Send + 'static, Eth::Error>
+    {
+        let start = self.resolve_block_number(start_exclusive)?;
+        let end = self.resolve_block_number(end_inclusive)?;
+        if start >= end {
+            return Err(EthApiError::InvalidParams(
+                "invalid parameters: start block must be lower than end block".to_string(),
+            )
+            .into())
+        }
+        if end - start > MAX_TRACE_CHAIN_BLOCKS {
+            return Err(EthApiError::InvalidParams(format!(
+                "Block range too large; currently limited to {MAX_TRACE_CHAIN_BLOCKS} blocks"
+            ))
+            .into())
+        }
+
+        let this = self.clone();
+        let stream = futures::stream::iter(start + 1..=end)
+            .map(move |number| {
+                let this = this.clone();
+                let opts = opts.clone();
+                async move {
+                    let _permit = this.acquire_trace_permit().await;
+                    let hash = this
+                        .inner
+                        .provider
+                        .block_hash(number)
+                        .map_err(Eth::Error::from_eth_err)?
+                        .ok_or(EthApiError::UnknownBlockNumber)?;
+                    // trace by hash so that the result matches the reported hash
+                    let traces = this.debug_trace_block(hash.into(), opts).await?;
+                    Ok(BlockTraceResult { block: U256::from(number), hash, traces })
+                }
+            })
+            .buffered(TRACE_CHAIN_CONCURRENCY);
+
+        Ok(stream)
+    }
+
     /// Trace the entire block asynchronously
     async fn trace_block(
         &self,
@@ -556,6 +635,10 @@ where
     /// generating an execution witness. The witness comprises of a map of all hashed trie nodes
     /// to their preimages that were required during the execution of the block, including during
     /// state root recomputation.
+    ///
+    /// The bytecodes of the accessed contracts and the RLP encoded headers of the ancestors up to
+    /// the oldest block hash accessed during execution are included as well, keyed by the code
+    /// hash and the block hash respectively, so that the block can be executed statelessly.
     pub async fn debug_execution_witness(
         &self,
         block_id: BlockNumberOrTag,
@@ -572,6 +655,7 @@ where
             .eth_api
             .spawn_with_state_at_block(block.parent_hash.into(), move |state| {
                 let evm_config = Call::evm_config(this.eth_api()).clone();
+                let chain_spec = this.inner.provider.chain_spec();
                 let mut db = StateBuilder::new()
                     .with_database(StateProviderDatabase::new(state))
                     .with_bundle_update()
@@ -580,7 +664,7 @@ where
                 pre_block_beacon_root_contract_call(
                     &mut db,
                     &evm_config,
-                    &this.inner.provider.chain_spec(),
+                    &chain_spec,
                     &cfg,
                     &block_env,
                     block.timestamp,
@@ -589,6 +673,15 @@ where
                 )
                 .map_err(|err| EthApiError::Internal(err.into()))?;
 
+                apply_blockhashes_update(
+                    &mut db,
+                    &chain_spec,
+                    block.timestamp,
+                    block.number,
+                    block.parent_hash,
+                )
+                .map_err(|err| EthApiError::Internal(err.into()))?;
+
                 // Re-execute all of the transactions in the block to load all touched accounts into
                 // the cache DB.
                 for tx in block.raw_transactions() {
@@ -607,6 +700,21 @@ where
                     db.commit(res.state);
                 }
 
+                // Apply the block rewards and withdrawals, so that the accounts they touch are part
+                // of the witness.
+                let total_difficulty = this
+                    .inner
+                    .provider
+                    .header_td_by_number(block.number)
+                    .map_err(Into::into)?
+                    .unwrap_or_default();
+                let balance_increments = post_block_balance_increments(
+                    &chain_spec,
+                    &block.block.clone().unseal(),
+                    total_difficulty,
+                );
+                db.increment_balances(balance_increments).map_err(Into::into)?;
+
                 // Merge all state transitions
                 db.merge_transitions(BundleRetention::Reverts);
 
@@ -641,9 +749,34 @@ where
                 // Generate an execution witness for the aggregated state of accessed accounts.
                 // Destruct the cache database to retrieve the state provider.
                 let state_provider = db.database.into_inner();
-                let witness = state_provider
+                let mut witness = state_provider
                     .witness(HashedPostState::default(), hashed_state)
                     .map_err(Into::into)?;
+
+                for (code_hash, code) in db.cache.contracts {
+                    witness.insert(code_hash, code.original_bytes());
+                }
+
+                // The parent header carries the state root the witness builds on, and the headers
+                // down to the oldest accessed block hash link it to the parent.
+                let oldest_block_hash = db
+                    .block_hashes
+                    .keys()
+                    .next()
+                    .copied()
+                    .unwrap_or(block.number)
+                    .min(block.number.saturating_sub(1));
+                for number in oldest_block_hash..block.number {
+                    let header = this
+                        .inner
+                        .provider
+                        .sealed_header(number)
+                        .map_err(Into::into)?
+                        .ok_or(EthApiError::UnknownBlockNumber)?;
+                    let (header, hash) = header.split();
+                    witness.insert(hash, alloy_rlp::encode(header).into());
+                }
+
                 Ok(witness)
             })
             .await
@@ -765,7 +898,8 @@ where
 #[async_trait]
 impl<Provider, Eth> DebugApiServer for DebugApi<Provider, Eth>
 where
-    Provider: BlockReaderIdExt
+    Provider: BadBlockReader
+        + BlockReaderIdExt
         + HeaderProvider
         + ChainSpecProvider
         + StateProviderFactory
@@ -844,17 +978,84 @@ where
     }
 
     /// Handler for `debug_getBadBlocks`
-    async fn bad_blocks(&self) -> RpcResult<Vec<RichBlock>> {
-        Err(internal_rpc_err("unimplemented"))
+    async fn bad_blocks(&self) -> RpcResult<Vec<BadBlock>> {
+        let bad_blocks = self.inner.provider.bad_blocks().to_rpc_result()?;
+        bad_blocks
+            .into_iter()
+            .map(|bad_block| {
+                let hash = bad_block.block.hash();
+                let rlp = alloy_rlp::encode(&bad_block.block).into();
+                let block = bad_block.block.unseal();
+                // the senders of an invalid block are not necessarily recoverable
+                let block = match block.clone().with_recovered_senders() {
+                    Some(block) => {
+                        from_block(block, U256::ZERO, BlockTransactionsKind::Full, Some(hash))
+                            .map_err(EthApiError::from)?
+                    }
+                    None => from_block_with_tx_hashes(
+                        BlockWithSenders { block, senders: Vec::new() },
+                        U256::ZERO,
+                        Some(hash),
+                    ),
+                };
+                Ok(BadBlock {
+                    hash,
+                    block,
+                    rlp,
+                    error: bad_block.error,
+                    pre_state_root: bad_block.pre_state_root,
+                })
+            })
+            .collect()
     }
 
     /// Handler for `debug_traceChain`
     async fn debug_trace_chain(
         &self,
-        _start_exclusive: BlockNumberOrTag,
-        _end_inclusive: BlockNumberOrTag,
+        start_exclusive: BlockNumberOrTag,
+        end_inclusive: BlockNumberOrTag,
+        opts: Option<GethDebugTracingOptions>,
     ) -> RpcResult<Vec<BlockTraceResult>> {
-        Err(internal_rpc_err("unimplemented"))
+        let stream = self
+            .trace_chain_stream(start_exclusive, end_inclusive, opts.unwrap_or_default())
+            .map_err(Into::into)?;
+        stream.try_collect().await.map_err(Into::into)
+    }
+
+    /// Handler for `debug_subscribeTraceChain`
+    async fn debug_subscribe_trace_chain(
+        &self,
+        pending: PendingSubscriptionSink,
+        start_exclusive: BlockNumberOrTag,
+        end_inclusive: BlockNumberOrTag,
+        opts: Option<GethDebugTracingOptions>,
+    ) -> jsonrpsee::core::SubscriptionResult {
+        let stream =
+            match self.trace_chain_stream(start_exclusive, end_inclusive, opts.unwrap_or_default())
+            {
+                Ok(stream) => stream,
+                Err(err) => {
+                    pending.reject(err).await;
+                    return Ok(())
+                }
+            };
+
+        let sink = pending.accept().await?;
+        self.eth_api().io_task_spawner().spawn(Box::pin(async move {
+            // stop at the first block that can't be traced, there's no way to report the error
+            // over an accepted subscription other than closing it
+            let stream = stream
+                .take_while(|res| {
+                    if let Err(err) = res {
+                        debug!(target: "rpc::debug", %err, "failed to trace block, closing traceChain subscription");
+                    }
+                    futures::future::ready(res.is_ok())
+                })
+                .filter_map(|res| futures::future::ready(res.ok()));
+            let _ = pipe_from_stream(sink, Box::pin(stream)).await;
+        }));
+
+        Ok(())
     }
 
     /// Handler for `debug_traceBlock`
@@ -1169,3 +1370,126 @@ struct DebugApiInner<Provider, Eth> {
     // restrict the number of concurrent calls to blocking calls
     blocking_task_guard: BlockingTaskGuard,
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::EthApi;
+    use reth_evm_ethereum::EthEvmConfig;
+    use reth_primitives::{Header, Transaction, TxKind, TxLegacy};
+    use reth_provider::test_utils::{ExtendedAccount, MockEthProvider};
+    use reth_rpc_eth_types::{
+        EthStateCache, FeeHistoryCache, FeeHistoryCacheConfig, GasPriceOracle,
+    };
+    use reth_rpc_server_types::constants::{DEFAULT_ETH_PROOF_WINDOW, DEFAULT_PROOF_PERMITS};
+    use reth_rpc_types::trace::geth::DefaultFrame;
+    use reth_tasks::pool::BlockingTaskPool;
+    use reth_testing_utils::generators::{self, sign_tx_with_key_pair};
+    use reth_transaction_pool::test_utils::{testing_pool, TestPool};
+
+    type TestDebugApi =
+        DebugApi<MockEthProvider, EthApi<MockEthProvider, TestPool, (), EthEvmConfig>>;
+
+    /// Creates a debug API over a chain of blocks on top of an empty genesis block, each block
+    /// contains a transfer from a different funded sender.
+    fn debug_api_with_blocks(count: u64) -> (TestDebugApi, Vec<Block>) {
+        let provider = MockEthProvider::default();
+        let mut rng = generators::rng();
+        let mut blocks = Vec::new();
+        let mut parent_hash = B256::ZERO;
+        for number in 0..=count {
+            let mut block = Block {
+                header: Header { number, parent_hash, ..Default::default() },
+                ..Default::default()
+            };
+            if number > 0 {
+                let key_pair = generators::generate_keys(&mut rng, 1)[0];
+                let tx = sign_tx_with_key_pair(
+                    key_pair,
+                    Transaction::Legacy(TxLegacy {
+                        chain_id: Some(1),
+                        gas_price: 1,
+                        gas_limit: 21_000,
+                        to: TxKind::Call(Address::repeat_byte(number as u8)),
+                        value: U256::from(number),
+                        ..Default::default()
+                    }),
+                );
+                provider.add_account(
+                    tx.recover_signer().unwrap(),
+                    ExtendedAccount::new(0, U256::from(1_000_000)),
+                );
+                block.body.push(tx);
+            }
+
+            parent_hash = block.header.hash_slow();
+            provider.add_block(parent_hash, block.clone());
+            blocks.push(block);
+        }
+
+        let evm_config = EthEvmConfig::default();
+        let cache = EthStateCache::spawn(provider.clone(), Default::default(), evm_config);
+        let eth_api = EthApi::new(
+            provider.clone(),
+            testing_pool(),
+            (),
+            cache.clone(),
+            GasPriceOracle::new(provider.clone(), Default::default(), cache.clone()),
+            u64::MAX,
+            DEFAULT_ETH_PROOF_WINDOW,
+            BlockingTaskPool::build().expect("failed to build tracing pool"),
+            FeeHistoryCache::new(cache, FeeHistoryCacheConfig::default()),
+            evm_config,
+            None,
+            DEFAULT_PROOF_PERMITS,
+        );
+        (DebugApi::new(provider, eth_api, BlockingTaskGuard::new(4)), blocks)
+    }
+
+    #[tokio::test]
+    async fn trace_chain_blocks() {
+        let (api, blocks) = debug_api_with_blocks(3);
+
+        let results: Vec<_> = api
+            .trace_chain_stream(0u64.into(), 3u64.into(), Default::default())
+            .unwrap()
+            .try_collect()
+            .await
+            .unwrap();
+        assert_eq!(results.len(), 3);
+        for (result, block) in results.into_iter().zip(&blocks[1..]) {
+            assert_eq!(result.block, U256::from(block.number));
+            assert_eq!(result.hash, block.header.hash_slow());
+            let [TraceResult::Success { result: GethTrace::Default(frame), tx_hash }] =
+                result.traces.as_slice()
+            else {
+                panic!("unexpected traces {:?}", result.traces)
+            };
+            assert_eq!(*tx_hash, Some(block.body[0].hash()));
+            assert_eq!(*frame, DefaultFrame { gas: 21_000, ..Default::default() });
+        }
+
+        // the start block is excluded
+        let results: Vec<_> = api
+            .trace_chain_stream(2u64.into(), 3u64.into(), Default::default())
+            .unwrap()
+            .try_collect()
+            .await
+            .unwrap();
+        assert_eq!(results.iter().map(|result| result.block).collect::<Vec<_>>(), [U256::from(3)]);
+    }
+
+    #[tokio::test]
+    async fn trace_chain_invalid_range() {
+        let (api, _) = debug_api_with_blocks(1);
+
+        assert!(api.trace_chain_stream(1u64.into(), 1u64.into(), Default::default()).is_err());
+        assert!(api
+            .trace_chain_stream(
+                0u64.into(),
+                (MAX_TRACE_CHAIN_BLOCKS + 1).into(),
+                Default::default()
+            )
+            .is_err());
+    }
+}
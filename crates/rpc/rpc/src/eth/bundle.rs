@@ -7,22 +7,29 @@ use reth_evm::{ConfigureEvm, ConfigureEvmEnv};
 use reth_primitives::{
     keccak256,
     revm_primitives::db::{DatabaseCommit, DatabaseRef},
-    PooledTransactionsElement, U256,
+    Address, PooledTransactionsElement, B256, U256,
 };
 use reth_revm::database::StateProviderDatabase;
 use reth_rpc_eth_api::{FromEthApiError, FromEvmError};
-use reth_rpc_types::mev::{EthCallBundle, EthCallBundleResponse, EthCallBundleTransactionResult};
+use reth_rpc_types::mev::{
+    CancelBundleRequest, EthBundleHash, EthCallBundle, EthCallBundleResponse,
+    EthCallBundleTransactionResult, EthSendBundle,
+};
 use reth_tasks::pool::BlockingTaskGuard;
+use reth_transaction_pool::{bundle::PoolBundle, TransactionPool};
 use revm::{
     db::CacheDB,
     primitives::{ResultAndState, TxEnv},
 };
-use revm_primitives::{EnvKzgSettings, EnvWithHandlerCfg, SpecId, MAX_BLOB_GAS_PER_BLOCK};
+use revm_primitives::{
+    BlockEnv, CfgEnvWithHandlerCfg, EnvKzgSettings, EnvWithHandlerCfg, SpecId,
+    MAX_BLOB_GAS_PER_BLOCK,
+};
 
 use reth_provider::{ChainSpecProvider, HeaderProvider};
 use reth_rpc_eth_api::{
     helpers::{Call, EthTransactions, LoadPendingBlock},
-    EthCallBundleApiServer,
+    EthCallBundleApiServer, EthSendBundleApiServer,
 };
 use reth_rpc_eth_types::{utils::recover_raw_transaction, EthApiError, RpcInvalidTransactionError};
 
@@ -103,40 +110,10 @@ where
 
         let block_id: reth_rpc_types::BlockId = state_block_number.into();
         // Note: the block number is considered the `parent` block: <https://github.com/flashbots/mev-geth/blob/fddf97beec5877483f879a77b7dea2e58a58d653/internal/ethapi/api.go#L2104>
-        let (cfg, mut block_env, at) = self.inner.eth_api.evm_env_at(block_id).await?;
-
-        // need to adjust the timestamp for the next block
-        if let Some(timestamp) = timestamp {
-            block_env.timestamp = U256::from(timestamp);
-        } else {
-            block_env.timestamp += U256::from(12);
-        }
-
-        if let Some(difficulty) = difficulty {
-            block_env.difficulty = U256::from(difficulty);
-        }
-
-        if let Some(gas_limit) = gas_limit {
-            block_env.gas_limit = U256::from(gas_limit);
-        }
-
-        if let Some(base_fee) = base_fee {
-            block_env.basefee = U256::from(base_fee);
-        } else if cfg.handler_cfg.spec_id.is_enabled_in(SpecId::LONDON) {
-            let parent_block = block_env.number.saturating_to::<u64>();
-            // here we need to fetch the _next_ block's basefee based on the parent block <https://github.com/flashbots/mev-geth/blob/fddf97beec5877483f879a77b7dea2e58a58d653/internal/ethapi/api.go#L2130>
-            let parent = LoadPendingBlock::provider(&self.inner.eth_api)
-                .header_by_number(parent_block)
-                .map_err(Eth::Error::from_eth_err)?
-                .ok_or_else(|| EthApiError::UnknownBlockNumber)?;
-            if let Some(base_fee) = parent.next_block_base_fee(
-                LoadPendingBlock::provider(&self.inner.eth_api)
-                    .chain_spec()
-                    .base_fee_params_at_block(parent_block),
-            ) {
-                block_env.basefee = U256::from(base_fee);
-            }
-        }
+        let overrides =
+            BundleBlockEnvOverrides { timestamp, difficulty, gas_limit, base_fee, coinbase: None };
+        let (cfg, mut block_env, at) =
+            bundle_evm_env_at(&self.inner.eth_api, block_id, overrides).await?;
 
         let state_block_number = block_env.number;
         // use the block number of the request
@@ -256,6 +233,152 @@ where
             })
             .await
     }
+
+    /// Adds a bundle to the bundle pool of the transaction pool. The payload builder includes the
+    /// bundle atomically at the top of the block it targets.
+    pub fn send_bundle(&self, bundle: EthSendBundle) -> Result<EthBundleHash, Eth::Error> {
+        let EthSendBundle {
+            txs,
+            block_number,
+            min_timestamp,
+            max_timestamp,
+            reverting_tx_hashes,
+            replacement_uuid,
+        } = bundle;
+        if txs.is_empty() {
+            return Err(EthApiError::InvalidParams(
+                EthBundleError::EmptyBundleTransactions.to_string(),
+            )
+            .into())
+        }
+        if block_number == 0 {
+            return Err(EthApiError::InvalidParams(
+                EthBundleError::BundleMissingBlockNumber.to_string(),
+            )
+            .into())
+        }
+
+        let transactions = txs
+            .into_iter()
+            .map(recover_raw_transaction)
+            .collect::<Result<Vec<_>, _>>()?
+            .into_iter()
+            .map(|tx| {
+                let (tx, signer) = tx.into_components();
+                tx.into_ecrecovered_transaction(signer)
+            })
+            .collect();
+        let bundle = PoolBundle::new(transactions, block_number)
+            .with_timestamps(min_timestamp, max_timestamp)
+            .with_reverting_tx_hashes(reverting_tx_hashes)
+            .with_replacement_uuid(replacement_uuid);
+
+        let pool = LoadPendingBlock::pool(&self.inner.eth_api);
+        let bundle_hash = pool
+            .bundle_pool()
+            .ok_or(EthApiError::Unsupported("the transaction pool does not support bundles"))?
+            .add_bundle(bundle)
+            .map_err(|err| EthApiError::InvalidParams(err.to_string()))?;
+
+        Ok(EthBundleHash { bundle_hash })
+    }
+
+    /// Removes a bundle from the bundle pool. The bundle is identified by its hash or by its
+    /// replacement uuid.
+    pub fn cancel_bundle(&self, request: CancelBundleRequest) -> Result<(), Eth::Error> {
+        let pool = LoadPendingBlock::pool(&self.inner.eth_api);
+        let bundle_pool = pool
+            .bundle_pool()
+            .ok_or(EthApiError::Unsupported("the transaction pool does not support bundles"))?;
+        match request.bundle_hash.parse::<B256>() {
+            Ok(hash) => bundle_pool.remove_bundle(&hash),
+            Err(_) => bundle_pool.remove_by_replacement_uuid(&request.bundle_hash),
+        };
+        Ok(())
+    }
+}
+
+#[async_trait::async_trait]
+impl<Eth> EthSendBundleApiServer for EthBundle<Eth>
+where
+    Eth: EthTransactions + LoadPendingBlock + Call + 'static,
+{
+    async fn send_bundle(&self, bundle: EthSendBundle) -> RpcResult<EthBundleHash> {
+        Self::send_bundle(self, bundle).map_err(Into::into)
+    }
+
+    async fn cancel_bundle(&self, request: CancelBundleRequest) -> RpcResult<()> {
+        Self::cancel_bundle(self, request).map_err(Into::into)
+    }
+}
+
+/// Overrides of the block environment a bundle is simulated in.
+#[derive(Debug, Default)]
+pub(crate) struct BundleBlockEnvOverrides {
+    /// Timestamp of the simulated block, defaults to the parent's timestamp + 12.
+    pub(crate) timestamp: Option<u64>,
+    /// Difficulty of the simulated block, defaults to the parent's difficulty.
+    pub(crate) difficulty: Option<U256>,
+    /// Gas limit of the simulated block, defaults to the parent's gas limit.
+    pub(crate) gas_limit: Option<u64>,
+    /// Base fee of the simulated block, defaults to the base fee of the parent's child.
+    pub(crate) base_fee: Option<u128>,
+    /// Coinbase of the simulated block, defaults to the parent's coinbase.
+    pub(crate) coinbase: Option<Address>,
+}
+
+/// Returns the environment to simulate a bundle on top of the given parent block.
+///
+/// The block number of the returned [`BlockEnv`] is still the number of the parent block, callers
+/// are expected to set the number of the simulated block.
+pub(crate) async fn bundle_evm_env_at<Eth>(
+    eth_api: &Eth,
+    parent: reth_rpc_types::BlockId,
+    overrides: BundleBlockEnvOverrides,
+) -> Result<(CfgEnvWithHandlerCfg, BlockEnv, reth_rpc_types::BlockId), Eth::Error>
+where
+    Eth: LoadPendingBlock + Call,
+{
+    let BundleBlockEnvOverrides { timestamp, difficulty, gas_limit, base_fee, coinbase } =
+        overrides;
+    let (cfg, mut block_env, at) = eth_api.evm_env_at(parent).await?;
+
+    // need to adjust the timestamp for the next block
+    if let Some(timestamp) = timestamp {
+        block_env.timestamp = U256::from(timestamp);
+    } else {
+        block_env.timestamp += U256::from(12);
+    }
+
+    if let Some(difficulty) = difficulty {
+        block_env.difficulty = difficulty;
+    }
+
+    if let Some(gas_limit) = gas_limit {
+        block_env.gas_limit = U256::from(gas_limit);
+    }
+
+    if let Some(coinbase) = coinbase {
+        block_env.coinbase = coinbase;
+    }
+
+    if let Some(base_fee) = base_fee {
+        block_env.basefee = U256::from(base_fee);
+    } else if cfg.handler_cfg.spec_id.is_enabled_in(SpecId::LONDON) {
+        let parent_block = block_env.number.saturating_to::<u64>();
+        // here we need to fetch the _next_ block's basefee based on the parent block <https://github.com/flashbots/mev-geth/blob/fddf97beec5877483f879a77b7dea2e58a58d653/internal/ethapi/api.go#L2130>
+        let parent = LoadPendingBlock::provider(eth_api)
+            .header_by_number(parent_block)
+            .map_err(Eth::Error::from_eth_err)?
+            .ok_or_else(|| EthApiError::UnknownBlockNumber)?;
+        if let Some(base_fee) = parent.next_block_base_fee(
+            LoadPendingBlock::provider(eth_api).chain_spec().base_fee_params_at_block(parent_block),
+        ) {
+            block_env.basefee = U256::from(base_fee);
+        }
+    }
+
+    Ok((cfg, block_env, at))
 }
 
 #[async_trait::async_trait]
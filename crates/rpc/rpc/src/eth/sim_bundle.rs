@@ -0,0 +1,906 @@
+//! `mev` namespace bundle simulation, see <https://github.com/flashbots/mev-share>.
+
+use std::{
+    collections::HashSet,
+    sync::Arc,
+    time::{Duration, Instant},
+};
+
+use jsonrpsee::core::RpcResult;
+use reth_evm::ConfigureEvm;
+use reth_primitives::{
+    revm_primitives::db::{DatabaseCommit, DatabaseRef},
+    Address, PooledTransactionsElement, TransactionSigned, TransactionSignedEcRecovered, TxHash,
+    U256,
+};
+use reth_revm::database::StateProviderDatabase;
+use reth_rpc_api::MevApiServer;
+use reth_rpc_eth_api::{
+    helpers::{Call, EthTransactions, LoadPendingBlock},
+    FromEthApiError, FromEvmError,
+};
+use reth_rpc_eth_types::{utils::recover_raw_transaction, EthApiError};
+use reth_rpc_types::{
+    mev::{
+        BundleItem, Inclusion, Refund, RefundConfig, SendBundleRequest, SendBundleResponse,
+        SimBundleLogs, SimBundleOverrides, SimBundleResponse, SimBundleTransactionResult,
+    },
+    BlockId,
+};
+use reth_tasks::pool::BlockingTaskGuard;
+use reth_transaction_pool::{bundle::PoolBundle, TransactionPool};
+use revm::{
+    db::CacheDB,
+    primitives::{EVMError, ResultAndState, TxEnv},
+    Evm,
+};
+use revm_primitives::{EnvKzgSettings, EnvWithHandlerCfg};
+
+use crate::eth::bundle::{bundle_evm_env_at, BundleBlockEnvOverrides};
+
+/// Maximum depth of nested bundles.
+const MAX_NESTED_BUNDLE_DEPTH: usize = 5;
+
+/// Maximum number of items in the body of a bundle.
+const MAX_BUNDLE_BODY_SIZE: usize = 50;
+
+/// Default timeout of a bundle simulation.
+const DEFAULT_SIM_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// Gas charged for every refund payout, see <https://github.com/flashbots/mev-share-node/blob/main/mevshare/sim_queue.go>
+const REFUND_PAYOUT_GAS: u64 = 30_000;
+
+/// `Eth` MEV-Share bundle implementation.
+pub struct EthSimBundle<Eth> {
+    /// All nested fields bundled together.
+    inner: Arc<EthSimBundleInner<Eth>>,
+}
+
+impl<Eth> EthSimBundle<Eth> {
+    /// Create a new `EthSimBundle` instance.
+    pub fn new(eth_api: Eth, blocking_task_guard: BlockingTaskGuard) -> Self {
+        Self { inner: Arc::new(EthSimBundleInner { eth_api, blocking_task_guard }) }
+    }
+}
+
+impl<Eth> EthSimBundle<Eth>
+where
+    Eth: EthTransactions + LoadPendingBlock + Call + 'static,
+{
+    /// Adds a MEV-Share bundle to the bundle pool of the transaction pool.
+    ///
+    /// Nested bundles are flattened into a single bundle, which the payload builder includes
+    /// atomically at the top of a block in the inclusion range of the bundle. The payload builder
+    /// can't pay out refunds, so bundles that configure refunds are rejected.
+    pub fn send_bundle(
+        &self,
+        request: SendBundleRequest,
+    ) -> Result<SendBundleResponse, Eth::Error> {
+        let pool = LoadPendingBlock::pool(&self.inner.eth_api);
+        let bundle = resolve_bundle(&pool, request, 0)
+            .and_then(|bundle| {
+                if bundle.has_refunds() {
+                    return Err(EthSimBundleError::RefundsNotSupported)
+                }
+                Ok(bundle)
+            })
+            .map_err(|err| EthApiError::InvalidParams(err.to_string()))?;
+
+        let Inclusion { block, max_block } = bundle.inclusion;
+        let mut transactions = Vec::new();
+        let mut reverting_tx_hashes = Vec::new();
+        bundle.flatten(&mut transactions, &mut reverting_tx_hashes);
+        let bundle = PoolBundle::new(transactions, block)
+            .with_max_block_number(max_block)
+            .with_reverting_tx_hashes(reverting_tx_hashes);
+
+        let bundle_hash = pool
+            .bundle_pool()
+            .ok_or(EthApiError::Unsupported("the transaction pool does not support bundles"))?
+            .add_bundle(bundle)
+            .map_err(|err| EthApiError::InvalidParams(err.to_string()))?;
+
+        Ok(SendBundleResponse { bundle_hash })
+    }
+
+    /// Simulates a MEV-Share bundle, including its nested bundles, on top of the given parent
+    /// block and pays out the refunds configured by the bundle.
+    ///
+    /// Transaction hashes of the bundle are resolved from the transaction pool. Simulation failures
+    /// such as reverting transactions are reported as an unsuccessful [`SimBundleResponse`].
+    pub async fn sim_bundle(
+        &self,
+        request: SendBundleRequest,
+        overrides: SimBundleOverrides,
+    ) -> Result<SimBundleResponse, Eth::Error> {
+        let SimBundleOverrides {
+            parent_block,
+            block_number,
+            coinbase,
+            timestamp,
+            gas_limit,
+            base_fee,
+            timeout,
+        } = overrides;
+        let deadline =
+            Instant::now() + timeout.map(Duration::from_secs).unwrap_or(DEFAULT_SIM_TIMEOUT);
+
+        let bundle = resolve_bundle(&LoadPendingBlock::pool(&self.inner.eth_api), request, 0)
+            .map_err(|err| EthApiError::InvalidParams(err.to_string()))?;
+
+        let overrides = BundleBlockEnvOverrides {
+            timestamp,
+            difficulty: None,
+            gas_limit,
+            base_fee: base_fee.map(u128::from),
+            coinbase,
+        };
+        let (cfg, mut block_env, at) = bundle_evm_env_at(
+            &self.inner.eth_api,
+            parent_block.unwrap_or_else(BlockId::latest),
+            overrides,
+        )
+        .await?;
+
+        let state_block = block_env.number.saturating_to::<u64>();
+        block_env.number = U256::from(block_number.unwrap_or(state_block + 1));
+
+        let _permit = self.inner.blocking_task_guard.clone().acquire_owned().await;
+        let eth_api = self.inner.eth_api.clone();
+
+        self.inner
+            .eth_api
+            .spawn_with_state_at_block(at, move |state| {
+                let env = EnvWithHandlerCfg::new_with_cfg_env(cfg, block_env, TxEnv::default());
+                let db = CacheDB::new(StateProviderDatabase::new(state));
+                let evm_config = Call::evm_config(&eth_api);
+                let mut evm = evm_config.evm_with_env(db, env);
+
+                let mut transactions = Vec::new();
+                let res = match simulate_bundle::<_, _, _, Eth::Error>(
+                    evm_config,
+                    &mut evm,
+                    &bundle,
+                    deadline,
+                    &mut transactions,
+                ) {
+                    Ok(res) => SimBundleResponse {
+                        success: true,
+                        error: None,
+                        state_block,
+                        mev_gas_price: res
+                            .profit
+                            .checked_div(U256::from(res.gas_used))
+                            .unwrap_or_default()
+                            .saturating_to(),
+                        profit: res.profit.saturating_to(),
+                        refundable_value: res.refundable_value.saturating_to(),
+                        gas_used: res.gas_used,
+                        logs: Some(res.logs),
+                        transactions,
+                    },
+                    Err(SimError::Bundle(err)) => SimBundleResponse {
+                        success: false,
+                        error: Some(err.to_string()),
+                        state_block,
+                        mev_gas_price: 0,
+                        profit: 0,
+                        refundable_value: 0,
+                        gas_used: 0,
+                        logs: None,
+                        transactions: Vec::new(),
+                    },
+                    Err(SimError::Eth(err)) => return Err(err),
+                };
+
+                Ok(res)
+            })
+            .await
+    }
+}
+
+#[async_trait::async_trait]
+impl<Eth> MevApiServer for EthSimBundle<Eth>
+where
+    Eth: EthTransactions + LoadPendingBlock + Call + 'static,
+{
+    async fn send_bundle(&self, request: SendBundleRequest) -> RpcResult<SendBundleResponse> {
+        Self::send_bundle(self, request).map_err(Into::into)
+    }
+
+    async fn sim_bundle(
+        &self,
+        request: SendBundleRequest,
+        overrides: SimBundleOverrides,
+    ) -> RpcResult<SimBundleResponse> {
+        Self::sim_bundle(self, request, overrides).await.map_err(Into::into)
+    }
+}
+
+/// Container type for `EthSimBundle` internals
+#[derive(Debug)]
+struct EthSimBundleInner<Eth> {
+    /// Access to commonly used code of the `eth` namespace
+    eth_api: Eth,
+    // restrict the number of concurrent simulations.
+    blocking_task_guard: BlockingTaskGuard,
+}
+
+impl<Eth> std::fmt::Debug for EthSimBundle<Eth> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("EthSimBundle").finish_non_exhaustive()
+    }
+}
+
+impl<Eth> Clone for EthSimBundle<Eth> {
+    fn clone(&self) -> Self {
+        Self { inner: Arc::clone(&self.inner) }
+    }
+}
+
+/// A bundle with all transactions recovered and validated.
+#[derive(Debug)]
+struct ResolvedBundle {
+    /// The blocks the bundle may be included in.
+    inclusion: Inclusion,
+    /// The transactions and nested bundles of the bundle.
+    body: Vec<ResolvedBundleItem>,
+    /// The refunds paid out for the items of the bundle.
+    refunds: Vec<Refund>,
+    /// Who receives the refunds if this bundle is refunded by an enclosing bundle.
+    refund_config: Vec<RefundConfig>,
+}
+
+impl ResolvedBundle {
+    /// Returns the signer of the first transaction of the bundle.
+    fn first_signer(&self) -> Address {
+        match &self.body[0] {
+            ResolvedBundleItem::Tx { signer, .. } => *signer,
+            ResolvedBundleItem::Bundle(bundle) => bundle.first_signer(),
+        }
+    }
+
+    /// Returns `true` if the bundle or any of its nested bundles pays out refunds.
+    fn has_refunds(&self) -> bool {
+        !self.refunds.is_empty() ||
+            self.body.iter().any(|item| match item {
+                ResolvedBundleItem::Tx { .. } => false,
+                ResolvedBundleItem::Bundle(bundle) => bundle.has_refunds(),
+            })
+    }
+
+    /// Appends the transactions of the bundle and its nested bundles in execution order, and the
+    /// hashes of the transactions that are allowed to revert.
+    fn flatten(
+        self,
+        transactions: &mut Vec<TransactionSignedEcRecovered>,
+        reverting_tx_hashes: &mut Vec<TxHash>,
+    ) {
+        for item in self.body {
+            match item {
+                ResolvedBundleItem::Tx { tx, signer, can_revert } => {
+                    if can_revert {
+                        reverting_tx_hashes.push(tx.hash());
+                    }
+                    transactions.push((*tx).with_signer(signer));
+                }
+                ResolvedBundleItem::Bundle(bundle) => {
+                    bundle.flatten(transactions, reverting_tx_hashes)
+                }
+            }
+        }
+    }
+}
+
+/// An item of a [`ResolvedBundle`].
+#[derive(Debug)]
+enum ResolvedBundleItem {
+    /// A recovered transaction.
+    Tx { tx: Box<TransactionSigned>, signer: Address, can_revert: bool },
+    /// A nested bundle.
+    Bundle(ResolvedBundle),
+}
+
+impl ResolvedBundleItem {
+    /// Returns who receives a refund paid out for this item.
+    ///
+    /// A transaction refunds its signer, a nested bundle its refund config or, if it has none, the
+    /// signer of its first transaction.
+    fn refund_configs(&self) -> Vec<RefundConfig> {
+        let address = match self {
+            Self::Tx { signer, .. } => *signer,
+            Self::Bundle(bundle) if !bundle.refund_config.is_empty() => {
+                return bundle.refund_config.clone()
+            }
+            Self::Bundle(bundle) => bundle.first_signer(),
+        };
+        vec![RefundConfig { address, percent: 100 }]
+    }
+}
+
+/// Recovers all transactions of the bundle, resolving transaction hashes from the pool, and
+/// validates its structure.
+fn resolve_bundle<Pool>(
+    pool: &Pool,
+    request: SendBundleRequest,
+    depth: usize,
+) -> Result<ResolvedBundle, EthSimBundleError>
+where
+    Pool: TransactionPool,
+{
+    if depth > MAX_NESTED_BUNDLE_DEPTH {
+        return Err(EthSimBundleError::MaxDepthExceeded)
+    }
+    let SendBundleRequest { inclusion, bundle_body, validity, .. } = request;
+    if bundle_body.is_empty() {
+        return Err(EthSimBundleError::EmptyBundleBody)
+    }
+    if bundle_body.len() > MAX_BUNDLE_BODY_SIZE {
+        return Err(EthSimBundleError::BundleTooLarge)
+    }
+    if inclusion.max_block.is_some_and(|max_block| max_block < inclusion.block) {
+        return Err(EthSimBundleError::InvalidInclusion)
+    }
+
+    let validity = validity.unwrap_or_default();
+    let refunds = validity.refund.unwrap_or_default();
+    let refund_config = validity.refund_config.unwrap_or_default();
+    if refunds.iter().any(|refund| refund.body_idx as usize >= bundle_body.len()) {
+        return Err(EthSimBundleError::InvalidRefundIndex)
+    }
+    if refunds.iter().map(|refund| refund.percent).sum::<u64>() > 100 {
+        return Err(EthSimBundleError::InvalidRefundPercent)
+    }
+    if !refund_config.is_empty() &&
+        refund_config.iter().map(|config| config.percent).sum::<u64>() != 100
+    {
+        return Err(EthSimBundleError::InvalidRefundConfig)
+    }
+
+    let body = bundle_body
+        .into_iter()
+        .map(|item| match item {
+            BundleItem::Hash { hash } => {
+                let tx = pool
+                    .get_pooled_transaction_element(hash)
+                    .and_then(|tx| tx.try_into_ecrecovered().ok())
+                    .ok_or(EthSimBundleError::UnknownTransaction(hash))?;
+                let (tx, signer) = tx.into_components();
+                Ok(ResolvedBundleItem::Tx {
+                    tx: Box::new(tx.into_transaction()),
+                    signer,
+                    can_revert: false,
+                })
+            }
+            BundleItem::Tx { tx, can_revert } => {
+                let (tx, signer) = recover_raw_transaction(tx)
+                    .map_err(|err| EthSimBundleError::InvalidTransactionData(err.to_string()))?
+                    .into_components();
+                // Verify that the given blob data, commitments, and proofs are all valid for this
+                // transaction.
+                if let PooledTransactionsElement::BlobTransaction(ref tx) = tx {
+                    tx.validate(EnvKzgSettings::Default.get()).map_err(|err| {
+                        EthSimBundleError::InvalidTransactionData(err.to_string())
+                    })?;
+                }
+                Ok(ResolvedBundleItem::Tx {
+                    tx: Box::new(tx.into_transaction()),
+                    signer,
+                    can_revert,
+                })
+            }
+            BundleItem::Bundle { bundle } => {
+                resolve_bundle(pool, *bundle, depth + 1).map(ResolvedBundleItem::Bundle)
+            }
+        })
+        .collect::<Result<Vec<_>, _>>()?;
+
+    Ok(ResolvedBundle { inclusion, body, refunds, refund_config })
+}
+
+/// The result of simulating a (nested) bundle.
+#[derive(Debug)]
+struct BundleSimResult {
+    /// The balance increase of the coinbase after all refunds were paid out.
+    profit: U256,
+    /// The part of the coinbase balance increase caused by refundable items.
+    refundable_value: U256,
+    /// Gas used by the bundle, including refund payouts.
+    gas_used: u64,
+    /// Logs of the items of the bundle.
+    logs: Vec<SimBundleLogs>,
+}
+
+/// Simulates the bundle on top of the current state of the EVM, committing its state changes, and
+/// pays out the refunds of the bundle from the coinbase.
+///
+/// The results of all executed transactions are appended to `transactions`.
+fn simulate_bundle<EvmConfig, EXT, DB, E>(
+    evm_config: &EvmConfig,
+    evm: &mut Evm<'_, EXT, CacheDB<DB>>,
+    bundle: &ResolvedBundle,
+    deadline: Instant,
+    transactions: &mut Vec<SimBundleTransactionResult>,
+) -> Result<BundleSimResult, SimError<E>>
+where
+    EvmConfig: ConfigureEvm,
+    DB: DatabaseRef,
+    EthApiError: From<DB::Error>,
+    E: FromEvmError + FromEthApiError,
+{
+    let block_number = evm.block().number.saturating_to::<u64>();
+    let Inclusion { block, max_block } = bundle.inclusion;
+    if block_number < block || block_number > max_block.unwrap_or(block) {
+        return Err(EthSimBundleError::BlockOutOfRange(block_number).into())
+    }
+
+    let coinbase = evm.block().coinbase;
+    let basefee = evm.block().basefee;
+    let initial_coinbase = balance_of(evm.db_mut(), coinbase)?;
+
+    let refundable =
+        bundle.refunds.iter().map(|refund| refund.body_idx as usize).collect::<HashSet<_>>();
+    let mut total_profit = U256::ZERO;
+    let mut refundable_value = U256::ZERO;
+    let mut gas_used = 0u64;
+    let mut logs = Vec::with_capacity(bundle.body.len());
+
+    for (idx, item) in bundle.body.iter().enumerate() {
+        if Instant::now() > deadline {
+            return Err(EthSimBundleError::Timeout.into())
+        }
+
+        let coinbase_before = balance_of(evm.db_mut(), coinbase)?;
+        match item {
+            ResolvedBundleItem::Tx { tx, signer, can_revert } => {
+                evm_config.fill_tx_env(evm.tx_mut(), tx, *signer);
+                let ResultAndState { result, state } = evm.transact().map_err(|err| match err {
+                    EVMError::Transaction(err) => SimError::Bundle(
+                        EthSimBundleError::InvalidTransaction(tx.hash(), err.to_string()),
+                    ),
+                    err => SimError::Eth(E::from_evm_err(err)),
+                })?;
+                let reverted = !result.is_success();
+                if reverted && !can_revert {
+                    return Err(EthSimBundleError::TransactionReverted(tx.hash()).into())
+                }
+                evm.db_mut().commit(state);
+
+                gas_used += result.gas_used();
+                transactions.push(SimBundleTransactionResult {
+                    tx_hash: tx.hash(),
+                    from_address: *signer,
+                    gas_used: result.gas_used(),
+                    profit: balance_of(evm.db_mut(), coinbase)?.saturating_sub(coinbase_before),
+                    reverted,
+                });
+                logs.push(SimBundleLogs { tx_logs: Some(result.into_logs()), bundle_logs: None });
+            }
+            ResolvedBundleItem::Bundle(inner) => {
+                let res = simulate_bundle(evm_config, evm, inner, deadline, transactions)?;
+                gas_used += res.gas_used;
+                logs.push(SimBundleLogs { tx_logs: None, bundle_logs: Some(res.logs) });
+            }
+        }
+
+        let delta = balance_of(evm.db_mut(), coinbase)?.saturating_sub(coinbase_before);
+        if refundable.contains(&idx) {
+            refundable_value += delta;
+        } else {
+            total_profit += delta;
+        }
+    }
+
+    // pay out the refunds from the coinbase, each payout is charged as a transfer transaction
+    for refund in &bundle.refunds {
+        let configs = bundle.body[refund.body_idx as usize].refund_configs();
+        let payout_cost = U256::from(REFUND_PAYOUT_GAS) * basefee;
+        let refund_value = total_profit * U256::from(refund.percent) / U256::from(100);
+        let payout_value = refund_value
+            .checked_sub(payout_cost * U256::from(configs.len()))
+            .ok_or(EthSimBundleError::RefundTooLow)?;
+
+        for config in configs {
+            let value = payout_value * U256::from(config.percent) / U256::from(100);
+            let db = evm.db_mut();
+            let account = db.load_account(coinbase).map_err(EthApiError::from)?;
+            account.info.balance = account
+                .info
+                .balance
+                .checked_sub(value + payout_cost)
+                .ok_or(EthSimBundleError::NegativeProfit)?;
+            let account = db.load_account(config.address).map_err(EthApiError::from)?;
+            account.info.balance += value;
+            gas_used += REFUND_PAYOUT_GAS;
+        }
+    }
+
+    let profit = balance_of(evm.db_mut(), coinbase)?
+        .checked_sub(initial_coinbase)
+        .ok_or(EthSimBundleError::NegativeProfit)?;
+
+    Ok(BundleSimResult { profit, refundable_value, gas_used, logs })
+}
+
+/// Returns the balance of the account in the simulation database.
+fn balance_of<DB, E>(db: &mut CacheDB<DB>, address: Address) -> Result<U256, SimError<E>>
+where
+    DB: DatabaseRef,
+    EthApiError: From<DB::Error>,
+    E: FromEthApiError,
+{
+    db.load_account(address)
+        .map(|account| account.info.balance)
+        .map_err(|err| SimError::Eth(E::from_eth_err(err)))
+}
+
+/// Errors that abort a bundle simulation.
+#[derive(Debug)]
+enum SimError<E> {
+    /// The bundle is invalid, reported as an unsuccessful simulation.
+    Bundle(EthSimBundleError),
+    /// Any other error.
+    Eth(E),
+}
+
+impl<E> From<EthSimBundleError> for SimError<E> {
+    fn from(err: EthSimBundleError) -> Self {
+        Self::Bundle(err)
+    }
+}
+
+impl<E: FromEthApiError> From<EthApiError> for SimError<E> {
+    fn from(err: EthApiError) -> Self {
+        Self::Eth(E::from_eth_err(err))
+    }
+}
+
+/// [`EthSimBundle`] specific errors.
+#[derive(Debug, thiserror::Error)]
+pub enum EthSimBundleError {
+    /// Thrown if bundles are nested deeper than [`MAX_NESTED_BUNDLE_DEPTH`].
+    #[error("max depth of {MAX_NESTED_BUNDLE_DEPTH} nested bundles exceeded")]
+    MaxDepthExceeded,
+    /// Thrown if the body of a bundle is empty.
+    #[error("bundle body is empty")]
+    EmptyBundleBody,
+    /// Thrown if the body of a bundle has more than [`MAX_BUNDLE_BODY_SIZE`] items.
+    #[error("bundle body exceeds the limit of {MAX_BUNDLE_BODY_SIZE} items")]
+    BundleTooLarge,
+    /// Thrown if the max block of a bundle is lower than its first block.
+    #[error("invalid inclusion: max block is lower than block")]
+    InvalidInclusion,
+    /// Thrown if a refund refers to an item outside of the bundle body.
+    #[error("refund body index out of range")]
+    InvalidRefundIndex,
+    /// Thrown if the refund percentages of a bundle sum up to more than 100.
+    #[error("refund percentages exceed 100")]
+    InvalidRefundPercent,
+    /// Thrown if the refund config percentages of a bundle do not sum up to 100.
+    #[error("refund config percentages must sum up to 100")]
+    InvalidRefundConfig,
+    /// Thrown if a transaction hash of the bundle is not in the transaction pool.
+    #[error("unknown transaction {0}")]
+    UnknownTransaction(TxHash),
+    /// Thrown if a raw transaction of the bundle can not be decoded or recovered.
+    #[error("invalid transaction data: {0}")]
+    InvalidTransactionData(String),
+    /// Thrown if the simulated block is not in the inclusion range of a bundle.
+    #[error("bundle can not be included in block {0}")]
+    BlockOutOfRange(u64),
+    /// Thrown if a transaction of the bundle is invalid in the simulated state.
+    #[error("invalid transaction {0}: {1}")]
+    InvalidTransaction(TxHash, String),
+    /// Thrown if a transaction of the bundle that is not allowed to revert reverted.
+    #[error("transaction {0} reverted")]
+    TransactionReverted(TxHash),
+    /// Thrown if a refund does not cover the cost of its payouts.
+    #[error("refund does not cover the payout cost")]
+    RefundTooLow,
+    /// Thrown if the coinbase balance decreased during the simulation.
+    #[error("bundle has negative profit")]
+    NegativeProfit,
+    /// Thrown if a bundle with refunds is sent to the bundle pool.
+    #[error("refunds are not supported by the bundle pool")]
+    RefundsNotSupported,
+    /// Thrown if the simulation exceeded its timeout.
+    #[error("simulation timed out")]
+    Timeout,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::EthApi;
+    use reth_chainspec::ChainSpecBuilder;
+    use reth_evm_ethereum::EthEvmConfig;
+    use reth_primitives::{
+        public_key_to_address, Block, Bytes, Header, Transaction, TxKind, TxLegacy,
+    };
+    use reth_provider::test_utils::{ExtendedAccount, MockEthProvider};
+    use reth_rpc_eth_types::{
+        EthStateCache, FeeHistoryCache, FeeHistoryCacheConfig, GasPriceOracle,
+    };
+    use reth_rpc_server_types::constants::{DEFAULT_ETH_PROOF_WINDOW, DEFAULT_PROOF_PERMITS};
+    use reth_rpc_types::mev::Validity;
+    use reth_tasks::pool::BlockingTaskPool;
+    use reth_testing_utils::generators::{self, sign_tx_with_key_pair};
+    use reth_transaction_pool::{
+        noop::NoopTransactionPool,
+        test_utils::{testing_pool, TestPool},
+    };
+    use secp256k1::Keypair;
+
+    const GWEI: u64 = 1_000_000_000;
+
+    /// The coinbase of the simulated block.
+    const COINBASE: Address = Address::repeat_byte(0xcb);
+
+    /// A contract that always reverts.
+    const REVERTER: Address = Address::repeat_byte(0xee);
+
+    type TestEthSimBundle = EthSimBundle<EthApi<MockEthProvider, TestPool, (), EthEvmConfig>>;
+
+    /// Creates the API on top of a genesis block, with funded accounts for the given keys.
+    fn sim_bundle_api(keys: &[Keypair]) -> TestEthSimBundle {
+        let provider = MockEthProvider {
+            chain_spec: Arc::new(ChainSpecBuilder::mainnet().shanghai_activated().build()),
+            ..Default::default()
+        };
+        let genesis = Block {
+            header: Header {
+                gas_limit: 30_000_000,
+                base_fee_per_gas: Some(GWEI),
+                ..Default::default()
+            },
+            ..Default::default()
+        };
+        provider.add_block(genesis.header.hash_slow(), genesis);
+        for key in keys {
+            provider.add_account(
+                public_key_to_address(key.public_key()),
+                ExtendedAccount::new(0, U256::from(10u128.pow(18))),
+            );
+        }
+        // PUSH1 0 PUSH1 0 REVERT
+        provider.add_account(
+            REVERTER,
+            ExtendedAccount::new(0, U256::ZERO)
+                .with_bytecode(Bytes::from_static(&[0x60, 0x00, 0x60, 0x00, 0xfd])),
+        );
+
+        let evm_config = EthEvmConfig::default();
+        let cache = EthStateCache::spawn(provider.clone(), Default::default(), evm_config);
+        let eth_api = EthApi::new(
+            provider.clone(),
+            testing_pool(),
+            (),
+            cache.clone(),
+            GasPriceOracle::new(provider, Default::default(), cache.clone()),
+            u64::MAX,
+            DEFAULT_ETH_PROOF_WINDOW,
+            BlockingTaskPool::build().expect("failed to build tracing pool"),
+            FeeHistoryCache::new(cache, FeeHistoryCacheConfig::default()),
+            evm_config,
+            None,
+            DEFAULT_PROOF_PERMITS,
+        );
+        EthSimBundle::new(eth_api, BlockingTaskGuard::new(4))
+    }
+
+    /// Signs a call with a priority fee of 1 gwei at the base fee of the simulated block.
+    fn call(key: Keypair, to: Address, value: U256, gas_limit: u64) -> TransactionSigned {
+        sign_tx_with_key_pair(
+            key,
+            Transaction::Legacy(TxLegacy {
+                chain_id: Some(1),
+                gas_price: 2 * GWEI as u128,
+                gas_limit,
+                to: TxKind::Call(to),
+                value,
+                ..Default::default()
+            }),
+        )
+    }
+
+    fn item(tx: &TransactionSigned, can_revert: bool) -> BundleItem {
+        BundleItem::Tx { tx: tx.envelope_encoded(), can_revert }
+    }
+
+    fn bundle(body: Vec<BundleItem>, refund: Option<Vec<Refund>>) -> SendBundleRequest {
+        SendBundleRequest {
+            inclusion: Inclusion::at_block(1),
+            bundle_body: body,
+            validity: Some(Validity { refund, refund_config: None }),
+            ..Default::default()
+        }
+    }
+
+    fn overrides() -> SimBundleOverrides {
+        SimBundleOverrides { coinbase: Some(COINBASE), base_fee: Some(GWEI), ..Default::default() }
+    }
+
+    #[tokio::test]
+    async fn sim_nested_bundle_with_refund() {
+        let keys = generators::generate_keys(&mut generators::rng(), 2);
+        let api = sim_bundle_api(&keys);
+
+        // a user transaction in a nested bundle, backrun by a searcher that pays the coinbase
+        let user_tx = call(keys[0], Address::repeat_byte(1), U256::from(1), 21_000);
+        let coinbase_payment = U256::from(10u64.pow(16));
+        let searcher_tx = call(keys[1], COINBASE, coinbase_payment, 21_000);
+        let request = bundle(
+            vec![
+                BundleItem::Bundle { bundle: Box::new(bundle(vec![item(&user_tx, false)], None)) },
+                item(&searcher_tx, false),
+            ],
+            Some(vec![Refund { body_idx: 0, percent: 50 }]),
+        );
+
+        let res = api.sim_bundle(request, overrides()).await.unwrap();
+        assert!(res.success, "{:?}", res.error);
+
+        // every transaction pays a priority fee of 1 gwei per gas to the coinbase
+        let priority_fee = U256::from(21_000 * GWEI);
+        let searcher_profit = priority_fee + coinbase_payment;
+        // half of the searcher's profit is refunded to the user, minus the cost of the payout
+        let refund = searcher_profit / U256::from(2);
+        let profit = priority_fee + searcher_profit - refund;
+
+        assert_eq!(U256::from(res.profit), profit);
+        assert_eq!(U256::from(res.refundable_value), priority_fee);
+        assert_eq!(res.gas_used, 2 * 21_000 + REFUND_PAYOUT_GAS);
+        assert_eq!(U256::from(res.mev_gas_price), profit / U256::from(res.gas_used));
+
+        let transactions = res
+            .transactions
+            .iter()
+            .map(|tx| (tx.tx_hash, tx.from_address, tx.profit, tx.reverted))
+            .collect::<Vec<_>>();
+        assert_eq!(
+            transactions,
+            vec![
+                (user_tx.hash(), user_tx.recover_signer().unwrap(), priority_fee, false),
+                (searcher_tx.hash(), searcher_tx.recover_signer().unwrap(), searcher_profit, false),
+            ]
+        );
+
+        let logs = res.logs.unwrap();
+        assert_eq!(logs.len(), 2);
+        assert_eq!(logs[0].bundle_logs.as_ref().map(Vec::len), Some(1));
+        assert!(logs[1].tx_logs.is_some());
+    }
+
+    #[tokio::test]
+    async fn sim_bundle_failures() {
+        let keys = generators::generate_keys(&mut generators::rng(), 2);
+        let api = sim_bundle_api(&keys);
+        let reverting_tx = call(keys[0], REVERTER, U256::ZERO, 50_000);
+
+        // a reverting transaction fails the bundle, unless it is allowed to revert
+        let res = api.sim_bundle(bundle(vec![item(&reverting_tx, false)], None), overrides());
+        let res = res.await.unwrap();
+        assert!(!res.success);
+        assert_eq!(
+            res.error,
+            Some(EthSimBundleError::TransactionReverted(reverting_tx.hash()).to_string())
+        );
+
+        let res = api.sim_bundle(bundle(vec![item(&reverting_tx, true)], None), overrides());
+        let res = res.await.unwrap();
+        assert!(res.success, "{:?}", res.error);
+        assert!(res.transactions[0].reverted);
+
+        // the refund must cover the cost of the payout
+        let tx = call(keys[1], Address::repeat_byte(1), U256::ZERO, 21_000);
+        let request = bundle(
+            vec![item(&reverting_tx, true), item(&tx, false)],
+            Some(vec![Refund { body_idx: 0, percent: 1 }]),
+        );
+        let res = api.sim_bundle(request, overrides()).await.unwrap();
+        assert_eq!(res.error, Some(EthSimBundleError::RefundTooLow.to_string()));
+
+        // the bundle can't be simulated outside of its inclusion range
+        let mut request = bundle(vec![item(&tx, false)], None);
+        request.inclusion = Inclusion::at_block(5);
+        let res = api.sim_bundle(request, overrides()).await.unwrap();
+        assert_eq!(res.error, Some(EthSimBundleError::BlockOutOfRange(1).to_string()));
+    }
+
+    #[tokio::test]
+    async fn send_nested_bundle() {
+        let keys = generators::generate_keys(&mut generators::rng(), 2);
+        let api = sim_bundle_api(&keys);
+        let first = call(keys[0], Address::repeat_byte(1), U256::ZERO, 21_000);
+        let second = call(keys[1], REVERTER, U256::ZERO, 50_000);
+
+        let mut request = bundle(
+            vec![
+                BundleItem::Bundle { bundle: Box::new(bundle(vec![item(&first, false)], None)) },
+                item(&second, true),
+            ],
+            None,
+        );
+        request.inclusion = Inclusion { block: 1, max_block: Some(3) };
+        let res = api.send_bundle(request.clone()).unwrap();
+
+        // the nested bundle is flattened into a single pool bundle for the inclusion range
+        let pool = LoadPendingBlock::pool(&api.inner.eth_api);
+        let pool = pool.bundle_pool().unwrap();
+        let bundles = pool.bundles_for_block(3, 0);
+        assert_eq!(bundles.len(), 1);
+        assert_eq!(bundles[0].hash(), res.bundle_hash);
+        let hashes = bundles[0].transactions().iter().map(|tx| tx.hash()).collect::<Vec<_>>();
+        assert_eq!(hashes, vec![first.hash(), second.hash()]);
+        assert!(!bundles[0].can_revert(&first.hash()));
+        assert!(bundles[0].can_revert(&second.hash()));
+        assert!(pool.bundles_for_block(4, 0).is_empty());
+
+        // refunds can't be paid out by the payload builder
+        request.validity = Some(Validity {
+            refund: Some(vec![Refund { body_idx: 0, percent: 50 }]),
+            refund_config: None,
+        });
+        assert!(api.send_bundle(request).is_err());
+    }
+
+    fn nested(depth: usize) -> SendBundleRequest {
+        let mut bundle = SendBundleRequest {
+            bundle_body: vec![BundleItem::Hash { hash: TxHash::ZERO }],
+            ..Default::default()
+        };
+        for _ in 0..depth {
+            bundle = SendBundleRequest {
+                bundle_body: vec![BundleItem::Bundle { bundle: Box::new(bundle) }],
+                ..Default::default()
+            };
+        }
+        bundle
+    }
+
+    #[test]
+    fn resolve_validates_structure() {
+        let pool = NoopTransactionPool::default();
+
+        let err = resolve_bundle(&pool, nested(MAX_NESTED_BUNDLE_DEPTH + 1), 0).unwrap_err();
+        assert!(matches!(err, EthSimBundleError::MaxDepthExceeded));
+
+        let err = resolve_bundle(&pool, nested(MAX_NESTED_BUNDLE_DEPTH), 0).unwrap_err();
+        assert!(matches!(err, EthSimBundleError::UnknownTransaction(_)));
+
+        let err = resolve_bundle(&pool, SendBundleRequest::default(), 0).unwrap_err();
+        assert!(matches!(err, EthSimBundleError::EmptyBundleBody));
+
+        let mut bundle = nested(0);
+        bundle.validity = Some(Validity {
+            refund: Some(vec![Refund { body_idx: 1, percent: 50 }]),
+            refund_config: None,
+        });
+        let err = resolve_bundle(&pool, bundle, 0).unwrap_err();
+        assert!(matches!(err, EthSimBundleError::InvalidRefundIndex));
+    }
+
+    #[test]
+    fn refund_configs() {
+        let signer = Address::with_last_byte(1);
+        let tx = || ResolvedBundleItem::Tx { tx: Box::default(), signer, can_revert: false };
+        let bundle = |refund_config| {
+            ResolvedBundleItem::Bundle(ResolvedBundle {
+                inclusion: Inclusion::at_block(1),
+                body: vec![tx()],
+                refunds: Vec::new(),
+                refund_config,
+            })
+        };
+
+        let expected = vec![RefundConfig { address: signer, percent: 100 }];
+        assert_eq!(tx().refund_configs(), expected);
+        assert_eq!(bundle(Vec::new()).refund_configs(), expected);
+
+        let config = vec![
+            RefundConfig { address: Address::with_last_byte(2), percent: 60 },
+            RefundConfig { address: Address::with_last_byte(3), percent: 40 },
+        ];
+        assert_eq!(bundle(config.clone()).refund_configs(), config);
+    }
+}
@@ -33,6 +33,7 @@ use pin_project as _;
 use tower as _;
 
 mod admin;
+mod anvil;
 mod debug;
 mod engine;
 pub mod eth;
@@ -42,15 +43,18 @@ mod reth;
 mod rpc;
 mod trace;
 mod txpool;
+mod validation;
 mod web3;
 pub use admin::AdminApi;
+pub use anvil::AnvilApi;
 pub use debug::DebugApi;
 pub use engine::{EngineApi, EngineEthApi};
-pub use eth::{EthApi, EthBundle, EthFilter, EthPubSub};
+pub use eth::{EthApi, EthBundle, EthFilter, EthPubSub, EthSimBundle};
 pub use net::NetApi;
 pub use otterscan::OtterscanApi;
 pub use reth::RethApi;
 pub use rpc::RPCApi;
 pub use trace::TraceApi;
 pub use txpool::TxPoolApi;
+pub use validation::{ValidationApi, ValidationApiError};
 pub use web3::Web3Api;
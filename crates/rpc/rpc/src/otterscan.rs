@@ -2,6 +2,7 @@ use alloy_primitives::Bytes;
 use async_trait::async_trait;
 use jsonrpsee::core::RpcResult;
 use reth_primitives::{Address, BlockNumberOrTag, TxHash, B256, U256};
+use reth_provider::AccountHistoryReader;
 use reth_rpc_api::{EthApiServer, OtterscanServer};
 use reth_rpc_eth_api::helpers::TraceExt;
 use reth_rpc_eth_types::EthApiError;
@@ -14,27 +15,28 @@ use reth_rpc_types::{
         },
         parity::{Action, CreateAction, CreateOutput, TraceOutput},
     },
-    AnyTransactionReceipt, BlockTransactions, Header, RichBlock,
+    AnyTransactionReceipt, BlockTransactions, Header, RichBlock, Transaction,
 };
 use revm_inspectors::{
     tracing::{types::CallTraceNode, TracingInspectorConfig},
     transfer::{TransferInspector, TransferKind},
 };
 use revm_primitives::ExecutionResult;
-use std::future::Future;
+use std::{collections::HashSet, future::Future, ops::Range};
 
 const API_LEVEL: u64 = 8;
 
 /// Otterscan API.
 #[derive(Debug)]
-pub struct OtterscanApi<Eth> {
+pub struct OtterscanApi<Provider, Eth> {
+    provider: Provider,
     eth: Eth,
 }
 
-impl<Eth> OtterscanApi<Eth> {
+impl<Provider, Eth> OtterscanApi<Provider, Eth> {
     /// Creates a new instance of `Otterscan`.
-    pub const fn new(eth: Eth) -> Self {
-        Self { eth }
+    pub const fn new(provider: Provider, eth: Eth) -> Self {
+        Self { provider, eth }
     }
 
     /// Constructs a `BlockDetails` from a block and its receipts.
@@ -56,9 +58,69 @@ impl<Eth> OtterscanApi<Eth> {
     }
 }
 
+impl<Provider, Eth> OtterscanApi<Provider, Eth>
+where
+    Eth: EthApiServer + TraceExt + 'static,
+{
+    /// Returns the transactions of the block that interacted with the given address, together
+    /// with their receipts.
+    ///
+    /// A transaction interacted with the address if the address is the sender, recipient or
+    /// beneficiary of any of its calls, including internal ones.
+    async fn address_transactions_in_block(
+        &self,
+        address: Address,
+        block_number: u64,
+    ) -> RpcResult<Vec<(Transaction, OtsTransactionReceipt)>> {
+        let indices = self
+            .eth
+            .trace_block_with(
+                block_number.into(),
+                TracingInspectorConfig::default_parity(),
+                move |tx_info, inspector, _, _, _| {
+                    let interacted = inspector.traces().nodes().iter().any(|node| {
+                        node.trace.caller == address ||
+                            node.trace.address == address ||
+                            node.trace.selfdestruct_refund_target == Some(address)
+                    });
+                    Ok(tx_info.index.filter(|_| interacted))
+                },
+            )
+            .await
+            .map_err(Into::into)?
+            .ok_or_else(|| EthApiError::UnknownBlockNumber)?
+            .into_iter()
+            .flatten()
+            .collect::<HashSet<_>>();
+        if indices.is_empty() {
+            return Ok(Vec::new())
+        }
+
+        let block = self.eth.block_by_number(block_number.into(), true);
+        let receipts = self.eth.block_receipts(block_number.into());
+        let (block, receipts) = futures::try_join!(block, receipts)?;
+
+        let block = block.ok_or_else(|| EthApiError::UnknownBlockNumber)?;
+        let receipts = receipts.ok_or_else(|| internal_rpc_err("receipts not found"))?;
+        let timestamp = Some(block.header.timestamp);
+        let BlockTransactions::Full(transactions) = block.inner.transactions else {
+            return Err(internal_rpc_err("block is not full"));
+        };
+
+        Ok(transactions
+            .into_iter()
+            .zip(receipts)
+            .enumerate()
+            .filter(|(index, _)| indices.contains(&(*index as u64)))
+            .map(|(_, (tx, receipt))| (tx, ots_receipt(receipt, timestamp)))
+            .collect())
+    }
+}
+
 #[async_trait]
-impl<Eth> OtterscanServer for OtterscanApi<Eth>
+impl<Provider, Eth> OtterscanServer for OtterscanApi<Provider, Eth>
 where
+    Provider: AccountHistoryReader + 'static,
     Eth: EthApiServer + TraceExt + 'static,
 {
     /// Handler for `{ots,erigon}_getHeaderByNumber`
@@ -216,22 +278,7 @@ where
         let timestamp = Some(block.header.timestamp);
         let receipts = receipts
             .drain(page_start..page_end)
-            .map(|receipt| {
-                let receipt = receipt.inner.map_inner(|receipt| OtsReceipt {
-                    status: receipt
-                        .inner
-                        .receipt
-                        .status
-                        .as_eip658()
-                        .expect("ETH API returned pre-EIP-658 status"),
-                    cumulative_gas_used: receipt.inner.receipt.cumulative_gas_used as u64,
-                    logs: None,
-                    logs_bloom: None,
-                    r#type: receipt.r#type,
-                });
-
-                OtsTransactionReceipt { receipt, timestamp }
-            })
+            .map(|receipt| ots_receipt(receipt, timestamp))
             .collect();
         Ok(OtsBlockTransactions { fullblock: block.inner.into(), receipts })
     }
@@ -239,21 +286,45 @@ where
     /// Handler for `searchTransactionsBefore`
     async fn search_transactions_before(
         &self,
-        _address: Address,
-        _block_number: u64,
-        _page_size: usize,
+        address: Address,
+        block_number: u64,
+        page_size: usize,
     ) -> RpcResult<TransactionsWithReceipts> {
-        Err(internal_rpc_err("unimplemented"))
+        // Block number 0 means the search starts at the most recent block.
+        let first_page = block_number == 0;
+        let end = if first_page { u64::MAX } else { block_number };
+        let blocks = AccountHistoryBlocks::new(&self.provider, address, 0..end, page_size, true);
+
+        let (page, has_more) =
+            collect_page(blocks, page_size, |num| self.address_transactions_in_block(address, num))
+                .await?;
+
+        // Transactions are returned from the most recent to the oldest one.
+        let (txs, receipts) = page.into_iter().flat_map(|block| block.into_iter().rev()).unzip();
+        Ok(TransactionsWithReceipts { txs, receipts, first_page, last_page: !has_more })
     }
 
     /// Handler for `searchTransactionsAfter`
     async fn search_transactions_after(
         &self,
-        _address: Address,
-        _block_number: u64,
-        _page_size: usize,
+        address: Address,
+        block_number: u64,
+        page_size: usize,
     ) -> RpcResult<TransactionsWithReceipts> {
-        Err(internal_rpc_err("unimplemented"))
+        // Block number 0 means the search starts at the genesis block.
+        let last_page = block_number == 0;
+        let start = if last_page { 0 } else { block_number + 1 };
+        let blocks =
+            AccountHistoryBlocks::new(&self.provider, address, start..u64::MAX, page_size, false);
+
+        let (page, has_more) =
+            collect_page(blocks, page_size, |num| self.address_transactions_in_block(address, num))
+                .await?;
+
+        // Transactions are returned from the most recent to the oldest one.
+        let (txs, receipts) =
+            page.into_iter().rev().flat_map(|block| block.into_iter().rev()).unzip();
+        Ok(TransactionsWithReceipts { txs, receipts, first_page: !has_more, last_page })
     }
 
     /// Handler for `getTransactionBySenderAndNonce`
@@ -364,6 +435,130 @@ where
     }
 }
 
+/// Converts a receipt into the receipt format of the otterscan API.
+fn ots_receipt(receipt: AnyTransactionReceipt, timestamp: Option<u64>) -> OtsTransactionReceipt {
+    let receipt = receipt.inner.map_inner(|receipt| OtsReceipt {
+        status: receipt
+            .inner
+            .receipt
+            .status
+            .as_eip658()
+            .expect("ETH API returned pre-EIP-658 status"),
+        cumulative_gas_used: receipt.inner.receipt.cumulative_gas_used as u64,
+        logs: None,
+        logs_bloom: None,
+        r#type: receipt.r#type,
+    });
+
+    OtsTransactionReceipt { receipt, timestamp }
+}
+
+/// Iterator over the blocks that changed an account, fetched from the history indices in batches.
+#[derive(Debug)]
+struct AccountHistoryBlocks<'a, Provider> {
+    provider: &'a Provider,
+    address: Address,
+    /// The range of the blocks that weren't fetched yet.
+    range: Range<u64>,
+    batch_size: usize,
+    reverse: bool,
+    batch: std::vec::IntoIter<u64>,
+}
+
+impl<'a, Provider: AccountHistoryReader> AccountHistoryBlocks<'a, Provider> {
+    /// Creates an iterator over the blocks in the given range, from the lowest to the highest one,
+    /// or the other way around if `reverse` is set.
+    fn new(
+        provider: &'a Provider,
+        address: Address,
+        range: Range<u64>,
+        batch_size: usize,
+        reverse: bool,
+    ) -> Self {
+        Self {
+            provider,
+            address,
+            range,
+            batch_size: batch_size.max(1),
+            reverse,
+            batch: Vec::new().into_iter(),
+        }
+    }
+}
+
+impl<Provider: AccountHistoryReader> Iterator for AccountHistoryBlocks<'_, Provider> {
+    type Item = RpcResult<u64>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if let Some(block) = self.batch.next() {
+            return Some(Ok(block))
+        }
+        if self.range.is_empty() {
+            return None
+        }
+
+        let batch = match self.provider.account_history_blocks(
+            self.address,
+            self.range.clone(),
+            self.batch_size,
+            self.reverse,
+        ) {
+            Ok(batch) => batch,
+            Err(err) => {
+                self.range = 0..0;
+                return Some(Err(EthApiError::from(err).into()))
+            }
+        };
+        match batch.last() {
+            Some(&last) if batch.len() == self.batch_size => {
+                if self.reverse {
+                    self.range.end = last;
+                } else {
+                    self.range.start = last + 1;
+                }
+            }
+            _ => self.range = 0..0,
+        }
+
+        self.batch = batch.into_iter();
+        self.batch.next().map(Ok)
+    }
+}
+
+/// Collects a page of search results from the given candidate blocks.
+///
+/// The `matches` closure returns the matching items of a block. Blocks are never split between
+/// pages, so the page is complete once it holds at least `page_size` items, and may hold more.
+///
+/// Returns the non-empty results of each block, in the order of the given blocks, and whether there
+/// are more matching blocks after the page.
+async fn collect_page<T, F, Fut>(
+    blocks: impl IntoIterator<Item = RpcResult<u64>>,
+    page_size: usize,
+    matches: F,
+) -> RpcResult<(Vec<Vec<T>>, bool)>
+where
+    F: Fn(u64) -> Fut,
+    Fut: Future<Output = RpcResult<Vec<T>>>,
+{
+    let mut page = Vec::new();
+    let mut len = 0;
+
+    for block in blocks {
+        let items = matches(block?).await?;
+        if items.is_empty() {
+            continue
+        }
+        if len >= page_size {
+            return Ok((page, true))
+        }
+        len += items.len();
+        page.push(items);
+    }
+
+    Ok((page, false))
+}
+
 /// Performs a binary search within a given block range to find the desired block number.
 ///
 /// The binary search is performed by calling the provided asynchronous `check` closure on the
@@ -422,4 +617,77 @@ mod tests {
         let num = binary_search(1, 10, |mid| Box::pin(async move { Ok(mid >= 11) })).await;
         assert_eq!(num, Ok(10));
     }
+
+    #[tokio::test]
+    async fn test_collect_page() {
+        // block `n` contains `n % 3` matching items
+        let matches = |block: u64| async move { Ok(vec![block; block as usize % 3]) };
+        fn blocks(blocks: impl IntoIterator<Item = u64>) -> impl Iterator<Item = RpcResult<u64>> {
+            blocks.into_iter().map(Ok)
+        }
+
+        // blocks are never split
+        let page = collect_page(blocks(1..=10), 2, matches).await;
+        assert_eq!(page, Ok((vec![vec![1], vec![2, 2]], true)));
+
+        // empty blocks are skipped when looking for more results
+        let page = collect_page(blocks(1..=10), 5, matches).await;
+        assert_eq!(page, Ok((vec![vec![1], vec![2, 2], vec![4], vec![5, 5]], true)));
+        let page = collect_page(blocks([7, 8, 9]), 3, matches).await;
+        assert_eq!(page, Ok((vec![vec![7], vec![8, 8]], false)));
+
+        // no more blocks
+        let page = collect_page(blocks((1..=10).rev()), 100, matches).await;
+        assert_eq!(page.map(|(page, has_more)| (page.len(), has_more)), Ok((7, false)));
+        let page = collect_page(blocks([3, 6, 9]), 1, matches).await;
+        assert_eq!(page, Ok((vec![], false)));
+    }
+
+    /// Serves the blocks of a history, recording the requested batches.
+    struct History(Vec<u64>, std::sync::Mutex<Vec<Vec<u64>>>);
+
+    impl AccountHistoryReader for History {
+        fn account_history_blocks(
+            &self,
+            _address: Address,
+            range: impl std::ops::RangeBounds<u64>,
+            limit: usize,
+            reverse: bool,
+        ) -> reth_provider::ProviderResult<Vec<u64>> {
+            let blocks = self.0.iter().copied().filter(|block| range.contains(block));
+            let batch: Vec<_> = if reverse {
+                blocks.rev().take(limit).collect()
+            } else {
+                blocks.take(limit).collect()
+            };
+            self.1.lock().unwrap().push(batch.clone());
+            Ok(batch)
+        }
+    }
+
+    #[test]
+    fn test_account_history_blocks() {
+        let history = History(vec![1, 3, 4, 7, 9], Default::default());
+        let blocks = |range, reverse| {
+            history.1.lock().unwrap().clear();
+            let blocks = AccountHistoryBlocks::new(&history, Address::ZERO, range, 2, reverse)
+                .collect::<RpcResult<Vec<_>>>()
+                .unwrap();
+            (blocks, history.1.lock().unwrap().clone())
+        };
+
+        assert_eq!(
+            blocks(0..u64::MAX, false),
+            (vec![1, 3, 4, 7, 9], vec![vec![1, 3], vec![4, 7], vec![9]])
+        );
+        assert_eq!(blocks(2..9, true), (vec![7, 4, 3], vec![vec![7, 4], vec![3]]));
+        assert_eq!(blocks(0..4, false), (vec![1, 3], vec![vec![1, 3]]));
+
+        // only the batches of the consumed blocks are fetched
+        history.1.lock().unwrap().clear();
+        let mut blocks = AccountHistoryBlocks::new(&history, Address::ZERO, 0..u64::MAX, 2, true);
+        assert_eq!(blocks.next(), Some(Ok(9)));
+        assert_eq!(blocks.next(), Some(Ok(7)));
+        assert_eq!(*history.1.lock().unwrap(), vec![vec![9, 7]]);
+    }
 }
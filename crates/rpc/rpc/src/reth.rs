@@ -4,9 +4,13 @@ use async_trait::async_trait;
 use jsonrpsee::core::RpcResult;
 use reth_errors::RethResult;
 use reth_primitives::{Address, BlockId, U256};
-use reth_provider::{BlockReaderIdExt, ChangeSetReader, StateProviderFactory};
+use reth_provider::{
+    BlockReaderIdExt, ChangeSetReader, StateProviderFactory, TableDigestReader,
+    MAX_TABLE_DIGEST_ENTRIES,
+};
 use reth_rpc_api::RethApiServer;
 use reth_rpc_eth_types::{EthApiError, EthResult};
+use reth_rpc_types::reth::{TableDigest, TableDigestCursor};
 use reth_tasks::TaskSpawner;
 use tokio::sync::oneshot;
 
@@ -34,7 +38,8 @@ impl<Provider> RethApi<Provider> {
 
 impl<Provider> RethApi<Provider>
 where
-    Provider: BlockReaderIdExt + ChangeSetReader + StateProviderFactory + 'static,
+    Provider:
+        BlockReaderIdExt + ChangeSetReader + StateProviderFactory + TableDigestReader + 'static,
 {
     /// Executes the future on a new blocking task.
     async fn on_blocking_task<C, F, R>(&self, c: C) -> EthResult<R>
@@ -82,12 +87,48 @@ where
         )?;
         Ok(hash_map)
     }
+
+    /// Returns the digest of the raw entries of the given table from `from` up to, but excluding,
+    /// `to`.
+    ///
+    /// At most [`MAX_TABLE_DIGEST_ENTRIES`] entries are digested, or `limit` if lower.
+    pub async fn table_digest(
+        &self,
+        table: String,
+        from: Option<TableDigestCursor>,
+        to: Option<TableDigestCursor>,
+        limit: Option<u64>,
+    ) -> EthResult<TableDigest> {
+        let limit = limit
+            .map_or(MAX_TABLE_DIGEST_ENTRIES, |limit| limit.clamp(1, MAX_TABLE_DIGEST_ENTRIES));
+        self.on_blocking_task(|this| async move {
+            let cursor = |cursor: TableDigestCursor| reth_provider::TableDigestCursor {
+                key: cursor.key,
+                subkey: cursor.subkey,
+            };
+            let digest = this.provider().table_digest(
+                &table,
+                from.map(cursor).as_ref(),
+                to.map(cursor).as_ref(),
+                limit,
+            )?;
+            Ok(TableDigest {
+                entries: digest.entries,
+                digest: digest.digest,
+                next: digest
+                    .next
+                    .map(|next| TableDigestCursor { key: next.key, subkey: next.subkey }),
+            })
+        })
+        .await
+    }
 }
 
 #[async_trait]
 impl<Provider> RethApiServer for RethApi<Provider>
 where
-    Provider: BlockReaderIdExt + ChangeSetReader + StateProviderFactory + 'static,
+    Provider:
+        BlockReaderIdExt + ChangeSetReader + StateProviderFactory + TableDigestReader + 'static,
 {
     /// Handler for `reth_getBalanceChangesInBlock`
     async fn reth_get_balance_changes_in_block(
@@ -96,6 +137,17 @@ where
     ) -> RpcResult<HashMap<Address, U256>> {
         Ok(Self::balance_changes_in_block(self, block_id).await?)
     }
+
+    /// Handler for `reth_tableDigest`
+    async fn reth_table_digest(
+        &self,
+        table: String,
+        from: Option<TableDigestCursor>,
+        to: Option<TableDigestCursor>,
+        limit: Option<u64>,
+    ) -> RpcResult<TableDigest> {
+        Ok(Self::table_digest(self, table, from, to, limit).await?)
+    }
 }
 
 impl<Provider> std::fmt::Debug for RethApi<Provider> {
@@ -0,0 +1,511 @@
+use std::{future::Future, sync::Arc};
+
+use async_trait::async_trait;
+use jsonrpsee::core::RpcResult;
+use reth_consensus::{Consensus, ConsensusError, PostExecutionInput};
+use reth_errors::{BlockExecutionError, BlockValidationError, ProviderError};
+use reth_evm::execute::{BlockExecutorProvider, Executor};
+use reth_primitives::{
+    BlobTransactionSidecar, BlobTransactionValidationError, BlockWithSenders, GotExpected, Receipt,
+    SealedBlock, SealedHeader, B256,
+};
+use reth_provider::{BlockReaderIdExt, StateProviderFactory};
+use reth_revm::database::StateProviderDatabase;
+use reth_rpc_api::BlockSubmissionValidationApiServer;
+use reth_rpc_server_types::result::{internal_rpc_err, invalid_params_rpc_err};
+use reth_rpc_types::{
+    engine::{BlobsBundleV1, PayloadError},
+    relay::{
+        BidTrace, BuilderBlockValidationRequest, BuilderBlockValidationRequestV2,
+        BuilderBlockValidationRequestV3,
+    },
+    ExecutionPayload,
+};
+use reth_rpc_types_compat::engine::payload::try_into_sealed_block;
+use reth_tasks::TaskSpawner;
+use revm_primitives::EnvKzgSettings;
+use tokio::sync::oneshot;
+
+/// The bound divisor of the gas limit, the gas limit can change by at most
+/// `parent_gas_limit / GAS_LIMIT_BOUND_DIVISOR` per block.
+const GAS_LIMIT_BOUND_DIVISOR: u64 = 1024;
+
+/// `flashbots` block submission validation API implementation.
+///
+/// This executes submitted blocks on top of their parent, and checks that they are valid, that
+/// they target the gas limit registered by the proposer and that they pay the proposer the value
+/// of the bid.
+pub struct ValidationApi<Provider, E> {
+    inner: Arc<ValidationApiInner<Provider, E>>,
+}
+
+// === impl ValidationApi ===
+
+impl<Provider, E> ValidationApi<Provider, E> {
+    /// Create a new instance of the [`ValidationApi`]
+    pub fn new(
+        provider: Provider,
+        consensus: Arc<dyn Consensus>,
+        executor_provider: E,
+        task_spawner: Box<dyn TaskSpawner>,
+    ) -> Self {
+        let inner =
+            Arc::new(ValidationApiInner { provider, consensus, executor_provider, task_spawner });
+        Self { inner }
+    }
+}
+
+impl<Provider, E> ValidationApi<Provider, E>
+where
+    Provider: BlockReaderIdExt + StateProviderFactory + Clone + 'static,
+    E: BlockExecutorProvider,
+{
+    /// Executes the future on a new blocking task.
+    async fn on_blocking_task<C, F, R>(&self, c: C) -> Result<R, ValidationApiError>
+    where
+        C: FnOnce(Self) -> F,
+        F: Future<Output = Result<R, ValidationApiError>> + Send + 'static,
+        R: Send + 'static,
+    {
+        let (tx, rx) = oneshot::channel();
+        let this = self.clone();
+        let f = c(this);
+        self.inner.task_spawner.spawn_blocking(Box::pin(async move {
+            let res = f.await;
+            let _ = tx.send(res);
+        }));
+        rx.await.map_err(|_| ValidationApiError::TaskClosed)?
+    }
+
+    /// Validates the submitted payload against the bid and executes it on top of its parent.
+    pub async fn validate_builder_submission(
+        &self,
+        payload: ExecutionPayload,
+        parent_beacon_block_root: Option<B256>,
+        message: BidTrace,
+        registered_gas_limit: u64,
+    ) -> Result<SealedBlock, ValidationApiError> {
+        let block = try_into_sealed_block(payload, parent_beacon_block_root)?;
+        self.on_blocking_task(|this| async move {
+            this.validate_block(&block, &message, registered_gas_limit)?;
+            Ok(block)
+        })
+        .await
+    }
+
+    /// Validates the block against the bid and executes it on top of its parent.
+    fn validate_block(
+        &self,
+        block: &SealedBlock,
+        message: &BidTrace,
+        registered_gas_limit: u64,
+    ) -> Result<(), ValidationApiError> {
+        validate_message_against_block(block, message)?;
+
+        let provider = &self.inner.provider;
+        let consensus = &self.inner.consensus;
+
+        let parent = provider
+            .sealed_header_by_id(block.parent_hash.into())?
+            .ok_or(ValidationApiError::MissingParent(block.parent_hash))?;
+        validate_gas_limit(registered_gas_limit, &parent, &block.header)?;
+
+        consensus.validate_header(&block.header)?;
+        consensus.validate_header_against_parent(&block.header, &parent)?;
+        consensus.validate_block_pre_execution(block)?;
+
+        let parent_td = provider
+            .header_td(&block.parent_hash)?
+            .ok_or(ValidationApiError::MissingParent(block.parent_hash))?;
+        let block =
+            block.clone().unseal().with_recovered_senders().ok_or_else(|| {
+                BlockExecutionError::from(BlockValidationError::SenderRecoveryError)
+            })?;
+
+        let state_provider = provider.state_by_block_hash(block.parent_hash)?;
+        let output = self
+            .inner
+            .executor_provider
+            .executor(StateProviderDatabase::new(&state_provider))
+            .execute((&block, parent_td + block.difficulty).into())?;
+
+        consensus.validate_block_post_execution(
+            &block,
+            PostExecutionInput::new(&output.receipts, &output.requests),
+        )?;
+
+        validate_proposer_payment(&block, message, &output.receipts, &output.state)?;
+
+        let state_root = state_provider.state_root(&output.state)?;
+        if state_root != block.state_root {
+            return Err(ConsensusError::BodyStateRootDiff(
+                GotExpected { got: state_root, expected: block.state_root }.into(),
+            )
+            .into())
+        }
+
+        Ok(())
+    }
+}
+
+#[async_trait]
+impl<Provider, E> BlockSubmissionValidationApiServer for ValidationApi<Provider, E>
+where
+    Provider: BlockReaderIdExt + StateProviderFactory + Clone + 'static,
+    E: BlockExecutorProvider,
+{
+    /// Handler for `flashbots_validateBuilderSubmissionV1`
+    async fn validate_builder_submission_v1(
+        &self,
+        request: BuilderBlockValidationRequest,
+    ) -> RpcResult<()> {
+        let BuilderBlockValidationRequest { request, registered_gas_limit } = request;
+        self.validate_builder_submission(
+            request.execution_payload,
+            None,
+            request.message,
+            registered_gas_limit,
+        )
+        .await?;
+        Ok(())
+    }
+
+    /// Handler for `flashbots_validateBuilderSubmissionV2`
+    async fn validate_builder_submission_v2(
+        &self,
+        request: BuilderBlockValidationRequestV2,
+    ) -> RpcResult<()> {
+        let BuilderBlockValidationRequestV2 { request, registered_gas_limit, withdrawals_root } =
+            request;
+        let block = self
+            .validate_builder_submission(
+                request.execution_payload,
+                None,
+                request.message,
+                registered_gas_limit,
+            )
+            .await?;
+
+        let got = block.withdrawals_root.unwrap_or_default();
+        if got != withdrawals_root {
+            return Err(ValidationApiError::WithdrawalsRootMismatch(GotExpected {
+                got,
+                expected: withdrawals_root,
+            })
+            .into())
+        }
+        Ok(())
+    }
+
+    /// Handler for `flashbots_validateBuilderSubmissionV3`
+    async fn validate_builder_submission_v3(
+        &self,
+        request: BuilderBlockValidationRequestV3,
+    ) -> RpcResult<()> {
+        let BuilderBlockValidationRequestV3 {
+            message,
+            execution_payload,
+            blobs_bundle,
+            parent_beacon_block_root,
+            registered_gas_limit,
+            ..
+        } = request;
+        let block = self
+            .validate_builder_submission(
+                ExecutionPayload::V3(execution_payload),
+                Some(parent_beacon_block_root),
+                message,
+                registered_gas_limit,
+            )
+            .await?;
+
+        validate_blobs_bundle(&block, blobs_bundle)?;
+        Ok(())
+    }
+}
+
+impl<Provider, E> Clone for ValidationApi<Provider, E> {
+    fn clone(&self) -> Self {
+        Self { inner: Arc::clone(&self.inner) }
+    }
+}
+
+impl<Provider, E> std::fmt::Debug for ValidationApi<Provider, E> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("ValidationApi").finish_non_exhaustive()
+    }
+}
+
+struct ValidationApiInner<Provider, E> {
+    /// The provider that can interact with the chain.
+    provider: Provider,
+    /// Validates the submitted blocks.
+    consensus: Arc<dyn Consensus>,
+    /// Executes the submitted blocks.
+    executor_provider: E,
+    /// The type that can spawn tasks which would otherwise block.
+    task_spawner: Box<dyn TaskSpawner>,
+}
+
+/// Checks that the block is the one the bid was made for.
+fn validate_message_against_block(
+    block: &SealedBlock,
+    message: &BidTrace,
+) -> Result<(), ValidationApiError> {
+    if block.hash() != message.block_hash {
+        return Err(ValidationApiError::BlockHashMismatch(GotExpected {
+            got: block.hash(),
+            expected: message.block_hash,
+        }))
+    }
+    if block.parent_hash != message.parent_hash {
+        return Err(ValidationApiError::ParentHashMismatch(GotExpected {
+            got: block.parent_hash,
+            expected: message.parent_hash,
+        }))
+    }
+    if block.gas_limit != message.gas_limit {
+        return Err(ValidationApiError::GasLimitMismatch(GotExpected {
+            got: block.gas_limit,
+            expected: message.gas_limit,
+        }))
+    }
+    if block.gas_used != message.gas_used {
+        return Err(ValidationApiError::GasUsedMismatch(GotExpected {
+            got: block.gas_used,
+            expected: message.gas_used,
+        }))
+    }
+    Ok(())
+}
+
+/// Checks that the gas limit moves as close to the registered gas limit as the parent's gas limit
+/// allows.
+fn validate_gas_limit(
+    registered_gas_limit: u64,
+    parent: &SealedHeader,
+    header: &SealedHeader,
+) -> Result<(), ValidationApiError> {
+    // the bounds are empty for parent gas limits below the divisor, in which case the gas limit
+    // can't change
+    let max_delta = (parent.gas_limit / GAS_LIMIT_BOUND_DIVISOR).saturating_sub(1);
+    let expected = if registered_gas_limit > parent.gas_limit {
+        registered_gas_limit.min(parent.gas_limit.saturating_add(max_delta))
+    } else {
+        registered_gas_limit.max(parent.gas_limit - max_delta)
+    };
+
+    if header.gas_limit != expected {
+        return Err(ValidationApiError::GasLimitMismatch(GotExpected {
+            got: header.gas_limit,
+            expected,
+        }))
+    }
+    Ok(())
+}
+
+/// Checks that the proposer is paid the value of the bid.
+///
+/// The payment is either the balance increase of the proposer's fee recipient, or the last
+/// transaction of the block, which must transfer exactly the value of the bid to the fee
+/// recipient.
+fn validate_proposer_payment(
+    block: &BlockWithSenders,
+    message: &BidTrace,
+    receipts: &[Receipt],
+    state: &reth_revm::db::BundleState,
+) -> Result<(), ValidationApiError> {
+    let (mut balance_before, balance_after) = state
+        .account(&message.proposer_fee_recipient)
+        .map(|account| {
+            (
+                account.original_info.as_ref().map(|info| info.balance).unwrap_or_default(),
+                account.info.as_ref().map(|info| info.balance).unwrap_or_default(),
+            )
+        })
+        .unwrap_or_default();
+    // withdrawals are not part of the payment
+    for withdrawal in block.withdrawals.iter().flatten() {
+        if withdrawal.address == message.proposer_fee_recipient {
+            balance_before += withdrawal.amount_wei();
+        }
+    }
+    if balance_after >= balance_before.saturating_add(message.value) {
+        return Ok(())
+    }
+
+    let (Some(receipt), Some(tx)) = (receipts.last(), block.body.last()) else {
+        return Err(ValidationApiError::ProposerPayment("block has no payment transaction"))
+    };
+    if !receipt.success {
+        return Err(ValidationApiError::ProposerPayment("payment transaction reverted"))
+    }
+    if tx.to() != Some(message.proposer_fee_recipient) {
+        return Err(ValidationApiError::ProposerPayment(
+            "payment transaction is not sent to the fee recipient",
+        ))
+    }
+    if tx.value() != message.value {
+        return Err(ValidationApiError::ProposerPayment(
+            "payment transaction value does not match the bid",
+        ))
+    }
+    if !tx.input().is_empty() {
+        return Err(ValidationApiError::ProposerPayment("payment transaction has calldata"))
+    }
+    if tx.effective_tip_per_gas(block.base_fee_per_gas).unwrap_or_default() != 0 {
+        return Err(ValidationApiError::ProposerPayment("payment transaction pays a priority fee"))
+    }
+    Ok(())
+}
+
+/// Checks that the blobs bundle contains the blobs of the block's blob transactions.
+fn validate_blobs_bundle(
+    block: &SealedBlock,
+    blobs_bundle: BlobsBundleV1,
+) -> Result<(), ValidationApiError> {
+    let versioned_hashes = block.blob_versioned_hashes_iter().copied().collect::<Vec<_>>();
+    let BlobsBundleV1 { commitments, proofs, blobs } = blobs_bundle;
+    let sidecar = BlobTransactionSidecar { blobs, commitments, proofs };
+    sidecar.validate(&versioned_hashes, EnvKzgSettings::Default.get())?;
+    Ok(())
+}
+
+/// Errors returned by the [`ValidationApi`].
+#[derive(Debug, thiserror::Error)]
+pub enum ValidationApiError {
+    /// The block hash does not match the bid.
+    #[error("block hash mismatch: {0}")]
+    BlockHashMismatch(GotExpected<B256>),
+    /// The parent hash does not match the bid.
+    #[error("parent hash mismatch: {0}")]
+    ParentHashMismatch(GotExpected<B256>),
+    /// The gas limit does not match the bid or the registered gas limit.
+    #[error("gas limit mismatch: {0}")]
+    GasLimitMismatch(GotExpected<u64>),
+    /// The gas used does not match the bid.
+    #[error("gas used mismatch: {0}")]
+    GasUsedMismatch(GotExpected<u64>),
+    /// The withdrawals root does not match the request.
+    #[error("withdrawals root mismatch: {0}")]
+    WithdrawalsRootMismatch(GotExpected<B256>),
+    /// The parent block is not known.
+    #[error("parent block {0} not found")]
+    MissingParent(B256),
+    /// The proposer is not paid the value of the bid.
+    #[error("invalid proposer payment: {0}")]
+    ProposerPayment(&'static str),
+    /// The blobs bundle does not match the blob transactions.
+    #[error("invalid blobs bundle: {0}")]
+    Blobs(#[from] BlobTransactionValidationError),
+    /// The payload can't be converted to a block.
+    #[error(transparent)]
+    Payload(#[from] PayloadError),
+    /// The block is invalid.
+    #[error(transparent)]
+    Consensus(#[from] ConsensusError),
+    /// The block could not be executed.
+    #[error(transparent)]
+    Execution(#[from] BlockExecutionError),
+    /// Failed to read the chain.
+    #[error(transparent)]
+    Provider(#[from] ProviderError),
+    /// The validation task was dropped.
+    #[error("validation task closed")]
+    TaskClosed,
+}
+
+impl From<ValidationApiError> for jsonrpsee::types::ErrorObject<'static> {
+    fn from(error: ValidationApiError) -> Self {
+        match error {
+            ValidationApiError::Provider(_) | ValidationApiError::TaskClosed => {
+                internal_rpc_err(error.to_string())
+            }
+            error => invalid_params_rpc_err(error.to_string()),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use reth_primitives::{
+        Address, Block, Header, Transaction, TransactionSigned, TxEip1559, TxKind, U256,
+    };
+    use reth_revm::db::BundleState;
+
+    fn header(gas_limit: u64) -> SealedHeader {
+        Header { gas_limit, ..Default::default() }.seal_slow()
+    }
+
+    #[test]
+    fn gas_limit_moves_towards_registered_gas_limit() {
+        let parent = header(30_000_000);
+
+        // the registered gas limit is within the bounds
+        assert!(validate_gas_limit(30_000_000, &parent, &header(30_000_000)).is_ok());
+        // the gas limit can only move by parent / 1024 - 1 per block
+        assert!(validate_gas_limit(36_000_000, &parent, &header(30_029_295)).is_ok());
+        assert!(validate_gas_limit(24_000_000, &parent, &header(29_970_705)).is_ok());
+        assert!(matches!(
+            validate_gas_limit(36_000_000, &parent, &header(30_000_000)),
+            Err(ValidationApiError::GasLimitMismatch(GotExpected {
+                got: 30_000_000,
+                expected: 30_029_295
+            }))
+        ));
+
+        // the gas limit can't move if the parent gas limit is below the divisor
+        assert!(validate_gas_limit(36_000_000, &header(1000), &header(1000)).is_ok());
+        assert!(validate_gas_limit(0, &header(0), &header(0)).is_ok());
+        assert!(validate_gas_limit(u64::MAX, &header(u64::MAX), &header(u64::MAX)).is_ok());
+    }
+
+    #[test]
+    fn proposer_payment_transaction() {
+        let fee_recipient = Address::with_last_byte(1);
+        let message = BidTrace {
+            proposer_fee_recipient: fee_recipient,
+            value: U256::from(100),
+            ..Default::default()
+        };
+        let payment = |value: u64| {
+            TransactionSigned::from_transaction_and_signature(
+                Transaction::Eip1559(TxEip1559 {
+                    to: TxKind::Call(fee_recipient),
+                    value: U256::from(value),
+                    ..Default::default()
+                }),
+                Default::default(),
+            )
+        };
+        let block = |tx: TransactionSigned| {
+            Block { body: vec![tx], ..Default::default() }
+                .with_senders_unchecked(vec![Address::ZERO])
+        };
+        let receipt = Receipt { success: true, ..Default::default() };
+        let state = BundleState::default();
+
+        assert!(validate_proposer_payment(
+            &block(payment(100)),
+            &message,
+            std::slice::from_ref(&receipt),
+            &state
+        )
+        .is_ok());
+        assert!(matches!(
+            validate_proposer_payment(
+                &block(payment(99)),
+                &message,
+                std::slice::from_ref(&receipt),
+                &state
+            ),
+            Err(ValidationApiError::ProposerPayment(_))
+        ));
+        let reverted = Receipt { success: false, ..receipt };
+        assert!(matches!(
+            validate_proposer_payment(&block(payment(100)), &message, &[reverted], &state),
+            Err(ValidationApiError::ProposerPayment(_))
+        ));
+    }
+}
@@ -390,12 +390,18 @@ impl<DB: Database> StageSet<DB> for HistoryIndexingStages {
             .add_stage(IndexStorageHistoryStage::new(
                 self.stages_config.index_storage_history,
                 self.stages_config.etl.clone(),
-                self.prune_modes.account_history,
+                // History of the filtered addresses needs to be indexed, so it's left to the
+                // pruner
+                self.prune_modes
+                    .storage_history
+                    .filter(|_| self.prune_modes.storage_history_filter.is_empty()),
             ))
             .add_stage(IndexAccountHistoryStage::new(
                 self.stages_config.index_account_history,
                 self.stages_config.etl.clone(),
-                self.prune_modes.storage_history,
+                self.prune_modes
+                    .account_history
+                    .filter(|_| self.prune_modes.account_history_filter.is_empty()),
             ))
     }
 }
@@ -151,7 +151,9 @@ impl<DB: Database> Stage<DB> for AccountHashingStage {
 
             let mut accounts_cursor = tx.cursor_read::<RawTable<tables::PlainAccountState>>()?;
             let mut collector =
-                Collector::new(self.etl_config.file_size, self.etl_config.dir.clone());
+                Collector::new(self.etl_config.file_size, self.etl_config.dir.clone())
+                    .with_workers(self.etl_config.workers)
+                    .with_compression(self.etl_config.compression);
             let mut channels = Vec::with_capacity(MAXIMUM_CHANNELS);
 
             // channels used to return result of account hashing
@@ -91,7 +91,9 @@ impl<DB: Database> Stage<DB> for StorageHashingStage {
 
             let mut storage_cursor = tx.cursor_read::<tables::PlainStorageState>()?;
             let mut collector =
-                Collector::new(self.etl_config.file_size, self.etl_config.dir.clone());
+                Collector::new(self.etl_config.file_size, self.etl_config.dir.clone())
+                    .with_workers(self.etl_config.workers)
+                    .with_compression(self.etl_config.compression);
             let mut channels = Vec::with_capacity(MAXIMUM_CHANNELS);
 
             for chunk in &storage_cursor.walk(None)?.chunks(WORKER_CHUNK_SIZE) {
@@ -78,8 +78,12 @@ where
             tip,
             consensus,
             sync_gap: None,
-            hash_collector: Collector::new(etl_config.file_size / 2, etl_config.dir.clone()),
-            header_collector: Collector::new(etl_config.file_size / 2, etl_config.dir),
+            hash_collector: Collector::new(etl_config.file_size / 2, etl_config.dir.clone())
+                .with_workers(etl_config.workers)
+                .with_compression(etl_config.compression),
+            header_collector: Collector::new(etl_config.file_size / 2, etl_config.dir)
+                .with_workers(etl_config.workers)
+                .with_compression(etl_config.compression),
             is_etl_ready: false,
         }
     }
@@ -106,7 +106,9 @@ impl<DB: Database> Stage<DB> for TransactionLookupStage {
 
         // 500MB temporary files
         let mut hash_collector: Collector<TxHash, TxNumber> =
-            Collector::new(self.etl_config.file_size, self.etl_config.dir.clone());
+            Collector::new(self.etl_config.file_size, self.etl_config.dir.clone())
+                .with_workers(self.etl_config.workers)
+                .with_compression(self.etl_config.compression);
 
         info!(
             target: "sync::stages::transaction_lookup",
@@ -50,7 +50,9 @@ where
 {
     let mut changeset_cursor = provider.tx_ref().cursor_read::<CS>()?;
 
-    let mut collector = Collector::new(etl_config.file_size, etl_config.dir.clone());
+    let mut collector = Collector::new(etl_config.file_size, etl_config.dir.clone())
+        .with_workers(etl_config.workers)
+        .with_compression(etl_config.compression);
     let mut cache: HashMap<P, Vec<u64>> = HashMap::new();
 
     let mut collect = |cache: &HashMap<P, Vec<u64>>| {
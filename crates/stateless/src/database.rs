@@ -0,0 +1,88 @@
+use alloy_rlp::Decodable;
+use reth_primitives::{keccak256, Address, Bytes, B256, KECCAK_EMPTY, U256};
+use reth_revm::{
+    primitives::{AccountInfo, Bytecode},
+    Database,
+};
+use reth_storage_errors::provider::ProviderError;
+use reth_trie::TrieAccount;
+use reth_trie_sparse::{SparseStateTrie, SparseTrieError};
+use std::collections::HashMap;
+
+/// A [`Database`] serving the parent state of a block from an execution witness.
+///
+/// Accounts and storage slots are read from the sparse trie revealed from the witness, bytecodes
+/// from the witness preimages. Reading state that isn't part of the witness is an error.
+#[derive(Debug)]
+pub struct WitnessDatabase<'a> {
+    trie: &'a SparseStateTrie,
+    witness: &'a HashMap<B256, Bytes>,
+    block_hashes: HashMap<u64, B256>,
+}
+
+impl<'a> WitnessDatabase<'a> {
+    /// Creates a new database from the revealed trie, the witness preimages keyed by their hash
+    /// and the hashes of the ancestor blocks.
+    pub const fn new(
+        trie: &'a SparseStateTrie,
+        witness: &'a HashMap<B256, Bytes>,
+        block_hashes: HashMap<u64, B256>,
+    ) -> Self {
+        Self { trie, witness, block_hashes }
+    }
+
+    /// Returns the account with the given hashed address.
+    fn account(&self, hashed_address: &B256) -> Result<Option<TrieAccount>, ProviderError> {
+        let Some(value) = self.trie.find_account_value(hashed_address).map_err(witness_error)?
+        else {
+            return Ok(None)
+        };
+        Ok(Some(TrieAccount::decode(&mut &value[..])?))
+    }
+}
+
+impl Database for WitnessDatabase<'_> {
+    type Error = ProviderError;
+
+    fn basic(&mut self, address: Address) -> Result<Option<AccountInfo>, Self::Error> {
+        Ok(self.account(&keccak256(address))?.map(|account| AccountInfo {
+            balance: account.balance,
+            nonce: account.nonce,
+            code_hash: account.code_hash,
+            code: None,
+        }))
+    }
+
+    fn code_by_hash(&mut self, code_hash: B256) -> Result<Bytecode, Self::Error> {
+        if code_hash == KECCAK_EMPTY {
+            return Ok(Bytecode::default())
+        }
+        self.witness.get(&code_hash).map(|code| Bytecode::new_raw(code.clone())).ok_or_else(|| {
+            ProviderError::TrieWitnessError(format!("bytecode {code_hash} is missing"))
+        })
+    }
+
+    fn storage(&mut self, address: Address, index: U256) -> Result<U256, Self::Error> {
+        let hashed_address = keccak256(address);
+        // The storage trie of a non-existent account is not part of the witness.
+        if self.account(&hashed_address)?.is_none() {
+            return Ok(U256::ZERO)
+        }
+
+        let hashed_slot = keccak256(B256::from(index));
+        let value =
+            self.trie.find_storage_value(&hashed_address, &hashed_slot).map_err(witness_error)?;
+        Ok(value.map(|value| U256::decode(&mut &value[..])).transpose()?.unwrap_or_default())
+    }
+
+    fn block_hash(&mut self, number: u64) -> Result<B256, Self::Error> {
+        self.block_hashes
+            .get(&number)
+            .copied()
+            .ok_or_else(|| ProviderError::HeaderNotFound(number.into()))
+    }
+}
+
+fn witness_error(error: SparseTrieError) -> ProviderError {
+    ProviderError::TrieWitnessError(error.to_string())
+}
@@ -0,0 +1,27 @@
+use reth_consensus::ConsensusError;
+use reth_evm::execute::BlockExecutionError;
+use reth_primitives::{GotExpected, B256};
+use reth_trie_sparse::SparseTrieError;
+
+/// Stateless block verification error.
+#[derive(Debug, thiserror::Error)]
+pub enum StatelessError {
+    /// The header of the parent block is not part of the witness.
+    #[error("parent header {0} is missing from the witness")]
+    MissingParentHeader(B256),
+    /// A preimage of the witness could not be decoded.
+    #[error(transparent)]
+    Rlp(#[from] alloy_rlp::Error),
+    /// The witness is missing trie nodes or contains invalid ones.
+    #[error("invalid witness: {0}")]
+    Witness(#[from] SparseTrieError),
+    /// The block failed consensus validation.
+    #[error(transparent)]
+    Consensus(#[from] ConsensusError),
+    /// The block failed to execute.
+    #[error(transparent)]
+    Execution(#[from] BlockExecutionError),
+    /// The computed post-state root doesn't match the one of the block.
+    #[error("state root mismatch: {0}")]
+    StateRootMismatch(GotExpected<B256>),
+}
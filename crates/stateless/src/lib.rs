@@ -0,0 +1,21 @@
+//! Stateless block verification.
+//!
+//! A block is executed on top of the parent state provided by an execution witness, as returned
+//! by `debug_executionWitness`, without access to a database. The post-state root is then computed
+//! by applying the changes to a sparse trie revealed from the witness.
+
+#![doc(
+    html_logo_url = "https://raw.githubusercontent.com/paradigmxyz/reth/main/assets/reth-docs.png",
+    html_favicon_url = "https://avatars0.githubusercontent.com/u/97369466?s=256",
+    issue_tracker_base_url = "https://github.com/paradigmxyz/reth/issues/"
+)]
+#![cfg_attr(docsrs, feature(doc_cfg, doc_auto_cfg))]
+
+mod database;
+pub use database::WitnessDatabase;
+
+mod error;
+pub use error::StatelessError;
+
+mod verify;
+pub use verify::verify_witness;
@@ -0,0 +1,320 @@
+use crate::{StatelessError, WitnessDatabase};
+use alloy_rlp::{encode_fixed_size, Decodable};
+use reth_consensus::{Consensus, PostExecutionInput};
+use reth_evm::execute::{BlockExecutionOutput, BlockExecutorProvider, Executor};
+use reth_primitives::{
+    constants::EMPTY_ROOT_HASH, keccak256, Bytes, GotExpected, Header, Receipt,
+    SealedBlockWithSenders, SealedHeader, B256, U256,
+};
+use reth_trie::{HashedPostState, Nibbles, TrieAccount};
+use reth_trie_sparse::{SparseStateTrie, SparseTrieError};
+use std::collections::HashMap;
+
+/// Executes the block on top of the parent state provided by the execution witness and verifies
+/// the post-state root.
+///
+/// The witness consists of the preimages returned by `debug_executionWitness`: the trie nodes of
+/// the accessed state, the bytecodes of the executed contracts and the headers of the ancestors of
+/// the block, down to the oldest one whose hash is accessed during execution. The preimages are
+/// keyed by their hash here, the keys of the RPC response are not trusted.
+///
+/// The block is validated against its parent with the given consensus before execution, and the
+/// execution output is validated after. Returns the execution output of the verified block.
+pub fn verify_witness<E>(
+    executor_provider: &E,
+    consensus: &dyn Consensus,
+    block: &SealedBlockWithSenders,
+    total_difficulty: U256,
+    witness: impl IntoIterator<Item = Bytes>,
+) -> Result<BlockExecutionOutput<Receipt>, StatelessError>
+where
+    E: BlockExecutorProvider,
+{
+    let witness = witness
+        .into_iter()
+        .map(|preimage| (keccak256(&preimage), preimage))
+        .collect::<HashMap<_, _>>();
+
+    let parent = witness
+        .get(&block.parent_hash)
+        .ok_or(StatelessError::MissingParentHeader(block.parent_hash))?;
+    let parent = SealedHeader::new(Header::decode(&mut &parent[..])?, block.parent_hash);
+    consensus.validate_header(&block.header)?;
+    consensus.validate_header_against_parent(&block.header, &parent)?;
+    consensus.validate_block_pre_execution(&block.block)?;
+
+    // The ancestor headers are linked by their parent hashes, starting from the parent.
+    let mut block_hashes = HashMap::default();
+    let mut hash = block.parent_hash;
+    while let Some(header) = witness.get(&hash) {
+        let Ok(header) = Header::decode(&mut &header[..]) else { break };
+        block_hashes.insert(header.number, hash);
+        hash = header.parent_hash;
+    }
+
+    let mut trie = SparseStateTrie::default();
+    trie.reveal_witness(parent.state_root, &witness)?;
+
+    let block_with_senders = block.clone().unseal();
+    let output = executor_provider
+        .executor(WitnessDatabase::new(&trie, &witness, block_hashes))
+        .execute((&block_with_senders, total_difficulty).into())?;
+    consensus.validate_block_post_execution(
+        &block_with_senders,
+        PostExecutionInput::new(&output.receipts, &output.requests),
+    )?;
+
+    let state_root =
+        state_root(&mut trie, HashedPostState::from_bundle_state(&output.state.state))?;
+    if state_root != block.state_root {
+        return Err(StatelessError::StateRootMismatch(GotExpected {
+            got: state_root,
+            expected: block.state_root,
+        }))
+    }
+
+    Ok(output)
+}
+
+/// Applies the changes to the revealed trie and returns the post-state root.
+fn state_root(
+    trie: &mut SparseStateTrie,
+    hashed_state: HashedPostState,
+) -> Result<B256, StatelessError> {
+    let mut storage_roots = HashMap::with_capacity(hashed_state.storages.len());
+    for (hashed_address, storage) in hashed_state.storages {
+        // The storage of an account that didn't exist in the parent state is empty.
+        if storage.wiped || trie.find_account_value(&hashed_address)?.is_none() {
+            trie.wipe_storage(hashed_address);
+        }
+        for (hashed_slot, value) in storage.storage {
+            let path = Nibbles::unpack(hashed_slot);
+            if value.is_zero() {
+                trie.remove_storage_leaf(hashed_address, &path)?;
+            } else {
+                trie.update_storage_leaf(hashed_address, path, encode_fixed_size(&value).to_vec())?;
+            }
+        }
+        let storage_root = trie.storage_root(hashed_address).ok_or(SparseTrieError::Blind)?;
+        storage_roots.insert(hashed_address, storage_root);
+    }
+
+    for (hashed_address, account) in &hashed_state.accounts {
+        let path = Nibbles::unpack(hashed_address);
+        match account {
+            Some(account) => {
+                let storage_root = match storage_roots.get(hashed_address) {
+                    Some(storage_root) => *storage_root,
+                    None => trie
+                        .find_account_value(hashed_address)?
+                        .map(|value| TrieAccount::decode(&mut &value[..]))
+                        .transpose()?
+                        .map_or(EMPTY_ROOT_HASH, |account| account.storage_root),
+                };
+                let value = alloy_rlp::encode(TrieAccount::from((*account, storage_root)));
+                trie.update_account_leaf(path, value)?;
+            }
+            None => trie.remove_account_leaf(&path)?,
+        }
+    }
+
+    // Accounts with changed storage are normally part of the changed accounts, otherwise only
+    // the storage root of the existing account is updated.
+    for (hashed_address, storage_root) in storage_roots {
+        if hashed_state.accounts.contains_key(&hashed_address) {
+            continue
+        }
+        let Some(value) = trie.find_account_value(&hashed_address)? else { continue };
+        let mut account = TrieAccount::decode(&mut &value[..])?;
+        account.storage_root = storage_root;
+        trie.update_account_leaf(Nibbles::unpack(hashed_address), alloy_rlp::encode(account))?;
+    }
+
+    // The account trie is revealed from the parent state root, it's never blind here.
+    Ok(trie.root().ok_or(SparseTrieError::Blind)?)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use reth_chainspec::ChainSpecBuilder;
+    use reth_consensus::test_utils::TestConsensus;
+    use reth_evm_ethereum::execute::EthExecutorProvider;
+    use reth_primitives::{
+        constants::ETHEREUM_BLOCK_GAS_LIMIT, Account, Address, BlockBody, SealedBlock, Signature,
+        StorageEntry, Transaction, TransactionSigned, TxKind, TxLegacy, Withdrawal, Withdrawals,
+    };
+    use reth_provider::{test_utils::create_test_provider_factory, HashingWriter, TrieWriter};
+    use reth_trie::{witness::TrieWitness, HashedStorage, StateRoot};
+    use reth_trie_db::{DatabaseStateRoot, DatabaseTrieWitness};
+    use std::sync::Arc;
+
+    /// Stores one into slot zero.
+    const CONTRACT_CODE: [u8; 5] = [0x60, 0x01, 0x60, 0x00, 0x55];
+
+    /// Creates a block that calls a contract writing to its storage, with a withdrawal to a new
+    /// account, and the witness of its parent state.
+    fn block_with_witness() -> (SealedBlockWithSenders, Vec<Bytes>) {
+        let sender = Address::random();
+        let contract = Address::random();
+        let beneficiary = Address::random();
+        let recipient = Address::random();
+
+        let code = Bytes::from_static(&CONTRACT_CODE);
+        let accounts = [
+            (sender, Account { balance: U256::from(1), ..Default::default() }),
+            (contract, Account { bytecode_hash: Some(keccak256(&code)), ..Default::default() }),
+            (beneficiary, Account { balance: U256::from(1), ..Default::default() }),
+        ];
+        let contract_storage = StorageEntry { key: B256::with_last_byte(1), value: U256::from(1) };
+
+        let factory = create_test_provider_factory();
+        let provider_rw = factory.provider_rw().unwrap();
+        provider_rw
+            .insert_account_for_hashing(
+                accounts.iter().map(|(address, account)| (*address, Some(*account))),
+            )
+            .unwrap();
+        provider_rw.insert_storage_for_hashing([(contract, [contract_storage])]).unwrap();
+        let (parent_state_root, updates) =
+            StateRoot::from_tx(provider_rw.tx_ref()).root_with_updates().unwrap();
+        provider_rw.write_trie_updates(&updates).unwrap();
+        provider_rw.commit().unwrap();
+
+        // The sender pays no gas, the beneficiary is touched without changes.
+        let mut hashed_state = HashedPostState::default();
+        for (address, mut account) in accounts {
+            if address == sender {
+                account.nonce += 1;
+            }
+            hashed_state.accounts.insert(keccak256(address), Some(account));
+        }
+        hashed_state.accounts.insert(
+            keccak256(recipient),
+            Some(Account { balance: U256::from(1_000_000_000), ..Default::default() }),
+        );
+        let mut contract_hashed_storage = HashedStorage::new(false);
+        contract_hashed_storage.storage.insert(keccak256(B256::ZERO), U256::from(1));
+        hashed_state.storages.insert(keccak256(contract), contract_hashed_storage);
+
+        let provider = factory.provider().unwrap();
+        let state_root =
+            StateRoot::overlay_root(provider.tx_ref(), hashed_state.clone(), Default::default())
+                .unwrap();
+        let witness = TrieWitness::overlay_witness(
+            provider.tx_ref(),
+            HashedPostState::default(),
+            hashed_state,
+        )
+        .unwrap();
+
+        let parent = Header { state_root: parent_state_root, ..Default::default() }.seal_slow();
+        let header = Header {
+            parent_hash: parent.hash(),
+            number: 1,
+            timestamp: 1,
+            beneficiary,
+            gas_limit: ETHEREUM_BLOCK_GAS_LIMIT,
+            base_fee_per_gas: Some(0),
+            state_root,
+            ..Default::default()
+        };
+        let transaction = TransactionSigned::from_transaction_and_signature(
+            Transaction::Legacy(TxLegacy {
+                chain_id: Some(1),
+                gas_limit: 100_000,
+                to: TxKind::Call(contract),
+                ..Default::default()
+            }),
+            Signature::default(),
+        );
+        let withdrawal = Withdrawal { address: recipient, amount: 1, ..Default::default() };
+        let block = SealedBlock::new(
+            header.seal_slow(),
+            BlockBody {
+                transactions: vec![transaction],
+                withdrawals: Some(Withdrawals::new(vec![withdrawal])),
+                ..Default::default()
+            },
+        );
+        let block = SealedBlockWithSenders::new(block, vec![sender]).unwrap();
+
+        let witness = witness
+            .into_values()
+            .chain([code, alloy_rlp::encode(parent.header()).into()])
+            .collect();
+        (block, witness)
+    }
+
+    fn executor_provider() -> EthExecutorProvider {
+        EthExecutorProvider::ethereum(Arc::new(
+            ChainSpecBuilder::mainnet().shanghai_activated().build(),
+        ))
+    }
+
+    #[test]
+    fn verify_witness_block() {
+        let (block, witness) = block_with_witness();
+        let output = verify_witness(
+            &executor_provider(),
+            &TestConsensus::default(),
+            &block,
+            U256::ZERO,
+            witness,
+        )
+        .unwrap();
+        assert_eq!(output.receipts.len(), 1);
+        assert!(output.receipts[0].success);
+    }
+
+    #[test]
+    fn verify_witness_state_root_mismatch() {
+        let (mut block, witness) = block_with_witness();
+        let expected = B256::random();
+        block.block.header =
+            Header { state_root: expected, ..block.header.clone().unseal() }.seal_slow();
+
+        let result = verify_witness(
+            &executor_provider(),
+            &TestConsensus::default(),
+            &block,
+            U256::ZERO,
+            witness,
+        );
+        assert!(matches!(
+            result,
+            Err(StatelessError::StateRootMismatch(GotExpected { expected: e, .. })) if e == expected
+        ));
+    }
+
+    #[test]
+    fn verify_witness_missing_preimages() {
+        let (block, witness) = block_with_witness();
+
+        // Without the parent header.
+        let without_parent =
+            witness.iter().filter(|preimage| keccak256(preimage) != block.parent_hash).cloned();
+        let result = verify_witness(
+            &executor_provider(),
+            &TestConsensus::default(),
+            &block,
+            U256::ZERO,
+            without_parent,
+        );
+        assert!(matches!(
+            result,
+            Err(StatelessError::MissingParentHeader(hash)) if hash == block.parent_hash
+        ));
+
+        // Without the contract code.
+        let without_code = witness.into_iter().filter(|preimage| preimage[..] != CONTRACT_CODE);
+        let result = verify_witness(
+            &executor_provider(),
+            &TestConsensus::default(),
+            &block,
+            U256::ZERO,
+            without_code,
+        );
+        assert!(matches!(result, Err(StatelessError::Execution(_))));
+    }
+}
@@ -0,0 +1,51 @@
+use crate::segments::Segment;
+use alloy_primitives::BlockNumber;
+use reth_db::tables;
+use reth_db_api::{cursor::DbCursorRO, database::Database, transaction::DbTx};
+use reth_provider::{
+    providers::{StaticFileProvider, StaticFileWriter},
+    DatabaseProviderRO,
+};
+use reth_static_file_types::StaticFileSegment;
+use reth_storage_errors::provider::ProviderResult;
+use std::ops::RangeInclusive;
+
+/// Static File segment responsible for [`StaticFileSegment::AccountChangeSets`] part of data.
+#[derive(Debug, Default)]
+pub struct AccountChangeSets;
+
+impl<DB: Database> Segment<DB> for AccountChangeSets {
+    fn segment(&self) -> StaticFileSegment {
+        StaticFileSegment::AccountChangeSets
+    }
+
+    fn copy_to_static_files(
+        &self,
+        provider: DatabaseProviderRO<DB>,
+        static_file_provider: StaticFileProvider,
+        block_range: RangeInclusive<BlockNumber>,
+    ) -> ProviderResult<()> {
+        let mut static_file_writer = static_file_provider
+            .get_writer(*block_range.start(), StaticFileSegment::AccountChangeSets)?;
+
+        let mut changesets_cursor =
+            provider.tx_ref().cursor_dup_read::<tables::AccountChangeSets>()?;
+        let mut changesets_walker = changesets_cursor.walk_range(block_range.clone())?.peekable();
+
+        for block in block_range {
+            let mut changes = Vec::new();
+            while let Some((_, change)) = changesets_walker
+                .next_if(|entry| entry.as_ref().map_or(true, |(number, _)| *number == block))
+                .transpose()?
+            {
+                changes.push(change);
+            }
+
+            let _static_file_block =
+                static_file_writer.append_account_change_set(block, changes)?;
+            debug_assert_eq!(_static_file_block, block);
+        }
+
+        Ok(())
+    }
+}
@@ -9,6 +9,12 @@ pub use headers::Headers;
 mod receipts;
 pub use receipts::Receipts;
 
+mod account_change_sets;
+pub use account_change_sets::AccountChangeSets;
+
+mod storage_change_sets;
+pub use storage_change_sets::StorageChangeSets;
+
 use alloy_primitives::BlockNumber;
 use reth_db_api::database::Database;
 use reth_provider::{providers::StaticFileProvider, DatabaseProviderRO};
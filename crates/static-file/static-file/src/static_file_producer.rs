@@ -66,12 +66,18 @@ pub struct StaticFileTargets {
     headers: Option<RangeInclusive<BlockNumber>>,
     receipts: Option<RangeInclusive<BlockNumber>>,
     transactions: Option<RangeInclusive<BlockNumber>>,
+    account_change_sets: Option<RangeInclusive<BlockNumber>>,
+    storage_change_sets: Option<RangeInclusive<BlockNumber>>,
 }
 
 impl StaticFileTargets {
     /// Returns `true` if any of the targets are [Some].
     pub const fn any(&self) -> bool {
-        self.headers.is_some() || self.receipts.is_some() || self.transactions.is_some()
+        self.headers.is_some() ||
+            self.receipts.is_some() ||
+            self.transactions.is_some() ||
+            self.account_change_sets.is_some() ||
+            self.storage_change_sets.is_some()
     }
 
     // Returns `true` if all targets are either [`None`] or has beginning of the range equal to the
@@ -81,6 +87,8 @@ impl StaticFileTargets {
             (self.headers.as_ref(), static_files.headers),
             (self.receipts.as_ref(), static_files.receipts),
             (self.transactions.as_ref(), static_files.transactions),
+            (self.account_change_sets.as_ref(), static_files.account_change_sets),
+            (self.storage_change_sets.as_ref(), static_files.storage_change_sets),
         ]
         .iter()
         .all(|(target_block_range, highest_static_fileted_block)| {
@@ -139,6 +147,12 @@ impl<DB: Database> StaticFileProducerInner<DB> {
         if let Some(block_range) = targets.receipts.clone() {
             segments.push((Box::new(segments::Receipts), block_range));
         }
+        if let Some(block_range) = targets.account_change_sets.clone() {
+            segments.push((Box::new(segments::AccountChangeSets), block_range));
+        }
+        if let Some(block_range) = targets.storage_change_sets.clone() {
+            segments.push((Box::new(segments::StorageChangeSets), block_range));
+        }
 
         segments.par_iter().try_for_each(|(segment, block_range)| -> ProviderResult<()> {
             debug!(target: "static_file", segment = %segment.segment(), ?block_range, "StaticFileProducer segment");
@@ -182,10 +196,14 @@ impl<DB: Database> StaticFileProducerInner<DB> {
             .map(|stage| provider.get_stage_checkpoint(stage).map(|c| c.map(|c| c.block_number)))
             .collect::<Result<Vec<_>, _>>()?;
 
+        // Change sets are still needed by the database to unwind blocks, so they're only moved to
+        // static files once finalized. See [`Self::get_static_file_targets`].
         let highest_static_files = HighestStaticFiles {
             headers: stages_checkpoints[0],
             receipts: stages_checkpoints[1],
             transactions: stages_checkpoints[2],
+            account_change_sets: None,
+            storage_change_sets: None,
         };
         let targets = self.get_static_file_targets(highest_static_files)?;
         self.run(targets)?;
@@ -196,13 +214,29 @@ impl<DB: Database> StaticFileProducerInner<DB> {
     /// Returns a static file targets at the provided finalized block numbers per segment.
     /// The target is determined by the check against highest `static_files` using
     /// [`reth_provider::providers::StaticFileProvider::get_highest_static_files`].
+    ///
+    /// Change set targets are additionally capped at the checkpoints of the
+    /// [`StageId::IndexAccountHistory`] and [`StageId::IndexStorageHistory`] stages, since those
+    /// read the change sets from the database.
     pub fn get_static_file_targets(
         &self,
-        finalized_block_numbers: HighestStaticFiles,
+        mut finalized_block_numbers: HighestStaticFiles,
     ) -> ProviderResult<StaticFileTargets> {
         let highest_static_files =
             self.provider_factory.static_file_provider().get_highest_static_files();
 
+        let provider = self.provider_factory.provider()?;
+        for (finalized_block_number, stage) in [
+            (&mut finalized_block_numbers.account_change_sets, StageId::IndexAccountHistory),
+            (&mut finalized_block_numbers.storage_change_sets, StageId::IndexStorageHistory),
+        ] {
+            if let Some(block_number) = finalized_block_number.as_mut() {
+                let checkpoint = provider.get_stage_checkpoint(stage)?.map(|c| c.block_number);
+                *finalized_block_number =
+                    checkpoint.map(|checkpoint| checkpoint.min(*block_number));
+            }
+        }
+
         let targets = StaticFileTargets {
             headers: finalized_block_numbers.headers.and_then(|finalized_block_number| {
                 self.get_static_file_target(highest_static_files.headers, finalized_block_number)
@@ -226,6 +260,28 @@ impl<DB: Database> StaticFileProducerInner<DB> {
                     finalized_block_number,
                 )
             }),
+            // StaticFile change sets only if they're not pruned according to the user
+            // configuration
+            account_change_sets: if self.prune_modes.account_history.is_none() {
+                finalized_block_numbers.account_change_sets.and_then(|finalized_block_number| {
+                    self.get_static_file_target(
+                        highest_static_files.account_change_sets,
+                        finalized_block_number,
+                    )
+                })
+            } else {
+                None
+            },
+            storage_change_sets: if self.prune_modes.storage_history.is_none() {
+                finalized_block_numbers.storage_change_sets.and_then(|finalized_block_number| {
+                    self.get_static_file_target(
+                        highest_static_files.storage_change_sets,
+                        finalized_block_number,
+                    )
+                })
+            } else {
+                None
+            },
         };
 
         trace!(
@@ -255,15 +311,21 @@ mod tests {
     use crate::static_file_producer::{
         StaticFileProducer, StaticFileProducerInner, StaticFileTargets,
     };
-    use alloy_primitives::{B256, U256};
+    use alloy_primitives::{Address, B256, U256};
     use assert_matches::assert_matches;
-    use reth_db::{test_utils::TempDatabase, DatabaseEnv};
-    use reth_db_api::{database::Database, transaction::DbTx};
+    use reth_db::{tables, test_utils::TempDatabase, DatabaseEnv};
+    use reth_db_api::{
+        database::Database,
+        models::{AccountBeforeTx, StorageBeforeTx},
+        transaction::{DbTx, DbTxMut},
+    };
+    use reth_primitives::{Account, StorageEntry};
     use reth_provider::{
         providers::StaticFileWriter, ProviderError, ProviderFactory, StaticFileProviderFactory,
     };
     use reth_prune_types::PruneModes;
     use reth_stages::test_utils::{StorageKind, TestStageDB};
+    use reth_stages_types::{StageCheckpoint, StageId};
     use reth_static_file_types::{HighestStaticFiles, StaticFileSegment};
     use reth_testing_utils::{
         generators,
@@ -322,6 +384,7 @@ mod tests {
                 headers: Some(1),
                 receipts: Some(1),
                 transactions: Some(1),
+                ..Default::default()
             })
             .expect("get static file targets");
         assert_eq!(
@@ -329,13 +392,20 @@ mod tests {
             StaticFileTargets {
                 headers: Some(0..=1),
                 receipts: Some(0..=1),
-                transactions: Some(0..=1)
+                transactions: Some(0..=1),
+                account_change_sets: None,
+                storage_change_sets: None,
             }
         );
         assert_matches!(static_file_producer.run(targets), Ok(_));
         assert_eq!(
             provider_factory.static_file_provider().get_highest_static_files(),
-            HighestStaticFiles { headers: Some(1), receipts: Some(1), transactions: Some(1) }
+            HighestStaticFiles {
+                headers: Some(1),
+                receipts: Some(1),
+                transactions: Some(1),
+                ..Default::default()
+            }
         );
 
         let targets = static_file_producer
@@ -343,6 +413,7 @@ mod tests {
                 headers: Some(3),
                 receipts: Some(3),
                 transactions: Some(3),
+                ..Default::default()
             })
             .expect("get static file targets");
         assert_eq!(
@@ -350,13 +421,20 @@ mod tests {
             StaticFileTargets {
                 headers: Some(2..=3),
                 receipts: Some(2..=3),
-                transactions: Some(2..=3)
+                transactions: Some(2..=3),
+                account_change_sets: None,
+                storage_change_sets: None,
             }
         );
         assert_matches!(static_file_producer.run(targets), Ok(_));
         assert_eq!(
             provider_factory.static_file_provider().get_highest_static_files(),
-            HighestStaticFiles { headers: Some(3), receipts: Some(3), transactions: Some(3) }
+            HighestStaticFiles {
+                headers: Some(3),
+                receipts: Some(3),
+                transactions: Some(3),
+                ..Default::default()
+            }
         );
 
         let targets = static_file_producer
@@ -364,6 +442,7 @@ mod tests {
                 headers: Some(4),
                 receipts: Some(4),
                 transactions: Some(4),
+                ..Default::default()
             })
             .expect("get static file targets");
         assert_eq!(
@@ -371,7 +450,9 @@ mod tests {
             StaticFileTargets {
                 headers: Some(4..=4),
                 receipts: Some(4..=4),
-                transactions: Some(4..=4)
+                transactions: Some(4..=4),
+                account_change_sets: None,
+                storage_change_sets: None,
             }
         );
         assert_matches!(
@@ -380,10 +461,81 @@ mod tests {
         );
         assert_eq!(
             provider_factory.static_file_provider().get_highest_static_files(),
-            HighestStaticFiles { headers: Some(3), receipts: Some(3), transactions: Some(3) }
+            HighestStaticFiles {
+                headers: Some(3),
+                receipts: Some(3),
+                transactions: Some(3),
+                ..Default::default()
+            }
         );
     }
 
+    #[test]
+    fn run_change_sets() {
+        let (provider_factory, _temp_static_files_dir) = setup();
+
+        let address = Address::with_last_byte(1);
+        let account = |block| Account { nonce: block, ..Default::default() };
+        let storage =
+            |block| StorageEntry::new(B256::with_last_byte(block as u8), U256::from(block));
+
+        let tx = provider_factory.db_ref().tx_mut().expect("init tx");
+        for block in 0..=3 {
+            tx.put::<tables::AccountChangeSets>(
+                block,
+                AccountBeforeTx { address, info: Some(account(block)) },
+            )
+            .expect("insert account change set");
+            tx.put::<tables::StorageChangeSets>((block, address).into(), storage(block))
+                .expect("insert storage change set");
+        }
+        for stage in [StageId::IndexAccountHistory, StageId::IndexStorageHistory] {
+            tx.put::<tables::StageCheckpoints>(stage.to_string(), StageCheckpoint::new(2))
+                .expect("insert stage checkpoint");
+        }
+        tx.commit().expect("commit tx");
+
+        let static_file_producer =
+            StaticFileProducerInner::new(provider_factory.clone(), PruneModes::default());
+
+        // Targets are capped at the history index stage checkpoints.
+        let targets = static_file_producer
+            .get_static_file_targets(HighestStaticFiles {
+                account_change_sets: Some(3),
+                storage_change_sets: Some(3),
+                ..Default::default()
+            })
+            .expect("get static file targets");
+        assert_eq!(
+            targets,
+            StaticFileTargets {
+                headers: None,
+                receipts: None,
+                transactions: None,
+                account_change_sets: Some(0..=2),
+                storage_change_sets: Some(0..=2),
+            }
+        );
+        assert_matches!(static_file_producer.run(targets), Ok(_));
+
+        let static_file_provider = provider_factory.static_file_provider();
+        for segment in [StaticFileSegment::AccountChangeSets, StaticFileSegment::StorageChangeSets]
+        {
+            assert_eq!(static_file_provider.get_highest_static_file_block(segment), Some(2));
+        }
+        for block in 0..=2 {
+            assert_eq!(
+                static_file_provider.account_change_set(block),
+                Ok(Some(vec![AccountBeforeTx { address, info: Some(account(block)) }]))
+            );
+            assert_eq!(
+                static_file_provider.storage_change_set(block),
+                Ok(Some(vec![StorageBeforeTx { address, entry: storage(block) }]))
+            );
+        }
+        assert_eq!(static_file_provider.account_change_set(3), Ok(None));
+    }
+
     /// Tests that a cloneable [`StaticFileProducer`] type is not susceptible to any race condition.
     #[test]
     fn only_one() {
@@ -408,6 +560,7 @@ mod tests {
                         headers: Some(1),
                         receipts: Some(1),
                         transactions: Some(1),
+                        ..Default::default()
                     })
                     .expect("get static file targets");
                 assert_matches!(locked_producer.run(targets.clone()), Ok(_));
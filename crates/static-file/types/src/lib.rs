@@ -32,6 +32,12 @@ pub struct HighestStaticFiles {
     /// Highest static file block of transactions, inclusive.
     /// If [`None`], no static file is available.
     pub transactions: Option<BlockNumber>,
+    /// Highest static file block of account change sets, inclusive.
+    /// If [`None`], no static file is available.
+    pub account_change_sets: Option<BlockNumber>,
+    /// Highest static file block of storage change sets, inclusive.
+    /// If [`None`], no static file is available.
+    pub storage_change_sets: Option<BlockNumber>,
 }
 
 impl HighestStaticFiles {
@@ -41,6 +47,8 @@ impl HighestStaticFiles {
             StaticFileSegment::Headers => self.headers,
             StaticFileSegment::Transactions => self.transactions,
             StaticFileSegment::Receipts => self.receipts,
+            StaticFileSegment::AccountChangeSets => self.account_change_sets,
+            StaticFileSegment::StorageChangeSets => self.storage_change_sets,
         }
     }
 
@@ -50,17 +58,37 @@ impl HighestStaticFiles {
             StaticFileSegment::Headers => &mut self.headers,
             StaticFileSegment::Transactions => &mut self.transactions,
             StaticFileSegment::Receipts => &mut self.receipts,
+            StaticFileSegment::AccountChangeSets => &mut self.account_change_sets,
+            StaticFileSegment::StorageChangeSets => &mut self.storage_change_sets,
         }
     }
 
     /// Returns the minimum block of all segments.
     pub fn min(&self) -> Option<u64> {
-        [self.headers, self.transactions, self.receipts].iter().filter_map(|&option| option).min()
+        [
+            self.headers,
+            self.transactions,
+            self.receipts,
+            self.account_change_sets,
+            self.storage_change_sets,
+        ]
+        .iter()
+        .filter_map(|&option| option)
+        .min()
     }
 
     /// Returns the maximum block of all segments.
     pub fn max(&self) -> Option<u64> {
-        [self.headers, self.transactions, self.receipts].iter().filter_map(|&option| option).max()
+        [
+            self.headers,
+            self.transactions,
+            self.receipts,
+            self.account_change_sets,
+            self.storage_change_sets,
+        ]
+        .iter()
+        .filter_map(|&option| option)
+        .max()
     }
 }
 
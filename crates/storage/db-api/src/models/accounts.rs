@@ -1,6 +1,9 @@
 //! Account related models and types.
 
-use std::ops::{Range, RangeInclusive};
+use std::{
+    cmp::Ordering,
+    ops::{Range, RangeInclusive},
+};
 
 use crate::{
     impl_fixed_arbitrary,
@@ -8,7 +11,7 @@ use crate::{
     DatabaseError,
 };
 use reth_codecs::{derive_arbitrary, Compact};
-use reth_primitives::{Account, Address, BlockNumber, Buf, StorageKey};
+use reth_primitives::{Account, Address, BlockNumber, Buf, StorageEntry, StorageKey};
 use serde::{Deserialize, Serialize};
 
 /// Account as it is saved in the database.
@@ -57,6 +60,191 @@ impl Compact for AccountBeforeTx {
     }
 }
 
+/// Storage slot as it is saved in the storage change sets static files.
+///
+/// Unlike the `StorageChangeSets` table, the [`Address`] is not part of the key and has to be
+/// stored alongside the [`StorageEntry`].
+#[derive(Debug, Default, Copy, Clone, Eq, PartialEq, Serialize)]
+pub struct StorageBeforeTx {
+    /// Address of the account the storage slot belongs to.
+    pub address: Address,
+    /// Storage slot before the transaction.
+    pub entry: StorageEntry,
+}
+
+impl Compact for StorageBeforeTx {
+    fn to_compact<B>(&self, buf: &mut B) -> usize
+    where
+        B: bytes::BufMut + AsMut<[u8]>,
+    {
+        buf.put_slice(self.address.as_slice());
+        self.entry.to_compact(buf) + 20
+    }
+
+    fn from_compact(buf: &[u8], len: usize) -> (Self, &[u8]) {
+        let address = Address::from_slice(&buf[..20]);
+        let (entry, buf) = StorageEntry::from_compact(&buf[20..], len - 20);
+        (Self { address, entry }, buf)
+    }
+}
+
+/// All account changes of a single block. A row of the account change sets static files.
+///
+/// Changes are kept sorted by [`Address`] and encoded together with the offset of every change,
+/// so [`BlockAccountChangeSet::find_compact`] can binary search a row without decoding it.
+#[derive(Debug, Default, Clone, Eq, PartialEq, Serialize)]
+pub struct BlockAccountChangeSet(pub Vec<AccountBeforeTx>);
+
+impl BlockAccountChangeSet {
+    /// Creates a new [`BlockAccountChangeSet`], sorting the changes by [`Address`].
+    pub fn new(mut changes: Vec<AccountBeforeTx>) -> Self {
+        changes.sort_unstable_by_key(|change| change.address);
+        Self(changes)
+    }
+
+    /// Finds the change of `address` in a compact encoded [`BlockAccountChangeSet`], decoding only
+    /// that change.
+    pub fn find_compact(buf: &[u8], address: Address) -> Option<AccountBeforeTx> {
+        IndexedChanges::new(buf)
+            .binary_search_by(|change| change[..20].cmp(address.as_slice()))
+            .map(|change| AccountBeforeTx::from_compact(change, change.len()).0)
+    }
+}
+
+impl Compact for BlockAccountChangeSet {
+    fn to_compact<B>(&self, buf: &mut B) -> usize
+    where
+        B: bytes::BufMut + AsMut<[u8]>,
+    {
+        debug_assert!(self.0.windows(2).all(|w| w[0].address <= w[1].address));
+        IndexedChanges::encode(&self.0, buf)
+    }
+
+    fn from_compact(buf: &[u8], len: usize) -> (Self, &[u8]) {
+        let changes = IndexedChanges::new(&buf[..len])
+            .iter()
+            .map(|change| AccountBeforeTx::from_compact(change, change.len()).0)
+            .collect();
+        (Self(changes), &buf[len..])
+    }
+}
+
+/// All storage changes of a single block. A row of the storage change sets static files.
+///
+/// Changes are kept sorted by [`Address`] and [`StorageKey`] and encoded together with the offset
+/// of every change, so [`BlockStorageChangeSet::find_compact`] can binary search a row without
+/// decoding it.
+#[derive(Debug, Default, Clone, Eq, PartialEq, Serialize)]
+pub struct BlockStorageChangeSet(pub Vec<StorageBeforeTx>);
+
+impl BlockStorageChangeSet {
+    /// Creates a new [`BlockStorageChangeSet`], sorting the changes by [`Address`] and
+    /// [`StorageKey`].
+    pub fn new(mut changes: Vec<StorageBeforeTx>) -> Self {
+        changes.sort_unstable_by_key(|change| (change.address, change.entry.key));
+        Self(changes)
+    }
+
+    /// Finds the change of the `address` storage slot `key` in a compact encoded
+    /// [`BlockStorageChangeSet`], decoding only that change.
+    pub fn find_compact(buf: &[u8], address: Address, key: StorageKey) -> Option<StorageBeforeTx> {
+        IndexedChanges::new(buf)
+            .binary_search_by(|change| {
+                change[..20]
+                    .cmp(address.as_slice())
+                    .then_with(|| change[20..52].cmp(key.as_slice()))
+            })
+            .map(|change| StorageBeforeTx::from_compact(change, change.len()).0)
+    }
+}
+
+impl Compact for BlockStorageChangeSet {
+    fn to_compact<B>(&self, buf: &mut B) -> usize
+    where
+        B: bytes::BufMut + AsMut<[u8]>,
+    {
+        debug_assert!(self
+            .0
+            .windows(2)
+            .all(|w| { (w[0].address, w[0].entry.key) <= (w[1].address, w[1].entry.key) }));
+        IndexedChanges::encode(&self.0, buf)
+    }
+
+    fn from_compact(buf: &[u8], len: usize) -> (Self, &[u8]) {
+        let changes = IndexedChanges::new(&buf[..len])
+            .iter()
+            .map(|change| StorageBeforeTx::from_compact(change, change.len()).0)
+            .collect();
+        (Self(changes), &buf[len..])
+    }
+}
+
+/// Compact encoded changes of a block change set.
+///
+/// Laid out as the number of changes, the end offset of every change and the changes themselves,
+/// all offsets being big-endian `u32`s relative to the start of the changes.
+struct IndexedChanges<'a> {
+    ends: &'a [u8],
+    changes: &'a [u8],
+}
+
+impl<'a> IndexedChanges<'a> {
+    fn encode<T: Compact, B: bytes::BufMut>(changes: &[T], buf: &mut B) -> usize {
+        let mut encoded = Vec::new();
+        let mut ends = Vec::with_capacity(changes.len());
+        for change in changes {
+            change.to_compact(&mut encoded);
+            ends.push(encoded.len() as u32);
+        }
+
+        buf.put_u32(changes.len() as u32);
+        for end in &ends {
+            buf.put_u32(*end);
+        }
+        buf.put_slice(&encoded);
+
+        4 + 4 * ends.len() + encoded.len()
+    }
+
+    fn new(buf: &'a [u8]) -> Self {
+        let len = u32::from_be_bytes(buf[..4].try_into().expect("4 bytes")) as usize;
+        let (ends, changes) = buf[4..].split_at(4 * len);
+        Self { ends, changes }
+    }
+
+    const fn len(&self) -> usize {
+        self.ends.len() / 4
+    }
+
+    fn end(&self, index: usize) -> usize {
+        u32::from_be_bytes(self.ends[4 * index..4 * index + 4].try_into().expect("4 bytes"))
+            as usize
+    }
+
+    fn get(&self, index: usize) -> &'a [u8] {
+        let start = if index == 0 { 0 } else { self.end(index - 1) };
+        &self.changes[start..self.end(index)]
+    }
+
+    fn iter(&self) -> impl Iterator<Item = &'a [u8]> + '_ {
+        (0..self.len()).map(|index| self.get(index))
+    }
+
+    fn binary_search_by(&self, f: impl Fn(&[u8]) -> Ordering) -> Option<&'a [u8]> {
+        let (mut low, mut high) = (0, self.len());
+        while low < high {
+            let mid = low + (high - low) / 2;
+            let change = self.get(mid);
+            match f(change) {
+                Ordering::Less => low = mid + 1,
+                Ordering::Greater => high = mid,
+                Ordering::Equal => return Some(change),
+            }
+        }
+        None
+    }
+}
+
 /// [`BlockNumber`] concatenated with [`Address`].
 ///
 /// Since it's used as a key, it isn't compressed when encoding it.
@@ -159,6 +347,7 @@ impl_fixed_arbitrary!((BlockNumberAddress, 28), (AddressStorageKey, 52));
 mod tests {
     use super::*;
     use rand::{thread_rng, Rng};
+    use reth_primitives::U256;
     use std::str::FromStr;
 
     #[test]
@@ -210,4 +399,85 @@ mod tests {
         let key = AddressStorageKey::arbitrary(&mut Unstructured::new(&bytes)).unwrap();
         assert_eq!(bytes, Encode::encode(key));
     }
+
+    #[test]
+    fn test_block_change_sets_compact() {
+        let address = Address::from_str("ba5e000000000000000000000000000000000000").unwrap();
+
+        let accounts = BlockAccountChangeSet(vec![
+            AccountBeforeTx { address: Address::ZERO, info: None },
+            AccountBeforeTx { address, info: Some(Account { nonce: 1, ..Default::default() }) },
+        ]);
+        let mut buf = Vec::new();
+        accounts.to_compact(&mut buf);
+        assert_eq!(BlockAccountChangeSet::from_compact(&buf, buf.len()).0, accounts);
+
+        let storages = BlockStorageChangeSet(vec![
+            StorageBeforeTx { address, entry: StorageEntry::default() },
+            StorageBeforeTx {
+                address,
+                entry: StorageEntry::new(StorageKey::with_last_byte(1), U256::from(7)),
+            },
+        ]);
+        let mut buf = Vec::new();
+        storages.to_compact(&mut buf);
+        assert_eq!(BlockStorageChangeSet::from_compact(&buf, buf.len()).0, storages);
+    }
+
+    #[test]
+    fn test_block_change_sets_find_compact() {
+        let mut rng = thread_rng();
+        let addresses = (0..100).map(|_| Address::random()).collect::<Vec<_>>();
+
+        let accounts = BlockAccountChangeSet::new(
+            addresses
+                .iter()
+                .map(|&address| AccountBeforeTx {
+                    address,
+                    info: Some(Account { nonce: rng.gen(), ..Default::default() }),
+                })
+                .collect(),
+        );
+        let mut buf = Vec::new();
+        accounts.to_compact(&mut buf);
+        for change in &accounts.0 {
+            assert_eq!(
+                BlockAccountChangeSet::find_compact(&buf, change.address).as_ref(),
+                Some(change)
+            );
+        }
+        assert_eq!(BlockAccountChangeSet::find_compact(&buf, Address::random()), None);
+
+        let storages = BlockStorageChangeSet::new(
+            addresses
+                .iter()
+                .flat_map(|&address| {
+                    (0..3u8).map(move |slot| StorageBeforeTx {
+                        address,
+                        entry: StorageEntry::new(
+                            StorageKey::with_last_byte(slot),
+                            U256::from(slot),
+                        ),
+                    })
+                })
+                .collect(),
+        );
+        let mut buf = Vec::new();
+        storages.to_compact(&mut buf);
+        for change in &storages.0 {
+            assert_eq!(
+                BlockStorageChangeSet::find_compact(&buf, change.address, change.entry.key)
+                    .as_ref(),
+                Some(change)
+            );
+        }
+        assert_eq!(
+            BlockStorageChangeSet::find_compact(&buf, addresses[0], StorageKey::with_last_byte(3)),
+            None
+        );
+
+        let mut buf = Vec::new();
+        BlockAccountChangeSet::default().to_compact(&mut buf);
+        assert_eq!(BlockAccountChangeSet::find_compact(&buf, Address::ZERO), None);
+    }
 }
@@ -0,0 +1,93 @@
+//! Bad block model.
+
+use crate::{
+    table::{Compress, Decompress},
+    DatabaseError,
+};
+use alloy_rlp::{Decodable, Encodable};
+use reth_primitives::{SealedBlock, B256};
+use serde::{Deserialize, Serialize};
+
+/// A block that failed validation, kept for debugging purposes.
+#[derive(Clone, Eq, PartialEq, Debug, Default, Serialize, Deserialize)]
+pub struct StoredBadBlock {
+    /// The invalid block.
+    pub block: SealedBlock,
+    /// The error the block failed validation with.
+    pub error: String,
+    /// The state root of the parent block, the state the block was executed on.
+    ///
+    /// This is zero if the parent block was unknown when the block was rejected.
+    pub pre_state_root: B256,
+    /// The position of the block in the order the bad blocks were saved, assigned when it's
+    /// inserted into the database.
+    pub sequence: u64,
+}
+
+impl StoredBadBlock {
+    /// Creates a new bad block, its sequence number is assigned once it's saved.
+    pub const fn new(block: SealedBlock, error: String, pre_state_root: B256) -> Self {
+        Self { block, error, pre_state_root, sequence: 0 }
+    }
+}
+
+impl Compress for StoredBadBlock {
+    type Compressed = Vec<u8>;
+
+    fn compress_to_buf<B: bytes::BufMut + AsMut<[u8]>>(self, buf: &mut B) {
+        buf.put_slice(self.pre_state_root.as_slice());
+        buf.put_u64(self.sequence);
+        self.error.encode(buf);
+        self.block.encode(buf);
+    }
+}
+
+impl Decompress for StoredBadBlock {
+    fn decompress<B: AsRef<[u8]>>(value: B) -> Result<Self, DatabaseError> {
+        let value = value.as_ref();
+        if value.len() < B256::len_bytes() + 8 {
+            return Err(DatabaseError::Decode)
+        }
+        let (pre_state_root, value) = value.split_at(B256::len_bytes());
+        let (sequence, mut value) = value.split_at(8);
+        let error = String::decode(&mut value).map_err(|_| DatabaseError::Decode)?;
+        let block = SealedBlock::decode(&mut value).map_err(|_| DatabaseError::Decode)?;
+        if !value.is_empty() {
+            return Err(DatabaseError::Decode)
+        }
+
+        Ok(Self {
+            block,
+            error,
+            pre_state_root: B256::from_slice(pre_state_root),
+            sequence: u64::from_be_bytes(sequence.try_into().map_err(|_| DatabaseError::Decode)?),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use reth_primitives::Header;
+
+    #[test]
+    fn bad_block_roundtrip() {
+        let bad_block = StoredBadBlock {
+            block: SealedBlock {
+                header: Header { number: 1, ..Default::default() }.seal_slow(),
+                ..Default::default()
+            },
+            error: "state root mismatch".to_string(),
+            pre_state_root: B256::with_last_byte(1),
+            sequence: 2,
+        };
+
+        let compressed = bad_block.clone().compress();
+        assert_eq!(StoredBadBlock::decompress(&compressed).unwrap(), bad_block);
+
+        // truncated or trailing data isn't a valid bad block
+        assert!(StoredBadBlock::decompress(&compressed[..compressed.len() - 1]).is_err());
+        assert!(StoredBadBlock::decompress([compressed.as_slice(), &[0]].concat()).is_err());
+        assert!(StoredBadBlock::decompress(&compressed[..B256::len_bytes()]).is_err());
+    }
+}
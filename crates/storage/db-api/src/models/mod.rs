@@ -12,6 +12,7 @@ use reth_trie_common::{StoredNibbles, StoredNibblesSubKey, *};
 use serde::{Deserialize, Serialize};
 
 pub mod accounts;
+pub mod bad_block;
 pub mod blocks;
 pub mod client_version;
 pub mod integer_list;
@@ -19,6 +20,7 @@ pub mod sharded_key;
 pub mod storage_sharded_key;
 
 pub use accounts::*;
+pub use bad_block::StoredBadBlock;
 pub use blocks::*;
 pub use client_version::ClientVersion;
 pub use sharded_key::ShardedKey;
@@ -217,6 +219,8 @@ impl_compression_for_compact!(
     StoredBlockWithdrawals,
     Bytecode,
     AccountBeforeTx,
+    BlockAccountChangeSet,
+    BlockStorageChangeSet,
     TransactionSignedNoHash,
     CompactU256,
     StageCheckpoint,
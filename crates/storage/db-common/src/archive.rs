@@ -0,0 +1,640 @@
+//! Portable database archives.
+//!
+//! An archive is a directory with the following layout:
+//!
+//! ```text
+//! manifest.json
+//! tables/<Table>/<chunk>.chunk
+//! static_files/<static file jar and its .conf/.off/.idx files>
+//! ```
+//!
+//! Every MDBX table is streamed from a single read-only transaction into size-bounded chunks of
+//! length-prefixed raw key/value pairs, so an archive can be created while the node is running.
+//! Static file jars are copied in the order their writer commits them in reverse (config, offsets
+//! and then data), which makes a copy of a jar that is being appended to healable on the next
+//! startup. Every file is checksummed and synced to disk, and the manifest is written last, so an
+//! interrupted export never results in an archive that can be imported.
+
+use crate::DbTool;
+use alloy_primitives::{BlockNumber, Keccak256, B256};
+use eyre::{bail, ensure, Result};
+use reth_db::{
+    tables, version::DB_VERSION, RawDupSort, RawKey, RawTable, RawValue, TableViewer, Tables,
+};
+use reth_db_api::{
+    cursor::{DbCursorRO, DbCursorRW, DbDupCursorRW},
+    database::Database,
+    table::{Decode, Decompress, DupSort, Table},
+    transaction::{DbTx, DbTxMut},
+};
+use reth_fs_util as fs;
+use reth_primitives::StaticFileSegment;
+use reth_provider::providers::StaticFileProvider;
+use reth_stages_types::StageId;
+use serde::{Deserialize, Serialize};
+use std::{
+    collections::BTreeMap,
+    fs::File,
+    io::{self, BufReader, BufWriter, Read, Write},
+    path::{Path, PathBuf},
+};
+use tracing::info;
+
+/// Version of the archive format.
+pub const ARCHIVE_VERSION: u32 = 1;
+
+/// Default maximum size of a table chunk in bytes.
+pub const DEFAULT_CHUNK_SIZE: u64 = 256 * 1024 * 1024;
+
+/// Name of the manifest file of an archive.
+pub const MANIFEST_FILE_NAME: &str = "manifest.json";
+
+/// Directory of the archive holding the table chunks.
+const TABLES_DIR: &str = "tables";
+
+/// Directory of the archive holding the static files.
+const STATIC_FILES_DIR: &str = "static_files";
+
+/// Extensions of the static file jar companion files, in the order they are copied before the
+/// data file.
+const STATIC_FILE_EXTENSIONS: [&str; 3] = ["conf", "off", "idx"];
+
+/// Describes the contents of an archive.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ArchiveManifest {
+    /// Version of the archive format.
+    pub version: u32,
+    /// Version of the database the archive was created from.
+    pub db_version: u64,
+    /// Genesis hash of the chain the archive was created from.
+    pub genesis_hash: B256,
+    /// Stage checkpoints at the time of the export, keyed by stage id.
+    pub stage_checkpoints: BTreeMap<String, BlockNumber>,
+    /// Archived database tables.
+    pub tables: Vec<ArchiveTable>,
+    /// Archived static files.
+    pub static_files: Vec<ArchiveFile>,
+}
+
+/// An archived database table.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ArchiveTable {
+    /// Name of the table.
+    pub name: String,
+    /// Total number of entries in the table.
+    pub entries: u64,
+    /// Chunks holding the table entries, in key order.
+    pub chunks: Vec<ArchiveFile>,
+}
+
+/// A file of an archive.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ArchiveFile {
+    /// Path of the file relative to the archive root.
+    pub path: PathBuf,
+    /// Size of the file in bytes.
+    pub size: u64,
+    /// Keccak256 hash of the file contents.
+    pub checksum: B256,
+}
+
+impl<DB: Database> DbTool<DB> {
+    /// Exports the database and the static files at `static_files_path` into an archive at
+    /// `archive_path`, which must not exist or be empty.
+    ///
+    /// All tables are read from a single read-only transaction, so the node may be running.
+    pub fn export_archive(
+        &self,
+        archive_path: &Path,
+        static_files_path: &Path,
+        chunk_size: u64,
+    ) -> Result<ArchiveManifest> {
+        ensure!(chunk_size > 0, "Chunk size must be greater than zero");
+        ensure!(
+            reth_db::is_database_empty(archive_path),
+            "Archive directory is not empty: {archive_path:?}"
+        );
+        fs::create_dir_all(archive_path)?;
+
+        let provider = self.provider_factory.provider()?.disable_long_read_transaction_safety();
+        let tx = provider.tx_ref();
+
+        let stage_checkpoints = tx
+            .cursor_read::<tables::StageCheckpoints>()?
+            .walk(None)?
+            .map(|entry| entry.map(|(stage, checkpoint)| (stage, checkpoint.block_number)))
+            .collect::<Result<_, _>>()?;
+
+        let mut archived_tables = Vec::with_capacity(Tables::COUNT);
+        for table in Tables::ALL {
+            let archived = table.view(&ExportViewer { tx, archive_path, chunk_size })?;
+            info!(target: "reth::cli", table = %table, entries = archived.entries, chunks = archived.chunks.len(), "Exported table");
+            archived_tables.push(archived);
+        }
+
+        // The read transaction is opened before copying the static files, so they can only be
+        // ahead of the stage checkpoints, which is healed on startup.
+        let static_files = export_static_files(static_files_path, archive_path)?;
+        info!(target: "reth::cli", files = static_files.len(), "Exported static files");
+
+        // The manifest must only be written once everything it describes is on disk.
+        sync_dir(&archive_path.join(TABLES_DIR))?;
+        sync_dir(archive_path)?;
+
+        let manifest = ArchiveManifest {
+            version: ARCHIVE_VERSION,
+            db_version: DB_VERSION,
+            genesis_hash: self.chain().genesis_hash(),
+            stage_checkpoints,
+            tables: archived_tables,
+            static_files,
+        };
+        let manifest_path = archive_path.join(MANIFEST_FILE_NAME);
+        fs::write_json_file(&manifest_path, &manifest)?;
+        open_file(&manifest_path)?.sync_all()?;
+        sync_dir(archive_path)?;
+
+        Ok(manifest)
+    }
+}
+
+/// Reads the manifest of the archive at `archive_path`, and checks that it can be imported into a
+/// database of the current version.
+pub fn read_archive_manifest(archive_path: &Path) -> Result<ArchiveManifest> {
+    let manifest: ArchiveManifest = fs::read_json_file(&archive_path.join(MANIFEST_FILE_NAME))?;
+    ensure!(
+        manifest.version == ARCHIVE_VERSION,
+        "Unsupported archive version {}, expected {ARCHIVE_VERSION}",
+        manifest.version
+    );
+    ensure!(
+        manifest.db_version == DB_VERSION,
+        "Archive was created from database version {}, expected {DB_VERSION}",
+        manifest.db_version
+    );
+    Ok(manifest)
+}
+
+/// Imports the archive at `archive_path` into an empty database and static files directory.
+///
+/// Every archived file is verified against its checksum before anything is written. The static
+/// files are copied and synced to disk before the tables, so the stage checkpoints are never
+/// committed ahead of the static files. Once imported, the static files and tables are verified to
+/// reach the stage checkpoints recorded in the manifest.
+pub fn import_archive<DB: Database>(
+    db: &DB,
+    archive_path: &Path,
+    static_files_path: &Path,
+) -> Result<ArchiveManifest> {
+    let manifest = read_archive_manifest(archive_path)?;
+
+    for file in manifest.tables.iter().flat_map(|table| &table.chunks).chain(&manifest.static_files)
+    {
+        verify_file(archive_path, file)?;
+    }
+    info!(target: "reth::cli", "Verified archive checksums");
+
+    fs::create_dir_all(static_files_path)?;
+    for file in &manifest.static_files {
+        let file_name = file
+            .path
+            .file_name()
+            .ok_or_else(|| eyre::eyre!("Invalid static file path in archive: {:?}", file.path))?;
+        let mut writer =
+            HashingWriter::new(BufWriter::new(fs::create_file(static_files_path.join(file_name))?));
+        io::copy(&mut open_file(&archive_path.join(&file.path))?, &mut writer)?;
+        let (size, checksum) = sync_file(writer)?;
+        ensure!(
+            size == file.size && checksum == file.checksum,
+            "Checksum mismatch while copying {:?}",
+            file.path
+        );
+    }
+    sync_dir(static_files_path)?;
+    info!(target: "reth::cli", files = manifest.static_files.len(), "Imported static files");
+
+    for archived in &manifest.tables {
+        let table: Tables = archived.name.parse().map_err(|err: String| eyre::eyre!(err))?;
+        table.view(&ImportViewer { db, archive_path, table: archived })?;
+        info!(target: "reth::cli", %table, entries = archived.entries, "Imported table");
+    }
+
+    verify_stage_checkpoints(db, static_files_path, &manifest)?;
+
+    Ok(manifest)
+}
+
+/// Verifies that the imported data reaches the stage checkpoints recorded in the manifest.
+///
+/// Headers and transactions are only stored in static files, and block bodies in the
+/// [`tables::BlockBodyIndices`] table, so none of them may end before the checkpoint of the stage
+/// writing them.
+fn verify_stage_checkpoints<DB: Database>(
+    db: &DB,
+    static_files_path: &Path,
+    manifest: &ArchiveManifest,
+) -> Result<()> {
+    let static_file_provider = StaticFileProvider::read_only(static_files_path)?;
+    let last_body_block = db
+        .view(|tx| tx.cursor_read::<tables::BlockBodyIndices>()?.last())??
+        .map(|(block, _)| block);
+
+    let tips = [
+        (
+            StageId::Headers,
+            static_file_provider.get_highest_static_file_block(StaticFileSegment::Headers),
+        ),
+        (
+            StageId::Bodies,
+            last_body_block.min(
+                static_file_provider.get_highest_static_file_block(StaticFileSegment::Transactions),
+            ),
+        ),
+    ];
+    for (stage, tip) in tips {
+        let Some(&checkpoint) = manifest.stage_checkpoints.get(stage.as_str()) else { continue };
+        ensure!(
+            tip.is_some_and(|tip| tip >= checkpoint),
+            "Imported data of stage {stage} ends at block {tip:?}, behind its checkpoint {checkpoint}"
+        );
+    }
+
+    Ok(())
+}
+
+/// Streams a table into chunks of an archive.
+struct ExportViewer<'a, TX> {
+    tx: &'a TX,
+    archive_path: &'a Path,
+    chunk_size: u64,
+}
+
+impl<TX: DbTx> TableViewer<ArchiveTable> for ExportViewer<'_, TX> {
+    type Error = eyre::Report;
+
+    fn view<T: Table>(&self) -> Result<ArchiveTable, Self::Error> {
+        let table_dir = Path::new(TABLES_DIR).join(T::NAME);
+        fs::create_dir_all(self.archive_path.join(&table_dir))?;
+
+        let mut chunks = Vec::new();
+        let mut entries = 0;
+        let mut writer: Option<(PathBuf, HashingWriter<BufWriter<File>>)> = None;
+
+        for entry in self.tx.cursor_read::<RawTable<T>>()?.walk(None)? {
+            let (key, value) = entry?;
+
+            if writer.is_none() {
+                let path = table_dir.join(format!("{:06}.chunk", chunks.len()));
+                let file = fs::create_file(self.archive_path.join(&path))?;
+                writer = Some((path, HashingWriter::new(BufWriter::new(file))));
+            }
+            let (_, chunk) = writer.as_mut().expect("exists");
+            write_record(chunk, key.raw_key())?;
+            write_record(chunk, value.raw_value())?;
+            entries += 1;
+
+            if chunk.size >= self.chunk_size {
+                chunks.push(finish_chunk(writer.take().expect("exists"))?);
+            }
+        }
+        if let Some(writer) = writer {
+            chunks.push(finish_chunk(writer)?);
+        }
+        sync_dir(&self.archive_path.join(&table_dir))?;
+
+        Ok(ArchiveTable { name: T::NAME.to_string(), entries, chunks })
+    }
+}
+
+/// Writes the chunks of an archived table into the database.
+struct ImportViewer<'a, DB> {
+    db: &'a DB,
+    archive_path: &'a Path,
+    table: &'a ArchiveTable,
+}
+
+impl<DB: Database> ImportViewer<'_, DB> {
+    /// Imports every chunk of the archived table in its own transaction, committing once per
+    /// chunk.
+    fn import(
+        &self,
+        mut import_chunk: impl FnMut(&DB::TXMut, &mut ChunkReader<'_>) -> Result<u64>,
+    ) -> Result<()> {
+        let mut entries = 0;
+        for chunk in &self.table.chunks {
+            let mut reader = ChunkReader {
+                path: &chunk.path,
+                reader: BufReader::new(open_file(&self.archive_path.join(&chunk.path))?),
+            };
+            let tx = self.db.tx_mut()?;
+            entries += import_chunk(&tx, &mut reader)?;
+            tx.commit()?;
+        }
+
+        ensure!(
+            entries == self.table.entries,
+            "Imported {entries} entries into {}, expected {}",
+            self.table.name,
+            self.table.entries
+        );
+        Ok(())
+    }
+}
+
+impl<DB: Database> TableViewer<()> for ImportViewer<'_, DB> {
+    type Error = eyre::Report;
+
+    fn view<T: Table>(&self) -> Result<(), Self::Error> {
+        self.import(|tx, reader| {
+            let mut cursor = tx.cursor_write::<RawTable<T>>()?;
+            let mut entries = 0;
+            while let Some((key, value)) = reader.next_entry()? {
+                // Entries were exported in key order, so they can be appended.
+                cursor.append(
+                    RawKey::<T::Key>::decode(key)?,
+                    RawValue::<T::Value>::decompress(value)?,
+                )?;
+                entries += 1;
+            }
+            Ok(entries)
+        })
+    }
+
+    fn view_dupsort<T: DupSort>(&self) -> Result<(), Self::Error> {
+        self.import(|tx, reader| {
+            let mut cursor = tx.cursor_dup_write::<RawDupSort<T>>()?;
+            let mut entries = 0;
+            while let Some((key, value)) = reader.next_entry()? {
+                cursor.append_dup(
+                    RawKey::<T::Key>::decode(key)?,
+                    RawValue::<T::Value>::decompress(value)?,
+                )?;
+                entries += 1;
+            }
+            Ok(entries)
+        })
+    }
+}
+
+/// Reads the entries of a table chunk.
+struct ChunkReader<'a> {
+    path: &'a Path,
+    reader: BufReader<File>,
+}
+
+impl ChunkReader<'_> {
+    /// Returns the next raw key/value pair of the chunk, or [`None`] at its end.
+    fn next_entry(&mut self) -> Result<Option<(Vec<u8>, Vec<u8>)>> {
+        let Some(key) = read_record(&mut self.reader)? else { return Ok(None) };
+        let Some(value) = read_record(&mut self.reader)? else {
+            bail!("Chunk {:?} is missing the value of its last entry", self.path)
+        };
+        Ok(Some((key, value)))
+    }
+}
+
+/// Copies every static file jar, along with its companion files, into the archive.
+fn export_static_files(static_files_path: &Path, archive_path: &Path) -> Result<Vec<ArchiveFile>> {
+    let archive_dir = Path::new(STATIC_FILES_DIR);
+    fs::create_dir_all(archive_path.join(archive_dir))?;
+
+    // Jar data files are named after their segment and block range, without an extension.
+    let mut jars = Vec::new();
+    for entry in fs::read_dir(static_files_path)? {
+        let path = entry?.path();
+        let is_jar = path.extension().is_none() &&
+            path.file_name()
+                .and_then(|name| name.to_str())
+                .is_some_and(|name| StaticFileSegment::parse_filename(name).is_some());
+        if is_jar {
+            jars.push(path);
+        }
+    }
+    jars.sort();
+
+    let mut files = Vec::new();
+    for jar in jars {
+        let companions = STATIC_FILE_EXTENSIONS
+            .iter()
+            .map(|extension| jar.with_extension(extension))
+            .filter(|path| path.exists());
+
+        for source in companions.chain(std::iter::once(jar.clone())) {
+            let file_name = source.file_name().expect("is a file");
+            let path = archive_dir.join(file_name);
+
+            let mut writer =
+                HashingWriter::new(BufWriter::new(fs::create_file(archive_path.join(&path))?));
+            io::copy(&mut open_file(&source)?, &mut writer)?;
+            let (size, checksum) = sync_file(writer)?;
+
+            files.push(ArchiveFile { path, size, checksum });
+        }
+    }
+
+    sync_dir(&archive_path.join(archive_dir))?;
+
+    Ok(files)
+}
+
+/// Verifies the size and checksum of an archived file.
+fn verify_file(archive_path: &Path, file: &ArchiveFile) -> Result<()> {
+    let mut writer = HashingWriter::new(io::sink());
+    io::copy(&mut open_file(&archive_path.join(&file.path))?, &mut writer)?;
+    let (_, size, checksum) = writer.finish();
+    ensure!(
+        size == file.size && checksum == file.checksum,
+        "Archive file {:?} is corrupted: expected {} bytes with checksum {}, found {size} bytes with checksum {checksum}",
+        file.path,
+        file.size,
+        file.checksum
+    );
+    Ok(())
+}
+
+/// Syncs a table chunk to disk and returns its description.
+fn finish_chunk((path, writer): (PathBuf, HashingWriter<BufWriter<File>>)) -> Result<ArchiveFile> {
+    let (size, checksum) = sync_file(writer)?;
+    Ok(ArchiveFile { path, size, checksum })
+}
+
+/// Flushes a file and syncs it to disk, returning the number of bytes written and their hash.
+fn sync_file(writer: HashingWriter<BufWriter<File>>) -> Result<(u64, B256)> {
+    let (inner, size, checksum) = writer.finish();
+    inner.into_inner().map_err(|err| err.into_error())?.sync_all()?;
+    Ok((size, checksum))
+}
+
+/// Syncs a directory to disk, so the files created in it are durable.
+fn sync_dir(path: &Path) -> Result<()> {
+    open_file(path)?.sync_all()?;
+    Ok(())
+}
+
+/// Writes a length-prefixed record.
+fn write_record(writer: &mut impl Write, data: &[u8]) -> io::Result<()> {
+    let len = u32::try_from(data.len())
+        .map_err(|_| io::Error::new(io::ErrorKind::InvalidInput, "record too large"))?;
+    writer.write_all(&len.to_le_bytes())?;
+    writer.write_all(data)
+}
+
+/// Reads a length-prefixed record, returning [`None`] at the end of the input.
+fn read_record(reader: &mut impl Read) -> io::Result<Option<Vec<u8>>> {
+    let mut len = [0; 4];
+    let mut read = 0;
+    while read < len.len() {
+        match reader.read(&mut len[read..])? {
+            0 if read == 0 => return Ok(None),
+            0 => return Err(io::ErrorKind::UnexpectedEof.into()),
+            n => read += n,
+        }
+    }
+
+    let mut data = vec![0; u32::from_le_bytes(len) as usize];
+    reader.read_exact(&mut data)?;
+    Ok(Some(data))
+}
+
+/// Opens a file for reading.
+fn open_file(path: &Path) -> Result<File> {
+    File::open(path).map_err(|err| eyre::eyre!("Failed to open {path:?}: {err}"))
+}
+
+/// Writer that hashes and counts everything written through it.
+struct HashingWriter<W> {
+    inner: W,
+    hasher: Keccak256,
+    size: u64,
+}
+
+impl<W> HashingWriter<W> {
+    fn new(inner: W) -> Self {
+        Self { inner, hasher: Keccak256::new(), size: 0 }
+    }
+
+    /// Returns the inner writer, along with the number of bytes written and their hash.
+    fn finish(self) -> (W, u64, B256) {
+        (self.inner, self.size, self.hasher.finalize())
+    }
+}
+
+impl<W: Write> Write for HashingWriter<W> {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        let written = self.inner.write(buf)?;
+        self.hasher.update(&buf[..written]);
+        self.size += written as u64;
+        Ok(written)
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        self.inner.flush()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use reth_db::{test_utils::create_test_rw_db, TableRawRow};
+    use reth_db_api::{cursor::DbCursorRO, models::AccountBeforeTx};
+    use reth_primitives::{Account, Address, Header, StorageEntry, U256};
+    use reth_provider::{
+        test_utils::create_test_provider_factory, StaticFileProviderFactory, StaticFileWriter,
+    };
+    use reth_stages_types::{StageCheckpoint, StageId};
+
+    #[test]
+    fn export_import_roundtrip() {
+        let factory = create_test_provider_factory();
+        let tx = factory.db_ref().tx_mut().unwrap();
+        for block in 0..100u64 {
+            let address = Address::with_last_byte(block as u8);
+            tx.put::<tables::PlainAccountState>(
+                address,
+                Account { nonce: block, ..Default::default() },
+            )
+            .unwrap();
+            tx.put::<tables::AccountChangeSets>(block, AccountBeforeTx { address, info: None })
+                .unwrap();
+            for slot in 0..3u8 {
+                tx.put::<tables::PlainStorageState>(
+                    address,
+                    StorageEntry::new(B256::with_last_byte(slot), U256::from(block)),
+                )
+                .unwrap();
+            }
+        }
+        for stage in [StageId::Headers, StageId::Execution] {
+            tx.put::<tables::StageCheckpoints>(stage.to_string(), StageCheckpoint::new(99))
+                .unwrap();
+        }
+        tx.commit().unwrap();
+
+        let static_file_provider = factory.static_file_provider();
+        let mut writer = static_file_provider.latest_writer(StaticFileSegment::Headers).unwrap();
+        for block in 0..100 {
+            let header = Header { number: block, ..Default::default() };
+            writer.append_header(&header, U256::ZERO, &header.hash_slow()).unwrap();
+        }
+        writer.commit().unwrap();
+        drop(writer);
+        let static_files_path = static_file_provider.directory().to_path_buf();
+
+        let archive = tempfile::tempdir().unwrap();
+        let tool = DbTool::new(factory.clone()).unwrap();
+        // Small chunks to make every table with entries span multiple chunks.
+        let manifest = tool.export_archive(archive.path(), &static_files_path, 64).unwrap();
+        assert_eq!(manifest.stage_checkpoints.get(StageId::Execution.as_str()), Some(&99));
+        assert!(manifest.static_files[0].path.ends_with("static_file_headers_0_499999.conf"));
+        assert!(manifest
+            .static_files
+            .last()
+            .is_some_and(|file| file.path.ends_with("static_file_headers_0_499999")));
+        let storage =
+            manifest.tables.iter().find(|table| table.name == tables::PlainStorageState::NAME);
+        assert_eq!(storage.map(|table| table.entries), Some(300));
+        assert!(storage.is_some_and(|table| table.chunks.len() > 1));
+
+        let db = create_test_rw_db();
+        let imported_static_files = tempfile::tempdir().unwrap();
+        assert_eq!(
+            import_archive(db.as_ref(), archive.path(), imported_static_files.path()).unwrap(),
+            manifest
+        );
+
+        let source = factory.db_ref().tx().unwrap();
+        let target = db.tx().unwrap();
+        fn walk(tx: &impl DbTx) -> Vec<TableRawRow<tables::PlainStorageState>> {
+            tx.cursor_read::<RawTable<tables::PlainStorageState>>()
+                .unwrap()
+                .walk(None)
+                .unwrap()
+                .collect::<Result<Vec<_>, _>>()
+                .unwrap()
+        }
+        assert_eq!(walk(&source), walk(&target));
+        assert_eq!(target.entries::<tables::PlainAccountState>().unwrap(), 100);
+        assert_eq!(
+            StaticFileProvider::read_only(imported_static_files.path())
+                .unwrap()
+                .get_highest_static_file_block(StaticFileSegment::Headers),
+            Some(99)
+        );
+
+        // Static files that are behind the stage checkpoints are rejected.
+        let mut behind = manifest.clone();
+        behind.stage_checkpoints.insert(StageId::Headers.to_string(), 150);
+        fs::write_json_file(&archive.path().join(MANIFEST_FILE_NAME), &behind).unwrap();
+        let db = create_test_rw_db();
+        let imported_static_files = tempfile::tempdir().unwrap();
+        assert!(import_archive(db.as_ref(), archive.path(), imported_static_files.path()).is_err());
+
+        // A corrupted chunk is detected before anything is written.
+        let chunk = archive.path().join(&storage.unwrap().chunks[0].path);
+        std::fs::write(chunk, b"corrupted").unwrap();
+        let db = create_test_rw_db();
+        let imported_static_files = tempfile::tempdir().unwrap();
+        assert!(import_archive(db.as_ref(), archive.path(), imported_static_files.path()).is_err());
+        assert_eq!(db.tx().unwrap().entries::<tables::PlainAccountState>().unwrap(), 0);
+    }
+}
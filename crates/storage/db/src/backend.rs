@@ -0,0 +1,490 @@
+//! Database backends that can be selected at runtime.
+//!
+//! [`AnyDatabaseEnv`] implements the database traits for any [`DatabaseBackend`], by dispatching
+//! every operation to the environment, transaction or cursor of the selected backend.
+
+use crate::{
+    mdbx::{self, DatabaseArguments, RO, RW},
+    redb::{self, RedbEnv},
+    DatabaseEnv, DatabaseError,
+};
+use metrics::Label;
+use reth_db_api::{
+    common::{PairResult, ValueOnlyResult},
+    cursor::{
+        DbCursorRO, DbCursorRW, DbDupCursorRO, DbDupCursorRW, DupWalker, RangeWalker,
+        ReverseWalker, Walker,
+    },
+    database::Database,
+    database_metrics::{DatabaseMetadata, DatabaseMetadataValue, DatabaseMetrics},
+    models::ClientVersion,
+    table::{DupSort, Table, TableImporter},
+    transaction::{DbTx, DbTxMut},
+};
+use reth_libmdbx::TransactionKind;
+use std::{
+    fmt,
+    ops::{Bound, RangeBounds},
+    path::Path,
+};
+
+/// The name of the file MDBX stores the tables in, inside the database directory.
+const MDBX_FILE_NAME: &str = "mdbx.dat";
+
+/// Embedded database storing the tables.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "clap", derive(clap::ValueEnum))]
+pub enum DatabaseBackend {
+    /// [MDBX](https://libmdbx.dqdkfa.ru/), a memory-mapped B+ tree.
+    #[default]
+    Mdbx,
+    /// [redb](https://docs.rs/redb), a pure-Rust copy-on-write B-tree.
+    Redb,
+}
+
+impl DatabaseBackend {
+    /// Returns the name of the file the backend stores the tables in, inside the database
+    /// directory.
+    pub const fn file_name(&self) -> &'static str {
+        match self {
+            Self::Mdbx => MDBX_FILE_NAME,
+            Self::Redb => redb::REDB_FILE_NAME,
+        }
+    }
+
+    /// Returns an error if the database directory at `path` contains the database of another
+    /// backend, so selecting the wrong backend doesn't silently create an empty database next to
+    /// it.
+    pub fn ensure_matches_db(&self, path: &Path) -> eyre::Result<()> {
+        for other in [Self::Mdbx, Self::Redb] {
+            if other != *self && path.join(other.file_name()).exists() {
+                eyre::bail!(
+                    "database at {} was created with the {other} backend, but the {self} backend \
+                     was selected",
+                    path.display()
+                )
+            }
+        }
+        Ok(())
+    }
+}
+
+impl fmt::Display for DatabaseBackend {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Mdbx => f.write_str("mdbx"),
+            Self::Redb => f.write_str("redb"),
+        }
+    }
+}
+
+/// Calls the same expression on the inner value of every variant.
+macro_rules! dispatch {
+    ($value:expr, $inner:ident => $body:expr) => {
+        match $value {
+            Self::Mdbx($inner) => $body,
+            Self::Redb($inner) => $body,
+        }
+    };
+}
+
+/// Database environment of any [`DatabaseBackend`].
+#[derive(Debug)]
+pub enum AnyDatabaseEnv {
+    /// MDBX environment.
+    Mdbx(DatabaseEnv),
+    /// redb environment.
+    Redb(RedbEnv),
+}
+
+impl AnyDatabaseEnv {
+    /// Returns the backend of the environment.
+    pub const fn backend(&self) -> DatabaseBackend {
+        match self {
+            Self::Mdbx(_) => DatabaseBackend::Mdbx,
+            Self::Redb(_) => DatabaseBackend::Redb,
+        }
+    }
+
+    /// Returns `true` if the environment was opened in read-only mode.
+    pub fn is_read_only(&self) -> bool {
+        match self {
+            Self::Mdbx(env) => env.is_read_only(),
+            Self::Redb(env) => env.is_read_only(),
+        }
+    }
+
+    /// Returns the MDBX environment, if the backend is MDBX.
+    pub const fn as_mdbx(&self) -> Option<&DatabaseEnv> {
+        match self {
+            Self::Mdbx(env) => Some(env),
+            Self::Redb(_) => None,
+        }
+    }
+
+    /// Creates all the tables defined in [`Tables`](crate::Tables), if necessary.
+    pub fn create_tables(&self) -> Result<(), DatabaseError> {
+        dispatch!(self, env => env.create_tables())
+    }
+
+    /// Records version that accesses the database with write privileges.
+    pub fn record_client_version(&self, version: ClientVersion) -> Result<(), DatabaseError> {
+        dispatch!(self, env => env.record_client_version(version))
+    }
+
+    /// Enables metrics on the database, if supported by the backend.
+    pub fn with_metrics(self) -> Self {
+        match self {
+            Self::Mdbx(env) => Self::Mdbx(env.with_metrics()),
+            Self::Redb(env) => Self::Redb(env),
+        }
+    }
+}
+
+impl From<DatabaseEnv> for AnyDatabaseEnv {
+    fn from(env: DatabaseEnv) -> Self {
+        Self::Mdbx(env)
+    }
+}
+
+impl From<RedbEnv> for AnyDatabaseEnv {
+    fn from(env: RedbEnv) -> Self {
+        Self::Redb(env)
+    }
+}
+
+impl Database for AnyDatabaseEnv {
+    type TX = AnyTx<RO>;
+    type TXMut = AnyTx<RW>;
+
+    fn tx(&self) -> Result<Self::TX, DatabaseError> {
+        Ok(match self {
+            Self::Mdbx(env) => AnyTx::Mdbx(env.tx()?),
+            Self::Redb(env) => AnyTx::Redb(env.tx()?),
+        })
+    }
+
+    fn tx_mut(&self) -> Result<Self::TXMut, DatabaseError> {
+        Ok(match self {
+            Self::Mdbx(env) => AnyTx::Mdbx(env.tx_mut()?),
+            Self::Redb(env) => AnyTx::Redb(env.tx_mut()?),
+        })
+    }
+}
+
+impl DatabaseMetrics for AnyDatabaseEnv {
+    fn report_metrics(&self) {
+        dispatch!(self, env => env.report_metrics())
+    }
+
+    fn gauge_metrics(&self) -> Vec<(&'static str, f64, Vec<Label>)> {
+        dispatch!(self, env => env.gauge_metrics())
+    }
+}
+
+impl DatabaseMetadata for AnyDatabaseEnv {
+    fn metadata(&self) -> DatabaseMetadataValue {
+        dispatch!(self, env => env.metadata())
+    }
+}
+
+/// Transaction of an [`AnyDatabaseEnv`].
+#[derive(Debug)]
+pub enum AnyTx<K: TransactionKind> {
+    /// MDBX transaction.
+    Mdbx(mdbx::tx::Tx<K>),
+    /// redb transaction.
+    Redb(redb::tx::Tx),
+}
+
+impl TableImporter for AnyTx<RW> {}
+
+impl<K: TransactionKind> DbTx for AnyTx<K> {
+    type Cursor<T: Table> = AnyCursor<K, T>;
+    type DupCursor<T: DupSort> = AnyCursor<K, T>;
+
+    fn get<T: Table>(&self, key: T::Key) -> Result<Option<T::Value>, DatabaseError> {
+        dispatch!(self, tx => tx.get::<T>(key))
+    }
+
+    fn commit(self) -> Result<bool, DatabaseError> {
+        dispatch!(self, tx => tx.commit())
+    }
+
+    fn abort(self) {
+        dispatch!(self, tx => tx.abort())
+    }
+
+    fn cursor_read<T: Table>(&self) -> Result<Self::Cursor<T>, DatabaseError> {
+        Ok(match self {
+            Self::Mdbx(tx) => AnyCursor::Mdbx(tx.cursor_read()?),
+            Self::Redb(tx) => AnyCursor::Redb(tx.cursor_read()?),
+        })
+    }
+
+    fn cursor_dup_read<T: DupSort>(&self) -> Result<Self::DupCursor<T>, DatabaseError> {
+        Ok(match self {
+            Self::Mdbx(tx) => AnyCursor::Mdbx(tx.cursor_dup_read()?),
+            Self::Redb(tx) => AnyCursor::Redb(tx.cursor_dup_read()?),
+        })
+    }
+
+    fn entries<T: Table>(&self) -> Result<usize, DatabaseError> {
+        dispatch!(self, tx => tx.entries::<T>())
+    }
+
+    fn disable_long_read_transaction_safety(&mut self) {
+        dispatch!(self, tx => tx.disable_long_read_transaction_safety())
+    }
+}
+
+impl DbTxMut for AnyTx<RW> {
+    type CursorMut<T: Table> = AnyCursor<RW, T>;
+    type DupCursorMut<T: DupSort> = AnyCursor<RW, T>;
+
+    fn put<T: Table>(&self, key: T::Key, value: T::Value) -> Result<(), DatabaseError> {
+        dispatch!(self, tx => tx.put::<T>(key, value))
+    }
+
+    fn delete<T: Table>(
+        &self,
+        key: T::Key,
+        value: Option<T::Value>,
+    ) -> Result<bool, DatabaseError> {
+        dispatch!(self, tx => tx.delete::<T>(key, value))
+    }
+
+    fn clear<T: Table>(&self) -> Result<(), DatabaseError> {
+        dispatch!(self, tx => tx.clear::<T>())
+    }
+
+    fn cursor_write<T: Table>(&self) -> Result<Self::CursorMut<T>, DatabaseError> {
+        Ok(match self {
+            Self::Mdbx(tx) => AnyCursor::Mdbx(tx.cursor_write()?),
+            Self::Redb(tx) => AnyCursor::Redb(tx.cursor_write()?),
+        })
+    }
+
+    fn cursor_dup_write<T: DupSort>(&self) -> Result<Self::DupCursorMut<T>, DatabaseError> {
+        Ok(match self {
+            Self::Mdbx(tx) => AnyCursor::Mdbx(tx.cursor_dup_write()?),
+            Self::Redb(tx) => AnyCursor::Redb(tx.cursor_dup_write()?),
+        })
+    }
+}
+
+/// Cursor of an [`AnyTx`].
+#[derive(Debug)]
+pub enum AnyCursor<K: TransactionKind, T: Table> {
+    /// MDBX cursor.
+    Mdbx(mdbx::cursor::Cursor<K, T>),
+    /// redb cursor.
+    Redb(redb::cursor::Cursor<T>),
+}
+
+impl<K: TransactionKind, T: Table> DbCursorRO<T> for AnyCursor<K, T> {
+    fn first(&mut self) -> PairResult<T> {
+        dispatch!(self, cursor => cursor.first())
+    }
+
+    fn seek_exact(&mut self, key: T::Key) -> PairResult<T> {
+        dispatch!(self, cursor => cursor.seek_exact(key))
+    }
+
+    fn seek(&mut self, key: T::Key) -> PairResult<T> {
+        dispatch!(self, cursor => cursor.seek(key))
+    }
+
+    fn next(&mut self) -> PairResult<T> {
+        dispatch!(self, cursor => cursor.next())
+    }
+
+    fn prev(&mut self) -> PairResult<T> {
+        dispatch!(self, cursor => cursor.prev())
+    }
+
+    fn last(&mut self) -> PairResult<T> {
+        dispatch!(self, cursor => cursor.last())
+    }
+
+    fn current(&mut self) -> PairResult<T> {
+        dispatch!(self, cursor => cursor.current())
+    }
+
+    fn walk(&mut self, start_key: Option<T::Key>) -> Result<Walker<'_, T, Self>, DatabaseError> {
+        let start = if let Some(start_key) = start_key {
+            self.seek(start_key).transpose()
+        } else {
+            self.first().transpose()
+        };
+
+        Ok(Walker::new(self, start))
+    }
+
+    fn walk_range(
+        &mut self,
+        range: impl RangeBounds<T::Key>,
+    ) -> Result<RangeWalker<'_, T, Self>, DatabaseError> {
+        let start = match range.start_bound().cloned() {
+            Bound::Included(key) => self.seek(key),
+            Bound::Excluded(_key) => {
+                unreachable!("Rust doesn't allow for Bound::Excluded in starting bounds");
+            }
+            Bound::Unbounded => self.first(),
+        }
+        .transpose();
+        Ok(RangeWalker::new(self, start, range.end_bound().cloned()))
+    }
+
+    fn walk_back(
+        &mut self,
+        start_key: Option<T::Key>,
+    ) -> Result<ReverseWalker<'_, T, Self>, DatabaseError> {
+        let start =
+            if let Some(start_key) = start_key { self.seek(start_key) } else { self.last() }
+                .transpose();
+
+        Ok(ReverseWalker::new(self, start))
+    }
+}
+
+impl<K: TransactionKind, T: DupSort> DbDupCursorRO<T> for AnyCursor<K, T> {
+    fn next_dup(&mut self) -> PairResult<T> {
+        dispatch!(self, cursor => cursor.next_dup())
+    }
+
+    fn next_no_dup(&mut self) -> PairResult<T> {
+        dispatch!(self, cursor => cursor.next_no_dup())
+    }
+
+    fn next_dup_val(&mut self) -> ValueOnlyResult<T> {
+        dispatch!(self, cursor => cursor.next_dup_val())
+    }
+
+    fn seek_by_key_subkey(&mut self, key: T::Key, subkey: T::SubKey) -> ValueOnlyResult<T> {
+        dispatch!(self, cursor => cursor.seek_by_key_subkey(key, subkey))
+    }
+
+    fn walk_dup(
+        &mut self,
+        key: Option<T::Key>,
+        subkey: Option<T::SubKey>,
+    ) -> Result<DupWalker<'_, T, Self>, DatabaseError> {
+        // Positions the inner cursor, and starts the walk from the same entry.
+        let start = dispatch!(self, cursor => cursor.walk_dup(key, subkey)?.start);
+        Ok(DupWalker::<'_, T, Self> { cursor: self, start })
+    }
+}
+
+impl<T: Table> DbCursorRW<T> for AnyCursor<RW, T> {
+    fn upsert(&mut self, key: T::Key, value: T::Value) -> Result<(), DatabaseError> {
+        dispatch!(self, cursor => cursor.upsert(key, value))
+    }
+
+    fn insert(&mut self, key: T::Key, value: T::Value) -> Result<(), DatabaseError> {
+        dispatch!(self, cursor => cursor.insert(key, value))
+    }
+
+    fn append(&mut self, key: T::Key, value: T::Value) -> Result<(), DatabaseError> {
+        dispatch!(self, cursor => cursor.append(key, value))
+    }
+
+    fn delete_current(&mut self) -> Result<(), DatabaseError> {
+        dispatch!(self, cursor => cursor.delete_current())
+    }
+}
+
+impl<T: DupSort> DbDupCursorRW<T> for AnyCursor<RW, T> {
+    fn delete_current_duplicates(&mut self) -> Result<(), DatabaseError> {
+        dispatch!(self, cursor => cursor.delete_current_duplicates())
+    }
+
+    fn append_dup(&mut self, key: T::Key, value: T::Value) -> Result<(), DatabaseError> {
+        dispatch!(self, cursor => cursor.append_dup(key, value))
+    }
+}
+
+/// Creates a new database at the specified path if it doesn't exist, with the given backend. Does
+/// NOT create tables. Check [`init_db`].
+pub fn create_db<P: AsRef<Path>>(
+    backend: DatabaseBackend,
+    path: P,
+    args: DatabaseArguments,
+) -> eyre::Result<AnyDatabaseEnv> {
+    backend.ensure_matches_db(path.as_ref())?;
+    Ok(match backend {
+        DatabaseBackend::Mdbx => mdbx::create_db(path, args)?.into(),
+        DatabaseBackend::Redb => redb::create_db(path, args)?.into(),
+    })
+}
+
+/// Opens up an existing database or creates a new one at the specified path, with the given
+/// backend. Creates tables if necessary. Read/Write mode.
+pub fn init_db<P: AsRef<Path>>(
+    backend: DatabaseBackend,
+    path: P,
+    args: DatabaseArguments,
+) -> eyre::Result<AnyDatabaseEnv> {
+    backend.ensure_matches_db(path.as_ref())?;
+    Ok(match backend {
+        DatabaseBackend::Mdbx => mdbx::init_db(path, args)?.into(),
+        DatabaseBackend::Redb => redb::init_db(path, args)?.into(),
+    })
+}
+
+/// Opens up an existing database with the given backend. Read only mode. It doesn't create it or
+/// create tables if missing.
+pub fn open_db_read_only(
+    backend: DatabaseBackend,
+    path: &Path,
+    args: DatabaseArguments,
+) -> eyre::Result<AnyDatabaseEnv> {
+    backend.ensure_matches_db(path)?;
+    Ok(match backend {
+        DatabaseBackend::Mdbx => mdbx::open_db_read_only(path, args)?.into(),
+        DatabaseBackend::Redb => redb::open_db_read_only(path, args)?.into(),
+    })
+}
+
+/// Opens up an existing database with the given backend. Read/Write mode. It doesn't create it or
+/// create tables if missing.
+pub fn open_db(
+    backend: DatabaseBackend,
+    path: &Path,
+    args: DatabaseArguments,
+) -> eyre::Result<AnyDatabaseEnv> {
+    backend.ensure_matches_db(path)?;
+    Ok(match backend {
+        DatabaseBackend::Mdbx => mdbx::open_db(path, args)?.into(),
+        DatabaseBackend::Redb => redb::open_db(path, args)?.into(),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use reth_db_api::models::ClientVersion;
+
+    #[test]
+    fn backend_mismatch() {
+        let args = || DatabaseArguments::new(ClientVersion::default());
+
+        for (backend, other) in [
+            (DatabaseBackend::Mdbx, DatabaseBackend::Redb),
+            (DatabaseBackend::Redb, DatabaseBackend::Mdbx),
+        ] {
+            let dir = tempfile::tempdir().unwrap();
+            drop(init_db(backend, dir.path(), args()).unwrap());
+            assert!(dir.path().join(backend.file_name()).exists());
+
+            // The database is opened with its own backend
+            drop(open_db(backend, dir.path(), args()).unwrap());
+
+            // But not with the other one, which doesn't create an empty database either
+            assert!(init_db(other, dir.path(), args()).is_err());
+            assert!(open_db(other, dir.path(), args()).is_err());
+            assert!(open_db_read_only(other, dir.path(), args()).is_err());
+            assert!(!dir.path().join(other.file_name()).exists());
+        }
+    }
+}
@@ -1,2 +1,4 @@
 #[cfg(feature = "mdbx")]
 pub(crate) mod mdbx;
+#[cfg(feature = "redb")]
+pub(crate) mod redb;
@@ -0,0 +1,478 @@
+//! Cursor wrapper for redb.
+
+use super::{
+    decode_dup_key, dup_key_upper_bound, encode_dup_key, error_info,
+    tx::{read_only_error, remove_duplicates, with_write_table, RawTable, SharedWriteTransaction},
+    Entry,
+};
+use crate::DatabaseError;
+use ::redb::{ReadOnlyTable, StorageError};
+use reth_db_api::{
+    common::{PairResult, ValueOnlyResult},
+    cursor::{
+        DbCursorRO, DbCursorRW, DbDupCursorRO, DbDupCursorRW, DupWalker, RangeWalker,
+        ReverseWalker, Walker,
+    },
+    table::{Compress, Decode, Decompress, DupSort, Encode, Table, TableRow},
+};
+use reth_libmdbx::Error as MDBXError;
+use reth_storage_errors::db::{DatabaseErrorInfo, DatabaseWriteError, DatabaseWriteOperation};
+use std::{borrow::Cow, collections::Bound, fmt, marker::PhantomData, ops::RangeBounds};
+
+/// Table a cursor operates on.
+enum CursorTable {
+    /// Table opened by a read-only transaction.
+    Read(ReadOnlyTable<&'static [u8], &'static [u8]>),
+    /// Write transaction in which the table is opened on every operation.
+    Write(SharedWriteTransaction),
+}
+
+/// Position of a cursor.
+#[derive(Debug)]
+enum Position {
+    /// The cursor is not positioned yet.
+    Unset,
+    /// The cursor is positioned at a stored entry, which can since have been deleted.
+    At(Entry),
+    /// The cursor moved past the last entry.
+    End,
+}
+
+/// Cursor wrapper to access KV items.
+///
+/// redb cursors can't outlive an operation on a write transaction, so the cursor only keeps its
+/// position and every operation seeks relative to it.
+pub struct Cursor<T: Table> {
+    /// Table of the cursor.
+    table: CursorTable,
+    /// Whether the entries are stored as the composite keys of a `DupSort` table.
+    dupsort: bool,
+    /// Current position of the cursor.
+    position: Position,
+    /// Phantom data to enforce encoding/decoding.
+    _dbi: PhantomData<T>,
+}
+
+impl<T: Table> fmt::Debug for Cursor<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Cursor")
+            .field("table", &T::NAME)
+            .field("dupsort", &self.dupsort)
+            .field("position", &self.position)
+            .finish()
+    }
+}
+
+impl<T: Table> Cursor<T> {
+    pub(crate) const fn new_read(
+        table: ReadOnlyTable<&'static [u8], &'static [u8]>,
+        dupsort: bool,
+    ) -> Self {
+        Self {
+            table: CursorTable::Read(table),
+            dupsort,
+            position: Position::Unset,
+            _dbi: PhantomData,
+        }
+    }
+
+    pub(crate) const fn new_write(tx: SharedWriteTransaction, dupsort: bool) -> Self {
+        Self {
+            table: CursorTable::Write(tx),
+            dupsort,
+            position: Position::Unset,
+            _dbi: PhantomData,
+        }
+    }
+
+    /// Reads from the table of the cursor.
+    fn read(
+        &self,
+        f: impl FnOnce(&dyn RawTable) -> Result<Option<Entry>, StorageError>,
+    ) -> Result<Option<Entry>, DatabaseError> {
+        let read = |table: &dyn RawTable| f(table).map_err(|e| DatabaseError::Read(error_info(e)));
+        match &self.table {
+            CursorTable::Read(table) => read(table),
+            CursorTable::Write(tx) => with_write_table(tx, T::NAME, |table| read(table)),
+        }
+    }
+
+    /// Writes to the table of the cursor.
+    fn write<R>(
+        &self,
+        f: impl FnOnce(&mut super::tx::RawTableMut<'_>) -> Result<R, DatabaseError>,
+    ) -> Result<R, DatabaseError> {
+        match &self.table {
+            CursorTable::Read(_) => Err(read_only_error()),
+            CursorTable::Write(tx) => with_write_table(tx, T::NAME, f),
+        }
+    }
+
+    /// Returns the stored key of a `(key, value)` pair.
+    fn stored_key(&self, key: &[u8], value: &[u8]) -> Vec<u8> {
+        if self.dupsort {
+            encode_dup_key(key, value)
+        } else {
+            key.to_vec()
+        }
+    }
+
+    /// Returns the smallest stored key of the entries of `key`.
+    fn lower_bound(&self, key: &[u8]) -> Vec<u8> {
+        self.stored_key(key, &[])
+    }
+
+    /// Returns the encoded key and the compressed value of a stored entry.
+    fn split<'a>(&self, entry: &'a Entry) -> Result<(Cow<'a, [u8]>, &'a [u8]), DatabaseError> {
+        if self.dupsort {
+            decode_dup_key(&entry.0).map(|(key, value)| (Cow::Owned(key), value))
+        } else {
+            Ok((Cow::Borrowed(&entry.0), &entry.1))
+        }
+    }
+
+    /// Decodes a stored entry.
+    fn decode(&self, entry: &Entry) -> Result<TableRow<T>, DatabaseError> {
+        let (key, value) = self.split(entry)?;
+        Ok((T::Key::decode(key)?, T::Value::decompress(value)?))
+    }
+
+    /// Moves the cursor to `entry` and decodes it. If there's no entry, the cursor is moved to
+    /// `otherwise`.
+    fn move_to(&mut self, entry: Option<Entry>, otherwise: Position) -> PairResult<T> {
+        match entry {
+            Some(entry) => {
+                let row = self.decode(&entry)?;
+                self.position = Position::At(entry);
+                Ok(Some(row))
+            }
+            None => {
+                self.position = otherwise;
+                Ok(None)
+            }
+        }
+    }
+
+    /// Returns the first entry with a stored key greater or equal to the one of `key`.
+    fn seek_entry(&self, key: &[u8]) -> Result<Option<Entry>, DatabaseError> {
+        let start = self.lower_bound(key);
+        self.read(|table| table.first_from(Bound::Included(&start)))
+    }
+
+    /// Returns `true` if `entry` is an entry of `key`.
+    fn is_entry_of(&self, entry: &Entry, key: &[u8]) -> Result<bool, DatabaseError> {
+        Ok(self.split(entry)?.0.as_ref() == key)
+    }
+
+    /// Returns the encoded key of the current entry.
+    fn current_key(&self) -> Result<Option<Vec<u8>>, DatabaseError> {
+        match &self.position {
+            Position::At(entry) => self.split(entry).map(|(key, _)| Some(key.into_owned())),
+            Position::Unset | Position::End => Ok(None),
+        }
+    }
+
+    /// Builds the error of a failed write operation.
+    fn write_error(
+        info: DatabaseErrorInfo,
+        operation: DatabaseWriteOperation,
+        key: &[u8],
+    ) -> DatabaseError {
+        DatabaseWriteError { info, operation, table_name: T::NAME, key: key.to_vec() }.into()
+    }
+
+    /// Inserts an entry and moves the cursor to it.
+    fn put(
+        &mut self,
+        key: &[u8],
+        value: &[u8],
+        operation: DatabaseWriteOperation,
+    ) -> Result<(), DatabaseError> {
+        let entry = if self.dupsort {
+            (encode_dup_key(key, value), Vec::new())
+        } else {
+            (key.to_vec(), value.to_vec())
+        };
+        self.write(|table| {
+            table
+                .insert(entry.0.as_slice(), entry.1.as_slice())
+                .map(|_| ())
+                .map_err(|e| Self::write_error(error_info(e), operation, key))
+        })?;
+        self.position = Position::At(entry);
+        Ok(())
+    }
+}
+
+impl<T: Table> DbCursorRO<T> for Cursor<T> {
+    fn first(&mut self) -> PairResult<T> {
+        let entry = self.read(|table| table.first_from(Bound::Unbounded))?;
+        self.move_to(entry, Position::End)
+    }
+
+    fn seek_exact(&mut self, key: <T as Table>::Key) -> PairResult<T> {
+        let key = key.encode();
+        let entry = self.seek_entry(key.as_ref())?;
+        let found =
+            entry.as_ref().map(|entry| self.is_entry_of(entry, key.as_ref())).transpose()?;
+        let row = self.move_to(entry, Position::End)?;
+        Ok(row.filter(|_| found.unwrap_or_default()))
+    }
+
+    fn seek(&mut self, key: <T as Table>::Key) -> PairResult<T> {
+        let entry = self.seek_entry(key.encode().as_ref())?;
+        self.move_to(entry, Position::End)
+    }
+
+    fn next(&mut self) -> PairResult<T> {
+        let entry = match &self.position {
+            Position::Unset => return self.first(),
+            Position::At((key, _)) => self.read(|table| table.first_from(Bound::Excluded(key)))?,
+            Position::End => return Ok(None),
+        };
+        self.move_to(entry, Position::End)
+    }
+
+    fn prev(&mut self) -> PairResult<T> {
+        let entry = match &self.position {
+            Position::Unset | Position::End => return self.last(),
+            Position::At((key, _)) => self.read(|table| table.last_before(Bound::Excluded(key)))?,
+        };
+        self.move_to(entry, Position::Unset)
+    }
+
+    fn last(&mut self) -> PairResult<T> {
+        let entry = self.read(|table| table.last_before(Bound::Unbounded))?;
+        self.move_to(entry, Position::Unset)
+    }
+
+    fn current(&mut self) -> PairResult<T> {
+        let Position::At((key, _)) = &self.position else { return Ok(None) };
+
+        // The entry is read again, as it could have been updated or deleted.
+        let key = key.clone();
+        let entry =
+            self.read(|table| Ok(table.get_raw(&key)?.map(|value| (key.clone(), value))))?;
+        entry.map(|entry| self.decode(&entry)).transpose()
+    }
+
+    fn walk(&mut self, start_key: Option<T::Key>) -> Result<Walker<'_, T, Self>, DatabaseError> {
+        let start = if let Some(start_key) = start_key {
+            self.seek(start_key).transpose()
+        } else {
+            self.first().transpose()
+        };
+
+        Ok(Walker::new(self, start))
+    }
+
+    fn walk_range(
+        &mut self,
+        range: impl RangeBounds<T::Key>,
+    ) -> Result<RangeWalker<'_, T, Self>, DatabaseError> {
+        let start = match range.start_bound().cloned() {
+            Bound::Included(key) => self.seek(key),
+            Bound::Excluded(_key) => {
+                unreachable!("Rust doesn't allow for Bound::Excluded in starting bounds");
+            }
+            Bound::Unbounded => self.first(),
+        }
+        .transpose();
+        Ok(RangeWalker::new(self, start, range.end_bound().cloned()))
+    }
+
+    fn walk_back(
+        &mut self,
+        start_key: Option<T::Key>,
+    ) -> Result<ReverseWalker<'_, T, Self>, DatabaseError> {
+        let start =
+            if let Some(start_key) = start_key { self.seek(start_key) } else { self.last() }
+                .transpose();
+
+        Ok(ReverseWalker::new(self, start))
+    }
+}
+
+impl<T: DupSort> DbDupCursorRO<T> for Cursor<T> {
+    /// Returns the next `(key, value)` pair of a DUPSORT table.
+    fn next_dup(&mut self) -> PairResult<T> {
+        let (stored, key) = match &self.position {
+            Position::Unset => return self.first(),
+            Position::At(entry) => (entry.0.clone(), self.split(entry)?.0.into_owned()),
+            Position::End => return Ok(None),
+        };
+
+        let entry = self.read(|table| table.first_from(Bound::Excluded(&stored)))?;
+        match entry {
+            Some(entry) if self.is_entry_of(&entry, &key)? => {
+                self.move_to(Some(entry), Position::End)
+            }
+            _ => Ok(None),
+        }
+    }
+
+    /// Returns the next `(key, value)` pair skipping the duplicates.
+    fn next_no_dup(&mut self) -> PairResult<T> {
+        let key = match &self.position {
+            Position::Unset => return self.first(),
+            Position::At(entry) => self.split(entry)?.0.into_owned(),
+            Position::End => return Ok(None),
+        };
+
+        let start = dup_key_upper_bound(&key);
+        let entry = self.read(|table| table.first_from(Bound::Included(&start)))?;
+        self.move_to(entry, Position::End)
+    }
+
+    /// Returns the next `value` of a duplicate `key`.
+    fn next_dup_val(&mut self) -> ValueOnlyResult<T> {
+        Ok(self.next_dup()?.map(|(_, value)| value))
+    }
+
+    fn seek_by_key_subkey(
+        &mut self,
+        key: <T as Table>::Key,
+        subkey: <T as DupSort>::SubKey,
+    ) -> ValueOnlyResult<T> {
+        let key = key.encode();
+        let start = encode_dup_key(key.as_ref(), subkey.encode().as_ref());
+        let entry = self.read(|table| table.first_from(Bound::Included(&start)))?;
+        match entry {
+            Some(entry) if self.is_entry_of(&entry, key.as_ref())? => {
+                Ok(self.move_to(Some(entry), Position::End)?.map(|(_, value)| value))
+            }
+            _ => Ok(None),
+        }
+    }
+
+    /// Depending on its arguments, returns an iterator starting at:
+    /// - Some(key), Some(subkey): a `key` item whose data is >= than `subkey`
+    /// - Some(key), None: first item of a specified `key`
+    /// - None, Some(subkey): like first case, but in the first key
+    /// - None, None: first item in the table of a DUPSORT table.
+    fn walk_dup(
+        &mut self,
+        key: Option<T::Key>,
+        subkey: Option<T::SubKey>,
+    ) -> Result<DupWalker<'_, T, Self>, DatabaseError> {
+        let start = match (key, subkey) {
+            (Some(key), Some(subkey)) => {
+                self.seek_by_key_subkey(key.clone(), subkey)?.map(|value| Ok((key, value)))
+            }
+            (Some(key), None) => self.seek_exact(key).transpose(),
+            (None, Some(subkey)) => {
+                if let Some((key, _)) = self.first()? {
+                    self.seek_by_key_subkey(key.clone(), subkey)?.map(|value| Ok((key, value)))
+                } else {
+                    Some(Err(DatabaseError::Read(MDBXError::NotFound.into())))
+                }
+            }
+            (None, None) => self.first().transpose(),
+        };
+
+        Ok(DupWalker::<'_, T, Self> { cursor: self, start })
+    }
+}
+
+impl<T: Table> DbCursorRW<T> for Cursor<T> {
+    /// Database operation that will update an existing row if a specified value already
+    /// exists in a table, and insert a new row if the specified value doesn't already exist
+    ///
+    /// For a DUPSORT table, `upsert` will not actually update-or-insert. If the key already exists,
+    /// it will append the value to the subkey, even if the subkeys are the same. So if you want
+    /// to properly upsert, you'll need to `seek_exact` & `delete_current` if the key+subkey was
+    /// found, before calling `upsert`.
+    fn upsert(&mut self, key: T::Key, value: T::Value) -> Result<(), DatabaseError> {
+        self.put(
+            key.encode().as_ref(),
+            value.compress().as_ref(),
+            DatabaseWriteOperation::CursorUpsert,
+        )
+    }
+
+    fn insert(&mut self, key: T::Key, value: T::Value) -> Result<(), DatabaseError> {
+        let key = key.encode();
+        if let Some(entry) = self.seek_entry(key.as_ref())? {
+            if self.is_entry_of(&entry, key.as_ref())? {
+                self.position = Position::At(entry);
+                return Err(Self::write_error(
+                    MDBXError::KeyExist.into(),
+                    DatabaseWriteOperation::CursorInsert,
+                    key.as_ref(),
+                ))
+            }
+        }
+
+        self.put(key.as_ref(), value.compress().as_ref(), DatabaseWriteOperation::CursorInsert)
+    }
+
+    /// Appends the data to the end of the table. Consequently, the append operation
+    /// will fail if the inserted key is less than the last table key
+    fn append(&mut self, key: T::Key, value: T::Value) -> Result<(), DatabaseError> {
+        let key = key.encode();
+        if let Some(last) = self.read(|table| table.last_before(Bound::Unbounded))? {
+            let last_key = self.split(&last)?.0.into_owned();
+            // Duplicates of the last key can still be appended to a DUPSORT table.
+            let mismatch = if self.dupsort {
+                key.as_ref() < last_key.as_slice()
+            } else {
+                key.as_ref() <= last_key.as_slice()
+            };
+            if mismatch {
+                self.position = Position::At(last);
+                return Err(Self::write_error(
+                    MDBXError::KeyMismatch.into(),
+                    DatabaseWriteOperation::CursorAppend,
+                    key.as_ref(),
+                ))
+            }
+        }
+
+        self.put(key.as_ref(), value.compress().as_ref(), DatabaseWriteOperation::CursorAppend)
+    }
+
+    fn delete_current(&mut self) -> Result<(), DatabaseError> {
+        let Position::At((key, _)) = &self.position else {
+            return Err(DatabaseError::Delete(MDBXError::NotFound.into()))
+        };
+
+        self.write(|table| {
+            table
+                .remove(key.as_slice())
+                .map(|_| ())
+                .map_err(|e| DatabaseError::Delete(error_info(e)))
+        })
+    }
+}
+
+impl<T: DupSort> DbDupCursorRW<T> for Cursor<T> {
+    fn delete_current_duplicates(&mut self) -> Result<(), DatabaseError> {
+        let Some(key) = self.current_key()? else {
+            return Err(DatabaseError::Delete(MDBXError::NotFound.into()))
+        };
+
+        self.write(|table| {
+            remove_duplicates(table, &key).map_err(|e| DatabaseError::Delete(error_info(e)))
+        })?;
+        Ok(())
+    }
+
+    fn append_dup(&mut self, key: T::Key, value: T::Value) -> Result<(), DatabaseError> {
+        let key = key.encode();
+        let value = value.compress();
+        if let Some(last) = self.read(|table| table.last_before(Bound::Unbounded))? {
+            // The key can't be lower than the last key, and the value has to be greater than the
+            // last duplicate of the same key.
+            if last.0.as_slice() >= encode_dup_key(key.as_ref(), value.as_ref()).as_slice() {
+                self.position = Position::At(last);
+                return Err(Self::write_error(
+                    MDBXError::KeyMismatch.into(),
+                    DatabaseWriteOperation::CursorAppendDup,
+                    key.as_ref(),
+                ))
+            }
+        }
+
+        self.put(key.as_ref(), value.as_ref(), DatabaseWriteOperation::CursorAppendDup)
+    }
+}
@@ -0,0 +1,335 @@
+//! Transaction wrapper for redb.
+
+use super::{
+    cursor::Cursor, decode_dup_key, dup_key_upper_bound, encode_dup_key, error_info, is_dupsort,
+    table_definition, Entry,
+};
+use crate::DatabaseError;
+use ::redb::{ReadTransaction, ReadableTable, StorageError, WriteTransaction};
+use reth_db_api::{
+    table::{Compress, Decompress, DupSort, Encode, Table, TableImporter},
+    transaction::{DbTx, DbTxMut},
+};
+use reth_storage_errors::db::{DatabaseWriteError, DatabaseWriteOperation};
+use std::{
+    fmt,
+    ops::Bound,
+    sync::{Arc, Mutex, MutexGuard},
+};
+
+/// A writable redb table of raw bytes.
+pub(crate) type RawTableMut<'txn> = ::redb::Table<'txn, &'static [u8], &'static [u8]>;
+
+/// Write transaction shared between a [`Tx`] and its cursors. It's taken on commit or abort.
+pub(crate) type SharedWriteTransaction = Arc<Mutex<Option<WriteTransaction>>>;
+
+/// Read operations on a redb table of raw bytes, implemented by both read-only and writable
+/// tables.
+pub(crate) trait RawTable {
+    /// Returns the value of `key`.
+    fn get_raw(&self, key: &[u8]) -> Result<Option<Vec<u8>>, StorageError>;
+
+    /// Returns the first entry with a key after the `start` bound.
+    fn first_from(&self, start: Bound<&[u8]>) -> Result<Option<Entry>, StorageError>;
+
+    /// Returns the last entry with a key before the `end` bound.
+    fn last_before(&self, end: Bound<&[u8]>) -> Result<Option<Entry>, StorageError>;
+
+    /// Returns the number of entries.
+    fn entries(&self) -> Result<u64, StorageError>;
+}
+
+impl<T: ReadableTable<&'static [u8], &'static [u8]>> RawTable for T {
+    fn get_raw(&self, key: &[u8]) -> Result<Option<Vec<u8>>, StorageError> {
+        Ok(self.get(key)?.map(|value| value.value().to_vec()))
+    }
+
+    fn first_from(&self, start: Bound<&[u8]>) -> Result<Option<Entry>, StorageError> {
+        self.range::<&[u8]>((start, Bound::Unbounded))?
+            .next()
+            .transpose()
+            .map(|entry| entry.map(|(key, value)| (key.value().to_vec(), value.value().to_vec())))
+    }
+
+    fn last_before(&self, end: Bound<&[u8]>) -> Result<Option<Entry>, StorageError> {
+        self.range::<&[u8]>((Bound::Unbounded, end))?
+            .next_back()
+            .transpose()
+            .map(|entry| entry.map(|(key, value)| (key.value().to_vec(), value.value().to_vec())))
+    }
+
+    fn entries(&self) -> Result<u64, StorageError> {
+        self.len()
+    }
+}
+
+/// Locks the shared write transaction, failing if it was already committed or aborted.
+fn lock(
+    tx: &SharedWriteTransaction,
+) -> Result<MutexGuard<'_, Option<WriteTransaction>>, DatabaseError> {
+    let guard = tx.lock().map_err(|_| DatabaseError::Other("poisoned transaction".to_string()))?;
+    if guard.is_none() {
+        return Err(DatabaseError::Other("transaction is closed".to_string()))
+    }
+    Ok(guard)
+}
+
+/// Opens the table with the given name in the shared write transaction and passes it to `f`.
+///
+/// The table is closed before returning, as redb doesn't allow a table to be open twice in the
+/// same transaction.
+pub(crate) fn with_write_table<R>(
+    tx: &SharedWriteTransaction,
+    name: &str,
+    f: impl FnOnce(&mut RawTableMut<'_>) -> Result<R, DatabaseError>,
+) -> Result<R, DatabaseError> {
+    let guard = lock(tx)?;
+    let tx = guard.as_ref().expect("transaction is open");
+    let mut table =
+        tx.open_table(table_definition(name)).map_err(|e| DatabaseError::Open(error_info(e)))?;
+    f(&mut table)
+}
+
+/// Removes all entries of `key` from a `DupSort` table, returning `true` if any was removed.
+pub(crate) fn remove_duplicates(
+    table: &mut RawTableMut<'_>,
+    key: &[u8],
+) -> Result<bool, StorageError> {
+    let start = encode_dup_key(key, &[]);
+    let end = dup_key_upper_bound(key);
+
+    let mut removed = false;
+    // Entries are only removed once they are read from the iterator.
+    for entry in table.extract_from_if::<&[u8], _>(start.as_slice()..end.as_slice(), |_, _| true)? {
+        entry?;
+        removed = true;
+    }
+    Ok(removed)
+}
+
+/// Wrapper for the redb transactions.
+pub struct Tx {
+    inner: TxInner,
+}
+
+/// Read-only or read-write redb transaction.
+enum TxInner {
+    Read(ReadTransaction),
+    Write(SharedWriteTransaction),
+}
+
+impl fmt::Debug for Tx {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let mode = match self.inner {
+            TxInner::Read(_) => "read-only",
+            TxInner::Write(_) => "read-write",
+        };
+        f.debug_struct("Tx").field("mode", &mode).finish_non_exhaustive()
+    }
+}
+
+impl Tx {
+    /// Creates new `Tx` object with a read-only transaction.
+    pub(crate) const fn new_read(tx: ReadTransaction) -> Self {
+        Self { inner: TxInner::Read(tx) }
+    }
+
+    /// Creates new `Tx` object with a read-write transaction.
+    pub(crate) fn new_write(tx: WriteTransaction) -> Self {
+        Self { inner: TxInner::Write(Arc::new(Mutex::new(Some(tx)))) }
+    }
+
+    /// Returns `true` if the transaction is read-only.
+    pub const fn is_read_only(&self) -> bool {
+        matches!(self.inner, TxInner::Read(_))
+    }
+
+    /// Create db Cursor
+    pub fn new_cursor<T: Table>(&self, dupsort: bool) -> Result<Cursor<T>, DatabaseError> {
+        match &self.inner {
+            TxInner::Read(tx) => Ok(Cursor::new_read(
+                tx.open_table(table_definition(T::NAME))
+                    .map_err(|e| DatabaseError::Open(error_info(e)))?,
+                dupsort,
+            )),
+            TxInner::Write(tx) => {
+                // Makes sure that the transaction is still open.
+                drop(lock(tx)?);
+                Ok(Cursor::new_write(tx.clone(), dupsort))
+            }
+        }
+    }
+
+    /// Opens the table with the given name and passes it to `f`.
+    fn with_table<R>(
+        &self,
+        name: &str,
+        f: impl FnOnce(&dyn RawTable) -> Result<R, DatabaseError>,
+    ) -> Result<R, DatabaseError> {
+        match &self.inner {
+            TxInner::Read(tx) => f(&tx
+                .open_table(table_definition(name))
+                .map_err(|e| DatabaseError::Open(error_info(e)))?),
+            TxInner::Write(tx) => with_write_table(tx, name, |table| f(table)),
+        }
+    }
+
+    /// Opens the table with the given name for writing and passes it to `f`.
+    fn with_table_mut<R>(
+        &self,
+        name: &str,
+        f: impl FnOnce(&mut RawTableMut<'_>) -> Result<R, DatabaseError>,
+    ) -> Result<R, DatabaseError> {
+        match &self.inner {
+            TxInner::Read(_) => Err(read_only_error()),
+            TxInner::Write(tx) => with_write_table(tx, name, f),
+        }
+    }
+}
+
+/// Error returned when writing with a read-only transaction.
+pub(crate) fn read_only_error() -> DatabaseError {
+    DatabaseError::Other("cannot write with a read-only transaction".to_string())
+}
+
+impl TableImporter for Tx {}
+
+impl DbTx for Tx {
+    type Cursor<T: Table> = Cursor<T>;
+    type DupCursor<T: DupSort> = Cursor<T>;
+
+    fn get<T: Table>(&self, key: T::Key) -> Result<Option<<T as Table>::Value>, DatabaseError> {
+        let key = key.encode();
+        let value = self.with_table(T::NAME, |table| {
+            if is_dupsort(T::NAME) {
+                // The first duplicate of the key.
+                let start = encode_dup_key(key.as_ref(), &[]);
+                let entry = table
+                    .first_from(Bound::Included(&start))
+                    .map_err(|e| DatabaseError::Read(error_info(e)))?;
+                entry
+                    .map(|(stored, _)| {
+                        decode_dup_key(&stored).map(|(entry_key, value)| {
+                            (entry_key == key.as_ref()).then(|| value.to_vec())
+                        })
+                    })
+                    .transpose()
+                    .map(Option::flatten)
+            } else {
+                table.get_raw(key.as_ref()).map_err(|e| DatabaseError::Read(error_info(e)))
+            }
+        })?;
+
+        value.map(T::Value::decompress_owned).transpose()
+    }
+
+    fn commit(self) -> Result<bool, DatabaseError> {
+        match self.inner {
+            // Cursors can still hold tables of the transaction, which are kept readable until
+            // dropped.
+            TxInner::Read(tx) => drop(tx),
+            TxInner::Write(tx) => {
+                let tx = lock(&tx)?.take().expect("transaction is open");
+                tx.commit().map_err(|e| DatabaseError::Commit(error_info(e)))?;
+            }
+        }
+
+        Ok(false)
+    }
+
+    fn abort(self) {
+        if let TxInner::Write(tx) = self.inner {
+            if let Some(tx) = lock(&tx).ok().and_then(|mut tx| tx.take()) {
+                let _ = tx.abort();
+            }
+        }
+    }
+
+    fn cursor_read<T: Table>(&self) -> Result<Self::Cursor<T>, DatabaseError> {
+        self.new_cursor(is_dupsort(T::NAME))
+    }
+
+    fn cursor_dup_read<T: DupSort>(&self) -> Result<Self::DupCursor<T>, DatabaseError> {
+        self.new_cursor(true)
+    }
+
+    fn entries<T: Table>(&self) -> Result<usize, DatabaseError> {
+        self.with_table(T::NAME, |table| {
+            table
+                .entries()
+                .map(|entries| entries as usize)
+                .map_err(|e| DatabaseError::Stats(error_info(e)))
+        })
+    }
+
+    fn disable_long_read_transaction_safety(&mut self) {}
+}
+
+impl DbTxMut for Tx {
+    type CursorMut<T: Table> = Cursor<T>;
+    type DupCursorMut<T: DupSort> = Cursor<T>;
+
+    fn put<T: Table>(&self, key: T::Key, value: T::Value) -> Result<(), DatabaseError> {
+        let key = key.encode();
+        let value = value.compress();
+        self.with_table_mut(T::NAME, |table| {
+            let result = if is_dupsort(T::NAME) {
+                table.insert(encode_dup_key(key.as_ref(), value.as_ref()).as_slice(), [].as_slice())
+            } else {
+                table.insert(key.as_ref(), value.as_ref())
+            };
+            result.map(|_| ()).map_err(|e| {
+                DatabaseWriteError {
+                    info: error_info(e),
+                    operation: DatabaseWriteOperation::Put,
+                    table_name: T::NAME,
+                    key: key.as_ref().to_vec(),
+                }
+                .into()
+            })
+        })
+    }
+
+    fn delete<T: Table>(
+        &self,
+        key: T::Key,
+        value: Option<T::Value>,
+    ) -> Result<bool, DatabaseError> {
+        let key = key.encode();
+        let value = value.map(Compress::compress);
+        self.with_table_mut(T::NAME, |table| {
+            let deleted = if is_dupsort(T::NAME) {
+                if let Some(value) = value {
+                    table
+                        .remove(encode_dup_key(key.as_ref(), value.as_ref()).as_slice())
+                        .map(|removed| removed.is_some())
+                } else {
+                    remove_duplicates(table, key.as_ref())
+                }
+            } else {
+                table.remove(key.as_ref()).map(|removed| removed.is_some())
+            };
+            deleted.map_err(|e| DatabaseError::Delete(error_info(e)))
+        })
+    }
+
+    fn clear<T: Table>(&self) -> Result<(), DatabaseError> {
+        self.with_table_mut(T::NAME, |table| {
+            table.retain(|_, _| false).map_err(|e| DatabaseError::Delete(error_info(e)))
+        })
+    }
+
+    fn cursor_write<T: Table>(&self) -> Result<Self::CursorMut<T>, DatabaseError> {
+        if self.is_read_only() {
+            return Err(read_only_error())
+        }
+        self.new_cursor(is_dupsort(T::NAME))
+    }
+
+    fn cursor_dup_write<T: DupSort>(&self) -> Result<Self::DupCursorMut<T>, DatabaseError> {
+        if self.is_read_only() {
+            return Err(read_only_error())
+        }
+        self.new_cursor(true)
+    }
+}
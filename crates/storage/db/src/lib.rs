@@ -15,6 +15,8 @@
 #![cfg_attr(not(test), warn(unused_crate_dependencies))]
 #![cfg_attr(docsrs, feature(doc_cfg, doc_auto_cfg))]
 
+#[cfg(feature = "redb")]
+pub mod backend;
 mod implementation;
 pub mod lockfile;
 #[cfg(feature = "mdbx")]
@@ -27,6 +29,8 @@ pub mod version;
 
 #[cfg(feature = "mdbx")]
 pub mod mdbx;
+#[cfg(feature = "redb")]
+pub mod redb;
 
 pub use reth_storage_errors::db::{DatabaseError, DatabaseWriteOperation};
 pub use tables::*;
@@ -36,6 +40,9 @@ pub use utils::is_database_empty;
 #[cfg(feature = "mdbx")]
 pub use mdbx::{create_db, init_db, open_db, open_db_read_only, DatabaseEnv, DatabaseEnvKind};
 
+#[cfg(feature = "redb")]
+pub use backend::{AnyDatabaseEnv, DatabaseBackend};
+
 pub use reth_db_api::*;
 
 /// Collection of database test utilities
@@ -1,10 +1,13 @@
-use super::{ReceiptMask, TransactionMask};
+use super::{AccountChangeSetMask, ReceiptMask, StorageChangeSetMask, TransactionMask};
 use crate::{
     add_static_file_mask,
     static_file::mask::{ColumnSelectorOne, ColumnSelectorTwo, HeaderMask},
     HeaderTerminalDifficulties, RawValue, Receipts, Transactions,
 };
-use reth_db_api::table::Table;
+use reth_db_api::{
+    models::{BlockAccountChangeSet, BlockStorageChangeSet},
+    table::Table,
+};
 use reth_primitives::{BlockHash, Header};
 
 // HEADER MASKS
@@ -20,3 +23,9 @@ add_static_file_mask!(ReceiptMask, <Receipts as Table>::Value, 0b1);
 // TRANSACTION MASKS
 add_static_file_mask!(TransactionMask, <Transactions as Table>::Value, 0b1);
 add_static_file_mask!(TransactionMask, RawValue<<Transactions as Table>::Value>, 0b1);
+
+// ACCOUNT CHANGE SET MASKS
+add_static_file_mask!(AccountChangeSetMask, BlockAccountChangeSet, 0b1);
+
+// STORAGE CHANGE SET MASKS
+add_static_file_mask!(StorageChangeSetMask, BlockStorageChangeSet, 0b1);
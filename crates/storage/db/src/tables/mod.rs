@@ -22,6 +22,7 @@ pub(crate) mod utils;
 use reth_db_api::{
     models::{
         accounts::{AccountBeforeTx, BlockNumberAddress},
+        bad_block::StoredBadBlock,
         blocks::{HeaderHash, StoredBlockOmmers},
         client_version::ClientVersion,
         storage_sharded_key::StorageShardedKey,
@@ -409,6 +410,9 @@ tables! {
 
     /// Stores generic chain state info, like the last finalized block.
     table ChainState<Key = ChainStateKey, Value = BlockNumber>;
+
+    /// Stores the most recent blocks that failed validation, along with the validation error.
+    table BadBlocks<Key = BlockHash, Value = StoredBadBlock>;
 }
 
 /// Keys for the `ChainState` table.
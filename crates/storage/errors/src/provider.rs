@@ -117,9 +117,30 @@ pub enum ProviderError {
     /// State is not available for the given block number because it is pruned.
     #[error("state at block #{0} is pruned")]
     StateAtBlockPruned(BlockNumber),
+    /// History of the address is not available at the given block number because it is pruned,
+    /// while the history of other addresses at this block may still be available.
+    #[error("history of address {address} at block #{block_number} is pruned")]
+    AddressHistoryPruned {
+        /// The address with the pruned history.
+        address: Address,
+        /// The block number at which the history was requested.
+        block_number: BlockNumber,
+    },
+    /// The history of the address can't be looked up because too many of its storage slots have
+    /// history.
+    #[error("more than {max} storage slots of address {address} have history")]
+    TooManyHistorySlots {
+        /// The address with the history.
+        address: Address,
+        /// The maximum number of storage slots with history.
+        max: usize,
+    },
     /// Provider does not support this particular request.
     #[error("this provider does not support this request")]
     UnsupportedProvider,
+    /// There is no database table with the given name.
+    #[error("unknown database table {0}")]
+    UnknownTable(String),
     /// Static File is not found at specified path.
     #[cfg(feature = "std")]
     #[error("not able to find {0} static file at {1}")]
@@ -0,0 +1,390 @@
+use crate::{
+    compression::{Compression, DeltaVarint, Lz4},
+    NippyJarError,
+};
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+use std::{io::Cursor, sync::OnceLock};
+use tracing::*;
+use zstd::{
+    bulk::{Compressor, Decompressor},
+    dict::{DecoderDictionary, EncoderDictionary},
+    zstd_safe,
+};
+
+/// Amount of sample bytes to collect per byte of a zstd dictionary before training it.
+const DICTIONARY_SAMPLES_RATIO: usize = 100;
+
+/// Compression of the values of a single column.
+#[derive(Debug, Serialize, Deserialize)]
+#[cfg_attr(test, derive(PartialEq))]
+pub enum ColumnCodec {
+    /// Values are stored as is.
+    Uncompressed,
+    /// Values are compressed with [`Lz4`].
+    Lz4(Lz4),
+    /// Values are compressed with [`ZstdColumn`].
+    Zstd(ZstdColumn),
+    /// Values are encoded with [`DeltaVarint`].
+    DeltaVarint(DeltaVarint),
+}
+
+impl Compression for ColumnCodec {
+    fn decompress_to(&self, value: &[u8], dest: &mut Vec<u8>) -> Result<(), NippyJarError> {
+        match self {
+            Self::Uncompressed => {
+                dest.extend_from_slice(value);
+                Ok(())
+            }
+            Self::Lz4(lz4) => lz4.decompress_to(value, dest),
+            Self::Zstd(zstd) => zstd.decompress_to(value, dest),
+            Self::DeltaVarint(delta) => delta.decompress_to(value, dest),
+        }
+    }
+
+    fn decompress(&self, value: &[u8]) -> Result<Vec<u8>, NippyJarError> {
+        match self {
+            Self::Uncompressed => Ok(value.to_vec()),
+            Self::Lz4(lz4) => lz4.decompress(value),
+            Self::Zstd(zstd) => zstd.decompress(value),
+            Self::DeltaVarint(delta) => delta.decompress(value),
+        }
+    }
+
+    fn compress_to(&self, src: &[u8], dest: &mut Vec<u8>) -> Result<usize, NippyJarError> {
+        match self {
+            Self::Uncompressed => {
+                dest.extend_from_slice(src);
+                Ok(src.len())
+            }
+            Self::Lz4(lz4) => {
+                dest.reserve(lz4_flex::block::get_maximum_output_size(src.len()));
+                lz4.compress_to(src, dest)
+            }
+            Self::Zstd(zstd) => zstd.compress_to(src, dest),
+            Self::DeltaVarint(delta) => delta.compress_to(src, dest),
+        }
+    }
+
+    fn compress(&self, src: &[u8]) -> Result<Vec<u8>, NippyJarError> {
+        match self {
+            Self::Uncompressed => Ok(src.to_vec()),
+            Self::Lz4(lz4) => lz4.compress(src),
+            Self::Zstd(zstd) => zstd.compress(src),
+            Self::DeltaVarint(delta) => delta.compress(src),
+        }
+    }
+}
+
+impl ColumnCodec {
+    /// Trains new dictionaries for the zstd columns from `samples`.
+    ///
+    /// If `force` is `false`, only columns that are due for retraining according to
+    /// [`ZstdColumn::should_train`] are trained. A column whose training fails, e.g. because there
+    /// are not enough samples, keeps its current dictionaries.
+    ///
+    /// Returns the number of trained dictionaries.
+    pub(crate) fn train_dictionaries(
+        codecs: &mut [Self],
+        samples: &mut DictionarySamples,
+        rows: usize,
+        force: bool,
+    ) -> usize {
+        let mut trained = 0;
+        for (column, codec) in codecs.iter_mut().enumerate() {
+            let Self::Zstd(zstd) = codec else { continue };
+            if !zstd.uses_dictionary() || !(force || zstd.should_train(rows)) {
+                continue
+            }
+
+            let Some((data, sizes)) = samples.take(column) else { continue };
+            match zstd.train(&data, &sizes, rows) {
+                Ok(()) => {
+                    debug!(target: "nippy-jar", ?column, ?rows, dictionaries = zstd.dictionaries.len(), "Trained ZSTD column dictionary.");
+                    trained += 1;
+                }
+                Err(err) => {
+                    debug!(target: "nippy-jar", ?column, ?rows, %err, "Failed to train ZSTD column dictionary.");
+                }
+            }
+        }
+        trained
+    }
+}
+
+/// Zstd compression of a single column, with optional dictionaries trained on the column values.
+///
+/// Dictionaries are trained in generations: whenever the jar grows by `retrain_interval` rows, a
+/// new dictionary is trained from the appended values and used to compress the following ones.
+/// Previous dictionaries are kept, since every compressed value references the dictionary it was
+/// compressed with. Values appended before the first dictionary is trained are compressed without
+/// one. Recompressing the jar compresses all values with a single dictionary again.
+#[derive(Debug, Serialize, Deserialize)]
+#[cfg_attr(test, derive(PartialEq))]
+pub struct ZstdColumn {
+    /// Compression level. A level of `0` uses zstd's default (currently `3`).
+    level: i32,
+    /// Max size of a dictionary. A size of `0` disables dictionaries.
+    max_dict_size: usize,
+    /// Number of rows appended after which a new dictionary is trained. An interval of `0`
+    /// disables the automatic training.
+    retrain_interval: usize,
+    /// Number of jar rows at the time of the last training.
+    trained_at_rows: usize,
+    /// Dictionary generations, from the oldest to the newest.
+    dictionaries: Vec<ZstdColumnDictionary>,
+}
+
+impl ZstdColumn {
+    /// Creates new [`ZstdColumn`] without dictionaries.
+    pub const fn new(level: i32) -> Self {
+        Self {
+            level,
+            max_dict_size: 0,
+            retrain_interval: 0,
+            trained_at_rows: 0,
+            dictionaries: Vec::new(),
+        }
+    }
+
+    /// Enables dictionaries of at most `max_dict_size` bytes, trained every `retrain_interval`
+    /// appended rows.
+    pub const fn with_dictionary(mut self, max_dict_size: usize, retrain_interval: usize) -> Self {
+        self.max_dict_size = max_dict_size;
+        self.retrain_interval = retrain_interval;
+        self
+    }
+
+    /// Returns the compression level.
+    pub const fn level(&self) -> i32 {
+        self.level
+    }
+
+    /// Returns `true` if the column uses dictionaries.
+    pub const fn uses_dictionary(&self) -> bool {
+        self.max_dict_size > 0
+    }
+
+    /// Returns the number of trained dictionary generations.
+    pub fn dictionaries(&self) -> usize {
+        self.dictionaries.len()
+    }
+
+    /// Returns `true` if the jar has grown by `retrain_interval` rows since the last training.
+    pub const fn should_train(&self, rows: usize) -> bool {
+        self.uses_dictionary() &&
+            self.retrain_interval > 0 &&
+            rows >= self.trained_at_rows + self.retrain_interval
+    }
+
+    /// Trains a new dictionary generation from continuous `samples` of `sizes`, which is used to
+    /// compress all values from now on.
+    ///
+    /// `rows` is the number of jar rows at the time of the training. A failed training is retried
+    /// after another `retrain_interval` rows.
+    pub fn train(
+        &mut self,
+        samples: &[u8],
+        sizes: &[usize],
+        rows: usize,
+    ) -> Result<(), NippyJarError> {
+        self.trained_at_rows = rows;
+        let dictionary = zstd::dict::from_continuous(samples, sizes, self.max_dict_size)?;
+        self.dictionaries.push(ZstdColumnDictionary::new(dictionary));
+        Ok(())
+    }
+
+    /// Returns the dictionary with the given id.
+    fn dictionary(&self, id: u32) -> Result<&ZstdColumnDictionary, NippyJarError> {
+        self.dictionaries
+            .iter()
+            .rev()
+            .find(|dictionary| dictionary.id == id)
+            .ok_or(NippyJarError::DictionaryNotFound(id))
+    }
+}
+
+impl Compression for ZstdColumn {
+    fn decompress_to(&self, value: &[u8], dest: &mut Vec<u8>) -> Result<(), NippyJarError> {
+        let mut decompressor = match zstd_safe::get_dict_id_from_frame(value) {
+            Some(id) => {
+                Decompressor::with_prepared_dictionary(self.dictionary(id.get())?.decoder())?
+            }
+            None => Decompressor::new()?,
+        };
+
+        if let Ok(Some(size)) = zstd_safe::get_frame_content_size(value) {
+            dest.reserve(size as usize);
+        }
+
+        // Appends to the existing content of `dest`.
+        let mut cursor = Cursor::new(dest);
+        cursor.set_position(cursor.get_ref().len() as u64);
+        decompressor.decompress_to_buffer(value, &mut cursor)?;
+
+        Ok(())
+    }
+
+    fn decompress(&self, value: &[u8]) -> Result<Vec<u8>, NippyJarError> {
+        let mut decompressed = Vec::new();
+        self.decompress_to(value, &mut decompressed)?;
+        Ok(decompressed)
+    }
+
+    fn compress_to(&self, src: &[u8], dest: &mut Vec<u8>) -> Result<usize, NippyJarError> {
+        let mut compressor = match self.dictionaries.last() {
+            Some(dictionary) => {
+                Compressor::with_prepared_dictionary(dictionary.encoder(self.level))?
+            }
+            None => Compressor::new(self.level)?,
+        };
+
+        let before = dest.len();
+        dest.reserve(zstd_safe::compress_bound(src.len()));
+
+        // Appends to the existing content of `dest`.
+        let mut cursor = Cursor::new(dest);
+        cursor.set_position(before as u64);
+        compressor.compress_to_buffer(src, &mut cursor)?;
+
+        Ok(cursor.get_ref().len() - before)
+    }
+
+    fn compress(&self, src: &[u8]) -> Result<Vec<u8>, NippyJarError> {
+        let mut compressed = Vec::new();
+        self.compress_to(src, &mut compressed)?;
+        Ok(compressed)
+    }
+}
+
+/// A trained zstd dictionary of a [`ZstdColumn`]. Serialized as the raw dictionary.
+pub(crate) struct ZstdColumnDictionary {
+    /// Dictionary id, which is referenced by the values compressed with it.
+    id: u32,
+    /// Raw dictionary.
+    raw: Vec<u8>,
+    /// Dictionary prepared for decompression.
+    decoder: DecoderDictionary<'static>,
+    /// Dictionary prepared for compression. Only loaded when compressing, which only happens with
+    /// the newest dictionary.
+    encoder: OnceLock<EncoderDictionary<'static>>,
+}
+
+impl ZstdColumnDictionary {
+    fn new(raw: Vec<u8>) -> Self {
+        Self {
+            id: zstd_safe::get_dict_id_from_dict(&raw).map_or(0, |id| id.get()),
+            decoder: DecoderDictionary::copy(&raw),
+            encoder: OnceLock::new(),
+            raw,
+        }
+    }
+
+    const fn decoder(&self) -> &DecoderDictionary<'static> {
+        &self.decoder
+    }
+
+    fn encoder(&self, level: i32) -> &EncoderDictionary<'static> {
+        self.encoder.get_or_init(|| EncoderDictionary::copy(&self.raw, level))
+    }
+}
+
+impl std::fmt::Debug for ZstdColumnDictionary {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("ZstdColumnDictionary")
+            .field("id", &self.id)
+            .field("size", &self.raw.len())
+            .finish_non_exhaustive()
+    }
+}
+
+#[cfg(test)]
+impl PartialEq for ZstdColumnDictionary {
+    fn eq(&self, other: &Self) -> bool {
+        self.raw == other.raw
+    }
+}
+
+impl Serialize for ZstdColumnDictionary {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        self.raw.serialize(serializer)
+    }
+}
+
+impl<'de> Deserialize<'de> for ZstdColumnDictionary {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        Ok(Self::new(Vec::deserialize(deserializer)?))
+    }
+}
+
+/// Column value samples to train the dictionaries of [`ZstdColumn`] codecs with.
+///
+/// Samples of a column are collected until they reach
+/// [`DICTIONARY_SAMPLES_RATIO`] times the max dictionary size of the column.
+#[derive(Debug)]
+pub struct DictionarySamples {
+    /// Samples per column. Columns without dictionaries have no samples.
+    columns: Vec<Option<ColumnSamples>>,
+}
+
+/// Samples of a single column.
+#[derive(Debug)]
+struct ColumnSamples {
+    /// Continuous sample data.
+    data: Vec<u8>,
+    /// Size of every sample in `data`.
+    sizes: Vec<usize>,
+    /// Max size of `data`.
+    max_size: usize,
+}
+
+impl DictionarySamples {
+    /// Creates [`DictionarySamples`] for the columns of `codecs` that use dictionaries.
+    pub fn new(codecs: &[ColumnCodec]) -> Self {
+        Self {
+            columns: codecs
+                .iter()
+                .map(|codec| match codec {
+                    ColumnCodec::Zstd(zstd) if zstd.uses_dictionary() => Some(ColumnSamples {
+                        data: Vec::new(),
+                        sizes: Vec::new(),
+                        max_size: zstd.max_dict_size * DICTIONARY_SAMPLES_RATIO,
+                    }),
+                    _ => None,
+                })
+                .collect(),
+        }
+    }
+
+    /// Returns `true` if any column uses dictionaries.
+    pub fn is_used(&self) -> bool {
+        self.columns.iter().any(Option::is_some)
+    }
+
+    /// Returns `true` if the samples of every column that uses dictionaries are complete.
+    pub fn is_full(&self) -> bool {
+        self.columns.iter().flatten().all(|samples| samples.data.len() >= samples.max_size)
+    }
+
+    /// Adds a value of `column` to the samples, unless they're complete.
+    pub fn add(&mut self, column: usize, value: &[u8]) {
+        if let Some(Some(samples)) = self.columns.get_mut(column) {
+            if samples.data.len() < samples.max_size && !value.is_empty() {
+                samples.data.extend_from_slice(value);
+                samples.sizes.push(value.len());
+            }
+        }
+    }
+
+    /// Takes the collected samples of `column`, if there are any.
+    fn take(&mut self, column: usize) -> Option<(Vec<u8>, Vec<usize>)> {
+        let samples = self.columns.get_mut(column)?.as_mut()?;
+        (!samples.sizes.is_empty())
+            .then(|| (std::mem::take(&mut samples.data), std::mem::take(&mut samples.sizes)))
+    }
+}
@@ -0,0 +1,158 @@
+use crate::{compression::Compression, NippyJarError};
+use serde::{Deserialize, Serialize};
+
+/// Size of an encoded integer word in bytes.
+const WORD_SIZE: usize = 8;
+
+/// Delta and varint encoding for columns of monotonic integers, e.g. block numbers or transaction
+/// numbers.
+///
+/// A value is read as a list of little-endian `u64` words. Each word is stored as the zigzag varint
+/// of its difference to the previous word of the value, so a value holding a single integer is
+/// stored as the varint of that integer. Values are encoded independently from each other, which
+/// keeps random row access possible. Values whose length isn't a multiple of the word size are
+/// stored as is.
+///
+/// ## Layout
+/// A varint header, holding the number of words shifted left by one for encoded values, or the
+/// length of the value shifted left by one with the lowest bit set for values stored as is.
+/// Followed by the varint deltas or the raw value respectively.
+#[derive(Debug, PartialEq, Eq, Serialize, Deserialize, Default)]
+#[non_exhaustive]
+pub struct DeltaVarint;
+
+impl Compression for DeltaVarint {
+    fn decompress_to(&self, value: &[u8], dest: &mut Vec<u8>) -> Result<(), NippyJarError> {
+        let (header, mut value) = read_varint(value)?;
+        let len = usize::try_from(header >> 1).map_err(|_| NippyJarError::InvalidDeltaVarint)?;
+
+        if header & 1 == 1 {
+            if value.len() != len {
+                return Err(NippyJarError::InvalidDeltaVarint)
+            }
+            dest.extend_from_slice(value);
+            return Ok(())
+        }
+
+        dest.reserve(len.saturating_mul(WORD_SIZE));
+        let mut previous = 0u64;
+        for _ in 0..len {
+            let (delta, rest) = read_varint(value)?;
+            value = rest;
+
+            previous = previous.wrapping_add(zigzag_decode(delta) as u64);
+            dest.extend_from_slice(&previous.to_le_bytes());
+        }
+
+        if !value.is_empty() {
+            return Err(NippyJarError::InvalidDeltaVarint)
+        }
+
+        Ok(())
+    }
+
+    fn decompress(&self, value: &[u8]) -> Result<Vec<u8>, NippyJarError> {
+        let mut decompressed = Vec::new();
+        self.decompress_to(value, &mut decompressed)?;
+        Ok(decompressed)
+    }
+
+    fn compress_to(&self, src: &[u8], dest: &mut Vec<u8>) -> Result<usize, NippyJarError> {
+        let before = dest.len();
+
+        if src.len() % WORD_SIZE != 0 {
+            write_varint(((src.len() as u64) << 1) | 1, dest);
+            dest.extend_from_slice(src);
+            return Ok(dest.len() - before)
+        }
+
+        write_varint(((src.len() / WORD_SIZE) as u64) << 1, dest);
+        let mut previous = 0u64;
+        for word in src.chunks_exact(WORD_SIZE) {
+            let word = u64::from_le_bytes(word.try_into().expect("chunk of word size"));
+            write_varint(zigzag_encode(word.wrapping_sub(previous) as i64), dest);
+            previous = word;
+        }
+
+        Ok(dest.len() - before)
+    }
+
+    fn compress(&self, src: &[u8]) -> Result<Vec<u8>, NippyJarError> {
+        let mut compressed = Vec::with_capacity(src.len());
+        self.compress_to(src, &mut compressed)?;
+        Ok(compressed)
+    }
+}
+
+const fn zigzag_encode(value: i64) -> u64 {
+    ((value << 1) ^ (value >> 63)) as u64
+}
+
+const fn zigzag_decode(value: u64) -> i64 {
+    (value >> 1) as i64 ^ -((value & 1) as i64)
+}
+
+fn write_varint(mut value: u64, dest: &mut Vec<u8>) {
+    while value >= 0x80 {
+        dest.push(value as u8 | 0x80);
+        value >>= 7;
+    }
+    dest.push(value as u8);
+}
+
+/// Reads a varint from the beginning of `src`, returning it alongside the remaining bytes.
+fn read_varint(src: &[u8]) -> Result<(u64, &[u8]), NippyJarError> {
+    let mut value = 0u64;
+    for (index, byte) in src.iter().enumerate().take(10) {
+        value |= ((byte & 0x7f) as u64) << (7 * index);
+        if byte & 0x80 == 0 {
+            return Ok((value, &src[index + 1..]))
+        }
+    }
+    Err(NippyJarError::InvalidDeltaVarint)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn words(words: &[u64]) -> Vec<u8> {
+        words.iter().flat_map(|word| word.to_le_bytes()).collect()
+    }
+
+    #[test]
+    fn delta_varint_roundtrip() {
+        let codec = DeltaVarint;
+
+        for value in [
+            vec![],
+            words(&[0]),
+            words(&[u64::MAX]),
+            words(&[17_000_000]),
+            words(&[17_000_000, 17_000_001, 17_000_005, 17_000_100]),
+            words(&[100, 50, u64::MAX, 0, 3]),
+            vec![1, 2, 3],
+            vec![0xff; 13],
+        ] {
+            let compressed = codec.compress(&value).unwrap();
+            assert_eq!(codec.decompress(&compressed).unwrap(), value);
+
+            // Appends to the existing buffer content
+            let mut dest = vec![42];
+            codec.decompress_to(&compressed, &mut dest).unwrap();
+            assert_eq!(dest[0], 42);
+            assert_eq!(&dest[1..], value.as_slice());
+        }
+
+        // Monotonic lists are stored as the first integer and small deltas
+        let value = words(&(17_000_000..17_000_100).collect::<Vec<_>>());
+        assert_eq!(codec.compress(&value).unwrap().len(), 2 + 4 + 99);
+
+        // Truncated values are rejected
+        let compressed = codec.compress(&words(&[1, 1000])).unwrap();
+        assert!(matches!(
+            codec.decompress(&compressed[..compressed.len() - 1]),
+            Err(NippyJarError::InvalidDeltaVarint)
+        ));
+    }
+}
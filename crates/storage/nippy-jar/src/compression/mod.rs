@@ -5,6 +5,10 @@ mod zstd;
 pub use self::zstd::{DecoderDictionary, Decompressor, Zstd, ZstdState};
 mod lz4;
 pub use self::lz4::Lz4;
+mod delta;
+pub use self::delta::DeltaVarint;
+mod column;
+pub use self::column::{ColumnCodec, DictionarySamples, ZstdColumn};
 
 /// Trait that will compress column values
 pub trait Compression: Serialize + for<'a> Deserialize<'a> {
@@ -46,6 +50,54 @@ pub trait Compression: Serialize + for<'a> Deserialize<'a> {
 pub enum Compressors {
     Zstd(Zstd),
     Lz4(Lz4),
+    /// A [`ColumnCodec`] per column.
+    Columns(Vec<ColumnCodec>),
+}
+
+impl Compressors {
+    /// Appends the compressed value of `column` from `src` to `dest`.
+    ///
+    /// Returns number of bytes written to `dest`.
+    pub fn compress_column_to(
+        &self,
+        column: usize,
+        src: &[u8],
+        dest: &mut Vec<u8>,
+    ) -> Result<usize, NippyJarError> {
+        match self {
+            Self::Columns(codecs) => column_codec(codecs, column)?.compress_to(src, dest),
+            _ => self.compress_to(src, dest),
+        }
+    }
+
+    /// Appends the decompressed value of `column` to the dest buffer. Requires `dest` to have
+    /// sufficient capacity, unless per column compression is used.
+    pub fn decompress_column_to(
+        &self,
+        column: usize,
+        value: &[u8],
+        dest: &mut Vec<u8>,
+    ) -> Result<(), NippyJarError> {
+        match self {
+            Self::Zstd(zstd) if zstd.use_dict => {
+                // If we are here, then for sure we have the necessary dictionaries and they're
+                // loaded (happens during deserialization). Otherwise, there's an issue
+                // somewhere else and we can't recover here anyway.
+                let dictionary = zstd.dictionaries.as_ref().expect("dictionaries to exist")[column]
+                    .loaded()
+                    .expect("dictionary to be loaded");
+                let mut decompressor = Decompressor::with_prepared_dictionary(dictionary)?;
+                Zstd::decompress_with_dictionary(value, dest, &mut decompressor)
+            }
+            Self::Columns(codecs) => column_codec(codecs, column)?.decompress_to(value, dest),
+            _ => self.decompress_to(value, dest),
+        }
+    }
+}
+
+/// Returns the codec of `column`.
+fn column_codec(codecs: &[ColumnCodec], column: usize) -> Result<&ColumnCodec, NippyJarError> {
+    codecs.get(column).ok_or(NippyJarError::ColumnLenMismatch(codecs.len(), column + 1))
 }
 
 impl Compression for Compressors {
@@ -53,12 +105,14 @@ impl Compression for Compressors {
         match self {
             Self::Zstd(zstd) => zstd.decompress_to(value, dest),
             Self::Lz4(lz4) => lz4.decompress_to(value, dest),
+            Self::Columns(_) => Err(NippyJarError::ColumnCompressionWithoutColumn),
         }
     }
     fn decompress(&self, value: &[u8]) -> Result<Vec<u8>, NippyJarError> {
         match self {
             Self::Zstd(zstd) => zstd.decompress(value),
             Self::Lz4(lz4) => lz4.decompress(value),
+            Self::Columns(_) => Err(NippyJarError::ColumnCompressionWithoutColumn),
         }
     }
 
@@ -68,6 +122,7 @@ impl Compression for Compressors {
             let result = match self {
                 Self::Zstd(zstd) => zstd.compress_to(src, dest),
                 Self::Lz4(lz4) => lz4.compress_to(src, dest),
+                Self::Columns(_) => Err(NippyJarError::ColumnCompressionWithoutColumn),
             };
 
             match result {
@@ -86,6 +141,7 @@ impl Compression for Compressors {
         match self {
             Self::Zstd(zstd) => zstd.compress(src),
             Self::Lz4(lz4) => lz4.compress(src),
+            Self::Columns(_) => Err(NippyJarError::ColumnCompressionWithoutColumn),
         }
     }
 
@@ -93,6 +149,7 @@ impl Compression for Compressors {
         match self {
             Self::Zstd(zstd) => zstd.is_ready(),
             Self::Lz4(lz4) => lz4.is_ready(),
+            Self::Columns(_) => true,
         }
     }
 
@@ -104,6 +161,7 @@ impl Compression for Compressors {
         match self {
             Self::Zstd(zstd) => zstd.prepare_compression(columns),
             Self::Lz4(lz4) => lz4.prepare_compression(columns),
+            Self::Columns(_) => Ok(()),
         }
     }
 }
@@ -1,11 +1,9 @@
 use crate::{
-    compression::{Compression, Compressors, Zstd},
     DataReader, InclusionFilter, NippyJar, NippyJarError, NippyJarHeader, PerfectHashingFunction,
     RefRow,
 };
 use std::{ops::Range, sync::Arc};
 use sucds::int_vectors::Access;
-use zstd::bulk::Decompressor;
 
 /// Simple cursor implementation to retrieve data from [`NippyJar`].
 #[derive(Clone)]
@@ -225,30 +223,11 @@ impl<'a, H: NippyJarHeader> NippyJarCursor<'a, H> {
 
         if let Some(compression) = self.jar.compressor() {
             let from = self.internal_buffer.len();
-            match compression {
-                Compressors::Zstd(z) if z.use_dict => {
-                    // If we are here, then for sure we have the necessary dictionaries and they're
-                    // loaded (happens during deserialization). Otherwise, there's an issue
-                    // somewhere else and we can't recover here anyway.
-                    let dictionaries = z.dictionaries.as_ref().expect("dictionaries to exist")
-                        [column]
-                        .loaded()
-                        .expect("dictionary to be loaded");
-                    let mut decompressor = Decompressor::with_prepared_dictionary(dictionaries)?;
-                    Zstd::decompress_with_dictionary(
-                        self.reader.data(column_offset_range),
-                        &mut self.internal_buffer,
-                        &mut decompressor,
-                    )?;
-                }
-                _ => {
-                    // Uses the chosen default decompressor
-                    compression.decompress_to(
-                        self.reader.data(column_offset_range),
-                        &mut self.internal_buffer,
-                    )?;
-                }
-            }
+            compression.decompress_column_to(
+                column,
+                self.reader.data(column_offset_range),
+                &mut self.internal_buffer,
+            )?;
             let to = self.internal_buffer.len();
 
             row.push(ValueRange::Internal(from..to));
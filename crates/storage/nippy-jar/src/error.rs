@@ -58,6 +58,12 @@ pub enum NippyJarError {
     DictionaryNotLoaded,
     #[error("it's not possible to generate a compressor after loading a dictionary.")]
     CompressorNotAllowed,
+    #[error("zstd dictionary with id {0} is not found.")]
+    DictionaryNotFound(u32),
+    #[error("per column compression requires the column of the value.")]
+    ColumnCompressionWithoutColumn,
+    #[error("invalid delta varint encoded value.")]
+    InvalidDeltaVarint,
     #[error("number of offsets ({0}) is smaller than prune request ({1}).")]
     InvalidPruning(u64, u64),
     #[error("jar has been frozen and cannot be modified.")]
@@ -17,6 +17,7 @@ use memmap2::Mmap;
 use serde::{Deserialize, Serialize};
 use std::{
     error::Error as StdError,
+    ffi::OsString,
     fs::{File, OpenOptions},
     ops::Range,
     path::{Path, PathBuf},
@@ -50,6 +51,7 @@ const NIPPY_JAR_VERSION: usize = 1;
 const INDEX_FILE_EXTENSION: &str = "idx";
 const OFFSETS_FILE_EXTENSION: &str = "off";
 const CONFIG_FILE_EXTENSION: &str = "conf";
+const REPLACEMENT_CONFIG_FILE_EXTENSION: &str = "conf_replacement";
 
 /// A [`RefRow`] is a list of column value slices pointing to either an internal buffer or a
 /// memory-mapped file.
@@ -154,6 +156,13 @@ impl NippyJar<()> {
         Self::load(path)
     }
 
+    /// Returns the data path of a jar that replaces the jar at `path`, see [`NippyJar::replace`].
+    pub fn replacement_path(path: &Path) -> PathBuf {
+        let mut file_name = OsString::from("replacement_");
+        file_name.push(path.file_name().unwrap_or_default());
+        path.with_file_name(file_name)
+    }
+
     /// Whether this [`NippyJar`] uses a [`InclusionFilters`] and [`Functions`].
     pub const fn uses_filters(&self) -> bool {
         self.filter.is_some() && self.phf.is_some()
@@ -190,6 +199,12 @@ impl<H: NippyJarHeader> NippyJar<H> {
         self
     }
 
+    /// Adds per column compression, with a [`compression::ColumnCodec`] for each column.
+    pub fn with_column_codecs(mut self, codecs: Vec<compression::ColumnCodec>) -> Self {
+        self.compressor = Some(Compressors::Columns(codecs));
+        self
+    }
+
     /// Adds [`filter::Cuckoo`] filter.
     pub fn with_cuckoo_filter(mut self, max_capacity: usize) -> Self {
         self.filter = Some(InclusionFilters::Cuckoo(Cuckoo::new(max_capacity)));
@@ -243,11 +258,36 @@ impl<H: NippyJarHeader> NippyJar<H> {
         self.compressor.as_mut()
     }
 
+    /// Trains new dictionaries for the [`compression::ZstdColumn`] codecs that use dictionaries,
+    /// from the column value `samples`. Values appended afterwards are compressed with them.
+    ///
+    /// `rows` is the number of rows the samples were taken from, e.g. the number of rows that are
+    /// going to be appended. The next dictionaries are only trained once the jar has grown past
+    /// them by the retrain interval.
+    ///
+    /// Returns the number of trained dictionaries.
+    pub fn train_dictionaries(
+        &mut self,
+        samples: &mut compression::DictionarySamples,
+        rows: usize,
+    ) -> usize {
+        match &mut self.compressor {
+            Some(Compressors::Columns(codecs)) => {
+                compression::ColumnCodec::train_dictionaries(codecs, samples, rows, true)
+            }
+            _ => 0,
+        }
+    }
+
     /// Loads the file configuration and returns [`Self`] without deserializing filters related
     /// structures or the offset list.
     ///
     /// **The user must ensure the header type matches the one used during the jar's creation.**
+    ///
+    /// An interrupted [`NippyJar::replace`] of the jar is finished first.
     pub fn load(path: &Path) -> Result<Self, NippyJarError> {
+        finish_replacement(path)?;
+
         // Read [`Self`] located at the data file.
         let config_path = path.with_extension(CONFIG_FILE_EXTENSION);
         let config_file = File::open(&config_path)
@@ -303,6 +343,40 @@ impl<H: NippyJarHeader> NippyJar<H> {
         Ok(())
     }
 
+    /// Replaces the files of the jar at `path` with the ones of this committed jar, which needs to
+    /// be located at the [`NippyJar::replacement_path`] of `path`. Returns the jar at `path`.
+    ///
+    /// The configuration file is moved next to the one of the replaced jar under a marker name
+    /// first, and only swapped in after the data and offsets files. So the replacement is
+    /// finished by the next [`NippyJar::load`] of the jar once the configuration file is moved,
+    /// and the jar keeps its own files if it was interrupted before. Jars with an index are not
+    /// supported.
+    pub fn replace(mut self, path: &Path) -> Result<Self, NippyJarError> {
+        if self.path != NippyJar::replacement_path(path) {
+            return Err(NippyJarError::Custom(format!(
+                "{} is not the replacement path of {}",
+                self.path.display(),
+                path.display()
+            )))
+        }
+        if self.index_path().exists() || path.with_extension(INDEX_FILE_EXTENSION).exists() {
+            return Err(NippyJarError::Custom(format!(
+                "can't replace {} with an index",
+                path.display()
+            )))
+        }
+
+        reth_fs_util::rename(
+            self.config_path(),
+            path.with_extension(REPLACEMENT_CONFIG_FILE_EXTENSION),
+        )?;
+        sync_parent_dir(path)?;
+        finish_replacement(path)?;
+
+        self.path = path.to_path_buf();
+        Ok(self)
+    }
+
     /// Returns a [`DataReader`] of the data and offset file
     pub fn open_data_reader(&self) -> Result<DataReader, NippyJarError> {
         DataReader::new(self.data_path())
@@ -332,6 +406,41 @@ impl<H: NippyJarHeader> NippyJar<H> {
     }
 }
 
+/// Finishes an interrupted [`NippyJar::replace`] of the jar at `path`, if the configuration file
+/// of the replacement was already moved next to the one of the jar.
+fn finish_replacement(path: &Path) -> Result<(), NippyJarError> {
+    let replacement_config_path = path.with_extension(REPLACEMENT_CONFIG_FILE_EXTENSION);
+    if !replacement_config_path.exists() {
+        return Ok(())
+    }
+
+    let replacement_path = NippyJar::replacement_path(path);
+    for (from, to) in [
+        (replacement_path.clone(), path.to_path_buf()),
+        (
+            replacement_path.with_extension(OFFSETS_FILE_EXTENSION),
+            path.with_extension(OFFSETS_FILE_EXTENSION),
+        ),
+    ] {
+        // The file was already moved if the replacement was interrupted afterwards
+        if from.exists() {
+            reth_fs_util::rename(from, to)?;
+        }
+    }
+    sync_parent_dir(path)?;
+
+    reth_fs_util::rename(replacement_config_path, path.with_extension(CONFIG_FILE_EXTENSION))?;
+    sync_parent_dir(path)
+}
+
+/// Syncs the directory of the file at `path`, so renames within it are durable.
+fn sync_parent_dir(path: &Path) -> Result<(), NippyJarError> {
+    if let Some(parent) = path.parent() {
+        OpenOptions::new().read(true).open(parent)?.sync_all()?;
+    }
+    Ok(())
+}
+
 impl<H: NippyJarHeader> InclusionFilter for NippyJar<H> {
     fn add(&mut self, element: &[u8]) -> Result<(), NippyJarError> {
         self.filter.as_mut().ok_or(NippyJarError::FilterMissing)?.add(element)
@@ -586,7 +695,7 @@ impl DataReader {
 #[cfg(test)]
 mod tests {
     use super::*;
-    use compression::Compression;
+    use compression::{ColumnCodec, Compression, DeltaVarint, DictionarySamples, Lz4, ZstdColumn};
     use rand::{rngs::SmallRng, seq::SliceRandom, RngCore, SeedableRng};
     use std::{collections::HashSet, fs::OpenOptions};
 
@@ -1078,6 +1187,165 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_column_codecs() {
+        let num_columns = 3;
+        let num_rows = 2000;
+        let retrain_interval = 800;
+        let file_path = tempfile::NamedTempFile::new().unwrap();
+
+        let rows = (0..num_rows as u64)
+            .map(|i| {
+                vec![
+                    format!(
+                        r#"{{"number":{i},"miner":"0x95222290dd7278aa3ddd389cc1e1d165cc4bafe5","gas":{}}}"#,
+                        i * 7 % 30_000_000
+                    )
+                    .into_bytes(),
+                    (i * 10..i * 10 + i % 5).flat_map(u64::to_le_bytes).collect(),
+                    vec![i as u8; 40],
+                ]
+            })
+            .collect::<Vec<_>>();
+
+        let codecs = || {
+            vec![
+                ColumnCodec::Zstd(ZstdColumn::new(0).with_dictionary(1024, retrain_interval)),
+                ColumnCodec::DeltaVarint(DeltaVarint),
+                ColumnCodec::Lz4(Lz4),
+            ]
+        };
+
+        // Codecs need to match the columns
+        assert!(matches!(
+            NippyJarWriter::new(
+                NippyJar::new_without_header(2, file_path.path()).with_column_codecs(codecs()),
+                ConsistencyFailStrategy::Heal
+            ),
+            Err(NippyJarError::ColumnLenMismatch(2, 3))
+        ));
+
+        // Appends all rows in several commits, which trains a new dictionary generation every
+        // `retrain_interval` rows
+        let nippy = NippyJar::new_without_header(num_columns, file_path.path())
+            .with_column_codecs(codecs());
+        let mut writer = NippyJarWriter::new(nippy, ConsistencyFailStrategy::Heal).unwrap();
+        for chunk in rows.chunks(250) {
+            for row in chunk {
+                for value in row {
+                    writer.append_column(Some(Ok(value))).unwrap();
+                }
+            }
+            writer.commit().unwrap();
+        }
+
+        let nippy = NippyJar::load_without_header(file_path.path()).unwrap();
+        assert_eq!(nippy.rows(), num_rows);
+        let Some(Compressors::Columns(loaded_codecs)) = nippy.compressor() else {
+            panic!("Expected column codecs")
+        };
+        let ColumnCodec::Zstd(zstd) = &loaded_codecs[0] else { panic!("Expected Zstd codec") };
+        assert_eq!(zstd.dictionaries(), num_rows / retrain_interval);
+
+        let assert_rows = |nippy: &NippyJar| {
+            let mut cursor = NippyJarCursor::new(nippy).unwrap();
+            for (row_num, row) in rows.iter().enumerate() {
+                let values = cursor.row_by_number(row_num).unwrap().unwrap();
+                assert_eq!(values, row.iter().map(Vec::as_slice).collect::<Vec<_>>());
+            }
+            assert!(cursor.next_row().unwrap().is_none());
+        };
+        assert_rows(&nippy);
+
+        // Trains the dictionaries up front, before writing any data
+        let recompressed_path = tempfile::NamedTempFile::new().unwrap();
+        let mut recompressed = NippyJar::new_without_header(num_columns, recompressed_path.path())
+            .with_column_codecs(codecs());
+        let mut samples = DictionarySamples::new(&codecs());
+        for row in &rows {
+            for (column, value) in row.iter().enumerate() {
+                samples.add(column, value);
+            }
+        }
+        assert_eq!(recompressed.train_dictionaries(&mut samples, num_rows), 1);
+
+        let mut writer = NippyJarWriter::new(recompressed, ConsistencyFailStrategy::Heal).unwrap();
+        for row in &rows {
+            for value in row {
+                writer.append_column(Some(Ok(value))).unwrap();
+            }
+        }
+        writer.commit().unwrap();
+
+        // Retraining is due once the jar grows past the sampled rows
+        let recompressed = NippyJar::load_without_header(recompressed_path.path()).unwrap();
+        let Some(Compressors::Columns(loaded_codecs)) = recompressed.compressor() else {
+            panic!("Expected column codecs")
+        };
+        let ColumnCodec::Zstd(zstd) = &loaded_codecs[0] else { panic!("Expected Zstd codec") };
+        assert_eq!(zstd.dictionaries(), 1);
+        assert!(!zstd.should_train(num_rows + retrain_interval - 1));
+        assert!(zstd.should_train(num_rows + retrain_interval));
+        assert_rows(&recompressed);
+
+        // Columns compress better than uncompressed data
+        let data_size = |nippy: &NippyJar| nippy.open_data_reader().unwrap().size();
+        let uncompressed_size = rows.iter().flatten().map(Vec::len).sum::<usize>();
+        assert!(data_size(&nippy) < uncompressed_size);
+        assert!(data_size(&recompressed) < data_size(&nippy));
+    }
+
+    #[test]
+    fn test_replace() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("jar");
+        let replacement_path = NippyJar::replacement_path(&path);
+
+        let write = |path: &Path, value: u8| {
+            let nippy = NippyJar::new_without_header(1, path)
+                .with_column_codecs(vec![ColumnCodec::Lz4(Lz4)]);
+            let mut writer = NippyJarWriter::new(nippy, ConsistencyFailStrategy::Heal).unwrap();
+            for _ in 0..10 {
+                writer.append_column(Some(Ok(&[value; 100]))).unwrap();
+            }
+            writer.commit().unwrap();
+            writer.into_jar()
+        };
+        let assert_value = |path: &Path, value: u8| {
+            let nippy = NippyJar::load_without_header(path).unwrap();
+            let mut cursor = NippyJarCursor::new(&nippy).unwrap();
+            for _ in 0..10 {
+                assert_eq!(cursor.next_row().unwrap(), Some(vec![&[value; 100][..]]));
+            }
+            assert!(cursor.next_row().unwrap().is_none());
+        };
+
+        // Only a jar at its replacement path can replace it
+        write(&path, 1);
+        assert!(write(dir.path().join("other").as_path(), 2).replace(&path).is_err());
+
+        let replaced = write(&replacement_path, 2).replace(&path).unwrap();
+        assert_eq!(replaced.data_path(), path);
+        assert_value(&path, 2);
+        assert!(!replacement_path.exists());
+
+        // Interrupted before the configuration file is moved, so the jar keeps its own files
+        write(&replacement_path, 3);
+        assert_value(&path, 2);
+
+        // Interrupted after the data file is moved, so loading the jar finishes the replacement
+        let replacement = write(&replacement_path, 3);
+        reth_fs_util::rename(
+            replacement.config_path(),
+            path.with_extension(REPLACEMENT_CONFIG_FILE_EXTENSION),
+        )
+        .unwrap();
+        reth_fs_util::rename(replacement.data_path(), &path).unwrap();
+        assert_value(&path, 3);
+        assert!(!replacement.offsets_path().exists());
+        assert!(!path.with_extension(REPLACEMENT_CONFIG_FILE_EXTENSION).exists());
+    }
+
     #[test]
     fn test_writer() {
         let (col1, col2) = test_data(None);
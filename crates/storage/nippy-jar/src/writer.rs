@@ -1,4 +1,7 @@
-use crate::{compression::Compression, ColumnResult, NippyJar, NippyJarError, NippyJarHeader};
+use crate::{
+    compression::{ColumnCodec, Compressors, DictionarySamples},
+    ColumnResult, NippyJar, NippyJarError, NippyJarHeader,
+};
 use std::{
     cmp::Ordering,
     fs::{File, OpenOptions},
@@ -41,6 +44,8 @@ pub struct NippyJarWriter<H: NippyJarHeader = ()> {
     column: usize,
     /// Whether the writer has changed data that needs to be committed.
     dirty: bool,
+    /// Column value samples to retrain the zstd column dictionaries with, if there are any.
+    dictionary_samples: Option<DictionarySamples>,
 }
 
 impl<H: NippyJarHeader> NippyJarWriter<H> {
@@ -52,6 +57,12 @@ impl<H: NippyJarHeader> NippyJarWriter<H> {
         jar: NippyJar<H>,
         check_mode: ConsistencyFailStrategy,
     ) -> Result<Self, NippyJarError> {
+        if let Some(Compressors::Columns(codecs)) = &jar.compressor {
+            if codecs.len() != jar.columns {
+                return Err(NippyJarError::ColumnLenMismatch(jar.columns, codecs.len()))
+            }
+        }
+
         let (data_file, offsets_file, is_created) =
             Self::create_or_open_files(jar.data_path(), &jar.offsets_path())?;
 
@@ -67,7 +78,12 @@ impl<H: NippyJarHeader> NippyJarWriter<H> {
             offsets: Vec::with_capacity(1_000_000),
             column: 0,
             dirty: false,
+            dictionary_samples: None,
         };
+        if let Some(Compressors::Columns(codecs)) = &writer.jar.compressor {
+            writer.dictionary_samples =
+                Some(DictionarySamples::new(codecs)).filter(DictionarySamples::is_used);
+        }
 
         // If we are opening a previously created jar, we need to check its consistency, and make
         // changes if necessary.
@@ -315,9 +331,13 @@ impl<H: NippyJarHeader> NippyJarWriter<H> {
     /// Writes column to data file. If it's the last column of the row, call `finalize_row()`
     fn write_column(&mut self, value: &[u8]) -> Result<usize, NippyJarError> {
         self.uncompressed_row_size += value.len();
+        if let Some(samples) = &mut self.dictionary_samples {
+            samples.add(self.column, value);
+        }
+
         let len = if let Some(compression) = &self.jar.compressor {
             let before = self.tmp_buf.len();
-            let len = compression.compress_to(value, &mut self.tmp_buf)?;
+            let len = compression.compress_column_to(self.column, value, &mut self.tmp_buf)?;
             self.data_file.write_all(&self.tmp_buf[before..before + len])?;
             len
         } else {
@@ -434,6 +454,7 @@ impl<H: NippyJarHeader> NippyJarWriter<H> {
         self.data_file.get_ref().sync_all()?;
 
         self.commit_offsets()?;
+        self.train_dictionaries();
 
         // Flushes `max_row_size` and total `rows` to disk.
         self.jar.freeze_config()?;
@@ -447,6 +468,7 @@ impl<H: NippyJarHeader> NippyJarWriter<H> {
         self.data_file.flush()?;
 
         self.commit_offsets_without_sync_all()?;
+        self.train_dictionaries();
 
         // Flushes `max_row_size` and total `rows` to disk.
         self.jar.freeze_config()?;
@@ -455,6 +477,16 @@ impl<H: NippyJarHeader> NippyJarWriter<H> {
         Ok(())
     }
 
+    /// Trains new zstd column dictionaries from the appended values, if the jar has grown enough
+    /// since their last training.
+    fn train_dictionaries(&mut self) {
+        if let (Some(samples), Some(Compressors::Columns(codecs))) =
+            (&mut self.dictionary_samples, &mut self.jar.compressor)
+        {
+            ColumnCodec::train_dictionaries(codecs, samples, self.jar.rows, false);
+        }
+    }
+
     /// Flushes offsets to disk.
     pub(crate) fn commit_offsets(&mut self) -> Result<(), NippyJarError> {
         self.commit_offsets_inner()?;
@@ -1,18 +1,20 @@
 use crate::{
-    providers::StaticFileProvider, AccountReader, BlockHashReader, BlockIdReader, BlockNumReader,
-    BlockReader, BlockReaderIdExt, BlockSource, CanonChainTracker, CanonStateNotifications,
-    CanonStateSubscriptions, ChainSpecProvider, ChangeSetReader, DatabaseProviderFactory,
-    DatabaseProviderRO, EvmEnvProvider, FinalizedBlockReader, HeaderProvider, ProviderError,
-    ProviderFactory, PruneCheckpointReader, ReceiptProvider, ReceiptProviderIdExt,
-    RequestsProvider, StageCheckpointReader, StateProviderBox, StateProviderFactory,
-    StaticFileProviderFactory, TransactionVariant, TransactionsProvider, WithdrawalsProvider,
+    providers::StaticFileProvider, to_range, AccountHistoryReader, AccountReader, BadBlockReader,
+    BlockHashReader, BlockIdReader, BlockNumReader, BlockReader, BlockReaderIdExt, BlockSource,
+    CanonChainTracker, CanonStateNotifications, CanonStateSubscriptions, ChainSpecProvider,
+    ChangeSetReader, DatabaseProviderFactory, DatabaseProviderRO, EvmEnvProvider,
+    FinalizedBlockReader, HeaderProvider, ProviderError, ProviderFactory, PruneCheckpointReader,
+    ReceiptProvider, ReceiptProviderIdExt, RequestsProvider, StageCheckpointReader,
+    StateProviderBox, StateProviderFactory, StaticFileProviderFactory, TableDigest,
+    TableDigestCursor, TableDigestReader, TransactionVariant, TransactionsProvider,
+    WithdrawalsProvider,
 };
 use alloy_rpc_types_engine::ForkchoiceState;
 use reth_chain_state::{BlockState, CanonicalInMemoryState, MemoryOverlayStateProvider};
 use reth_chainspec::{ChainInfo, ChainSpec};
 use reth_db_api::{
     database::Database,
-    models::{AccountBeforeTx, StoredBlockBodyIndices},
+    models::{AccountBeforeTx, StoredBadBlock, StoredBlockBodyIndices},
 };
 use reth_evm::ConfigureEvmEnv;
 use reth_primitives::{
@@ -1215,6 +1217,92 @@ where
     }
 }
 
+impl<DB> AccountHistoryReader for BlockchainProvider2<DB>
+where
+    DB: Database,
+{
+    fn account_history_blocks(
+        &self,
+        address: Address,
+        range: impl RangeBounds<BlockNumber>,
+        limit: usize,
+        reverse: bool,
+    ) -> ProviderResult<Vec<BlockNumber>> {
+        let range = to_range(range);
+
+        // Blocks that are not persisted yet are only in memory, on top of the database blocks.
+        let mut database_range = range.clone();
+        let mut in_memory = Vec::new();
+        if let Some(head) = self.canonical_in_memory_state.head_state() {
+            database_range.end = database_range.end.min(head.anchor().number + 1);
+            for block_state in head.chain() {
+                let block = block_state.block();
+                let changed =
+                    block.execution_outcome().bundle.account(&address).is_some_and(|account| {
+                        account.is_info_changed() ||
+                            account.storage.values().any(|slot| slot.is_changed())
+                    });
+                if changed && range.contains(&block.block().number) {
+                    in_memory.push(block.block().number);
+                }
+            }
+        }
+
+        // The in-memory chain is ordered from the newest to the oldest block.
+        if reverse {
+            in_memory.truncate(limit);
+            let remaining = limit - in_memory.len();
+            if remaining > 0 {
+                in_memory.extend(self.database.provider()?.account_history_blocks(
+                    address,
+                    database_range,
+                    remaining,
+                    true,
+                )?);
+            }
+            Ok(in_memory)
+        } else {
+            let mut blocks = self.database.provider()?.account_history_blocks(
+                address,
+                database_range,
+                limit,
+                false,
+            )?;
+            let remaining = limit - blocks.len();
+            blocks.extend(in_memory.into_iter().rev().take(remaining));
+            Ok(blocks)
+        }
+    }
+}
+
+impl<DB> BadBlockReader for BlockchainProvider2<DB>
+where
+    DB: Database,
+{
+    fn bad_blocks(&self) -> ProviderResult<Vec<StoredBadBlock>> {
+        self.database.provider()?.bad_blocks()
+    }
+
+    fn bad_block(&self, hash: BlockHash) -> ProviderResult<Option<StoredBadBlock>> {
+        self.database.provider()?.bad_block(hash)
+    }
+}
+
+impl<DB> TableDigestReader for BlockchainProvider2<DB>
+where
+    DB: Database,
+{
+    fn table_digest(
+        &self,
+        table: &str,
+        start: Option<&TableDigestCursor>,
+        end: Option<&TableDigestCursor>,
+        limit: u64,
+    ) -> ProviderResult<TableDigest> {
+        self.database.provider()?.table_digest(table, start, end, limit)
+    }
+}
+
 impl<DB> AccountReader for BlockchainProvider2<DB>
 where
     DB: Database + Sync + Send,
@@ -1226,3 +1314,75 @@ where
         state_provider.basic_account(address)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        test_utils::{blocks::BlockchainTestData, create_test_provider_factory},
+        BlockWriter,
+    };
+    use reth_chain_state::{ExecutedBlock, NewCanonicalChain};
+    use std::sync::Arc;
+
+    #[test]
+    fn account_history_blocks_with_in_memory_blocks() {
+        let factory = create_test_provider_factory();
+        let data = BlockchainTestData::default();
+        let (database_blocks, in_memory_blocks) = data.blocks.split_at(3);
+
+        // Blocks 1 to 3 are persisted, 4 and 5 are only in memory.
+        let provider_rw = factory.provider_rw().unwrap();
+        provider_rw.insert_historical_block(data.genesis.seal_with_senders().unwrap()).unwrap();
+        let mut execution_outcome = database_blocks[0].1.clone();
+        for (_, outcome) in &database_blocks[1..] {
+            execution_outcome.extend(outcome.clone());
+        }
+        provider_rw
+            .append_blocks_with_state(
+                database_blocks.iter().map(|(block, _)| block.clone()).collect(),
+                execution_outcome,
+                Default::default(),
+                Default::default(),
+            )
+            .unwrap();
+        provider_rw.commit().unwrap();
+
+        let provider = BlockchainProvider2::new(factory).unwrap();
+        let in_memory_state = provider.canonical_in_memory_state();
+        in_memory_state.update_chain(NewCanonicalChain::Commit {
+            new: in_memory_blocks
+                .iter()
+                .map(|(block, outcome)| {
+                    ExecutedBlock::new(
+                        Arc::new(block.block.clone()),
+                        Arc::new(block.senders.clone()),
+                        Arc::new(outcome.clone()),
+                        Default::default(),
+                        Default::default(),
+                    )
+                })
+                .collect(),
+        });
+        in_memory_state.set_canonical_head(in_memory_blocks[1].0.header.clone());
+
+        let blocks = |address, range: std::ops::Range<u64>, limit, reverse| {
+            provider.account_history_blocks(address, range, limit, reverse).unwrap()
+        };
+
+        // Changed in blocks 1 and 2 only.
+        let account = Address::new([0x60; 20]);
+        assert_eq!(blocks(account, 0..u64::MAX, usize::MAX, false), vec![1, 2]);
+        assert_eq!(blocks(account, 0..u64::MAX, usize::MAX, true), vec![2, 1]);
+
+        // Changed in blocks 3 to 5.
+        let account = Address::with_last_byte(2);
+        assert_eq!(blocks(account, 0..u64::MAX, usize::MAX, false), vec![3, 4, 5]);
+        assert_eq!(blocks(account, 0..u64::MAX, 2, false), vec![3, 4]);
+        assert_eq!(blocks(account, 4..u64::MAX, usize::MAX, false), vec![4, 5]);
+        assert_eq!(blocks(account, 0..u64::MAX, usize::MAX, true), vec![5, 4, 3]);
+        assert_eq!(blocks(account, 0..u64::MAX, 1, true), vec![5]);
+        assert_eq!(blocks(account, 0..5, 2, true), vec![4, 3]);
+        assert_eq!(blocks(account, 0..4, usize::MAX, true), vec![3]);
+    }
+}
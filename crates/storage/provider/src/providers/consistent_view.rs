@@ -21,13 +21,20 @@ pub use reth_storage_errors::provider::ConsistentViewError;
 /// 1) have a failover for when the state changes and handle [`ConsistentViewError::Inconsistent`]
 ///    appropriately.
 /// 2) be sure that the state does not change.
-#[derive(Clone, Debug)]
+#[derive(Debug)]
 pub struct ConsistentDbView<DB, Provider> {
     database: PhantomData<DB>,
     provider: Provider,
     tip: Option<B256>,
 }
 
+// Manual impl, the derive would require `DB: Clone`.
+impl<DB, Provider: Clone> Clone for ConsistentDbView<DB, Provider> {
+    fn clone(&self) -> Self {
+        Self { database: PhantomData, provider: self.provider.clone(), tip: self.tip }
+    }
+}
+
 impl<DB, Provider> ConsistentDbView<DB, Provider>
 where
     DB: Database,
@@ -1,5 +1,8 @@
 use crate::{
-    providers::{state::latest::LatestStateProvider, StaticFileProvider},
+    providers::{
+        state::latest::LatestStateProvider, LowestAvailableAddressBlocksCache, RevertStateCache,
+        StaticFileProvider,
+    },
     to_range,
     traits::{BlockSource, ReceiptProvider},
     BlockHashReader, BlockNumReader, BlockReader, ChainSpecProvider, DatabaseProviderFactory,
@@ -48,6 +51,10 @@ pub struct ProviderFactory<DB> {
     static_file_provider: StaticFileProvider,
     /// Optional pruning configuration
     prune_modes: PruneModes,
+    /// Cache of the revert states used by historical state providers
+    revert_state_cache: RevertStateCache,
+    /// Cache of the lowest available blocks of addresses with their own prune modes
+    lowest_available_address_blocks_cache: LowestAvailableAddressBlocksCache,
 }
 
 impl<DB> ProviderFactory<DB> {
@@ -57,7 +64,14 @@ impl<DB> ProviderFactory<DB> {
         chain_spec: Arc<ChainSpec>,
         static_file_provider: StaticFileProvider,
     ) -> Self {
-        Self { db: Arc::new(db), chain_spec, static_file_provider, prune_modes: PruneModes::none() }
+        Self {
+            db: Arc::new(db),
+            chain_spec,
+            static_file_provider,
+            prune_modes: PruneModes::none(),
+            revert_state_cache: RevertStateCache::default(),
+            lowest_available_address_blocks_cache: Default::default(),
+        }
     }
 
     /// Enables metrics on the static file provider.
@@ -69,6 +83,13 @@ impl<DB> ProviderFactory<DB> {
     /// Sets the pruning configuration for an existing [`ProviderFactory`].
     pub fn with_prune_modes(mut self, prune_modes: PruneModes) -> Self {
         self.prune_modes = prune_modes;
+        self.lowest_available_address_blocks_cache = Default::default();
+        self
+    }
+
+    /// Sets the number of revert states cached for the historical state providers.
+    pub fn with_revert_state_cache_size(mut self, capacity: usize) -> Self {
+        self.revert_state_cache = RevertStateCache::new(capacity);
         self
     }
 
@@ -98,6 +119,8 @@ impl ProviderFactory<DatabaseEnv> {
             chain_spec,
             static_file_provider,
             prune_modes: PruneModes::none(),
+            revert_state_cache: RevertStateCache::default(),
+            lowest_available_address_blocks_cache: Default::default(),
         })
     }
 }
@@ -145,7 +168,13 @@ impl<DB: Database> ProviderFactory<DB> {
         &self,
         block_number: BlockNumber,
     ) -> ProviderResult<StateProviderBox> {
-        let state_provider = self.provider()?.state_provider_by_block_number(block_number)?;
+        let state_provider = self
+            .provider()?
+            .with_revert_state_cache(self.revert_state_cache.clone())
+            .with_lowest_available_address_blocks_cache(
+                self.lowest_available_address_blocks_cache.clone(),
+            )
+            .state_provider_by_block_number(block_number)?;
         trace!(target: "providers::db", ?block_number, "Returning historical state provider for block number");
         Ok(state_provider)
     }
@@ -158,7 +187,13 @@ impl<DB: Database> ProviderFactory<DB> {
             .block_number(block_hash)?
             .ok_or(ProviderError::BlockHashNotFound(block_hash))?;
 
-        let state_provider = self.provider()?.state_provider_by_block_number(block_number)?;
+        let state_provider = self
+            .provider()?
+            .with_revert_state_cache(self.revert_state_cache.clone())
+            .with_lowest_available_address_blocks_cache(
+                self.lowest_available_address_blocks_cache.clone(),
+            )
+            .state_provider_by_block_number(block_number)?;
         trace!(target: "providers::db", ?block_number, %block_hash, "Returning historical state provider for block hash");
         Ok(state_provider)
     }
@@ -598,6 +633,10 @@ impl<DB> Clone for ProviderFactory<DB> {
             chain_spec: self.chain_spec.clone(),
             static_file_provider: self.static_file_provider.clone(),
             prune_modes: self.prune_modes.clone(),
+            revert_state_cache: self.revert_state_cache.clone(),
+            lowest_available_address_blocks_cache: self
+                .lowest_available_address_blocks_cache
+                .clone(),
         }
     }
 }
@@ -607,7 +646,9 @@ mod tests {
     use crate::{
         providers::{StaticFileProvider, StaticFileWriter},
         test_utils::create_test_provider_factory,
-        BlockHashReader, BlockNumReader, BlockWriter, HeaderSyncGapProvider, TransactionsProvider,
+        AccountHistoryReader, BadBlockReader, BadBlockWriter, BlockHashReader, BlockNumReader,
+        BlockWriter, HeaderSyncGapProvider, TableDigest, TableDigestCursor, TableDigestReader,
+        TransactionsProvider, MAX_ACCOUNT_HISTORY_SLOTS, MAX_BAD_BLOCKS,
     };
     use alloy_rlp::Decodable;
     use assert_matches::assert_matches;
@@ -617,8 +658,16 @@ mod tests {
         mdbx::DatabaseArguments,
         tables,
         test_utils::{create_test_static_files_dir, ERROR_TEMPDIR},
+        BlockNumberList, RawKey, RawTable, RawValue,
+    };
+    use reth_db_api::{
+        models::{storage_sharded_key::StorageShardedKey, ShardedKey, StoredBadBlock},
+        transaction::{DbTx, DbTxMut},
+    };
+    use reth_primitives::{
+        hex_literal::hex, Address, SealedBlock, StaticFileSegment, StorageEntry, TxNumber, B256,
+        U256,
     };
-    use reth_primitives::{hex_literal::hex, SealedBlock, StaticFileSegment, TxNumber, B256, U256};
     use reth_prune_types::{PruneMode, PruneModes};
     use reth_storage_errors::provider::ProviderError;
     use reth_testing_utils::{
@@ -782,4 +831,239 @@ mod tests {
         assert_eq!(gap.local_head, head);
         assert_eq!(gap.target.tip(), consensus_tip.into());
     }
+
+    #[test]
+    fn account_history_blocks() {
+        let factory = create_test_provider_factory();
+        let address = Address::with_last_byte(1);
+        let other = Address::with_last_byte(2);
+        let slot = B256::with_last_byte(1);
+
+        let provider = factory.provider_rw().unwrap();
+        let tx = provider.tx_ref();
+        tx.put::<tables::AccountsHistory>(
+            ShardedKey::new(address, 4),
+            BlockNumberList::new_pre_sorted([1, 4]),
+        )
+        .unwrap();
+        tx.put::<tables::AccountsHistory>(
+            ShardedKey::new(address, u64::MAX),
+            BlockNumberList::new_pre_sorted([7, 10]),
+        )
+        .unwrap();
+        tx.put::<tables::AccountsHistory>(
+            ShardedKey::new(other, u64::MAX),
+            BlockNumberList::new_pre_sorted([2, 3]),
+        )
+        .unwrap();
+        tx.put::<tables::StoragesHistory>(
+            StorageShardedKey::new(address, B256::ZERO, u64::MAX),
+            BlockNumberList::new_pre_sorted([4, 5]),
+        )
+        .unwrap();
+        tx.put::<tables::StoragesHistory>(
+            StorageShardedKey::new(address, slot, 2),
+            BlockNumberList::new_pre_sorted([2]),
+        )
+        .unwrap();
+        tx.put::<tables::StoragesHistory>(
+            StorageShardedKey::new(address, slot, u64::MAX),
+            BlockNumberList::new_pre_sorted([8, 12]),
+        )
+        .unwrap();
+        tx.put::<tables::StoragesHistory>(
+            StorageShardedKey::new(other, B256::ZERO, u64::MAX),
+            BlockNumberList::new_pre_sorted([6]),
+        )
+        .unwrap();
+        provider.commit().unwrap();
+
+        let provider = factory.provider().unwrap();
+        let blocks = |address, range: std::ops::Range<u64>, limit, reverse| {
+            provider.account_history_blocks(address, range, limit, reverse).unwrap()
+        };
+        assert_eq!(blocks(address, 0..u64::MAX, usize::MAX, false), vec![1, 2, 4, 5, 7, 8, 10, 12]);
+        assert_eq!(blocks(address, 3..9, usize::MAX, false), vec![4, 5, 7, 8]);
+        assert_eq!(blocks(address, 0..3, usize::MAX, false), vec![1, 2]);
+        assert_eq!(blocks(address, 11..u64::MAX, usize::MAX, false), vec![12]);
+        assert_eq!(blocks(address, 5..5, usize::MAX, false), Vec::<u64>::new());
+        assert_eq!(blocks(other, 0..u64::MAX, usize::MAX, false), vec![2, 3, 6]);
+        assert_eq!(
+            blocks(Address::with_last_byte(3), 0..u64::MAX, usize::MAX, false),
+            Vec::<u64>::new()
+        );
+
+        // limited
+        assert_eq!(blocks(address, 0..u64::MAX, 3, false), vec![1, 2, 4]);
+        assert_eq!(blocks(address, 5..u64::MAX, 2, false), vec![5, 7]);
+        assert_eq!(blocks(address, 0..u64::MAX, 0, false), Vec::<u64>::new());
+
+        // reverse
+        assert_eq!(blocks(address, 0..u64::MAX, usize::MAX, true), vec![12, 10, 8, 7, 5, 4, 2, 1]);
+        assert_eq!(blocks(address, 0..u64::MAX, 3, true), vec![12, 10, 8]);
+        assert_eq!(blocks(address, 3..8, usize::MAX, true), vec![7, 5, 4]);
+        assert_eq!(blocks(address, 0..7, 2, true), vec![5, 4]);
+        assert_eq!(blocks(address, 0..2, usize::MAX, true), vec![1]);
+        assert_eq!(blocks(other, 0..u64::MAX, usize::MAX, true), vec![6, 3, 2]);
+        drop(provider);
+
+        // too many storage slots with history
+        let provider = factory.provider_rw().unwrap();
+        for slot in 0..MAX_ACCOUNT_HISTORY_SLOTS as u64 {
+            provider
+                .tx_ref()
+                .put::<tables::StoragesHistory>(
+                    StorageShardedKey::new(other, B256::from(U256::from(slot)), u64::MAX),
+                    BlockNumberList::new_pre_sorted([6]),
+                )
+                .unwrap();
+        }
+        provider.commit().unwrap();
+        let provider = factory.provider().unwrap();
+        assert_eq!(provider.account_history_blocks(other, .., 1, false), Ok(vec![2]));
+
+        let provider = factory.provider_rw().unwrap();
+        provider
+            .tx_ref()
+            .put::<tables::StoragesHistory>(
+                StorageShardedKey::new(other, B256::repeat_byte(0xff), u64::MAX),
+                BlockNumberList::new_pre_sorted([6]),
+            )
+            .unwrap();
+        provider.commit().unwrap();
+        let provider = factory.provider().unwrap();
+        assert_eq!(
+            provider.account_history_blocks(other, .., 1, false),
+            Err(ProviderError::TooManyHistorySlots {
+                address: other,
+                max: MAX_ACCOUNT_HISTORY_SLOTS
+            })
+        );
+    }
+
+    #[test]
+    fn bad_blocks() {
+        let factory = create_test_provider_factory();
+        let mut rng = generators::rng();
+
+        let provider = factory.provider_rw().unwrap();
+        // the blocks are saved by descending block number
+        let bad_blocks = (0..MAX_BAD_BLOCKS as u64 + 2)
+            .rev()
+            .map(|number| {
+                StoredBadBlock::new(
+                    random_block(&mut rng, number, None, Some(0), None),
+                    format!("bad block {number}"),
+                    B256::random(),
+                )
+            })
+            .collect::<Vec<_>>();
+        for bad_block in bad_blocks.iter().cloned() {
+            provider.insert_bad_block(bad_block).unwrap();
+        }
+        let bad_blocks = bad_blocks
+            .into_iter()
+            .enumerate()
+            .map(|(sequence, bad_block)| StoredBadBlock { sequence: sequence as u64, ..bad_block })
+            .collect::<Vec<_>>();
+
+        // bad blocks that can't be decoded are skipped
+        let undecodable = B256::random();
+        provider
+            .tx_ref()
+            .put::<RawTable<tables::BadBlocks>>(
+                RawKey::new(undecodable),
+                RawValue::from_vec(vec![1, 2, 3]),
+            )
+            .unwrap();
+        provider.commit().unwrap();
+
+        // the first saved blocks are evicted, the rest is ordered by descending sequence
+        let provider = factory.provider().unwrap();
+        let expected = bad_blocks.iter().skip(2).rev().cloned().collect::<Vec<_>>();
+        assert_eq!(provider.bad_blocks().unwrap(), expected);
+        assert_eq!(provider.bad_block(bad_blocks[0].block.hash()).unwrap(), None);
+        assert_eq!(
+            provider.bad_block(bad_blocks[2].block.hash()).unwrap(),
+            Some(bad_blocks[2].clone())
+        );
+        assert!(provider.bad_block(undecodable).is_err());
+
+        // saving a bad block drops the ones that can't be decoded
+        let provider = factory.provider_rw().unwrap();
+        let bad_block = StoredBadBlock::new(
+            random_block(&mut rng, 0, None, Some(0), None),
+            "bad block".to_string(),
+            B256::random(),
+        );
+        provider.insert_bad_block(bad_block.clone()).unwrap();
+        assert_eq!(provider.tx_ref().entries::<tables::BadBlocks>().unwrap(), MAX_BAD_BLOCKS);
+        assert_eq!(
+            provider.bad_blocks().unwrap().first(),
+            Some(&StoredBadBlock { sequence: MAX_BAD_BLOCKS as u64 + 2, ..bad_block })
+        );
+    }
+
+    #[test]
+    fn table_digest() {
+        let factory = create_test_provider_factory();
+
+        let provider = factory.provider_rw().unwrap();
+        for address in 1..=3 {
+            for slot in 1..=2 {
+                let entry =
+                    StorageEntry { key: B256::with_last_byte(slot), value: U256::from(slot) };
+                provider
+                    .tx_ref()
+                    .put::<tables::PlainStorageState>(Address::with_last_byte(address), entry)
+                    .unwrap();
+            }
+        }
+        provider.commit().unwrap();
+
+        let provider = factory.provider().unwrap();
+        let table = tables::Tables::PlainStorageState.name();
+        let full = provider.table_digest(table, None, None, u64::MAX).unwrap();
+        assert_eq!(full.entries, 6);
+        assert_eq!(full.next, None);
+
+        // the limit is enforced within the duplicate entries of a key
+        let second = Address::with_last_byte(2);
+        let limited = provider.table_digest(table, None, None, 3).unwrap();
+        assert_eq!(limited.entries, 3);
+        let next = limited.next.clone().unwrap();
+        assert_eq!(&next.key[..], second.as_slice());
+        assert!(next.subkey.is_some());
+        assert_eq!(
+            provider.table_digest(table, None, Some(&next), u64::MAX),
+            Ok(TableDigest { next: None, ..limited })
+        );
+
+        let rest = provider.table_digest(table, Some(&next), None, u64::MAX).unwrap();
+        assert_eq!(rest.entries, 3);
+        assert_eq!(rest.next, None);
+        assert_ne!(rest.digest, full.digest);
+
+        // resuming from the cursors digests every entry exactly once
+        let mut start = None;
+        let mut entries = 0;
+        loop {
+            let digest = provider.table_digest(table, start.as_ref(), None, 1).unwrap();
+            entries += digest.entries;
+            match digest.next {
+                Some(next) => start = Some(next),
+                None => break,
+            }
+        }
+        assert_eq!(entries, 6);
+
+        let third = TableDigestCursor::from_key(Address::with_last_byte(3).to_vec());
+        let last = provider.table_digest(table, Some(&third), None, u64::MAX).unwrap();
+        assert_eq!(last.entries, 2);
+
+        assert_eq!(
+            provider.table_digest("NoSuchTable", None, None, u64::MAX),
+            Err(ProviderError::UnknownTable("NoSuchTable".to_string()))
+        );
+    }
 }
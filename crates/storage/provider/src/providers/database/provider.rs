@@ -1,25 +1,31 @@
 use crate::{
     bundle_state::StorageRevertsIter,
-    providers::{database::metrics, static_file::StaticFileWriter, StaticFileProvider},
+    providers::{
+        database::metrics, static_file::StaticFileWriter, LowestAvailableAddressBlocks,
+        LowestAvailableAddressBlocksCache, RevertStateCache, StaticFileProvider,
+    },
     to_range,
     traits::{
-        AccountExtReader, BlockSource, ChangeSetReader, ReceiptProvider, StageCheckpointWriter,
+        AccountExtReader, AccountHistoryReader, BlockSource, ChangeSetReader, ReceiptProvider,
+        StageCheckpointWriter, MAX_ACCOUNT_HISTORY_SLOTS,
     },
     writer::UnifiedStorageWriter,
-    AccountReader, BlockExecutionReader, BlockExecutionWriter, BlockHashReader, BlockNumReader,
-    BlockReader, BlockWriter, BundleStateInit, EvmEnvProvider, FinalizedBlockReader,
-    FinalizedBlockWriter, HashingWriter, HeaderProvider, HeaderSyncGap, HeaderSyncGapProvider,
-    HistoricalStateProvider, HistoryWriter, LatestStateProvider, OriginalValuesKnown,
-    ProviderError, PruneCheckpointReader, PruneCheckpointWriter, RequestsProvider, RevertsInit,
-    StageCheckpointReader, StateChangeWriter, StateProviderBox, StateWriter, StatsReader,
-    StorageReader, StorageTrieWriter, TransactionVariant, TransactionsProvider,
-    TransactionsProviderExt, TrieWriter, WithdrawalsProvider,
+    AccountReader, BadBlockReader, BadBlockWriter, BlockExecutionReader, BlockExecutionWriter,
+    BlockHashReader, BlockNumReader, BlockReader, BlockWriter, BundleStateInit, EvmEnvProvider,
+    FinalizedBlockReader, FinalizedBlockWriter, HashingWriter, HeaderProvider, HeaderSyncGap,
+    HeaderSyncGapProvider, HistoricalStateProvider, HistoryWriter, LatestStateProvider,
+    OriginalValuesKnown, ProviderError, PruneCheckpointReader, PruneCheckpointWriter,
+    RequestsProvider, RevertsInit, StageCheckpointReader, StateChangeWriter, StateProviderBox,
+    StateWriter, StatsReader, StorageReader, StorageTrieWriter, TableDigest, TableDigestCursor,
+    TableDigestHasher, TableDigestReader, TransactionVariant, TransactionsProvider,
+    TransactionsProviderExt, TrieWriter, WithdrawalsProvider, MAX_BAD_BLOCKS,
 };
 use itertools::{izip, Itertools};
 use rayon::slice::ParallelSliceMut;
 use reth_chainspec::{ChainInfo, ChainSpec, EthereumHardforks};
 use reth_db::{
     cursor::DbDupCursorRW, tables, BlockNumberList, PlainAccountState, PlainStorageState,
+    RawDupSort, RawKey, RawTable, RawValue, TableViewer, Tables,
 };
 use reth_db_api::{
     common::KeyValue,
@@ -27,9 +33,10 @@ use reth_db_api::{
     database::Database,
     models::{
         sharded_key, storage_sharded_key::StorageShardedKey, AccountBeforeTx, BlockNumberAddress,
-        ShardedKey, StoredBlockBodyIndices, StoredBlockOmmers, StoredBlockWithdrawals,
+        ShardedKey, StoredBadBlock, StoredBlockBodyIndices, StoredBlockOmmers,
+        StoredBlockWithdrawals,
     },
-    table::{Table, TableRow},
+    table::{DupSort, Key, Table, TableRow, Value},
     transaction::{DbTx, DbTxMut},
     DatabaseError,
 };
@@ -37,13 +44,16 @@ use reth_evm::ConfigureEvmEnv;
 use reth_execution_types::{Chain, ExecutionOutcome};
 use reth_network_p2p::headers::downloader::SyncTarget;
 use reth_primitives::{
-    keccak256, Account, Address, Block, BlockHash, BlockHashOrNumber, BlockNumber,
-    BlockWithSenders, Bytecode, GotExpected, Header, Receipt, Requests, SealedBlock,
+    keccak256, Account, Address, Block, BlockHash, BlockHashOrNumber, BlockNumHash, BlockNumber,
+    BlockWithSenders, Bytecode, Bytes, GotExpected, Header, Receipt, Requests, SealedBlock,
     SealedBlockWithSenders, SealedHeader, StaticFileSegment, StorageEntry, TransactionMeta,
     TransactionSigned, TransactionSignedEcRecovered, TransactionSignedNoHash, TxHash, TxNumber,
     Withdrawal, Withdrawals, B256, U256,
 };
-use reth_prune_types::{PruneCheckpoint, PruneLimiter, PruneModes, PruneSegment};
+use reth_prune_types::{
+    HistoryPruneConfig, PruneCheckpoint, PruneLimiter, PruneMode, PruneModes, PrunePurpose,
+    PruneSegment,
+};
 use reth_stages_types::{StageCheckpoint, StageId};
 use reth_storage_errors::provider::{ProviderResult, RootMismatch};
 use reth_trie::{
@@ -57,8 +67,8 @@ use revm::{
     primitives::{BlockEnv, CfgEnvWithHandlerCfg},
 };
 use std::{
-    cmp::Ordering,
-    collections::{hash_map, BTreeMap, BTreeSet, HashMap, HashSet},
+    cmp::{Ordering, Reverse},
+    collections::{hash_map, BTreeMap, BTreeSet, BinaryHeap, HashMap, HashSet},
     fmt::Debug,
     ops::{Bound, Deref, DerefMut, Range, RangeBounds, RangeInclusive},
     sync::{mpsc, Arc},
@@ -115,6 +125,10 @@ pub struct DatabaseProvider<TX> {
     static_file_provider: StaticFileProvider,
     /// Pruning configuration
     prune_modes: PruneModes,
+    /// Cache of the revert states used by historical state providers
+    revert_state_cache: Option<RevertStateCache>,
+    /// Cache of the lowest available blocks of addresses with their own prune modes
+    lowest_available_address_blocks_cache: Option<LowestAvailableAddressBlocksCache>,
 }
 
 impl<TX> DatabaseProvider<TX> {
@@ -127,6 +141,22 @@ impl<TX> DatabaseProvider<TX> {
     pub const fn prune_modes_ref(&self) -> &PruneModes {
         &self.prune_modes
     }
+
+    /// Sets the cache of the revert states used by the historical state providers.
+    pub fn with_revert_state_cache(mut self, revert_state_cache: RevertStateCache) -> Self {
+        self.revert_state_cache = Some(revert_state_cache);
+        self
+    }
+
+    /// Sets the cache of the lowest available blocks of addresses with their own prune modes used
+    /// by the historical state providers.
+    pub fn with_lowest_available_address_blocks_cache(
+        mut self,
+        cache: LowestAvailableAddressBlocksCache,
+    ) -> Self {
+        self.lowest_available_address_blocks_cache = Some(cache);
+        self
+    }
 }
 
 impl<TX: DbTxMut> DatabaseProvider<TX> {
@@ -137,7 +167,14 @@ impl<TX: DbTxMut> DatabaseProvider<TX> {
         static_file_provider: StaticFileProvider,
         prune_modes: PruneModes,
     ) -> Self {
-        Self { tx, chain_spec, static_file_provider, prune_modes }
+        Self {
+            tx,
+            chain_spec,
+            static_file_provider,
+            prune_modes,
+            revert_state_cache: None,
+            lowest_available_address_blocks_cache: None,
+        }
     }
 }
 
@@ -160,9 +197,14 @@ impl<TX: DbTx + 'static> DatabaseProvider<TX> {
             self.get_prune_checkpoint(PruneSegment::AccountHistory)?;
         let storage_history_prune_checkpoint =
             self.get_prune_checkpoint(PruneSegment::StorageHistory)?;
+        let lowest_available_address_blocks = self.lowest_available_address_blocks()?;
 
         let mut state_provider =
-            HistoricalStateProvider::new(self.tx, block_number, self.static_file_provider);
+            HistoricalStateProvider::new(self.tx, block_number, self.static_file_provider)
+                .with_lowest_available_address_blocks(lowest_available_address_blocks);
+        if let Some(revert_state_cache) = self.revert_state_cache {
+            state_provider = state_provider.with_revert_state_cache(revert_state_cache);
+        }
 
         // If we pruned account or storage history, we can't return state on every historical block.
         // Instead, we should cap it at the latest prune checkpoint for corresponding prune segment.
@@ -183,6 +225,77 @@ impl<TX: DbTx + 'static> DatabaseProvider<TX> {
 
         Ok(Box::new(state_provider))
     }
+
+    /// Returns the lowest blocks at which the history of addresses pruned according to their own
+    /// prune modes is available, calculated from the best block.
+    ///
+    /// The blocks are taken from the [`LowestAvailableAddressBlocksCache`] if the provider has
+    /// one and they were already resolved on top of the best block.
+    fn lowest_available_address_blocks(&self) -> ProviderResult<Arc<LowestAvailableAddressBlocks>> {
+        let account_history_filter = self.prune_modes.account_history.is_some() &&
+            !self.prune_modes.account_history_filter.is_empty();
+        let storage_history_filter = self.prune_modes.storage_history.is_some() &&
+            !self.prune_modes.storage_history_filter.is_empty();
+        if !account_history_filter && !storage_history_filter {
+            return Ok(Default::default())
+        }
+
+        let tip = self.best_block_number()?;
+        let Some(cache) = &self.lowest_available_address_blocks_cache else {
+            return self.resolve_lowest_available_address_blocks(tip).map(Arc::new)
+        };
+        let tip_hash =
+            self.block_hash(tip)?.ok_or_else(|| ProviderError::HeaderNotFound(tip.into()))?;
+        cache.get_or_try_insert_with(BlockNumHash::new(tip, tip_hash), || {
+            self.resolve_lowest_available_address_blocks(tip)
+        })
+    }
+
+    /// Resolves the lowest blocks at which the history of addresses pruned according to their
+    /// own prune modes is available on top of the given tip.
+    fn resolve_lowest_available_address_blocks(
+        &self,
+        tip: BlockNumber,
+    ) -> ProviderResult<LowestAvailableAddressBlocks> {
+        let lowest_available_blocks = |mode: Option<PruneMode>,
+                                       filter: &HistoryPruneConfig,
+                                       segment: PruneSegment|
+         -> ProviderResult<HashMap<_, _>> {
+            // Address filters are only used if the segment is pruned
+            if mode.is_none() {
+                return Ok(HashMap::default())
+            }
+            let filter = filter.resolve_durations(
+                tip,
+                segment.min_blocks(PrunePurpose::User),
+                |block_number| {
+                    self.header_by_number(block_number)?
+                        .map(|header| header.timestamp)
+                        .ok_or_else(|| ProviderError::HeaderNotFound(block_number.into()))
+                },
+            )?;
+            Ok(filter
+                .prune_target_blocks(tip, segment)
+                // An invalid prune mode fails the pruner, so nothing is pruned
+                .unwrap_or_default()
+                .into_iter()
+                .map(|(address, block_number)| (address, block_number.map(|number| number + 1)))
+                .collect())
+        };
+
+        Ok(LowestAvailableAddressBlocks {
+            account_history: lowest_available_blocks(
+                self.prune_modes.account_history,
+                &self.prune_modes.account_history_filter,
+                PruneSegment::AccountHistory,
+            )?,
+            storage_history: lowest_available_blocks(
+                self.prune_modes.storage_history,
+                &self.prune_modes.storage_history_filter,
+                PruneSegment::StorageHistory,
+            )?,
+        })
+    }
 }
 
 impl<DB: Database> DatabaseProviderRW<DB> {
@@ -275,7 +388,14 @@ impl<TX: DbTx> DatabaseProvider<TX> {
         static_file_provider: StaticFileProvider,
         prune_modes: PruneModes,
     ) -> Self {
-        Self { tx, chain_spec, static_file_provider, prune_modes }
+        Self {
+            tx,
+            chain_spec,
+            static_file_provider,
+            prune_modes,
+            revert_state_cache: None,
+            lowest_available_address_blocks_cache: None,
+        }
     }
 
     /// Consume `DbTx` or `DbTxMut`.
@@ -1742,18 +1862,163 @@ impl<TX: DbTx> ChangeSetReader for DatabaseProvider<TX> {
         &self,
         block_number: BlockNumber,
     ) -> ProviderResult<Vec<AccountBeforeTx>> {
-        let range = block_number..=block_number;
-        self.tx
-            .cursor_read::<tables::AccountChangeSets>()?
-            .walk_range(range)?
-            .map(|result| -> ProviderResult<_> {
-                let (_, account_before) = result?;
-                Ok(account_before)
-            })
-            .collect()
+        self.static_file_provider
+            .get_with_static_file_or_database(
+                StaticFileSegment::AccountChangeSets,
+                block_number,
+                |static_file| static_file.account_change_set(block_number),
+                || {
+                    let range = block_number..=block_number;
+                    self.tx
+                        .cursor_read::<tables::AccountChangeSets>()?
+                        .walk_range(range)?
+                        .map(|result| -> ProviderResult<_> {
+                            let (_, account_before) = result?;
+                            Ok(account_before)
+                        })
+                        .collect::<ProviderResult<Vec<_>>>()
+                        .map(Some)
+                },
+            )
+            .map(Option::unwrap_or_default)
     }
 }
 
+impl<TX: DbTx> AccountHistoryReader for DatabaseProvider<TX> {
+    fn account_history_blocks(
+        &self,
+        address: Address,
+        range: impl RangeBounds<BlockNumber>,
+        limit: usize,
+        reverse: bool,
+    ) -> ProviderResult<Vec<BlockNumber>> {
+        let range = to_range(range);
+        if range.is_empty() || limit == 0 {
+            return Ok(Vec::new())
+        }
+
+        let mut account_cursor = self.tx.cursor_read::<tables::AccountsHistory>()?;
+        let mut storage_cursor = self.tx.cursor_read::<tables::StoragesHistory>()?;
+
+        // Collect the storage slots of the account that have history, skipping their shards. Every
+        // slot is looked up for each returned block, so their number is capped.
+        let mut slots = Vec::new();
+        let mut entry = storage_cursor.seek(StorageShardedKey::new(address, B256::ZERO, 0))?;
+        while let Some((key, _)) = entry.filter(|(key, _)| key.address == address) {
+            if slots.len() == MAX_ACCOUNT_HISTORY_SLOTS {
+                return Err(ProviderError::TooManyHistorySlots {
+                    address,
+                    max: MAX_ACCOUNT_HISTORY_SLOTS,
+                })
+            }
+            let slot = key.sharded_key.key;
+            slots.push(slot);
+            entry = match U256::from_be_bytes(slot.0).checked_add(U256::from(1)) {
+                Some(next_slot) => {
+                    storage_cursor.seek(StorageShardedKey::new(address, next_slot.into(), 0))?
+                }
+                None => None,
+            };
+        }
+
+        // The histories of the account and its storage slots are merged, only the next block of
+        // each history is looked up at a time, so the walked shards are bounded by the limit.
+        // Source `0` is the account history, source `i` the history of the `i - 1`th slot.
+        let mut next_block = |source: usize, block: BlockNumber| -> ProviderResult<_> {
+            let next = if source == 0 {
+                history_block(
+                    &mut account_cursor,
+                    |block| ShardedKey::new(address, block),
+                    |key| key.key == address,
+                    block,
+                    reverse,
+                )?
+            } else {
+                let slot = slots[source - 1];
+                history_block(
+                    &mut storage_cursor,
+                    |block| StorageShardedKey::new(address, slot, block),
+                    |key| key.address == address && key.sharded_key.key == slot,
+                    block,
+                    reverse,
+                )?
+            };
+            Ok(next.filter(|block| range.contains(block)))
+        };
+        // Blocks are ordered by the heap from the first to the last one to return.
+        let order = |block: BlockNumber| Reverse(if reverse { !block } else { block });
+
+        let first = if reverse { range.end - 1 } else { range.start };
+        let mut heap = BinaryHeap::with_capacity(slots.len() + 1);
+        for source in 0..=slots.len() {
+            if let Some(block) = next_block(source, first)? {
+                heap.push((order(block), source, block));
+            }
+        }
+
+        let mut blocks = Vec::new();
+        while let Some((_, source, block)) = heap.pop() {
+            if blocks.last() != Some(&block) {
+                blocks.push(block);
+                if blocks.len() == limit {
+                    break
+                }
+            }
+
+            let following = if reverse { block.checked_sub(1) } else { block.checked_add(1) };
+            if let Some(following) = following {
+                if let Some(block) = next_block(source, following)? {
+                    heap.push((order(block), source, block));
+                }
+            }
+        }
+
+        Ok(blocks)
+    }
+}
+
+/// Returns the first block in the history of a key that is not lower than the given block, or
+/// the last one that is not higher if `reverse` is set.
+///
+/// History shards are keyed by their highest block number, `shard_key` returns the key of the
+/// shard with the given highest block number and `is_shard` checks whether a key belongs to the
+/// history of the key.
+fn history_block<T, C>(
+    cursor: &mut C,
+    shard_key: impl Fn(BlockNumber) -> T::Key,
+    is_shard: impl Fn(&T::Key) -> bool,
+    block: BlockNumber,
+    reverse: bool,
+) -> ProviderResult<Option<BlockNumber>>
+where
+    T: Table<Value = BlockNumberList>,
+    C: DbCursorRO<T>,
+{
+    // The first shard with a key that is not lower than the block is the only one that can
+    // contain the block, or the next higher one.
+    let shard = cursor.seek(shard_key(block))?;
+    if !reverse {
+        return Ok(shard.filter(|(key, _)| is_shard(key)).and_then(|(_, list)| {
+            let rank = block.checked_sub(1).map_or(0, |block| list.rank(block));
+            list.select(rank)
+        }))
+    }
+
+    let previous = match shard {
+        Some((key, list)) if is_shard(&key) => {
+            let rank = list.rank(block);
+            if rank > 0 {
+                return Ok(list.select(rank - 1))
+            }
+            cursor.prev()?
+        }
+        Some(_) => cursor.prev()?,
+        None => cursor.last()?,
+    };
+    // All blocks of the previous shard are lower than the block.
+    Ok(previous.filter(|(key, _)| is_shard(key)).and_then(|(_, list)| list.max()))
+}
+
 impl<TX: DbTx> HeaderSyncGapProvider for DatabaseProvider<TX> {
     fn sync_gap(
         &self,
@@ -3684,6 +3949,170 @@ impl<TX: DbTxMut> FinalizedBlockWriter for DatabaseProvider<TX> {
     }
 }
 
+impl<TX: DbTx> DatabaseProvider<TX> {
+    /// Returns the hashes of all stored bad blocks, along with the bad block or the error it
+    /// failed to decode with.
+    fn raw_bad_blocks(
+        &self,
+    ) -> ProviderResult<Vec<(BlockHash, Result<StoredBadBlock, DatabaseError>)>> {
+        let mut cursor = self.tx.cursor_read::<RawTable<tables::BadBlocks>>()?;
+        let mut bad_blocks = Vec::new();
+        for entry in cursor.walk(None)? {
+            let (hash, bad_block) = entry?;
+            bad_blocks.push((hash.key()?, bad_block.value()));
+        }
+        Ok(bad_blocks)
+    }
+}
+
+impl<TX: DbTx> BadBlockReader for DatabaseProvider<TX> {
+    fn bad_blocks(&self) -> ProviderResult<Vec<StoredBadBlock>> {
+        let mut bad_blocks = Vec::new();
+        for (hash, bad_block) in self.raw_bad_blocks()? {
+            match bad_block {
+                Ok(bad_block) => bad_blocks.push(bad_block),
+                Err(err) => {
+                    warn!(target: "providers::db", %hash, %err, "Skipping undecodable bad block")
+                }
+            }
+        }
+        bad_blocks.sort_unstable_by_key(|bad_block| Reverse(bad_block.sequence));
+        Ok(bad_blocks)
+    }
+
+    fn bad_block(&self, hash: BlockHash) -> ProviderResult<Option<StoredBadBlock>> {
+        Ok(self.tx.get::<tables::BadBlocks>(hash)?)
+    }
+}
+
+impl<TX: DbTx> TableDigestReader for DatabaseProvider<TX> {
+    fn table_digest(
+        &self,
+        table: &str,
+        start: Option<&TableDigestCursor>,
+        end: Option<&TableDigestCursor>,
+        limit: u64,
+    ) -> ProviderResult<TableDigest> {
+        let table =
+            table.parse::<Tables>().map_err(|_| ProviderError::UnknownTable(table.to_string()))?;
+        table.view(&TableDigestViewer { tx: &self.tx, start, end, limit })
+    }
+}
+
+/// Digests a range of raw table entries for [`TableDigestReader`].
+struct TableDigestViewer<'a, TX> {
+    tx: &'a TX,
+    start: Option<&'a TableDigestCursor>,
+    end: Option<&'a TableDigestCursor>,
+    limit: u64,
+}
+
+impl<TX: DbTx> TableDigestViewer<'_, TX> {
+    /// Digests `first` and the entries returned by `next`, until the end of the range or the
+    /// entry limit is reached.
+    fn digest<K: Key, V: Value>(
+        &self,
+        first: Option<(RawKey<K>, RawValue<V>)>,
+        mut next: impl FnMut() -> Result<Option<(RawKey<K>, RawValue<V>)>, DatabaseError>,
+        dupsort: bool,
+    ) -> ProviderResult<TableDigest> {
+        let mut hasher = TableDigestHasher::default();
+        let mut entry = first;
+        while let Some((key, value)) = entry {
+            let (key, value) = (key.raw_key(), value.raw_value());
+            if self.end.is_some_and(|end| !end.is_past(key, value)) {
+                break
+            }
+            if hasher.entries() >= self.limit {
+                let (entries, digest) = hasher.finalize();
+                let next = TableDigestCursor {
+                    key: Bytes::copy_from_slice(key),
+                    subkey: dupsort.then(|| Bytes::copy_from_slice(value)),
+                };
+                return Ok(TableDigest { entries, digest, next: Some(next) })
+            }
+
+            hasher.update(key, &[value]);
+            entry = next()?;
+        }
+
+        let (entries, digest) = hasher.finalize();
+        Ok(TableDigest { entries, digest, next: None })
+    }
+}
+
+impl<TX: DbTx> TableViewer<TableDigest> for TableDigestViewer<'_, TX> {
+    type Error = ProviderError;
+
+    fn view<T: Table>(&self) -> Result<TableDigest, Self::Error> {
+        let mut cursor = self.tx.cursor_read::<RawTable<T>>()?;
+        let mut first = match self.start {
+            Some(start) => cursor.seek(RawKey::from_vec(start.key.to_vec()))?,
+            None => cursor.first()?,
+        };
+        // Keys are unique, so at most the entry of the start key is before a start subkey.
+        if first.as_ref().is_some_and(|(key, value)| {
+            self.start.is_some_and(|start| start.is_past(key.raw_key(), value.raw_value()))
+        }) {
+            first = cursor.next()?;
+        }
+        self.digest(first, || cursor.next(), false)
+    }
+
+    fn view_dupsort<T: DupSort>(&self) -> Result<TableDigest, Self::Error> {
+        let mut cursor = self.tx.cursor_dup_read::<RawDupSort<T>>()?;
+        let first = match self.start {
+            Some(TableDigestCursor { key, subkey: Some(subkey) }) => {
+                let key = RawKey::<T::Key>::from_vec(key.to_vec());
+                match cursor.seek_by_key_subkey(key.clone(), RawKey::from_vec(subkey.to_vec()))? {
+                    Some(value) => Some((key, value)),
+                    // No duplicate of the start key is at or after the subkey.
+                    None => match cursor.seek(key.clone())? {
+                        Some((found, _)) if found == key => cursor.next_no_dup()?,
+                        entry => entry,
+                    },
+                }
+            }
+            Some(TableDigestCursor { key, subkey: None }) => {
+                cursor.seek(RawKey::from_vec(key.to_vec()))?
+            }
+            None => cursor.first()?,
+        };
+        self.digest(first, || cursor.next(), true)
+    }
+}
+
+impl<TX: DbTxMut + DbTx> BadBlockWriter for DatabaseProvider<TX> {
+    fn insert_bad_block(&self, mut bad_block: StoredBadBlock) -> ProviderResult<()> {
+        let hash = bad_block.block.hash();
+
+        // the table is tiny, so the other bad blocks are read to order and evict them
+        let mut bad_blocks = Vec::new();
+        for (stored_hash, stored) in self.raw_bad_blocks()? {
+            match stored {
+                Ok(stored) if stored_hash != hash => {
+                    bad_blocks.push((stored.sequence, stored_hash))
+                }
+                Ok(_) => {}
+                // bad blocks that can't be decoded are dropped
+                Err(_) => {
+                    self.tx.delete::<tables::BadBlocks>(stored_hash, None)?;
+                }
+            }
+        }
+
+        // evict the oldest bad blocks
+        bad_blocks.sort_unstable_by_key(|(sequence, _)| Reverse(*sequence));
+        for (_, evicted) in bad_blocks.iter().skip(MAX_BAD_BLOCKS - 1) {
+            self.tx.delete::<tables::BadBlocks>(*evicted, None)?;
+        }
+
+        bad_block.sequence = bad_blocks.first().map_or(0, |(sequence, _)| sequence + 1);
+        self.tx.put::<tables::BadBlocks>(hash, bad_block)?;
+        Ok(())
+    }
+}
+
 /// Helper method to recover senders for any blocks in the db which do not have senders. This
 /// compares the length of the input senders [`Vec`], with the length of given transactions [`Vec`],
 /// and will add to the input senders vec if there are more transactions.
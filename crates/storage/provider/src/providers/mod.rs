@@ -1,11 +1,13 @@
 use crate::{
-    AccountReader, BlockHashReader, BlockIdReader, BlockNumReader, BlockReader, BlockReaderIdExt,
-    BlockSource, BlockchainTreePendingStateProvider, CanonChainTracker, CanonStateNotifications,
-    CanonStateSubscriptions, ChainSpecProvider, ChangeSetReader, DatabaseProviderFactory,
-    EvmEnvProvider, FinalizedBlockReader, FullExecutionDataProvider, HeaderProvider, ProviderError,
-    PruneCheckpointReader, ReceiptProvider, ReceiptProviderIdExt, RequestsProvider,
-    StageCheckpointReader, StateProviderBox, StateProviderFactory, StaticFileProviderFactory,
-    TransactionVariant, TransactionsProvider, TreeViewer, WithdrawalsProvider,
+    AccountHistoryReader, AccountReader, BadBlockReader, BlockHashReader, BlockIdReader,
+    BlockNumReader, BlockReader, BlockReaderIdExt, BlockSource, BlockchainTreePendingStateProvider,
+    CanonChainTracker, CanonStateNotifications, CanonStateSubscriptions, ChainSpecProvider,
+    ChangeSetReader, DatabaseProviderFactory, EvmEnvProvider, FinalizedBlockReader,
+    FullExecutionDataProvider, HeaderProvider, ProviderError, PruneCheckpointReader,
+    ReceiptProvider, ReceiptProviderIdExt, RequestsProvider, StageCheckpointReader,
+    StateProviderBox, StateProviderFactory, StaticFileProviderFactory, TableDigest,
+    TableDigestCursor, TableDigestReader, TransactionVariant, TransactionsProvider, TreeViewer,
+    WithdrawalsProvider,
 };
 use reth_blockchain_tree_api::{
     error::{CanonicalError, InsertBlockError},
@@ -16,7 +18,7 @@ use reth_chain_state::{ChainInfoTracker, ForkChoiceNotifications, ForkChoiceSubs
 use reth_chainspec::{ChainInfo, ChainSpec};
 use reth_db_api::{
     database::Database,
-    models::{AccountBeforeTx, StoredBlockBodyIndices},
+    models::{AccountBeforeTx, StoredBadBlock, StoredBlockBodyIndices},
 };
 use reth_evm::ConfigureEvmEnv;
 use reth_primitives::{
@@ -48,8 +50,12 @@ pub use static_file::{
 
 mod state;
 pub use state::{
-    historical::{HistoricalStateProvider, HistoricalStateProviderRef},
+    historical::{
+        HistoricalStateProvider, HistoricalStateProviderRef, LowestAvailableAddressBlocks,
+        LowestAvailableAddressBlocksCache,
+    },
     latest::{LatestStateProvider, LatestStateProviderRef},
+    revert_cache::{RevertStateCache, DEFAULT_REVERT_STATE_CACHE_SIZE},
 };
 
 mod bundle_state_provider;
@@ -967,6 +973,49 @@ where
     }
 }
 
+impl<DB> AccountHistoryReader for BlockchainProvider<DB>
+where
+    DB: Database,
+{
+    fn account_history_blocks(
+        &self,
+        address: Address,
+        range: impl RangeBounds<BlockNumber>,
+        limit: usize,
+        reverse: bool,
+    ) -> ProviderResult<Vec<BlockNumber>> {
+        self.database.provider()?.account_history_blocks(address, range, limit, reverse)
+    }
+}
+
+impl<DB> BadBlockReader for BlockchainProvider<DB>
+where
+    DB: Database,
+{
+    fn bad_blocks(&self) -> ProviderResult<Vec<StoredBadBlock>> {
+        self.database.provider()?.bad_blocks()
+    }
+
+    fn bad_block(&self, hash: BlockHash) -> ProviderResult<Option<StoredBadBlock>> {
+        self.database.provider()?.bad_block(hash)
+    }
+}
+
+impl<DB> TableDigestReader for BlockchainProvider<DB>
+where
+    DB: Database,
+{
+    fn table_digest(
+        &self,
+        table: &str,
+        start: Option<&TableDigestCursor>,
+        end: Option<&TableDigestCursor>,
+        limit: u64,
+    ) -> ProviderResult<TableDigest> {
+        self.database.provider()?.table_digest(table, start, end, limit)
+    }
+}
+
 impl<DB> AccountReader for BlockchainProvider<DB>
 where
     DB: Database + Sync + Send,
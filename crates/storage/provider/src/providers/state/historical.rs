@@ -1,17 +1,20 @@
 use crate::{
-    providers::{state::macros::delegate_provider_impls, StaticFileProvider},
+    providers::{state::macros::delegate_provider_impls, RevertStateCache, StaticFileProvider},
     AccountReader, BlockHashReader, ProviderError, StateProvider, StateRootProvider,
 };
+use parking_lot::Mutex;
 use reth_db::{tables, BlockNumberList};
 use reth_db_api::{
     cursor::{DbCursorRO, DbDupCursorRO},
-    models::{storage_sharded_key::StorageShardedKey, ShardedKey},
+    models::{
+        storage_sharded_key::StorageShardedKey, AccountBeforeTx, ShardedKey, StorageBeforeTx,
+    },
     table::Table,
     transaction::DbTx,
 };
 use reth_primitives::{
-    constants::EPOCH_SLOTS, Account, Address, BlockNumber, Bytecode, Bytes, StaticFileSegment,
-    StorageKey, StorageValue, B256,
+    constants::EPOCH_SLOTS, keccak256, Account, Address, BlockNumHash, BlockNumber, Bytecode,
+    Bytes, StaticFileSegment, StorageKey, StorageValue, B256, U256,
 };
 use reth_storage_api::StateProofProvider;
 use reth_storage_errors::provider::ProviderResult;
@@ -23,7 +26,7 @@ use reth_trie_db::{
     DatabaseHashedPostState, DatabaseProof, DatabaseStateRoot, DatabaseStorageRoot,
     DatabaseTrieWitness,
 };
-use std::{collections::HashMap, fmt::Debug};
+use std::{collections::HashMap, fmt::Debug, ops::RangeInclusive, sync::Arc};
 
 /// State provider for a given block number which takes a tx reference.
 ///
@@ -36,6 +39,9 @@ use std::{collections::HashMap, fmt::Debug};
 /// - [`tables::StoragesHistory`]
 /// - [`tables::AccountChangeSets`]
 /// - [`tables::StorageChangeSets`]
+///
+/// Change sets of blocks that have been moved to the [`StaticFileSegment::AccountChangeSets`] and
+/// [`StaticFileSegment::StorageChangeSets`] static files are read from there instead.
 #[derive(Debug)]
 pub struct HistoricalStateProviderRef<'b, TX: DbTx> {
     /// Transaction
@@ -44,6 +50,10 @@ pub struct HistoricalStateProviderRef<'b, TX: DbTx> {
     block_number: BlockNumber,
     /// Lowest blocks at which different parts of the state are available.
     lowest_available_blocks: LowestAvailableBlocks,
+    /// Lowest blocks at which the history of addresses with their own prune modes is available.
+    lowest_available_address_blocks: Option<&'b LowestAvailableAddressBlocks>,
+    /// Cache of the revert states used for state roots and proofs.
+    revert_state_cache: Option<&'b RevertStateCache>,
     /// Static File provider
     static_file_provider: StaticFileProvider,
 }
@@ -63,7 +73,14 @@ impl<'b, TX: DbTx> HistoricalStateProviderRef<'b, TX> {
         block_number: BlockNumber,
         static_file_provider: StaticFileProvider,
     ) -> Self {
-        Self { tx, block_number, lowest_available_blocks: Default::default(), static_file_provider }
+        Self {
+            tx,
+            block_number,
+            lowest_available_blocks: Default::default(),
+            lowest_available_address_blocks: None,
+            revert_state_cache: None,
+            static_file_provider,
+        }
     }
 
     /// Create new `StateProvider` for historical block number and lowest block numbers at which
@@ -74,21 +91,49 @@ impl<'b, TX: DbTx> HistoricalStateProviderRef<'b, TX> {
         lowest_available_blocks: LowestAvailableBlocks,
         static_file_provider: StaticFileProvider,
     ) -> Self {
-        Self { tx, block_number, lowest_available_blocks, static_file_provider }
+        Self {
+            tx,
+            block_number,
+            lowest_available_blocks,
+            lowest_available_address_blocks: None,
+            revert_state_cache: None,
+            static_file_provider,
+        }
+    }
+
+    /// Set the lowest block numbers at which the histories of addresses with their own prune
+    /// modes are available.
+    pub const fn with_lowest_available_address_blocks(
+        mut self,
+        lowest_available_address_blocks: &'b LowestAvailableAddressBlocks,
+    ) -> Self {
+        self.lowest_available_address_blocks = Some(lowest_available_address_blocks);
+        self
+    }
+
+    /// Set the cache of the revert states used for state roots and proofs.
+    pub const fn with_revert_state_cache(
+        mut self,
+        revert_state_cache: &'b RevertStateCache,
+    ) -> Self {
+        self.revert_state_cache = Some(revert_state_cache);
+        self
     }
 
     /// Lookup an account in the `AccountsHistory` table
     pub fn account_history_lookup(&self, address: Address) -> ProviderResult<HistoryInfo> {
-        if !self.lowest_available_blocks.is_account_history_available(self.block_number) {
-            return Err(ProviderError::StateAtBlockPruned(self.block_number))
-        }
+        let lowest_available_block_number = self.lowest_available_history_block_number(
+            address,
+            self.lowest_available_blocks.account_history_block_number,
+            self.lowest_available_address_blocks.map(|blocks| &blocks.account_history),
+        )?;
 
         // history key to search IntegerList of block number changesets.
         let history_key = ShardedKey::new(address, self.block_number);
         self.history_info::<tables::AccountsHistory, _>(
             history_key,
             |key| key.key == address,
-            self.lowest_available_blocks.account_history_block_number,
+            lowest_available_block_number,
         )
     }
 
@@ -98,37 +143,149 @@ impl<'b, TX: DbTx> HistoricalStateProviderRef<'b, TX> {
         address: Address,
         storage_key: StorageKey,
     ) -> ProviderResult<HistoryInfo> {
-        if !self.lowest_available_blocks.is_storage_history_available(self.block_number) {
-            return Err(ProviderError::StateAtBlockPruned(self.block_number))
-        }
+        let lowest_available_block_number = self.lowest_available_history_block_number(
+            address,
+            self.lowest_available_blocks.storage_history_block_number,
+            self.lowest_available_address_blocks.map(|blocks| &blocks.storage_history),
+        )?;
 
         // history key to search IntegerList of block number changesets.
         let history_key = StorageShardedKey::new(address, storage_key, self.block_number);
         self.history_info::<tables::StoragesHistory, _>(
             history_key,
             |key| key.address == address && key.sharded_key.key == storage_key,
-            self.lowest_available_blocks.storage_history_block_number,
+            lowest_available_block_number,
         )
     }
 
+    /// Returns the lowest block number at which the history of the address is available, or an
+    /// error if it's not available at the block number of this provider.
+    ///
+    /// If some addresses are pruned according to their own prune modes, the pruned history of
+    /// the other addresses is reported per address, as the state of the block is still partially
+    /// available.
+    fn lowest_available_history_block_number(
+        &self,
+        address: Address,
+        lowest_available_block_number: Option<BlockNumber>,
+        lowest_available_address_blocks: Option<&HashMap<Address, Option<BlockNumber>>>,
+    ) -> ProviderResult<Option<BlockNumber>> {
+        let is_available = |lowest: Option<BlockNumber>| {
+            lowest.map_or(true, |block_number| block_number <= self.block_number)
+        };
+
+        match lowest_available_address_blocks.filter(|blocks| !blocks.is_empty()) {
+            None if is_available(lowest_available_block_number) => {
+                Ok(lowest_available_block_number)
+            }
+            None => Err(ProviderError::StateAtBlockPruned(self.block_number)),
+            Some(blocks) => {
+                let lowest = blocks.get(&address).copied().unwrap_or(lowest_available_block_number);
+                if !is_available(lowest) {
+                    return Err(ProviderError::AddressHistoryPruned {
+                        address,
+                        block_number: self.block_number,
+                    })
+                }
+                // The history of addresses added to the filter may have been pruned according
+                // to the segment prune mode before, so it's always looked up as pruned.
+                Ok(lowest.or(lowest_available_block_number))
+            }
+        }
+    }
+
     /// Retrieve revert hashed state for this history provider.
-    fn revert_state(&self) -> ProviderResult<HashedPostState> {
+    ///
+    /// If the provider has a [`RevertStateCache`], the revert state is derived from a cached one
+    /// when possible and cached for later use.
+    fn revert_state(&self) -> ProviderResult<Arc<HashedPostState>> {
         if !self.lowest_available_blocks.is_account_history_available(self.block_number) ||
             !self.lowest_available_blocks.is_storage_history_available(self.block_number)
         {
             return Err(ProviderError::StateAtBlockPruned(self.block_number))
         }
 
+        // Headers written by the pipeline only go to static files, so the database tip may be
+        // behind the static file one.
         let tip = self
             .tx
             .cursor_read::<tables::CanonicalHeaders>()?
             .last()?
             .map(|(tip, _)| tip)
-            .or_else(|| {
-                self.static_file_provider.get_highest_static_file_block(StaticFileSegment::Headers)
-            })
+            .max(
+                self.static_file_provider.get_highest_static_file_block(StaticFileSegment::Headers),
+            )
             .ok_or(ProviderError::BestBlockNotFound)?;
 
+        let Some(cache) = self.revert_state_cache else {
+            self.warn_old_block(tip);
+            return Ok(Arc::new(self.revert_range(self.block_number..=tip)?))
+        };
+
+        let tip_hash =
+            self.block_hash(tip)?.ok_or_else(|| ProviderError::HeaderNotFound(tip.into()))?;
+        let tip = BlockNumHash::new(tip, tip_hash);
+        let cached = cache.get(self.block_number, |cached_tip| {
+            Ok(cached_tip.number <= tip.number &&
+                self.block_hash(cached_tip.number)? == Some(cached_tip.hash))
+        })?;
+
+        let revert_state = match cached {
+            Some(cached) if cached.block_number == self.block_number && cached.tip == tip => {
+                return Ok(cached.state)
+            }
+            Some(cached) => {
+                // Changes of the blocks after the cached tip are overridden by the earlier cached
+                // ones, while the changes of the blocks below the cached block override them.
+                let mut revert_state = if cached.tip.number < tip.number {
+                    let mut revert_state = self.revert_range(cached.tip.number + 1..=tip.number)?;
+                    revert_state.extend(Arc::unwrap_or_clone(cached.state));
+                    revert_state
+                } else {
+                    Arc::unwrap_or_clone(cached.state)
+                };
+                if self.block_number < cached.block_number {
+                    revert_state
+                        .extend(self.revert_range(self.block_number..=cached.block_number - 1)?);
+                }
+                revert_state
+            }
+            None => {
+                self.warn_old_block(tip.number);
+                self.revert_range(self.block_number..=tip.number)?
+            }
+        };
+
+        let revert_state = Arc::new(revert_state);
+        cache.insert(self.block_number, tip, revert_state.clone());
+        Ok(revert_state)
+    }
+
+    /// Returns an error if the history of any address matching the predicate was pruned at the
+    /// block number of this provider, according to the address' own prune mode.
+    ///
+    /// The change sets of such addresses are missing, so the revert state is wrong for them and
+    /// only lookups that don't touch them can be served.
+    fn ensure_address_history_available(
+        &self,
+        mut touches: impl FnMut(&Address) -> bool,
+    ) -> ProviderResult<()> {
+        let Some(blocks) = self.lowest_available_address_blocks else { return Ok(()) };
+        let pruned = blocks.account_history.iter().chain(&blocks.storage_history).find(
+            |(address, lowest)| {
+                lowest.is_some_and(|lowest| lowest > self.block_number) && touches(address)
+            },
+        );
+        match pruned {
+            Some((address, _)) => Err(ProviderError::AddressHistoryPruned {
+                address: *address,
+                block_number: self.block_number,
+            }),
+            None => Ok(()),
+        }
+    }
+
+    fn warn_old_block(&self, tip: BlockNumber) {
         if tip.saturating_sub(self.block_number) > EPOCH_SLOTS {
             tracing::warn!(
                 target: "provider::historical_sp",
@@ -136,8 +293,97 @@ impl<'b, TX: DbTx> HistoricalStateProviderRef<'b, TX> {
                 "Attempt to calculate state root for an old block might result in OOM, treat carefully"
             );
         }
+    }
 
-        Ok(HashedPostState::from_reverts(self.tx, self.block_number)?)
+    /// Retrieve revert hashed state of the change sets in the given block range.
+    fn revert_range(&self, range: RangeInclusive<BlockNumber>) -> ProviderResult<HashedPostState> {
+        let (start, end) = range.into_inner();
+
+        // Change sets up to the highest static file block are no longer in the database, so the
+        // reverts are collected from static files first and the database reverts for the
+        // following blocks are applied on top of them. Since the earliest change takes precedence,
+        // static file reverts override the database ones.
+        let highest_static_file_block = |segment| {
+            self.static_file_provider
+                .get_highest_static_file_block(segment)
+                .filter(|highest| *highest >= start)
+                .map(|highest| highest.min(end))
+        };
+        let highest_accounts = highest_static_file_block(StaticFileSegment::AccountChangeSets);
+        let highest_storages = highest_static_file_block(StaticFileSegment::StorageChangeSets);
+
+        if highest_accounts.is_none() && highest_storages.is_none() {
+            return Ok(HashedPostState::from_reverts_range(self.tx, start..=end)?)
+        }
+
+        let database_start = highest_accounts
+            .map_or(start, |block| block + 1)
+            .min(highest_storages.map_or(start, |block| block + 1));
+
+        let mut revert_state = HashedPostState::from_reverts_range(self.tx, database_start..=end)?;
+        revert_state.extend(self.static_file_revert_state(
+            start,
+            highest_accounts,
+            highest_storages,
+        )?);
+        Ok(revert_state)
+    }
+
+    /// Retrieve revert hashed state from the change set static files, for all blocks from the
+    /// start block up to the provided highest account and storage change sets blocks, inclusive.
+    fn static_file_revert_state(
+        &self,
+        start: BlockNumber,
+        highest_accounts: Option<BlockNumber>,
+        highest_storages: Option<BlockNumber>,
+    ) -> ProviderResult<HashedPostState> {
+        let mut accounts = HashMap::<Address, Option<Account>>::default();
+        let mut storages = HashMap::<Address, HashMap<B256, U256>>::default();
+
+        for block_number in highest_accounts.into_iter().flat_map(|highest| start..=highest) {
+            let account_changes = self
+                .static_file_provider
+                .account_change_set(block_number)?
+                .ok_or(ProviderError::MissingStaticFileBlock(
+                    StaticFileSegment::AccountChangeSets,
+                    block_number,
+                ))?;
+            for AccountBeforeTx { address, info } in account_changes {
+                accounts.entry(address).or_insert(info);
+            }
+        }
+
+        for block_number in highest_storages.into_iter().flat_map(|highest| start..=highest) {
+            let storage_changes = self
+                .static_file_provider
+                .storage_change_set(block_number)?
+                .ok_or(ProviderError::MissingStaticFileBlock(
+                    StaticFileSegment::StorageChangeSets,
+                    block_number,
+                ))?;
+            for StorageBeforeTx { address, entry } in storage_changes {
+                storages.entry(address).or_default().entry(entry.key).or_insert(entry.value);
+            }
+        }
+
+        Ok(HashedPostState {
+            accounts: accounts
+                .into_iter()
+                .map(|(address, info)| (keccak256(address), info))
+                .collect(),
+            storages: storages
+                .into_iter()
+                .map(|(address, storage)| {
+                    (
+                        keccak256(address),
+                        HashedStorage::from_iter(
+                            false,
+                            storage.into_iter().map(|(slot, value)| (keccak256(slot), value)),
+                        ),
+                    )
+                })
+                .collect(),
+        })
     }
 
     fn history_info<T, K>(
@@ -210,10 +456,19 @@ impl<'b, TX: DbTx> AccountReader for HistoricalStateProviderRef<'b, TX> {
         match self.account_history_lookup(address)? {
             HistoryInfo::NotYetWritten => Ok(None),
             HistoryInfo::InChangeset(changeset_block_number) => Ok(self
-                .tx
-                .cursor_dup_read::<tables::AccountChangeSets>()?
-                .seek_by_key_subkey(changeset_block_number, address)?
-                .filter(|acc| acc.address == address)
+                .static_file_provider
+                .get_with_static_file_or_database(
+                    StaticFileSegment::AccountChangeSets,
+                    changeset_block_number,
+                    |static_file| static_file.account_change(changeset_block_number, address),
+                    || {
+                        Ok(self
+                            .tx
+                            .cursor_dup_read::<tables::AccountChangeSets>()?
+                            .seek_by_key_subkey(changeset_block_number, address)?
+                            .filter(|acc| acc.address == address))
+                    },
+                )?
                 .ok_or(ProviderError::AccountChangesetNotFound {
                     block_number: changeset_block_number,
                     address,
@@ -264,7 +519,9 @@ impl<'b, TX: DbTx> BlockHashReader for HistoricalStateProviderRef<'b, TX> {
 
 impl<'b, TX: DbTx> StateRootProvider for HistoricalStateProviderRef<'b, TX> {
     fn hashed_state_root(&self, hashed_state: HashedPostState) -> ProviderResult<B256> {
-        let mut revert_state = self.revert_state()?;
+        // The state root depends on every account.
+        self.ensure_address_history_available(|_| true)?;
+        let mut revert_state = Arc::unwrap_or_clone(self.revert_state()?);
         revert_state.extend(hashed_state);
         StateRoot::overlay_root(self.tx, revert_state, Default::default())
             .map_err(|err| ProviderError::Database(err.into()))
@@ -274,7 +531,8 @@ impl<'b, TX: DbTx> StateRootProvider for HistoricalStateProviderRef<'b, TX> {
         &self,
         hashed_state: HashedPostState,
     ) -> ProviderResult<(B256, TrieUpdates)> {
-        let mut revert_state = self.revert_state()?;
+        self.ensure_address_history_available(|_| true)?;
+        let mut revert_state = Arc::unwrap_or_clone(self.revert_state()?);
         revert_state.extend(hashed_state);
         StateRoot::overlay_root_with_updates(self.tx, revert_state, Default::default())
             .map_err(|err| ProviderError::Database(err.into()))
@@ -298,7 +556,8 @@ impl<'b, TX: DbTx> StateProofProvider for HistoricalStateProviderRef<'b, TX> {
         address: Address,
         slots: &[B256],
     ) -> ProviderResult<AccountProof> {
-        let mut revert_state = self.revert_state()?;
+        self.ensure_address_history_available(|pruned| *pruned == address)?;
+        let mut revert_state = Arc::unwrap_or_clone(self.revert_state()?);
         revert_state.extend(hashed_state);
         Proof::overlay_account_proof(self.tx, revert_state, address, slots)
             .map_err(Into::<ProviderError>::into)
@@ -309,7 +568,12 @@ impl<'b, TX: DbTx> StateProofProvider for HistoricalStateProviderRef<'b, TX> {
         overlay: HashedPostState,
         target: HashedPostState,
     ) -> ProviderResult<HashMap<B256, Bytes>> {
-        let mut revert_state = self.revert_state()?;
+        self.ensure_address_history_available(|pruned| {
+            let hashed_address = keccak256(pruned);
+            target.accounts.contains_key(&hashed_address) ||
+                target.storages.contains_key(&hashed_address)
+        })?;
+        let mut revert_state = Arc::unwrap_or_clone(self.revert_state()?);
         revert_state.extend(overlay);
         TrieWitness::overlay_witness(self.tx, revert_state, target)
             .map_err(Into::<ProviderError>::into)
@@ -326,10 +590,26 @@ impl<'b, TX: DbTx> StateProvider for HistoricalStateProviderRef<'b, TX> {
         match self.storage_history_lookup(address, storage_key)? {
             HistoryInfo::NotYetWritten => Ok(None),
             HistoryInfo::InChangeset(changeset_block_number) => Ok(Some(
-                self.tx
-                    .cursor_dup_read::<tables::StorageChangeSets>()?
-                    .seek_by_key_subkey((changeset_block_number, address).into(), storage_key)?
-                    .filter(|entry| entry.key == storage_key)
+                self.static_file_provider
+                    .get_with_static_file_or_database(
+                        StaticFileSegment::StorageChangeSets,
+                        changeset_block_number,
+                        |static_file| {
+                            Ok(static_file
+                                .storage_change(changeset_block_number, address, storage_key)?
+                                .map(|change| change.entry))
+                        },
+                        || {
+                            Ok(self
+                                .tx
+                                .cursor_dup_read::<tables::StorageChangeSets>()?
+                                .seek_by_key_subkey(
+                                    (changeset_block_number, address).into(),
+                                    storage_key,
+                                )?
+                                .filter(|entry| entry.key == storage_key))
+                        },
+                    )?
                     .ok_or_else(|| ProviderError::StorageChangesetNotFound {
                         block_number: changeset_block_number,
                         address,
@@ -363,6 +643,10 @@ pub struct HistoricalStateProvider<TX: DbTx> {
     block_number: BlockNumber,
     /// Lowest blocks at which different parts of the state are available.
     lowest_available_blocks: LowestAvailableBlocks,
+    /// Lowest blocks at which the history of addresses with their own prune modes is available.
+    lowest_available_address_blocks: Arc<LowestAvailableAddressBlocks>,
+    /// Cache of the revert states used for state roots and proofs.
+    revert_state_cache: Option<RevertStateCache>,
     /// Static File provider
     static_file_provider: StaticFileProvider,
 }
@@ -374,7 +658,14 @@ impl<TX: DbTx> HistoricalStateProvider<TX> {
         block_number: BlockNumber,
         static_file_provider: StaticFileProvider,
     ) -> Self {
-        Self { tx, block_number, lowest_available_blocks: Default::default(), static_file_provider }
+        Self {
+            tx,
+            block_number,
+            lowest_available_blocks: Default::default(),
+            lowest_available_address_blocks: Default::default(),
+            revert_state_cache: None,
+            static_file_provider,
+        }
     }
 
     /// Set the lowest block number at which the account history is available.
@@ -395,15 +686,36 @@ impl<TX: DbTx> HistoricalStateProvider<TX> {
         self
     }
 
+    /// Set the lowest block numbers at which the histories of addresses with their own prune
+    /// modes are available.
+    pub fn with_lowest_available_address_blocks(
+        mut self,
+        lowest_available_address_blocks: Arc<LowestAvailableAddressBlocks>,
+    ) -> Self {
+        self.lowest_available_address_blocks = lowest_available_address_blocks;
+        self
+    }
+
+    /// Set the cache of the revert states used for state roots and proofs.
+    pub fn with_revert_state_cache(mut self, revert_state_cache: RevertStateCache) -> Self {
+        self.revert_state_cache = Some(revert_state_cache);
+        self
+    }
+
     /// Returns a new provider that takes the `TX` as reference
     #[inline(always)]
     fn as_ref(&self) -> HistoricalStateProviderRef<'_, TX> {
-        HistoricalStateProviderRef::new_with_lowest_available_blocks(
+        let provider = HistoricalStateProviderRef::new_with_lowest_available_blocks(
             &self.tx,
             self.block_number,
             self.lowest_available_blocks,
             self.static_file_provider.clone(),
         )
+        .with_lowest_available_address_blocks(&self.lowest_available_address_blocks);
+        match &self.revert_state_cache {
+            Some(cache) => provider.with_revert_state_cache(cache),
+            None => provider,
+        }
     }
 }
 
@@ -438,21 +750,85 @@ impl LowestAvailableBlocks {
     }
 }
 
+/// Lowest blocks at which the history of addresses pruned according to their own prune modes is
+/// available, see [`reth_prune_types::PruneModes::account_history_filter`] and
+/// [`reth_prune_types::PruneModes::storage_history_filter`].
+///
+/// Addresses that are not listed are available from the corresponding block of
+/// [`LowestAvailableBlocks`].
+#[derive(Clone, Debug, Default)]
+pub struct LowestAvailableAddressBlocks {
+    /// Lowest block number at which the account history of each address is available.
+    /// [`Option::None`] means all history of the address is available.
+    pub account_history: HashMap<Address, Option<BlockNumber>>,
+    /// Lowest block number at which the storage history of each address is available.
+    /// [`Option::None`] means all history of the address is available.
+    pub storage_history: HashMap<Address, Option<BlockNumber>>,
+}
+
+/// A cache of the [`LowestAvailableAddressBlocks`] resolved on top of the latest tip, shared by
+/// the historical state providers of a [`ProviderFactory`](crate::ProviderFactory).
+///
+/// Resolving the duration based prune modes of the address filters looks up the headers of the
+/// pruned blocks, so it's only done once per tip. The cached blocks are replaced as soon as they
+/// are requested for another tip, including a reorged one with the same number.
+#[derive(Clone, Debug, Default)]
+pub struct LowestAvailableAddressBlocksCache {
+    inner: Arc<Mutex<Option<CachedLowestAvailableAddressBlocks>>>,
+}
+
+/// [`LowestAvailableAddressBlocks`] resolved on top of a tip.
+type CachedLowestAvailableAddressBlocks = (BlockNumHash, Arc<LowestAvailableAddressBlocks>);
+
+impl LowestAvailableAddressBlocksCache {
+    /// Returns the blocks resolved on top of the given tip, resolving and caching them with the
+    /// given closure if they're not cached yet.
+    pub(crate) fn get_or_try_insert_with(
+        &self,
+        tip: BlockNumHash,
+        resolve: impl FnOnce() -> ProviderResult<LowestAvailableAddressBlocks>,
+    ) -> ProviderResult<Arc<LowestAvailableAddressBlocks>> {
+        if let Some((cached_tip, blocks)) = self.inner.lock().as_ref() {
+            if *cached_tip == tip {
+                return Ok(blocks.clone())
+            }
+        }
+
+        // Resolve outside of the lock, concurrent providers may resolve the same tip twice
+        let blocks = Arc::new(resolve()?);
+        *self.inner.lock() = Some((tip, blocks.clone()));
+        Ok(blocks)
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use crate::{
-        providers::state::historical::{HistoryInfo, LowestAvailableBlocks},
+        providers::{
+            state::historical::{
+                HistoryInfo, LowestAvailableAddressBlocks, LowestAvailableAddressBlocksCache,
+                LowestAvailableBlocks,
+            },
+            RevertStateCache, StaticFileWriter,
+        },
         test_utils::create_test_provider_factory,
-        AccountReader, HistoricalStateProvider, HistoricalStateProviderRef, StateProvider,
-        StaticFileProviderFactory,
+        AccountReader, HistoricalStateProvider, HistoricalStateProviderRef, StateProofProvider,
+        StateProvider, StateRootProvider, StaticFileProviderFactory,
     };
     use reth_db::{tables, BlockNumberList};
     use reth_db_api::{
-        models::{storage_sharded_key::StorageShardedKey, AccountBeforeTx, ShardedKey},
+        models::{
+            storage_sharded_key::StorageShardedKey, AccountBeforeTx, ShardedKey, StorageBeforeTx,
+        },
         transaction::{DbTx, DbTxMut},
     };
-    use reth_primitives::{address, b256, Account, Address, StorageEntry, B256, U256};
+    use reth_primitives::{
+        address, b256, keccak256, Account, Address, BlockNumHash, Header, StaticFileSegment,
+        StorageEntry, B256, U256,
+    };
     use reth_storage_errors::provider::ProviderError;
+    use reth_trie::HashedPostState;
+    use std::{ops::RangeInclusive, sync::Arc};
 
     const ADDRESS: Address = address!("0000000000000000000000000000000000000001");
     const HIGHER_ADDRESS: Address = address!("0000000000000000000000000000000000000005");
@@ -695,6 +1071,123 @@ mod tests {
         );
     }
 
+    #[test]
+    fn history_provider_get_from_static_files() {
+        let factory = create_test_provider_factory();
+        let tx = factory.provider_rw().unwrap().into_tx();
+        let static_file_provider = factory.static_file_provider();
+
+        let acc_at3 = Account { nonce: 3, balance: U256::ZERO, bytecode_hash: None };
+        let acc_at7 = Account { nonce: 7, balance: U256::ZERO, bytecode_hash: None };
+        let storage_at3 = StorageEntry { key: STORAGE, value: U256::from(3) };
+        let storage_at7 = StorageEntry { key: STORAGE, value: U256::from(7) };
+
+        tx.put::<tables::AccountsHistory>(
+            ShardedKey { key: ADDRESS, highest_block_number: u64::MAX },
+            BlockNumberList::new([3, 7]).unwrap(),
+        )
+        .unwrap();
+        tx.put::<tables::StoragesHistory>(
+            StorageShardedKey {
+                address: ADDRESS,
+                sharded_key: ShardedKey { key: STORAGE, highest_block_number: u64::MAX },
+            },
+            BlockNumberList::new([3, 7]).unwrap(),
+        )
+        .unwrap();
+
+        // Blocks up to 4 have been moved to static files, the rest is in the database.
+        {
+            let mut accounts_writer =
+                static_file_provider.latest_writer(StaticFileSegment::AccountChangeSets).unwrap();
+            let mut storages_writer =
+                static_file_provider.latest_writer(StaticFileSegment::StorageChangeSets).unwrap();
+            for block in 0..=4 {
+                let (accounts, storages) = if block == 3 {
+                    (
+                        vec![AccountBeforeTx { address: ADDRESS, info: Some(acc_at3) }],
+                        vec![StorageBeforeTx { address: ADDRESS, entry: storage_at3 }],
+                    )
+                } else {
+                    (Vec::new(), Vec::new())
+                };
+                accounts_writer.append_account_change_set(block, accounts).unwrap();
+                storages_writer.append_storage_change_set(block, storages).unwrap();
+            }
+            accounts_writer.commit().unwrap();
+            storages_writer.commit().unwrap();
+        }
+        tx.put::<tables::AccountChangeSets>(
+            7,
+            AccountBeforeTx { address: ADDRESS, info: Some(acc_at7) },
+        )
+        .unwrap();
+        tx.put::<tables::StorageChangeSets>((7, ADDRESS).into(), storage_at7).unwrap();
+        tx.put::<tables::CanonicalHeaders>(10, B256::ZERO).unwrap();
+        tx.commit().unwrap();
+
+        let tx = factory.provider().unwrap().into_tx();
+
+        let provider = HistoricalStateProviderRef::new(&tx, 3, static_file_provider.clone());
+        assert_eq!(provider.basic_account(ADDRESS), Ok(Some(acc_at3)));
+        assert_eq!(provider.storage(ADDRESS, STORAGE), Ok(Some(storage_at3.value)));
+
+        // Earliest changes, read from static files, take precedence over the database ones.
+        let revert_state = provider.revert_state().unwrap();
+        assert_eq!(revert_state.accounts.get(&keccak256(ADDRESS)), Some(&Some(acc_at3)));
+        assert_eq!(
+            revert_state
+                .storages
+                .get(&keccak256(ADDRESS))
+                .unwrap()
+                .storage
+                .get(&keccak256(STORAGE)),
+            Some(&storage_at3.value)
+        );
+
+        let provider = HistoricalStateProviderRef::new(&tx, 5, static_file_provider);
+        assert_eq!(provider.basic_account(ADDRESS), Ok(Some(acc_at7)));
+        assert_eq!(provider.storage(ADDRESS, STORAGE), Ok(Some(storage_at7.value)));
+        let revert_state = provider.revert_state().unwrap();
+        assert_eq!(revert_state.accounts.get(&keccak256(ADDRESS)), Some(&Some(acc_at7)));
+    }
+
+    #[test]
+    fn history_provider_revert_state_static_file_tip() {
+        let factory = create_test_provider_factory();
+        let tx = factory.provider_rw().unwrap().into_tx();
+        let static_file_provider = factory.static_file_provider();
+        let cache = RevertStateCache::new(2);
+
+        let acc_at6 = Account { nonce: 6, balance: U256::ZERO, bytecode_hash: None };
+
+        // Headers up to block 8 are only in static files, the database tip is stale.
+        {
+            let mut headers_writer =
+                static_file_provider.latest_writer(StaticFileSegment::Headers).unwrap();
+            for number in 0..=8 {
+                let header = Header { number, ..Default::default() };
+                headers_writer
+                    .append_header(&header, U256::ZERO, &B256::with_last_byte(number as u8))
+                    .unwrap();
+            }
+            headers_writer.commit().unwrap();
+        }
+        tx.put::<tables::CanonicalHeaders>(2, B256::with_last_byte(2)).unwrap();
+        tx.put::<tables::AccountChangeSets>(
+            6,
+            AccountBeforeTx { address: ADDRESS, info: Some(acc_at6) },
+        )
+        .unwrap();
+        tx.commit().unwrap();
+
+        let tx = factory.provider().unwrap().into_tx();
+        let provider = HistoricalStateProviderRef::new(&tx, 4, static_file_provider);
+        let revert_state = provider.revert_state().unwrap();
+        assert_eq!(revert_state.accounts.get(&keccak256(ADDRESS)), Some(&Some(acc_at6)));
+        assert_eq!(provider.with_revert_state_cache(&cache).revert_state().unwrap(), revert_state);
+    }
+
     #[test]
     fn history_provider_unavailable() {
         let factory = create_test_provider_factory();
@@ -755,4 +1248,158 @@ mod tests {
             Ok(HistoryInfo::MaybeInPlainState)
         );
     }
+
+    #[test]
+    fn history_provider_address_unavailable() {
+        let factory = create_test_provider_factory();
+        let tx = factory.provider_rw().unwrap().into_tx();
+        let static_file_provider = factory.static_file_provider();
+
+        // `ADDRESS` is pruned up to a lower block than the other addresses, and `HIGHER_ADDRESS`
+        // up to a higher one
+        let lowest_available_address_blocks = LowestAvailableAddressBlocks {
+            account_history: [(ADDRESS, Some(2)), (HIGHER_ADDRESS, Some(5))].into(),
+            storage_history: [(ADDRESS, None), (HIGHER_ADDRESS, Some(5))].into(),
+        };
+        let provider = HistoricalStateProviderRef::new_with_lowest_available_blocks(
+            &tx,
+            3,
+            LowestAvailableBlocks {
+                account_history_block_number: Some(4),
+                storage_history_block_number: Some(4),
+            },
+            static_file_provider,
+        )
+        .with_lowest_available_address_blocks(&lowest_available_address_blocks);
+
+        assert_eq!(provider.account_history_lookup(ADDRESS), Ok(HistoryInfo::MaybeInPlainState));
+        assert_eq!(
+            provider.storage_history_lookup(ADDRESS, STORAGE),
+            Ok(HistoryInfo::MaybeInPlainState)
+        );
+        for address in [HIGHER_ADDRESS, Address::with_last_byte(3)] {
+            assert_eq!(
+                provider.account_history_lookup(address),
+                Err(ProviderError::AddressHistoryPruned { address, block_number: 3 })
+            );
+            assert_eq!(
+                provider.storage_history_lookup(address, STORAGE),
+                Err(ProviderError::AddressHistoryPruned { address, block_number: 3 })
+            );
+        }
+
+        // Only the lookups touching an address with pruned change sets fail, while the state
+        // root depends on all of them.
+        tx.put::<tables::CanonicalHeaders>(3, B256::ZERO).unwrap();
+        let provider = HistoricalStateProviderRef::new(&tx, 3, factory.static_file_provider())
+            .with_lowest_available_address_blocks(&lowest_available_address_blocks);
+        let pruned =
+            Err(ProviderError::AddressHistoryPruned { address: HIGHER_ADDRESS, block_number: 3 });
+        assert!(provider.hashed_proof(HashedPostState::default(), ADDRESS, &[STORAGE]).is_ok());
+        assert_eq!(
+            provider.hashed_proof(HashedPostState::default(), HIGHER_ADDRESS, &[]).map(|_| ()),
+            pruned
+        );
+        let target = HashedPostState::default().with_accounts([(keccak256(HIGHER_ADDRESS), None)]);
+        assert_eq!(provider.witness(HashedPostState::default(), target).map(|_| ()), pruned);
+        assert_eq!(provider.hashed_state_root(HashedPostState::default()).map(|_| ()), pruned);
+    }
+
+    #[test]
+    fn lowest_available_address_blocks_cache() {
+        let cache = LowestAvailableAddressBlocksCache::default();
+        let tip = BlockNumHash::new(10, B256::with_last_byte(1));
+        let resolved = |block_number| {
+            Ok(LowestAvailableAddressBlocks {
+                account_history: [(ADDRESS, Some(block_number))].into(),
+                storage_history: Default::default(),
+            })
+        };
+
+        let blocks = cache.get_or_try_insert_with(tip, || resolved(5)).unwrap();
+        assert_eq!(blocks.account_history[&ADDRESS], Some(5));
+
+        // The blocks are only resolved once per tip
+        let cached = cache.get_or_try_insert_with(tip, || panic!("resolved twice")).unwrap();
+        assert!(Arc::ptr_eq(&blocks, &cached));
+
+        // A reorged tip with the same number is resolved again
+        let reorged_tip = BlockNumHash::new(10, B256::with_last_byte(2));
+        let blocks = cache.get_or_try_insert_with(reorged_tip, || resolved(6)).unwrap();
+        assert_eq!(blocks.account_history[&ADDRESS], Some(6));
+
+        // Failures are not cached
+        assert!(cache
+            .get_or_try_insert_with(tip, || Err(ProviderError::HeaderNotFound(10.into())))
+            .is_err());
+        let blocks = cache.get_or_try_insert_with(tip, || resolved(5)).unwrap();
+        assert_eq!(blocks.account_history[&ADDRESS], Some(5));
+    }
+
+    #[test]
+    fn history_provider_revert_state_cache() {
+        let factory = create_test_provider_factory();
+        let cache = RevertStateCache::new(2);
+
+        // Every block changes `ADDRESS` and creates an account of its own. Blocks of a fork have
+        // different hashes and changes.
+        let insert_blocks = |blocks: RangeInclusive<u64>, fork: u64| {
+            let tx = factory.provider_rw().unwrap().into_tx();
+            for block in blocks {
+                tx.delete::<tables::AccountChangeSets>(block, None).unwrap();
+                tx.delete::<tables::StorageChangeSets>((block, ADDRESS).into(), None).unwrap();
+
+                let value = block * 100 + fork;
+                let account = Account { nonce: value, ..Default::default() };
+                tx.put::<tables::CanonicalHeaders>(block, B256::from(U256::from(value))).unwrap();
+                tx.put::<tables::AccountChangeSets>(
+                    block,
+                    AccountBeforeTx { address: ADDRESS, info: Some(account) },
+                )
+                .unwrap();
+                tx.put::<tables::AccountChangeSets>(
+                    block,
+                    AccountBeforeTx { address: Address::with_last_byte(value as u8), info: None },
+                )
+                .unwrap();
+                tx.put::<tables::StorageChangeSets>(
+                    (block, ADDRESS).into(),
+                    StorageEntry { key: STORAGE, value: U256::from(value) },
+                )
+                .unwrap();
+            }
+            tx.commit().unwrap();
+        };
+
+        // The cached revert states match the ones collected from all change sets up to the tip.
+        let assert_revert_state = |block_number| {
+            let tx = factory.provider().unwrap().into_tx();
+            let provider =
+                HistoricalStateProviderRef::new(&tx, block_number, factory.static_file_provider());
+            let expected = provider.revert_state().unwrap();
+            assert_eq!(
+                provider.with_revert_state_cache(&cache).revert_state().unwrap(),
+                expected,
+                "revert state at block {block_number}"
+            );
+        };
+
+        insert_blocks(0..=6, 0);
+        // Collected, derived from a higher block, cached and collected above the cached blocks.
+        for block_number in [4, 2, 2, 5] {
+            assert_revert_state(block_number);
+        }
+
+        // Brought up to date with the new blocks.
+        insert_blocks(7..=8, 0);
+        for block_number in [2, 5, 1] {
+            assert_revert_state(block_number);
+        }
+
+        // Revert states on top of the reorged blocks are dropped.
+        insert_blocks(8..=9, 1);
+        for block_number in [1, 3, 8, 7] {
+            assert_revert_state(block_number);
+        }
+    }
 }
@@ -0,0 +1,137 @@
+use parking_lot::Mutex;
+use reth_primitives::{BlockNumHash, BlockNumber};
+use reth_storage_errors::provider::ProviderResult;
+use reth_trie::HashedPostState;
+use std::{collections::BTreeMap, sync::Arc};
+
+/// The default number of revert states kept by the [`RevertStateCache`].
+pub const DEFAULT_REVERT_STATE_CACHE_SIZE: usize = 8;
+
+/// A cache of the revert states of historical blocks, shared by the historical state providers
+/// of a [`ProviderFactory`](crate::ProviderFactory).
+///
+/// Historical state roots and proofs are calculated on top of the latest trie with the revert
+/// state of the block, collected from the change sets of all blocks from the historical block up
+/// to the tip. A cached revert state is brought up to date with the change sets of the blocks
+/// added since it was collected, and the revert state of a lower block is derived from it with
+/// the change sets of the blocks in between, so neither has to walk the change sets up to the tip
+/// again.
+///
+/// The cache is bounded by the number of revert states, the least recently used one is evicted
+/// first. Revert states collected on top of a tip that is no longer canonical are dropped.
+#[derive(Clone, Debug)]
+pub struct RevertStateCache {
+    inner: Arc<Mutex<RevertStateCacheInner>>,
+}
+
+impl RevertStateCache {
+    /// Creates a new cache keeping up to the given number of revert states.
+    pub fn new(capacity: usize) -> Self {
+        Self {
+            inner: Arc::new(Mutex::new(RevertStateCacheInner {
+                capacity,
+                last_used: 0,
+                entries: BTreeMap::new(),
+            })),
+        }
+    }
+
+    /// Returns the cached revert state of the lowest block at or above the given block, collected
+    /// on top of a tip that is still canonical according to the given closure.
+    pub(crate) fn get(
+        &self,
+        block_number: BlockNumber,
+        mut is_canonical: impl FnMut(BlockNumHash) -> ProviderResult<bool>,
+    ) -> ProviderResult<Option<CachedRevertState>> {
+        // Checking the tips reads the database, so it's done without holding the lock.
+        let candidates = self
+            .inner
+            .lock()
+            .entries
+            .range(block_number..)
+            .map(|(number, entry)| (*number, entry.tip))
+            .collect::<Vec<_>>();
+        let mut reorged = Vec::new();
+        let mut found = None;
+        for (number, tip) in candidates {
+            if is_canonical(tip)? {
+                found = Some((number, tip));
+                break
+            }
+            reorged.push((number, tip));
+        }
+
+        // The entries might have been replaced or evicted since the tips were checked.
+        let mut inner = self.inner.lock();
+        for (number, tip) in reorged {
+            if inner.entries.get(&number).is_some_and(|entry| entry.tip == tip) {
+                inner.entries.remove(&number);
+            }
+        }
+
+        let Some((number, tip)) = found else { return Ok(None) };
+        inner.last_used += 1;
+        let last_used = inner.last_used;
+        let Some(entry) = inner.entries.get_mut(&number).filter(|entry| entry.tip == tip) else {
+            return Ok(None)
+        };
+        entry.last_used = last_used;
+        Ok(Some(CachedRevertState { block_number: number, tip, state: entry.state.clone() }))
+    }
+
+    /// Caches the revert state of the given block, collected on top of the given tip.
+    pub(crate) fn insert(
+        &self,
+        block_number: BlockNumber,
+        tip: BlockNumHash,
+        state: Arc<HashedPostState>,
+    ) {
+        let mut inner = self.inner.lock();
+        if inner.capacity == 0 {
+            return
+        }
+
+        inner.last_used += 1;
+        let last_used = inner.last_used;
+        inner.entries.insert(block_number, RevertStateEntry { tip, state, last_used });
+        while inner.entries.len() > inner.capacity {
+            let Some(lru) = inner.entries.iter().min_by_key(|(_, entry)| entry.last_used) else {
+                break
+            };
+            let lru = *lru.0;
+            inner.entries.remove(&lru);
+        }
+    }
+}
+
+impl Default for RevertStateCache {
+    fn default() -> Self {
+        Self::new(DEFAULT_REVERT_STATE_CACHE_SIZE)
+    }
+}
+
+/// A revert state returned by the [`RevertStateCache`].
+#[derive(Debug)]
+pub(crate) struct CachedRevertState {
+    /// The block the revert state reverts to, i.e. the first block of the reverted change sets.
+    pub(crate) block_number: BlockNumber,
+    /// The tip the revert state was collected on top of, i.e. the last block of the reverted
+    /// change sets.
+    pub(crate) tip: BlockNumHash,
+    /// The revert state.
+    pub(crate) state: Arc<HashedPostState>,
+}
+
+#[derive(Debug)]
+struct RevertStateCacheInner {
+    capacity: usize,
+    last_used: u64,
+    entries: BTreeMap<BlockNumber, RevertStateEntry>,
+}
+
+#[derive(Debug)]
+struct RevertStateEntry {
+    tip: BlockNumHash,
+    state: Arc<HashedPostState>,
+    last_used: u64,
+}
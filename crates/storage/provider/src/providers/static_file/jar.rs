@@ -7,10 +7,15 @@ use crate::{
     TransactionsProvider,
 };
 use reth_chainspec::ChainInfo;
-use reth_db::static_file::{HeaderMask, ReceiptMask, StaticFileCursor, TransactionMask};
-use reth_db_api::models::CompactU256;
+use reth_db::static_file::{
+    AccountChangeSetMask, ColumnSelectorOne, HeaderMask, ReceiptMask, StaticFileCursor,
+    StorageChangeSetMask, TransactionMask,
+};
+use reth_db_api::models::{
+    AccountBeforeTx, BlockAccountChangeSet, BlockStorageChangeSet, CompactU256, StorageBeforeTx,
+};
 use reth_primitives::{
-    Address, BlockHash, BlockHashOrNumber, BlockNumber, Header, Receipt, SealedHeader,
+    Address, BlockHash, BlockHashOrNumber, BlockNumber, Header, Receipt, SealedHeader, StorageKey,
     TransactionMeta, TransactionSigned, TransactionSignedNoHash, TxHash, TxNumber, B256, U256,
 };
 use reth_storage_errors::provider::{ProviderError, ProviderResult};
@@ -72,6 +77,55 @@ impl<'a> StaticFileJarProvider<'a> {
         self.metrics = Some(metrics);
         self
     }
+
+    /// Returns the account changes of the given block.
+    pub fn account_change_set(
+        &self,
+        block: BlockNumber,
+    ) -> ProviderResult<Option<Vec<AccountBeforeTx>>> {
+        Ok(self
+            .cursor()?
+            .get_one::<AccountChangeSetMask<BlockAccountChangeSet>>(block.into())?
+            .map(|changes| changes.0))
+    }
+
+    /// Returns the storage changes of the given block.
+    pub fn storage_change_set(
+        &self,
+        block: BlockNumber,
+    ) -> ProviderResult<Option<Vec<StorageBeforeTx>>> {
+        Ok(self
+            .cursor()?
+            .get_one::<StorageChangeSetMask<BlockStorageChangeSet>>(block.into())?
+            .map(|changes| changes.0))
+    }
+
+    /// Returns the change of `address` in the given block, without decoding the rest of the
+    /// block account changes.
+    pub fn account_change(
+        &self,
+        block: BlockNumber,
+        address: Address,
+    ) -> ProviderResult<Option<AccountBeforeTx>> {
+        Ok(self
+            .cursor()?
+            .get(block.into(), <AccountChangeSetMask<BlockAccountChangeSet>>::MASK)?
+            .and_then(|row| BlockAccountChangeSet::find_compact(row[0], address)))
+    }
+
+    /// Returns the change of the `address` storage slot `key` in the given block, without decoding
+    /// the rest of the block storage changes.
+    pub fn storage_change(
+        &self,
+        block: BlockNumber,
+        address: Address,
+        key: StorageKey,
+    ) -> ProviderResult<Option<StorageBeforeTx>> {
+        Ok(self
+            .cursor()?
+            .get(block.into(), <StorageChangeSetMask<BlockStorageChangeSet>>::MASK)?
+            .and_then(|row| BlockStorageChangeSet::find_compact(row[0], address, key)))
+    }
 }
 
 impl<'a> HeaderProvider for StaticFileJarProvider<'a> {
@@ -18,7 +18,7 @@ use reth_db::{
 };
 use reth_db_api::{
     cursor::DbCursorRO,
-    models::{CompactU256, StoredBlockBodyIndices},
+    models::{AccountBeforeTx, CompactU256, StorageBeforeTx, StoredBlockBodyIndices},
     table::Table,
     transaction::DbTx,
 };
@@ -27,9 +27,9 @@ use reth_primitives::{
     keccak256,
     static_file::{find_fixed_range, HighestStaticFiles, SegmentHeader, SegmentRangeInclusive},
     Address, Block, BlockHash, BlockHashOrNumber, BlockNumber, BlockWithSenders, Header, Receipt,
-    SealedBlock, SealedBlockWithSenders, SealedHeader, StaticFileSegment, TransactionMeta,
-    TransactionSigned, TransactionSignedNoHash, TxHash, TxNumber, Withdrawal, Withdrawals, B256,
-    U256,
+    SealedBlock, SealedBlockWithSenders, SealedHeader, StaticFileSegment, StorageKey,
+    TransactionMeta, TransactionSigned, TransactionSignedNoHash, TxHash, TxNumber, Withdrawal,
+    Withdrawals, B256, U256,
 };
 use reth_stages_types::{PipelineTarget, StageId};
 use reth_storage_errors::provider::{ProviderError, ProviderResult};
@@ -447,12 +447,7 @@ impl StaticFileProvider {
                 } else if tx_index.get(&segment).map(|index| index.len()) == Some(1) {
                     // Only happens if we unwind all the txs/receipts from the first static file.
                     // Should only happen in test scenarios.
-                    if jar.user_header().expected_block_start() == 0 &&
-                        matches!(
-                            segment,
-                            StaticFileSegment::Receipts | StaticFileSegment::Transactions
-                        )
-                    {
+                    if jar.user_header().expected_block_start() == 0 && segment.is_tx_based() {
                         tx_index.remove(&segment);
                     }
                 }
@@ -563,6 +558,11 @@ impl StaticFileProvider {
                 continue
             }
 
+            if segment.is_change_sets() && self.get_highest_static_file_block(segment).is_none() {
+                // Change sets are only moved to static files once finalized, so there may be none.
+                continue
+            }
+
             let initial_highest_block = self.get_highest_static_file_block(segment);
 
             //  File consistency is broken if:
@@ -575,7 +575,7 @@ impl StaticFileProvider {
             //   accordingly.
             self.ensure_file_consistency(segment)?;
 
-            // Only applies to block-based static files. (Headers)
+            // Only applies to block-based static files. (Headers & Change Sets)
             //
             // The updated `highest_block` may have decreased if we healed from a pruning
             // interruption.
@@ -647,6 +647,10 @@ impl StaticFileProvider {
                     highest_tx,
                     highest_block,
                 )?,
+                StaticFileSegment::AccountChangeSets | StaticFileSegment::StorageChangeSets => {
+                    self.ensure_change_set_invariants(provider, segment, highest_block)?;
+                    None
+                }
             } {
                 update_unwind_target(unwind);
             }
@@ -711,7 +715,9 @@ impl StaticFileProvider {
             .get_stage_checkpoint(match segment {
                 StaticFileSegment::Headers => StageId::Headers,
                 StaticFileSegment::Transactions => StageId::Bodies,
-                StaticFileSegment::Receipts => StageId::Execution,
+                StaticFileSegment::Receipts |
+                StaticFileSegment::AccountChangeSets |
+                StaticFileSegment::StorageChangeSets => StageId::Execution,
             })?
             .unwrap_or_default()
             .block_number;
@@ -756,6 +762,39 @@ impl StaticFileProvider {
         Ok(None)
     }
 
+    /// Check invariants for the change set static file segments.
+    ///
+    /// Change sets are only moved to static files by the static file producer, and the database
+    /// keeps the change sets above the highest static file block. So only a static file ahead of
+    /// the [`StageId::Execution`] checkpoint is inconsistent, in which case the extra rows are
+    /// removed from the static file.
+    fn ensure_change_set_invariants<TX: DbTx>(
+        &self,
+        provider: &DatabaseProvider<TX>,
+        segment: StaticFileSegment,
+        highest_static_file_block: Option<BlockNumber>,
+    ) -> ProviderResult<()> {
+        let Some(highest_static_file_block) = highest_static_file_block else { return Ok(()) };
+
+        let checkpoint_block_number =
+            provider.get_stage_checkpoint(StageId::Execution)?.unwrap_or_default().block_number;
+
+        if checkpoint_block_number < highest_static_file_block {
+            info!(
+                target: "reth::providers",
+                ?segment,
+                from = highest_static_file_block,
+                to = checkpoint_block_number,
+                "Unwinding static file segment."
+            );
+            let mut writer = self.latest_writer(segment)?;
+            writer.prune_change_sets(highest_static_file_block - checkpoint_block_number)?;
+            writer.commit()?;
+        }
+
+        Ok(())
+    }
+
     /// Gets the highest static file block if it exists for a static file segment.
     ///
     /// If there is nothing on disk for the given segment, this will return [`None`].
@@ -779,9 +818,92 @@ impl StaticFileProvider {
             headers: self.get_highest_static_file_block(StaticFileSegment::Headers),
             receipts: self.get_highest_static_file_block(StaticFileSegment::Receipts),
             transactions: self.get_highest_static_file_block(StaticFileSegment::Transactions),
+            account_change_sets: self
+                .get_highest_static_file_block(StaticFileSegment::AccountChangeSets),
+            storage_change_sets: self
+                .get_highest_static_file_block(StaticFileSegment::StorageChangeSets),
         }
     }
 
+    /// Returns the account changes of the given block from the account change sets static files.
+    ///
+    /// Returns [`None`] if the block has not been moved to static files.
+    pub fn account_change_set(
+        &self,
+        block: BlockNumber,
+    ) -> ProviderResult<Option<Vec<AccountBeforeTx>>> {
+        self.get_segment_provider_from_block(StaticFileSegment::AccountChangeSets, block, None)
+            .and_then(|provider| provider.account_change_set(block))
+            .or_else(|err| {
+                if let ProviderError::MissingStaticFileBlock(_, _) = err {
+                    Ok(None)
+                } else {
+                    Err(err)
+                }
+            })
+    }
+
+    /// Returns the storage changes of the given block from the storage change sets static files.
+    ///
+    /// Returns [`None`] if the block has not been moved to static files.
+    pub fn storage_change_set(
+        &self,
+        block: BlockNumber,
+    ) -> ProviderResult<Option<Vec<StorageBeforeTx>>> {
+        self.get_segment_provider_from_block(StaticFileSegment::StorageChangeSets, block, None)
+            .and_then(|provider| provider.storage_change_set(block))
+            .or_else(|err| {
+                if let ProviderError::MissingStaticFileBlock(_, _) = err {
+                    Ok(None)
+                } else {
+                    Err(err)
+                }
+            })
+    }
+
+    /// Returns the change of `address` in the given block from the account change sets static
+    /// files.
+    ///
+    /// Returns [`None`] if the block has not been moved to static files or `address` did not
+    /// change in it.
+    pub fn account_change(
+        &self,
+        block: BlockNumber,
+        address: Address,
+    ) -> ProviderResult<Option<AccountBeforeTx>> {
+        self.get_segment_provider_from_block(StaticFileSegment::AccountChangeSets, block, None)
+            .and_then(|provider| provider.account_change(block, address))
+            .or_else(|err| {
+                if let ProviderError::MissingStaticFileBlock(_, _) = err {
+                    Ok(None)
+                } else {
+                    Err(err)
+                }
+            })
+    }
+
+    /// Returns the change of the `address` storage slot `key` in the given block from the storage
+    /// change sets static files.
+    ///
+    /// Returns [`None`] if the block has not been moved to static files or the slot did not
+    /// change in it.
+    pub fn storage_change(
+        &self,
+        block: BlockNumber,
+        address: Address,
+        key: StorageKey,
+    ) -> ProviderResult<Option<StorageBeforeTx>> {
+        self.get_segment_provider_from_block(StaticFileSegment::StorageChangeSets, block, None)
+            .and_then(|provider| provider.storage_change(block, address, key))
+            .or_else(|err| {
+                if let ProviderError::MissingStaticFileBlock(_, _) = err {
+                    Ok(None)
+                } else {
+                    Err(err)
+                }
+            })
+    }
+
     /// Iterates through segment `static_files` in reverse order, executing a function until it
     /// returns some object. Useful for finding objects by [`TxHash`] or [`BlockHash`].
     pub fn find_static_file<T>(
@@ -821,11 +943,10 @@ impl StaticFileProvider {
         F: FnMut(&mut StaticFileCursor<'_>, u64) -> ProviderResult<Option<T>>,
         P: FnMut(&T) -> bool,
     {
-        let get_provider = |start: u64| match segment {
-            StaticFileSegment::Headers => {
+        let get_provider = |start: u64| {
+            if segment.is_block_based() {
                 self.get_segment_provider_from_block(segment, start, None)
-            }
-            StaticFileSegment::Transactions | StaticFileSegment::Receipts => {
+            } else {
                 self.get_segment_provider_from_transaction(segment, start, None)
             }
         };
@@ -860,7 +981,7 @@ impl StaticFileProvider {
                                 "Could not find block or tx number on a range request"
                             );
 
-                            let err = if segment.is_headers() {
+                            let err = if segment.is_block_based() {
                                 ProviderError::MissingStaticFileBlock(segment, number)
                             } else {
                                 ProviderError::MissingStaticFileTx(segment, number)
@@ -897,11 +1018,10 @@ impl StaticFileProvider {
         F: Fn(&mut StaticFileCursor<'_>, u64) -> ProviderResult<Option<T>> + 'a,
         T: std::fmt::Debug,
     {
-        let get_provider = move |start: u64| match segment {
-            StaticFileSegment::Headers => {
+        let get_provider = move |start: u64| {
+            if segment.is_block_based() {
                 self.get_segment_provider_from_block(segment, start, None)
-            }
-            StaticFileSegment::Transactions | StaticFileSegment::Receipts => {
+            } else {
                 self.get_segment_provider_from_transaction(segment, start, None)
             }
         };
@@ -949,11 +1069,10 @@ impl StaticFileProvider {
         FD: Fn() -> ProviderResult<Option<T>>,
     {
         // If there is, check the maximum block or transaction number of the segment.
-        let static_file_upper_bound = match segment {
-            StaticFileSegment::Headers => self.get_highest_static_file_block(segment),
-            StaticFileSegment::Transactions | StaticFileSegment::Receipts => {
-                self.get_highest_static_file_tx(segment)
-            }
+        let static_file_upper_bound = if segment.is_block_based() {
+            self.get_highest_static_file_block(segment)
+        } else {
+            self.get_highest_static_file_tx(segment)
         };
 
         if static_file_upper_bound
@@ -991,11 +1110,10 @@ impl StaticFileProvider {
         let mut data = Vec::new();
 
         // If there is, check the maximum block or transaction number of the segment.
-        if let Some(static_file_upper_bound) = match segment {
-            StaticFileSegment::Headers => self.get_highest_static_file_block(segment),
-            StaticFileSegment::Transactions | StaticFileSegment::Receipts => {
-                self.get_highest_static_file_tx(segment)
-            }
+        if let Some(static_file_upper_bound) = if segment.is_block_based() {
+            self.get_highest_static_file_block(segment)
+        } else {
+            self.get_highest_static_file_tx(segment)
         } {
             if block_or_tx_range.start <= static_file_upper_bound {
                 let end = block_or_tx_range.end.min(static_file_upper_bound + 1);
@@ -4,7 +4,9 @@ use super::{
 use crate::providers::static_file::metrics::StaticFileProviderOperation;
 use parking_lot::{lock_api::RwLockWriteGuard, RawRwLock, RwLock};
 use reth_codecs::Compact;
-use reth_db_api::models::CompactU256;
+use reth_db_api::models::{
+    AccountBeforeTx, BlockAccountChangeSet, BlockStorageChangeSet, CompactU256, StorageBeforeTx,
+};
 use reth_nippy_jar::{ConsistencyFailStrategy, NippyJar, NippyJarError, NippyJarWriter};
 use reth_primitives::{
     static_file::{find_fixed_range, SegmentHeader, SegmentRangeInclusive},
@@ -29,6 +31,8 @@ pub(crate) struct StaticFileWriters {
     headers: RwLock<Option<StaticFileProviderRW>>,
     transactions: RwLock<Option<StaticFileProviderRW>>,
     receipts: RwLock<Option<StaticFileProviderRW>>,
+    account_change_sets: RwLock<Option<StaticFileProviderRW>>,
+    storage_change_sets: RwLock<Option<StaticFileProviderRW>>,
 }
 
 impl StaticFileWriters {
@@ -41,6 +45,8 @@ impl StaticFileWriters {
             StaticFileSegment::Headers => self.headers.write(),
             StaticFileSegment::Transactions => self.transactions.write(),
             StaticFileSegment::Receipts => self.receipts.write(),
+            StaticFileSegment::AccountChangeSets => self.account_change_sets.write(),
+            StaticFileSegment::StorageChangeSets => self.storage_change_sets.write(),
         };
 
         if write_guard.is_none() {
@@ -51,7 +57,13 @@ impl StaticFileWriters {
     }
 
     pub(crate) fn commit(&self) -> ProviderResult<()> {
-        for writer_lock in [&self.headers, &self.transactions, &self.receipts] {
+        for writer_lock in [
+            &self.headers,
+            &self.transactions,
+            &self.receipts,
+            &self.account_change_sets,
+            &self.storage_change_sets,
+        ] {
             let mut writer = writer_lock.write();
             if let Some(writer) = writer.as_mut() {
                 writer.commit()?;
@@ -205,7 +217,7 @@ impl StaticFileProviderRW {
         })?;
 
         // If we have lost rows (in this run or previous), we need to update the [SegmentHeader].
-        let expected_rows = if self.user_header().segment().is_headers() {
+        let expected_rows = if self.user_header().segment().is_block_based() {
             self.user_header().block_len().unwrap_or_default()
         } else {
             self.user_header().tx_len().unwrap_or_default()
@@ -233,6 +245,10 @@ impl StaticFileProviderRW {
         if let Some((to_delete, last_block_number)) = self.prune_on_commit.take() {
             match self.writer.user_header().segment() {
                 StaticFileSegment::Headers => self.prune_header_data(to_delete)?,
+                segment @ (StaticFileSegment::AccountChangeSets |
+                StaticFileSegment::StorageChangeSets) => {
+                    self.prune_change_set_data(segment, to_delete)?
+                }
                 StaticFileSegment::Transactions => self
                     .prune_transaction_data(to_delete, last_block_number.expect("should exist"))?,
                 StaticFileSegment::Receipts => {
@@ -408,13 +424,10 @@ impl StaticFileProviderRW {
     ) -> ProviderResult<()> {
         let mut remaining_rows = num_rows;
         while remaining_rows > 0 {
-            let len = match segment {
-                StaticFileSegment::Headers => {
-                    self.writer.user_header().block_len().unwrap_or_default()
-                }
-                StaticFileSegment::Transactions | StaticFileSegment::Receipts => {
-                    self.writer.user_header().tx_len().unwrap_or_default()
-                }
+            let len = if segment.is_block_based() {
+                self.writer.user_header().block_len().unwrap_or_default()
+            } else {
+                self.writer.user_header().tx_len().unwrap_or_default()
             };
 
             if remaining_rows >= len {
@@ -648,6 +661,68 @@ impl StaticFileProviderRW {
         Ok(Some(tx_number))
     }
 
+    /// Appends the account changes of a block to the static file.
+    ///
+    /// It **CALLS** `increment_block()` since every block has exactly one row, even if it has no
+    /// account changes.
+    ///
+    /// Returns the current [`BlockNumber`] as seen in the static file.
+    pub fn append_account_change_set(
+        &mut self,
+        block_number: BlockNumber,
+        changes: Vec<AccountBeforeTx>,
+    ) -> ProviderResult<BlockNumber> {
+        let start = Instant::now();
+        self.ensure_no_queued_prune()?;
+
+        debug_assert!(self.writer.user_header().segment() == StaticFileSegment::AccountChangeSets);
+
+        let block_number = self.increment_block(block_number)?;
+
+        self.append_column(BlockAccountChangeSet::new(changes))?;
+
+        if let Some(metrics) = &self.metrics {
+            metrics.record_segment_operation(
+                StaticFileSegment::AccountChangeSets,
+                StaticFileProviderOperation::Append,
+                Some(start.elapsed()),
+            );
+        }
+
+        Ok(block_number)
+    }
+
+    /// Appends the storage changes of a block to the static file.
+    ///
+    /// It **CALLS** `increment_block()` since every block has exactly one row, even if it has no
+    /// storage changes.
+    ///
+    /// Returns the current [`BlockNumber`] as seen in the static file.
+    pub fn append_storage_change_set(
+        &mut self,
+        block_number: BlockNumber,
+        changes: Vec<StorageBeforeTx>,
+    ) -> ProviderResult<BlockNumber> {
+        let start = Instant::now();
+        self.ensure_no_queued_prune()?;
+
+        debug_assert!(self.writer.user_header().segment() == StaticFileSegment::StorageChangeSets);
+
+        let block_number = self.increment_block(block_number)?;
+
+        self.append_column(BlockStorageChangeSet::new(changes))?;
+
+        if let Some(metrics) = &self.metrics {
+            metrics.record_segment_operation(
+                StaticFileSegment::StorageChangeSets,
+                StaticFileProviderOperation::Append,
+                Some(start.elapsed()),
+            );
+        }
+
+        Ok(block_number)
+    }
+
     /// Adds an instruction to prune `to_delete`transactions during commit.
     ///
     /// Note: `last_block` refers to the block the unwinds ends at.
@@ -678,6 +753,12 @@ impl StaticFileProviderRW {
         self.queue_prune(to_delete, None)
     }
 
+    /// Adds an instruction to prune the change sets of the last `to_delete` blocks during commit.
+    pub fn prune_change_sets(&mut self, to_delete: u64) -> ProviderResult<()> {
+        debug_assert!(self.writer.user_header().segment().is_change_sets());
+        self.queue_prune(to_delete, None)
+    }
+
     /// Adds an instruction to prune `to_delete` elements during commit.
     ///
     /// Note: `last_block` refers to the block the unwinds ends at if dealing with transaction-based
@@ -770,6 +851,29 @@ impl StaticFileProviderRW {
         Ok(())
     }
 
+    /// Prunes the change sets of the last `to_delete` blocks from the data file.
+    fn prune_change_set_data(
+        &mut self,
+        segment: StaticFileSegment,
+        to_delete: u64,
+    ) -> ProviderResult<()> {
+        let start = Instant::now();
+
+        debug_assert!(self.writer.user_header().segment() == segment);
+
+        self.truncate(segment, to_delete, None)?;
+
+        if let Some(metrics) = &self.metrics {
+            metrics.record_segment_operation(
+                segment,
+                StaticFileProviderOperation::Prune,
+                Some(start.elapsed()),
+            );
+        }
+
+        Ok(())
+    }
+
     fn reader(&self) -> StaticFileProvider {
         Self::upgrade_provider_to_strong_reference(&self.reader)
     }
@@ -824,7 +928,7 @@ fn create_jar(
 
     // Transaction and Receipt already have the compression scheme used natively in its encoding.
     // (zstd-dictionary)
-    if segment.is_headers() {
+    if segment.is_block_based() {
         jar = jar.with_lz4();
     }
 
@@ -1,20 +1,25 @@
 use crate::{
     traits::{BlockSource, ReceiptProvider},
-    AccountReader, BlockHashReader, BlockIdReader, BlockNumReader, BlockReader, BlockReaderIdExt,
-    ChainSpecProvider, ChangeSetReader, EvmEnvProvider, HeaderProvider, ReceiptProviderIdExt,
-    RequestsProvider, StateProvider, StateProviderBox, StateProviderFactory, StateRootProvider,
-    TransactionVariant, TransactionsProvider, WithdrawalsProvider,
+    AccountHistoryReader, AccountReader, BadBlockReader, BlockHashReader, BlockIdReader,
+    BlockNumReader, BlockReader, BlockReaderIdExt, ChainSpecProvider, ChangeSetReader,
+    DatabaseProviderFactory, DatabaseProviderRO, EvmEnvProvider, HeaderProvider,
+    ReceiptProviderIdExt, RequestsProvider, StateProvider, StateProviderBox, StateProviderFactory,
+    StateRootProvider, TableDigest, TableDigestCursor, TableDigestReader, TransactionVariant,
+    TransactionsProvider, WithdrawalsProvider,
 };
 use parking_lot::Mutex;
 use reth_chainspec::{ChainInfo, ChainSpec};
-use reth_db_api::models::{AccountBeforeTx, StoredBlockBodyIndices};
+use reth_db_api::{
+    mock::DatabaseMock,
+    models::{AccountBeforeTx, StoredBadBlock, StoredBlockBodyIndices},
+};
 use reth_evm::ConfigureEvmEnv;
 use reth_primitives::{
     keccak256, Account, Address, Block, BlockHash, BlockHashOrNumber, BlockId, BlockNumber,
     BlockNumberOrTag, BlockWithSenders, Bytecode, Bytes, Header, Receipt, SealedBlock,
     SealedBlockWithSenders, SealedHeader, StorageKey, StorageValue, TransactionMeta,
     TransactionSigned, TransactionSignedNoHash, TxHash, TxNumber, Withdrawal, Withdrawals, B256,
-    U256,
+    KECCAK_EMPTY, U256,
 };
 use reth_stages_types::{StageCheckpoint, StageId};
 use reth_storage_api::{StageCheckpointReader, StateProofProvider};
@@ -136,6 +141,12 @@ impl MockEthProvider {
     }
 }
 
+impl DatabaseProviderFactory<DatabaseMock> for MockEthProvider {
+    fn database_provider_ro(&self) -> ProviderResult<DatabaseProviderRO<DatabaseMock>> {
+        Err(ProviderError::UnsupportedProvider)
+    }
+}
+
 impl HeaderProvider for MockEthProvider {
     fn header(&self, block_hash: &BlockHash) -> ProviderResult<Option<Header>> {
         let lock = self.headers.lock();
@@ -476,18 +487,23 @@ impl BlockReader for MockEthProvider {
 
     fn block_with_senders(
         &self,
-        _id: BlockHashOrNumber,
+        id: BlockHashOrNumber,
         _transaction_kind: TransactionVariant,
     ) -> ProviderResult<Option<BlockWithSenders>> {
-        Ok(None)
+        Ok(self.block(id)?.and_then(Block::with_recovered_senders))
     }
 
     fn sealed_block_with_senders(
         &self,
-        _id: BlockHashOrNumber,
+        id: BlockHashOrNumber,
         _transaction_kind: TransactionVariant,
     ) -> ProviderResult<Option<SealedBlockWithSenders>> {
-        Ok(None)
+        let lock = self.blocks.lock();
+        let block = match id {
+            BlockHashOrNumber::Hash(hash) => lock.get_key_value(&hash),
+            BlockHashOrNumber::Number(num) => lock.iter().find(|(_, b)| b.number == num),
+        };
+        Ok(block.and_then(|(hash, block)| block.clone().seal(*hash).seal_with_senders()))
     }
 
     fn block_range(&self, range: RangeInclusive<BlockNumber>) -> ProviderResult<Vec<Block>> {
@@ -762,3 +778,37 @@ impl ChangeSetReader for MockEthProvider {
         Ok(Vec::default())
     }
 }
+
+impl BadBlockReader for MockEthProvider {
+    fn bad_blocks(&self) -> ProviderResult<Vec<StoredBadBlock>> {
+        Ok(Vec::default())
+    }
+
+    fn bad_block(&self, _hash: BlockHash) -> ProviderResult<Option<StoredBadBlock>> {
+        Ok(None)
+    }
+}
+
+impl TableDigestReader for MockEthProvider {
+    fn table_digest(
+        &self,
+        _table: &str,
+        _start: Option<&TableDigestCursor>,
+        _end: Option<&TableDigestCursor>,
+        _limit: u64,
+    ) -> ProviderResult<TableDigest> {
+        Ok(TableDigest { entries: 0, digest: KECCAK_EMPTY, next: None })
+    }
+}
+
+impl AccountHistoryReader for MockEthProvider {
+    fn account_history_blocks(
+        &self,
+        _address: Address,
+        _range: impl RangeBounds<BlockNumber>,
+        _limit: usize,
+        _reverse: bool,
+    ) -> ProviderResult<Vec<BlockNumber>> {
+        Ok(Vec::default())
+    }
+}
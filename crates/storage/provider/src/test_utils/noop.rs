@@ -9,14 +9,14 @@ use reth_chain_state::{
     ForkChoiceSubscriptions,
 };
 use reth_chainspec::{ChainInfo, ChainSpec, MAINNET};
-use reth_db_api::models::{AccountBeforeTx, StoredBlockBodyIndices};
+use reth_db_api::models::{AccountBeforeTx, StoredBadBlock, StoredBlockBodyIndices};
 use reth_errors::ProviderError;
 use reth_evm::ConfigureEvmEnv;
 use reth_primitives::{
     Account, Address, Block, BlockHash, BlockHashOrNumber, BlockId, BlockNumber, BlockNumberOrTag,
     BlockWithSenders, Bytecode, Bytes, Header, Receipt, SealedBlock, SealedBlockWithSenders,
     SealedHeader, StorageKey, StorageValue, TransactionMeta, TransactionSigned,
-    TransactionSignedNoHash, TxHash, TxNumber, Withdrawal, Withdrawals, B256, U256,
+    TransactionSignedNoHash, TxHash, TxNumber, Withdrawal, Withdrawals, B256, KECCAK_EMPTY, U256,
 };
 use reth_prune_types::{PruneCheckpoint, PruneSegment};
 use reth_stages_types::{StageCheckpoint, StageId};
@@ -29,11 +29,12 @@ use tokio::sync::{broadcast, watch};
 use crate::{
     providers::StaticFileProvider,
     traits::{BlockSource, ReceiptProvider},
-    AccountReader, BlockHashReader, BlockIdReader, BlockNumReader, BlockReader, BlockReaderIdExt,
-    ChainSpecProvider, ChangeSetReader, EvmEnvProvider, HeaderProvider, PruneCheckpointReader,
-    ReceiptProviderIdExt, RequestsProvider, StageCheckpointReader, StateProvider, StateProviderBox,
-    StateProviderFactory, StateRootProvider, StaticFileProviderFactory, TransactionVariant,
-    TransactionsProvider, WithdrawalsProvider,
+    AccountHistoryReader, AccountReader, BadBlockReader, BlockHashReader, BlockIdReader,
+    BlockNumReader, BlockReader, BlockReaderIdExt, ChainSpecProvider, ChangeSetReader,
+    EvmEnvProvider, HeaderProvider, PruneCheckpointReader, ReceiptProviderIdExt, RequestsProvider,
+    StageCheckpointReader, StateProvider, StateProviderBox, StateProviderFactory,
+    StateRootProvider, StaticFileProviderFactory, TableDigest, TableDigestCursor,
+    TableDigestReader, TransactionVariant, TransactionsProvider, WithdrawalsProvider,
 };
 
 /// Supports various api interfaces for testing purposes.
@@ -316,6 +317,40 @@ impl ChangeSetReader for NoopProvider {
     }
 }
 
+impl BadBlockReader for NoopProvider {
+    fn bad_blocks(&self) -> ProviderResult<Vec<StoredBadBlock>> {
+        Ok(Vec::default())
+    }
+
+    fn bad_block(&self, _hash: BlockHash) -> ProviderResult<Option<StoredBadBlock>> {
+        Ok(None)
+    }
+}
+
+impl TableDigestReader for NoopProvider {
+    fn table_digest(
+        &self,
+        _table: &str,
+        _start: Option<&TableDigestCursor>,
+        _end: Option<&TableDigestCursor>,
+        _limit: u64,
+    ) -> ProviderResult<TableDigest> {
+        Ok(TableDigest { entries: 0, digest: KECCAK_EMPTY, next: None })
+    }
+}
+
+impl AccountHistoryReader for NoopProvider {
+    fn account_history_blocks(
+        &self,
+        _address: Address,
+        _range: impl RangeBounds<BlockNumber>,
+        _limit: usize,
+        _reverse: bool,
+    ) -> ProviderResult<Vec<BlockNumber>> {
+        Ok(Vec::default())
+    }
+}
+
 impl StateRootProvider for NoopProvider {
     fn hashed_state_root(&self, _state: HashedPostState) -> ProviderResult<B256> {
         Ok(B256::default())
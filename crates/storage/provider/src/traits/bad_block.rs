@@ -0,0 +1,27 @@
+use reth_db_api::models::StoredBadBlock;
+use reth_errors::ProviderResult;
+use reth_primitives::BlockHash;
+
+/// The maximum number of bad blocks that are kept in the database.
+///
+/// Once the limit is reached, the blocks that were saved first are evicted first.
+pub const MAX_BAD_BLOCKS: usize = 10;
+
+/// Functionality to read the blocks that failed validation from the database.
+#[auto_impl::auto_impl(&, Arc)]
+pub trait BadBlockReader: Send + Sync {
+    /// Returns all stored bad blocks, most recently saved first.
+    ///
+    /// Bad blocks that can't be decoded are skipped.
+    fn bad_blocks(&self) -> ProviderResult<Vec<StoredBadBlock>>;
+
+    /// Returns the bad block with the given hash, if it is stored.
+    fn bad_block(&self, hash: BlockHash) -> ProviderResult<Option<StoredBadBlock>>;
+}
+
+/// Functionality to write blocks that failed validation to the database.
+pub trait BadBlockWriter: Send + Sync {
+    /// Saves the given bad block in the DB, assigning its sequence number and evicting the oldest
+    /// bad blocks if there are more than [`MAX_BAD_BLOCKS`].
+    fn insert_bad_block(&self, bad_block: StoredBadBlock) -> ProviderResult<()>;
+}
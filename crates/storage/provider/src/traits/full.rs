@@ -1,9 +1,10 @@
 //! Helper provider traits to encapsulate all provider traits for simplicity.
 
 use crate::{
-    AccountReader, BlockReaderIdExt, ChainSpecProvider, ChangeSetReader, DatabaseProviderFactory,
-    EvmEnvProvider, HeaderProvider, StageCheckpointReader, StateProviderFactory,
-    StaticFileProviderFactory, TransactionsProvider,
+    AccountHistoryReader, AccountReader, BadBlockReader, BlockReaderIdExt, ChainSpecProvider,
+    ChangeSetReader, DatabaseProviderFactory, EvmEnvProvider, HeaderProvider,
+    StageCheckpointReader, StateProviderFactory, StaticFileProviderFactory, TableDigestReader,
+    TransactionsProvider,
 };
 use reth_chain_state::CanonStateSubscriptions;
 use reth_db_api::database::Database;
@@ -14,6 +15,9 @@ pub trait FullProvider<DB: Database>:
     + StaticFileProviderFactory
     + BlockReaderIdExt
     + AccountReader
+    + AccountHistoryReader
+    + BadBlockReader
+    + TableDigestReader
     + StateProviderFactory
     + EvmEnvProvider
     + ChainSpecProvider
@@ -31,6 +35,9 @@ impl<T, DB: Database> FullProvider<DB> for T where
         + StaticFileProviderFactory
         + BlockReaderIdExt
         + AccountReader
+        + AccountHistoryReader
+        + BadBlockReader
+        + TableDigestReader
         + StateProviderFactory
         + EvmEnvProvider
         + ChainSpecProvider
@@ -46,3 +46,11 @@ pub use tree_viewer::TreeViewer;
 
 mod finalized_block;
 pub use finalized_block::{FinalizedBlockReader, FinalizedBlockWriter};
+
+mod bad_block;
+pub use bad_block::{BadBlockReader, BadBlockWriter, MAX_BAD_BLOCKS};
+
+mod table_digest;
+pub use table_digest::{
+    TableDigest, TableDigestCursor, TableDigestHasher, TableDigestReader, MAX_TABLE_DIGEST_ENTRIES,
+};
@@ -0,0 +1,100 @@
+use reth_errors::ProviderResult;
+use reth_primitives::{alloy_primitives::Keccak256, Bytes, B256};
+use std::cmp::Ordering;
+
+/// The maximum number of entries digested by a single [`TableDigestReader::table_digest`] call
+/// that is served to other nodes.
+pub const MAX_TABLE_DIGEST_ENTRIES: u64 = 100_000;
+
+/// Position of an entry of a database table.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TableDigestCursor {
+    /// Raw key of the entry.
+    pub key: Bytes,
+    /// Raw value of the entry among the duplicates of `key` in a `DupSort` table, or `None` to
+    /// point at the first entry of `key`.
+    ///
+    /// Duplicates are ordered by their raw values, so this points at the first duplicate of `key`
+    /// with a raw value at or after it.
+    pub subkey: Option<Bytes>,
+}
+
+impl TableDigestCursor {
+    /// Returns a cursor pointing at the first entry of `key`.
+    pub fn from_key(key: impl Into<Bytes>) -> Self {
+        Self { key: key.into(), subkey: None }
+    }
+
+    /// Returns `true` if the cursor points past the entry with the raw `key` and `value`.
+    pub fn is_past(&self, key: &[u8], value: &[u8]) -> bool {
+        match key.cmp(&self.key[..]) {
+            Ordering::Less => true,
+            Ordering::Equal => self.subkey.as_ref().is_some_and(|subkey| value < &subkey[..]),
+            Ordering::Greater => false,
+        }
+    }
+}
+
+/// Digest of the raw entries of a database table within a range.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TableDigest {
+    /// Number of digested entries.
+    pub entries: u64,
+    /// Keccak256 digest of the length-prefixed raw keys and values, in key order.
+    pub digest: B256,
+    /// Position of the first entry that was not digested because the entry limit was reached,
+    /// or `None` if the whole range was digested.
+    pub next: Option<TableDigestCursor>,
+}
+
+/// Incremental digest of raw table entries, as computed by [`TableDigestReader::table_digest`]
+/// and the range digests of `reth db checksum`.
+///
+/// Every entry is hashed as its length-prefixed raw key followed by its length-prefixed value
+/// columns, with little-endian `u32` lengths.
+#[derive(Debug, Clone, Default)]
+pub struct TableDigestHasher {
+    hasher: Keccak256,
+    entries: u64,
+}
+
+impl TableDigestHasher {
+    /// Adds an entry to the digest.
+    pub fn update(&mut self, key: &[u8], columns: &[&[u8]]) {
+        self.hasher.update((key.len() as u32).to_le_bytes());
+        self.hasher.update(key);
+        for column in columns {
+            self.hasher.update((column.len() as u32).to_le_bytes());
+            self.hasher.update(column);
+        }
+        self.entries += 1;
+    }
+
+    /// Returns the number of digested entries.
+    pub const fn entries(&self) -> u64 {
+        self.entries
+    }
+
+    /// Returns the number of digested entries and their digest.
+    pub fn finalize(self) -> (u64, B256) {
+        (self.entries, self.hasher.finalize())
+    }
+}
+
+/// Functionality to digest ranges of raw database table entries, to compare the databases of
+/// different nodes.
+#[auto_impl::auto_impl(&, Arc)]
+pub trait TableDigestReader: Send + Sync {
+    /// Returns the digest of the entries of the table named `table` from `start` up to, but
+    /// excluding, `end`, unbounded if `None`.
+    ///
+    /// At most `limit` entries are digested. If the limit is reached, [`TableDigest::next`] is the
+    /// position to continue from, which may be in the middle of the duplicates of a key.
+    fn table_digest(
+        &self,
+        table: &str,
+        start: Option<&TableDigestCursor>,
+        end: Option<&TableDigestCursor>,
+        limit: u64,
+    ) -> ProviderResult<TableDigest>;
+}
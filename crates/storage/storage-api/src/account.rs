@@ -53,3 +53,28 @@ pub trait ChangeSetReader: Send + Sync {
         block_number: BlockNumber,
     ) -> ProviderResult<Vec<AccountBeforeTx>>;
 }
+
+/// The maximum number of storage slots with history that are merged to look up the blocks that
+/// changed an account.
+pub const MAX_ACCOUNT_HISTORY_SLOTS: usize = 10_000;
+
+/// Account history index reader
+#[auto_impl(&, Arc, Box)]
+pub trait AccountHistoryReader: Send + Sync {
+    /// Returns up to `limit` numbers of the blocks in the given range that changed the account or
+    /// any of its storage slots, starting from the lowest one in ascending order, or from the
+    /// highest one in descending order if `reverse` is set.
+    ///
+    /// The blocks are looked up in the account and storage history indices, so blocks that only
+    /// touched the account without changing it are not included.
+    ///
+    /// Returns [`ProviderError::TooManyHistorySlots`](reth_storage_errors::provider::ProviderError::TooManyHistorySlots)
+    /// if more than [`MAX_ACCOUNT_HISTORY_SLOTS`] storage slots of the account have history.
+    fn account_history_blocks(
+        &self,
+        address: Address,
+        range: impl RangeBounds<BlockNumber>,
+        limit: usize,
+        reverse: bool,
+    ) -> ProviderResult<Vec<BlockNumber>>;
+}
@@ -0,0 +1,421 @@
+//! A pool of transaction bundles, as submitted via `eth_sendBundle`.
+//!
+//! A bundle is an ordered list of transactions that must be included atomically at the top of the
+//! block it targets. Bundles are kept separate from the transaction pool: they are never
+//! propagated and are only pulled by the payload builder when it builds a block the bundle is
+//! eligible for.
+
+use parking_lot::RwLock;
+use reth_primitives::{keccak256, TransactionSignedEcRecovered, TxHash, B256};
+use std::{
+    collections::{BTreeMap, HashMap, HashSet},
+    sync::Arc,
+};
+
+/// The default maximum number of bundles in the [`BundlePool`].
+pub const DEFAULT_MAX_BUNDLES: usize = 1024;
+
+/// The maximum number of transactions in a single bundle.
+pub const MAX_BUNDLE_TRANSACTIONS: usize = 100;
+
+/// A bundle of transactions that must be included atomically and in order.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PoolBundle {
+    /// The hash of the bundle.
+    hash: B256,
+    /// The transactions of the bundle, in execution order.
+    transactions: Vec<TransactionSignedEcRecovered>,
+    /// The block the bundle targets.
+    block_number: u64,
+    /// The last block the bundle may be included in, if it targets a range of blocks.
+    max_block_number: Option<u64>,
+    /// The minimum timestamp of the block the bundle may be included in.
+    min_timestamp: Option<u64>,
+    /// The maximum timestamp of the block the bundle may be included in.
+    max_timestamp: Option<u64>,
+    /// Hashes of transactions that are allowed to revert.
+    reverting_tx_hashes: HashSet<TxHash>,
+    /// Identifier used to replace or cancel the bundle.
+    replacement_uuid: Option<String>,
+}
+
+impl PoolBundle {
+    /// Creates a new bundle of the given transactions that targets the given block.
+    ///
+    /// The hash of the bundle is the keccak256 hash of the concatenated transaction hashes.
+    pub fn new(transactions: Vec<TransactionSignedEcRecovered>, block_number: u64) -> Self {
+        let hash = keccak256(transactions.iter().flat_map(|tx| tx.hash().0).collect::<Vec<_>>());
+        Self {
+            hash,
+            transactions,
+            block_number,
+            max_block_number: None,
+            min_timestamp: None,
+            max_timestamp: None,
+            reverting_tx_hashes: HashSet::new(),
+            replacement_uuid: None,
+        }
+    }
+
+    /// Sets the last block the bundle may be included in, so that it targets all blocks from its
+    /// block number up to and including the given block.
+    pub const fn with_max_block_number(mut self, max_block_number: Option<u64>) -> Self {
+        self.max_block_number = max_block_number;
+        self
+    }
+
+    /// Sets the range of block timestamps the bundle may be included in.
+    pub const fn with_timestamps(
+        mut self,
+        min_timestamp: Option<u64>,
+        max_timestamp: Option<u64>,
+    ) -> Self {
+        self.min_timestamp = min_timestamp;
+        self.max_timestamp = max_timestamp;
+        self
+    }
+
+    /// Sets the hashes of the transactions that are allowed to revert.
+    pub fn with_reverting_tx_hashes(mut self, hashes: impl IntoIterator<Item = TxHash>) -> Self {
+        self.reverting_tx_hashes = hashes.into_iter().collect();
+        self
+    }
+
+    /// Sets the identifier used to replace or cancel the bundle.
+    pub fn with_replacement_uuid(mut self, replacement_uuid: Option<String>) -> Self {
+        self.replacement_uuid = replacement_uuid;
+        self
+    }
+
+    /// Returns the hash of the bundle.
+    pub const fn hash(&self) -> B256 {
+        self.hash
+    }
+
+    /// Returns the transactions of the bundle, in execution order.
+    pub fn transactions(&self) -> &[TransactionSignedEcRecovered] {
+        &self.transactions
+    }
+
+    /// Returns the block the bundle targets.
+    pub const fn block_number(&self) -> u64 {
+        self.block_number
+    }
+
+    /// Returns the last block the bundle may be included in.
+    pub fn max_block_number(&self) -> u64 {
+        self.max_block_number.unwrap_or(self.block_number)
+    }
+
+    /// Returns the identifier used to replace or cancel the bundle.
+    pub fn replacement_uuid(&self) -> Option<&str> {
+        self.replacement_uuid.as_deref()
+    }
+
+    /// Returns true if the transaction with the given hash is allowed to revert.
+    pub fn can_revert(&self, tx_hash: &TxHash) -> bool {
+        self.reverting_tx_hashes.contains(tx_hash)
+    }
+
+    /// Returns true if the bundle may be included in the block with the given number and
+    /// timestamp.
+    pub fn is_eligible(&self, block_number: u64, timestamp: u64) -> bool {
+        (self.block_number..=self.max_block_number()).contains(&block_number) &&
+            self.min_timestamp.map_or(true, |min| timestamp >= min) &&
+            self.max_timestamp.map_or(true, |max| timestamp <= max)
+    }
+}
+
+/// Errors that can occur when adding a bundle to the [`BundlePool`].
+#[derive(Debug, Clone, PartialEq, Eq, thiserror::Error)]
+pub enum BundlePoolError {
+    /// Thrown if the bundle has no transactions.
+    #[error("bundle has no transactions")]
+    EmptyBundle,
+    /// Thrown if the bundle has more than [`MAX_BUNDLE_TRANSACTIONS`] transactions.
+    #[error("bundle exceeds the limit of {MAX_BUNDLE_TRANSACTIONS} transactions")]
+    TooManyTransactions,
+    /// Thrown if the bundle contains a blob transaction.
+    #[error("blob transactions are not supported in bundles")]
+    BlobTransaction,
+    /// Thrown if the bundle targets a block that is already canonical.
+    #[error("bundle targets block {target} but the chain is already at block {tip}")]
+    OutdatedBlock {
+        /// The block the bundle targets.
+        target: u64,
+        /// The number of the canonical tip.
+        tip: u64,
+    },
+    /// Thrown if the max block number of the bundle is lower than its block number.
+    #[error("bundle max block number is lower than its block number")]
+    InvalidBlockRange,
+    /// Thrown if the min timestamp of the bundle is greater than its max timestamp.
+    #[error("bundle min timestamp is greater than its max timestamp")]
+    InvalidTimestampRange,
+    /// Thrown if the pool has reached its maximum number of bundles.
+    #[error("bundle pool is full")]
+    PoolFull,
+}
+
+/// A shareable pool of [`PoolBundle`]s.
+///
+/// Bundles are returned in the order they were added. Bundles that target a block that became
+/// canonical are removed on [`BundlePool::on_canonical_block`].
+#[derive(Debug, Clone)]
+pub struct BundlePool {
+    inner: Arc<RwLock<BundlePoolInner>>,
+}
+
+impl BundlePool {
+    /// Creates a new pool that holds at most `max_bundles` bundles.
+    pub fn new(max_bundles: usize) -> Self {
+        Self { inner: Arc::new(RwLock::new(BundlePoolInner::new(max_bundles))) }
+    }
+
+    /// Adds a bundle to the pool and returns its hash.
+    ///
+    /// If a bundle with the same replacement uuid is in the pool, it is replaced.
+    pub fn add_bundle(&self, bundle: PoolBundle) -> Result<B256, BundlePoolError> {
+        if bundle.transactions.is_empty() {
+            return Err(BundlePoolError::EmptyBundle)
+        }
+        if bundle.transactions.len() > MAX_BUNDLE_TRANSACTIONS {
+            return Err(BundlePoolError::TooManyTransactions)
+        }
+        if bundle.transactions.iter().any(|tx| tx.is_eip4844()) {
+            return Err(BundlePoolError::BlobTransaction)
+        }
+        if bundle.max_block_number() < bundle.block_number {
+            return Err(BundlePoolError::InvalidBlockRange)
+        }
+        if bundle.min_timestamp.zip(bundle.max_timestamp).is_some_and(|(min, max)| min > max) {
+            return Err(BundlePoolError::InvalidTimestampRange)
+        }
+        self.inner.write().insert(bundle)
+    }
+
+    /// Removes the bundle with the given hash from the pool.
+    pub fn remove_bundle(&self, hash: &B256) -> Option<Arc<PoolBundle>> {
+        let mut inner = self.inner.write();
+        let id = inner.by_hash.get(hash).copied()?;
+        inner.remove(id)
+    }
+
+    /// Removes the bundle with the given replacement uuid from the pool.
+    pub fn remove_by_replacement_uuid(&self, replacement_uuid: &str) -> Option<Arc<PoolBundle>> {
+        let mut inner = self.inner.write();
+        let id = inner.by_uuid.get(replacement_uuid).copied()?;
+        inner.remove(id)
+    }
+
+    /// Returns the bundle with the given hash.
+    pub fn get(&self, hash: &B256) -> Option<Arc<PoolBundle>> {
+        let inner = self.inner.read();
+        inner.by_hash.get(hash).and_then(|id| inner.bundles.get(id)).cloned()
+    }
+
+    /// Returns all bundles that may be included in the block with the given number and timestamp,
+    /// in the order they were added.
+    pub fn bundles_for_block(&self, block_number: u64, timestamp: u64) -> Vec<Arc<PoolBundle>> {
+        self.inner
+            .read()
+            .bundles
+            .values()
+            .filter(|bundle| bundle.is_eligible(block_number, timestamp))
+            .cloned()
+            .collect()
+    }
+
+    /// Removes all bundles that can only be included in the given canonical block or an earlier
+    /// one.
+    pub fn on_canonical_block(&self, block_number: u64) {
+        let mut inner = self.inner.write();
+        inner.tip = inner.tip.max(block_number);
+        let outdated = inner
+            .bundles
+            .iter()
+            .filter(|(_, bundle)| bundle.max_block_number() <= block_number)
+            .map(|(id, _)| *id)
+            .collect::<Vec<_>>();
+        for id in outdated {
+            inner.remove(id);
+        }
+    }
+
+    /// Returns the number of bundles in the pool.
+    pub fn len(&self) -> usize {
+        self.inner.read().bundles.len()
+    }
+
+    /// Returns true if the pool contains no bundles.
+    pub fn is_empty(&self) -> bool {
+        self.inner.read().bundles.is_empty()
+    }
+}
+
+impl Default for BundlePool {
+    fn default() -> Self {
+        Self::new(DEFAULT_MAX_BUNDLES)
+    }
+}
+
+/// The internals of the [`BundlePool`].
+#[derive(Debug)]
+struct BundlePoolInner {
+    /// The maximum number of bundles.
+    max_bundles: usize,
+    /// The id assigned to the next bundle.
+    next_id: u64,
+    /// The number of the canonical tip.
+    tip: u64,
+    /// All bundles by their insertion id.
+    bundles: BTreeMap<u64, Arc<PoolBundle>>,
+    /// Insertion ids by bundle hash.
+    by_hash: HashMap<B256, u64>,
+    /// Insertion ids by replacement uuid.
+    by_uuid: HashMap<String, u64>,
+}
+
+impl BundlePoolInner {
+    fn new(max_bundles: usize) -> Self {
+        Self {
+            max_bundles,
+            next_id: 0,
+            tip: 0,
+            bundles: BTreeMap::new(),
+            by_hash: HashMap::new(),
+            by_uuid: HashMap::new(),
+        }
+    }
+
+    fn insert(&mut self, bundle: PoolBundle) -> Result<B256, BundlePoolError> {
+        if bundle.max_block_number() <= self.tip {
+            return Err(BundlePoolError::OutdatedBlock {
+                target: bundle.max_block_number(),
+                tip: self.tip,
+            })
+        }
+        if let Some(id) = bundle.replacement_uuid().and_then(|uuid| self.by_uuid.get(uuid)) {
+            let id = *id;
+            self.remove(id);
+        }
+        if self.by_hash.contains_key(&bundle.hash) {
+            return Ok(bundle.hash)
+        }
+        if self.bundles.len() >= self.max_bundles {
+            return Err(BundlePoolError::PoolFull)
+        }
+
+        let id = self.next_id;
+        self.next_id += 1;
+        let hash = bundle.hash;
+        self.by_hash.insert(hash, id);
+        if let Some(uuid) = &bundle.replacement_uuid {
+            self.by_uuid.insert(uuid.clone(), id);
+        }
+        self.bundles.insert(id, Arc::new(bundle));
+        Ok(hash)
+    }
+
+    fn remove(&mut self, id: u64) -> Option<Arc<PoolBundle>> {
+        let bundle = self.bundles.remove(&id)?;
+        self.by_hash.remove(&bundle.hash);
+        if let Some(uuid) = &bundle.replacement_uuid {
+            self.by_uuid.remove(uuid);
+        }
+        Some(bundle)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use reth_primitives::{Address, Signature, Transaction, TransactionSigned, TxLegacy};
+
+    fn tx(nonce: u64) -> TransactionSignedEcRecovered {
+        let tx = TransactionSigned::from_transaction_and_signature(
+            Transaction::Legacy(TxLegacy { nonce, ..Default::default() }),
+            Signature::default(),
+        );
+        TransactionSignedEcRecovered::from_signed_transaction(tx, Address::ZERO)
+    }
+
+    #[test]
+    fn add_and_select_bundles() {
+        let pool = BundlePool::default();
+        let first = pool.add_bundle(PoolBundle::new(vec![tx(0)], 1)).unwrap();
+        let second = pool
+            .add_bundle(PoolBundle::new(vec![tx(1), tx(2)], 1).with_timestamps(Some(10), Some(20)))
+            .unwrap();
+        pool.add_bundle(PoolBundle::new(vec![tx(3)], 2)).unwrap();
+
+        // re-adding a bundle is a no-op
+        assert_eq!(pool.add_bundle(PoolBundle::new(vec![tx(0)], 1)), Ok(first));
+        assert_eq!(pool.len(), 3);
+
+        let hashes = |bundles: Vec<Arc<PoolBundle>>| {
+            bundles.iter().map(|bundle| bundle.hash()).collect::<Vec<_>>()
+        };
+        assert_eq!(hashes(pool.bundles_for_block(1, 15)), vec![first, second]);
+        assert_eq!(hashes(pool.bundles_for_block(1, 21)), vec![first]);
+
+        pool.on_canonical_block(1);
+        assert_eq!(pool.len(), 1);
+        assert_eq!(
+            pool.add_bundle(PoolBundle::new(vec![tx(4)], 1)),
+            Err(BundlePoolError::OutdatedBlock { target: 1, tip: 1 })
+        );
+    }
+
+    #[test]
+    fn bundles_targeting_block_range() {
+        let pool = BundlePool::default();
+        let hash = pool.add_bundle(PoolBundle::new(vec![tx(0)], 2).with_max_block_number(Some(4)));
+
+        assert!(pool.bundles_for_block(1, 0).is_empty());
+        assert_eq!(pool.bundles_for_block(4, 0)[0].hash(), hash.unwrap());
+        assert!(pool.bundles_for_block(5, 0).is_empty());
+
+        // the bundle stays in the pool until its last block is canonical
+        pool.on_canonical_block(3);
+        assert_eq!(pool.len(), 1);
+        pool.on_canonical_block(4);
+        assert!(pool.is_empty());
+
+        assert_eq!(
+            pool.add_bundle(PoolBundle::new(vec![tx(1)], 6).with_max_block_number(Some(5))),
+            Err(BundlePoolError::InvalidBlockRange)
+        );
+    }
+
+    #[test]
+    fn replace_and_cancel_bundles() {
+        let pool = BundlePool::default();
+        let uuid = Some("uuid".to_string());
+        let first =
+            pool.add_bundle(PoolBundle::new(vec![tx(0)], 1).with_replacement_uuid(uuid.clone()));
+        let second =
+            pool.add_bundle(PoolBundle::new(vec![tx(1)], 1).with_replacement_uuid(uuid)).unwrap();
+
+        assert!(pool.get(&first.unwrap()).is_none());
+        assert_eq!(pool.len(), 1);
+
+        assert_eq!(pool.remove_by_replacement_uuid("uuid").unwrap().hash(), second);
+        assert!(pool.is_empty());
+    }
+
+    #[test]
+    fn reject_invalid_bundles() {
+        let pool = BundlePool::new(1);
+        assert_eq!(pool.add_bundle(PoolBundle::new(vec![], 1)), Err(BundlePoolError::EmptyBundle));
+        assert_eq!(
+            pool.add_bundle(PoolBundle::new(vec![tx(0)], 1).with_timestamps(Some(2), Some(1))),
+            Err(BundlePoolError::InvalidTimestampRange)
+        );
+        pool.add_bundle(PoolBundle::new(vec![tx(0)], 1)).unwrap();
+        assert_eq!(
+            pool.add_bundle(PoolBundle::new(vec![tx(1)], 1)),
+            Err(BundlePoolError::PoolFull)
+        );
+    }
+}
@@ -150,7 +150,7 @@
 #![cfg_attr(docsrs, feature(doc_cfg, doc_auto_cfg))]
 #![cfg_attr(not(test), warn(unused_crate_dependencies))]
 
-use crate::{identifier::TransactionId, pool::PoolInner};
+use crate::{bundle::BundlePool, identifier::TransactionId, pool::PoolInner};
 use aquamarine as _;
 use reth_eth_wire_types::HandleMempoolData;
 use reth_primitives::{Address, BlobTransactionSidecar, PooledTransactionsElement, TxHash, U256};
@@ -188,6 +188,7 @@ pub mod pool;
 pub mod validate;
 
 pub mod blobstore;
+pub mod bundle;
 mod config;
 pub mod identifier;
 mod ordering;
@@ -523,6 +524,10 @@ where
     ) -> Result<Vec<BlobTransactionSidecar>, BlobStoreError> {
         self.pool.blob_store().get_exact(tx_hashes)
     }
+
+    fn bundle_pool(&self) -> Option<&BundlePool> {
+        Some(self.pool.bundle_pool())
+    }
 }
 
 impl<V, T, S> TransactionPoolExt for Pool<V, T, S>
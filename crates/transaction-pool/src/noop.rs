@@ -5,6 +5,7 @@
 
 use crate::{
     blobstore::BlobStoreError,
+    bundle::BundlePool,
     error::PoolError,
     traits::{
         BestTransactionsAttributes, GetPooledTransactionLimit, NewBlobSidecar,
@@ -242,6 +243,10 @@ impl TransactionPool for NoopTransactionPool {
         }
         Err(BlobStoreError::MissingSidecar(tx_hashes[0]))
     }
+
+    fn bundle_pool(&self) -> Option<&BundlePool> {
+        None
+    }
 }
 
 /// A [`TransactionValidator`] that does nothing.
@@ -66,6 +66,7 @@
 //!    category (2.) and become pending.
 
 use crate::{
+    bundle::BundlePool,
     error::{PoolError, PoolErrorKind, PoolResult},
     identifier::{SenderId, SenderIdentifiers, TransactionId},
     pool::{
@@ -149,6 +150,8 @@ where
     blob_transaction_sidecar_listener: Mutex<Vec<BlobTransactionSidecarListener>>,
     /// Metrics for the blob store
     blob_store_metrics: BlobStoreMetrics,
+    /// Bundles that are included atomically at the top of a block.
+    bundles: BundlePool,
 }
 
 // === impl PoolInner ===
@@ -172,6 +175,7 @@ where
             config,
             blob_store,
             blob_store_metrics: Default::default(),
+            bundles: Default::default(),
         }
     }
 
@@ -180,6 +184,11 @@ where
         &self.blob_store
     }
 
+    /// Returns the pool of bundles.
+    pub(crate) const fn bundle_pool(&self) -> &BundlePool {
+        &self.bundles
+    }
+
     /// Returns stats about the size of the pool.
     pub(crate) fn size(&self) -> PoolSize {
         self.get_pool_data().size()
@@ -386,6 +395,9 @@ where
         // This will discard outdated transactions based on the account's nonce
         self.delete_discarded_blobs(outcome.discarded.iter());
 
+        // bundles that targeted the new tip can no longer be included
+        self.bundles.on_canonical_block(new_tip.number);
+
         // notify listeners about updates
         self.notify_on_new_state(outcome);
     }
@@ -2,6 +2,7 @@
 
 use crate::{
     blobstore::BlobStoreError,
+    bundle::BundlePool,
     error::PoolResult,
     pool::{state::SubPool, BestTransactionFilter, TransactionEvents},
     validate::ValidPoolTransaction,
@@ -384,6 +385,10 @@ pub trait TransactionPool: Send + Sync + Clone {
         &self,
         tx_hashes: Vec<TxHash>,
     ) -> Result<Vec<BlobTransactionSidecar>, BlobStoreError>;
+
+    /// Returns the [`BundlePool`] of bundles that are included atomically at the top of a block,
+    /// if this pool supports bundles.
+    fn bundle_pool(&self) -> Option<&BundlePool>;
 }
 
 /// Extension for [TransactionPool] trait that allows to set the current block info.
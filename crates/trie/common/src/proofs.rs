@@ -6,17 +6,20 @@ use alloy_rlp::{encode_fixed_size, Decodable};
 use alloy_trie::{
     nodes::TrieNode,
     proof::{verify_proof, ProofVerificationError},
-    EMPTY_ROOT_HASH,
+    BranchNodeCompact, TrieMask, EMPTY_ROOT_HASH,
 };
 use reth_primitives_traits::{constants::KECCAK_EMPTY, Account};
 use serde::{Deserialize, Serialize};
 use std::collections::{BTreeMap, HashMap};
 
 /// The state multiproof of target accounts and multiproofs of their storage tries.
-#[derive(Clone, Default, Debug)]
+#[derive(Clone, PartialEq, Eq, Default, Debug)]
 pub struct MultiProof {
     /// State trie multiproof for requested accounts.
     pub account_subtree: BTreeMap<Nibbles, Bytes>,
+    /// Masks of the branch nodes of the state trie multiproof that are stored in the database
+    /// trie. The masks of the other branch nodes are empty.
+    pub branch_node_masks: HashMap<Nibbles, BranchNodeMasks>,
     /// Storage trie multiproofs.
     pub storages: HashMap<B256, StorageMultiProof>,
 }
@@ -75,17 +78,24 @@ impl MultiProof {
 }
 
 /// The merkle multiproof of storage trie.
-#[derive(Clone, Debug)]
+#[derive(Clone, PartialEq, Eq, Debug)]
 pub struct StorageMultiProof {
     /// Storage trie root.
     pub root: B256,
     /// Storage multiproof for requested slots.
     pub subtree: BTreeMap<Nibbles, Bytes>,
+    /// Masks of the branch nodes of the storage multiproof that are stored in the database trie.
+    /// The masks of the other branch nodes are empty.
+    pub branch_node_masks: HashMap<Nibbles, BranchNodeMasks>,
 }
 
 impl Default for StorageMultiProof {
     fn default() -> Self {
-        Self { root: EMPTY_ROOT_HASH, subtree: BTreeMap::default() }
+        Self {
+            root: EMPTY_ROOT_HASH,
+            subtree: BTreeMap::default(),
+            branch_node_masks: HashMap::default(),
+        }
     }
 }
 
@@ -119,6 +129,42 @@ impl StorageMultiProof {
     }
 }
 
+/// The hash mask and the tree mask of a branch node, as stored with the node in the database trie.
+#[derive(Clone, Copy, PartialEq, Eq, Default, Debug)]
+pub struct BranchNodeMasks {
+    /// The children that are branch nodes, whose hashes are stored with the node.
+    pub hash_mask: TrieMask,
+    /// The children with a branch node stored in the database trie at their path, or below the
+    /// extension node at their path.
+    pub tree_mask: TrieMask,
+}
+
+impl BranchNodeMasks {
+    /// Returns `true` if both masks are empty, in which case the branch node isn't stored in the
+    /// database trie.
+    pub const fn is_empty(&self) -> bool {
+        self.hash_mask.is_empty() && self.tree_mask.is_empty()
+    }
+
+    /// Collects the masks of the branch nodes of a proof from the branch nodes that were updated
+    /// by the hash builder which retained the proof.
+    ///
+    /// The hash builder only updates the branch nodes that are stored in the database trie, so
+    /// the masks of the other branch nodes of the proof are empty.
+    pub fn from_updated_nodes(
+        proof: &BTreeMap<Nibbles, Bytes>,
+        updated_nodes: impl IntoIterator<Item = (Nibbles, BranchNodeCompact)>,
+    ) -> HashMap<Nibbles, Self> {
+        updated_nodes
+            .into_iter()
+            .filter(|(path, _)| proof.contains_key(path))
+            .map(|(path, node)| {
+                (path, Self { hash_mask: node.hash_mask, tree_mask: node.tree_mask })
+            })
+            .collect()
+    }
+}
+
 /// The merkle proof with the relevant account info.
 #[derive(Clone, PartialEq, Eq, Debug, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
@@ -1,9 +1,10 @@
 use crate::{DatabaseHashedCursorFactory, DatabaseTrieCursorFactory};
 use reth_db_api::transaction::DbTx;
 use reth_execution_errors::StateProofError;
-use reth_primitives::{Address, B256};
+use reth_primitives::{keccak256, Address, B256};
 use reth_trie::{hashed_cursor::HashedPostStateCursorFactory, proof::Proof, HashedPostState};
 use reth_trie_common::AccountProof;
+use std::collections::HashMap;
 
 /// Extends [`Proof`] with operations specific for working with a database transaction.
 pub trait DatabaseProof<'a, TX> {
@@ -11,6 +12,9 @@ pub trait DatabaseProof<'a, TX> {
     fn from_tx(tx: &'a TX) -> Self;
 
     /// Generates the state proof for target account and slots on top of this [`HashedPostState`].
+    ///
+    /// The storage trie of the account is walked in parallel, split by the first nibble of the
+    /// slots.
     fn overlay_account_proof(
         tx: &'a TX,
         post_state: HashedPostState,
@@ -37,9 +41,14 @@ impl<'a, TX: DbTx> DatabaseProof<'a, TX>
         let sorted = post_state.into_sorted();
         let hashed_cursor_factory =
             HashedPostStateCursorFactory::new(DatabaseHashedCursorFactory::new(tx), &sorted);
-        Self::from_tx(tx)
+        Ok(Self::from_tx(tx)
             .with_hashed_cursor_factory(hashed_cursor_factory)
             .with_prefix_sets_mut(prefix_sets)
-            .account_proof(address, slots)
+            .with_targets(HashMap::from([(
+                keccak256(address),
+                slots.iter().map(keccak256).collect(),
+            )]))
+            .par_multiproof()?
+            .account_proof(address, slots)?)
     }
 }
@@ -123,6 +123,13 @@ pub trait DatabaseHashedPostState<TX>: Sized {
     /// Initializes [`HashedPostState`] from reverts. Iterates over state reverts from the specified
     /// block up to the current tip and aggregates them into hashed state in reverse.
     fn from_reverts(tx: &TX, from: BlockNumber) -> Result<Self, DatabaseError>;
+
+    /// Initializes [`HashedPostState`] from the reverts of the given block range, i.e. the state
+    /// before the first block of the range of all accounts and storage slots changed in it.
+    fn from_reverts_range(
+        tx: &TX,
+        range: RangeInclusive<BlockNumber>,
+    ) -> Result<Self, DatabaseError>;
 }
 
 impl<'a, TX: DbTx> DatabaseStateRoot<'a, TX>
@@ -199,10 +206,20 @@ impl<'a, TX: DbTx> DatabaseStateRoot<'a, TX>
 
 impl<TX: DbTx> DatabaseHashedPostState<TX> for HashedPostState {
     fn from_reverts(tx: &TX, from: BlockNumber) -> Result<Self, DatabaseError> {
+        Self::from_reverts_range(tx, from..=BlockNumber::MAX)
+    }
+
+    fn from_reverts_range(
+        tx: &TX,
+        range: RangeInclusive<BlockNumber>,
+    ) -> Result<Self, DatabaseError> {
+        let storage_range = BlockNumberAddress((*range.start(), Address::ZERO))..=
+            BlockNumberAddress((*range.end(), Address::repeat_byte(u8::MAX)));
+
         // Iterate over account changesets and record value before first occurring account change.
         let mut accounts = HashMap::<Address, Option<Account>>::default();
         let mut account_changesets_cursor = tx.cursor_read::<tables::AccountChangeSets>()?;
-        for entry in account_changesets_cursor.walk_range(from..)? {
+        for entry in account_changesets_cursor.walk_range(range)? {
             let (_, AccountBeforeTx { address, info }) = entry?;
             if let hash_map::Entry::Vacant(entry) = accounts.entry(address) {
                 entry.insert(info);
@@ -212,9 +229,7 @@ impl<TX: DbTx> DatabaseHashedPostState<TX> for HashedPostState {
         // Iterate over storage changesets and record value before first occurring storage change.
         let mut storages = HashMap::<Address, HashMap<B256, U256>>::default();
         let mut storage_changesets_cursor = tx.cursor_read::<tables::StorageChangeSets>()?;
-        for entry in
-            storage_changesets_cursor.walk_range(BlockNumberAddress((from, Address::ZERO))..)?
-        {
+        for entry in storage_changesets_cursor.walk_range(storage_range)? {
             let (BlockNumberAddress((_, address)), storage) = entry?;
             let account_storage = storages.entry(address).or_default();
             if let hash_map::Entry::Vacant(entry) = account_storage.entry(storage.key) {
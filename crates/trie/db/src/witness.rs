@@ -13,6 +13,9 @@ pub trait DatabaseTrieWitness<'a, TX> {
     fn from_tx(tx: &'a TX) -> Self;
 
     /// Generates trie witness for target state on top of this [`HashedPostState`].
+    ///
+    /// The storage tries of the target accounts are walked in parallel, split by the first nibble
+    /// of the slots.
     fn overlay_witness(
         tx: &'a TX,
         post_state: HashedPostState,
@@ -39,6 +42,6 @@ impl<'a, TX: DbTx> DatabaseTrieWitness<'a, TX>
         Self::from_tx(tx)
             .with_hashed_cursor_factory(hashed_cursor_factory)
             .with_prefix_sets_mut(prefix_sets)
-            .compute(target)
+            .par_compute(target)
     }
 }
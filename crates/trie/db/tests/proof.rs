@@ -1,16 +1,25 @@
 use reth_chainspec::{Chain, ChainSpec, HOLESKY, MAINNET};
-use reth_db_api::database::Database;
+use reth_db::tables;
+use reth_db_api::{database::Database, transaction::DbTxMut};
 use reth_primitives::{
     constants::EMPTY_ROOT_HASH, keccak256, Account, Address, Bytes, StorageEntry, B256, U256,
 };
 use reth_provider::{
-    test_utils::create_test_provider_factory, HashingWriter, ProviderFactory, TrieWriter,
+    test_utils::create_test_provider_factory, HashingWriter, ProviderFactory, StorageTrieWriter,
+    TrieWriter,
 };
 use reth_storage_errors::provider::ProviderResult;
-use reth_trie::{proof::Proof, Nibbles, StateRoot};
+use reth_trie::{
+    proof::{self, Proof},
+    Nibbles, StateRoot, StorageRoot,
+};
 use reth_trie_common::{AccountProof, StorageProof};
-use reth_trie_db::{DatabaseProof, DatabaseStateRoot};
+use reth_trie_db::{
+    DatabaseHashedCursorFactory, DatabaseProof, DatabaseStateRoot, DatabaseStorageRoot,
+    DatabaseTrieCursorFactory,
+};
 use std::{
+    collections::HashMap,
     str::FromStr,
     sync::{Arc, LazyLock},
 };
@@ -288,3 +297,74 @@ fn holesky_deposit_contract_proof() {
     similar_asserts::assert_eq!(account_proof, expected);
     assert_eq!(account_proof.verify(root), Ok(()));
 }
+
+#[test]
+fn par_storage_multiproof_matches_sequential() {
+    let storages = [
+        (0..1000u64).map(|slot| keccak256(B256::from(U256::from(slot)))).collect::<Vec<_>>(),
+        vec![B256::with_last_byte(1)],
+        vec![B256::with_last_byte(1), B256::repeat_byte(0xf0)],
+        [
+            "0x30af561000000000000000000000000000000000000000000000000000000000",
+            "0x30af569000000000000000000000000000000000000000000000000000000000",
+            "0x30af650000000000000000000000000000000000000000000000000000000000",
+            "0x3100000000000000000000000000000000000000000000000000000000000000",
+        ]
+        .into_iter()
+        .map(|key| B256::from_str(key).unwrap())
+        .collect(),
+        [
+            "0x30af561000000000000000000000000000000000000000000000000000000000",
+            "0x30af650000000000000000000000000000000000000000000000000000000000",
+            "0x30b0000000000000000000000000000000000000000000000000000000000000",
+            "0xf000000000000000000000000000000000000000000000000000000000000000",
+        ]
+        .into_iter()
+        .map(|key| B256::from_str(key).unwrap())
+        .collect(),
+    ];
+
+    for hashed_slots in storages {
+        let factory = create_test_provider_factory();
+        let provider = factory.provider_rw().unwrap();
+        let hashed_address = keccak256(Address::random());
+        let account = Account { nonce: 1, ..Default::default() };
+        provider.tx_ref().put::<tables::HashedAccounts>(hashed_address, account).unwrap();
+        for (idx, key) in hashed_slots.iter().enumerate() {
+            provider
+                .tx_ref()
+                .put::<tables::HashedStorages>(
+                    hashed_address,
+                    StorageEntry { key: *key, value: U256::from(idx + 1) },
+                )
+                .unwrap();
+        }
+        let (root, _, updates) = StorageRoot::from_tx_hashed(provider.tx_ref(), hashed_address)
+            .root_with_updates()
+            .unwrap();
+        provider.write_individual_storage_trie_updates(hashed_address, &updates).unwrap();
+
+        let missing = B256::repeat_byte(0x55);
+        for targets in [
+            vec![],
+            hashed_slots.iter().take(3).copied().collect(),
+            vec![missing],
+            vec![hashed_slots[hashed_slots.len() - 1], missing],
+        ] {
+            let storage_proof = || {
+                proof::StorageProof::new_hashed(
+                    DatabaseTrieCursorFactory::new(provider.tx_ref()),
+                    DatabaseHashedCursorFactory::new(provider.tx_ref()),
+                    hashed_address,
+                )
+            };
+            let expected = storage_proof().storage_multiproof(&targets).unwrap();
+            assert_eq!(expected.root, root);
+            assert_eq!(storage_proof().par_storage_multiproof(&targets).unwrap(), expected);
+
+            let proof = Proof::from_tx(provider.tx_ref())
+                .with_targets(HashMap::from([(hashed_address, targets)]));
+            assert_eq!(proof.par_multiproof().unwrap(), proof.multiproof().unwrap());
+        }
+    }
+}
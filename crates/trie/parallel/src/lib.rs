@@ -21,6 +21,10 @@ pub mod async_root;
 #[cfg(feature = "parallel")]
 pub mod parallel_root;
 
+/// Implementation of parallel proof computation.
+#[cfg(feature = "parallel")]
+pub mod parallel_proof;
+
 /// Parallel state root metrics.
 #[cfg(feature = "metrics")]
 pub mod metrics;
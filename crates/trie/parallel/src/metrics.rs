@@ -42,3 +42,30 @@ pub struct ParallelTrieMetrics {
     /// The number of leaves for which we did not pre-compute the storage roots.
     pub missed_leaves: Histogram,
 }
+
+/// Parallel proof metrics.
+#[derive(Metrics)]
+#[metrics(scope = "trie_parallel.proof")]
+pub struct ParallelProofMetrics {
+    /// The number of seconds multiproof generation lasted.
+    pub duration_seconds: Histogram,
+    /// The number of branches added during multiproof generation.
+    pub branches_added: Histogram,
+    /// The number of leaves added during multiproof generation.
+    pub leaves_added: Histogram,
+    /// The number of storage multiproofs generated in parallel.
+    pub precomputed_storage_proofs: Histogram,
+    /// The number of leaves for which we did not pre-compute the storage multiproof.
+    pub missed_leaves: Histogram,
+}
+
+impl ParallelProofMetrics {
+    /// Record multiproof generation stats.
+    pub fn record(&self, stats: ParallelTrieStats) {
+        self.duration_seconds.record(stats.duration().as_secs_f64());
+        self.branches_added.record(stats.branches_added() as f64);
+        self.leaves_added.record(stats.leaves_added() as f64);
+        self.precomputed_storage_proofs.record(stats.precomputed_storage_roots() as f64);
+        self.missed_leaves.record(stats.missed_leaves() as f64);
+    }
+}
@@ -0,0 +1,336 @@
+#[cfg(feature = "metrics")]
+use crate::metrics::ParallelProofMetrics;
+use crate::{parallel_root::ParallelStateRootError, stats::ParallelTrieTracker};
+use alloy_rlp::{BufMut, Encodable};
+use rayon::prelude::*;
+use reth_db_api::database::Database;
+use reth_primitives::B256;
+use reth_provider::{providers::ConsistentDbView, DatabaseProviderFactory, ProviderError};
+use reth_trie::{
+    hashed_cursor::{HashedCursorFactory, HashedPostStateCursorFactory},
+    node_iter::{TrieElement, TrieNodeIter},
+    prefix_set::TriePrefixSetsMut,
+    proof::StorageProof,
+    trie_cursor::TrieCursorFactory,
+    walker::TrieWalker,
+    BranchNodeMasks, HashBuilder, HashedPostStateSorted, MultiProof, Nibbles, TrieAccount,
+};
+use reth_trie_common::proof::ProofRetainer;
+use reth_trie_db::{DatabaseHashedCursorFactory, DatabaseTrieCursorFactory};
+use std::{collections::HashMap, sync::Arc};
+use tracing::*;
+
+/// Parallel proof calculator.
+///
+/// The calculator starts off by generating storage multiproofs of the target and changed accounts
+/// in parallel. Once that's done, it proceeds to walking the state trie retrieving the
+/// pre-computed storage multiproofs when needed, and retains the proofs of the target accounts.
+///
+/// The result is the same [`MultiProof`] as the one of the sequential
+/// [`Proof`](reth_trie::proof::Proof) generator.
+///
+/// The changed hashed state and its prefix sets are shared, so that the many multiproofs generated
+/// on top of the same state don't need to copy it.
+///
+/// Internally, the calculator uses [`ConsistentDbView`] since
+/// it needs to rely on database state saying the same until
+/// the last transaction is open.
+/// See docs of using [`ConsistentDbView`] for caveats.
+#[derive(Debug)]
+pub struct ParallelProof<DB, Provider> {
+    /// Consistent view of the database.
+    view: ConsistentDbView<DB, Provider>,
+    /// Sorted changed hashed state the proofs are generated on top of.
+    state_sorted: Arc<HashedPostStateSorted>,
+    /// Prefix sets of the changed hashed state.
+    prefix_sets: Arc<TriePrefixSetsMut>,
+    /// Whether the masks of the branch nodes of the proofs are collected.
+    branch_node_masks: bool,
+    /// Parallel proof metrics.
+    #[cfg(feature = "metrics")]
+    metrics: ParallelProofMetrics,
+}
+
+impl<DB, Provider> ParallelProof<DB, Provider> {
+    /// Create new parallel proof calculator on top of the sorted changed hashed state and its
+    /// prefix sets.
+    pub fn new(
+        view: ConsistentDbView<DB, Provider>,
+        state_sorted: Arc<HashedPostStateSorted>,
+        prefix_sets: Arc<TriePrefixSetsMut>,
+    ) -> Self {
+        Self {
+            view,
+            state_sorted,
+            prefix_sets,
+            branch_node_masks: false,
+            #[cfg(feature = "metrics")]
+            metrics: ParallelProofMetrics::default(),
+        }
+    }
+
+    /// Set whether the masks of the branch nodes of the proofs are collected, which are needed to
+    /// reveal the proofs in a sparse trie.
+    pub const fn with_branch_node_masks(mut self, branch_node_masks: bool) -> Self {
+        self.branch_node_masks = branch_node_masks;
+        self
+    }
+}
+
+impl<DB, Provider> ParallelProof<DB, Provider>
+where
+    DB: Database,
+    Provider: DatabaseProviderFactory<DB> + Send + Sync,
+{
+    /// Generate a state multiproof for the target hashed addresses and hashed slots in parallel.
+    pub fn multiproof(
+        self,
+        targets: HashMap<B256, Vec<B256>>,
+    ) -> Result<MultiProof, ParallelStateRootError> {
+        let mut tracker = ParallelTrieTracker::default();
+
+        // Storage multiproofs are generated for the target accounts and the accounts with changed
+        // storage, since the storage roots of both are needed to walk the state trie.
+        let mut storage_proof_targets = self.prefix_sets.storage_prefix_sets.clone();
+        for hashed_address in targets.keys() {
+            storage_proof_targets.entry(*hashed_address).or_default();
+        }
+
+        // Pre-calculate storage multiproofs in parallel.
+        tracker.set_precomputed_storage_roots(storage_proof_targets.len() as u64);
+        debug!(target: "trie::parallel_proof", len = storage_proof_targets.len(), "pre-generating storage proofs");
+        let mut storage_proofs = storage_proof_targets
+            .into_par_iter()
+            .map(|(hashed_address, prefix_set)| {
+                let provider_ro = self.view.provider_ro()?;
+                let trie_cursor_factory = DatabaseTrieCursorFactory::new(provider_ro.tx_ref());
+                let hashed_cursor_factory = HashedPostStateCursorFactory::new(
+                    DatabaseHashedCursorFactory::new(provider_ro.tx_ref()),
+                    &self.state_sorted,
+                );
+                let slots = targets.get(&hashed_address).map_or(&[][..], Vec::as_slice);
+                let storage_multiproof = StorageProof::new_hashed(
+                    trie_cursor_factory,
+                    hashed_cursor_factory,
+                    hashed_address,
+                )
+                .with_prefix_set_mut(prefix_set)
+                .with_branch_node_masks(self.branch_node_masks)
+                .storage_multiproof(slots)
+                .map_err(ProviderError::from)?;
+                Ok((hashed_address, storage_multiproof))
+            })
+            .collect::<Result<HashMap<_, _>, ParallelStateRootError>>()?;
+
+        trace!(target: "trie::parallel_proof", "generating account proof");
+        let provider_ro = self.view.provider_ro()?;
+        let hashed_cursor_factory = HashedPostStateCursorFactory::new(
+            DatabaseHashedCursorFactory::new(provider_ro.tx_ref()),
+            &self.state_sorted,
+        );
+        let trie_cursor_factory = DatabaseTrieCursorFactory::new(provider_ro.tx_ref());
+
+        // Create the walker.
+        let mut account_prefix_set = self.prefix_sets.account_prefix_set.clone();
+        account_prefix_set.extend(targets.keys().map(Nibbles::unpack));
+        let walker = TrieWalker::new(
+            trie_cursor_factory.account_trie_cursor().map_err(ProviderError::Database)?,
+            account_prefix_set.freeze(),
+        );
+
+        // Create a hash builder to rebuild the root node since it is not available in the database.
+        // The updated branch nodes are retained for the masks of the proof nodes.
+        let retainer = ProofRetainer::from_iter(targets.keys().map(Nibbles::unpack));
+        let mut hash_builder = HashBuilder::default()
+            .with_proof_retainer(retainer)
+            .with_updates(self.branch_node_masks);
+
+        let mut storages = HashMap::default();
+        let mut account_rlp = Vec::with_capacity(128);
+        let mut account_node_iter = TrieNodeIter::new(
+            walker,
+            hashed_cursor_factory.hashed_account_cursor().map_err(ProviderError::Database)?,
+        );
+        while let Some(account_node) =
+            account_node_iter.try_next().map_err(ProviderError::Database)?
+        {
+            match account_node {
+                TrieElement::Branch(node) => {
+                    tracker.inc_branch();
+                    hash_builder.add_branch(node.key, node.value, node.children_are_in_trie);
+                }
+                TrieElement::Leaf(hashed_address, account) => {
+                    let storage_multiproof = match storage_proofs.remove(&hashed_address) {
+                        Some(proof) => proof,
+                        // Since we do not store all intermediate nodes in the database, there might
+                        // be a possibility of re-adding a non-modified leaf to the hash builder.
+                        None => {
+                            tracker.inc_missed_leaves();
+                            StorageProof::new_hashed(
+                                trie_cursor_factory.clone(),
+                                hashed_cursor_factory.clone(),
+                                hashed_address,
+                            )
+                            .with_branch_node_masks(self.branch_node_masks)
+                            .storage_multiproof(&[])
+                            .map_err(ProviderError::from)?
+                        }
+                    };
+
+                    // Encode account
+                    account_rlp.clear();
+                    let account = TrieAccount::from((account, storage_multiproof.root));
+                    account.encode(&mut account_rlp as &mut dyn BufMut);
+
+                    tracker.inc_leaf();
+                    hash_builder.add_leaf(Nibbles::unpack(hashed_address), &account_rlp);
+                    storages.insert(hashed_address, storage_multiproof);
+                }
+            }
+        }
+        let _ = hash_builder.root();
+        let account_subtree = hash_builder.take_proofs();
+        let (_, updated_nodes) = hash_builder.split();
+        let branch_node_masks =
+            BranchNodeMasks::from_updated_nodes(&account_subtree, updated_nodes);
+
+        let stats = tracker.finish();
+
+        #[cfg(feature = "metrics")]
+        self.metrics.record(stats);
+
+        trace!(
+            target: "trie::parallel_proof",
+            duration = ?stats.duration(),
+            branches_added = stats.branches_added(),
+            leaves_added = stats.leaves_added(),
+            missed_leaves = stats.missed_leaves(),
+            precomputed_storage_proofs = stats.precomputed_storage_roots(),
+            "generated multiproof"
+        );
+
+        Ok(MultiProof { account_subtree, branch_node_masks, storages })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rand::Rng;
+    use reth_primitives::{keccak256, Account, Address, StorageEntry, U256};
+    use reth_provider::{test_utils::create_test_provider_factory, HashingWriter};
+    use reth_trie::{proof::Proof, HashedPostState, HashedStorage};
+    use reth_trie_db::DatabaseProof;
+
+    #[test]
+    fn random_parallel_proof() {
+        let factory = create_test_provider_factory();
+        let consistent_view = ConsistentDbView::new(factory.clone(), None);
+
+        let mut rng = rand::thread_rng();
+        let state = (0..100)
+            .map(|_| {
+                let address = Address::random();
+                let account =
+                    Account { balance: U256::from(rng.gen::<u64>()), ..Default::default() };
+                let mut storage = HashMap::<B256, U256>::default();
+                let has_storage = rng.gen_bool(0.7);
+                if has_storage {
+                    for _ in 0..100 {
+                        storage.insert(
+                            B256::from(U256::from(rng.gen::<u64>())),
+                            U256::from(rng.gen::<u64>()),
+                        );
+                    }
+                }
+                (address, (account, storage))
+            })
+            .collect::<HashMap<_, _>>();
+
+        {
+            let provider_rw = factory.provider_rw().unwrap();
+            provider_rw
+                .insert_account_for_hashing(
+                    state.iter().map(|(address, (account, _))| (*address, Some(*account))),
+                )
+                .unwrap();
+            provider_rw
+                .insert_storage_for_hashing(state.iter().map(|(address, (_, storage))| {
+                    (
+                        *address,
+                        storage
+                            .iter()
+                            .map(|(slot, value)| StorageEntry { key: *slot, value: *value }),
+                    )
+                }))
+                .unwrap();
+            provider_rw.commit().unwrap();
+        }
+
+        // Target some of the accounts with some of their slots, and an account that doesn't
+        // exist.
+        let mut targets = HashMap::<B256, Vec<B256>>::default();
+        for (address, (_, storage)) in &state {
+            if rng.gen_bool(0.5) {
+                let slots = storage
+                    .keys()
+                    .filter(|_| rng.gen_bool(0.3))
+                    .map(keccak256)
+                    .chain(std::iter::once(B256::random()))
+                    .collect();
+                targets.insert(keccak256(address), slots);
+            }
+        }
+        targets.insert(B256::random(), vec![B256::random()]);
+
+        let mut hashed_state = HashedPostState::default();
+        for (address, (account, storage)) in &state {
+            let hashed_address = keccak256(address);
+
+            if rng.gen_bool(0.3) {
+                let account = Account { balance: U256::from(rng.gen::<u64>()), ..*account };
+                hashed_state.accounts.insert(hashed_address, Some(account));
+            }
+
+            if rng.gen_bool(0.3) {
+                let hashed_storage = hashed_state
+                    .storages
+                    .entry(hashed_address)
+                    .or_insert_with(|| HashedStorage::new(false));
+                for slot in storage.keys() {
+                    if rng.gen_bool(0.5) {
+                        hashed_storage
+                            .storage
+                            .insert(keccak256(slot), U256::from(rng.gen::<u64>()));
+                    }
+                }
+            }
+        }
+
+        for hashed_state in [HashedPostState::default(), hashed_state] {
+            let provider = factory.provider().unwrap();
+            let prefix_sets = hashed_state.construct_prefix_sets();
+            let sorted = hashed_state.clone().into_sorted();
+            let expected = Proof::from_tx(provider.tx_ref())
+                .with_hashed_cursor_factory(HashedPostStateCursorFactory::new(
+                    DatabaseHashedCursorFactory::new(provider.tx_ref()),
+                    &sorted,
+                ))
+                .with_prefix_sets_mut(prefix_sets)
+                .with_targets(targets.clone())
+                .multiproof()
+                .unwrap();
+
+            assert_eq!(
+                ParallelProof::new(
+                    consistent_view.clone(),
+                    Arc::new(sorted),
+                    Arc::new(hashed_state.construct_prefix_sets()),
+                )
+                .multiproof(targets.clone())
+                .unwrap(),
+                expected
+            );
+        }
+    }
+}
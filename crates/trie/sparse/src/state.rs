@@ -0,0 +1,625 @@
+use crate::{RevealedSparseTrie, SparseTrie, SparseTrieResult, SparseTrieUpdates};
+use alloy_rlp::Decodable;
+use reth_execution_errors::SparseTrieError;
+use reth_primitives::{Bytes, B256};
+use reth_trie_common::{
+    BranchNodeMasks, MultiProof, Nibbles, TrieAccount, TrieNode, CHILD_INDEX_RANGE, EMPTY_ROOT_HASH,
+};
+use std::collections::{HashMap, HashSet};
+
+/// Sparse state trie representing lazy-loaded Ethereum state trie.
+///
+/// The account trie and the storage tries are revealed from multiproofs of the touched accounts
+/// and storage slots, and then updated in place.
+#[derive(Default, Debug)]
+pub struct SparseStateTrie {
+    /// Sparse account trie.
+    state: SparseTrie,
+    /// Sparse storage tries.
+    storages: HashMap<B256, SparseTrie>,
+    /// Collection of revealed account and storage keys.
+    revealed: HashMap<B256, HashSet<B256>>,
+    /// Whether the updates of the database tries are retained by the tries revealed from
+    /// multiproofs.
+    retain_updates: bool,
+}
+
+impl SparseStateTrie {
+    /// Create state trie from the account trie.
+    pub fn from_state(state: SparseTrie) -> Self {
+        Self { state, ..Default::default() }
+    }
+
+    /// Sets whether the tries revealed from multiproofs retain the updates of the database tries,
+    /// see [`RevealedSparseTrie::with_updates`].
+    pub const fn with_updates(mut self, retain_updates: bool) -> Self {
+        self.retain_updates = retain_updates;
+        self
+    }
+
+    /// Returns `true` if account was already revealed.
+    pub fn is_account_revealed(&self, account: &B256) -> bool {
+        self.revealed.contains_key(account)
+    }
+
+    /// Returns `true` if storage slot for account was already revealed.
+    pub fn is_storage_slot_revealed(&self, account: &B256, slot: &B256) -> bool {
+        self.revealed.get(account).is_some_and(|slots| slots.contains(slot))
+    }
+
+    /// Returns the leaf value of the account with the given hashed address, if it's revealed.
+    pub fn get_account_value(&self, account: &B256) -> Option<&Vec<u8>> {
+        self.state.as_revealed_ref()?.get_leaf_value(&Nibbles::unpack(account))
+    }
+
+    /// Finds the leaf value of the account with the given hashed address by traversing the
+    /// account trie. See [`RevealedSparseTrie::find_leaf`](crate::RevealedSparseTrie::find_leaf).
+    pub fn find_account_value(&self, account: &B256) -> SparseTrieResult<Option<&Vec<u8>>> {
+        self.state.find_leaf(&Nibbles::unpack(account))
+    }
+
+    /// Finds the leaf value of the storage slot of the account by traversing the storage trie
+    /// of the account. Returns [`SparseTrieError::Blind`] if the storage trie wasn't revealed.
+    pub fn find_storage_value(
+        &self,
+        account: &B256,
+        slot: &B256,
+    ) -> SparseTrieResult<Option<&Vec<u8>>> {
+        self.storages.get(account).ok_or(SparseTrieError::Blind)?.find_leaf(&Nibbles::unpack(slot))
+    }
+
+    /// Returns mutable reference to the storage trie of the account, if any of it was revealed.
+    pub fn storage_trie_mut(&mut self, account: &B256) -> Option<&mut SparseTrie> {
+        self.storages.get_mut(account)
+    }
+
+    /// Reveal unknown trie paths from the multiproof of the given target accounts and storage
+    /// slots.
+    ///
+    /// An account trie without nodes in the multiproof is empty, as the root node is part of the
+    /// proof of any account in a non-empty trie. The same applies to storage tries of accounts
+    /// with target slots, while a storage trie without nodes and target slots stays blind unless
+    /// its root is empty.
+    pub fn reveal_multiproof(
+        &mut self,
+        targets: HashMap<B256, HashSet<B256>>,
+        multiproof: MultiProof,
+    ) -> SparseTrieResult<()> {
+        if multiproof.account_subtree.is_empty() {
+            if !targets.is_empty() && self.state.is_blind() {
+                self.state = revealed_empty(self.retain_updates);
+            }
+        } else {
+            reveal_proof_nodes(
+                &mut self.state,
+                multiproof.account_subtree,
+                &multiproof.branch_node_masks,
+                self.retain_updates,
+            )?;
+        }
+
+        for (account, storage) in multiproof.storages {
+            let trie = self.storages.entry(account).or_default();
+            if storage.subtree.is_empty() {
+                let has_target_slots = targets.get(&account).is_some_and(|slots| !slots.is_empty());
+                if trie.is_blind() && (storage.root == EMPTY_ROOT_HASH || has_target_slots) {
+                    *trie = revealed_empty(self.retain_updates);
+                }
+            } else {
+                reveal_proof_nodes(
+                    trie,
+                    storage.subtree,
+                    &storage.branch_node_masks,
+                    self.retain_updates,
+                )?;
+            }
+        }
+
+        for (account, slots) in targets {
+            self.revealed.entry(account).or_default().extend(slots);
+        }
+
+        Ok(())
+    }
+
+    /// Reveal the account trie with the given root and the storage tries of the revealed accounts
+    /// from the nodes of an execution witness, keyed by their hash.
+    ///
+    /// The nodes that aren't part of the witness stay blinded. Returns
+    /// [`SparseTrieError::BlindedNode`] if the root node of a non-empty account trie isn't part
+    /// of the witness.
+    pub fn reveal_witness(
+        &mut self,
+        state_root: B256,
+        witness: &HashMap<B256, Bytes>,
+    ) -> SparseTrieResult<()> {
+        if state_root != EMPTY_ROOT_HASH && !witness.contains_key(&state_root) {
+            return Err(SparseTrieError::BlindedNode { path: Nibbles::default(), hash: state_root })
+        }
+
+        for (path, value) in reveal_witness_nodes(&mut self.state, state_root, witness)? {
+            // Leaves of the account trie are keyed by the full hashed address.
+            if path.len() != B256::len_bytes() * 2 {
+                return Err(SparseTrieError::InvalidNode { path, node: value.into() })
+            }
+            let account = TrieAccount::decode(&mut &value[..]).map_err(|_| {
+                SparseTrieError::InvalidNode { path: path.clone(), node: value.into() }
+            })?;
+            let storage = self.storages.entry(B256::from_slice(&path.pack())).or_default();
+            reveal_witness_nodes(storage, account.storage_root, witness)?;
+        }
+
+        Ok(())
+    }
+
+    /// Update the leaf node of the account trie with the given full path.
+    pub fn update_account_leaf(&mut self, path: Nibbles, value: Vec<u8>) -> SparseTrieResult<()> {
+        self.state.update_leaf(path, value)
+    }
+
+    /// Remove the leaf node of the account trie with the given full path.
+    pub fn remove_account_leaf(&mut self, path: &Nibbles) -> SparseTrieResult<()> {
+        self.state.remove_leaf(path)
+    }
+
+    /// Update the leaf node of the storage trie of the given account.
+    pub fn update_storage_leaf(
+        &mut self,
+        account: B256,
+        slot: Nibbles,
+        value: Vec<u8>,
+    ) -> SparseTrieResult<()> {
+        self.storages.get_mut(&account).ok_or(SparseTrieError::Blind)?.update_leaf(slot, value)
+    }
+
+    /// Remove the leaf node of the storage trie of the given account.
+    pub fn remove_storage_leaf(&mut self, account: B256, slot: &Nibbles) -> SparseTrieResult<()> {
+        self.storages.get_mut(&account).ok_or(SparseTrieError::Blind)?.remove_leaf(slot)
+    }
+
+    /// Wipe the storage trie of the given account, revealing it as empty.
+    pub fn wipe_storage(&mut self, account: B256) {
+        let mut trie = RevealedSparseTrie::default().with_updates(self.retain_updates);
+        trie.wipe();
+        self.storages.insert(account, SparseTrie::Revealed(Box::new(trie)));
+    }
+
+    /// Calculates the storage root of the account, if its storage trie has been revealed.
+    pub fn storage_root(&mut self, account: B256) -> Option<B256> {
+        self.storages.get_mut(&account)?.root()
+    }
+
+    /// Calculates the state root, if the account trie has been revealed.
+    pub fn root(&mut self) -> Option<B256> {
+        self.state.root()
+    }
+
+    /// Returns the updates of the account trie and the non-empty updates of the storage tries
+    /// collected since they were last taken, keyed by hashed address. The updates are collected
+    /// when the roots are calculated, so they're only complete once all of them are calculated.
+    pub fn take_updates(&mut self) -> (SparseTrieUpdates, HashMap<B256, SparseTrieUpdates>) {
+        let state =
+            self.state.as_revealed_mut().map(|trie| trie.take_updates()).unwrap_or_default();
+        let storages = self
+            .storages
+            .iter_mut()
+            .filter_map(|(account, trie)| {
+                let updates = trie.as_revealed_mut()?.take_updates();
+                (updates != SparseTrieUpdates::default()).then_some((*account, updates))
+            })
+            .collect();
+        (state, storages)
+    }
+}
+
+/// Returns an empty revealed trie, retaining updates if `retain_updates` is set.
+fn revealed_empty(retain_updates: bool) -> SparseTrie {
+    SparseTrie::Revealed(Box::new(RevealedSparseTrie::default().with_updates(retain_updates)))
+}
+
+/// Reveals the nodes of the trie with the given root that are part of the witness, and returns
+/// the full paths and values of the revealed leaves. An empty root reveals an empty trie.
+fn reveal_witness_nodes(
+    trie: &mut SparseTrie,
+    root: B256,
+    witness: &HashMap<B256, Bytes>,
+) -> SparseTrieResult<Vec<(Nibbles, Vec<u8>)>> {
+    let mut leaves = Vec::new();
+    if root == EMPTY_ROOT_HASH {
+        if trie.is_blind() {
+            *trie = SparseTrie::revealed_empty();
+        }
+        return Ok(leaves)
+    }
+
+    // Parents are revealed before their children, as the children are only pushed once the
+    // parent is decoded.
+    let mut stack = vec![(Nibbles::default(), root)];
+    while let Some((path, hash)) = stack.pop() {
+        let Some(encoded) = witness.get(&hash) else { continue };
+        let node = TrieNode::decode(&mut &encoded[..]).map_err(|_| {
+            SparseTrieError::InvalidNode { path: path.clone(), node: encoded.clone() }
+        })?;
+        collect_witness_children(&path, &node, &mut stack, &mut leaves)?;
+        trie.reveal_node(path, node, None)?;
+    }
+
+    Ok(leaves)
+}
+
+/// Pushes the hashed children of the node to the stack and collects its leaves, descending into
+/// the children that are embedded in the node.
+fn collect_witness_children(
+    path: &Nibbles,
+    node: &TrieNode,
+    stack: &mut Vec<(Nibbles, B256)>,
+    leaves: &mut Vec<(Nibbles, Vec<u8>)>,
+) -> SparseTrieResult<()> {
+    let mut visit_child = |child_path: Nibbles, child: &[u8]| {
+        if child.len() == B256::len_bytes() + 1 {
+            stack.push((child_path, B256::from_slice(&child[1..])));
+            Ok(())
+        } else {
+            let node =
+                TrieNode::decode(&mut &child[..]).map_err(|_| SparseTrieError::InvalidNode {
+                    path: child_path.clone(),
+                    node: child.to_vec().into(),
+                })?;
+            collect_witness_children(&child_path, &node, stack, leaves)
+        }
+    };
+
+    match node {
+        TrieNode::Branch(branch) => {
+            let mut stack_ptr = branch.as_ref().first_child_index();
+            for idx in CHILD_INDEX_RANGE {
+                if branch.state_mask.is_bit_set(idx) {
+                    let mut child_path = path.clone();
+                    child_path.push_unchecked(idx);
+                    visit_child(child_path, &branch.stack[stack_ptr])?;
+                    stack_ptr += 1;
+                }
+            }
+        }
+        TrieNode::Extension(ext) => visit_child(path.join(&ext.key), &ext.child)?,
+        TrieNode::Leaf(leaf) => leaves.push((path.join(&leaf.key), leaf.value.clone())),
+    }
+
+    Ok(())
+}
+
+/// Reveals the RLP encoded proof nodes, ordered by path, in the sparse trie, with the masks of the
+/// branch nodes. The root node reveals the trie if it's blind, retaining updates if
+/// `retain_updates` is set.
+pub(crate) fn reveal_proof_nodes(
+    trie: &mut SparseTrie,
+    nodes: impl IntoIterator<Item = (Nibbles, Bytes)>,
+    masks: &HashMap<Nibbles, BranchNodeMasks>,
+    retain_updates: bool,
+) -> SparseTrieResult<()> {
+    for (path, node) in nodes {
+        let decoded = TrieNode::decode(&mut &node[..])
+            .map_err(|_| SparseTrieError::InvalidNode { path: path.clone(), node })?;
+        let node_masks = Some(masks.get(&path).copied().unwrap_or_default());
+        if path.is_empty() {
+            trie.reveal_root(decoded, node_masks, retain_updates)?;
+        } else {
+            trie.reveal_node(path, decoded, node_masks)?;
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use alloy_rlp::encode_fixed_size;
+    use rand::Rng;
+    use reth_db_api::database::Database;
+    use reth_primitives::{keccak256, Account, Address, StorageEntry, U256};
+    use reth_provider::{
+        test_utils::create_test_provider_factory, HashingWriter, ProviderFactory, TrieWriter,
+    };
+    use reth_trie::{
+        proof::Proof, witness::TrieWitness, HashedPostState, HashedStorage, StateRoot, TrieAccount,
+    };
+    use reth_trie_common::LeafNode;
+    use reth_trie_db::{DatabaseProof, DatabaseStateRoot, DatabaseTrieWitness};
+
+    type State = HashMap<Address, (Account, HashMap<B256, U256>)>;
+
+    /// Creates random accounts, some of them with storage.
+    fn random_state(rng: &mut impl Rng) -> State {
+        (0..100)
+            .map(|_| {
+                let account =
+                    Account { balance: U256::from(rng.gen::<u64>()), ..Default::default() };
+                let mut storage = HashMap::default();
+                if rng.gen_bool(0.5) {
+                    for _ in 0..rng.gen_range(1..30) {
+                        storage.insert(B256::random(), U256::from(rng.gen::<u64>() + 1));
+                    }
+                }
+                (Address::random(), (account, storage))
+            })
+            .collect()
+    }
+
+    /// Inserts the state into the hashed tables and writes the trie nodes.
+    fn insert_state<DB: Database>(factory: &ProviderFactory<DB>, state: &State) {
+        let provider_rw = factory.provider_rw().unwrap();
+        provider_rw
+            .insert_account_for_hashing(
+                state.iter().map(|(address, (account, _))| (*address, Some(*account))),
+            )
+            .unwrap();
+        provider_rw
+            .insert_storage_for_hashing(state.iter().map(|(address, (_, storage))| {
+                (
+                    *address,
+                    storage.iter().map(|(slot, value)| StorageEntry { key: *slot, value: *value }),
+                )
+            }))
+            .unwrap();
+        let (_, updates) = StateRoot::from_tx(provider_rw.tx_ref()).root_with_updates().unwrap();
+        provider_rw.write_trie_updates(&updates).unwrap();
+        provider_rw.commit().unwrap();
+    }
+
+    /// Creates random changes to the state. Every account with changed storage is updated too.
+    fn random_changes(rng: &mut impl Rng, state: &State, with_removals: bool) -> HashedPostState {
+        let mut hashed_state = HashedPostState::default();
+        for (address, (account, storage)) in state {
+            let hashed_address = keccak256(address);
+            if with_removals && rng.gen_bool(0.1) {
+                hashed_state.accounts.insert(hashed_address, None);
+                hashed_state.storages.insert(hashed_address, HashedStorage::new(true));
+                continue
+            }
+
+            if rng.gen_bool(0.3) {
+                let mut hashed_storage = HashedStorage::new(false);
+                for slot in storage.keys() {
+                    if rng.gen_bool(0.3) {
+                        let value = if with_removals && rng.gen_bool(0.5) {
+                            U256::ZERO
+                        } else {
+                            U256::from(rng.gen::<u64>() + 1)
+                        };
+                        hashed_storage.storage.insert(keccak256(slot), value);
+                    }
+                }
+                hashed_storage.storage.insert(B256::random(), U256::from(rng.gen::<u64>() + 1));
+                hashed_state.storages.insert(hashed_address, hashed_storage);
+                hashed_state.accounts.insert(hashed_address, Some(*account));
+            }
+
+            if rng.gen_bool(0.3) {
+                let account = Account { nonce: account.nonce + 1, ..*account };
+                hashed_state.accounts.insert(hashed_address, Some(account));
+            }
+        }
+
+        // New accounts.
+        for _ in 0..10 {
+            let account = Account { balance: U256::from(rng.gen::<u64>()), ..Default::default() };
+            hashed_state.accounts.insert(B256::random(), Some(account));
+        }
+
+        hashed_state
+    }
+
+    /// Applies the changes to the revealed sparse state trie.
+    fn apply_changes(
+        sparse: &mut SparseStateTrie,
+        hashed_state: &HashedPostState,
+        mut storage_roots: HashMap<B256, B256>,
+    ) {
+        for (hashed_address, storage) in &hashed_state.storages {
+            if storage.wiped {
+                sparse.wipe_storage(*hashed_address);
+            }
+            for (hashed_slot, value) in &storage.storage {
+                let path = Nibbles::unpack(hashed_slot);
+                if value.is_zero() {
+                    sparse.remove_storage_leaf(*hashed_address, &path).unwrap();
+                } else {
+                    let value = encode_fixed_size(value).to_vec();
+                    sparse.update_storage_leaf(*hashed_address, path, value).unwrap();
+                }
+            }
+            storage_roots.insert(*hashed_address, sparse.storage_root(*hashed_address).unwrap());
+        }
+
+        for (hashed_address, account) in &hashed_state.accounts {
+            let path = Nibbles::unpack(hashed_address);
+            match account {
+                Some(account) => {
+                    let storage_root =
+                        storage_roots.get(hashed_address).copied().unwrap_or(EMPTY_ROOT_HASH);
+                    let account = TrieAccount::from((*account, storage_root));
+                    sparse.update_account_leaf(path, alloy_rlp::encode(account)).unwrap();
+                }
+                None => sparse.remove_account_leaf(&path).unwrap(),
+            }
+        }
+    }
+
+    /// Reveals the multiproof of the targets from the database in the sparse state trie, and
+    /// returns the storage roots of the target accounts.
+    fn reveal_targets<DB: Database>(
+        factory: &ProviderFactory<DB>,
+        sparse: &mut SparseStateTrie,
+        targets: HashMap<B256, HashSet<B256>>,
+    ) -> HashMap<B256, B256> {
+        let provider = factory.provider().unwrap();
+        let multiproof = Proof::from_tx(provider.tx_ref())
+            .with_targets(
+                targets
+                    .iter()
+                    .map(|(account, slots)| (*account, slots.iter().copied().collect()))
+                    .collect(),
+            )
+            .with_branch_node_masks(true)
+            .multiproof()
+            .unwrap();
+        let storage_roots =
+            multiproof.storages.iter().map(|(account, proof)| (*account, proof.root)).collect();
+        sparse.reveal_multiproof(targets, multiproof).unwrap();
+        storage_roots
+    }
+
+    #[test]
+    fn sparse_state_trie_updates() {
+        let factory = create_test_provider_factory();
+        let mut rng = rand::thread_rng();
+        let state = random_state(&mut rng);
+        insert_state(&factory, &state);
+
+        let hashed_state = random_changes(&mut rng, &state, false);
+        let targets = hashed_state
+            .accounts
+            .keys()
+            .map(|account| {
+                let slots = hashed_state
+                    .storages
+                    .get(account)
+                    .map(|storage| storage.storage.keys().copied().collect())
+                    .unwrap_or_default();
+                (*account, slots)
+            })
+            .collect::<HashMap<_, _>>();
+
+        let mut sparse = SparseStateTrie::default();
+        let storage_roots = reveal_targets(&factory, &mut sparse, targets.clone());
+        assert!(targets.keys().all(|account| sparse.is_account_revealed(account)));
+        assert!(targets.iter().all(|(account, slots)| {
+            slots.iter().all(|slot| sparse.is_storage_slot_revealed(account, slot))
+        }));
+
+        let provider = factory.provider().unwrap();
+        assert_eq!(sparse.root(), Some(StateRoot::from_tx(provider.tx_ref()).root().unwrap()));
+
+        apply_changes(&mut sparse, &hashed_state, storage_roots);
+        assert_eq!(
+            sparse.root(),
+            Some(
+                StateRoot::overlay_root(provider.tx_ref(), hashed_state, Default::default())
+                    .unwrap()
+            )
+        );
+    }
+
+    #[test]
+    fn sparse_state_trie_removals() {
+        let factory = create_test_provider_factory();
+        let mut rng = rand::thread_rng();
+        let state = random_state(&mut rng);
+        insert_state(&factory, &state);
+
+        // Removals might collapse branches into the nodes that aren't on the paths of the changed
+        // keys, so all of them are revealed.
+        let hashed_state = random_changes(&mut rng, &state, true);
+        let mut targets = state
+            .iter()
+            .map(|(address, (_, storage))| {
+                (keccak256(address), storage.keys().map(keccak256).collect::<HashSet<_>>())
+            })
+            .collect::<HashMap<_, _>>();
+        for (account, storage) in &hashed_state.storages {
+            targets.entry(*account).or_default().extend(storage.storage.keys().copied());
+        }
+        for account in hashed_state.accounts.keys() {
+            targets.entry(*account).or_default();
+        }
+
+        let mut sparse = SparseStateTrie::default();
+        let storage_roots = reveal_targets(&factory, &mut sparse, targets);
+
+        apply_changes(&mut sparse, &hashed_state, storage_roots);
+        let provider = factory.provider().unwrap();
+        assert_eq!(
+            sparse.root(),
+            Some(
+                StateRoot::overlay_root(provider.tx_ref(), hashed_state, Default::default())
+                    .unwrap()
+            )
+        );
+    }
+
+    #[test]
+    fn sparse_state_trie_reveal_witness() {
+        let factory = create_test_provider_factory();
+        let mut rng = rand::thread_rng();
+        let state = random_state(&mut rng);
+        insert_state(&factory, &state);
+
+        let hashed_state = random_changes(&mut rng, &state, true);
+        let provider = factory.provider().unwrap();
+        let state_root = StateRoot::from_tx(provider.tx_ref()).root().unwrap();
+        let witness = TrieWitness::overlay_witness(
+            provider.tx_ref(),
+            HashedPostState::default(),
+            hashed_state.clone(),
+        )
+        .unwrap();
+
+        let mut sparse = SparseStateTrie::default();
+        sparse.reveal_witness(state_root, &witness).unwrap();
+
+        // The changed accounts are found, or proven to not exist.
+        let mut storage_roots = HashMap::default();
+        for hashed_address in hashed_state.accounts.keys() {
+            let value = sparse.find_account_value(hashed_address).unwrap();
+            let existing = state.iter().find(|(address, _)| keccak256(address) == *hashed_address);
+            assert_eq!(value.is_some(), existing.is_some());
+            if let Some(value) = value {
+                let account = TrieAccount::decode(&mut &value[..]).unwrap();
+                assert_eq!(account.balance, existing.unwrap().1 .0.balance);
+                storage_roots.insert(*hashed_address, account.storage_root);
+            }
+        }
+
+        apply_changes(&mut sparse, &hashed_state, storage_roots);
+        assert_eq!(
+            sparse.root(),
+            Some(
+                StateRoot::overlay_root(provider.tx_ref(), hashed_state, Default::default())
+                    .unwrap()
+            )
+        );
+    }
+
+    #[test]
+    fn sparse_state_trie_reveal_witness_short_leaf() {
+        let value = alloy_rlp::encode(TrieAccount::default());
+        let root = alloy_rlp::encode(TrieNode::Leaf(LeafNode::new(
+            Nibbles::from_nibbles([0x1]),
+            value.clone(),
+        )));
+        let state_root = keccak256(&root);
+        let witness = HashMap::from([(state_root, Bytes::from(root))]);
+
+        let mut sparse = SparseStateTrie::default();
+        assert_eq!(
+            sparse.reveal_witness(state_root, &witness),
+            Err(SparseTrieError::InvalidNode {
+                path: Nibbles::from_nibbles([0x1]),
+                node: value.into()
+            })
+        );
+    }
+
+    #[test]
+    fn sparse_state_trie_blind_storage() {
+        let mut sparse = SparseStateTrie::default();
+        assert_eq!(sparse.root(), None);
+        assert_eq!(
+            sparse.update_storage_leaf(B256::ZERO, Nibbles::unpack(B256::ZERO), vec![1]),
+            Err(SparseTrieError::Blind)
+        );
+
+        sparse.wipe_storage(B256::ZERO);
+        assert_eq!(sparse.storage_root(B256::ZERO), Some(EMPTY_ROOT_HASH));
+    }
+}
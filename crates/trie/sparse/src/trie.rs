@@ -0,0 +1,1081 @@
+use alloy_rlp::{Decodable, EMPTY_STRING_CODE};
+use reth_execution_errors::SparseTrieError;
+use reth_primitives::{keccak256, B256};
+use reth_trie_common::{
+    word_rlp, BranchNodeCompact, BranchNodeMasks, BranchNodeRef, ExtensionNodeRef, LeafNodeRef,
+    Nibbles, TrieMask, TrieNode, CHILD_INDEX_RANGE,
+};
+use std::collections::{HashMap, HashSet};
+
+/// Result type with [`SparseTrieError`] as error.
+pub type SparseTrieResult<Ok> = Result<Ok, SparseTrieError>;
+
+/// Inner representation of the sparse trie.
+/// Sparse trie is blind by default until nodes are revealed.
+#[derive(PartialEq, Eq, Default, Debug)]
+pub enum SparseTrie {
+    /// None of the trie nodes are known.
+    #[default]
+    Blind,
+    /// The trie nodes have been revealed.
+    Revealed(Box<RevealedSparseTrie>),
+}
+
+impl SparseTrie {
+    /// Creates new revealed empty trie.
+    pub fn revealed_empty() -> Self {
+        Self::Revealed(Box::default())
+    }
+
+    /// Returns `true` if the sparse trie has no revealed nodes.
+    pub const fn is_blind(&self) -> bool {
+        matches!(self, Self::Blind)
+    }
+
+    /// Returns reference to revealed sparse trie if the trie is not blind.
+    pub fn as_revealed_ref(&self) -> Option<&RevealedSparseTrie> {
+        if let Self::Revealed(revealed) = self {
+            Some(revealed)
+        } else {
+            None
+        }
+    }
+
+    /// Returns mutable reference to revealed sparse trie if the trie is not blind.
+    pub fn as_revealed_mut(&mut self) -> Option<&mut RevealedSparseTrie> {
+        if let Self::Revealed(revealed) = self {
+            Some(revealed)
+        } else {
+            None
+        }
+    }
+
+    /// Reveals the root node with its masks if the trie is blind, and returns the revealed trie.
+    /// The updates of the revealed trie are retained if `retain_updates` is set, see
+    /// [`RevealedSparseTrie::with_updates`].
+    pub fn reveal_root(
+        &mut self,
+        root: TrieNode,
+        masks: Option<BranchNodeMasks>,
+        retain_updates: bool,
+    ) -> SparseTrieResult<&mut RevealedSparseTrie> {
+        if self.is_blind() {
+            *self = Self::Revealed(Box::new(RevealedSparseTrie::from_root(
+                root,
+                masks,
+                retain_updates,
+            )?));
+        }
+        Ok(self.as_revealed_mut().expect("trie is revealed"))
+    }
+
+    /// Reveals the node at the given path with its masks. The root node at the empty path reveals
+    /// the trie without retaining updates if it's blind, the other nodes can only be revealed on a
+    /// revealed trie.
+    pub fn reveal_node(
+        &mut self,
+        path: Nibbles,
+        node: TrieNode,
+        masks: Option<BranchNodeMasks>,
+    ) -> SparseTrieResult<()> {
+        if path.is_empty() {
+            self.reveal_root(node, masks, false)?;
+            Ok(())
+        } else {
+            self.as_revealed_mut().ok_or(SparseTrieError::Blind)?.reveal_node(path, node, masks)
+        }
+    }
+
+    /// Finds the value of the leaf with the given full path by traversing the trie.
+    pub fn find_leaf(&self, path: &Nibbles) -> SparseTrieResult<Option<&Vec<u8>>> {
+        self.as_revealed_ref().ok_or(SparseTrieError::Blind)?.find_leaf(path)
+    }
+
+    /// Update the leaf node with the given full path.
+    pub fn update_leaf(&mut self, path: Nibbles, value: Vec<u8>) -> SparseTrieResult<()> {
+        self.as_revealed_mut().ok_or(SparseTrieError::Blind)?.update_leaf(path, value)
+    }
+
+    /// Remove the leaf node with the given full path.
+    pub fn remove_leaf(&mut self, path: &Nibbles) -> SparseTrieResult<()> {
+        self.as_revealed_mut().ok_or(SparseTrieError::Blind)?.remove_leaf(path)
+    }
+
+    /// Calculates and returns the trie root if the trie has been revealed.
+    pub fn root(&mut self) -> Option<B256> {
+        Some(self.as_revealed_mut()?.root())
+    }
+}
+
+/// The representation of revealed sparse trie.
+///
+/// Nodes are stored by their path, and the values of the leaves by the full path of the leaf. The
+/// nodes that weren't revealed are stored as [`SparseNode::Hash`], and can't be traversed.
+///
+/// The RLP encodings of the nodes are cached, and invalidated on the path of every updated leaf,
+/// so that the root is incrementally recomputed only for the changed subtries.
+///
+/// If updates are retained, the branch nodes that are recomputed with the root are collected as
+/// [`SparseTrieUpdates`] of the database trie. This requires the masks of the revealed branch
+/// nodes, which tell whether their blinded children are branch nodes stored in the database trie.
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub struct RevealedSparseTrie {
+    /// All trie nodes.
+    nodes: HashMap<Nibbles, SparseNode>,
+    /// All leaf values.
+    values: HashMap<Nibbles, Vec<u8>>,
+    /// The bits of the blinded nodes in the masks of their parent branch nodes, if known.
+    blinded_mask_bits: HashMap<Nibbles, ChildMaskBits>,
+    /// Paths of the revealed branch nodes that are stored in the database trie.
+    stored_branch_nodes: HashSet<Nibbles>,
+    /// Updates of the database trie, if they are retained.
+    updates: Option<SparseTrieUpdates>,
+    /// Reusable buffer for RLP encoding of nodes.
+    rlp_buf: Vec<u8>,
+}
+
+impl Default for RevealedSparseTrie {
+    fn default() -> Self {
+        Self {
+            nodes: HashMap::from([(Nibbles::default(), SparseNode::Empty)]),
+            values: HashMap::default(),
+            blinded_mask_bits: HashMap::default(),
+            stored_branch_nodes: HashSet::default(),
+            updates: None,
+            rlp_buf: Vec::new(),
+        }
+    }
+}
+
+impl RevealedSparseTrie {
+    /// Create new revealed sparse trie from the given root node and its masks, retaining the
+    /// updates if `retain_updates` is set.
+    pub fn from_root(
+        node: TrieNode,
+        masks: Option<BranchNodeMasks>,
+        retain_updates: bool,
+    ) -> SparseTrieResult<Self> {
+        let mut this =
+            Self { nodes: HashMap::default(), ..Default::default() }.with_updates(retain_updates);
+        this.reveal_node(Nibbles::default(), node, masks)?;
+        Ok(this)
+    }
+
+    /// Sets whether the updates of the database trie are retained. They are collected when the
+    /// root is calculated, and returned by [`Self::take_updates`].
+    pub fn with_updates(mut self, retain_updates: bool) -> Self {
+        self.updates = retain_updates.then(SparseTrieUpdates::default);
+        self
+    }
+
+    /// Returns the updates of the database trie collected since they were last taken, or empty
+    /// updates if they aren't retained.
+    pub fn take_updates(&mut self) -> SparseTrieUpdates {
+        self.updates.as_mut().map(std::mem::take).unwrap_or_default()
+    }
+
+    /// Removes all nodes and leaf values, leaving an empty trie. If updates are retained, they are
+    /// marked as wiped, as the nodes stored in the database trie are all removed.
+    pub fn wipe(&mut self) {
+        let updates =
+            self.updates.is_some().then(|| SparseTrieUpdates { wiped: true, ..Default::default() });
+        *self = Self { updates, ..Default::default() };
+    }
+
+    /// Returns a reference to the leaf value with the given full path, if it's revealed.
+    pub fn get_leaf_value(&self, path: &Nibbles) -> Option<&Vec<u8>> {
+        self.values.get(path)
+    }
+
+    /// Finds the value of the leaf with the given full path by traversing the trie.
+    ///
+    /// Unlike [`Self::get_leaf_value`], this tells a leaf that doesn't exist from one that wasn't
+    /// revealed: returns `None` if the revealed nodes prove that the leaf doesn't exist, and
+    /// [`SparseTrieError::BlindedNode`] if the path of the leaf goes through a node that wasn't
+    /// revealed.
+    pub fn find_leaf(&self, path: &Nibbles) -> SparseTrieResult<Option<&Vec<u8>>> {
+        let mut current = Nibbles::default();
+        loop {
+            match self
+                .nodes
+                .get(&current)
+                .ok_or_else(|| SparseTrieError::MissingNode(current.clone()))?
+            {
+                SparseNode::Empty => return Ok(None),
+                &SparseNode::Hash(hash) => {
+                    return Err(SparseTrieError::BlindedNode { path: current, hash })
+                }
+                SparseNode::Leaf { key, .. } => {
+                    if path[current.len()..] != key[..] {
+                        return Ok(None)
+                    }
+                    return Ok(self.values.get(path))
+                }
+                SparseNode::Extension { key, .. } => {
+                    if !path[current.len()..].starts_with(&key[..]) {
+                        return Ok(None)
+                    }
+                    current.extend_from_slice(key);
+                }
+                SparseNode::Branch { state_mask, .. } => {
+                    let nibble = path[current.len()];
+                    if !state_mask.is_bit_set(nibble) {
+                        return Ok(None)
+                    }
+                    current.push_unchecked(nibble);
+                }
+            }
+        }
+    }
+
+    /// Reveal the trie node at the given path, with its masks if it's a branch node. Nodes that
+    /// are already revealed are kept, as they might have been updated since.
+    ///
+    /// The masks are required to retain the updates of the database trie, and are empty for
+    /// branch nodes that aren't stored in the database trie.
+    pub fn reveal_node(
+        &mut self,
+        path: Nibbles,
+        node: TrieNode,
+        masks: Option<BranchNodeMasks>,
+    ) -> SparseTrieResult<()> {
+        // The RLP encoding of a node revealed in place of a blinded node is its hash, since the
+        // node wasn't updated.
+        let rlp = match self.nodes.get(&path) {
+            Some(SparseNode::Hash(hash)) => Some(word_rlp(hash)),
+            Some(_) => return Ok(()),
+            None => None,
+        };
+        self.reveal_node_with_rlp(path, node, masks, rlp)
+    }
+
+    /// Reveals the node at the given path that isn't revealed yet, with its cached RLP encoding.
+    fn reveal_node_with_rlp(
+        &mut self,
+        path: Nibbles,
+        node: TrieNode,
+        masks: Option<BranchNodeMasks>,
+        rlp: Option<Vec<u8>>,
+    ) -> SparseTrieResult<()> {
+        let mask_bits = self.blinded_mask_bits.remove(&path);
+        match node {
+            TrieNode::Branch(branch) => {
+                let mut stack_ptr = branch.as_ref().first_child_index();
+                for idx in CHILD_INDEX_RANGE {
+                    if branch.state_mask.is_bit_set(idx) {
+                        let mut child_path = path.clone();
+                        child_path.push_unchecked(idx);
+                        let child_mask_bits = masks.map(|masks| ChildMaskBits {
+                            hash: masks.hash_mask.is_bit_set(idx),
+                            tree: masks.tree_mask.is_bit_set(idx),
+                        });
+                        self.reveal_child(child_path, &branch.stack[stack_ptr], child_mask_bits)?;
+                        stack_ptr += 1;
+                    }
+                }
+                if masks.is_some_and(|masks| !masks.is_empty()) {
+                    self.stored_branch_nodes.insert(path.clone());
+                }
+                self.nodes.insert(path, SparseNode::Branch { state_mask: branch.state_mask, rlp });
+            }
+            TrieNode::Extension(ext) => {
+                // The child of an extension node is a branch node, which is stored in the database
+                // trie if the bit of the extension node in the tree mask of its parent is set.
+                let child_mask_bits = mask_bits.map(|bits| ChildMaskBits { hash: true, ..bits });
+                self.reveal_child(path.join(&ext.key), &ext.child, child_mask_bits)?;
+                self.nodes.insert(path, SparseNode::Extension { key: ext.key, rlp });
+            }
+            TrieNode::Leaf(leaf) => {
+                self.values.entry(path.join(&leaf.key)).or_insert(leaf.value);
+                self.nodes.insert(path, SparseNode::Leaf { key: leaf.key, rlp });
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Reveals the child with the given RLP, which is either the hash of the child node or the
+    /// child node itself if its encoding is shorter than 32 bytes. Children that are already
+    /// revealed are kept.
+    fn reveal_child(
+        &mut self,
+        path: Nibbles,
+        child: &[u8],
+        mask_bits: Option<ChildMaskBits>,
+    ) -> SparseTrieResult<()> {
+        if self.nodes.contains_key(&path) {
+            return Ok(())
+        }
+        if let Some(mask_bits) = mask_bits {
+            self.blinded_mask_bits.insert(path.clone(), mask_bits);
+        }
+
+        if child.len() == B256::len_bytes() + 1 {
+            let hash = B256::from_slice(&child[1..]);
+            self.nodes.insert(path, SparseNode::Hash(hash));
+            Ok(())
+        } else {
+            let node = TrieNode::decode(&mut &child[..]).map_err(|_| {
+                SparseTrieError::InvalidNode { path: path.clone(), node: child.to_vec().into() }
+            })?;
+            self.reveal_node_with_rlp(path, node, None, Some(child.to_vec()))
+        }
+    }
+
+    /// Update the leaf node with the given full path, inserting it if it doesn't exist.
+    ///
+    /// Returns [`SparseTrieError::BlindedNode`] if the path of the leaf goes through a node that
+    /// wasn't revealed. If updates are retained, this is also returned if an extension node is
+    /// split and the masks of its blinded child aren't known, which is the case for the child of an
+    /// extension node at the root.
+    pub fn update_leaf(&mut self, path: Nibbles, value: Vec<u8>) -> SparseTrieResult<()> {
+        let mut current = Nibbles::default();
+        loop {
+            let node = self
+                .nodes
+                .get_mut(&current)
+                .ok_or_else(|| SparseTrieError::MissingNode(current.clone()))?;
+            node.clear_rlp();
+
+            match node {
+                SparseNode::Empty => {
+                    *node = SparseNode::new_leaf(path.slice(current.len()..));
+                    break
+                }
+                &mut SparseNode::Hash(hash) => {
+                    return Err(SparseTrieError::BlindedNode { path: current, hash })
+                }
+                SparseNode::Leaf { key, .. } => {
+                    let key = key.clone();
+                    let existing_path = current.join(&key);
+                    if existing_path == path {
+                        // Only the value is updated.
+                        break
+                    }
+
+                    // Split the leaf into a branch with the existing and the new leaf, under an
+                    // extension with the common prefix if there's one.
+                    let common = key.common_prefix_length(&path[current.len()..]);
+                    if common > 0 {
+                        *node = SparseNode::new_ext(key.slice(..common));
+                    }
+                    let branch_path = path.slice(..current.len() + common);
+
+                    let mut state_mask = TrieMask::default();
+                    for leaf_path in [&existing_path, &path] {
+                        let nibble = leaf_path[branch_path.len()];
+                        state_mask.set_bit(nibble);
+                        self.nodes.insert(
+                            leaf_path.slice(..branch_path.len() + 1),
+                            SparseNode::new_leaf(leaf_path.slice(branch_path.len() + 1..)),
+                        );
+                    }
+                    self.nodes.insert(branch_path, SparseNode::new_branch(state_mask));
+                    break
+                }
+                SparseNode::Extension { key, .. } => {
+                    if path[current.len()..].starts_with(&key[..]) {
+                        current.extend_from_slice(key);
+                        continue
+                    }
+
+                    // Split the extension into a branch with the existing extension child and the
+                    // new leaf, under an extension with the common prefix if there's one.
+                    let ext_key = key.clone();
+                    let child_path = current.join(&ext_key);
+                    if let Some(&SparseNode::Hash(hash)) = self.nodes.get(&child_path) {
+                        if self.updates.is_some() &&
+                            !self.blinded_mask_bits.contains_key(&child_path)
+                        {
+                            return Err(SparseTrieError::BlindedNode { path: child_path, hash })
+                        }
+                    }
+
+                    let common = ext_key.common_prefix_length(&path[current.len()..]);
+                    if common > 0 {
+                        self.nodes
+                            .insert(current.clone(), SparseNode::new_ext(ext_key.slice(..common)));
+                    }
+                    let branch_path = path.slice(..current.len() + common);
+
+                    let mut state_mask = TrieMask::default();
+                    let ext_nibble = ext_key[common];
+                    state_mask.set_bit(ext_nibble);
+                    if ext_key.len() > common + 1 {
+                        // The remainder of the key leads to the existing extension child.
+                        let mut ext_path = branch_path.clone();
+                        ext_path.push_unchecked(ext_nibble);
+                        self.nodes
+                            .insert(ext_path, SparseNode::new_ext(ext_key.slice(common + 1..)));
+                    }
+
+                    state_mask.set_bit(path[branch_path.len()]);
+                    self.nodes.insert(
+                        path.slice(..branch_path.len() + 1),
+                        SparseNode::new_leaf(path.slice(branch_path.len() + 1..)),
+                    );
+                    self.nodes.insert(branch_path, SparseNode::new_branch(state_mask));
+                    break
+                }
+                SparseNode::Branch { state_mask, .. } => {
+                    let nibble = path[current.len()];
+                    current.push_unchecked(nibble);
+                    if !state_mask.is_bit_set(nibble) {
+                        state_mask.set_bit(nibble);
+                        let leaf = SparseNode::new_leaf(path.slice(current.len()..));
+                        self.nodes.insert(current, leaf);
+                        break
+                    }
+                }
+            }
+        }
+
+        self.values.insert(path, value);
+        Ok(())
+    }
+
+    /// Remove the leaf node with the given full path. Nothing is removed if the leaf doesn't
+    /// exist.
+    ///
+    /// Returns [`SparseTrieError::BlindedNode`] if the path of the leaf goes through a node that
+    /// wasn't revealed, or if the removal collapses a branch into its only remaining child and
+    /// that child wasn't revealed. The trie is left unchanged on error.
+    pub fn remove_leaf(&mut self, path: &Nibbles) -> SparseTrieResult<()> {
+        // Find the leaf, collecting the paths of the nodes above it.
+        let mut stack = Vec::new();
+        let mut current = Nibbles::default();
+        loop {
+            match self
+                .nodes
+                .get(&current)
+                .ok_or_else(|| SparseTrieError::MissingNode(current.clone()))?
+            {
+                SparseNode::Empty => return Ok(()),
+                &SparseNode::Hash(hash) => {
+                    return Err(SparseTrieError::BlindedNode { path: current, hash })
+                }
+                SparseNode::Leaf { key, .. } => {
+                    if path[current.len()..] != key[..] {
+                        return Ok(())
+                    }
+                    break
+                }
+                SparseNode::Extension { key, .. } => {
+                    if !path[current.len()..].starts_with(&key[..]) {
+                        return Ok(())
+                    }
+                    stack.push(current.clone());
+                    current.extend_from_slice(key);
+                }
+                SparseNode::Branch { state_mask, .. } => {
+                    let nibble = path[current.len()];
+                    if !state_mask.is_bit_set(nibble) {
+                        return Ok(())
+                    }
+                    stack.push(current.clone());
+                    current.push_unchecked(nibble);
+                }
+            }
+        }
+
+        let Some(branch_path) = stack.pop() else {
+            // The leaf is the root.
+            self.nodes.insert(Nibbles::default(), SparseNode::Empty);
+            self.values.remove(path);
+            return Ok(())
+        };
+
+        // Leaves are always children of branches, since extensions are merged into leaves.
+        let Some(SparseNode::Branch { state_mask, .. }) = self.nodes.get(&branch_path) else {
+            return Err(SparseTrieError::MissingNode(branch_path))
+        };
+        let state_mask =
+            TrieMask::new(state_mask.get() & !TrieMask::from_nibble(path[branch_path.len()]).get());
+
+        // The branch with a single child left is collapsed into the child, which has to be
+        // revealed.
+        let mut remaining_child = None;
+        if state_mask.count_ones() == 1 {
+            let mut child_path = branch_path.clone();
+            child_path.push_unchecked(state_mask.trailing_zeros() as u8);
+            match self.nodes.get(&child_path) {
+                Some(&SparseNode::Hash(hash)) => {
+                    return Err(SparseTrieError::BlindedNode { path: child_path, hash })
+                }
+                Some(_) => remaining_child = Some(child_path),
+                None => return Err(SparseTrieError::MissingNode(child_path)),
+            }
+        }
+
+        self.nodes.remove(&current);
+        self.values.remove(path);
+        for node_path in &stack {
+            if let Some(node) = self.nodes.get_mut(node_path) {
+                node.clear_rlp();
+            }
+        }
+
+        let Some(child_path) = remaining_child else {
+            self.nodes.insert(branch_path, SparseNode::new_branch(state_mask));
+            return Ok(())
+        };
+
+        self.remove_branch_node(&branch_path);
+        let child_nibble = child_path.slice(branch_path.len()..);
+        let collapsed = match self.nodes.remove(&child_path).expect("child exists") {
+            SparseNode::Leaf { key, .. } => SparseNode::new_leaf(child_nibble.join(&key)),
+            SparseNode::Extension { key, .. } => SparseNode::new_ext(child_nibble.join(&key)),
+            child @ SparseNode::Branch { .. } => {
+                self.nodes.insert(child_path, child);
+                SparseNode::new_ext(child_nibble)
+            }
+            SparseNode::Empty | SparseNode::Hash(_) => {
+                unreachable!("branch children are non-empty and revealed")
+            }
+        };
+
+        // Merge the collapsed node into the parent extension, if there's one.
+        if let Some(parent_path) = stack.last() {
+            if let Some(SparseNode::Extension { key: parent_key, .. }) = self.nodes.get(parent_path)
+            {
+                let merged = match &collapsed {
+                    SparseNode::Leaf { key, .. } => {
+                        Some(SparseNode::new_leaf(parent_key.join(key)))
+                    }
+                    SparseNode::Extension { key, .. } => {
+                        Some(SparseNode::new_ext(parent_key.join(key)))
+                    }
+                    _ => None,
+                };
+                if let Some(merged) = merged {
+                    self.nodes.remove(&branch_path);
+                    self.nodes.insert(parent_path.clone(), merged);
+                    return Ok(())
+                }
+            }
+        }
+
+        self.nodes.insert(branch_path, collapsed);
+        Ok(())
+    }
+
+    /// Calculates and returns the trie root. If updates are retained, the recomputed branch nodes
+    /// are collected.
+    pub fn root(&mut self) -> B256 {
+        rlp_node_hash(&self.rlp_node(Nibbles::default()))
+    }
+
+    /// Returns the RLP encoding of the node at the given path, or the RLP encoded hash of the node
+    /// if the encoding is 32 bytes or longer. The results are cached in the nodes.
+    fn rlp_node(&mut self, path: Nibbles) -> Vec<u8> {
+        let node = self.nodes.get(&path).expect("node exists");
+        let rlp = match node {
+            SparseNode::Empty => return vec![EMPTY_STRING_CODE],
+            SparseNode::Hash(hash) => return word_rlp(hash),
+            SparseNode::Leaf { rlp: Some(rlp), .. } |
+            SparseNode::Extension { rlp: Some(rlp), .. } |
+            SparseNode::Branch { rlp: Some(rlp), .. } => return rlp.clone(),
+            SparseNode::Leaf { key, rlp: None } => {
+                let value = self.values.get(&path.join(key)).expect("leaf value exists");
+                self.rlp_buf.clear();
+                LeafNodeRef::new(key, value).rlp(&mut self.rlp_buf)
+            }
+            SparseNode::Extension { key, rlp: None } => {
+                let key = key.clone();
+                let child = self.rlp_node(path.join(&key));
+                self.rlp_buf.clear();
+                ExtensionNodeRef::new(&key, &child).rlp(&mut self.rlp_buf)
+            }
+            &SparseNode::Branch { state_mask, rlp: None } => {
+                let mut stack = Vec::with_capacity(state_mask.count_ones() as usize);
+                let mut masks = BranchNodeMasks::default();
+                let mut hashes = Vec::new();
+                for nibble in CHILD_INDEX_RANGE.filter(|nibble| state_mask.is_bit_set(*nibble)) {
+                    let mut child_path = path.clone();
+                    child_path.push_unchecked(nibble);
+                    let child_rlp = self.rlp_node(child_path.clone());
+                    if self.updates.is_some() {
+                        let bits = self.child_mask_bits(&child_path);
+                        if bits.hash {
+                            masks.hash_mask.set_bit(nibble);
+                            hashes.push(B256::from_slice(&child_rlp[1..]));
+                        }
+                        if bits.tree {
+                            masks.tree_mask.set_bit(nibble);
+                        }
+                    }
+                    stack.push(child_rlp);
+                }
+                self.rlp_buf.clear();
+                let rlp = BranchNodeRef::new(&stack, &state_mask).rlp(&mut self.rlp_buf);
+                if self.updates.is_some() {
+                    self.update_branch_node(&path, state_mask, masks, hashes, &rlp);
+                }
+                rlp
+            }
+        };
+
+        self.nodes.get_mut(&path).expect("node exists").set_rlp(rlp.clone());
+        rlp
+    }
+
+    /// Returns the bits of the node at the given path in the masks of its parent branch node.
+    fn child_mask_bits(&self, path: &Nibbles) -> ChildMaskBits {
+        match self.nodes.get(path) {
+            Some(SparseNode::Hash(_)) => {
+                self.blinded_mask_bits.get(path).copied().unwrap_or_default()
+            }
+            Some(SparseNode::Branch { .. }) => {
+                ChildMaskBits { hash: true, tree: self.stored_branch_nodes.contains(path) }
+            }
+            Some(SparseNode::Extension { key, .. }) => {
+                ChildMaskBits { hash: false, tree: self.child_mask_bits(&path.join(key)).tree }
+            }
+            Some(SparseNode::Empty | SparseNode::Leaf { .. }) | None => ChildMaskBits::default(),
+        }
+    }
+
+    /// Collects the update of the recomputed branch node at the given path. The branch node is
+    /// stored in the database trie if any of its masks is set, otherwise it's removed.
+    fn update_branch_node(
+        &mut self,
+        path: &Nibbles,
+        state_mask: TrieMask,
+        masks: BranchNodeMasks,
+        hashes: Vec<B256>,
+        rlp: &[u8],
+    ) {
+        if masks.is_empty() {
+            self.remove_branch_node(path);
+            return
+        }
+
+        self.stored_branch_nodes.insert(path.clone());
+        if let Some(updates) = self.updates.as_mut() {
+            // Like the hash builder, the root node is updated with its hash.
+            let root_hash = path.is_empty().then(|| rlp_node_hash(rlp));
+            let node = BranchNodeCompact::new(
+                state_mask,
+                masks.tree_mask,
+                masks.hash_mask,
+                hashes,
+                root_hash,
+            );
+            updates.removed_nodes.remove(path);
+            updates.updated_nodes.insert(path.clone(), node);
+        }
+    }
+
+    /// Removes the branch node at the given path from the database trie.
+    fn remove_branch_node(&mut self, path: &Nibbles) {
+        self.stored_branch_nodes.remove(path);
+        if let Some(updates) = self.updates.as_mut() {
+            updates.updated_nodes.remove(path);
+            updates.removed_nodes.insert(path.clone());
+        }
+    }
+}
+
+/// Returns the hash of the node with the given RLP encoding, which is either the RLP encoded hash
+/// of the node or the node itself if its encoding is shorter than 32 bytes.
+fn rlp_node_hash(rlp: &[u8]) -> B256 {
+    if rlp.len() == B256::len_bytes() + 1 {
+        B256::from_slice(&rlp[1..])
+    } else {
+        keccak256(rlp)
+    }
+}
+
+/// Updates of the branch nodes of the database trie, collected by a [`RevealedSparseTrie`] when
+/// its root is calculated.
+#[derive(Clone, PartialEq, Eq, Default, Debug)]
+pub struct SparseTrieUpdates {
+    /// The updated branch nodes that are stored in the database trie, keyed by path.
+    pub updated_nodes: HashMap<Nibbles, BranchNodeCompact>,
+    /// Paths of the branch nodes that were removed or aren't stored in the database trie anymore.
+    pub removed_nodes: HashSet<Nibbles>,
+    /// Whether the trie was wiped, which removes all of its nodes from the database trie before
+    /// the updated nodes are inserted.
+    pub wiped: bool,
+}
+
+/// The bits of a node in the hash mask and the tree mask of its parent branch node.
+#[derive(Clone, Copy, PartialEq, Eq, Default, Debug)]
+struct ChildMaskBits {
+    /// Whether the node is a branch node, whose hash is stored with its parent.
+    hash: bool,
+    /// Whether a branch node is stored in the database trie at the path of the node, or below it
+    /// if the node is an extension node.
+    tree: bool,
+}
+
+/// Enum representing trie nodes in sparse trie.
+#[derive(PartialEq, Eq, Clone, Debug)]
+pub enum SparseNode {
+    /// Empty trie node.
+    Empty,
+    /// The hash of the node that was not revealed.
+    Hash(B256),
+    /// Sparse leaf node with remaining key suffix.
+    Leaf {
+        /// Remaining key suffix for the leaf node.
+        key: Nibbles,
+        /// Cached RLP encoding of the node, or the RLP encoded hash of the node if the encoding
+        /// is 32 bytes or longer.
+        rlp: Option<Vec<u8>>,
+    },
+    /// Sparse extension node with key.
+    Extension {
+        /// The key slice stored by this extension node.
+        key: Nibbles,
+        /// Cached RLP encoding of the node, or the RLP encoded hash of the node if the encoding
+        /// is 32 bytes or longer.
+        rlp: Option<Vec<u8>>,
+    },
+    /// Sparse branch node with state mask.
+    Branch {
+        /// The bitmask representing children present in the branch node.
+        state_mask: TrieMask,
+        /// Cached RLP encoding of the node, or the RLP encoded hash of the node if the encoding
+        /// is 32 bytes or longer.
+        rlp: Option<Vec<u8>>,
+    },
+}
+
+impl SparseNode {
+    /// Create new [`SparseNode::Leaf`] from the key suffix.
+    pub const fn new_leaf(key: Nibbles) -> Self {
+        Self::Leaf { key, rlp: None }
+    }
+
+    /// Create new [`SparseNode::Extension`] from the key slice.
+    pub const fn new_ext(key: Nibbles) -> Self {
+        Self::Extension { key, rlp: None }
+    }
+
+    /// Create new [`SparseNode::Branch`] from the state mask.
+    pub const fn new_branch(state_mask: TrieMask) -> Self {
+        Self::Branch { state_mask, rlp: None }
+    }
+
+    /// Clears the cached RLP encoding of the node.
+    fn clear_rlp(&mut self) {
+        if let Self::Leaf { rlp, .. } | Self::Extension { rlp, .. } | Self::Branch { rlp, .. } =
+            self
+        {
+            *rlp = None;
+        }
+    }
+
+    /// Sets the cached RLP encoding of the node.
+    fn set_rlp(&mut self, value: Vec<u8>) {
+        if let Self::Leaf { rlp, .. } | Self::Extension { rlp, .. } | Self::Branch { rlp, .. } =
+            self
+        {
+            *rlp = Some(value);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::state::reveal_proof_nodes;
+    use proptest::prelude::*;
+    use reth_primitives::{Bytes, U256};
+    use reth_trie_common::{proof::ProofRetainer, HashBuilder, EMPTY_ROOT_HASH};
+    use std::collections::BTreeMap;
+
+    /// Calculates the root of the trie with the given leaves, retaining the proofs of the leaves
+    /// with the target paths.
+    fn hash_builder_root(
+        leaves: &BTreeMap<Nibbles, Vec<u8>>,
+        targets: impl IntoIterator<Item = Nibbles>,
+    ) -> (B256, BTreeMap<Nibbles, reth_primitives::Bytes>) {
+        let mut hash_builder =
+            HashBuilder::default().with_proof_retainer(ProofRetainer::from_iter(targets));
+        for (path, value) in leaves {
+            hash_builder.add_leaf(path.clone(), value);
+        }
+        let root = hash_builder.root();
+        (root, hash_builder.take_proofs())
+    }
+
+    /// Calculates the branch nodes of the trie with the given leaves that are stored in the
+    /// database trie, and retains the proofs of the leaves with the target paths together with the
+    /// masks of their branch nodes.
+    fn hash_builder_nodes(
+        leaves: &BTreeMap<Nibbles, Vec<u8>>,
+        targets: impl IntoIterator<Item = Nibbles>,
+    ) -> (
+        BTreeMap<Nibbles, BranchNodeCompact>,
+        BTreeMap<Nibbles, Bytes>,
+        HashMap<Nibbles, BranchNodeMasks>,
+    ) {
+        let mut hash_builder = HashBuilder::default()
+            .with_proof_retainer(ProofRetainer::from_iter(targets))
+            .with_updates(true);
+        for (path, value) in leaves {
+            hash_builder.add_leaf(path.clone(), value);
+        }
+        let _ = hash_builder.root();
+        let proofs = hash_builder.take_proofs();
+        let (_, updated_nodes) = hash_builder.split();
+        let masks = BranchNodeMasks::from_updated_nodes(&proofs, updated_nodes.clone());
+        (updated_nodes.into_iter().collect(), proofs, masks)
+    }
+
+    /// Reveals the proof nodes in the blind sparse trie.
+    fn reveal_proofs(proofs: BTreeMap<Nibbles, reth_primitives::Bytes>) -> SparseTrie {
+        let mut sparse = SparseTrie::default();
+        for (path, node) in proofs {
+            sparse.reveal_node(path, TrieNode::decode(&mut &node[..]).unwrap(), None).unwrap();
+        }
+        sparse
+    }
+
+    /// Builds a leaf path sharing a long prefix with the other paths with the same `prefix`, so
+    /// that the trie has both extensions and branches.
+    fn leaf_path(prefix: u8, suffix: u8) -> Nibbles {
+        let mut key = B256::ZERO;
+        key[0] = prefix;
+        key[31] = suffix;
+        Nibbles::unpack(key)
+    }
+
+    #[test]
+    fn sparse_trie_empty_root() {
+        assert_eq!(SparseTrie::default().root(), None);
+        assert_eq!(SparseTrie::revealed_empty().root(), Some(EMPTY_ROOT_HASH));
+    }
+
+    #[test]
+    fn sparse_trie_blind() {
+        let mut sparse = SparseTrie::default();
+        assert_eq!(sparse.update_leaf(leaf_path(0, 0), vec![1]), Err(SparseTrieError::Blind));
+        assert_eq!(sparse.remove_leaf(&leaf_path(0, 0)), Err(SparseTrieError::Blind));
+    }
+
+    #[test]
+    fn sparse_trie_remove_all_leaves() {
+        let leaves = (0..=4)
+            .flat_map(|prefix| (0..16).map(move |suffix| (leaf_path(prefix, suffix), vec![suffix])))
+            .collect::<BTreeMap<_, _>>();
+
+        let mut sparse = SparseTrie::revealed_empty();
+        for (path, value) in &leaves {
+            sparse.update_leaf(path.clone(), value.clone()).unwrap();
+        }
+        assert_eq!(sparse.root(), Some(hash_builder_root(&leaves, []).0));
+
+        let mut remaining = leaves;
+        while let Some((path, _)) = remaining.pop_first() {
+            sparse.remove_leaf(&path).unwrap();
+            assert_eq!(sparse.root(), Some(hash_builder_root(&remaining, []).0));
+        }
+        assert_eq!(sparse.root(), Some(EMPTY_ROOT_HASH));
+    }
+
+    #[test]
+    fn sparse_trie_reveal_and_update() {
+        let leaves = (0..=3)
+            .flat_map(|prefix| (0..8).map(move |suffix| (leaf_path(prefix, suffix), vec![suffix])))
+            .collect::<BTreeMap<_, _>>();
+        let target = leaf_path(1, 2);
+        let (root, proofs) = hash_builder_root(&leaves, [target.clone()]);
+
+        let mut sparse = reveal_proofs(proofs);
+        assert_eq!(sparse.root(), Some(root));
+        assert_eq!(sparse.as_revealed_mut().unwrap().get_leaf_value(&target), Some(&vec![2]));
+
+        // Update the revealed leaf and insert new leaves next to it.
+        let mut expected = leaves;
+        for (path, value) in [(target, vec![0xff]), (leaf_path(1, 0xaa), vec![0xaa])] {
+            sparse.update_leaf(path.clone(), value.clone()).unwrap();
+            expected.insert(path, value);
+        }
+        assert_eq!(sparse.root(), Some(hash_builder_root(&expected, []).0));
+
+        // The leaves of the blinded subtries can't be updated.
+        let blinded = leaf_path(2, 0);
+        assert!(matches!(
+            sparse.update_leaf(blinded.clone(), vec![1]),
+            Err(SparseTrieError::BlindedNode { .. })
+        ));
+        assert!(matches!(sparse.remove_leaf(&blinded), Err(SparseTrieError::BlindedNode { .. })));
+        assert_eq!(sparse.root(), Some(hash_builder_root(&expected, []).0));
+    }
+
+    #[test]
+    fn sparse_trie_remove_with_blinded_sibling() {
+        let leaves =
+            BTreeMap::from([(leaf_path(0, 0), vec![0; 32]), (leaf_path(0x10, 0), vec![1; 32])]);
+        let target = leaf_path(0, 0);
+        let (root, proofs) = hash_builder_root(&leaves, [target.clone()]);
+
+        // The removal collapses the root branch into the blinded sibling, so the trie is left
+        // unchanged.
+        let mut sparse = reveal_proofs(proofs);
+        let before = sparse.as_revealed_mut().unwrap().clone();
+        assert!(matches!(sparse.remove_leaf(&target), Err(SparseTrieError::BlindedNode { .. })));
+        assert_eq!(sparse.as_revealed_mut().unwrap(), &before);
+        assert_eq!(sparse.root(), Some(root));
+
+        // Once the sibling is revealed, the leaf can be removed.
+        let (_, proofs) = hash_builder_root(&leaves, [target.clone(), leaf_path(0x10, 0)]);
+        for (path, node) in proofs {
+            sparse.reveal_node(path, TrieNode::decode(&mut &node[..]).unwrap(), None).unwrap();
+        }
+        sparse.remove_leaf(&target).unwrap();
+        assert_eq!(
+            sparse.root(),
+            Some(hash_builder_root(&BTreeMap::from([(leaf_path(0x10, 0), vec![1; 32])]), []).0)
+        );
+    }
+
+    #[test]
+    fn sparse_trie_updates_root_extension() {
+        // The root is an extension node above the branch node of the leaves, which is blinded.
+        let leaves = (0..16)
+            .map(|suffix| (leaf_path(0, suffix << 4), vec![suffix; 32]))
+            .collect::<BTreeMap<_, _>>();
+        let (mut stored_nodes, proofs, masks) = hash_builder_nodes(&leaves, [leaf_path(0x10, 0)]);
+        let mut sparse = SparseTrie::default();
+        reveal_proof_nodes(&mut sparse, proofs, &masks, true).unwrap();
+
+        // Splitting the extension node needs the masks of its child, which aren't known until the
+        // child is revealed.
+        let path = leaf_path(0x10, 0);
+        let Err(SparseTrieError::BlindedNode { path: blinded, .. }) =
+            sparse.update_leaf(path.clone(), vec![0xff; 32])
+        else {
+            panic!("expected blinded node error")
+        };
+        let (_, proofs, masks) = hash_builder_nodes(&leaves, [leaf_path(0, 0)]);
+        assert!(proofs.contains_key(&blinded));
+        reveal_proof_nodes(&mut sparse, proofs, &masks, true).unwrap();
+        sparse.update_leaf(path.clone(), vec![0xff; 32]).unwrap();
+
+        let mut expected = leaves;
+        expected.insert(path, vec![0xff; 32]);
+        assert_eq!(sparse.root(), Some(hash_builder_root(&expected, []).0));
+
+        let updates = sparse.as_revealed_mut().unwrap().take_updates();
+        for path in &updates.removed_nodes {
+            stored_nodes.remove(path);
+        }
+        stored_nodes.extend(updates.updated_nodes);
+        assert_eq!(stored_nodes, hash_builder_nodes(&expected, []).0);
+    }
+
+    proptest! {
+        #![proptest_config(ProptestConfig::with_cases(64))]
+
+        /// Applies batches of random updates and removals to a sparse trie revealed from the
+        /// proofs of all leaves, and checks the root against the hash builder after each batch.
+        #[test]
+        fn sparse_trie_fuzz(
+            initial in proptest::collection::btree_map((0u8..4, any::<u8>()), any::<u64>(), 0..64),
+            batches in proptest::collection::vec(
+                proptest::collection::vec(((0u8..4, any::<u8>()), any::<Option<u64>>()), 1..32),
+                1..8,
+            ),
+        ) {
+            let mut expected = initial
+                .into_iter()
+                .map(|((prefix, suffix), value)| {
+                    (leaf_path(prefix, suffix), alloy_rlp::encode(U256::from(value)))
+                })
+                .collect::<BTreeMap<_, _>>();
+
+            let mut sparse = if expected.is_empty() {
+                SparseTrie::revealed_empty()
+            } else {
+                reveal_proofs(hash_builder_root(&expected, expected.keys().cloned()).1)
+            };
+            prop_assert_eq!(sparse.root(), Some(hash_builder_root(&expected, []).0));
+
+            for batch in batches {
+                for ((prefix, suffix), value) in batch {
+                    let path = leaf_path(prefix, suffix);
+                    if let Some(value) = value {
+                        let value = alloy_rlp::encode(U256::from(value));
+                        sparse.update_leaf(path.clone(), value.clone()).unwrap();
+                        expected.insert(path, value);
+                    } else {
+                        sparse.remove_leaf(&path).unwrap();
+                        expected.remove(&path);
+                    }
+                }
+                prop_assert_eq!(sparse.root(), Some(hash_builder_root(&expected, []).0));
+            }
+        }
+
+        /// Applies random updates and removals to a sparse trie revealed from the proofs of the
+        /// changed leaves, revealing the blinded nodes the changes run into, and checks that the
+        /// collected updates turn the stored branch nodes into the ones of the changed trie.
+        #[test]
+        fn sparse_trie_updates_fuzz(
+            initial in proptest::collection::btree_map((0u8..4, any::<u8>()), any::<u64>(), 0..64),
+            changes in proptest::collection::vec(
+                ((0u8..4, any::<u8>()), any::<Option<u64>>()),
+                1..32,
+            ),
+        ) {
+            let initial = initial
+                .into_iter()
+                .map(|((prefix, suffix), value)| {
+                    (leaf_path(prefix, suffix), alloy_rlp::encode(U256::from(value)))
+                })
+                .collect::<BTreeMap<_, _>>();
+            let targets = changes.iter().map(|((prefix, suffix), _)| leaf_path(*prefix, *suffix));
+            let (mut stored_nodes, proofs, masks) = hash_builder_nodes(&initial, targets);
+
+            let mut sparse = if initial.is_empty() {
+                SparseTrie::Revealed(Box::new(RevealedSparseTrie::default().with_updates(true)))
+            } else {
+                let mut sparse = SparseTrie::default();
+                reveal_proof_nodes(&mut sparse, proofs, &masks, true).unwrap();
+                sparse
+            };
+
+            let mut expected = initial.clone();
+            for ((prefix, suffix), value) in changes {
+                let path = leaf_path(prefix, suffix);
+                let value = value.map(|value| alloy_rlp::encode(U256::from(value)));
+                let mut revealed = HashSet::new();
+                loop {
+                    let result = match &value {
+                        Some(value) => sparse.update_leaf(path.clone(), value.clone()),
+                        None => sparse.remove_leaf(&path),
+                    };
+                    let Err(SparseTrieError::BlindedNode { path: blinded, .. }) = result else {
+                        result.unwrap();
+                        break
+                    };
+
+                    // Reveal the blinded node with the proof of a leaf under its path.
+                    prop_assert!(revealed.insert(blinded.clone()));
+                    let mut key = blinded;
+                    while key.len() < 64 {
+                        key.push_unchecked(0);
+                    }
+                    let (_, proofs, masks) = hash_builder_nodes(&initial, [key]);
+                    reveal_proof_nodes(&mut sparse, proofs, &masks, true).unwrap();
+                }
+                match value {
+                    Some(value) => expected.insert(path, value),
+                    None => expected.remove(&path),
+                };
+            }
+            prop_assert_eq!(sparse.root(), Some(hash_builder_root(&expected, []).0));
+
+            let updates = sparse.as_revealed_mut().unwrap().take_updates();
+            for path in &updates.removed_nodes {
+                stored_nodes.remove(path);
+            }
+            stored_nodes.extend(updates.updated_nodes);
+            prop_assert_eq!(stored_nodes, hash_builder_nodes(&expected, []).0);
+        }
+    }
+}
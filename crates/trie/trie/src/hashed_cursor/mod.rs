@@ -6,6 +6,7 @@ mod post_state;
 pub use post_state::*;
 
 /// The factory trait for creating cursors over the hashed state.
+#[auto_impl::auto_impl(&)]
 pub trait HashedCursorFactory {
     /// The hashed account cursor type.
     type AccountCursor: HashedCursor<Value = Account>;
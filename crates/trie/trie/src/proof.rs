@@ -1,18 +1,22 @@
 use crate::{
-    hashed_cursor::{HashedCursorFactory, HashedStorageCursor},
+    hashed_cursor::{HashedCursor, HashedCursorFactory, HashedStorageCursor},
     node_iter::{TrieElement, TrieNodeIter},
-    prefix_set::TriePrefixSetsMut,
-    trie_cursor::TrieCursorFactory,
+    prefix_set::{PrefixSet, PrefixSetMut, TriePrefixSetsMut},
+    trie_cursor::{TrieCursor, TrieCursorFactory},
     walker::TrieWalker,
-    HashBuilder, Nibbles,
+    BranchNodeCompact, HashBuilder, Nibbles,
 };
-use alloy_rlp::{BufMut, Encodable};
+use alloy_rlp::{BufMut, Decodable, Encodable};
+use rayon::prelude::*;
 use reth_execution_errors::trie::StateProofError;
-use reth_primitives::{keccak256, Address, B256};
+use reth_primitives::{keccak256, Address, B256, U256};
+use reth_storage_errors::db::DatabaseError;
 use reth_trie_common::{
-    proof::ProofRetainer, AccountProof, MultiProof, StorageMultiProof, TrieAccount,
+    proof::ProofRetainer, AccountProof, BranchNode, BranchNodeMasks, ExtensionNode, LeafNode,
+    MultiProof, StorageMultiProof, TrieAccount, TrieMask, TrieNode, CHILD_INDEX_RANGE,
+    EMPTY_ROOT_HASH,
 };
-use std::collections::HashMap;
+use std::collections::{BTreeMap, HashMap};
 
 /// A struct for generating merkle proofs.
 ///
@@ -29,6 +33,8 @@ pub struct Proof<T, H> {
     prefix_sets: TriePrefixSetsMut,
     /// Proof targets.
     targets: HashMap<B256, Vec<B256>>,
+    /// Whether the masks of the branch nodes of the proofs are collected.
+    branch_node_masks: bool,
 }
 
 impl<T, H> Proof<T, H> {
@@ -39,6 +45,7 @@ impl<T, H> Proof<T, H> {
             hashed_cursor_factory: h,
             prefix_sets: TriePrefixSetsMut::default(),
             targets: HashMap::default(),
+            branch_node_masks: false,
         }
     }
 
@@ -49,6 +56,7 @@ impl<T, H> Proof<T, H> {
             hashed_cursor_factory,
             prefix_sets: self.prefix_sets,
             targets: self.targets,
+            branch_node_masks: self.branch_node_masks,
         }
     }
 
@@ -63,6 +71,31 @@ impl<T, H> Proof<T, H> {
         self.targets = targets;
         self
     }
+
+    /// Set whether the masks of the branch nodes of the proofs are collected, which are needed to
+    /// reveal the proofs in a sparse trie.
+    pub const fn with_branch_node_masks(mut self, branch_node_masks: bool) -> Self {
+        self.branch_node_masks = branch_node_masks;
+        self
+    }
+
+    /// Returns the storage proof generator of the account.
+    fn storage_proof(&self, hashed_address: B256) -> StorageProof<&T, &H> {
+        let prefix_set =
+            self.prefix_sets.storage_prefix_sets.get(&hashed_address).cloned().unwrap_or_default();
+        StorageProof::new_hashed(
+            &self.trie_cursor_factory,
+            &self.hashed_cursor_factory,
+            hashed_address,
+        )
+        .with_prefix_set_mut(prefix_set)
+        .with_branch_node_masks(self.branch_node_masks)
+    }
+
+    /// Returns the target slots of the account.
+    fn target_slots(&self, hashed_address: &B256) -> &[B256] {
+        self.targets.get(hashed_address).map_or(&[], Vec::as_slice)
+    }
 }
 
 impl<T, H> Proof<T, H>
@@ -87,6 +120,23 @@ where
 
     /// Generate a state multiproof according to specified targets.
     pub fn multiproof(&self) -> Result<MultiProof, StateProofError> {
+        self.multiproof_with(|hashed_address| self.storage_multiproof(hashed_address))
+    }
+
+    /// Generate a storage multiproof according to specified targets.
+    pub fn storage_multiproof(
+        &self,
+        hashed_address: B256,
+    ) -> Result<StorageMultiProof, StateProofError> {
+        self.storage_proof(hashed_address).storage_multiproof(self.target_slots(&hashed_address))
+    }
+
+    /// Generate a state multiproof according to specified targets, generating the storage
+    /// multiproofs of the walked accounts with the given closure.
+    fn multiproof_with(
+        &self,
+        mut storage_multiproof: impl FnMut(B256) -> Result<StorageMultiProof, StateProofError>,
+    ) -> Result<MultiProof, StateProofError> {
         let hashed_account_cursor = self.hashed_cursor_factory.hashed_account_cursor()?;
         let trie_cursor = self.trie_cursor_factory.account_trie_cursor()?;
 
@@ -96,8 +146,11 @@ where
         let walker = TrieWalker::new(trie_cursor, prefix_set.freeze());
 
         // Create a hash builder to rebuild the root node since it is not available in the database.
+        // The updated branch nodes are retained for the masks of the proof nodes.
         let retainer = ProofRetainer::from_iter(self.targets.keys().map(Nibbles::unpack));
-        let mut hash_builder = HashBuilder::default().with_proof_retainer(retainer);
+        let mut hash_builder = HashBuilder::default()
+            .with_proof_retainer(retainer)
+            .with_updates(self.branch_node_masks);
 
         let mut storages = HashMap::default();
         let mut account_rlp = Vec::with_capacity(128);
@@ -108,7 +161,7 @@ where
                     hash_builder.add_branch(node.key, node.value, node.children_are_in_trie);
                 }
                 TrieElement::Leaf(hashed_address, account) => {
-                    let storage_multiproof = self.storage_multiproof(hashed_address)?;
+                    let storage_multiproof = storage_multiproof(hashed_address)?;
 
                     // Encode account
                     account_rlp.clear();
@@ -121,51 +174,352 @@ where
             }
         }
         let _ = hash_builder.root();
-        Ok(MultiProof { account_subtree: hash_builder.take_proofs(), storages })
+        let account_subtree = hash_builder.take_proofs();
+        let branch_node_masks = proof_branch_node_masks(&account_subtree, hash_builder);
+        Ok(MultiProof { account_subtree, branch_node_masks, storages })
     }
+}
 
-    /// Generate a storage multiproof according to specified targets.
+impl<T, H> Proof<T, H>
+where
+    T: TrieCursorFactory + Sync,
+    H: HashedCursorFactory + Clone + Sync,
+{
+    /// Generate a state multiproof according to specified targets, generating the storage
+    /// multiproofs of the accounts with target slots with
+    /// [`StorageProof::par_storage_multiproof`].
+    pub fn par_multiproof(&self) -> Result<MultiProof, StateProofError> {
+        self.multiproof_with(|hashed_address| {
+            let slots = self.target_slots(&hashed_address);
+            let storage_proof = self.storage_proof(hashed_address);
+            if slots.is_empty() {
+                storage_proof.storage_multiproof(slots)
+            } else {
+                storage_proof.par_storage_multiproof(slots)
+            }
+        })
+    }
+}
+
+/// A struct for generating storage merkle proofs of a single account.
+///
+/// See [`Proof`] for generating proofs of accounts together with their storage.
+#[derive(Debug)]
+pub struct StorageProof<T, H> {
+    /// The factory for traversing trie nodes.
+    trie_cursor_factory: T,
+    /// The factory for hashed cursors.
+    hashed_cursor_factory: H,
+    /// The hashed address of the account.
+    hashed_address: B256,
+    /// The set of storage key prefixes that have changed.
+    prefix_set: PrefixSetMut,
+    /// Whether the masks of the branch nodes of the proof are collected.
+    branch_node_masks: bool,
+}
+
+impl<T, H> StorageProof<T, H> {
+    /// Create a new [`StorageProof`] instance.
+    pub fn new(t: T, h: H, address: Address) -> Self {
+        Self::new_hashed(t, h, keccak256(address))
+    }
+
+    /// Create a new [`StorageProof`] instance with the hashed address.
+    pub fn new_hashed(t: T, h: H, hashed_address: B256) -> Self {
+        Self {
+            trie_cursor_factory: t,
+            hashed_cursor_factory: h,
+            hashed_address,
+            prefix_set: PrefixSetMut::default(),
+            branch_node_masks: false,
+        }
+    }
+
+    /// Set the changed storage key prefixes. It has to be mutable in order to allow extension with
+    /// proof targets.
+    pub fn with_prefix_set_mut(mut self, prefix_set: PrefixSetMut) -> Self {
+        self.prefix_set = prefix_set;
+        self
+    }
+
+    /// Set whether the masks of the branch nodes of the proof are collected, which are needed to
+    /// reveal the proof in a sparse trie.
+    pub const fn with_branch_node_masks(mut self, branch_node_masks: bool) -> Self {
+        self.branch_node_masks = branch_node_masks;
+        self
+    }
+}
+
+impl<T, H> StorageProof<T, H>
+where
+    T: TrieCursorFactory,
+    H: HashedCursorFactory,
+{
+    /// Generate a storage multiproof for the given hashed slots.
     pub fn storage_multiproof(
-        &self,
-        hashed_address: B256,
+        mut self,
+        targets: &[B256],
     ) -> Result<StorageMultiProof, StateProofError> {
         let mut hashed_storage_cursor =
-            self.hashed_cursor_factory.hashed_storage_cursor(hashed_address)?;
+            self.hashed_cursor_factory.hashed_storage_cursor(self.hashed_address)?;
 
         // short circuit on empty storage
         if hashed_storage_cursor.is_storage_empty()? {
             return Ok(StorageMultiProof::default())
         }
 
-        let target_nibbles = self
-            .targets
-            .get(&hashed_address)
-            .map_or(Vec::new(), |slots| slots.iter().map(Nibbles::unpack).collect());
+        let target_nibbles = targets.iter().map(Nibbles::unpack).collect::<Vec<_>>();
+        self.prefix_set.extend(target_nibbles.clone());
+        let trie_cursor = self.trie_cursor_factory.storage_trie_cursor(self.hashed_address)?;
+        storage_trie_multiproof(
+            trie_cursor,
+            hashed_storage_cursor,
+            self.prefix_set.freeze(),
+            ProofRetainer::from_iter(target_nibbles),
+            self.branch_node_masks,
+        )
+    }
+}
 
-        let mut prefix_set =
-            self.prefix_sets.storage_prefix_sets.get(&hashed_address).cloned().unwrap_or_default();
-        prefix_set.extend(target_nibbles.clone());
-        let trie_cursor = self.trie_cursor_factory.storage_trie_cursor(hashed_address)?;
-        let walker = TrieWalker::new(trie_cursor, prefix_set.freeze());
+impl<T, H> StorageProof<T, H>
+where
+    T: TrieCursorFactory + Sync,
+    H: HashedCursorFactory + Sync,
+{
+    /// Generate a storage multiproof for the given hashed slots like
+    /// [`Self::storage_multiproof`], splitting the storage trie by the first nibble of the hashed
+    /// slots and walking the subtries in parallel.
+    ///
+    /// The root node is assembled from the root nodes of the subtries, so the walks can't skip
+    /// whole subtries with the hashes of the root node in the database. The masks of the branch
+    /// nodes aren't collected from the subtries, the storage trie is walked sequentially if they
+    /// are requested.
+    pub fn par_storage_multiproof(
+        mut self,
+        targets: &[B256],
+    ) -> Result<StorageMultiProof, StateProofError> {
+        if self.branch_node_masks {
+            return self.storage_multiproof(targets)
+        }
 
-        let retainer = ProofRetainer::from_iter(target_nibbles);
-        let mut hash_builder = HashBuilder::default().with_proof_retainer(retainer);
-        let mut storage_node_iter = TrieNodeIter::new(walker, hashed_storage_cursor);
-        while let Some(node) = storage_node_iter.try_next()? {
-            match node {
-                TrieElement::Branch(node) => {
-                    hash_builder.add_branch(node.key, node.value, node.children_are_in_trie);
-                }
-                TrieElement::Leaf(hashed_slot, value) => {
-                    hash_builder.add_leaf(
-                        Nibbles::unpack(hashed_slot),
-                        alloy_rlp::encode_fixed_size(&value).as_ref(),
-                    );
+        // short circuit on empty storage
+        if self
+            .hashed_cursor_factory
+            .hashed_storage_cursor(self.hashed_address)?
+            .is_storage_empty()?
+        {
+            return Ok(StorageMultiProof::default())
+        }
+
+        let target_nibbles = targets.iter().map(Nibbles::unpack).collect::<Vec<_>>();
+        self.prefix_set.extend(target_nibbles.clone());
+        let prefix_set = self.prefix_set.freeze();
+
+        // The root node of every subtrie is retained, to derive the child of the root node of the
+        // storage trie at its nibble.
+        let subtries = CHILD_INDEX_RANGE
+            .into_par_iter()
+            .map(|nibble| {
+                let trie_cursor = SubtrieCursor::new(
+                    self.trie_cursor_factory.storage_trie_cursor(self.hashed_address)?,
+                    nibble,
+                );
+                let hashed_storage_cursor = SubtrieCursor::new(
+                    self.hashed_cursor_factory.hashed_storage_cursor(self.hashed_address)?,
+                    nibble,
+                );
+                let retainer = ProofRetainer::from_iter(
+                    target_nibbles
+                        .iter()
+                        .filter(|target| target[0] == nibble)
+                        .cloned()
+                        .chain([Nibbles::default()]),
+                );
+                let proof = storage_trie_multiproof(
+                    trie_cursor,
+                    hashed_storage_cursor,
+                    prefix_set.clone(),
+                    retainer,
+                    false,
+                )?;
+                Ok((nibble, proof))
+            })
+            .collect::<Result<Vec<_>, StateProofError>>()?;
+
+        let mut subtries = subtries
+            .into_iter()
+            .filter(|(_, proof)| proof.root != EMPTY_ROOT_HASH)
+            .collect::<Vec<_>>();
+        if subtries.len() <= 1 {
+            // The root node of the only subtrie is the root node of the storage trie.
+            let Some((_, mut proof)) = subtries.pop() else {
+                return Ok(StorageMultiProof::default())
+            };
+            if targets.is_empty() {
+                proof.subtree.remove(&Nibbles::default());
+            }
+            return Ok(proof)
+        }
+
+        let mut subtree = BTreeMap::default();
+        let mut state_mask = TrieMask::default();
+        let mut stack = Vec::with_capacity(subtries.len());
+        for (nibble, mut proof) in subtries {
+            // All keys of the subtrie start with the nibble, so its root node is a leaf or an
+            // extension node whose key starts with it.
+            let root_node = proof.subtree.remove(&Nibbles::default()).unwrap_or_default();
+            let child = match TrieNode::decode(&mut &root_node[..])? {
+                TrieNode::Extension(extension) if extension.key.len() == 1 => extension.child,
+                node => {
+                    let node = match node {
+                        TrieNode::Extension(extension) => TrieNode::Extension(ExtensionNode::new(
+                            extension.key.slice(1..),
+                            extension.child,
+                        )),
+                        TrieNode::Leaf(leaf) => {
+                            TrieNode::Leaf(LeafNode::new(leaf.key.slice(1..), leaf.value))
+                        }
+                        TrieNode::Branch(_) => {
+                            return Err(alloy_rlp::Error::Custom(
+                                "branch node at the root of a storage subtrie",
+                            )
+                            .into())
+                        }
+                    };
+                    let mut rlp = Vec::new();
+                    let child = node.rlp(&mut rlp);
+                    if target_nibbles.iter().any(|target| target[0] == nibble) {
+                        subtree.insert(Nibbles::from_nibbles_unchecked([nibble]), rlp.into());
+                    }
+                    child
                 }
+            };
+            state_mask |= TrieMask::from_nibble(nibble);
+            stack.push(child);
+            subtree.extend(proof.subtree);
+        }
+
+        let root_node = alloy_rlp::encode(BranchNode::new(stack, state_mask));
+        let root = keccak256(&root_node);
+        if !targets.is_empty() {
+            subtree.insert(Nibbles::default(), root_node.into());
+        }
+        Ok(StorageMultiProof { root, subtree, branch_node_masks: HashMap::default() })
+    }
+}
+
+/// Generates the multiproof of a storage trie walked with the given cursors, retaining the proofs
+/// of the retainer targets.
+fn storage_trie_multiproof<C, HC>(
+    trie_cursor: C,
+    hashed_storage_cursor: HC,
+    prefix_set: PrefixSet,
+    retainer: ProofRetainer,
+    branch_node_masks: bool,
+) -> Result<StorageMultiProof, StateProofError>
+where
+    C: TrieCursor,
+    HC: HashedCursor<Value = U256>,
+{
+    let walker = TrieWalker::new(trie_cursor, prefix_set);
+    let mut hash_builder =
+        HashBuilder::default().with_proof_retainer(retainer).with_updates(branch_node_masks);
+    let mut storage_node_iter = TrieNodeIter::new(walker, hashed_storage_cursor);
+    while let Some(node) = storage_node_iter.try_next()? {
+        match node {
+            TrieElement::Branch(node) => {
+                hash_builder.add_branch(node.key, node.value, node.children_are_in_trie);
+            }
+            TrieElement::Leaf(hashed_slot, value) => {
+                hash_builder.add_leaf(
+                    Nibbles::unpack(hashed_slot),
+                    alloy_rlp::encode_fixed_size(&value).as_ref(),
+                );
             }
         }
+    }
+
+    let root = hash_builder.root();
+    let subtree = hash_builder.take_proofs();
+    let branch_node_masks = proof_branch_node_masks(&subtree, hash_builder);
+    Ok(StorageMultiProof { root, subtree, branch_node_masks })
+}
+
+/// Returns the masks of the branch nodes of the proof retained by the hash builder, which are
+/// empty if the hash builder doesn't collect the updated branch nodes.
+fn proof_branch_node_masks(
+    proof: &BTreeMap<Nibbles, reth_primitives::Bytes>,
+    hash_builder: HashBuilder,
+) -> HashMap<Nibbles, BranchNodeMasks> {
+    let (_, updated_nodes) = hash_builder.split();
+    BranchNodeMasks::from_updated_nodes(proof, updated_nodes)
+}
+
+/// A cursor over the subtrie of the nodes or the hashed entries with keys starting with a nibble.
+#[derive(Debug)]
+struct SubtrieCursor<C> {
+    cursor: C,
+    nibble: u8,
+}
+
+impl<C> SubtrieCursor<C> {
+    const fn new(cursor: C, nibble: u8) -> Self {
+        Self { cursor, nibble }
+    }
+
+    /// Returns the entry if its key is in the subtrie.
+    fn filter<K, V>(
+        &self,
+        entry: Option<(K, V)>,
+        first_nibble: impl Fn(&K) -> Option<u8>,
+    ) -> Option<(K, V)> {
+        entry.filter(|(key, _)| first_nibble(key) == Some(self.nibble))
+    }
+}
+
+impl<C: TrieCursor> TrieCursor for SubtrieCursor<C> {
+    fn seek_exact(
+        &mut self,
+        key: Nibbles,
+    ) -> Result<Option<(Nibbles, BranchNodeCompact)>, DatabaseError> {
+        if key.first() != Some(self.nibble) {
+            return Ok(None)
+        }
+        self.cursor.seek_exact(key)
+    }
+
+    fn seek(
+        &mut self,
+        key: Nibbles,
+    ) -> Result<Option<(Nibbles, BranchNodeCompact)>, DatabaseError> {
+        let key = key.max(Nibbles::from_nibbles_unchecked([self.nibble]));
+        let entry = self.cursor.seek(key)?;
+        Ok(self.filter(entry, |key| key.first()))
+    }
+
+    fn next(&mut self) -> Result<Option<(Nibbles, BranchNodeCompact)>, DatabaseError> {
+        let entry = self.cursor.next()?;
+        Ok(self.filter(entry, |key| key.first()))
+    }
+
+    fn current(&mut self) -> Result<Option<Nibbles>, DatabaseError> {
+        self.cursor.current()
+    }
+}
+
+impl<C: HashedCursor> HashedCursor for SubtrieCursor<C> {
+    type Value = C::Value;
+
+    fn seek(&mut self, key: B256) -> Result<Option<(B256, Self::Value)>, DatabaseError> {
+        let mut start = B256::ZERO;
+        start[0] = self.nibble << 4;
+        let key = key.max(start);
+        let entry = self.cursor.seek(key)?;
+        Ok(self.filter(entry, |key| Some(key[0] >> 4)))
+    }
 
-        let root = hash_builder.root();
-        Ok(StorageMultiProof { root, subtree: hash_builder.take_proofs() })
+    fn next(&mut self) -> Result<Option<(B256, Self::Value)>, DatabaseError> {
+        let entry = self.cursor.next()?;
+        Ok(self.filter(entry, |key| Some(key[0] >> 4)))
     }
 }
@@ -12,6 +12,16 @@ pub struct TrieUpdates {
 }
 
 impl TrieUpdates {
+    /// Creates trie updates from the updated and removed account trie nodes, and the updates of
+    /// the storage tries.
+    pub const fn from_nodes(
+        account_nodes: HashMap<Nibbles, BranchNodeCompact>,
+        removed_nodes: HashSet<Nibbles>,
+        storage_tries: HashMap<B256, StorageTrieUpdates>,
+    ) -> Self {
+        Self { account_nodes, removed_nodes, storage_tries }
+    }
+
     /// Returns `true` if the updates are empty.
     pub fn is_empty(&self) -> bool {
         self.account_nodes.is_empty() &&
@@ -108,6 +118,16 @@ impl StorageTrieUpdates {
 }
 
 impl StorageTrieUpdates {
+    /// Creates storage trie updates from the updated and removed storage trie nodes. If the trie
+    /// is deleted, all of its nodes are removed before the updated nodes are inserted.
+    pub const fn from_nodes(
+        is_deleted: bool,
+        storage_nodes: HashMap<Nibbles, BranchNodeCompact>,
+        removed_nodes: HashSet<Nibbles>,
+    ) -> Self {
+        Self { is_deleted, storage_nodes, removed_nodes }
+    }
+
     /// Returns empty storage trie updates with `deleted` set to `true`.
     pub fn deleted() -> Self {
         Self {
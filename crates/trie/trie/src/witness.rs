@@ -5,9 +5,9 @@ use crate::{
 use alloy_rlp::{BufMut, Decodable, Encodable};
 use itertools::Either;
 use reth_execution_errors::{StateProofError, TrieWitnessError};
-use reth_primitives::{constants::EMPTY_ROOT_HASH, keccak256, Bytes, B256};
+use reth_primitives::{keccak256, Bytes, B256};
 use reth_trie_common::{
-    BranchNode, HashBuilder, Nibbles, TrieAccount, TrieNode, CHILD_INDEX_RANGE,
+    BranchNode, HashBuilder, MultiProof, Nibbles, TrieAccount, TrieNode, CHILD_INDEX_RANGE,
 };
 use std::collections::{BTreeMap, HashMap, HashSet};
 
@@ -63,9 +63,16 @@ where
     /// # Arguments
     ///
     /// `state` - state transition containing both modified and touched accounts and storage slots.
-    pub fn compute(
+    pub fn compute(self, state: HashedPostState) -> Result<HashMap<B256, Bytes>, TrieWitnessError> {
+        self.compute_with(state, Proof::multiproof)
+    }
+
+    /// Compute the state transition witness for the trie, generating the multiproof of the
+    /// targets with the given closure.
+    fn compute_with(
         mut self,
         state: HashedPostState,
+        multiproof: impl FnOnce(&Proof<T, H>) -> Result<MultiProof, StateProofError>,
     ) -> Result<HashMap<B256, Bytes>, TrieWitnessError> {
         let proof_targets = HashMap::from_iter(
             state.accounts.keys().map(|hashed_address| (*hashed_address, Vec::new())).chain(
@@ -74,41 +81,43 @@ where
                 }),
             ),
         );
-        let account_multiproof =
-            Proof::new(self.trie_cursor_factory.clone(), self.hashed_cursor_factory.clone())
+        let mut account_multiproof = multiproof(
+            &Proof::new(self.trie_cursor_factory.clone(), self.hashed_cursor_factory.clone())
                 .with_prefix_sets_mut(self.prefix_sets.clone())
-                .with_targets(proof_targets.clone())
-                .multiproof()?;
+                .with_targets(proof_targets.clone()),
+        )?;
 
         // Attempt to compute state root from proofs and gather additional
         // information for the witness.
         let mut account_rlp = Vec::with_capacity(128);
         let mut account_trie_nodes = BTreeMap::default();
+        let mut account_targets = Vec::with_capacity(proof_targets.len());
         for (hashed_address, hashed_slots) in proof_targets {
             let key = Nibbles::unpack(hashed_address);
-            let storage_multiproof = account_multiproof
-                .storages
-                .get(&hashed_address)
-                .ok_or(TrieWitnessError::MissingStorageMultiProof(hashed_address))?;
+            // Storage multiproofs are only generated for existing accounts, the storage of the
+            // other accounts is empty.
+            let storage_multiproof =
+                account_multiproof.storages.remove(&hashed_address).unwrap_or_default();
 
             // Gather and record account trie nodes.
             let account = state
                 .accounts
                 .get(&hashed_address)
                 .ok_or(TrieWitnessError::MissingAccount(hashed_address))?;
-            let value = if account.is_some() || storage_multiproof.root != EMPTY_ROOT_HASH {
+            // Destroyed accounts are removed together with their storage.
+            let value = account.map(|account| {
                 account_rlp.clear();
-                TrieAccount::from((account.unwrap_or_default(), storage_multiproof.root))
+                TrieAccount::from((account, storage_multiproof.root))
                     .encode(&mut account_rlp as &mut dyn BufMut);
-                Some(account_rlp.clone())
-            } else {
-                None
-            };
+                account_rlp.clone()
+            });
+            account_targets.push((key.clone(), value.is_none()));
             let proof = account_multiproof.account_subtree.iter().filter(|e| key.starts_with(e.0));
             account_trie_nodes.extend(self.target_nodes(key.clone(), value, proof)?);
 
             // Gather and record storage trie nodes for this account.
             let mut storage_trie_nodes = BTreeMap::default();
+            let mut storage_targets = Vec::with_capacity(hashed_slots.len());
             let storage = state.storages.get(&hashed_address);
             for hashed_slot in hashed_slots {
                 let slot_key = Nibbles::unpack(hashed_slot);
@@ -116,6 +125,7 @@ where
                     .and_then(|s| s.storage.get(&hashed_slot))
                     .filter(|v| !v.is_zero())
                     .map(|v| alloy_rlp::encode_fixed_size(v).to_vec());
+                storage_targets.push((slot_key.clone(), slot_value.is_none()));
                 let proof = storage_multiproof.subtree.iter().filter(|e| slot_key.starts_with(e.0));
                 storage_trie_nodes.extend(self.target_nodes(
                     slot_key.clone(),
@@ -124,49 +134,67 @@ where
                 )?);
             }
 
-            let root = Self::next_root_from_proofs(storage_trie_nodes, |key: Nibbles| {
-                // Right pad the target with 0s.
-                let mut padded_key = key.pack();
-                padded_key.resize(32, 0);
-                let mut proof = Proof::new(
-                    self.trie_cursor_factory.clone(),
-                    self.hashed_cursor_factory.clone(),
-                )
-                .with_prefix_sets_mut(self.prefix_sets.clone())
-                .with_targets(HashMap::from([(B256::from_slice(&padded_key), Vec::new())]))
-                .storage_multiproof(hashed_address)?;
-
-                // The subtree only contains the proof for a single target.
-                let node =
-                    proof.subtree.remove(&key).ok_or(TrieWitnessError::MissingTargetNode(key))?;
+            // The next storage root is computed to record the nodes revealed by removals, it
+            // differs from the current one if the target state changes the storage.
+            Self::next_root_from_proofs(storage_trie_nodes, |key: Nibbles| {
+                let node = self.storage_node(hashed_address, key)?;
                 self.witness.insert(keccak256(node.as_ref()), node.clone()); // record in witness
                 Ok(node)
             })?;
-            debug_assert_eq!(storage_multiproof.root, root);
+
+            for path in collapsed_children(&storage_multiproof.subtree, &storage_targets)? {
+                let node = self.storage_node(hashed_address, path)?;
+                self.witness.insert(keccak256(node.as_ref()), node);
+            }
         }
 
         Self::next_root_from_proofs(account_trie_nodes, |key: Nibbles| {
-            // Right pad the target with 0s.
-            let mut padded_key = key.pack();
-            padded_key.resize(32, 0);
-            let mut proof =
-                Proof::new(self.trie_cursor_factory.clone(), self.hashed_cursor_factory.clone())
-                    .with_prefix_sets_mut(self.prefix_sets.clone())
-                    .with_targets(HashMap::from([(B256::from_slice(&padded_key), Vec::new())]))
-                    .multiproof()?;
-
-            // The subtree only contains the proof for a single target.
-            let node = proof
-                .account_subtree
-                .remove(&key)
-                .ok_or(TrieWitnessError::MissingTargetNode(key))?;
+            let node = self.account_node(key)?;
             self.witness.insert(keccak256(node.as_ref()), node.clone()); // record in witness
             Ok(node)
         })?;
 
+        for path in collapsed_children(&account_multiproof.account_subtree, &account_targets)? {
+            let node = self.account_node(path)?;
+            self.witness.insert(keccak256(node.as_ref()), node);
+        }
+
         Ok(self.witness)
     }
 
+    /// Returns the account trie node at the given path.
+    fn account_node(&self, path: Nibbles) -> Result<Bytes, TrieWitnessError> {
+        // Right pad the target with 0s.
+        let mut padded_key = path.pack();
+        padded_key.resize(32, 0);
+        let mut proof =
+            Proof::new(self.trie_cursor_factory.clone(), self.hashed_cursor_factory.clone())
+                .with_prefix_sets_mut(self.prefix_sets.clone())
+                .with_targets(HashMap::from([(B256::from_slice(&padded_key), Vec::new())]))
+                .multiproof()?;
+
+        // The subtree only contains the proof for a single target.
+        proof.account_subtree.remove(&path).ok_or(TrieWitnessError::MissingTargetNode(path))
+    }
+
+    /// Returns the storage trie node of the account at the given path.
+    fn storage_node(&self, hashed_address: B256, path: Nibbles) -> Result<Bytes, TrieWitnessError> {
+        // Right pad the target with 0s.
+        let mut padded_key = path.pack();
+        padded_key.resize(32, 0);
+        let mut proof =
+            Proof::new(self.trie_cursor_factory.clone(), self.hashed_cursor_factory.clone())
+                .with_prefix_sets_mut(self.prefix_sets.clone())
+                .with_targets(HashMap::from([(
+                    hashed_address,
+                    vec![B256::from_slice(&padded_key)],
+                )]))
+                .storage_multiproof(hashed_address)?;
+
+        // The subtree only contains the proof for a single target.
+        proof.subtree.remove(&path).ok_or(TrieWitnessError::MissingTargetNode(path))
+    }
+
     /// Decodes and unrolls all nodes from the proof. Returns only sibling nodes
     /// in the path of the target and the final leaf node with updated value.
     fn target_nodes<'b>(
@@ -270,6 +298,66 @@ where
     }
 }
 
+impl<T, H> TrieWitness<T, H>
+where
+    T: TrieCursorFactory + Clone + Sync,
+    H: HashedCursorFactory + Clone + Sync,
+{
+    /// Compute the state transition witness for the trie like [`Self::compute`], generating the
+    /// storage proofs of the targets with [`Proof::par_multiproof`].
+    pub fn par_compute(
+        self,
+        state: HashedPostState,
+    ) -> Result<HashMap<B256, Bytes>, TrieWitnessError> {
+        self.compute_with(state, Proof::par_multiproof)
+    }
+}
+
+/// Returns the paths of the children that the branch nodes of the proof might collapse into when
+/// the removed targets are applied, and that aren't part of the proof.
+///
+/// A branch node collapses if a single child remains after the removals. The targets are given
+/// by their key and whether they're removed. The children that have no removed targets below them
+/// remain, so if there's only one of those, it's the child the branch collapses into unless some
+/// child with removed targets remains as well.
+fn collapsed_children(
+    proof: &BTreeMap<Nibbles, Bytes>,
+    targets: &[(Nibbles, bool)],
+) -> Result<Vec<Nibbles>, TrieWitnessError> {
+    let mut children = Vec::new();
+    for (path, encoded) in proof {
+        let TrieNode::Branch(branch) = TrieNode::decode(&mut &encoded[..])? else { continue };
+
+        let below = targets.iter().filter(|(key, _)| key.starts_with(path)).collect::<Vec<_>>();
+        if !below.iter().any(|(_, removed)| *removed) {
+            continue
+        }
+
+        // The remaining children by path, and whether they're referenced by hash rather than
+        // embedded in the branch node.
+        let mut remaining = Vec::new();
+        let mut stack_ptr = branch.as_ref().first_child_index();
+        for index in CHILD_INDEX_RANGE {
+            if branch.state_mask.is_bit_set(index) {
+                let mut child_path = path.clone();
+                child_path.push(index);
+                if !below.iter().any(|(key, removed)| *removed && key.starts_with(&child_path)) {
+                    let is_hash = branch.stack[stack_ptr].len() == B256::len_bytes() + 1;
+                    remaining.push((child_path, is_hash));
+                }
+                stack_ptr += 1;
+            }
+        }
+
+        if let [(child_path, true)] = remaining.as_slice() {
+            if !proof.contains_key(child_path) {
+                children.push(child_path.clone());
+            }
+        }
+    }
+    Ok(children)
+}
+
 /// Returned branch node children with keys in order.
 fn branch_node_children(prefix: Nibbles, node: &BranchNode) -> Vec<(Nibbles, B256)> {
     let mut children = Vec::with_capacity(node.state_mask.count_ones() as usize);